@@ -0,0 +1,115 @@
+//! `cargo bench` entry point
+//!
+//! Criterion benchmarks for each phase of every program in benches/programs:
+//! `lex` (lexer::tokenize), `parse` (parser::parse) and `run` (Interpreter::execute
+//! on the optimized AST, in a fresh interpreter each time). Arguments after `--`
+//! go to criterion, e.g. `cargo bench -- fib` or `cargo bench -- --save-baseline main`.
+//!
+//! `web_dispatch/echo` serves web_handler's handler the way web.serve does, minus
+//! the sockets: requests go through the web queue and dispatch_web_requests, and
+//! each runs on a handler snapshot in its own arena.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use flowlang::arena::ArenaAlloc;
+use flowlang::config::ProjectConfig;
+use flowlang::interpreter::{handlers, Interpreter};
+use flowlang::optimizer::Optimizer;
+use flowlang::runtime::WebCallbackRequest;
+use flowlang::stdlib::web;
+use flowlang::types::Value;
+use flowlang::{lexer, parser};
+use tokio::sync::oneshot;
+
+// Arenas only take effect with the allocator the flowlang binary installs
+#[global_allocator]
+static ALLOCATOR: ArenaAlloc = ArenaAlloc;
+
+/// Requests served per web_dispatch iteration
+const WEB_REQUESTS: usize = 300;
+
+fn programs() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("benches/programs");
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .expect("benches/programs is missing")
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "flow"))
+        .collect();
+    files.sort();
+    files
+}
+
+fn bench_programs(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    for path in programs() {
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let source = fs::read_to_string(&path).unwrap().replace('\u{feff}', "");
+        let script_dir = path.parent().unwrap().to_path_buf();
+        let tokens = lexer::tokenize(&source).unwrap();
+        let ast = Optimizer::new().optimize(parser::parse(tokens.clone()).unwrap());
+
+        let mut group = c.benchmark_group(&name);
+        group.bench_function("lex", |b| b.iter(|| lexer::tokenize(&source).unwrap()));
+        group.bench_function("parse", |b| {
+            b.iter_batched(|| tokens.clone(), |tokens| parser::parse(tokens).unwrap(), BatchSize::SmallInput)
+        });
+        group.bench_function("run", |b| {
+            b.to_async(&runtime).iter_batched(
+                || (Interpreter::with_dir(script_dir.clone(), ProjectConfig::default()), ast.clone()),
+                |(mut interpreter, ast)| async move { interpreter.execute(ast).await.unwrap() },
+                BatchSize::SmallInput,
+            )
+        });
+        group.finish();
+    }
+}
+
+fn bench_web_dispatch(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("benches/programs/web_handler.flow");
+    let source = fs::read_to_string(&path).unwrap().replace('\u{feff}', "");
+    let ast = Optimizer::new().optimize(parser::parse(lexer::tokenize(&source).unwrap()).unwrap());
+    let main = runtime.block_on(async {
+        let mut main = Interpreter::with_dir(path.parent().unwrap().to_path_buf(), ProjectConfig::default());
+        main.execute(ast).await.unwrap();
+        main
+    });
+    let handler = main.lookup("handler").expect("web_handler.flow defines handler");
+    let res = web::response_prototype();
+
+    c.bench_function("web_dispatch/echo", |b| {
+        b.to_async(&runtime).iter(|| serve_requests(&main, &handler, &res))
+    });
+}
+
+/// Queue WEB_REQUESTS echo requests, dispatch them as permits allow, and wait for every response
+async fn serve_requests(main: &Interpreter, handler: &Value, res: &Value) {
+    let queue = main.runtime().web_callback_sender();
+    let headers = [("user-agent", "bench"), ("content-type", "application/json")];
+    let mut responses = Vec::with_capacity(WEB_REQUESTS);
+    for i in 0..WEB_REQUESTS {
+        let body = format!("{{\"n\": {}, \"msg\": \"hello\"}}", i);
+        let request = web::request_value("POST", "/echo", "", headers.into_iter(), body.as_bytes(), "127.0.0.1".to_string());
+        let (response_tx, response_rx) = oneshot::channel();
+        let callback = WebCallbackRequest { callback: handler.clone(), args: vec![request, res.clone()], response_tx };
+        queue.send(callback).await.expect("the web queue holds a full batch");
+        responses.push(response_rx);
+    }
+
+    let mut started = 0;
+    while started < WEB_REQUESTS {
+        started += handlers::dispatch_web_requests(main, WEB_REQUESTS - started).await;
+        tokio::task::yield_now().await;
+    }
+    for response in responses {
+        let response = response.await.unwrap();
+        assert!(matches!(response, Value::Relic(_)), "handler failed: {}", response);
+    }
+}
+
+criterion_group!(benches, bench_programs, bench_web_dispatch);
+criterion_main!(benches);
@@ -0,0 +1,9 @@
+//! Arbitrary text into the lexer: it must return tokens or a FlowError, never panic
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|source: &str| {
+    let _ = flowlang::lexer::tokenize(source);
+});
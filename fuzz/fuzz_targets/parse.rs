@@ -0,0 +1,11 @@
+//! Arbitrary text through the lexer and parser
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|source: &str| {
+    if let Ok(tokens) = flowlang::lexer::tokenize(source) {
+        let _ = flowlang::parser::parse(tokens);
+    }
+});
@@ -0,0 +1,71 @@
+//! Sequences of FlowLang fragments through the lexer and parser
+//!
+//! Random bytes rarely get past the lexer, so this target builds its input from
+//! keywords, brackets, literals and interpolations. That reaches the parser with
+//! almost-valid programs: unclosed blocks, dangling operators, interpolations
+//! cut off halfway and deep nesting.
+
+#![no_main]
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+
+const KEYWORDS: &[&str] = &[
+    "let", "seal", "cast Spell", "in Stance", "shift Stance", "abandon Stance", "enter Phase", "in", "from", "to",
+    "return", "attempt", "rescue", "as", "circle", "sigil", "ritual", "end", "invoke Aura", "when", "otherwise",
+    "await", "wait", "perform", "rupture", "ward", "rebound", "break seal", "panic", "wound", "Ember", "Silk",
+    "Pulse", "Relic", "Constellation", "Hollow", "both!", "either!", "negate!",
+];
+
+const SYMBOLS: &[&str] = &[
+    "(", ")", "[", "]", "{", "}", ",", ".", ":", "::", ";", "=", "+", "-", "*", "/", "%", ">>", "<<", "is~", "->",
+    "!!", "@", "\n",
+];
+
+#[derive(Arbitrary, Debug)]
+enum Fragment {
+    Keyword(u8),
+    Symbol(u8),
+    Identifier(u8),
+    Number(f64),
+    Silk(String),
+    /// A template literal with an interpolation, possibly left open
+    Template { text: String, closed: bool },
+    /// `n` opening brackets of one kind, which a later fragment may or may not close
+    Open { bracket: u8, n: u8 },
+    Raw(String),
+}
+
+impl Fragment {
+    fn render(&self, out: &mut String) {
+        match self {
+            Fragment::Keyword(i) => out.push_str(KEYWORDS[*i as usize % KEYWORDS.len()]),
+            Fragment::Symbol(i) => out.push_str(SYMBOLS[*i as usize % SYMBOLS.len()]),
+            Fragment::Identifier(i) => out.push_str(["x", "y", "spell", "data", "_"][*i as usize % 5]),
+            Fragment::Number(n) => out.push_str(&n.to_string()),
+            Fragment::Silk(text) => out.push_str(&format!("{:?}", text)),
+            Fragment::Template { text, closed } => {
+                out.push_str(&format!("`{}${{", text.replace('`', "")));
+                if *closed {
+                    out.push_str("x}`");
+                }
+            }
+            Fragment::Open { bracket, n } => {
+                let bracket = ["(", "[", "{", "${", "in Stance (x) {"][*bracket as usize % 5];
+                out.push_str(&bracket.repeat(*n as usize));
+            }
+            Fragment::Raw(text) => out.push_str(text),
+        }
+        out.push(' ');
+    }
+}
+
+fuzz_target!(|fragments: Vec<Fragment>| {
+    let mut source = String::new();
+    for fragment in &fragments {
+        fragment.render(&mut source);
+    }
+    if let Ok(tokens) = flowlang::lexer::tokenize(&source) {
+        let _ = flowlang::parser::parse(tokens);
+    }
+});
@@ -0,0 +1,307 @@
+//! Per-request bump arenas for web handlers
+//!
+//! A web handler's Values (its scope maps, Silks, Constellations and temporaries)
+//! live exactly as long as its request. While a handler task is being polled, the
+//! global allocator serves its small allocations from that request's arena by bumping
+//! a pointer through a 64 KiB chunk, and freeing one only decrements the chunk's
+//! count of live allocations. When the request ends the arena lets go of its chunk,
+//! and a chunk with nothing left alive in it is reset and handed to the next request.
+//!
+//! Values that escape the request, the response and anything stored in std:sync,
+//! are copied to the shared heap with `on_heap`. Anything else that happens to
+//! outlive its request (a connection the handler opened, say) stays valid: it keeps
+//! its chunk from being reset until it is freed.
+//!
+//! The library doesn't install `ArenaAlloc`; the `flowlang` binary and the criterion
+//! benches do. Without it an arena is inert and everything comes from the normal
+//! heap, so fuzz targets and programs embedding FlowLang keep their own allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+/// 64 MiB of address space, only backed by memory as chunks are used
+const CHUNK_COUNT: usize = 1024;
+/// Larger or more aligned allocations always go to the shared heap
+const MAX_ARENA_SIZE: usize = 4 * 1024;
+const MAX_ARENA_ALIGN: usize = 16;
+
+/// Set in a chunk's state while an arena is bumping through it; the other bits
+/// count its live allocations. A state of 0 means free.
+const HELD: usize = 1 << (usize::BITS - 1);
+
+static REGION: AtomicPtr<u8> = AtomicPtr::new(ptr::null_mut());
+static CHUNKS: [AtomicUsize; CHUNK_COUNT] = [const { AtomicUsize::new(0) }; CHUNK_COUNT];
+/// Where the search for a free chunk starts
+static NEXT_CHUNK: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The arena of the handler being polled on this thread, if any
+    static CURRENT: Cell<*const Arena> = const { Cell::new(ptr::null()) };
+}
+
+fn region_layout() -> Layout {
+    Layout::from_size_align(CHUNK_SIZE * CHUNK_COUNT, CHUNK_SIZE).unwrap()
+}
+
+/// The address space all chunks come from, reserved on first use
+fn region() -> *mut u8 {
+    let base = REGION.load(Ordering::Acquire);
+    if !base.is_null() {
+        return base;
+    }
+    let fresh = unsafe { System.alloc(region_layout()) };
+    if fresh.is_null() {
+        return fresh;
+    }
+    match REGION.compare_exchange(ptr::null_mut(), fresh, Ordering::AcqRel, Ordering::Acquire) {
+        Ok(_) => fresh,
+        Err(existing) => {
+            unsafe { System.dealloc(fresh, region_layout()) };
+            existing
+        }
+    }
+}
+
+/// The chunk `ptr` was bumped from, or None for shared heap memory
+fn chunk_of(ptr: *const u8) -> Option<usize> {
+    let base = REGION.load(Ordering::Relaxed);
+    let offset = (ptr as usize).checked_sub(base as usize)?;
+    (!base.is_null() && offset < CHUNK_SIZE * CHUNK_COUNT).then_some(offset / CHUNK_SIZE)
+}
+
+fn claim_chunk() -> Option<usize> {
+    let start = NEXT_CHUNK.load(Ordering::Relaxed);
+    (0..CHUNK_COUNT).map(|i| (start + i) % CHUNK_COUNT).find(|&chunk| {
+        // Acquire pairs with the frees that emptied the chunk
+        let claimed = CHUNKS[chunk].compare_exchange(0, HELD, Ordering::Acquire, Ordering::Relaxed).is_ok();
+        if claimed {
+            NEXT_CHUNK.store(chunk + 1, Ordering::Relaxed);
+        }
+        claimed
+    })
+}
+
+/// The system allocator, except inside an arena. Install it in a binary with
+/// `#[global_allocator]` for arenas to take effect.
+pub struct ArenaAlloc;
+
+unsafe impl GlobalAlloc for ArenaAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() <= MAX_ARENA_SIZE && layout.align() <= MAX_ARENA_ALIGN {
+            let arena = CURRENT.try_with(Cell::get).unwrap_or(ptr::null());
+            if !arena.is_null() {
+                let bumped = (*arena).bump(layout);
+                if !bumped.is_null() {
+                    return bumped;
+                }
+            }
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        match chunk_of(ptr) {
+            // Release pairs with claim_chunk, so the next owner sees these writes done
+            Some(chunk) => {
+                CHUNKS[chunk].fetch_sub(1, Ordering::Release);
+            }
+            None => System.dealloc(ptr, layout),
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let in_arena = !CURRENT.try_with(Cell::get).unwrap_or(ptr::null()).is_null();
+        if chunk_of(ptr).is_none() && !in_arena {
+            return System.realloc(ptr, layout, new_size);
+        }
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let moved = self.alloc(new_layout);
+        if !moved.is_null() {
+            ptr::copy_nonoverlapping(ptr, moved, layout.size().min(new_size));
+            self.dealloc(ptr, layout);
+        }
+        moved
+    }
+}
+
+/// One request's arena: the chunk it is bumping through, if it has one
+#[derive(Default)]
+pub struct Arena {
+    chunk: Cell<Option<usize>>,
+    offset: Cell<usize>,
+}
+
+impl Arena {
+    pub fn new() -> Self {
+        Arena::default()
+    }
+
+    /// Run `f` with its allocations in this arena
+    pub fn enter<T>(&self, f: impl FnOnce() -> T) -> T {
+        let _current = CurrentArena::set(self);
+        f()
+    }
+
+    /// Poll `future` in this arena; the arena is released when the future is dropped
+    pub fn run<F: Future>(self, future: F) -> InArena<F> {
+        InArena { arena: self, future: Box::pin(future) }
+    }
+
+    fn bump(&self, layout: Layout) -> *mut u8 {
+        let base = region();
+        if base.is_null() {
+            return base;
+        }
+        if let Some(chunk) = self.chunk.get() {
+            let start = self.offset.get().next_multiple_of(layout.align());
+            if start + layout.size() <= CHUNK_SIZE {
+                self.offset.set(start + layout.size());
+                CHUNKS[chunk].fetch_add(1, Ordering::Relaxed);
+                return unsafe { base.add(chunk * CHUNK_SIZE + start) };
+            }
+            self.release();
+        }
+        // A fresh chunk starts at offset 0, which fits anything up to MAX_ARENA_SIZE
+        let Some(chunk) = claim_chunk() else {
+            return ptr::null_mut();
+        };
+        self.chunk.set(Some(chunk));
+        self.offset.set(layout.size());
+        CHUNKS[chunk].fetch_add(1, Ordering::Relaxed);
+        unsafe { base.add(chunk * CHUNK_SIZE) }
+    }
+
+    /// Stop bumping through the current chunk; it is free once its allocations are
+    fn release(&self) {
+        if let Some(chunk) = self.chunk.take() {
+            CHUNKS[chunk].fetch_and(!HELD, Ordering::Release);
+        }
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Run `f` with its allocations on the shared heap, for values that outlive the request
+pub fn on_heap<T>(f: impl FnOnce() -> T) -> T {
+    let _current = CurrentArena::replace(ptr::null());
+    f()
+}
+
+/// Makes an arena current on this thread until dropped, even if polling panics
+struct CurrentArena(*const Arena);
+
+impl CurrentArena {
+    fn set(arena: &Arena) -> Self {
+        Self::replace(arena)
+    }
+
+    fn replace(arena: *const Arena) -> Self {
+        CurrentArena(CURRENT.with(|current| current.replace(arena)))
+    }
+}
+
+impl Drop for CurrentArena {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.set(self.0));
+    }
+}
+
+/// A future polled inside its own arena, see `Arena::run`
+pub struct InArena<F> {
+    arena: Arena,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for InArena<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let this = self.get_mut();
+        let _current = CurrentArena::set(&this.arena);
+        this.future.as_mut().poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: ArenaAlloc = ArenaAlloc;
+
+    fn live(chunk: usize) -> usize {
+        CHUNKS[chunk].load(Ordering::Acquire) & !HELD
+    }
+
+    #[test]
+    fn test_small_allocations_bump_from_the_arena() {
+        let arena = Arena::new();
+        let (small, large, heap) = arena.enter(|| {
+            (Box::new(7u64), vec![0u8; MAX_ARENA_SIZE + 1], on_heap(|| Box::new(8u64)))
+        });
+        let chunk = chunk_of(&*small as *const u64 as *const u8).expect("bumped from a chunk");
+        assert!(chunk_of(large.as_ptr()).is_none());
+        assert!(chunk_of(&*heap as *const u64 as *const u8).is_none());
+
+        // Leaving the arena doesn't invalidate what it handed out
+        drop(arena);
+        assert_eq!(*small, 7);
+        assert_eq!(live(chunk), 1);
+        drop(small);
+    }
+
+    #[test]
+    fn test_growing_moves_to_the_heap_outside_the_arena() {
+        let arena = Arena::new();
+        let mut text = arena.enter(|| String::from("Aria"));
+        assert!(chunk_of(text.as_ptr()).is_some());
+        text.push_str(&"!".repeat(MAX_ARENA_SIZE));
+        assert!(chunk_of(text.as_ptr()).is_none());
+        assert!(text.starts_with("Aria!"));
+    }
+
+    #[test]
+    fn test_escaped_values_keep_their_memory() {
+        use std::sync::mpsc;
+
+        let (tx, rx) = mpsc::channel();
+        let arena = Arena::new();
+        let (pinned, copied) = arena.enter(|| {
+            let value = String::from("stored in a global");
+            tx.send(value.clone()).unwrap();
+            (value.clone(), on_heap(|| value.clone()))
+        });
+        drop(arena);
+        let sent = rx.recv().unwrap();
+        let chunk = chunk_of(pinned.as_ptr()).expect("bumped from a chunk");
+        assert_eq!(chunk_of(sent.as_ptr()), Some(chunk));
+        assert!(chunk_of(copied.as_ptr()).is_none());
+
+        // Later requests never get a chunk something still points into
+        for _ in 0..CHUNK_COUNT + 1 {
+            let arena = Arena::new();
+            let other = arena.enter(|| Box::new([0u8; 64]));
+            assert_ne!(chunk_of(other.as_ptr()), Some(chunk));
+        }
+        assert_eq!(pinned, "stored in a global");
+        assert_eq!(sent, "stored in a global");
+    }
+
+    #[tokio::test]
+    async fn test_future_runs_in_its_arena() {
+        let boxed = Arena::new().run(async { Box::new(1u32) }).await;
+        assert!(chunk_of(&*boxed as *const u32 as *const u8).is_some());
+        // Outside the future, allocations are back on the heap
+        assert!(chunk_of(&*Box::new(2u32) as *const u32 as *const u8).is_none());
+    }
+}
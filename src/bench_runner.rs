@@ -0,0 +1,278 @@
+// Benchmark runner for `flowlang dev bench-suite`
+//
+// Times the lexer, parser and interpreter on every `.flow` program under
+// `benches/` and prints the median of several runs per phase. Results can be
+// saved as a baseline; comparing against one fails the run when a phase got
+// slower than the threshold, so interpreter regressions show up in CI.
+
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::config::ProjectConfig;
+use crate::error::FlowError;
+
+/// Version of the baseline file. Bump when its shape changes.
+const BASELINE_VERSION: u32 = 1;
+
+/// Changes smaller than this are noise, whatever the percentage
+const NOISE_FLOOR_MS: f64 = 0.05;
+
+pub struct BenchOptions {
+    pub iterations: usize,
+    /// Write the results here
+    pub save: Option<PathBuf>,
+    /// Compare the results to this earlier `save`
+    pub baseline: Option<PathBuf>,
+    /// Slowdown, in percent, that counts as a regression
+    pub threshold: f64,
+}
+
+/// Median milliseconds per phase of one program
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PhaseTimes {
+    pub lex_ms: f64,
+    pub parse_ms: f64,
+    pub run_ms: f64,
+}
+
+impl PhaseTimes {
+    fn phases(&self) -> [(&'static str, f64); 3] {
+        [("lex", self.lex_ms), ("parse", self.parse_ms), ("run", self.run_ms)]
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Baseline {
+    version: u32,
+    benchmarks: BTreeMap<String, PhaseTimes>,
+}
+
+/// Run every benchmark found under `paths`. Returns false when a program failed
+/// or a phase regressed against the baseline.
+pub async fn run(paths: Vec<PathBuf>, options: BenchOptions) -> bool {
+    let roots = if paths.is_empty() { vec![PathBuf::from("benches")] } else { paths };
+
+    let mut files = Vec::new();
+    for root in &roots {
+        collect_programs(root, &mut files);
+    }
+    files.sort();
+
+    if files.is_empty() {
+        eprintln!("{}", "❌ No benchmark programs found (looking for *.flow)".red().bold());
+        return false;
+    }
+
+    let baseline = match &options.baseline {
+        Some(path) => match load_baseline(path) {
+            Ok(baseline) => Some(baseline),
+            Err(e) => {
+                crate::error::print_error(&e);
+                return false;
+            }
+        },
+        None => None,
+    };
+
+    let iterations = options.iterations.max(1);
+    println!(
+        "{} {} program(s), median of {} run(s)",
+        "⏱️  Benchmarking".bright_cyan().bold(),
+        files.len(),
+        iterations
+    );
+    println!();
+    println!("{:<24} {:>10} {:>10} {:>10}", "program", "lex", "parse", "run");
+
+    let mut results = BTreeMap::new();
+    let mut failed = false;
+    let mut regressions = Vec::new();
+
+    for file in &files {
+        let name = bench_name(file);
+        let times = match bench_program(file, iterations).await {
+            Ok(times) => times,
+            Err(e) => {
+                println!("{:<24} {}", name, "failed".red().bold());
+                for line in e.to_string().lines() {
+                    println!("    {}", line);
+                }
+                failed = true;
+                continue;
+            }
+        };
+
+        let previous = baseline.as_ref().and_then(|b| b.benchmarks.get(&name));
+        let mut row = format!("{:<24}", name);
+        for (phase, ms) in times.phases() {
+            let cell = format!("{:>8.3}ms", ms);
+            let change = previous.map(|p| {
+                let before = p.phases().iter().find(|(n, _)| *n == phase).map(|(_, v)| *v).unwrap_or(0.0);
+                (before, change_percent(before, ms))
+            });
+            row.push(' ');
+            match change {
+                Some((before, percent)) if is_regression(before, ms, options.threshold) => {
+                    row.push_str(&cell.red().bold().to_string());
+                    regressions.push(format!("{} {}: {:.3}ms -> {:.3}ms (+{:.1}%)", name, phase, before, ms, percent));
+                }
+                Some((before, _)) if is_regression(ms, before, options.threshold) => {
+                    row.push_str(&cell.green().to_string());
+                }
+                _ => row.push_str(&cell),
+            }
+        }
+        println!("{}", row);
+        results.insert(name, times);
+    }
+    println!();
+
+    if let Some(path) = &options.save {
+        let baseline = Baseline { version: BASELINE_VERSION, benchmarks: results };
+        match save_baseline(path, &baseline) {
+            Ok(()) => println!("{} {}", "💾 Saved baseline to".green(), path.display()),
+            Err(e) => {
+                crate::error::print_error(&e);
+                failed = true;
+            }
+        }
+    }
+
+    if !regressions.is_empty() {
+        println!("{} (threshold {}%)", "Regressions:".red().bold(), options.threshold);
+        for regression in &regressions {
+            println!("  {} {}", "●".red(), regression);
+        }
+        println!();
+    }
+
+    if failed || !regressions.is_empty() {
+        println!("{} {}", "❌".red(), "Benchmarks failed".bright_red().bold());
+        false
+    } else {
+        println!("{} {}", "✅".green(), "Benchmarks finished".bright_green().bold());
+        true
+    }
+}
+
+/// Time each phase `iterations` times after one warmup run
+async fn bench_program(path: &Path, iterations: usize) -> Result<PhaseTimes, FlowError> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| FlowError::rift(&format!("Failed to read benchmark: {}", e), 0, 0))?
+        .replace("\u{feff}", "");
+    let script_dir = path.parent().unwrap_or_else(|| Path::new(".")).to_path_buf();
+
+    let mut lex = Vec::with_capacity(iterations);
+    let mut parse = Vec::with_capacity(iterations);
+    let mut run = Vec::with_capacity(iterations);
+
+    for i in 0..=iterations {
+        let start = Instant::now();
+        let tokens = crate::lexer::tokenize(&source)?;
+        let lexed = start.elapsed();
+
+        let start = Instant::now();
+        let ast = crate::parser::parse(tokens)?;
+        let parsed = start.elapsed();
+
+        let ast = crate::optimizer::Optimizer::new().optimize(ast);
+        let mut interpreter = crate::interpreter::Interpreter::with_dir(script_dir.clone(), ProjectConfig::default());
+        let start = Instant::now();
+        interpreter.execute(ast).await?;
+        let ran = start.elapsed();
+
+        // The first run warms caches and the module loader
+        if i > 0 {
+            lex.push(lexed);
+            parse.push(parsed);
+            run.push(ran);
+        }
+    }
+
+    Ok(PhaseTimes { lex_ms: median_ms(&mut lex), parse_ms: median_ms(&mut parse), run_ms: median_ms(&mut run) })
+}
+
+fn median_ms(samples: &mut [Duration]) -> f64 {
+    samples.sort();
+    samples[samples.len() / 2].as_secs_f64() * 1000.0
+}
+
+fn change_percent(before: f64, after: f64) -> f64 {
+    if before > 0.0 { (after - before) / before * 100.0 } else { 0.0 }
+}
+
+/// Whether `after` is slower than `before` by more than `threshold` percent and the noise floor
+fn is_regression(before: f64, after: f64, threshold: f64) -> bool {
+    after - before > NOISE_FLOOR_MS && change_percent(before, after) > threshold
+}
+
+/// Path relative to the benches root without the extension, e.g. "programs/fib"
+fn bench_name(path: &Path) -> String {
+    let relative = path.strip_prefix("benches").unwrap_or(path);
+    relative.with_extension("").to_string_lossy().replace('\\', "/")
+}
+
+fn collect_programs(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return;
+    }
+
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        if entry_path.is_dir() {
+            collect_programs(&entry_path, files);
+        } else if entry_path.extension().map(|e| e == "flow").unwrap_or(false) {
+            files.push(entry_path);
+        }
+    }
+}
+
+fn load_baseline(path: &Path) -> Result<Baseline, FlowError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| FlowError::rift(&format!("Failed to read baseline {}: {}", path.display(), e), 0, 0))?;
+    let baseline: Baseline = serde_json::from_str(&content)
+        .map_err(|e| FlowError::glitch(&format!("Failed to parse baseline {}: {}", path.display(), e), 0, 0))?;
+    if baseline.version != BASELINE_VERSION {
+        return Err(FlowError::glitch(
+            &format!("Baseline {} is version {}, expected {}; save a new one", path.display(), baseline.version, BASELINE_VERSION),
+            0,
+            0,
+        ));
+    }
+    Ok(baseline)
+}
+
+fn save_baseline(path: &Path, baseline: &Baseline) -> Result<(), FlowError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| FlowError::rift(&format!("Failed to create {}: {}", parent.display(), e), 0, 0))?;
+    }
+    let json = serde_json::to_string_pretty(baseline)
+        .map_err(|e| FlowError::rift(&format!("Failed to serialize baseline: {}", e), 0, 0))?;
+    fs::write(path, json).map_err(|e| FlowError::rift(&format!("Failed to write baseline {}: {}", path.display(), e), 0, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regression_needs_threshold_and_noise_floor() {
+        assert!(is_regression(10.0, 13.0, 20.0));
+        assert!(!is_regression(10.0, 11.0, 20.0));
+        // Tiny phases jitter by large percentages
+        assert!(!is_regression(0.01, 0.03, 20.0));
+        assert!(!is_regression(13.0, 10.0, 20.0));
+        assert_eq!(bench_name(Path::new("benches/programs/fib.flow")), "programs/fib");
+    }
+}
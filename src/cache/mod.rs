@@ -1,65 +1,70 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::io::{Read, Write};
+use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
-use crate::parser::ast::Program;
+use crate::parser::ast::{Import, Program};
 use crate::error::FlowError;
 
+// Bump when CacheEntry or the AST changes shape; older files are ignored and rewritten.
+// Entries are bincode, which can't skip fields, so AST fields must not use skip_serializing_if.
+const CACHE_FORMAT: u32 = 11;
+
+/// One cached module. The key covers the module's own source and, recursively,
+/// the keys of every local circle it imports, so editing an imported file
+/// invalidates every module that depends on it.
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    format: u32,
+    source_hash: Vec<u8>,
+    /// Canonical paths of imported local circles, in import order
+    deps: Vec<PathBuf>,
+    key: Vec<u8>,
+    program: Program,
+}
+
 pub struct CacheManager {
     cache_dir: PathBuf,
+    // Module keys computed during this process; sources don't change mid-run
+    keys: RefCell<HashMap<PathBuf, Vec<u8>>>,
+}
+
+impl Default for CacheManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CacheManager {
     pub fn new() -> Self {
-        let cache_dir = PathBuf::from(".flowlang/ast");
+        // Workspace members share the root's cache
+        let cache_dir = crate::config::project_root().join(".flowlang").join("ast");
         if !cache_dir.exists() {
             let _ = fs::create_dir_all(&cache_dir);
         }
-        CacheManager { cache_dir }
+        CacheManager { cache_dir, keys: RefCell::new(HashMap::new()) }
     }
 
+    /// Cached AST for `file_path`, if neither it nor anything it imports has changed
     pub fn load(&self, file_path: &Path, source: &str) -> Option<Program> {
-        let cache_path = self.get_cache_path(file_path);
-        
-        if !cache_path.exists() {
-            return None;
-        }
-
-        // Read cache file
-        // Format: [Hash (32 bytes)][Bincode Encoded AST]
-        let mut file = match fs::File::open(&cache_path) {
-            Ok(f) => f,
-            Err(_) => return None,
-        };
-
-        let mut buffer = Vec::new();
-        if file.read_to_end(&mut buffer).is_err() {
-            return None;
-        }
-
-        if buffer.len() < 32 {
-            return None; // Invalid cache
-        }
-
-        // Verify hash
-        let stored_hash = &buffer[0..32];
-        let current_hash = self.compute_hash(source);
-        
-        if stored_hash != current_hash.as_slice() {
+        let entry = self.read_entry(file_path)?;
+        if entry.source_hash != compute_hash(source) {
             return None; // Source changed, cache invalid
         }
 
-        // Deserialize AST
-        let ast_data = &buffer[32..];
-        match bincode::deserialize(ast_data) {
-            Ok(program) => Some(program),
-            Err(_) => None,
+        let mut visiting = HashSet::new();
+        visiting.insert(canonical(file_path));
+        let deps_key = self.combine(&entry.source_hash, &entry.deps, &mut visiting)?;
+        if deps_key != entry.key {
+            return None; // An imported circle changed
         }
+        Some(entry.program)
     }
 
     pub fn save(&self, file_path: &Path, source: &str, program: &Program) -> Result<(), FlowError> {
         let cache_path = self.get_cache_path(file_path);
-        
+
         // Ensure directory exists
         if let Some(parent) = cache_path.parent() {
             if !parent.exists() {
@@ -67,47 +72,182 @@ impl CacheManager {
             }
         }
 
-        let hash = self.compute_hash(source);
-        let ast_data = bincode::serialize(program).map_err(|e| {
-            FlowError::runtime(&format!("Failed to serialize AST: {}", e), 0, 0)
+        let source_hash = compute_hash(source);
+        let deps = local_dependencies(file_path, program);
+        let mut visiting = HashSet::new();
+        visiting.insert(canonical(file_path));
+        let key = self.combine(&source_hash, &deps, &mut visiting).ok_or_else(|| {
+            FlowError::runtime("Failed to hash imported circles", 0, 0)
         })?;
 
-        let mut file = fs::File::create(&cache_path).map_err(|e| {
-            FlowError::runtime(&format!("Failed to create cache file: {}", e), 0, 0)
+        let entry = CacheEntry { format: CACHE_FORMAT, source_hash, deps, key, program: program.clone() };
+        let data = bincode::serialize(&entry).map_err(|e| {
+            FlowError::runtime(&format!("Failed to serialize AST: {}", e), 0, 0)
         })?;
 
-        // Write hash then data
-        file.write_all(&hash).map_err(|e| {
-            FlowError::runtime(&format!("Failed to write cache: {}", e), 0, 0)
-        })?;
-        
-        file.write_all(&ast_data).map_err(|e| {
+        fs::write(&cache_path, data).map_err(|e| {
             FlowError::runtime(&format!("Failed to write cache: {}", e), 0, 0)
-        })?;
+        })
+    }
+
+    fn key_for(&self, path: &Path, visiting: &mut HashSet<PathBuf>) -> Option<Vec<u8>> {
+        if let Some(key) = self.keys.borrow().get(path) {
+            return Some(key.clone());
+        }
 
-        Ok(())
+        let source = fs::read_to_string(path).ok()?.replace("\u{feff}", "");
+        let source_hash = compute_hash(&source);
+
+        // A circular import can't include itself; the runtime reports the cycle anyway
+        if !visiting.insert(path.to_path_buf()) {
+            return Some(source_hash);
+        }
+
+        // The dependency list comes from the cached entry when it is still current,
+        // so checking a warm cache never re-parses unchanged files
+        let deps = match self.read_entry(path) {
+            Some(entry) if entry.source_hash == source_hash => entry.deps,
+            _ => {
+                let program = crate::lexer::tokenize(&source).and_then(crate::parser::parse).ok()?;
+                local_dependencies(path, &program)
+            }
+        };
+
+        let key = self.combine(&source_hash, &deps, visiting);
+        visiting.remove(path);
+        let key = key?;
+        self.keys.borrow_mut().insert(path.to_path_buf(), key.clone());
+        Some(key)
     }
 
-    fn compute_hash(&self, source: &str) -> Vec<u8> {
+    fn combine(&self, source_hash: &[u8], deps: &[PathBuf], visiting: &mut HashSet<PathBuf>) -> Option<Vec<u8>> {
         let mut hasher = Sha256::new();
-        hasher.update(source.as_bytes());
-        hasher.finalize().to_vec()
+        hasher.update(source_hash);
+        for dep in deps {
+            hasher.update(dep.to_string_lossy().as_bytes());
+            hasher.update(self.key_for(dep, visiting)?);
+        }
+        Some(hasher.finalize().to_vec())
+    }
+
+    fn read_entry(&self, file_path: &Path) -> Option<CacheEntry> {
+        let data = fs::read(self.get_cache_path(file_path)).ok()?;
+        let entry: CacheEntry = bincode::deserialize(&data).ok()?;
+        (entry.format == CACHE_FORMAT).then_some(entry)
     }
 
     fn get_cache_path(&self, file_path: &Path) -> PathBuf {
         // Create a unique filename based on the absolute path hash to avoid collisions
         // and handle files with same name in different dirs
-        let abs_path = fs::canonicalize(file_path).unwrap_or(file_path.to_path_buf());
+        let abs_path = canonical(file_path);
         let path_str = abs_path.to_string_lossy();
-        
+
         let mut hasher = Sha256::new();
         hasher.update(path_str.as_bytes());
         let path_hash = hex::encode(hasher.finalize());
-        
+
         let filename = file_path.file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("unknown");
-            
+
         self.cache_dir.join(format!("{}_{}.flowast", filename, &path_hash[0..8]))
     }
 }
+
+/// Where a local (non std:/pkg:/URL) import points, relative to the importing
+/// file's directory. The interpreter resolves circles with the same rules.
+pub fn import_path(dir: &Path, import: &Import) -> PathBuf {
+    let mut module_path = dir.to_path_buf();
+
+    if let Some(path) = &import.from_path {
+        // Accept Windows-style separators on every platform
+        module_path.push(path.replace('\\', "/"));
+    } else {
+        module_path.push(&import.module);
+    }
+
+    // Add .flow extension if missing
+    if module_path.extension().is_none() {
+        module_path.set_extension("flow");
+    }
+    module_path
+}
+
+// Whether an import is resolved by path rather than from the stdlib or a package
+pub fn is_local_import(import: &Import) -> bool {
+    match &import.from_path {
+        Some(path) => !["std:", "pkg:", "github.com/", "gitlab.com/", "bitbucket.org/"]
+            .iter()
+            .any(|prefix| path.starts_with(prefix)),
+        None => true,
+    }
+}
+
+// Packages are pinned by their spec, so only local circles take part in the graph.
+// Imports that don't resolve are left out; running the program reports them.
+fn local_dependencies(file_path: &Path, program: &Program) -> Vec<PathBuf> {
+    let dir = canonical(file_path).parent().map(Path::to_path_buf).unwrap_or_default();
+    program.imports.iter()
+        .filter(|import| is_local_import(import))
+        .filter_map(|import| crate::platform::canonicalize(&import_path(&dir, import)).ok())
+        .collect()
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    crate::platform::canonicalize(path).unwrap_or(path.to_path_buf())
+}
+
+fn compute_hash(source: &str) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(source.as_bytes());
+    hasher.finalize().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_invalidates_parent() {
+        let dir = std::env::temp_dir().join(format!("flowlang_cache_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("lib")).unwrap();
+
+        let main_path = dir.join("main.flow");
+        let main_source = "circle util from \"lib/util.flow\"\nshout(util.answer)\n";
+        fs::write(&main_path, main_source).unwrap();
+        fs::write(dir.join("lib/util.flow"), "circle deep from \"deep.flow\"\n@export let answer = deep.value\n").unwrap();
+        fs::write(dir.join("lib/deep.flow"), "@export let value = 42\n").unwrap();
+
+        let program = crate::lexer::tokenize(main_source).and_then(crate::parser::parse).unwrap();
+        let cache = CacheManager { cache_dir: dir.join("cache"), keys: RefCell::new(HashMap::new()) };
+        cache.save(&main_path, main_source, &program).unwrap();
+        assert!(cache.load(&main_path, main_source).is_some());
+
+        // Editing a transitive import changes the key, even though main.flow didn't change
+        fs::write(dir.join("lib/deep.flow"), "@export let value = 7\n").unwrap();
+        let fresh = CacheManager { cache_dir: dir.join("cache"), keys: RefCell::new(HashMap::new()) };
+        assert!(fresh.load(&main_path, main_source).is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_optional_fields_round_trip() {
+        let dir = std::env::temp_dir().join(format!("flowlang_cache_fields_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("main.flow");
+        let source = "@export\n@deprecated(\"use other\")\ncast Spell old() {\n    return 1\n}\nenter Phase x in [1] {\n    shout(x)\n}\nattempt {\n    old()\n} rescue retry 2 backoff 10ms {\n    shout(0)\n}\n";
+        fs::write(&path, source).unwrap();
+
+        let program = crate::lexer::tokenize(source).and_then(crate::parser::parse).unwrap();
+        let cache = CacheManager { cache_dir: dir.join("cache"), keys: RefCell::new(HashMap::new()) };
+        cache.save(&path, source, &program).unwrap();
+        let loaded = cache.load(&path, source).expect("cached AST should deserialize");
+        assert_eq!(format!("{:?}", loaded.statements), format!("{:?}", program.statements));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
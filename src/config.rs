@@ -1,20 +1,111 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
+use std::sync::OnceLock;
 use crate::error::FlowError;
+use crate::semver::{Version, VersionReq, FLOWLANG_VERSION};
+
+const CONFIG_FILE: &str = "config.flowlang.json";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ProjectConfig {
     pub name: String,
     pub version: String,
     pub entry: String,
+    /// FlowLang versions the project works with, e.g. `">=1.0, <2.0"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flowlang: Option<String>,
     #[serde(default)]
     pub authors: Vec<String>,
     #[serde(default)]
     pub type_required: bool,
+    /// Let scripts call `evaluate()` and `circleDynamic()`; `flowlang run --allow-eval` sets it too
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_eval: bool,
     #[serde(default)]
     pub packages: HashMap<String, String>, // alias -> "github.com/user/repo@ref"
+    /// Private package registries by name, for `<registry>:<name>@<range>` packages
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub registries: HashMap<String, RegistryConfig>,
+    #[serde(default, skip_serializing_if = "LintConfig::is_empty")]
+    pub lint: LintConfig,
+    #[serde(default, skip_serializing_if = "WebConfig::is_empty")]
+    pub web: WebConfig,
+    /// Package directories of a workspace, e.g. `["packages/*"]`; only read from the root config
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<String>,
+    /// Directories of the surrounding workspace's packages by name, so `pkg:<name>`
+    /// reaches a sibling. Filled in by `Workspace::apply`.
+    #[serde(skip)]
+    pub workspace_members: HashMap<String, PathBuf>,
+}
+
+/// How seriously `flowlang lint` treats a rule
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+/// The "lint" section of config.flowlang.json
+///
+/// ```json
+/// "lint": { "rules": { "unused-variable": "deny", "deep-nesting": "allow" }, "max_nesting": 5 }
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LintConfig {
+    /// Rule name -> level, overriding the rule's default
+    #[serde(default)]
+    pub rules: HashMap<String, LintLevel>,
+    /// Deepest block nesting allowed before `deep-nesting` fires
+    #[serde(default)]
+    pub max_nesting: Option<usize>,
+}
+
+impl LintConfig {
+    fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.max_nesting.is_none()
+    }
+}
+
+/// An entry of the "registries" section of config.flowlang.json
+///
+/// ```json
+/// "registries": { "acme": { "url": "https://flow.acme.internal/api", "token_env": "ACME_FLOW_TOKEN" } }
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RegistryConfig {
+    pub url: String,
+    /// Environment variable holding the bearer token, so the token stays out of the repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+}
+
+/// The "web" section of config.flowlang.json
+///
+/// ```json
+/// "web": { "queue_capacity": 4096, "overflow": "drop-oldest", "max_handlers": 100 }
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct WebConfig {
+    /// Requests that may wait for a handler before `overflow` applies
+    #[serde(default)]
+    pub queue_capacity: Option<usize>,
+    /// "reject" (503), "drop-oldest" or "block"
+    #[serde(default)]
+    pub overflow: Option<crate::runtime::queue::OverflowPolicy>,
+    /// Web handlers allowed to run at once
+    #[serde(default)]
+    pub max_handlers: Option<usize>,
+}
+
+impl WebConfig {
+    fn is_empty(&self) -> bool {
+        self.queue_capacity.is_none() && self.overflow.is_none() && self.max_handlers.is_none()
+    }
 }
 
 impl Default for ProjectConfig {
@@ -23,9 +114,16 @@ impl Default for ProjectConfig {
             name: "my-flow-project".to_string(),
             version: "0.1.0".to_string(),
             entry: "src/main.flow".to_string(),
+            flowlang: None,
             authors: vec![],
             type_required: false,
+            allow_eval: false,
             packages: HashMap::new(),
+            registries: HashMap::new(),
+            lint: LintConfig::default(),
+            web: WebConfig::default(),
+            members: Vec::new(),
+            workspace_members: HashMap::new(),
         }
     }
 }
@@ -53,4 +151,236 @@ impl ProjectConfig {
         serde_json::from_str(&content)
             .map_err(|e| FlowError::glitch(&format!("Failed to parse config file: {}", e), 0, 0))
     }
-}
\ No newline at end of file
+
+    /// Fails when this interpreter is outside the project's `flowlang` range
+    pub fn check_flowlang(&self, who: &str) -> Result<(), FlowError> {
+        let Some(range) = &self.flowlang else { return Ok(()) };
+        let req = VersionReq::parse(range).map_err(|e| {
+            FlowError::glitch(&format!("{} has an invalid flowlang range: {}", who, e), 0, 0)
+        })?;
+        let current = Version::parse(FLOWLANG_VERSION).expect("crate version is semver");
+        if !req.matches(&current) {
+            return Err(FlowError::glitch(
+                &format!("{} needs FlowLang {}, but this is FlowLang {}", who, range, current),
+                0,
+                0,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks a package before any of its code is parsed: its `version` must be a
+    /// semantic version and its `flowlang` range must include this interpreter
+    pub fn check_package(&self, alias: &str) -> Result<(), FlowError> {
+        let version = Version::parse(&self.version).map_err(|e| {
+            FlowError::glitch(&format!("Package '{}' has an invalid version: {}", alias, e), 0, 0)
+        })?;
+        self.check_flowlang(&format!("Package '{}' {}", alias, version))
+    }
+
+    /// The config for the project in `dir` (the defaults if it has none), joined with
+    /// the workspace around it
+    pub fn for_dir(dir: &Path) -> Result<Self, FlowError> {
+        let path = dir.join(CONFIG_FILE);
+        let mut config = if path.exists() { Self::load(&path)? } else { Self::default() };
+        if let Some(workspace) = Workspace::discover(dir)? {
+            workspace.apply(&mut config);
+        }
+        Ok(config)
+    }
+}
+
+/// Several packages in one repository, declared by `members` in the root config.
+/// Members share the root's `.flowlang/` (installed packages and the parse cache),
+/// resolve packages from one combined list, and import each other as `pkg:<name>`.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub root: PathBuf,
+    /// Name (from each member's config) and directory, in `members` order
+    pub members: Vec<(String, PathBuf)>,
+    /// Every package alias declared anywhere in the workspace
+    packages: HashMap<String, String>,
+    /// The root's registries, which members share
+    registries: HashMap<String, RegistryConfig>,
+}
+
+impl Workspace {
+    /// The workspace `dir` belongs to: the nearest directory at or above it whose
+    /// config lists `members`
+    pub fn discover(dir: &Path) -> Result<Option<Self>, FlowError> {
+        let dir = crate::platform::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+        for candidate in dir.ancestors() {
+            let path = candidate.join(CONFIG_FILE);
+            if !path.is_file() {
+                continue;
+            }
+            let config = ProjectConfig::load(&path)?;
+            if !config.members.is_empty() {
+                return Self::load(candidate, config).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn load(root: &Path, root_config: ProjectConfig) -> Result<Self, FlowError> {
+        let mut dirs = Vec::new();
+        for pattern in &root_config.members {
+            let full = root.join(pattern).to_string_lossy().replace('\\', "/");
+            let matches = glob::glob(&full).map_err(|e| {
+                FlowError::glitch(&format!("Workspace member pattern '{}' is invalid: {}", pattern, e), 0, 0)
+            })?;
+            let before = dirs.len();
+            // Anything a pattern matches without a config (a README, a scratch folder) is skipped
+            dirs.extend(matches.flatten().filter(|dir| dir.join(CONFIG_FILE).is_file()));
+            if dirs.len() == before && !pattern.contains(['*', '?', '[']) {
+                return Err(FlowError::glitch(
+                    &format!("Workspace member '{}' has no {}", pattern, CONFIG_FILE),
+                    0,
+                    0,
+                ));
+            }
+        }
+
+        let mut workspace = Workspace {
+            root: root.to_path_buf(),
+            members: Vec::new(),
+            packages: HashMap::new(),
+            registries: root_config.registries.clone(),
+        };
+        let mut sources = HashMap::new();
+        workspace.add_packages(&root_config, "the workspace root", &mut sources)?;
+        for dir in dirs {
+            let config = ProjectConfig::load(&dir.join(CONFIG_FILE))?;
+            if let Some((_, other)) = workspace.members.iter().find(|(name, _)| *name == config.name) {
+                return Err(FlowError::glitch(
+                    &format!("Workspace members {} and {} are both named '{}'", other.display(), dir.display(), config.name),
+                    0,
+                    0,
+                ));
+            }
+            workspace.add_packages(&config, &config.name, &mut sources)?;
+            workspace.members.push((config.name, dir));
+        }
+        Ok(workspace)
+    }
+
+    /// One URL per alias across the workspace, so every member runs the same version
+    fn add_packages(
+        &mut self,
+        config: &ProjectConfig,
+        owner: &str,
+        sources: &mut HashMap<String, String>,
+    ) -> Result<(), FlowError> {
+        for (alias, url) in &config.packages {
+            match self.packages.get(alias) {
+                Some(existing) if existing != url => {
+                    return Err(FlowError::glitch(
+                        &format!(
+                            "Workspace package '{}' is {} in {} but {} in {}",
+                            alias, existing, sources[alias], url, owner
+                        ),
+                        0,
+                        0,
+                    ))
+                }
+                Some(_) => {}
+                None => {
+                    self.packages.insert(alias.clone(), url.clone());
+                    sources.insert(alias.clone(), owner.to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Give a member's (or the root's) config the workspace's packages, registries and siblings
+    pub fn apply(&self, config: &mut ProjectConfig) {
+        for (alias, url) in &self.packages {
+            config.packages.entry(alias.clone()).or_insert_with(|| url.clone());
+        }
+        for (name, registry) in &self.registries {
+            config.registries.entry(name.clone()).or_insert_with(|| registry.clone());
+        }
+        config.workspace_members = self.members.iter().cloned().collect();
+    }
+
+    pub fn member(&self, name: &str) -> Option<&Path> {
+        self.members.iter().find(|(member, _)| member == name).map(|(_, dir)| dir.as_path())
+    }
+
+    /// The member whose directory holds `path`
+    pub fn member_containing(&self, path: &Path) -> Option<&Path> {
+        let path = crate::platform::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        self.members.iter().map(|(_, dir)| dir.as_path()).find(|dir| path.starts_with(dir))
+    }
+}
+
+/// Where `.flowlang/` lives for this process: the root of the workspace around the
+/// current directory, or the current directory itself
+pub fn project_root() -> &'static Path {
+    static ROOT: OnceLock<PathBuf> = OnceLock::new();
+    ROOT.get_or_init(|| {
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        match Workspace::discover(&cwd) {
+            Ok(Some(workspace)) => workspace.root,
+            _ => cwd,
+        }
+    })
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_config(dir: &Path, body: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(CONFIG_FILE), body).unwrap();
+    }
+
+    #[test]
+    fn test_workspace_members_and_shared_packages() {
+        let dir = std::env::temp_dir().join(format!("flowlang_workspace_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        write_config(
+            &dir,
+            r#"{"name": "mono", "version": "0.1.0", "entry": "src/main.flow", "members": ["packages/*"], "packages": {"shared": "github.com/x/shared@v1"}}"#,
+        );
+        write_config(&dir.join("packages/api"), r#"{"name": "api", "version": "0.1.0", "entry": "src/main.flow"}"#);
+        write_config(&dir.join("packages/core"), r#"{"name": "core", "version": "0.1.0", "entry": "src/main.flow"}"#);
+        // Matched by the glob but not a package
+        fs::create_dir_all(dir.join("packages/notes")).unwrap();
+
+        let workspace = Workspace::discover(&dir.join("packages/api")).unwrap().expect("workspace above member");
+        let names: Vec<&str> = workspace.members.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["api", "core"]);
+
+        let config = ProjectConfig::for_dir(&dir.join("packages/api")).unwrap();
+        assert_eq!(config.packages["shared"], "github.com/x/shared@v1");
+        assert!(config.workspace_members.contains_key("core"));
+
+        // Members may not pin a different version of a shared alias
+        write_config(
+            &dir.join("packages/core"),
+            r#"{"name": "core", "version": "0.1.0", "entry": "src/main.flow", "packages": {"shared": "github.com/x/shared@v2"}}"#,
+        );
+        let err = Workspace::discover(&dir).unwrap_err();
+        assert!(err.to_string().contains("shared@v2 in core"), "{}", err);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_package_version_checks() {
+        let config = |version: &str, flowlang: Option<&str>| ProjectConfig {
+            version: version.to_string(),
+            flowlang: flowlang.map(String::from),
+            ..Default::default()
+        };
+        assert!(config("1.4.0", None).check_package("http").is_ok());
+        assert!(config("1.4.0", Some(">=1.0, <2.0")).check_package("http").is_ok());
+
+        let err = config("1.4", None).check_package("http").unwrap_err();
+        assert!(err.to_string().contains("invalid version"), "{}", err);
+        let err = config("1.4.0", Some(">=99.0")).check_package("http").unwrap_err();
+        assert!(err.to_string().contains("Package 'http' 1.4.0 needs FlowLang >=99.0"), "{}", err);
+    }
+}
@@ -0,0 +1,598 @@
+// Documentation generator for `flowlang doc`
+//
+// Doc comments are `---` lines directly above a declaration (decorator lines such as
+// `@export` may sit in between). A `---` block at the top of a file, followed by a blank
+// line, describes the module itself. Comments never reach the parser, so declarations
+// come from the AST and their comments are read back from the source by line number.
+//
+//     --- Greets someone by name.
+//     --- @param name Who to greet
+//     --- @returns The greeting
+//     @export cast Spell greet(Silk name) -> Silk { ... }
+
+use clap::ValueEnum;
+use colored::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::FlowError;
+use crate::parser::ast::{Expression, Parameter, Program, Statement};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DocFormat {
+    Markdown,
+    Html,
+}
+
+impl DocFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            DocFormat::Markdown => "md",
+            DocFormat::Html => "html",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Spell,
+    Ritual,
+    Sigil,
+    Seal,
+    Variable,
+}
+
+impl ItemKind {
+    fn heading(&self) -> &'static str {
+        match self {
+            ItemKind::Spell => "Spells",
+            ItemKind::Ritual => "Rituals",
+            ItemKind::Sigil => "Sigils",
+            ItemKind::Seal => "Seals",
+            ItemKind::Variable => "Variables",
+        }
+    }
+}
+
+const SECTION_ORDER: [ItemKind; 5] = [ItemKind::Spell, ItemKind::Ritual, ItemKind::Sigil, ItemKind::Seal, ItemKind::Variable];
+
+/// One documented declaration
+#[derive(Debug)]
+pub struct DocItem {
+    pub kind: ItemKind,
+    pub name: String,
+    pub signature: String,
+    pub exported: bool,
+    /// Free text of the doc comment, tags removed
+    pub text: Vec<String>,
+    /// (name, type, description) for Spell and Ritual parameters
+    pub params: Vec<(String, Option<String>, String)>,
+    /// (type, description) of the return value
+    pub returns: Option<(Option<String>, String)>,
+}
+
+/// Everything documented in one .flow file
+#[derive(Debug)]
+pub struct ModuleDoc {
+    pub name: String,
+    pub summary: Vec<String>,
+    pub items: Vec<DocItem>,
+}
+
+/// Extract documentation from a parsed module. Only exported declarations are included
+/// unless `include_private` is set or the module exports nothing (a plain script).
+pub fn extract(name: &str, source: &str, program: &Program, include_private: bool) -> ModuleDoc {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut items = Vec::new();
+
+    for stmt in &program.statements {
+        let (kind, item_name, signature, params, return_type, exported, line) = match stmt {
+            Statement::FunctionDecl { name, params, return_type, is_exported, line, .. } => (
+                ItemKind::Spell,
+                name,
+                format!("cast Spell {}({}){}", name, param_list(params), return_suffix(return_type)),
+                params.as_slice(),
+                return_type.as_ref().map(|t| t.to_string()),
+                *is_exported,
+                *line,
+            ),
+            Statement::Ritual { name, params, return_type, is_exported, line, .. } => (
+                ItemKind::Ritual,
+                name,
+                format!("ritual {}({}){}", name, param_list(params), return_suffix(return_type)),
+                params.as_slice(),
+                return_type.as_ref().map(|t| t.to_string()),
+                *is_exported,
+                *line,
+            ),
+            Statement::SigilDecl { name, fields, is_error, is_exported, line } => {
+                let fields: Vec<String> = fields.iter().map(|f| format!("{}: {}", f.name, f.field_type)).collect();
+                let body = if fields.is_empty() { "{}".to_string() } else { format!("{{ {} }}", fields.join(", ")) };
+                let keyword = if *is_error { "sigil error" } else { "sigil" };
+                (ItemKind::Sigil, name, format!("{} {} {}", keyword, name, body), &[][..], None, *is_exported, *line)
+            }
+            Statement::Seal { name, type_annotation, value, is_exported, line } => (
+                ItemKind::Seal,
+                name,
+                format!("seal {}{}{}", name, type_suffix(type_annotation), value_suffix(value)),
+                &[][..],
+                None,
+                *is_exported,
+                *line,
+            ),
+            Statement::Let { name, type_annotation, value, is_exported, line } => (
+                ItemKind::Variable,
+                name,
+                format!("let {}{}{}", name, type_suffix(type_annotation), value_suffix(value)),
+                &[][..],
+                None,
+                *is_exported,
+                *line,
+            ),
+            _ => continue,
+        };
+
+        let comment = doc_comment_above(&lines, line);
+        // Unexported, undocumented variables are implementation details even in scripts
+        if kind == ItemKind::Variable && !exported && comment.is_empty() {
+            continue;
+        }
+
+        let mut item = DocItem {
+            kind,
+            name: item_name.clone(),
+            signature,
+            exported,
+            text: Vec::new(),
+            params: params
+                .iter()
+                .map(|p| (p.name.clone(), p.type_annotation.as_ref().map(|t| t.to_string()), String::new()))
+                .collect(),
+            returns: return_type.map(|t| (Some(t), String::new())),
+        };
+        apply_tags(&mut item, comment);
+        items.push(item);
+    }
+
+    if !include_private && items.iter().any(|item| item.exported) {
+        items.retain(|item| item.exported);
+    }
+
+    ModuleDoc { name: name.to_string(), summary: module_summary(&lines), items }
+}
+
+fn param_list(params: &[Parameter]) -> String {
+    params
+        .iter()
+        .map(|p| match &p.type_annotation {
+            Some(t) => format!("{} {}", t, p.name),
+            None => p.name.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn return_suffix(return_type: &Option<crate::types::EssenceType>) -> String {
+    return_type.as_ref().map(|t| format!(" -> {}", t)).unwrap_or_default()
+}
+
+fn type_suffix(type_annotation: &Option<crate::types::EssenceType>) -> String {
+    type_annotation.as_ref().map(|t| format!(": {}", t)).unwrap_or_default()
+}
+
+// Only literal values are shown; anything computed would be misleading out of context
+fn value_suffix(value: &Expression) -> String {
+    match value {
+        Expression::Number(n) => format!(" = {}", n),
+        Expression::String(s) => format!(" = {:?}", s),
+        Expression::Boolean(b) => format!(" = {}", b),
+        _ => String::new(),
+    }
+}
+
+fn doc_line(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix("---")?;
+    // A line of dashes is a separator, not documentation
+    if rest.starts_with('-') {
+        return None;
+    }
+    Some(rest.strip_prefix(' ').unwrap_or(rest))
+}
+
+/// The `---` block directly above the declaration on `line` (1-based), skipping decorators
+fn doc_comment_above<'a>(lines: &[&'a str], line: usize) -> Vec<&'a str> {
+    let mut index = line.saturating_sub(1);
+    while index > 0 && lines[index - 1].trim_start().starts_with('@') {
+        index -= 1;
+    }
+
+    let end = index;
+    while index > 0 && doc_line(lines[index - 1]).is_some() {
+        index -= 1;
+    }
+    lines[index..end].iter().filter_map(|l| doc_line(l)).collect()
+}
+
+/// A `---` block at the very top of the file, separated from the code by a blank line
+fn module_summary(lines: &[&str]) -> Vec<String> {
+    let block: Vec<String> = lines.iter().map_while(|l| doc_line(l)).map(str::to_string).collect();
+    match lines.get(block.len()) {
+        Some(next) if !block.is_empty() && next.trim().is_empty() => trim_blank(block),
+        _ => Vec::new(),
+    }
+}
+
+fn trim_blank(mut text: Vec<String>) -> Vec<String> {
+    while text.last().map(|l| l.trim().is_empty()).unwrap_or(false) {
+        text.pop();
+    }
+    while text.first().map(|l| l.trim().is_empty()).unwrap_or(false) {
+        text.remove(0);
+    }
+    text
+}
+
+// `@param name description` and `@returns description` fill in the signature tables;
+// every other line is free text.
+fn apply_tags(item: &mut DocItem, comment: Vec<&str>) {
+    for line in comment {
+        if let Some(rest) = line.trim().strip_prefix("@param ") {
+            let (name, description) = rest.trim().split_once(' ').unwrap_or((rest.trim(), ""));
+            match item.params.iter_mut().find(|(p, ..)| p == name) {
+                Some(param) => param.2 = description.trim().to_string(),
+                None => item.params.push((name.to_string(), None, description.trim().to_string())),
+            }
+        } else if let Some(rest) = line.trim().strip_prefix("@returns").or_else(|| line.trim().strip_prefix("@return")) {
+            let description = rest.trim().to_string();
+            match &mut item.returns {
+                Some(returns) => returns.1 = description,
+                None => item.returns = Some((None, description)),
+            }
+        } else {
+            item.text.push(line.to_string());
+        }
+    }
+    item.text = trim_blank(std::mem::take(&mut item.text));
+}
+
+pub fn render_markdown(module: &ModuleDoc) -> String {
+    let mut out = format!("# {}\n\n", module.name);
+    if !module.summary.is_empty() {
+        out.push_str(&module.summary.join("\n"));
+        out.push_str("\n\n");
+    }
+    if module.items.is_empty() {
+        out.push_str("_Nothing documented._\n");
+        return out;
+    }
+
+    for kind in SECTION_ORDER {
+        let items: Vec<&DocItem> = module.items.iter().filter(|i| i.kind == kind).collect();
+        if items.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("## {}\n\n", kind.heading()));
+
+        for item in items {
+            out.push_str(&format!("### `{}`\n\n```flowlang\n{}\n```\n\n", item.name, item.signature));
+            if !item.text.is_empty() {
+                out.push_str(&item.text.join("\n"));
+                out.push_str("\n\n");
+            }
+            if !item.params.is_empty() {
+                out.push_str("| Parameter | Type | Description |\n|---|---|---|\n");
+                for (name, ty, description) in &item.params {
+                    out.push_str(&format!("| `{}` | {} | {} |\n", name, type_cell(ty), description));
+                }
+                out.push('\n');
+            }
+            if let Some((ty, description)) = &item.returns {
+                let ty = ty.as_ref().map(|t| format!("`{}`", t));
+                let text = [ty.unwrap_or_default(), description.clone()]
+                    .into_iter()
+                    .filter(|s| !s.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" — ");
+                out.push_str(&format!("**Returns:** {}\n\n", text));
+            }
+        }
+    }
+    out
+}
+
+fn type_cell(ty: &Option<String>) -> String {
+    ty.as_ref().map(|t| format!("`{}`", t)).unwrap_or_else(|| "—".to_string())
+}
+
+pub fn render_html(module: &ModuleDoc) -> String {
+    let mut body = format!("<h1>{}</h1>\n", escape(&module.name));
+    body.push_str(&text_to_html(&module.summary));
+
+    for kind in SECTION_ORDER {
+        let items: Vec<&DocItem> = module.items.iter().filter(|i| i.kind == kind).collect();
+        if items.is_empty() {
+            continue;
+        }
+        body.push_str(&format!("<h2>{}</h2>\n", kind.heading()));
+
+        for item in items {
+            body.push_str(&format!(
+                "<section id=\"{}\">\n<h3><code>{}</code></h3>\n<pre><code>{}</code></pre>\n",
+                escape(&item.name),
+                escape(&item.name),
+                escape(&item.signature)
+            ));
+            body.push_str(&text_to_html(&item.text));
+            if !item.params.is_empty() {
+                body.push_str("<table>\n<tr><th>Parameter</th><th>Type</th><th>Description</th></tr>\n");
+                for (name, ty, description) in &item.params {
+                    body.push_str(&format!(
+                        "<tr><td><code>{}</code></td><td>{}</td><td>{}</td></tr>\n",
+                        escape(name),
+                        ty.as_ref().map(|t| format!("<code>{}</code>", escape(t))).unwrap_or_else(|| "—".to_string()),
+                        inline_html(description)
+                    ));
+                }
+                body.push_str("</table>\n");
+            }
+            if let Some((ty, description)) = &item.returns {
+                let text = [ty.as_ref().map(|t| format!("<code>{}</code>", escape(t))).unwrap_or_default(), inline_html(description)]
+                    .into_iter()
+                    .filter(|s| !s.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" — ");
+                body.push_str(&format!("<p><strong>Returns:</strong> {}</p>\n", text));
+            }
+            body.push_str("</section>\n");
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(&module.name),
+        HTML_STYLE,
+        body
+    )
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:52rem;margin:2rem auto;padding:0 1rem;line-height:1.5}\
+pre{background:#f4f4f8;padding:.75rem;overflow-x:auto}code{font-family:ui-monospace,monospace}\
+table{border-collapse:collapse}th,td{border:1px solid #ddd;padding:.3rem .6rem;text-align:left}\
+section{margin-bottom:2rem}";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// `code` spans are the only inline Markdown worth supporting in doc comments
+fn inline_html(text: &str) -> String {
+    let mut out = String::new();
+    for (i, part) in escape(text).split('`').enumerate() {
+        if i % 2 == 1 {
+            out.push_str(&format!("<code>{}</code>", part));
+        } else {
+            out.push_str(part);
+        }
+    }
+    out
+}
+
+// Paragraphs, `- ` lists and ``` fences: enough Markdown for doc comments
+fn text_to_html(lines: &[String]) -> String {
+    let mut out = String::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut in_list = false;
+    let mut in_code = false;
+
+    fn flush(out: &mut String, paragraph: &mut Vec<String>) {
+        if !paragraph.is_empty() {
+            out.push_str(&format!("<p>{}</p>\n", paragraph.join(" ")));
+            paragraph.clear();
+        }
+    }
+
+    for line in lines {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            flush(&mut out, &mut paragraph);
+            out.push_str(if in_code { "</code></pre>\n" } else { "<pre><code>" });
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            out.push_str(&escape(line));
+            out.push('\n');
+            continue;
+        }
+
+        let item = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* "));
+        if item.is_some() != in_list {
+            flush(&mut out, &mut paragraph);
+            out.push_str(if in_list { "</ul>\n" } else { "<ul>\n" });
+            in_list = !in_list;
+        }
+        match item {
+            Some(item) => out.push_str(&format!("<li>{}</li>\n", inline_html(item))),
+            None if trimmed.is_empty() => flush(&mut out, &mut paragraph),
+            None => paragraph.push(inline_html(trimmed)),
+        }
+    }
+
+    flush(&mut out, &mut paragraph);
+    if in_list {
+        out.push_str("</ul>\n");
+    }
+    if in_code {
+        out.push_str("</code></pre>\n");
+    }
+    out
+}
+
+/// Generate docs for every .flow file under `paths` into `out`. Returns true on success.
+pub fn run(paths: Vec<PathBuf>, format: DocFormat, out: &Path, include_private: bool) -> bool {
+    let roots = if paths.is_empty() {
+        vec![if Path::new("src").is_dir() { PathBuf::from("src") } else { PathBuf::from(".") }]
+    } else {
+        paths
+    };
+
+    let mut files = Vec::new();
+    for root in &roots {
+        collect_sources(root, root, &mut files);
+    }
+    files.sort();
+
+    if files.is_empty() {
+        eprintln!("{}", "❌ No .flow files found to document".red().bold());
+        return false;
+    }
+
+    println!("{} {} module(s)", "📚 Documenting".bright_cyan().bold(), files.len());
+
+    let mut written = Vec::new();
+    let mut failed = false;
+    for (file, name) in &files {
+        let module = match document_file(file, name, include_private) {
+            Ok(module) => module,
+            Err(e) => {
+                println!("{} {} {}", "✗".red().bold(), file.display(), e.to_string().red());
+                failed = true;
+                continue;
+            }
+        };
+
+        let target = out.join(format!("{}.{}", name, format.extension()));
+        let content = match format {
+            DocFormat::Markdown => render_markdown(&module),
+            DocFormat::Html => render_html(&module),
+        };
+        let result = target
+            .parent()
+            .map(fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| fs::write(&target, content));
+        match result {
+            Ok(()) => {
+                println!("{} {} {}", "✓".green().bold(), target.display(), format!("({} items)", module.items.len()).dimmed());
+                written.push((name.clone(), module));
+            }
+            Err(e) => {
+                println!("{} {} {}", "✗".red().bold(), target.display(), e.to_string().red());
+                failed = true;
+            }
+        }
+    }
+
+    if let Err(e) = write_index(&written, format, out) {
+        eprintln!("{} {}", "❌ Failed to write index:".red().bold(), e);
+        return false;
+    }
+    !failed
+}
+
+fn document_file(path: &Path, name: &str, include_private: bool) -> Result<ModuleDoc, FlowError> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| FlowError::rift(&format!("Failed to read file: {}", e), 0, 0))?
+        .replace("\u{feff}", "");
+    let tokens = crate::lexer::tokenize(&source)?;
+    let program = crate::parser::parse(tokens)?;
+    Ok(extract(name, &source, &program, include_private))
+}
+
+fn write_index(modules: &[(String, ModuleDoc)], format: DocFormat, out: &Path) -> std::io::Result<()> {
+    let target = out.join(format!("index.{}", format.extension()));
+    let content = match format {
+        DocFormat::Markdown => {
+            let mut text = String::from("# API Reference\n\n");
+            for (name, module) in modules {
+                let summary = module.summary.first().map(|s| format!(" — {}", s)).unwrap_or_default();
+                text.push_str(&format!("- [{}]({}.md){}\n", name, name, summary));
+            }
+            text
+        }
+        DocFormat::Html => {
+            let mut list = String::new();
+            for (name, module) in modules {
+                let summary = module.summary.first().map(|s| format!(" — {}", inline_html(s))).unwrap_or_default();
+                list.push_str(&format!("<li><a href=\"{}.html\">{}</a>{}</li>\n", escape(name), escape(name), summary));
+            }
+            format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>API Reference</title>\n<style>{}</style>\n</head>\n<body>\n<h1>API Reference</h1>\n<ul>\n{}</ul>\n</body>\n</html>\n",
+                HTML_STYLE, list
+            )
+        }
+    };
+    fs::create_dir_all(out)?;
+    fs::write(target, content)
+}
+
+/// Find documentable sources, naming each by its path relative to `root` without
+/// the extension ("lib/utils"). Tests and hidden directories (.flowlang/pkg) are skipped.
+fn collect_sources(root: &Path, path: &Path, files: &mut Vec<(PathBuf, String)>) {
+    if path.is_file() {
+        let name = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        files.push((path.to_path_buf(), name));
+        return;
+    }
+
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if entry_path.is_dir() {
+            if !file_name.starts_with('.') && file_name != "__snapshots__" {
+                collect_sources(root, &entry_path, files);
+            }
+        } else if file_name.ends_with(".flow") && !file_name.ends_with("_test.flow") {
+            let relative = entry_path.strip_prefix(root).unwrap_or(&entry_path).with_extension("");
+            let name = relative.to_string_lossy().replace('\\', "/");
+            files.push((entry_path, name));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(source: &str, include_private: bool) -> ModuleDoc {
+        let program = crate::parser::parse(crate::lexer::tokenize(source).unwrap()).unwrap();
+        extract("demo", source, &program, include_private)
+    }
+
+    #[test]
+    fn test_extracts_exported_items_with_tags() {
+        let source = "--- String helpers.\n\n--- Greets someone.\n--- @param name Who to greet\n--- @returns The greeting\n@export cast Spell greet(Silk name) -> Silk {\n    return \"Hi \" + name\n}\n\ncast Spell helper() {\n    return 1\n}\n\n--- Maximum length\n@export seal MAX: Ember = 10\n";
+        let module = document(source, false);
+
+        assert_eq!(module.summary, vec!["String helpers."]);
+        assert_eq!(module.items.len(), 2);
+
+        let greet = &module.items[0];
+        assert_eq!(greet.signature, "cast Spell greet(Silk name) -> Silk");
+        assert_eq!(greet.text, vec!["Greets someone."]);
+        assert_eq!(greet.params, vec![("name".to_string(), Some("Silk".to_string()), "Who to greet".to_string())]);
+        assert_eq!(greet.returns, Some((Some("Silk".to_string()), "The greeting".to_string())));
+
+        assert_eq!(module.items[1].signature, "seal MAX: Ember = 10");
+        assert_eq!(module.items[1].text, vec!["Maximum length"]);
+
+        assert_eq!(document(source, true).items.len(), 3);
+    }
+
+    #[test]
+    fn test_renders_markdown_and_html() {
+        let module = document("--- Adds `a` and `b`.\ncast Spell add(a, b) {\n    return a + b\n}\n", false);
+        let markdown = render_markdown(&module);
+        assert!(markdown.contains("## Spells"));
+        assert!(markdown.contains("cast Spell add(a, b)"));
+        assert!(markdown.contains("| `a` | — |  |"));
+
+        let html = render_html(&module);
+        assert!(html.contains("<p>Adds <code>a</code> and <code>b</code>.</p>"));
+    }
+}
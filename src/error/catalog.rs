@@ -0,0 +1,247 @@
+// Message catalog for user-facing error output
+//
+// Error text lives in locale files (src/error/locales/*.toml) keyed by stable error
+// codes, so it can be translated or switched to a plain, professional register
+// without touching the code that raises errors. English is always loaded as the base
+// and other locales are layered on top, so a partial translation still renders.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// How errors are presented to the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorStyle {
+    /// Episode banners and "Next Time" teasers (default)
+    Anime,
+    /// Compiler-style `error[FL0004]: ...` output
+    Plain,
+}
+
+impl ErrorStyle {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "anime" => Some(ErrorStyle::Anime),
+            "plain" => Some(ErrorStyle::Plain),
+            _ => None,
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            ErrorStyle::Anime => "anime",
+            ErrorStyle::Plain => "plain",
+        }
+    }
+}
+
+/// Locales bundled into the binary
+const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("locales/en.toml")),
+    ("ja", include_str!("locales/ja.toml")),
+];
+
+struct Settings {
+    style: ErrorStyle,
+    catalog: Catalog,
+}
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// Flattened "section.key" -> text lookup for one locale
+pub struct Catalog {
+    entries: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// Build the catalog for `locale`, falling back to English for missing keys.
+    /// Returns None if no locale file matches.
+    pub fn load(locale: &str) -> Option<Self> {
+        let locale = locale.to_ascii_lowercase();
+        let overlay = LOCALES.iter().find(|(name, _)| *name == locale)?;
+
+        let mut entries = HashMap::new();
+        flatten_into(&mut entries, LOCALES[0].1);
+        if overlay.0 != "en" {
+            flatten_into(&mut entries, overlay.1);
+        }
+
+        Some(Catalog { entries })
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(|s| s.as_str())
+    }
+
+    /// UI label such as "Error" or "Next Time"
+    pub fn label<'a>(&'a self, key: &'a str) -> &'a str {
+        self.get(&format!("labels.{}", key)).unwrap_or(key)
+    }
+
+    /// Human-readable name for an error kind ("Type" -> "Type error")
+    pub fn kind<'a>(&'a self, kind: &'a str) -> &'a str {
+        self.get(&format!("kinds.{}", kind)).unwrap_or(kind)
+    }
+
+    /// A field (title, scene, next_time, summary, ...) for an error code
+    pub fn code_text(&self, code: &str, field: &str) -> &str {
+        self.get(&format!("codes.{}.{}", code, field))
+            .or_else(|| self.get(&format!("codes.FL0000.{}", field)))
+            .unwrap_or("")
+    }
+
+    /// A list field (such as `causes`) for an error code, one entry per item.
+    /// Unlike `code_text` there is no FL0000 fallback.
+    pub fn code_list(&self, code: &str, field: &str) -> Vec<&str> {
+        self.get(&format!("codes.{}.{}", code, field))
+            .map(|text| text.lines().collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether the catalog documents `code`
+    pub fn has_code(&self, code: &str) -> bool {
+        self.get(&format!("codes.{}.title", code)).is_some()
+    }
+
+    /// All documented error codes, sorted
+    pub fn codes(&self) -> Vec<&str> {
+        let mut codes: Vec<&str> = self
+            .entries
+            .keys()
+            .filter_map(|k| k.strip_prefix("codes."))
+            .filter_map(|k| k.split('.').next())
+            .collect();
+        codes.sort();
+        codes.dedup();
+        codes
+    }
+
+    /// A built-in message in the given style
+    pub fn message(&self, key: &str, style: ErrorStyle) -> String {
+        self.get(&format!("messages.{}.{}", style.key(), key))
+            .or_else(|| self.get(&format!("messages.anime.{}", key)))
+            .unwrap_or(key)
+            .to_string()
+    }
+}
+
+fn flatten_into(entries: &mut HashMap<String, String>, source: &str) {
+    // Bundled files are checked by the catalog tests, so a parse failure here is a bug
+    let table: toml::Table = source.parse().expect("bundled locale file is valid TOML");
+    flatten_table(entries, "", &table);
+}
+
+fn flatten_table(entries: &mut HashMap<String, String>, prefix: &str, table: &toml::Table) {
+    for (key, value) in table {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match value {
+            toml::Value::Table(inner) => flatten_table(entries, &path, inner),
+            toml::Value::String(text) => {
+                entries.insert(path, text.trim_matches('\n').to_string());
+            }
+            // Lists are stored one item per line
+            toml::Value::Array(items) => {
+                let lines: Vec<String> = items
+                    .iter()
+                    .map(|item| item.as_str().map(str::to_string).unwrap_or_else(|| item.to_string()))
+                    .collect();
+                entries.insert(path, lines.join("\n"));
+            }
+            other => {
+                entries.insert(path, other.to_string());
+            }
+        }
+    }
+}
+
+/// Pick the locale: explicit choice, then FLOWLANG_LOCALE, then LC_ALL / LANG.
+/// Environment values like "ja_JP.UTF-8" match on the language part.
+fn detect_locale(explicit: Option<&str>) -> Result<Catalog, String> {
+    if let Some(locale) = explicit {
+        return Catalog::load(locale).ok_or_else(|| {
+            let known: Vec<&str> = LOCALES.iter().map(|(name, _)| *name).collect();
+            format!("Unknown locale '{}' (available: {})", locale, known.join(", "))
+        });
+    }
+
+    for var in ["FLOWLANG_LOCALE", "LC_ALL", "LANG"] {
+        if let Ok(value) = std::env::var(var) {
+            let language = value.split(['_', '.', '-']).next().unwrap_or("");
+            if let Some(catalog) = Catalog::load(language) {
+                return Ok(catalog);
+            }
+        }
+    }
+
+    Ok(Catalog::load("en").expect("English catalog is bundled"))
+}
+
+/// Set the error style and locale for this process. `style` and `locale` come from
+/// the command line; when absent FLOWLANG_ERROR_STYLE and the locale environment are used.
+/// Only the first call has any effect.
+pub fn configure(style: Option<&str>, locale: Option<&str>) -> Result<(), String> {
+    let style = match style.map(str::to_string).or_else(|| std::env::var("FLOWLANG_ERROR_STYLE").ok()) {
+        Some(name) => ErrorStyle::parse(&name)
+            .ok_or_else(|| format!("Unknown error style '{}' (expected \"anime\" or \"plain\")", name))?,
+        None => ErrorStyle::Anime,
+    };
+    let catalog = detect_locale(locale)?;
+
+    let _ = SETTINGS.set(Settings { style, catalog });
+    Ok(())
+}
+
+fn settings() -> &'static Settings {
+    SETTINGS.get_or_init(|| Settings {
+        style: ErrorStyle::Anime,
+        catalog: Catalog::load("en").expect("English catalog is bundled"),
+    })
+}
+
+/// The active error style
+pub fn style() -> ErrorStyle {
+    settings().style
+}
+
+/// The active catalog
+pub fn catalog() -> &'static Catalog {
+    &settings().catalog
+}
+
+/// A built-in message in the active locale and style
+pub fn message(key: &str) -> String {
+    catalog().message(key, style())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_locales_cover_english_codes() {
+        let en = Catalog::load("en").unwrap();
+        let en_codes = en.codes();
+        assert!(en_codes.contains(&"FL0000") && en_codes.contains(&"FL0039"));
+
+        for code in &en_codes {
+            for field in ["title", "scene", "next_time", "summary", "explanation"] {
+                assert!(!en.code_text(code, field).is_empty(), "{} is missing {}", code, field);
+            }
+        }
+
+        // Every other locale parses and only uses codes English knows about
+        for (name, _) in LOCALES {
+            let catalog = Catalog::load(name).unwrap();
+            assert_eq!(catalog.codes(), en_codes, "locale {} adds unknown codes", name);
+        }
+    }
+
+    #[test]
+    fn test_locale_falls_back_to_english() {
+        let ja = Catalog::load("JA").unwrap();
+        assert_eq!(ja.label("error"), "エラー");
+        assert_eq!(ja.code_text("FL0004", "title"), "THE FORBIDDEN TYPE");
+        assert_eq!(ja.message("division_by_zero", ErrorStyle::Plain), "ゼロで割ることはできません");
+        assert!(ja.code_list("FL0004", "causes").len() > 1);
+        assert!(Catalog::load("xx").is_none());
+    }
+}
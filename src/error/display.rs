@@ -1,52 +1,37 @@
 // Enhanced error printing with episode system
-use super::{FlowError, get_episode_for_error, render_trace_tree};
+use super::catalog::{self, ErrorStyle};
+use super::{FlowError, get_episode_for_error, render_cause_tree, render_trace_tree};
 use colored::*;
 
 pub fn print_error_with_episode(error: &FlowError, show_trace: bool, trace_options: &super::TraceOptions, filename: Option<&str>) {
+    if crate::platform::is_quiet() {
+        print_error_line(error, filename);
+        return;
+    }
+    if catalog::style() == ErrorStyle::Plain {
+        print_error_plain(error, filename);
+        return;
+    }
+
     println!();
     
     // Get episode info based on error type
     let error_type = error.error_type_name();
-    let message = match error {
-        FlowError::Syntax { message, .. } => message.as_str(),
-        FlowError::Type { message, .. } => message.as_str(),
-        FlowError::Runtime { message, .. } => message.as_str(),
-        FlowError::Undefined { message, .. } => message.as_str(),
-        FlowError::OutOfRange { message, .. } => message.as_str(),
-        FlowError::DivisionByZero { message, .. } => message.as_str(),
-        FlowError::Rift { message, .. } => message.as_str(),
-        FlowError::Glitch { message, .. } => message.as_str(),
-        FlowError::VoidTear { message, .. } => message.as_str(),
-        FlowError::Spirit { message, .. } => message.as_str(),
-        FlowError::Panic { message, .. } => message.as_str(),
-        FlowError::Wound { message, .. } => message.as_str(),
-        FlowError::Break { .. } => "Break seal used outside loop",
-        FlowError::Continue { .. } => "Continue seal used outside loop",
-    };
-    
-    let (line, column) = match error {
-        FlowError::Syntax { line, column, .. } => (*line, *column),
-        FlowError::Type { line, column, .. } => (*line, *column),
-        FlowError::Runtime { line, column, .. } => (*line, *column),
-        FlowError::Undefined { line, column, .. } => (*line, *column),
-        FlowError::OutOfRange { line, column, .. } => (*line, *column),
-        FlowError::DivisionByZero { line, column, .. } => (*line, *column),
-        FlowError::Rift { line, column, .. } => (*line, *column),
-        FlowError::Glitch { line, column, .. } => (*line, *column),
-        FlowError::VoidTear { line, column, .. } => (*line, *column),
-        FlowError::Spirit { line, column, .. } => (*line, *column),
-        FlowError::Panic { line, column, .. } => (*line, *column),
-        FlowError::Wound { line, column, .. } => (*line, *column),
-        FlowError::Break { line, column } => (*line, *column),
-        FlowError::Continue { line, column } => (*line, *column),
-    };
+    let message = error.message();
+    let (line, _) = error.position();
     
-    let episode = get_episode_for_error(error_type, message);
+    let episode = get_episode_for_error(error.episode_kind(), &message);
+    let labels = catalog::catalog();
     
     // Print episode banner
     println!("{}", episode.banner().bright_cyan().bold());
-    println!("{}: {} \"{}\"", "Error".red().bold(), error_type.yellow(), message.bright_white());
-    println!("{}: line {}, stance {}", "Scene".cyan(), line, episode.scene_context.green());
+    println!("{} [{}]: {} \"{}\"", labels.label("error").red().bold(), episode.code.dimmed(), error_type.yellow(), message.bright_white());
+    println!("{}: {} {}, {} {}", labels.label("scene").cyan(), labels.label("line"), line, labels.label("stance"), episode.scene_context.green());
+    
+    let causes = render_cause_tree(error, trace_options);
+    if !causes.is_empty() {
+        println!("{}", causes.trim_end().bright_white());
+    }
     
     // Print trace tree if enabled and available
     if show_trace && trace_options.enabled {
@@ -67,9 +52,83 @@ pub fn print_error_with_episode(error: &FlowError, show_trace: bool, trace_optio
         }
     }
     
+    for fix in error.fixes() {
+        println!();
+        println!("💡 {}: {}", labels.label("suggestion").bright_green().bold(), fix.message);
+        println!("   {}", labels.label(if fix.safe { "fix_hint" } else { "fix_hint_unsafe" }).dimmed());
+    }
+    
     // Print "Next Time" teaser
     println!();
-    println!("{}", "Next Time:".bright_yellow().bold());
+    println!("{}", format!("{}:", labels.label("next_time")).bright_yellow().bold());
     println!("   {}", format!("\"{}\"", episode.next_time).italic().bright_white());
+    if episode.code != "FL0000" {
+        println!();
+        println!("{}", labels.label("explain_hint").replace("{code}", episode.code).dimmed());
+    }
     println!();
 }
+
+// One line on stderr for `--quiet`, so stdout holds only what the script printed:
+//
+//   error[FL0004]: Type error: Cannot add Ember and Silk (script.flow:3:5)
+fn print_error_line(error: &FlowError, filename: Option<&str>) {
+    let catalog = catalog::catalog();
+    let (line, column) = error.position();
+    let at = match line {
+        0 => String::new(),
+        _ => format!(" ({}:{}:{})", filename.unwrap_or("script.flow"), line, column),
+    };
+    eprintln!(
+        "{}: {}: {}{}",
+        format!("{}[{}]", catalog.label("error").to_lowercase(), error.code()).red().bold(),
+        catalog.kind(error.error_type_name()),
+        error.message(),
+        at
+    );
+}
+
+// Compiler-style output for `--error-style plain`:
+//
+//   error[FL0004]: Type error: Cannot add Ember and Silk
+//     --> script.flow:3:5
+//     = help: A value was used with an operation that does not accept its type.
+//     = caused by: I/O error: connection refused (line 2)                  <- caused_by only
+//     = suggestion: replace '==' with 'is~' (run `flowlang fix` to apply it)   <- fix-its only
+//     = note: run `flowlang explain FL0004` for details
+fn print_error_plain(error: &FlowError, filename: Option<&str>) {
+    let catalog = catalog::catalog();
+    let code = error.code();
+    let (line, column) = error.position();
+
+    println!(
+        "{}: {}: {}",
+        format!("{}[{}]", catalog.label("error").to_lowercase(), code).red().bold(),
+        catalog.kind(error.error_type_name()),
+        error.message()
+    );
+    if line > 0 {
+        println!("  {} {}:{}:{}", "-->".cyan(), filename.unwrap_or("script.flow"), line, column);
+    }
+    let summary = catalog.code_text(code, "summary");
+    if !summary.is_empty() {
+        println!("  {} {}: {}", "=".cyan(), catalog.label("help"), summary);
+    }
+    for cause in error.chain().skip(1) {
+        let at = match cause.position().0 {
+            0 => String::new(),
+            line => format!(" ({} {})", catalog.label("line"), line),
+        };
+        println!(
+            "  {} {}: {}: {}{}",
+            "=".cyan(), catalog.label("caused_by").to_lowercase(), catalog.kind(cause.error_type_name()), cause.message(), at
+        );
+    }
+    for fix in error.fixes() {
+        let hint = catalog.label(if fix.safe { "fix_hint" } else { "fix_hint_unsafe" });
+        println!("  {} {}: {} ({})", "=".cyan(), catalog.label("suggestion"), fix.message, hint);
+    }
+    if code != "FL0000" {
+        println!("  {} {}: {}", "=".cyan(), catalog.label("note"), catalog.label("explain_hint").replace("{code}", code));
+    }
+}
@@ -0,0 +1,109 @@
+// Extended error descriptions for `flowlang explain FL0004`
+//
+// Every page is assembled from the message catalog: the episode banner and "Next Time"
+// teaser from the anime style, plus the explanation, common causes and example fix
+// kept alongside each code in the locale files.
+use super::catalog::{self, ErrorStyle};
+use super::episode_by_code;
+use colored::*;
+
+/// Turn user input like "FL0004", "fl4" or "4" into the canonical "FL0004" form
+pub fn normalize_code(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    let digits = match trimmed.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("fl") => &trimmed[2..],
+        _ => trimmed,
+    };
+    if digits.is_empty() || digits.len() > 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(format!("FL{:0>4}", digits))
+}
+
+/// Print the explanation page for `input`. Returns false if the code is unknown.
+pub fn print_explanation(input: &str) -> bool {
+    let catalog = catalog::catalog();
+    let code = match normalize_code(input) {
+        Some(code) if catalog.has_code(&code) => code,
+        _ => {
+            eprintln!(
+                "{} {}",
+                "❌".red(),
+                format!("Unknown error code '{}'. Run `flowlang explain --list` to see all codes.", input).red().bold()
+            );
+            return false;
+        }
+    };
+
+    let anime = catalog::style() == ErrorStyle::Anime;
+    let episode = episode_by_code(&code);
+
+    println!();
+    match &episode {
+        Some(episode) if anime => {
+            println!("{}", episode.banner().bright_cyan().bold());
+            println!(
+                "{} · {} {} · {}: {}",
+                code.yellow().bold(),
+                catalog.label("season"),
+                episode.season,
+                catalog.label("scene").cyan(),
+                episode.scene_context.green()
+            );
+        }
+        _ => println!("{}", code.yellow().bold()),
+    }
+    println!("{}", catalog.code_text(&code, "summary").bright_white().bold());
+
+    println!();
+    println!("{}", catalog.code_text(&code, "explanation"));
+
+    let causes = catalog.code_list(&code, "causes");
+    if !causes.is_empty() {
+        println!();
+        println!("{}", format!("{}:", catalog.label("causes")).bright_yellow().bold());
+        for cause in causes {
+            println!("  • {}", cause);
+        }
+    }
+
+    let fix = catalog.code_list(&code, "fix");
+    if !fix.is_empty() {
+        println!();
+        println!("{}", format!("{}:", catalog.label("fix")).bright_yellow().bold());
+        for line in fix {
+            println!("    {}", line.bright_white());
+        }
+    }
+
+    if let Some(episode) = episode.filter(|_| anime) {
+        println!();
+        println!("{}", format!("{}:", catalog.label("next_time")).bright_yellow().bold());
+        println!("   {}", format!("\"{}\"", episode.next_time).italic().bright_white());
+    }
+    println!();
+    true
+}
+
+/// Print every documented code with its one-line summary
+pub fn print_code_list() {
+    let catalog = catalog::catalog();
+    for code in catalog.codes() {
+        println!("{}  {}", code.yellow(), catalog.code_text(code, "summary"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_code() {
+        assert_eq!(normalize_code("FL0004").as_deref(), Some("FL0004"));
+        assert_eq!(normalize_code("fl4").as_deref(), Some("FL0004"));
+        assert_eq!(normalize_code(" 25 ").as_deref(), Some("FL0025"));
+        assert_eq!(normalize_code("FL").as_deref(), None);
+        assert_eq!(normalize_code("E0308").as_deref(), None);
+        assert_eq!(normalize_code("FL12345").as_deref(), None);
+    }
+}
@@ -0,0 +1,181 @@
+// Fix-its: structured suggested edits attached to errors
+//
+// An error that knows how to repair itself (`otherwise` instead of `abandon Stance`,
+// `==` instead of `is~`, ...) carries a FixIt. The error display shows the suggestion
+// and `flowlang fix` applies the safe ones to the file.
+
+use crate::lexer::unicode::extends_cluster;
+
+/// Replace `len` characters starting at (line, column) with `replacement`.
+/// Lines and columns are 1-based, like token positions: a column is one
+/// user-perceived character (see `lexer::unicode`), while `len` counts chars.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edit {
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixIt {
+    /// Short description, e.g. "replace 'otherwise' with 'abandon Stance'"
+    pub message: String,
+    pub edits: Vec<Edit>,
+    /// Safe fixes keep the meaning the author clearly intended and are applied by
+    /// `flowlang fix`; the rest need `--unsafe`
+    pub safe: bool,
+}
+
+impl FixIt {
+    /// A single replacement of `len` characters at (line, column)
+    pub fn replace(message: impl Into<String>, line: usize, column: usize, len: usize, replacement: &str) -> Self {
+        FixIt {
+            message: message.into(),
+            edits: vec![Edit { line, column, len, replacement: replacement.to_string() }],
+            safe: true,
+        }
+    }
+
+    pub fn unsafe_fix(mut self) -> Self {
+        self.safe = false;
+        self
+    }
+
+    /// Apply the edits to `source`. Returns None if an edit points outside the text.
+    pub fn apply(&self, source: &str) -> Option<String> {
+        let mut chars: Vec<char> = source.chars().collect();
+
+        // Resolve every position first, then edit from the back so earlier offsets stay valid
+        let mut resolved = Vec::new();
+        for edit in &self.edits {
+            let start = char_offset(&chars, edit.line, edit.column)?;
+            if start + edit.len > chars.len() {
+                return None;
+            }
+            resolved.push((start, edit));
+        }
+        resolved.sort_by_key(|(start, _)| std::cmp::Reverse(*start));
+
+        for (start, edit) in resolved {
+            chars.splice(start..start + edit.len, edit.replacement.chars());
+        }
+        Some(chars.into_iter().collect())
+    }
+}
+
+/// Outcome of `fix_source`: the rewritten text, the fixes applied in order, and the
+/// error that stopped the loop (None once the source parses)
+pub struct FixResult {
+    pub source: String,
+    pub applied: Vec<FixIt>,
+    pub remaining: Option<super::FlowError>,
+}
+
+// Each pass can only see the first error, so one file with many mistakes takes many passes
+const MAX_FIX_PASSES: usize = 50;
+
+/// Repeatedly parse `source` and apply the first fix-it of each error until it parses,
+/// an error has no applicable fix, or a fix stops making progress.
+pub fn fix_source(source: &str, allow_unsafe: bool) -> FixResult {
+    let mut current = source.to_string();
+    let mut applied = Vec::new();
+
+    for _ in 0..MAX_FIX_PASSES {
+        let error = match crate::lexer::tokenize(&current).and_then(crate::parser::parse) {
+            Ok(_) => return FixResult { source: current, applied, remaining: None },
+            Err(e) => e,
+        };
+
+        let fix = error.fixes().iter().find(|f| f.safe || allow_unsafe).cloned();
+        let next = fix.as_ref().and_then(|f| f.apply(&current));
+        match (fix, next) {
+            (Some(fix), Some(next)) if next != current => {
+                current = next;
+                applied.push(fix);
+            }
+            _ => return FixResult { source: current, applied, remaining: Some(error) },
+        }
+    }
+
+    let remaining = crate::lexer::tokenize(&current).and_then(crate::parser::parse).err();
+    FixResult { source: current, applied, remaining }
+}
+
+fn char_offset(chars: &[char], line: usize, column: usize) -> Option<usize> {
+    let mut current_line = 1;
+    let mut line_start = 0;
+    if line == 0 || column == 0 {
+        return None;
+    }
+    for (i, c) in chars.iter().enumerate() {
+        if current_line == line {
+            break;
+        }
+        if *c == '\n' {
+            current_line += 1;
+            line_start = i + 1;
+        }
+    }
+    if current_line != line {
+        return None;
+    }
+
+    // Walk to the start of the column'th cluster on the line
+    let mut index = line_start;
+    let mut current_column = 1;
+    while current_column < column {
+        index += 1;
+        match chars.get(index) {
+            Some(&c) if !extends_cluster(&chars[..index], c) => current_column += 1,
+            Some(_) => {}
+            None => return Some(index + column - current_column - 1),
+        }
+    }
+    Some(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_edits() {
+        let fix = FixIt::replace("use is~", 2, 11, 2, "is~");
+        assert_eq!(fix.apply("let a = 1\nlet b = a == 1\n").as_deref(), Some("let a = 1\nlet b = a is~ 1\n"));
+
+        let two = FixIt {
+            message: "both".to_string(),
+            edits: vec![
+                Edit { line: 1, column: 1, len: 2, replacement: "in Stance".to_string() },
+                Edit { line: 1, column: 5, len: 1, replacement: "é".to_string() },
+            ],
+            safe: true,
+        };
+        assert_eq!(two.apply("if (x) {}").as_deref(), Some("in Stance (é) {}"));
+
+        // The combining accent and the skin tone don't take columns of their own
+        let fix = FixIt::replace("use is~", 1, 14, 2, "is~");
+        assert_eq!(fix.apply("let cafe\u{301} = 👍🏽 == 1").as_deref(), Some("let cafe\u{301} = 👍🏽 is~ 1"));
+        assert_eq!(FixIt::replace("out of range", 3, 1, 1, "x").apply("one line"), None);
+    }
+
+    #[test]
+    fn test_fix_source() {
+        let source = "var count = 1\nif (count == 1 && true) {\n    shout(\"one\")\n} else {\n    shout(\"other\")\n}\n";
+        let result = fix_source(source, false);
+        assert!(result.remaining.is_none());
+        assert_eq!(result.applied.len(), 5);
+        assert_eq!(
+            result.source,
+            "let count = 1\nin Stance (count is~ 1 both! true) {\n    shout(\"one\")\n} abandon Stance {\n    shout(\"other\")\n}\n"
+        );
+
+        // '=' in a condition is only rewritten with allow_unsafe
+        let assignment = "let x = 1\nin Stance (x = 1) { shout(x) }\n";
+        let result = fix_source(assignment, false);
+        assert!(result.applied.is_empty() && result.remaining.is_some());
+        let result = fix_source(assignment, true);
+        assert_eq!(result.source, "let x = 1\nin Stance (x is~ 1) { shout(x) }\n");
+    }
+}
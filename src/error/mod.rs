@@ -1,22 +1,43 @@
 use colored::*;
 use std::fmt;
 
+use crate::types::Value;
+
 // Episode system for anime-themed errors
 pub mod episodes;
-pub use episodes::{EpisodeInfo, StackFrame, get_episode_for_error};
+pub use episodes::{EpisodeInfo, StackFrame, episode_by_code, get_episode_for_error};
 
 // Trace tree visualization
 pub mod trace;
-pub use trace::{TraceOptions, render_trace_tree, get_terminal_width};
+pub use trace::{TraceOptions, render_cause_tree, render_trace_tree, get_terminal_width};
+
+// Localizable message catalog and error codes
+pub mod catalog;
+
+// `flowlang explain` pages built from the catalog
+pub mod explain;
+
+// Structured suggested edits (fix-its) carried by syntax errors
+pub mod fixit;
+pub use fixit::FixIt;
 
 // Enhanced error display with episodes
 pub mod display;
 pub use display::print_error_with_episode;
 
 
+/// The parts of a `sigil error` error, boxed so FlowError stays small
+#[derive(Debug, Clone)]
+pub struct CustomError {
+    pub name: String,
+    /// The sigil instance that was ruptured
+    pub payload: Value,
+    pub message: String,
+}
+
 #[derive(Debug, Clone)]
 pub enum FlowError {
-    Syntax { message: String, line: usize, column: usize },
+    Syntax { message: String, line: usize, column: usize, fixes: Vec<FixIt> },
     Type { message: String, line: usize, column: usize },
     Runtime { message: String, line: usize, column: usize },
     Undefined { message: String, line: usize, column: usize },
@@ -29,6 +50,11 @@ pub enum FlowError {
     Spirit { message: String, line: usize, column: usize },    // Generic catchable error
     Panic { message: String, line: usize, column: usize },     // Catastrophic failure
     Wound { message: String, line: usize, column: usize },     // Soft error (non-fatal)
+    /// An error declared with `sigil error Name { ... }`
+    Custom { error: Box<CustomError>, line: usize, column: usize },
+    /// An error raised while handling another: `rupture Spirit "msg" caused_by e`.
+    /// Reads as `error` everywhere except when walking the chain.
+    Caused { error: Box<FlowError>, cause: Box<FlowError> },
     
     // Control Flow "Errors" (Internal use only)
     Break { line: usize, column: usize },
@@ -41,6 +67,25 @@ impl FlowError {
             message: message.to_string(),
             line,
             column,
+            fixes: Vec::new(),
+        }
+    }
+
+    /// A syntax error that knows how to repair itself
+    pub fn syntax_with_fix(message: &str, line: usize, column: usize, fix: FixIt) -> Self {
+        FlowError::Syntax {
+            message: message.to_string(),
+            line,
+            column,
+            fixes: vec![fix],
+        }
+    }
+
+    /// Suggested edits attached to the error (only syntax errors carry any)
+    pub fn fixes(&self) -> &[FixIt] {
+        match self {
+            FlowError::Syntax { fixes, .. } => fixes,
+            _ => &[],
         }
     }
     
@@ -78,7 +123,7 @@ impl FlowError {
     
     pub fn division_by_zero(line: usize, column: usize) -> Self {
         FlowError::DivisionByZero {
-            message: "STOP! To divide by the Hollow is to tear reality apart!".to_string(),
+            message: catalog::message("division_by_zero"),
             line,
             column,
         }
@@ -133,6 +178,24 @@ impl FlowError {
         }
     }
     
+    /// This error, remembering that it was raised while handling `cause`
+    pub fn caused_by(self, cause: FlowError) -> Self {
+        FlowError::Caused { error: Box::new(self), cause: Box::new(cause) }
+    }
+
+    /// The error this one was raised while handling, if any
+    pub fn cause(&self) -> Option<&FlowError> {
+        match self {
+            FlowError::Caused { cause, .. } => Some(cause),
+            _ => None,
+        }
+    }
+
+    /// Each error in the cause chain, outermost first, starting with this one
+    pub fn chain(&self) -> impl Iterator<Item = &FlowError> {
+        std::iter::successors(Some(self), |err| err.cause())
+    }
+    
     pub fn break_seal(line: usize, column: usize) -> Self {
         FlowError::Break { line, column }
     }
@@ -141,6 +204,114 @@ impl FlowError {
         FlowError::Continue { line, column }
     }
     
+    /// The error message (a catalog message for Break/Continue, which carry none)
+    pub fn message(&self) -> String {
+        match self {
+            FlowError::Syntax { message, .. }
+            | FlowError::Type { message, .. }
+            | FlowError::Runtime { message, .. }
+            | FlowError::Undefined { message, .. }
+            | FlowError::OutOfRange { message, .. }
+            | FlowError::DivisionByZero { message, .. }
+            | FlowError::Rift { message, .. }
+            | FlowError::Glitch { message, .. }
+            | FlowError::VoidTear { message, .. }
+            | FlowError::Spirit { message, .. }
+            | FlowError::Panic { message, .. }
+            | FlowError::Wound { message, .. } => message.clone(),
+            FlowError::Custom { error, .. } => error.message.clone(),
+            FlowError::Caused { error, .. } => error.message(),
+            FlowError::Break { .. } => catalog::message("break_outside_loop"),
+            FlowError::Continue { .. } => catalog::message("continue_outside_loop"),
+        }
+    }
+
+    /// (line, column) where the error was raised
+    pub fn position(&self) -> (usize, usize) {
+        match self {
+            FlowError::Syntax { line, column, .. }
+            | FlowError::Type { line, column, .. }
+            | FlowError::Runtime { line, column, .. }
+            | FlowError::Undefined { line, column, .. }
+            | FlowError::OutOfRange { line, column, .. }
+            | FlowError::DivisionByZero { line, column, .. }
+            | FlowError::Rift { line, column, .. }
+            | FlowError::Glitch { line, column, .. }
+            | FlowError::VoidTear { line, column, .. }
+            | FlowError::Spirit { line, column, .. }
+            | FlowError::Panic { line, column, .. }
+            | FlowError::Wound { line, column, .. }
+            | FlowError::Custom { line, column, .. }
+            | FlowError::Break { line, column }
+            | FlowError::Continue { line, column } => (*line, *column),
+            FlowError::Caused { error, .. } => error.position(),
+        }
+    }
+
+    /// This error placed at (line, column), unless it already knows where it was raised
+    pub fn or_at(mut self, at_line: usize, at_column: usize) -> Self {
+        if let FlowError::Caused { error, cause } = self {
+            return FlowError::Caused { error: Box::new(error.or_at(at_line, at_column)), cause };
+        }
+        if let FlowError::Syntax { line, column, .. }
+        | FlowError::Type { line, column, .. }
+        | FlowError::Runtime { line, column, .. }
+        | FlowError::Undefined { line, column, .. }
+        | FlowError::OutOfRange { line, column, .. }
+        | FlowError::DivisionByZero { line, column, .. }
+        | FlowError::Rift { line, column, .. }
+        | FlowError::Glitch { line, column, .. }
+        | FlowError::VoidTear { line, column, .. }
+        | FlowError::Spirit { line, column, .. }
+        | FlowError::Panic { line, column, .. }
+        | FlowError::Wound { line, column, .. }
+        | FlowError::Custom { line, column, .. }
+        | FlowError::Break { line, column }
+        | FlowError::Continue { line, column } = &mut self
+        {
+            if *line == 0 {
+                *line = at_line;
+                *column = at_column;
+            }
+        }
+        self
+    }
+
+    /// Stable error code (e.g. "FL0004") for tooling and `flowlang explain`
+    pub fn code(&self) -> &'static str {
+        get_episode_for_error(self.episode_kind(), &self.message()).code
+    }
+
+    /// Process exit status when this error goes uncaught. Each class gets its own,
+    /// so scripts and CI can tell a failed fetch from a bug; 2 is left for usage errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            FlowError::Syntax { .. } => 3,
+            FlowError::Type { .. } => 4,
+            FlowError::Undefined { .. } => 5,
+            FlowError::OutOfRange { .. } => 6,
+            FlowError::DivisionByZero { .. } => 7,
+            FlowError::Rift { .. } => 10,
+            FlowError::Glitch { .. } => 11,
+            FlowError::VoidTear { .. } => 12,
+            FlowError::Spirit { .. } | FlowError::Custom { .. } => 13,
+            FlowError::Panic { .. } => 14,
+            FlowError::Wound { .. } => 15,
+            FlowError::Caused { error, .. } => error.exit_code(),
+            FlowError::Runtime { .. } | FlowError::Break { .. } | FlowError::Continue { .. } => 1,
+        }
+    }
+
+    /// The kind that picks the episode and code. Errors declared with `sigil error`
+    /// use Spirit's, since their names mean nothing to the catalog.
+    pub fn episode_kind(&self) -> &str {
+        match self {
+            FlowError::Custom { .. } => "Spirit",
+            FlowError::Caused { error, .. } => error.episode_kind(),
+            _ => self.error_type_name(),
+        }
+    }
+
     pub fn error_type_name(&self) -> &str {
         match self {
             FlowError::Syntax { .. } => "Syntax",
@@ -155,6 +326,8 @@ impl FlowError {
             FlowError::Spirit { .. } => "Spirit",
             FlowError::Panic { .. } => "Panic",
             FlowError::Wound { .. } => "Wound",
+            FlowError::Custom { error, .. } => &error.name,
+            FlowError::Caused { error, .. } => error.error_type_name(),
             FlowError::Break { .. } => "Break",
             FlowError::Continue { .. } => "Continue",
         }
@@ -164,7 +337,7 @@ impl FlowError {
 impl fmt::Display for FlowError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            FlowError::Syntax { message, line, column } => {
+            FlowError::Syntax { message, line, column, .. } => {
                 write!(f, "Syntax Error at {}:{} - {}", line, column, message)
             }
             FlowError::Type { message, line, column } => {
@@ -200,6 +373,10 @@ impl fmt::Display for FlowError {
             FlowError::Wound { message, line, column } => {
                 write!(f, "🩹 WOUND at {}:{} - {}", line, column, message)
             }
+            FlowError::Custom { error, line, column } => {
+                write!(f, "💥 {} at {}:{} - {}", error.name, line, column, error.message)
+            }
+            FlowError::Caused { error, .. } => write!(f, "{}", error),
             FlowError::Break { line, column } => {
                 write!(f, "Break at {}:{}", line, column)
             }
@@ -1,5 +1,5 @@
 // Trace tree rendering functions
-use super::StackFrame;
+use super::{FlowError, StackFrame, catalog, get_episode_for_error};
 
 pub struct TraceOptions {
     pub enabled: bool,
@@ -113,6 +113,57 @@ fn render_raw_trace(frames: &[StackFrame]) -> String {
     output
 }
 
+/// The errors `error` was raised while handling (`caused_by`), each nested under
+/// the one it caused. Empty when there is no cause.
+pub fn render_cause_tree(error: &FlowError, options: &TraceOptions) -> String {
+    let causes: Vec<&FlowError> = error.chain().skip(1).collect();
+    if causes.is_empty() {
+        return String::new();
+    }
+
+    let labels = catalog::catalog();
+    let mut output = String::new();
+    if options.raw_mode {
+        output.push_str("\n[CAUSED BY]\n");
+        for (i, cause) in causes.iter().enumerate() {
+            let (line, column) = cause.position();
+            output.push_str(&format!(
+                "#{}: {} [{}] {}:{} {}\n",
+                i, cause.error_type_name(), cause.code(), line, column, cause.message()
+            ));
+        }
+        return output;
+    }
+
+    // Errors from native functions don't know their line
+    let at = |cause: &FlowError| match cause.position().0 {
+        0 => String::new(),
+        line => format!(" @ {} {}", labels.label("line"), line),
+    };
+
+    if options.compact {
+        for cause in causes {
+            output.push_str(&format!(
+                "\n ↳ {} {}{}: {}",
+                labels.label("caused_by"), cause.error_type_name(), at(cause), cause.message()
+            ));
+        }
+        return output;
+    }
+
+    output.push_str(&format!("\n🔗 {}\n", labels.label("caused_by").to_uppercase()));
+    for (depth, cause) in causes.iter().enumerate() {
+        let indent = "   ".repeat(depth);
+        let episode = get_episode_for_error(cause.episode_kind(), &cause.message());
+        output.push_str(&format!("{}└─ {}\n", indent, episode.banner()));
+        output.push_str(&format!(
+            "{}   {} [{}]: \"{}\"{}\n",
+            indent, cause.error_type_name(), episode.code, cause.message(), at(cause)
+        ));
+    }
+    output
+}
+
 // Helper to detect terminal width
 pub fn get_terminal_width() -> usize {
     // Try to get from environment variable
@@ -125,3 +176,27 @@ pub fn get_terminal_width() -> usize {
     // Default fallback
     80
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cause_tree_nests_each_cause() {
+        let root = FlowError::rift("connection refused", 4, 0);
+        let middle = FlowError::glitch("bad config", 9, 0).caused_by(root);
+        let error = FlowError::spirit("could not start", 12, 0).caused_by(middle);
+        assert_eq!(error.error_type_name(), "Spirit");
+        assert_eq!(error.chain().count(), 3);
+
+        let tree = render_cause_tree(&error, &TraceOptions::default());
+        let lines: Vec<&str> = tree.lines().filter(|l| l.trim_start().starts_with("└─")).collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with("   └─"));
+        assert!(tree.contains("\"connection refused\""));
+
+        let raw = render_cause_tree(&error, &TraceOptions { raw_mode: true, ..TraceOptions::default() });
+        assert!(raw.contains("#0: Glitch") && raw.contains("#1: Rift"));
+        assert!(render_cause_tree(&FlowError::spirit("alone", 1, 0), &TraceOptions::default()).is_empty());
+    }
+}
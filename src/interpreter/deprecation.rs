@@ -0,0 +1,36 @@
+//! Spells declared with `@deprecated("message")`
+//!
+//! Declaring a deprecated Spell records its body here; calling it tells the
+//! instrumentation hooks, so `flowlang run --verbose` can print a warning.
+
+use crate::parser::ast::Statement;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+struct Deprecated {
+    /// Keeps the body alive so its address can't be reused by another Spell
+    body: Arc<Vec<Statement>>,
+    name: String,
+    message: String,
+}
+
+#[derive(Clone, Default)]
+pub struct Deprecations(Arc<Mutex<HashMap<usize, Deprecated>>>);
+
+impl Deprecations {
+    pub fn register(&self, body: &Arc<Vec<Statement>>, name: &str, message: String) {
+        let mut spells = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        spells.retain(|_, spell| Arc::strong_count(&spell.body) > 1);
+        spells.insert(Arc::as_ptr(body) as usize, Deprecated {
+            body: body.clone(),
+            name: name.to_string(),
+            message,
+        });
+    }
+
+    /// The declared name and deprecation message, if the Spell with this body is deprecated
+    pub fn lookup(&self, body: &Arc<Vec<Statement>>) -> Option<(String, String)> {
+        let spells = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        spells.get(&(Arc::as_ptr(body) as usize)).map(|spell| (spell.name.clone(), spell.message.clone()))
+    }
+}
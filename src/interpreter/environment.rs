@@ -2,18 +2,46 @@ use std::collections::HashMap;
 use crate::types::Value;
 use crate::error::FlowError;
 
+#[derive(Debug, Clone)]
+struct Binding {
+    value: Value,
+    is_mutable: bool,
+    is_exported: bool,
+    /// A Spell's captured copy of a global, restored when the Spell is called
+    snapshot: bool,
+}
+
+/// Scoped variable bindings
+///
+/// Web handlers run on a clone of the interpreter, so a global changed inside a
+/// handler would only change that request's copy. `freeze_globals` makes such
+/// changes an error instead: handlers see globals as a read-only snapshot and
+/// keep shared state in std:sync.
 #[derive(Debug, Clone)]
 pub struct Environment {
-    scopes: Vec<HashMap<String, (Value, bool, bool)>>, // (value, is_mutable, is_exported)
+    scopes: Vec<HashMap<String, Binding>>,
+    globals_frozen: bool,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Environment {
     pub fn new() -> Self {
         Environment {
             scopes: vec![HashMap::new()],
+            globals_frozen: false,
         }
     }
     
+    /// Reject assignments to globals (used for web handler interpreters)
+    pub fn freeze_globals(&mut self) {
+        self.globals_frozen = true;
+    }
+    
     pub fn push_scope(&mut self) {
         self.scopes.push(HashMap::new());
     }
@@ -31,30 +59,62 @@ impl Environment {
     
     pub fn define_with_export(&mut self, name: String, value: Value, is_mutable: bool, is_exported: bool) {
         if let Some(scope) = self.scopes.last_mut() {
-            scope.insert(name, (value, is_mutable, is_exported));
+            scope.insert(name, Binding { value, is_mutable, is_exported, snapshot: false });
+        }
+    }
+    
+    /// Restore a binding captured by a Spell's closure into the current scope
+    pub fn define_captured(&mut self, name: String, value: Value) {
+        let snapshot = self.scopes.first().is_some_and(|globals| globals.contains_key(&name));
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name, Binding { value, is_mutable: true, is_exported: false, snapshot });
         }
     }
     
     pub fn get(&self, name: &str) -> Option<Value> {
         for scope in self.scopes.iter().rev() {
-            if let Some((value, _, _)) = scope.get(name) {
-                return Some(value.clone());
+            if let Some(binding) = scope.get(name) {
+                return Some(binding.value.clone());
             }
         }
         None
     }
     
+    /// Whether assigning `name` is refused because globals are frozen
+    pub fn is_frozen_global(&self, name: &str) -> bool {
+        if !self.globals_frozen {
+            return false;
+        }
+        for (depth, scope) in self.scopes.iter().enumerate().rev() {
+            if let Some(binding) = scope.get(name) {
+                return depth == 0 || binding.snapshot;
+            }
+        }
+        false
+    }
+    
     pub fn set(&mut self, name: &str, value: Value) -> Result<(), FlowError> {
+        let frozen = self.is_frozen_global(name);
         for scope in self.scopes.iter_mut().rev() {
-            if let Some((old_value, is_mutable, _)) = scope.get_mut(name) {
-                if !*is_mutable {
+            if let Some(binding) = scope.get_mut(name) {
+                if !binding.is_mutable {
                     return Err(FlowError::runtime(
                         &format!("Cannot reassign sealed essence '{}'! It is bound eternally.", name),
                         0,
                         0,
                     ));
                 }
-                *old_value = value;
+                if frozen {
+                    return Err(FlowError::runtime(
+                        &format!(
+                            "Cannot change global '{}' inside a web handler! Each request sees a read-only snapshot of globals, so the change would vanish with the request. Keep shared state in std:sync (sync.cell, sync.map) instead.",
+                            name
+                        ),
+                        0,
+                        0,
+                    ));
+                }
+                binding.value = value;
                 return Ok(());
             }
         }
@@ -71,10 +131,10 @@ impl Environment {
         
         // Only export from the global scope (index 0)
         if let Some(global_scope) = self.scopes.first() {
-            for (name, (value, _, is_exported)) in global_scope {
+            for (name, binding) in global_scope {
                 // Only include exported members
-                if *is_exported {
-                    public_vars.insert(name.clone(), value.clone());
+                if binding.is_exported {
+                    public_vars.insert(name.clone(), binding.value.clone());
                 }
             }
         }
@@ -88,8 +148,8 @@ impl Environment {
         
         // Only export from the global scope (index 0)
         if let Some(global_scope) = self.scopes.first() {
-            for (name, (value, _, _)) in global_scope {
-                all_vars.insert(name.clone(), value.clone());
+            for (name, binding) in global_scope {
+                all_vars.insert(name.clone(), binding.value.clone());
             }
         }
         
@@ -107,11 +167,36 @@ impl Environment {
         
         // Iterate from outer to inner scope so inner values override outer
         for scope in self.scopes.iter() {
-            for (name, (value, _, _)) in scope {
-                visible.insert(name.clone(), value.clone());
+            for (name, binding) in scope {
+                visible.insert(name.clone(), binding.value.clone());
             }
         }
         
         visible
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frozen_globals_are_read_only() {
+        let mut env = Environment::new();
+        env.define("hits".to_string(), Value::Number(0.0), true);
+        env.freeze_globals();
+
+        assert!(env.set("hits", Value::Number(1.0)).is_err());
+
+        // A Spell's captured copy of the global is frozen too
+        env.push_scope();
+        env.define_captured("hits".to_string(), Value::Number(0.0));
+        assert!(env.set("hits", Value::Number(1.0)).is_err());
+
+        // Locals, including ones shadowing a global, stay assignable
+        env.define("count".to_string(), Value::Number(0.0), true);
+        assert!(env.set("count", Value::Number(1.0)).is_ok());
+        env.define("hits".to_string(), Value::Number(0.0), true);
+        assert!(env.set("hits", Value::Number(1.0)).is_ok());
+    }
+}
@@ -0,0 +1,60 @@
+// Running web handlers
+//
+// Every request runs on its own snapshot of the main interpreter, inside a bump
+// arena that lives as long as the request (see crate::arena). The snapshot, the
+// handler's scopes and every Value it makes along the way come out of that arena
+// and go back in one piece when the response is sent, instead of one free at a
+// time. Only the snapshot and the handler call run in the arena; the task around
+// them, the permit and the response channel stay on the shared heap. The response
+// leaves the request, so it is copied to the shared heap first; std:sync does the
+// same for what it stores.
+
+use std::sync::Arc;
+
+use colored::Colorize;
+
+use super::Interpreter;
+use crate::arena::{self, Arena};
+use crate::types::Value;
+
+/// Start up to `limit` queued web requests, each on a snapshot interpreter in its
+/// own task, while handler permits last. Returns how many were started.
+pub async fn dispatch_web_requests(main: &Interpreter, limit: usize) -> usize {
+    let runtime = &main.runtime;
+    let semaphore = runtime.web_handler_semaphore();
+    let mut started = 0;
+    // With every permit taken, leave the rest of the queue for a later tick
+    while started < limit && semaphore.available_permits() > 0 {
+        let Some(web_request) = runtime.get_web_callback().await else {
+            break;
+        };
+        // Globals are read-only in the handler interpreter (see Environment)
+        let arena = Arena::new();
+        let mut task_interpreter = arena.enter(|| main.handler_snapshot());
+        let permit = semaphore.clone().acquire_owned().await.unwrap();
+        let metrics = runtime.metrics();
+
+        tokio::spawn(async move {
+            // The permit is held until the response is sent
+            let _permit = permit;
+
+            // A panic fails this request, not the server
+            let outcome = arena.run(crate::types::catch_panic(
+                "<web handler>",
+                task_interpreter.execute_function(web_request.callback, web_request.args),
+            )).await;
+            let result = arena::on_heap(|| match outcome {
+                Ok(value) => value.deep_clone(),
+                Err(e) => {
+                    metrics.handler_error();
+                    eprintln!("{} {}", "⚠️ Web handler error:".yellow(), e);
+                    Value::String(Arc::new(format!("Error: {}", e)))
+                }
+            });
+
+            let _ = web_request.response_tx.send(result);
+        });
+        started += 1;
+    }
+    started
+}
@@ -0,0 +1,200 @@
+// Instrumentation hooks for profilers and APM tools
+//
+// An Interpreter carries a list of Instrumentation hooks shared with its clones
+// (web handlers) and the circles it imports. With no hooks installed the
+// interpreter skips all bookkeeping, so the hot path only pays for an is_empty check.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use colored::*;
+
+use crate::error::FlowError;
+use crate::parser::ast::Statement;
+use crate::types::Value;
+
+/// Callbacks fired by the interpreter. Every method has an empty default, so a hook
+/// only implements what it needs. Hooks run inline on the interpreter's task and
+/// should return quickly.
+pub trait Instrumentation: Send + Sync {
+    /// Before a statement runs
+    fn statement_enter(&self, _statement: &Statement, _line: usize) {}
+
+    /// After a statement finished, successfully or not
+    fn statement_exit(&self, _statement: &Statement, _line: usize, _elapsed: Duration) {}
+
+    /// After `let`, `seal`, an assignment or a Phase loop gave `name` a new value
+    fn variable_write(&self, _name: &str, _value: &Value) {}
+
+    /// Before a Spell body runs. `name` is the name it was called by, or
+    /// "<anonymous>" for inline Spells and callbacks.
+    fn spell_call(&self, _name: &str, _args: &[Value]) {}
+
+    /// After a Spell returned or failed
+    fn spell_return(&self, _name: &str, _result: Result<&Value, &FlowError>, _elapsed: Duration) {}
+
+    /// When an error is raised. Fired once where it starts, not again for every
+    /// statement it propagates through. Break/continue signals are not errors.
+    fn error_raised(&self, _error: &FlowError) {}
+
+    /// Before a Spell declared `@deprecated("message")` runs; `name` is its declared name
+    fn deprecated_call(&self, _name: &str, _message: &str) {}
+}
+
+pub type Instruments = Arc<Vec<Arc<dyn Instrumentation>>>;
+
+/// Per-Spell call counts and timings for `flowlang run --profile`
+#[derive(Default)]
+pub struct SpellProfiler {
+    spells: Mutex<HashMap<String, SpellStats>>,
+    errors: Mutex<usize>,
+}
+
+#[derive(Default, Clone)]
+struct SpellStats {
+    calls: u64,
+    failures: u64,
+    total: Duration,
+    max: Duration,
+}
+
+impl Instrumentation for SpellProfiler {
+    fn spell_return(&self, name: &str, result: Result<&Value, &FlowError>, elapsed: Duration) {
+        let mut spells = self.spells.lock().unwrap();
+        let stats = spells.entry(name.to_string()).or_default();
+        stats.calls += 1;
+        stats.total += elapsed;
+        stats.max = stats.max.max(elapsed);
+        if result.is_err() {
+            stats.failures += 1;
+        }
+    }
+
+    fn error_raised(&self, _error: &FlowError) {
+        *self.errors.lock().unwrap() += 1;
+    }
+}
+
+impl SpellProfiler {
+    /// Print the table, slowest total time first. Times include nested calls.
+    pub fn print_report(&self) {
+        let spells = self.spells.lock().unwrap();
+        let mut rows: Vec<(&String, &SpellStats)> = spells.iter().collect();
+        rows.sort_by(|a, b| b.1.total.cmp(&a.1.total).then_with(|| a.0.cmp(b.0)));
+
+        println!();
+        println!("{}", "📊 Spell profile".bright_cyan().bold());
+        if rows.is_empty() {
+            println!("   {}", "No Spells were cast".dimmed());
+            return;
+        }
+
+        println!("   {:<28} {:>8} {:>12} {:>12} {:>12} {:>8}", "Spell", "calls", "total ms", "mean ms", "max ms", "errors");
+        for (name, stats) in rows {
+            let total = stats.total.as_secs_f64() * 1000.0;
+            println!(
+                "   {:<28} {:>8} {:>12.3} {:>12.3} {:>12.3} {:>8}",
+                name,
+                stats.calls,
+                total,
+                total / stats.calls as f64,
+                stats.max.as_secs_f64() * 1000.0,
+                stats.failures,
+            );
+        }
+
+        let errors = *self.errors.lock().unwrap();
+        if errors > 0 {
+            println!("   {} error(s) raised", errors);
+        }
+    }
+}
+
+/// Warnings for calls to `@deprecated` Spells under `flowlang run --verbose`.
+/// Each Spell warns once per calling line, on stderr.
+#[derive(Default)]
+pub struct DeprecationWarnings {
+    line: AtomicUsize,
+    warned: Mutex<HashSet<(String, usize)>>,
+}
+
+impl Instrumentation for DeprecationWarnings {
+    fn statement_enter(&self, _statement: &Statement, line: usize) {
+        self.line.store(line, Ordering::Relaxed);
+    }
+
+    fn deprecated_call(&self, name: &str, message: &str) {
+        let line = self.line.load(Ordering::Relaxed);
+        if !self.warned.lock().unwrap().insert((name.to_string(), line)) {
+            return;
+        }
+        let mut warning = format!("⚠️  '{}' is deprecated", name);
+        if !message.is_empty() {
+            warning.push_str(&format!(": {}", message));
+        }
+        eprintln!("{} {}", warning.yellow(), format!("(called on line {})", line).dimmed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::Interpreter;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl Instrumentation for Recorder {
+        fn spell_call(&self, name: &str, args: &[Value]) {
+            self.events.lock().unwrap().push(format!("call {} {}", name, args.len()));
+        }
+
+        fn spell_return(&self, name: &str, result: Result<&Value, &FlowError>, _elapsed: Duration) {
+            let outcome = result.map(|v| v.to_string()).unwrap_or_else(|_| "error".to_string());
+            self.events.lock().unwrap().push(format!("return {} {}", name, outcome));
+        }
+
+        fn error_raised(&self, error: &FlowError) {
+            self.events.lock().unwrap().push(format!("raise {}", error.position().0));
+        }
+
+        fn deprecated_call(&self, name: &str, message: &str) {
+            self.events.lock().unwrap().push(format!("deprecated {} {}", name, message));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hooks_fire() {
+        let source = "cast Spell double(n) {\n    return n * 2\n}\ncast Spell fail() {\n    rupture Spirit \"boom\"\n}\nshout(double(21))\nattempt {\n    fail()\n} rescue Spirit as e {\n    shout(\"rescued\")\n}\n";
+        let program = crate::lexer::tokenize(source).and_then(crate::parser::parse).unwrap();
+
+        let recorder = Arc::new(Recorder::default());
+        let mut interpreter = Interpreter::new(Default::default());
+        interpreter.add_instrumentation(recorder.clone());
+        interpreter.execute(program).await.unwrap();
+
+        let events = recorder.events.lock().unwrap();
+        assert_eq!(
+            *events,
+            vec!["call double 1", "return double 42", "call fail 0", "raise 5", "return fail error"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deprecated_calls_reach_hooks() {
+        let source = "@deprecated(\"use add\")\ncast Spell plus(a, b) {\n    return a + b\n}\nlet alias = plus\nshout(alias(1, 2))\n";
+        let program = crate::lexer::tokenize(source).and_then(crate::parser::parse).unwrap();
+
+        let recorder = Arc::new(Recorder::default());
+        let mut interpreter = Interpreter::new(Default::default());
+        interpreter.add_instrumentation(recorder.clone());
+        interpreter.execute(program).await.unwrap();
+
+        let events = recorder.events.lock().unwrap();
+        assert_eq!(*events, vec!["call alias 2", "deprecated plus use add", "return alias 3"]);
+    }
+}
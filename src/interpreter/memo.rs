@@ -0,0 +1,99 @@
+//! Result caches for Spells declared with `@memo`
+//!
+//! Each memoized Spell gets a table from its arguments to its result. Tables are
+//! keyed by the Spell's body, which every copy of the Spell value shares, and are
+//! shared by interpreter clones so web requests reuse each other's results.
+
+use crate::parser::ast::Statement;
+use crate::stdlib::covenant::member_key;
+use crate::types::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// How many results one `@memo` Spell keeps; the oldest are dropped first
+pub const MEMO_CAPACITY: usize = 1024;
+
+struct MemoTable {
+    /// Keeps the body alive so its address can't be reused by another Spell
+    body: Arc<Vec<Statement>>,
+    results: HashMap<String, Value>,
+    order: VecDeque<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct MemoTables(Arc<Mutex<HashMap<usize, MemoTable>>>);
+
+fn body_id(body: &Arc<Vec<Statement>>) -> usize {
+    Arc::as_ptr(body) as usize
+}
+
+impl MemoTables {
+    fn lock(&self) -> MutexGuard<'_, HashMap<usize, MemoTable>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Start caching results for the Spell with this body
+    pub fn register(&self, body: &Arc<Vec<Statement>>) {
+        let mut tables = self.lock();
+        // Forget Spells that no longer exist anywhere but here (e.g. declared inside a loop)
+        tables.retain(|_, table| Arc::strong_count(&table.body) > 1);
+        tables.insert(body_id(body), MemoTable {
+            body: body.clone(),
+            results: HashMap::new(),
+            order: VecDeque::new(),
+        });
+    }
+
+    /// The cache key for a call, or None when the Spell isn't memoized or an
+    /// argument (such as a Spell) can't be compared by value
+    pub fn key_for(&self, body: &Arc<Vec<Statement>>, args: &[Value]) -> Option<String> {
+        if !self.lock().contains_key(&body_id(body)) {
+            return None;
+        }
+        let keys = args.iter().map(member_key).collect::<Result<Vec<_>, _>>().ok()?;
+        Some(keys.join(","))
+    }
+
+    pub fn get(&self, body: &Arc<Vec<Statement>>, key: &str) -> Option<Value> {
+        self.lock().get(&body_id(body))?.results.get(key).cloned()
+    }
+
+    pub fn store(&self, body: &Arc<Vec<Statement>>, key: String, value: Value) {
+        let mut tables = self.lock();
+        let Some(table) = tables.get_mut(&body_id(body)) else {
+            return;
+        };
+        if table.results.insert(key.clone(), value).is_none() {
+            table.order.push_back(key);
+            if table.order.len() > MEMO_CAPACITY {
+                if let Some(oldest) = table.order.pop_front() {
+                    table.results.remove(&oldest);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_the_oldest_results_past_capacity() {
+        let memo = MemoTables::default();
+        let body = Arc::new(Vec::new());
+        memo.register(&body);
+
+        for n in 0..=MEMO_CAPACITY {
+            let key = memo.key_for(&body, &[Value::Number(n as f64)]).unwrap();
+            memo.store(&body, key, Value::Number(n as f64 * 2.0));
+        }
+        let first = memo.key_for(&body, &[Value::Number(0.0)]).unwrap();
+        let last = memo.key_for(&body, &[Value::Number(MEMO_CAPACITY as f64)]).unwrap();
+        assert!(memo.get(&body, &first).is_none());
+        assert!(matches!(memo.get(&body, &last), Some(Value::Number(n)) if n == MEMO_CAPACITY as f64 * 2.0));
+
+        // Spells that aren't memoized never get a key
+        assert!(memo.key_for(&Arc::new(Vec::new()), &[Value::Null]).is_none());
+    }
+}
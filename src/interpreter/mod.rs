@@ -1,16 +1,31 @@
+pub mod deprecation;
 pub mod environment;
+pub mod retry;
+pub mod instrument;
+pub mod memo;
+pub mod output;
+pub mod handlers;
+pub mod sandbox;
+pub mod time_travel;
 
 use environment::Environment;
-use crate::error::FlowError;
+use deprecation::Deprecations;
+use instrument::{Instrumentation, Instruments};
+use memo::MemoTables;
+use sandbox::Sandbox;
+use output::{CaptureSink, OutputSink, StdSink, Stream};
+use crate::error::{CustomError, FlowError};
 use crate::parser::ast::*;
-use crate::types::{Value, AsyncContext};
+use crate::types::{Value, AsyncContext, RelicMap};
 use crate::stdlib;
-use crate::runtime::Runtime;
+use crate::stdlib::assert::Snapshots;
+use crate::runtime::{Runtime, RuntimeConfig};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::fs;
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::config::ProjectConfig;
 
@@ -19,7 +34,7 @@ pub struct Interpreter {
     env: Environment,
     module_cache: Arc<tokio::sync::Mutex<HashMap<String, Environment>>>,
     current_dir: PathBuf,
-    project_root: PathBuf,  // Project root (where config.flowlang.json lives)
+    project_root: PathBuf,  // Project root (where config.flowlang.json lives, or the workspace root)
     current_file: String,  // Track current file for error reporting
     loading_stack: Vec<String>,  // Track module loading chain for circular dependency detection
     config: ProjectConfig,
@@ -27,6 +42,24 @@ pub struct Interpreter {
     runtime: Arc<Runtime>,
     /// Sigil definitions (name -> fields)
     sigil_definitions: Arc<tokio::sync::Mutex<HashMap<String, Vec<SigilField>>>>,
+    /// Sigils declared with `sigil error`, which rupture accepts as error types
+    error_sigils: Arc<std::sync::Mutex<HashSet<String>>>,
+    /// Profiler/APM hooks, shared with clones and imported circles
+    instruments: Instruments,
+    /// Set once the current error was passed to error_raised, so outer statements don't repeat it
+    error_reported: bool,
+    /// Where shout/roar/whisper/chant write, shared with clones and imported circles
+    output: Arc<dyn OutputSink>,
+    /// Results of `@memo` Spells, shared with clones
+    memo: MemoTables,
+    /// Spells declared `@deprecated`, shared with clones and imported circles
+    deprecations: Deprecations,
+    /// The error behind each `rescue ... as e` binding, so `caused_by e` can chain it
+    rescued: HashMap<String, FlowError>,
+    /// Limits for code run by `vm.run`, shared with clones
+    sandbox: Option<Arc<Sandbox>>,
+    /// Where `assert.matchesSnapshot` reads and writes, shared with clones and imported circles
+    snapshots: Arc<Snapshots>,
 }
 
 impl Interpreter {
@@ -35,18 +68,27 @@ impl Interpreter {
         Interpreter {
             env: Environment::new(),
             module_cache: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
-            current_dir: cwd.clone(),
-            project_root: cwd,
+            current_dir: cwd,
+            project_root: crate::config::project_root().to_path_buf(),
             current_file: "main.flow".to_string(),
             loading_stack: Vec::new(),
+            runtime: Arc::new(Runtime::with_config(RuntimeConfig::from_web_config(&config.web))),
             config,
-            runtime: Arc::new(Runtime::new()),
             sigil_definitions: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            error_sigils: Arc::default(),
+            instruments: Arc::new(Vec::new()),
+            error_reported: false,
+            output: Arc::new(StdSink),
+            memo: MemoTables::default(),
+            deprecations: Deprecations::default(),
+            rescued: HashMap::new(),
+            sandbox: None,
+            snapshots: Arc::default(),
         }
     }
     
     pub fn with_dir(dir: PathBuf, config: ProjectConfig) -> Self {
-        let project_root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let project_root = crate::config::project_root().to_path_buf();
         Interpreter {
             env: Environment::new(),
             module_cache: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
@@ -54,9 +96,18 @@ impl Interpreter {
             project_root,
             current_file: "module.flow".to_string(),
             loading_stack: Vec::new(),
+            runtime: Arc::new(Runtime::with_config(RuntimeConfig::from_web_config(&config.web))),
             config,
-            runtime: Arc::new(Runtime::new()),
             sigil_definitions: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            error_sigils: Arc::default(),
+            instruments: Arc::new(Vec::new()),
+            error_reported: false,
+            output: Arc::new(StdSink),
+            memo: MemoTables::default(),
+            deprecations: Deprecations::default(),
+            rescued: HashMap::new(),
+            sandbox: None,
+            snapshots: Arc::default(),
         }
     }
     
@@ -65,18 +116,250 @@ impl Interpreter {
         self.runtime.clone()
     }
     
+    /// A clone for running one web request: globals are a read-only snapshot,
+    /// since changes to this clone would never reach other requests
+    pub fn handler_snapshot(&self) -> Self {
+        let mut interpreter = self.clone();
+        interpreter.env.freeze_globals();
+        interpreter
+    }
+    
+    /// A fresh interpreter for `vm.run`: empty globals and its own runtime, writing
+    /// to the same output. Inside a sandbox, the new one keeps the outer limits too.
+    pub fn sandboxed(&self, sandbox: Sandbox) -> Self {
+        let sandbox = match &self.sandbox {
+            Some(outer) => sandbox.within(outer),
+            None => sandbox,
+        };
+        let mut interpreter = Interpreter::new(ProjectConfig::default());
+        interpreter.current_file = "<vm>".to_string();
+        interpreter.output = self.output.clone();
+        interpreter.sandbox = Some(Arc::new(sandbox));
+        interpreter
+    }
+    
+    /// The limits this interpreter runs under, if it was made by `sandboxed`
+    pub fn sandbox(&self) -> Option<&Sandbox> {
+        self.sandbox.as_deref()
+    }
+    
+    /// Where `assert.matchesSnapshot` keeps snapshots and whether it may write them
+    pub fn snapshots(&self) -> &Snapshots {
+        &self.snapshots
+    }
+    
+    pub fn set_snapshots(&mut self, snapshots: Snapshots) {
+        self.snapshots = Arc::new(snapshots);
+    }
+    
+    /// The `@export`-ed globals, as a circle import would see them
+    pub fn exports(&self) -> RelicMap {
+        // Scopes are unordered, so members are listed alphabetically
+        let mut exports: RelicMap = self.env.get_all_public().into_iter().collect();
+        exports.sort_keys();
+        exports
+    }
+    
+    /// The fields of a declared Sigil, for `reflect.sigilFields`
+    pub async fn sigil_fields(&self, name: &str) -> Option<Vec<SigilField>> {
+        self.sigil_definitions.lock().await.get(name).cloned()
+    }
+    
+    // Stops sandboxed code at its deadline; see `sandbox`
+    fn check_sandbox(&self) -> Result<(), FlowError> {
+        match &self.sandbox {
+            Some(sandbox) => sandbox.check_time(),
+            None => Ok(()),
+        }
+    }
+    
+    /// Send the output built-ins somewhere other than stdout/stderr
+    pub fn set_output(&mut self, sink: Arc<dyn OutputSink>) {
+        self.output = sink;
+    }
+    
+    /// Run a program with its output captured instead of printed, returning the
+    /// lines it wrote in order. Output from later callbacks goes to the previous sink.
+    pub async fn execute_captured(&mut self, program: Program) -> (Result<(), FlowError>, Vec<(Stream, String)>) {
+        let capture = Arc::new(CaptureSink::new());
+        let previous = std::mem::replace(&mut self.output, capture.clone());
+        let result = self.execute(program).await;
+        self.output = previous;
+        (result, capture.take())
+    }
+    
+    /// Hand an error nothing rescued to the `os.onUncaught` Spell, if one was registered.
+    /// The Spell gets `{ type, message, code, line, exitCode }`, plus `payload` for a
+    /// `sigil error` type. If the Spell itself fails, that error is returned.
+    pub async fn report_uncaught(&mut self, err: &FlowError) -> Result<(), FlowError> {
+        let Some(handler) = self.runtime.uncaught_handler() else {
+            return Ok(());
+        };
+        let mut fields = RelicMap::new();
+        fields.insert("type".to_string(), Value::String(Arc::new(err.error_type_name().to_string())));
+        fields.insert("message".to_string(), Value::String(Arc::new(err.message())));
+        fields.insert("code".to_string(), Value::String(Arc::new(err.code().to_string())));
+        fields.insert("line".to_string(), Value::Number(err.position().0 as f64));
+        fields.insert("exitCode".to_string(), Value::Number(err.exit_code() as f64));
+        if let Value::Relic(payload) = rescued_value(err) {
+            fields.insert("payload".to_string(), Value::Relic(payload));
+        }
+        self.execute_function(handler, vec![Value::Relic(Arc::new(fields))]).await.map(|_| ())
+    }
+    
+    /// Bind `name` in the current scope, e.g. the REPL's `_`
+    pub fn define(&mut self, name: &str, value: Value) {
+        self.env.define(name.to_string(), value, true);
+    }
+    
+    /// The value `name` refers to where the interpreter is now
+    pub fn lookup(&self, name: &str) -> Option<Value> {
+        self.env.get(name)
+    }
+    
+    /// Reassign an existing variable, with the same checks as `name = value`
+    pub fn assign(&mut self, name: &str, value: Value) -> Result<(), FlowError> {
+        self.env.set(name, value.clone())?;
+        self.variable_written(name, &value);
+        Ok(())
+    }
+    
+    /// Install a hook that observes statements, Spell calls and errors
+    pub fn add_instrumentation(&mut self, hook: Arc<dyn Instrumentation>) {
+        Arc::make_mut(&mut self.instruments).push(hook);
+    }
+    
+    fn spell_started(&self, name: &str, args: &[Value]) -> Option<Instant> {
+        if self.instruments.is_empty() {
+            return None;
+        }
+        for hook in self.instruments.iter() {
+            hook.spell_call(name, args);
+        }
+        Some(Instant::now())
+    }
+    
+    fn variable_written(&self, name: &str, value: &Value) {
+        for hook in self.instruments.iter() {
+            hook.variable_write(name, value);
+        }
+    }
+    
+    /// Sleep for `ms` milliseconds, running event loop callbacks that come due meanwhile
+    async fn wait_ms(&mut self, ms: u64) {
+        let start = std::time::Instant::now();
+        let wait_duration = std::time::Duration::from_millis(ms);
+        
+        while start.elapsed() < wait_duration {
+            self.run_pending_callbacks().await;
+            
+            // Sleep for a short tick interval
+            let remaining = wait_duration.saturating_sub(start.elapsed());
+            let tick = std::cmp::min(remaining, std::time::Duration::from_millis(10));
+            if tick > std::time::Duration::ZERO {
+                tokio::time::sleep(tick).await;
+            }
+        }
+    }
+    
+    /// Run the event loop callbacks queued so far; returns whether there were any
+    async fn run_pending_callbacks(&mut self) -> bool {
+        let mut ran = false;
+        while let Some(request) = self.runtime.run_event_loop_tick().await {
+            ran = true;
+            if let Err(e) = self.execute_function(request.callback, request.args).await {
+                eprintln!("Callback error: {}", e);
+            }
+        }
+        ran
+    }
+    
+    /// `await handle`: keep the event loop going (callbacks and web requests) until the
+    /// handle is gone, then run what its ending queued, `onClose` Spells included
+    async fn await_handle(&mut self, id: u64) {
+        while self.runtime.has_handle(id).await && !self.runtime.is_shutdown_signaled() {
+            let ran = self.run_pending_callbacks().await;
+            let served = handlers::dispatch_web_requests(self, 10).await;
+            if !ran && served == 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+            } else {
+                tokio::task::yield_now().await;
+            }
+        }
+        self.run_pending_callbacks().await;
+    }
+    
+    /// Run an attempt body; stops at the first error or `return`
+    async fn execute_block_once(&mut self, body: &[Statement]) -> Result<Option<Value>, FlowError> {
+        for stmt in body {
+            if let Some(val) = self.execute_statement(stmt).await? {
+                return Ok(Some(val));
+            }
+        }
+        Ok(None)
+    }
+    
+    /// The error named by `caused_by`. A rescued binding that still holds what rescue
+    /// gave it chains the original error; any other value becomes a Spirit with its text.
+    async fn cause_error(&mut self, cause: &Expression, line: usize) -> Result<FlowError, FlowError> {
+        let value = self.evaluate_expression(cause).await?;
+        if let Expression::Identifier(name) = cause {
+            if let Some(err) = self.rescued.get(name) {
+                let unchanged = match (&value, rescued_value(err)) {
+                    (Value::String(a), Value::String(b)) => a == &b,
+                    (Value::Relic(a), Value::Relic(b)) => Arc::ptr_eq(a, &b),
+                    _ => false,
+                };
+                if unchanged {
+                    return Ok(err.clone());
+                }
+            }
+        }
+        Ok(FlowError::spirit(&value.to_string(), line, 0))
+    }
+    
+    // Only hooks hear about deprecated calls, so without any this is a single check
+    fn deprecated_called(&self, body: &Arc<Vec<Statement>>) {
+        if self.instruments.is_empty() {
+            return;
+        }
+        if let Some((name, message)) = self.deprecations.lookup(body) {
+            for hook in self.instruments.iter() {
+                hook.deprecated_call(&name, &message);
+            }
+        }
+    }
+    
+    fn spell_finished(&self, name: &str, started: Option<Instant>, result: &Result<Value, FlowError>) {
+        if let Some(started) = started {
+            let elapsed = started.elapsed();
+            for hook in self.instruments.iter() {
+                hook.spell_return(name, result.as_ref(), elapsed);
+            }
+        }
+    }
+    
     /// Execute a FlowLang function with given arguments
     /// Useful for calling FlowLang handlers from native code (e.g., web server)
     pub async fn execute_function(&mut self, func: Value, args: Vec<Value>) -> Result<Value, FlowError> {
         match func {
             Value::Function { params, body, closure, .. } => {
+                let started = self.spell_started("<anonymous>", &args);
+                self.deprecated_called(&body);
+                let memo_key = self.memo.key_for(&body, &args);
+                if let Some(hit) = memo_key.as_ref().and_then(|key| self.memo.get(&body, key)) {
+                    let result = Ok(hit);
+                    self.spell_finished("<anonymous>", started, &result);
+                    return result;
+                }
+                
                 // Push new scope for function
                 self.env.push_scope();
                 
                 // Restore closure bindings if present (for module-level imports)
                 if let Some(ref captured) = closure {
                     for (name, value) in captured.iter() {
-                        self.env.define(name.clone(), value.clone(), true);
+                        self.env.define_captured(name.clone(), value.clone());
                     }
                 }
                 
@@ -86,27 +369,89 @@ impl Interpreter {
                 }
                 
                 // Execute function body
-                let mut result = Value::Null;
+                let mut result = Ok(Value::Null);
                 for stmt in body.iter() {
-                    if let Some(ret_val) = self.execute_statement(stmt).await? {
-                        result = ret_val;
-                        break; // Early return
+                    match self.execute_statement(stmt).await {
+                        Ok(Some(ret_val)) => {
+                            result = Ok(ret_val);
+                            break; // Early return
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            result = Err(e);
+                            break;
+                        }
                     }
                 }
                 
-                // Pop scope
+                // Pop scope (also on error, so repeated native callers don't leak scopes)
                 self.env.pop_scope();
                 
-                Ok(result)
+                if let (Some(key), Ok(value)) = (memo_key, &result) {
+                    self.memo.store(&body, key, value.clone());
+                }
+                
+                self.spell_finished("<anonymous>", started, &result);
+                result
             }
             Value::NativeFunction(f) => {
                 // Native functions are synchronous
-                f.0(args)
+                f.call("<callback>", args)
+            }
+            Value::AsyncNativeFunction(f) => {
+                let ctx = AsyncContext {
+                    runtime: self.runtime.clone(),
+                };
+                f.call("<callback>", args, ctx).await
+            }
+            Value::InterpreterNativeFunction(f) => {
+                f.call("<callback>", args, self).await
             }
             _ => Err(FlowError::type_error("Not a function", 0, 0))
         }
     }
     
+    /// One `>> step` of a combo chain. The value so far becomes the first argument of a
+    /// built-in, a Spell in scope or a module function; a bare name that is none of
+    /// those is a method of the value instead (`>> upper`, `>> join(", ")`).
+    async fn combo_step(&mut self, value: Value, op: &ChainOperation) -> Result<Value, FlowError> {
+        let piped = Expression::Identifier(COMBO_VALUE.to_string());
+        let step = match op {
+            ChainOperation::Call(name, arguments) => self.combo_call(piped, name, arguments),
+            ChainOperation::Method(name) => self.combo_call(piped, name, &[]),
+            ChainOperation::Member(object, method, arguments) => Expression::MethodCall {
+                object: Box::new(object.clone()),
+                method: method.clone(),
+                arguments: std::iter::once(piped).chain(arguments.iter().cloned()).collect(),
+                cache: crate::optimizer::InlineCache::new(),
+            },
+        };
+        
+        // The value is bound in a scope of its own so the step runs as ordinary call code
+        self.env.push_scope();
+        self.env.define(COMBO_VALUE.to_string(), value, false);
+        let result = self.evaluate_expression(&step).await;
+        self.env.pop_scope();
+        result
+    }
+    
+    fn combo_call(&self, piped: Expression, name: &str, arguments: &[Expression]) -> Expression {
+        let is_spell = stdlib::is_builtin(name) || self.env.get(name).is_some_and(|v| v.type_name() == "Spell");
+        if is_spell {
+            Expression::Call {
+                callee: Box::new(Expression::Identifier(name.to_string())),
+                arguments: std::iter::once(piped).chain(arguments.iter().cloned()).collect(),
+            }
+        } else {
+            Expression::MethodCall {
+                object: Box::new(piped),
+                method: name.to_string(),
+                arguments: arguments.to_vec(),
+                cache: crate::optimizer::InlineCache::new(),
+            }
+        }
+    }
+    
     fn check_type_compatibility(&self, value: &Value, expected: &crate::types::EssenceType) -> bool {
         use crate::types::{EssenceType, Value};
         match (value, expected) {
@@ -135,12 +480,18 @@ impl Interpreter {
                 }
                 true
             }
-            (Value::Function { .. } | Value::NativeFunction(_), EssenceType::Spell) => true,
+            (
+                Value::Function { .. }
+                | Value::NativeFunction(_)
+                | Value::AsyncNativeFunction(_)
+                | Value::InterpreterNativeFunction(_),
+                EssenceType::Spell,
+            ) => true,
             _ => false,
         }
     }
 
-    pub fn execute<'a>(&'a mut self, program: Program) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), FlowError>> + 'a>> {
+    pub fn execute<'a>(&'a mut self, program: Program) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), FlowError>> + Send + 'a>> {
         Box::pin(async move {
             // Process imports first
             for import in program.imports {
@@ -156,7 +507,70 @@ impl Interpreter {
         })
     }
     
+    // `evaluate(source)` and `circleDynamic(path)`, which need the interpreter
+    async fn call_eval_builtin(&mut self, name: &str, args: Vec<Value>) -> Result<Value, FlowError> {
+        if !self.config.allow_eval {
+            return Err(FlowError::runtime(
+                &format!("{}() is disabled; run with --allow-eval or set \"allow_eval\": true in config.flowlang.json", name),
+                0,
+                0,
+            ));
+        }
+        let arg = match args.as_slice() {
+            [Value::String(arg)] => arg.to_string(),
+            _ => {
+                let expected = if name == "evaluate" { "source" } else { "path" };
+                return Err(FlowError::type_error(&format!("{}() expects 1 Silk {}", name, expected), 0, 0));
+            }
+        };
+        if name == "evaluate" {
+            self.evaluate_source(&arg).await
+        } else {
+            self.import_dynamic(&arg).await
+        }
+    }
+
+    /// Run `source` as a circle of its own and return its `@export`-ed values. It
+    /// resolves relative imports from this file's directory and shares its module cache.
+    pub async fn evaluate_source(&mut self, source: &str) -> Result<Value, FlowError> {
+        let ast = crate::lexer::tokenize(source).and_then(crate::parser::parse)?;
+        let mut module_interpreter = Interpreter::with_dir(self.current_dir.clone(), self.config.clone());
+        module_interpreter.current_file = "<eval>".to_string();
+        module_interpreter.module_cache = self.module_cache.clone();
+        module_interpreter.sigil_definitions = self.sigil_definitions.clone();
+        module_interpreter.error_sigils = self.error_sigils.clone();
+        module_interpreter.instruments = self.instruments.clone();
+        module_interpreter.output = self.output.clone();
+        module_interpreter.snapshots = self.snapshots.clone();
+        module_interpreter.memo = self.memo.clone();
+        module_interpreter.deprecations = self.deprecations.clone();
+        module_interpreter.project_root = self.project_root.clone();
+        module_interpreter.loading_stack = self.loading_stack.clone();
+        module_interpreter.execute(ast).await?;
+        Ok(Value::Relic(Arc::new(module_interpreter.exports())))
+    }
+
+    /// `circle _ from path` with a path known only at runtime, returning the module
+    /// Relic instead of binding it. Paths are resolved exactly as static imports are.
+    pub async fn import_dynamic(&mut self, path: &str) -> Result<Value, FlowError> {
+        const SLOT: &str = "<circleDynamic>";
+        let import = Import {
+            module: path.to_string(),
+            alias: Some(SLOT.to_string()),
+            from_path: Some(path.to_string()),
+            selective: None,
+        };
+        // A scope of its own keeps the binding out of the caller's names
+        self.env.push_scope();
+        let result = self.execute_import(&import).await.map(|_| self.env.get(SLOT).unwrap_or(Value::Null));
+        self.env.pop_scope();
+        result
+    }
+
     pub async fn execute_import(&mut self, import: &Import) -> Result<(), FlowError> {
+        if let Some(sandbox) = &self.sandbox {
+            sandbox.check_import(import.from_path.as_deref().unwrap_or(&import.module))?;
+        }
         // Check for std: import
         if let Some(path) = &import.from_path {
             if path.starts_with("std:") {
@@ -178,31 +592,38 @@ impl Interpreter {
             if path.starts_with("pkg:") {
                 let pkg_alias = &path[4..];
                 
-                // Resolve package path from config
-                let pkg_path = self.config.packages.get(pkg_alias)
-                    .ok_or_else(|| FlowError::runtime(
-                        &format!("Package '{}' not found in config.flowlang.json. Run 'flowlang add <package>' first.", pkg_alias),
-                        0, 0
-                    ))?;
-                
-                // Parse spec to get local path
-                let spec = crate::package_manager::PackageSpec::parse(pkg_path)?;
-                let pkg_dir = self.project_root.join(".flowlang").join("pkg").join(spec.local_path());
-                
-                if !pkg_dir.exists() {
+                // Resolve package path from config, then from the workspace's members
+                let (pkg_dir, pkg_config) = if let Some(pkg_path) = self.config.packages.get(pkg_alias) {
+                    // Parse spec to get local path
+                    let spec = crate::package_manager::PackageSpec::parse(pkg_path)?;
+                    let pkg_dir = self.project_root.join(".flowlang").join("pkg").join(spec.local_path());
+                    
+                    if !pkg_dir.exists() {
+                        return Err(FlowError::runtime(
+                            &format!("Package '{}' not installed. Run 'flowlang install' first.", pkg_alias),
+                            0, 0
+                        ));
+                    }
+                    crate::package_manager::PackageManager::new(self.project_root.clone()).verify(&spec, &pkg_dir)?;
+                    
+                    // Load package config to get entry point
+                    let pkg_config_path = pkg_dir.join("config.flowlang.json");
+                    let pkg_config = if pkg_config_path.exists() {
+                        crate::config::ProjectConfig::load(&pkg_config_path)?
+                    } else {
+                        crate::config::ProjectConfig::default()
+                    };
+                    (pkg_dir, pkg_config)
+                } else if let Some(member_dir) = self.config.workspace_members.get(pkg_alias) {
+                    // A sibling keeps the workspace's packages for its own pkg: imports
+                    (member_dir.clone(), crate::config::ProjectConfig::for_dir(member_dir)?)
+                } else {
                     return Err(FlowError::runtime(
-                        &format!("Package '{}' not installed. Run 'flowlang install' first.", pkg_alias),
+                        &format!("Package '{}' not found in config.flowlang.json. Run 'flowlang add <package>' first.", pkg_alias),
                         0, 0
                     ));
-                }
-                
-                // Load package config to get entry point
-                let pkg_config_path = pkg_dir.join("config.flowlang.json");
-                let pkg_config = if pkg_config_path.exists() {
-                    crate::config::ProjectConfig::load(&pkg_config_path)?
-                } else {
-                    crate::config::ProjectConfig::default()
                 };
+                pkg_config.check_package(pkg_alias)?;
                 
                 // Build path to entry file
                 let entry_path = pkg_dir.join(&pkg_config.entry);
@@ -241,6 +662,12 @@ impl Interpreter {
                     // Share the cache state!
                     module_interpreter.module_cache = self.module_cache.clone();
                     module_interpreter.sigil_definitions = self.sigil_definitions.clone();
+                    module_interpreter.error_sigils = self.error_sigils.clone();
+                    module_interpreter.instruments = self.instruments.clone();
+                    module_interpreter.output = self.output.clone();
+                    module_interpreter.snapshots = self.snapshots.clone();
+                    module_interpreter.memo = self.memo.clone();
+                    module_interpreter.deprecations = self.deprecations.clone();
 
                     // Inherit parent's project_root for pkg: resolution in nested dependencies
                     module_interpreter.project_root = self.project_root.clone();
@@ -255,10 +682,9 @@ impl Interpreter {
                 let alias = import.alias.clone().unwrap_or(import.module.clone());
                 
                 let public_vars = module_env.get_all_public();
-                let mut module_map = HashMap::new();
-                for (name, value) in public_vars {
-                    module_map.insert(name, value);
-                }
+                // Scopes are unordered, so members are listed alphabetically
+                let mut module_map: RelicMap = public_vars.into_iter().collect();
+                module_map.sort_keys();
                 
                 let relic = Value::Relic(Arc::new(module_map));
                 self.env.define(alias, relic, false);
@@ -272,9 +698,13 @@ impl Interpreter {
                 
                 // Auto-install if not present
                 if !pkg_dir.exists() {
-                    println!("📦 Auto-installing {}...", path);
+                    if !crate::platform::is_quiet() {
+                        println!("📦 Auto-installing {}...", path);
+                    }
                     let pm = crate::package_manager::PackageManager::new(self.project_root.clone());
                     pm.fetch_package(&spec)?;
+                } else {
+                    crate::package_manager::PackageManager::new(self.project_root.clone()).verify(&spec, &pkg_dir)?;
                 }
                 
                 // Load package config to get entry point
@@ -284,6 +714,7 @@ impl Interpreter {
                 } else {
                     crate::config::ProjectConfig::default()
                 };
+                pkg_config.check_package(path)?;
                 
                 // Build path to entry file
                 let entry_path = pkg_dir.join(&pkg_config.entry);
@@ -324,6 +755,12 @@ impl Interpreter {
                     // Share the cache state!
                     module_interpreter.module_cache = self.module_cache.clone();
                     module_interpreter.sigil_definitions = self.sigil_definitions.clone();
+                    module_interpreter.error_sigils = self.error_sigils.clone();
+                    module_interpreter.instruments = self.instruments.clone();
+                    module_interpreter.output = self.output.clone();
+                    module_interpreter.snapshots = self.snapshots.clone();
+                    module_interpreter.memo = self.memo.clone();
+                    module_interpreter.deprecations = self.deprecations.clone();
 
                     // Inherit parent's project_root for pkg: resolution in nested dependencies
                     module_interpreter.project_root = self.project_root.clone();
@@ -339,10 +776,9 @@ impl Interpreter {
                 let alias = import.alias.clone().unwrap_or(import.module.clone());
                 
                 let public_vars = module_env.get_all_public();
-                let mut module_map = HashMap::new();
-                for (name, value) in public_vars {
-                    module_map.insert(name, value);
-                }
+                // Scopes are unordered, so members are listed alphabetically
+                let mut module_map: RelicMap = public_vars.into_iter().collect();
+                module_map.sort_keys();
                 
                 let relic = Value::Relic(Arc::new(module_map));
                 self.env.define(alias, relic, false);
@@ -351,20 +787,9 @@ impl Interpreter {
         }
 
         // 1. Resolve path
-        let mut module_path = self.current_dir.clone();
+        let module_path = crate::cache::import_path(&self.current_dir, import);
         
-        if let Some(path) = &import.from_path {
-            module_path.push(path);
-        } else {
-            module_path.push(&import.module);
-        }
-        
-        // Add .flow extension if missing
-        if module_path.extension().is_none() {
-            module_path.set_extension("flow");
-        }
-        
-        let canonical_path = match fs::canonicalize(&module_path) {
+        let canonical_path = match crate::platform::canonicalize(&module_path) {
             Ok(p) => p,
             Err(_) => {
                 // Try relative to current dir if canonicalize fails (e.g. file doesn't exist yet)
@@ -421,9 +846,17 @@ impl Interpreter {
             // Strip BOM
             let source = source.replace("\u{feff}", "");
             
-            // Parse
-            let tokens = crate::lexer::tokenize(&source)?;
-            let ast = crate::parser::parse(tokens)?;
+            // Parse, reusing the AST cache while neither this circle nor its imports changed
+            let cache_manager = crate::cache::CacheManager::new();
+            let ast = match cache_manager.load(&canonical_path, &source) {
+                Some(ast) => ast,
+                None => {
+                    let tokens = crate::lexer::tokenize(&source)?;
+                    let ast = crate::parser::parse(tokens)?;
+                    let _ = cache_manager.save(&canonical_path, &source, &ast);
+                    ast
+                }
+            };
             
             // Execute in new interpreter
             let module_dir = canonical_path.parent().unwrap().to_path_buf();
@@ -432,6 +865,12 @@ impl Interpreter {
             // Share the cache state!
             module_interpreter.module_cache = self.module_cache.clone();
             module_interpreter.sigil_definitions = self.sigil_definitions.clone();
+            module_interpreter.error_sigils = self.error_sigils.clone();
+            module_interpreter.instruments = self.instruments.clone();
+            module_interpreter.output = self.output.clone();
+            module_interpreter.snapshots = self.snapshots.clone();
+            module_interpreter.memo = self.memo.clone();
+            module_interpreter.deprecations = self.deprecations.clone();
 
             // Set the current file for error reporting
             module_interpreter.current_file = canonical_path.file_name()
@@ -482,10 +921,9 @@ impl Interpreter {
             let alias = import.alias.clone().unwrap_or(import.module.clone());
         
             // Import as object/map (Relic)
-            let mut module_map = HashMap::new();
-            for (name, value) in public_vars {
-                module_map.insert(name, value);
-            }
+            // Scopes are unordered, so members are listed alphabetically
+            let mut module_map: RelicMap = public_vars.into_iter().collect();
+            module_map.sort_keys();
             
             let relic = Value::Relic(Arc::new(module_map));
             self.env.define(alias, relic, false); // Modules are sealed (immutable)
@@ -494,8 +932,47 @@ impl Interpreter {
         Ok(())
     }
     
-    #[async_recursion::async_recursion]
     pub async fn execute_statement(&mut self, stmt: &Statement) -> Result<Option<Value>, FlowError> {
+        self.check_sandbox()?;
+        if self.instruments.is_empty() {
+            return self.run_statement(stmt).await;
+        }
+        
+        let line = stmt.line();
+        for hook in self.instruments.iter() {
+            hook.statement_enter(stmt, line);
+        }
+        // A statement starting means any earlier error was rescued
+        self.error_reported = false;
+        let started = Instant::now();
+        
+        let result = self.run_statement(stmt).await;
+        
+        if let Err(e) = &result {
+            if !self.error_reported && !matches!(e, FlowError::Break { .. } | FlowError::Continue { .. }) {
+                self.error_reported = true;
+                for hook in self.instruments.iter() {
+                    hook.error_raised(e);
+                }
+            }
+        }
+        let elapsed = started.elapsed();
+        for hook in self.instruments.iter() {
+            hook.statement_exit(stmt, line, elapsed);
+        }
+        result
+    }
+    
+    // The statement itself, inside a trace-level span when built with tracing
+    async fn run_statement(&mut self, stmt: &Statement) -> Result<Option<Value>, FlowError> {
+        let run = self.execute_statement_inner(stmt);
+        #[cfg(feature = "tracing")]
+        let run = tracing::Instrument::instrument(run, tracing::trace_span!("statement", line = stmt.line()));
+        run.await
+    }
+    
+    #[async_recursion::async_recursion]
+    async fn execute_statement_inner(&mut self, stmt: &Statement) -> Result<Option<Value>, FlowError> {
         match stmt {
             Statement::Let { name, type_annotation, value, is_exported, line } => {
                 let val = self.evaluate_expression(value).await?;
@@ -517,6 +994,7 @@ impl Interpreter {
                     ));
                 }
                 
+                self.variable_written(name, &val);
                 self.env.define_with_export(name.clone(), val, true, *is_exported);
                 Ok(None)
             }
@@ -538,6 +1016,7 @@ impl Interpreter {
                     ));
                 }
                 
+                self.variable_written(name, &val);
                 self.env.define_with_export(name.clone(), val, false, *is_exported);
                 Ok(None)
             }
@@ -547,7 +1026,13 @@ impl Interpreter {
                 
                 // Try to update the variable
                 match self.env.set(name, val.clone()) {
-                    Ok(_) => Ok(None),
+                    Ok(_) => {
+                        self.variable_written(name, &val);
+                        Ok(None)
+                    }
+                    Err(FlowError::Runtime { message, .. }) if self.env.is_frozen_global(name) => {
+                        Err(FlowError::runtime(&message, *line, 0))
+                    }
                     Err(_) => {
                         // Variable doesn't exist or is sealed
                         Err(FlowError::runtime(
@@ -559,7 +1044,7 @@ impl Interpreter {
                 }
             }
             
-            Statement::FunctionDecl { name, params, return_type, body, sigils: _, is_exported, line } => {
+            Statement::FunctionDecl { name, params, return_type, body, sigils, is_exported, line } => {
                 // Check strict mode for params and return type
                 if self.config.type_required {
                     if return_type.is_none() {
@@ -589,14 +1074,47 @@ impl Interpreter {
                     Some(Arc::new(captured))
                 };
                 
-                let func = Value::Function {
+                let body = Arc::new(body.clone());
+                if has_sigil(sigils, "memo") {
+                    self.memo.register(&body);
+                }
+                if let Some(deprecated) = sigils.iter().find(|s| s.name == "deprecated") {
+                    let message = match deprecated.arguments.first() {
+                        Some(expr) => self.evaluate_expression(expr).await?.to_string(),
+                        None => String::new(),
+                    };
+                    self.deprecations.register(&body, name, message);
+                }
+                
+                let mut func = Value::Function {
                     params: param_names,
                     param_types,
                     return_type: return_type.clone(),
-                    body: Arc::new(body.clone()),
+                    body,
                     is_async: false,
                     closure,
                 };
+
+                // Decorators wrap inside out: the sigil nearest the Spell runs first
+                for sigil in sigils.iter().rev().filter(|s| !BUILTIN_SIGILS.contains(&s.name.as_str())) {
+                    let decorator = match self.env.get(&sigil.name) {
+                        Some(decorator) if decorator.type_name() == "Spell" => decorator,
+                        _ => continue,
+                    };
+                    let mut args = vec![func];
+                    for argument in &sigil.arguments {
+                        args.push(self.evaluate_expression(argument).await?);
+                    }
+                    func = self.execute_function(decorator, args).await?;
+                    if func.type_name() != "Spell" {
+                        return Err(FlowError::type_error(
+                            &format!("Decorator @{} must return a Spell to replace '{}', but returned {}", sigil.name, name, func.type_name()),
+                            *line,
+                            0,
+                        ));
+                    }
+                }
+
                 self.env.define_with_export(name.clone(), func, false, *is_exported);
                 Ok(None)
             }
@@ -751,8 +1269,11 @@ impl Interpreter {
                         };
                         
                         for i in start..=end {
+                            self.check_sandbox()?;
                             self.env.push_scope();
-                            self.env.define(variable.clone(), Value::Number(i as f64), false);
+                            let value = Value::Number(i as f64);
+                            self.variable_written(variable, &value);
+                            self.env.define(variable.clone(), value, false);
                             
                             let mut break_loop = false;
                             
@@ -785,14 +1306,42 @@ impl Interpreter {
                         }
                     }
                     
-                    PhaseKind::ForEach { variable, collection } => {
+                    PhaseKind::ForEach { variable, value_variable, collection } => {
                         let collection_val = self.evaluate_expression(collection).await?;
                         
-                        match collection_val {
-                            Value::Array(arr) => {
-                                for item in arr.iter() {
+                        // What each pass binds: the item (or key), plus the value for two variables.
+                        // Lazy iterators (e.g. file.lines) are Relics with a `next` Spell; Hollow
+                        // from `next()` ends the loop, so they are stepped through below instead.
+                        let is_iterator = value_variable.is_none()
+                            && matches!(&collection_val, Value::Relic(iter) if iter.get("next").is_some_and(|n| n.type_name() == "Spell"));
+                        let bindings: Option<Vec<(Value, Option<Value>)>> = match (&collection_val, value_variable) {
+                            _ if is_iterator => None,
+                            (Value::Array(arr), None) => Some(arr.iter().map(|item| (item.clone(), None)).collect()),
+                            (Value::Array(arr), Some(_)) => Some(
+                                arr.iter().enumerate().map(|(i, item)| (Value::Number(i as f64), Some(item.clone()))).collect(),
+                            ),
+                            (Value::Covenant(set), None) => Some(set.values().map(|item| (item.clone(), None)).collect()),
+                            (Value::Covenant(set), Some(_)) => Some(
+                                set.values().enumerate().map(|(i, item)| (Value::Number(i as f64), Some(item.clone()))).collect(),
+                            ),
+                            (Value::Relic(map), _) => Some(
+                                map.iter()
+                                    .map(|(k, v)| (Value::String(Arc::new(k.clone())), value_variable.as_ref().map(|_| v.clone())))
+                                    .collect(),
+                            ),
+                            _ => None,
+                        };
+                        
+                        match (bindings, collection_val) {
+                            (Some(bindings), _) => {
+                                for (item, value) in bindings {
                                     self.env.push_scope();
-                                    self.env.define(variable.clone(), item.clone(), false);
+                                    self.variable_written(variable, &item);
+                                    self.env.define(variable.clone(), item, false);
+                                    if let (Some(name), Some(value)) = (value_variable, value) {
+                                        self.variable_written(name, &value);
+                                        self.env.define(name.clone(), value, false);
+                                    }
                                     
                                     let mut break_loop = false;
                                     
@@ -824,8 +1373,50 @@ impl Interpreter {
                                     }
                                 }
                             }
+                            (None, Value::Relic(iter)) if is_iterator => {
+                                let next = iter["next"].clone();
+                                loop {
+                                    let item = self.execute_function(next.clone(), vec![]).await?;
+                                    if matches!(item, Value::Null) {
+                                        break;
+                                    }
+
+                                    self.env.push_scope();
+                                    self.variable_written(variable, &item);
+                                    self.env.define(variable.clone(), item, false);
+
+                                    let mut break_loop = false;
+
+                                    for stmt in body {
+                                        match self.execute_statement(stmt).await {
+                                            Ok(Some(ret)) => {
+                                                self.env.pop_scope();
+                                                return Ok(Some(ret));
+                                            }
+                                            Ok(None) => {}
+                                            Err(FlowError::Break { .. }) => {
+                                                break_loop = true;
+                                                break;
+                                            }
+                                            Err(FlowError::Continue { .. }) => {
+                                                break; // Break inner statement loop, continue outer phase loop
+                                            }
+                                            Err(e) => {
+                                                self.env.pop_scope();
+                                                return Err(e);
+                                            }
+                                        }
+                                    }
+
+                                    self.env.pop_scope();
+
+                                    if break_loop {
+                                        break;
+                                    }
+                                }
+                            }
                             _ => return Err(FlowError::type_error(
-                                "For-each loop requires a Constellation (array)!",
+                                "For-each loop requires a Constellation (array), Relic or Covenant!",
                                 *line,
                                 0,
                             )),
@@ -834,6 +1425,7 @@ impl Interpreter {
                     
                     PhaseKind::Until { condition } => {
                         loop {
+                            self.check_sandbox()?;
                             let cond_val = self.evaluate_expression(condition).await?;
                             if cond_val.is_truthy() {
                                 break;
@@ -873,6 +1465,7 @@ impl Interpreter {
                     
                     PhaseKind::Forever => {
                         loop {
+                            self.check_sandbox()?;
                             self.env.push_scope();
                             let mut break_loop = false;
                             
@@ -937,26 +1530,7 @@ impl Interpreter {
                     )),
                 };
                 
-                // Process callbacks while waiting
-                let start = std::time::Instant::now();
-                let wait_duration = std::time::Duration::from_millis(ms);
-                
-                while start.elapsed() < wait_duration {
-                    // Process any pending callbacks
-                    while let Some(request) = self.runtime.run_event_loop_tick().await {
-                        if let Err(e) = self.execute_function(request.callback, request.args).await {
-                            eprintln!("Callback error: {}", e);
-                        }
-                    }
-                    
-                    // Sleep for a short tick interval
-                    let remaining = wait_duration.saturating_sub(start.elapsed());
-                    let tick = std::cmp::min(remaining, std::time::Duration::from_millis(10));
-                    if tick > std::time::Duration::ZERO {
-                        tokio::time::sleep(tick).await;
-                    }
-                }
-                
+                self.wait_ms(ms).await;
                 Ok(None)
             }
             
@@ -980,89 +1554,45 @@ impl Interpreter {
             }
             
             // ⚔️ ERROR ARC - Attempt/Rescue Implementation
-            Statement::Attempt { body, rescue_clauses, finally_block, line } => {
-                let mut result = Ok(None);
-                let mut error_caught = false;
-                
-                // Execute the attempt block
-                for stmt in body {
-                    match self.execute_statement(stmt).await {
-                        Ok(val) => result = Ok(val),
-                        Err(err) => {
-                            // Error occurred - try to match rescue clauses
-                            error_caught = true;
-                            let error_type = err.error_type_name();
-                            let error_msg = err.to_string();
-                            
-                            let mut handled = false;
-                            
-                            for rescue in rescue_clauses {
-                                // Check if this rescue clause matches the error type
-                                let type_matches = rescue.error_type.as_ref()
-                                    .map(|t| t == error_type)
-                                    .unwrap_or(true); // No type specified = catch all
-                                
-                                if type_matches {
-                                    handled = true;
-                                    
-                                    // Bind error to variable if specified
-                                    if let Some(binding) = &rescue.binding {
-                                        self.env.define(
-                                            binding.clone(),
-                                            Value::String(Arc::new(error_msg.clone())),
-                                            true
-                                        );
-                                    }
-                                    
-                                    // Handle retry logic
-                                    if let Some(retry_count) = rescue.retry_count {
-                                        let mut attempts = 0;
-                                        loop {
-                                            attempts += 1;
-                                            
-                                            // Execute rescue body
-                                            for rescue_stmt in &rescue.body {
-                                                self.execute_statement(rescue_stmt).await?;
-                                            }
-                                            
-                                            if attempts >= retry_count {
-                                                break;
-                                            }
-                                            
-                                            // Retry the attempt block
-                                            let mut retry_success = true;
-                                            for stmt in body {
-                                                if let Err(_) = self.execute_statement(stmt).await {
-                                                    retry_success = false;
-                                                    break;
-                                                }
-                                            }
-                                            
-                                            if retry_success {
-                                                break;
-                                            }
-                                        }
-                                    } else {
-                                        // No retry - just execute rescue body
-                                        for rescue_stmt in &rescue.body {
-                                            self.execute_statement(rescue_stmt).await?;
-                                        }
-                                    }
-                                    
-                                    result = Ok(None);
-                                    break;
-                                }
-                            }
-                            
-                            if !handled {
-                                // No rescue clause matched - propagate error
-                                result = Err(err);
-                            }
-                            
-                            break;
+            Statement::Attempt { body, rescue_clauses, finally_block, line: _ } => {
+                // A clause with `retry N` re-runs the attempt body up to N more times before
+                // its own body runs; every failure picks its clause again by error type
+                let mut retries = vec![0; rescue_clauses.len()];
+                let result = loop {
+                    let err = match self.execute_block_once(body).await {
+                        Ok(val) => break Ok(val),
+                        Err(err) => err,
+                    };
+                    
+                    let error_type = err.error_type_name();
+                    let matched = rescue_clauses.iter().position(|rescue| {
+                        // No type specified = catch all
+                        rescue.error_type.as_ref().map(|t| t == error_type).unwrap_or(true)
+                    });
+                    let Some(index) = matched else {
+                        // No rescue clause matched - propagate error
+                        break Err(err);
+                    };
+                    let rescue = &rescue_clauses[index];
+                    
+                    if retries[index] < rescue.retry_count.unwrap_or(0) {
+                        retries[index] += 1;
+                        if let Some(base) = rescue.backoff_ms {
+                            self.wait_ms(retry::backoff_delay(base, retries[index])).await;
                         }
+                        continue;
                     }
-                }
+                    
+                    // Bind error to variable if specified
+                    if let Some(binding) = &rescue.binding {
+                        self.env.define(binding.clone(), rescued_value(&err), true);
+                        self.rescued.insert(binding.clone(), err);
+                    }
+                    for rescue_stmt in &rescue.body {
+                        self.execute_statement(rescue_stmt).await?;
+                    }
+                    break Ok(None);
+                };
                 
                 // Execute finally block if present
                 if let Some(finally) = finally_block {
@@ -1097,24 +1627,27 @@ impl Interpreter {
                 }
             }
             
-            Statement::Ward { body, line: _ } => {
+            Statement::Ward { body, binding, on_absorb, line: _ } => {
                 // Ward: Error containment
                 // Execute body, if error occurs, suppress it and continue
-                for stmt in body {
-                    match self.execute_statement(stmt).await {
-                        Ok(val) => {
-                            if val.is_some() {
-                                return Ok(val);
-                            }
-                        }
-                        Err(err) => {
-                            // Ward absorbs the error
-                            eprintln!("🛡️ WARD ABSORBED ERROR: {}", err);
-                            break; // Stop executing ward body, but don't propagate error
-                        }
-                    }
+                let err = match self.execute_block_once(body).await {
+                    Ok(val) => return Ok(val),
+                    Err(err) => err,
+                };
+                
+                // Ward absorbs the error; an on_absorb handler takes it instead of stderr
+                let Some(handler) = on_absorb else {
+                    eprintln!("🛡️ WARD ABSORBED ERROR: {}", err);
+                    return Ok(None);
+                };
+                self.env.push_scope();
+                if let Some(binding) = binding {
+                    self.env.define(binding.clone(), rescued_value(&err), true);
+                    self.rescued.insert(binding.clone(), err);
                 }
-                Ok(None)
+                let result = self.execute_block_once(handler).await;
+                self.env.pop_scope();
+                result
             }
             
             Statement::BreakSeal { line } => {
@@ -1142,22 +1675,54 @@ impl Interpreter {
                 Ok(None)
             }
             
-            Statement::Rupture { error_type, message, line } => {
+            Statement::Rupture { error_type, message, cause, line } => {
+                let is_builtin = matches!(error_type.as_str(), "Rift" | "Glitch" | "VoidTear" | "Spirit");
+                if !is_builtin && !self.error_sigils.lock().unwrap().contains(error_type) {
+                    return Err(FlowError::runtime(
+                        &format!("Unknown error type: {} (declare it with 'sigil error {} {{ ... }}')", error_type, error_type),
+                        *line,
+                        0,
+                    ));
+                }
+                
                 let msg_val = self.evaluate_expression(message).await?;
                 let msg = msg_val.to_string();
+                let cause = match cause {
+                    Some(cause) => Some(self.cause_error(cause, *line).await?),
+                    None => None,
+                };
                 
-                match error_type.as_str() {
-                    "Rift" => Err(FlowError::Rift { message: msg, line: *line, column: 0 }),
-                    "Glitch" => Err(FlowError::Glitch { message: msg, line: *line, column: 0 }),
-                    "VoidTear" => Err(FlowError::VoidTear { message: msg, line: *line, column: 0 }),
-                    "Spirit" => Err(FlowError::Spirit { message: msg, line: *line, column: 0 }),
-                    _ => Err(FlowError::runtime(&format!("Unknown error type: {}", error_type), *line, 0)),
-                }
+                let err = match error_type.as_str() {
+                    "Rift" => FlowError::Rift { message: msg, line: *line, column: 0 },
+                    "Glitch" => FlowError::Glitch { message: msg, line: *line, column: 0 },
+                    "VoidTear" => FlowError::VoidTear { message: msg, line: *line, column: 0 },
+                    "Spirit" => FlowError::Spirit { message: msg, line: *line, column: 0 },
+                    _ => {
+                        // A `message` field reads best; otherwise show the whole payload
+                        let message = match &msg_val {
+                            Value::Relic(fields) if fields.is_empty() => error_type.clone(),
+                            Value::Relic(fields) => match fields.get("message") {
+                                Some(Value::String(text)) => text.to_string(),
+                                _ => msg,
+                            },
+                            _ => msg,
+                        };
+                        let error = CustomError { name: error_type.clone(), payload: msg_val, message };
+                        FlowError::Custom { error: Box::new(error), line: *line, column: 0 }
+                    }
+                };
+                Err(match cause {
+                    Some(cause) => err.caused_by(cause),
+                    None => err,
+                })
             }
             
             // Sigil type definitions (stored for type checking but don't execute)
-            Statement::SigilDecl { name, fields, is_exported: _, line: _ } => {
+            Statement::SigilDecl { name, fields, is_error, is_exported: _, line: _ } => {
                 self.sigil_definitions.lock().await.insert(name.clone(), fields.clone());
+                if *is_error {
+                    self.error_sigils.lock().unwrap().insert(name.clone());
+                }
                 Ok(None)
             }
         }
@@ -1168,10 +1733,11 @@ impl Interpreter {
         match expr {
             Expression::Number(n) => Ok(Value::Number(*n)),
             Expression::String(s) => Ok(Value::String(Arc::new(s.clone()))),
-            Expression::InterpolatedString(parts) => {
+            Expression::InterpolatedString { parts, spans } => {
                 let mut result = String::new();
-                for part in parts {
-                    let val = self.evaluate_expression(part).await?;
+                for (part, span) in parts.iter().zip(spans) {
+                    let val = self.evaluate_expression(part).await
+                        .map_err(|e| e.or_at(span.line, span.column))?;
                     result.push_str(&val.to_string());
                 }
                 Ok(Value::String(Arc::new(result)))
@@ -1179,13 +1745,18 @@ impl Interpreter {
             Expression::Boolean(b) => Ok(Value::Boolean(*b)),
             
             Expression::Identifier(name) => {
-                self.env.get(name).ok_or_else(|| {
-                    FlowError::undefined(
-                        &format!("You speak the name '{}' but no essence responds!", name),
-                        0,
-                        0,
-                    )
-                })
+                if let Some(value) = self.env.get(name) {
+                    return Ok(value);
+                }
+                // A Sigil's name stands for itself, so `reflect.sigilFields(User)` reads naturally
+                if self.sigil_definitions.lock().await.contains_key(name) {
+                    return Ok(Value::String(Arc::new(name.clone())));
+                }
+                Err(FlowError::undefined(
+                    &format!("You speak the name '{}' but no essence responds!", name),
+                    0,
+                    0,
+                ))
             }
             
             Expression::Binary { left, operator, right } => {
@@ -1227,8 +1798,13 @@ impl Interpreter {
                 
                 // Check if it's a simple identifier call (for built-ins)
                 if let Expression::Identifier(name) = callee.as_ref() {
+                    if name == "evaluate" || name == "circleDynamic" {
+                        // Boxed so the loaders don't grow every expression's future
+                        return Box::pin(self.call_eval_builtin(name, arg_values)).await;
+                    }
                     if stdlib::is_builtin(name) {
-                        return stdlib::call_builtin(name, arg_values);
+                        return std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| stdlib::call_builtin(name, arg_values, self.output.as_ref())))
+                            .unwrap_or_else(|payload| Err(crate::types::panic_error(name, payload)));
                     }
                 }
                 
@@ -1237,6 +1813,10 @@ impl Interpreter {
                 
                 match func_val {
                     Value::Function { params, param_types, return_type, body, is_async: _, closure } => {
+                        let name = callee_name(callee);
+                        let started = self.spell_started(name, &arg_values);
+                        self.deprecated_called(&body);
+                        let call = async {
                         if params.len() != arg_values.len() {
                             return Err(FlowError::runtime(
                                 &format!(
@@ -1267,13 +1847,18 @@ impl Interpreter {
                             }
                         }
                         
+                        let memo_key = self.memo.key_for(&body, &arg_values);
+                        if let Some(hit) = memo_key.as_ref().and_then(|key| self.memo.get(&body, key)) {
+                            return Ok(hit);
+                        }
+                        
                         // Create new scope for function
                         self.env.push_scope();
 
                         // Restore closure bindings if present
                         if let Some(ref captured) = closure {
                             for (name, value) in captured.iter() {
-                                self.env.define(name.clone(), value.clone(), true);
+                                self.env.define_captured(name.clone(), value.clone());
                             }
                         }
                         
@@ -1308,10 +1893,19 @@ impl Interpreter {
                             }
                         }
                         
-                        Ok(result)
+                        if let Some(key) = memo_key {
+                            self.memo.store(&body, key, result.clone());
+                        }
+                        Ok::<Value, FlowError>(result)
+                        };
+                        #[cfg(feature = "tracing")]
+                        let call = tracing::Instrument::instrument(call, tracing::info_span!("spell", name = %name));
+                        let result = call.await;
+                        self.spell_finished(name, started, &result);
+                        result
                     }
                     Value::NativeFunction(func) => {
-                        (func.0)(arg_values)
+                        func.call(callee_name(callee), arg_values)
                     }
                     Value::AsyncNativeFunction(func) => {
                         // Create async context with runtime access
@@ -1319,7 +1913,10 @@ impl Interpreter {
                             runtime: self.runtime.clone(),
                         };
                         // Call the async native function
-                        (func.0)(arg_values, ctx).await
+                        func.call(callee_name(callee), arg_values, ctx).await
+                    }
+                    Value::InterpreterNativeFunction(func) => {
+                        func.call(callee_name(callee), arg_values, self).await
                     }
                     _ => Err(FlowError::type_error(
                         "Can only call Spells!",
@@ -1338,7 +1935,7 @@ impl Interpreter {
             }
 
             Expression::Relic { entries } => {
-                let mut map = HashMap::new();
+                let mut map = RelicMap::new();
                 for (key, value_expr) in entries {
                     let val = self.evaluate_expression(value_expr).await?;
                     map.insert(key.clone(), val);
@@ -1360,7 +1957,7 @@ impl Interpreter {
                 drop(locked_defs); // Release lock before awaiting evaluations
 
                 // 2. Evaluate fields into a map
-                let mut instance_fields = HashMap::new();
+                let mut instance_fields = RelicMap::new();
                 for (key, value_expr) in fields {
                     let val = self.evaluate_expression(value_expr).await?;
                     instance_fields.insert(key.clone(), val);
@@ -1398,7 +1995,25 @@ impl Interpreter {
                 Ok(Value::Relic(Arc::new(instance_fields)))
             }
             
-            Expression::Index { object, index } => {
+            Expression::Attempt { expr, binding, fallback, line: _ } => {
+                match self.evaluate_expression(expr).await {
+                    // break/continue signals belong to the enclosing loop
+                    Err(err) if !matches!(err, FlowError::Break { .. } | FlowError::Continue { .. }) => {
+                        let Some(binding) = binding else {
+                            return self.evaluate_expression(fallback).await;
+                        };
+                        self.env.push_scope();
+                        self.env.define(binding.clone(), rescued_value(&err), true);
+                        self.rescued.insert(binding.clone(), err);
+                        let result = self.evaluate_expression(fallback).await;
+                        self.env.pop_scope();
+                        result
+                    }
+                    result => result,
+                }
+            }
+            
+            Expression::Index { object, index, cache } => {
                 let obj_val = self.evaluate_expression(object).await?;
                 let idx_val = self.evaluate_expression(index).await?;
                 
@@ -1416,7 +2031,7 @@ impl Interpreter {
                         }
                     }
                     (Value::Relic(map), Value::String(key)) => {
-                        map.get(key.as_str()).cloned().ok_or_else(|| {
+                        cache.get(&map, key.as_str()).cloned().ok_or_else(|| {
                             FlowError::undefined(
                                 &format!("The Relic holds no secret named '{}'!", key),
                                 0,
@@ -1445,7 +2060,7 @@ impl Interpreter {
                 }
             }
             
-            Expression::MethodCall { object, method, arguments } => {
+            Expression::MethodCall { object, method, arguments, cache } => {
                 let obj_value = self.evaluate_expression(object).await?;
                 
                 // Evaluate arguments
@@ -1476,6 +2091,13 @@ impl Interpreter {
                                 }
                                 Ok(Value::String(Arc::new(s.to_lowercase())))
                             }
+                            "repeat" => match arg_values.as_slice() {
+                                [Value::Number(n)] => {
+                                    let count = stdlib::array::repeat_count("Silk.repeat()", *n, s.len())?;
+                                    Ok(Value::String(Arc::new(s.repeat(count))))
+                                }
+                                _ => Err(FlowError::runtime("Silk.repeat() takes exactly 1 argument (a count)", 0, 0)),
+                            },
                             "substring" => {
                                 if arg_values.len() != 2 {
                                     return Err(FlowError::runtime("Silk.substring() takes 2 arguments (start, end)", 0, 0));
@@ -1496,6 +2118,7 @@ impl Interpreter {
                                 let substr: String = chars[start..end].iter().collect();
                                 Ok(Value::String(Arc::new(substr)))
                             }
+                            "format" => Ok(Value::String(Arc::new(stdlib::format::forge(s, &arg_values)?))),
                             _ => Err(FlowError::runtime(
                                 &format!("Unknown method '{}' on Silk", method),
                                 0,
@@ -1630,7 +2253,7 @@ impl Interpreter {
                                             // Restore closure bindings if present
                                             if let Some(ref captured) = closure {
                                                 for (name, value) in captured.iter() {
-                                                    self.env.define(name.clone(), value.clone(), true);
+                                                    self.env.define_captured(name.clone(), value.clone());
                                                 }
                                             }
                                             // Bind the element to the first parameter
@@ -1647,7 +2270,7 @@ impl Interpreter {
                                             ret_val
                                         }
                                         Value::NativeFunction(nf) => {
-                                            (nf.0)(vec![item.clone()])?
+                                            nf.call("<callback>", vec![item.clone()])?
                                         }
                                         _ => {
                                             return Err(FlowError::type_error(
@@ -1690,7 +2313,7 @@ impl Interpreter {
                                             // Restore closure bindings if present
                                             if let Some(ref captured) = closure {
                                                 for (name, value) in captured.iter() {
-                                                    self.env.define(name.clone(), value.clone(), true);
+                                                    self.env.define_captured(name.clone(), value.clone());
                                                 }
                                             }
                                             self.env.define(params[0].clone(), item.clone(), true);
@@ -1706,7 +2329,7 @@ impl Interpreter {
                                             ret_val.is_truthy()
                                         }
                                         Value::NativeFunction(nf) => {
-                                            let result = (nf.0)(vec![item.clone()])?;
+                                            let result = nf.call("<callback>", vec![item.clone()])?;
                                             result.is_truthy()
                                         }
                                         _ => {
@@ -1753,7 +2376,7 @@ impl Interpreter {
                                             // Restore closure bindings if present
                                             if let Some(ref captured) = closure {
                                                 for (name, value) in captured.iter() {
-                                                    self.env.define(name.clone(), value.clone(), true);
+                                                    self.env.define_captured(name.clone(), value.clone());
                                                 }
                                             }
                                             self.env.define(params[0].clone(), accumulator.clone(), true);
@@ -1770,7 +2393,7 @@ impl Interpreter {
                                             ret_val
                                         }
                                         Value::NativeFunction(nf) => {
-                                            (nf.0)(vec![accumulator.clone(), item.clone()])?
+                                            nf.call("<callback>", vec![accumulator.clone(), item.clone()])?
                                         }
                                         _ => {
                                             return Err(FlowError::type_error(
@@ -1811,7 +2434,7 @@ impl Interpreter {
                                             // Restore closure bindings if present
                                             if let Some(ref captured) = closure {
                                                 for (name, value) in captured.iter() {
-                                                    self.env.define(name.clone(), value.clone(), true);
+                                                    self.env.define_captured(name.clone(), value.clone());
                                                 }
                                             }
                                             self.env.define(params[0].clone(), item.clone(), true);
@@ -1827,7 +2450,7 @@ impl Interpreter {
                                             ret_val.is_truthy()
                                         }
                                         Value::NativeFunction(nf) => {
-                                            let result = (nf.0)(vec![item.clone()])?;
+                                            let result = nf.call("<callback>", vec![item.clone()])?;
                                             result.is_truthy()
                                         }
                                         _ => {
@@ -1873,7 +2496,7 @@ impl Interpreter {
                                             // Restore closure bindings if present
                                             if let Some(ref captured) = closure {
                                                 for (name, value) in captured.iter() {
-                                                    self.env.define(name.clone(), value.clone(), true);
+                                                    self.env.define_captured(name.clone(), value.clone());
                                                 }
                                             }
                                             self.env.define(params[0].clone(), item.clone(), true);
@@ -1889,7 +2512,7 @@ impl Interpreter {
                                             ret_val.is_truthy()
                                         }
                                         Value::NativeFunction(nf) => {
-                                            let result = (nf.0)(vec![item.clone()])?;
+                                            let result = nf.call("<callback>", vec![item.clone()])?;
                                             result.is_truthy()
                                         }
                                         _ => {
@@ -1935,7 +2558,7 @@ impl Interpreter {
                                             // Restore closure bindings if present
                                             if let Some(ref captured) = closure {
                                                 for (name, value) in captured.iter() {
-                                                    self.env.define(name.clone(), value.clone(), true);
+                                                    self.env.define_captured(name.clone(), value.clone());
                                                 }
                                             }
                                             self.env.define(params[0].clone(), item.clone(), true);
@@ -1951,7 +2574,7 @@ impl Interpreter {
                                             ret_val.is_truthy()
                                         }
                                         Value::NativeFunction(nf) => {
-                                            let result = (nf.0)(vec![item.clone()])?;
+                                            let result = nf.call("<callback>", vec![item.clone()])?;
                                             result.is_truthy()
                                         }
                                         _ => {
@@ -2003,6 +2626,167 @@ impl Interpreter {
                                 let joined: Vec<String> = arr.iter().map(|v| v.to_string()).collect();
                                 Ok(Value::String(Arc::new(joined.join(&separator))))
                             }
+                            "sort" => {
+                                // sort() or sort(compare) - stable; compare(a, b) returns < 0 to keep a first
+                                match arg_values.as_slice() {
+                                    [] => {
+                                        let mut sorted = arr.as_ref().clone();
+                                        let mut failed = None;
+                                        sorted.sort_by(|a, b| stdlib::array::natural_order(a, b).unwrap_or_else(|e| {
+                                            failed.get_or_insert(e);
+                                            std::cmp::Ordering::Equal
+                                        }));
+                                        match failed {
+                                            Some(e) => Err(e),
+                                            None => Ok(Value::Array(Arc::new(sorted))),
+                                        }
+                                    }
+                                    [compare] => Ok(Value::Array(Arc::new(self.merge_sort(arr.as_ref().clone(), compare).await?))),
+                                    _ => Err(FlowError::runtime(
+                                        "Constellation.sort() takes at most 1 argument (a comparator Spell)",
+                                        0,
+                                        0,
+                                    )),
+                                }
+                            }
+                            "sortBy" => {
+                                // sortBy(spell) - stable sort by the key the Spell returns for each element
+                                if arg_values.len() != 1 {
+                                    return Err(FlowError::runtime(
+                                        "Constellation.sortBy() takes exactly 1 argument (a Spell)",
+                                        0,
+                                        0,
+                                    ));
+                                }
+                                let mut keyed = Vec::with_capacity(arr.len());
+                                for item in arr.iter() {
+                                    let key = self.execute_function(arg_values[0].clone(), vec![item.clone()]).await?;
+                                    keyed.push((key, item.clone()));
+                                }
+                                let mut failed = None;
+                                keyed.sort_by(|(a, _), (b, _)| stdlib::array::natural_order(a, b).unwrap_or_else(|e| {
+                                    failed.get_or_insert(e);
+                                    std::cmp::Ordering::Equal
+                                }));
+                                match failed {
+                                    Some(e) => Err(e),
+                                    None => Ok(Value::Array(Arc::new(keyed.into_iter().map(|(_, item)| item).collect()))),
+                                }
+                            }
+                            "min" | "max" => {
+                                if !arg_values.is_empty() {
+                                    return Err(FlowError::runtime(
+                                        &format!("Constellation.{}() takes no arguments", method),
+                                        0,
+                                        0,
+                                    ));
+                                }
+                                let wanted = if method == "min" { std::cmp::Ordering::Less } else { std::cmp::Ordering::Greater };
+                                stdlib::array::extreme(arr, wanted)
+                            }
+                            "sum" => {
+                                if !arg_values.is_empty() {
+                                    return Err(FlowError::runtime("Constellation.sum() takes no arguments", 0, 0));
+                                }
+                                let mut total = 0.0;
+                                for item in arr.iter() {
+                                    match item {
+                                        Value::Number(n) => total += n,
+                                        other => return Err(FlowError::type_error(
+                                            &format!("Constellation.sum() needs Embers, found a {}", other.type_name()),
+                                            0,
+                                            0,
+                                        )),
+                                    }
+                                }
+                                Ok(Value::Number(total))
+                            }
+                            "unique" => {
+                                if !arg_values.is_empty() {
+                                    return Err(FlowError::runtime("Constellation.unique() takes no arguments", 0, 0));
+                                }
+                                Ok(Value::Array(Arc::new(stdlib::array::unique(arr))))
+                            }
+                            "flatten" => {
+                                // flatten() or flatten(depth) - one level unless told otherwise
+                                let depth = match arg_values.as_slice() {
+                                    [] => 1,
+                                    [Value::Number(n)] if *n >= 0.0 && n.fract() == 0.0 => *n as usize,
+                                    _ => return Err(FlowError::runtime(
+                                        "Constellation.flatten() takes at most 1 argument (a whole depth)",
+                                        0,
+                                        0,
+                                    )),
+                                };
+                                Ok(Value::Array(Arc::new(stdlib::array::flatten(arr, depth))))
+                            }
+                            "chunk" | "windowed" => {
+                                // chunk(size) - consecutive slices, the last one may be short
+                                // windowed(size) - every run of `size` neighbours, sliding by one
+                                let size = match arg_values.as_slice() {
+                                    [Value::Number(n)] if *n >= 1.0 && n.fract() == 0.0 => *n as usize,
+                                    _ => return Err(FlowError::runtime(
+                                        &format!("Constellation.{}() takes exactly 1 argument (a whole size of at least 1)", method),
+                                        0,
+                                        0,
+                                    )),
+                                };
+                                let slices: Vec<Value> = if method == "chunk" {
+                                    arr.chunks(size).map(|c| Value::Array(Arc::new(c.to_vec()))).collect()
+                                } else {
+                                    arr.windows(size).map(|w| Value::Array(Arc::new(w.to_vec()))).collect()
+                                };
+                                Ok(Value::Array(Arc::new(slices)))
+                            }
+                            "zip" => {
+                                // zip(other) - [a, b] pairs, as long as the shorter Constellation
+                                match arg_values.as_slice() {
+                                    [Value::Array(other)] => Ok(Value::Array(Arc::new(
+                                        arr.iter()
+                                            .zip(other.iter())
+                                            .map(|(a, b)| Value::Array(Arc::new(vec![a.clone(), b.clone()])))
+                                            .collect(),
+                                    ))),
+                                    _ => Err(FlowError::runtime(
+                                        "Constellation.zip() takes exactly 1 argument (a Constellation)",
+                                        0,
+                                        0,
+                                    )),
+                                }
+                            }
+                            "groupBy" => {
+                                // groupBy(spell) - Relic from each key the Spell returns (as Silk) to its elements
+                                if arg_values.len() != 1 {
+                                    return Err(FlowError::runtime(
+                                        "Constellation.groupBy() takes exactly 1 argument (a Spell)",
+                                        0,
+                                        0,
+                                    ));
+                                }
+                                let mut groups: HashMap<String, Vec<Value>> = HashMap::new();
+                                for item in arr.iter() {
+                                    let key = self.execute_function(arg_values[0].clone(), vec![item.clone()]).await?;
+                                    groups.entry(key.to_string()).or_default().push(item.clone());
+                                }
+                                Ok(Value::Relic(Arc::new(
+                                    groups.into_iter().map(|(k, v)| (k, Value::Array(Arc::new(v)))).collect(),
+                                )))
+                            }
+                            "indexOf" | "includes" => {
+                                if arg_values.len() != 1 {
+                                    return Err(FlowError::runtime(
+                                        &format!("Constellation.{}() takes exactly 1 argument", method),
+                                        0,
+                                        0,
+                                    ));
+                                }
+                                let position = arr.iter().position(|item| item.deep_equals(&arg_values[0]));
+                                if method == "includes" {
+                                    Ok(Value::Boolean(position.is_some()))
+                                } else {
+                                    Ok(Value::Number(position.map_or(-1.0, |i| i as f64)))
+                                }
+                            }
                             _ => Err(FlowError::runtime(
                                 &format!("Unknown method '{}' on Constellation", method),
                                 0,
@@ -2012,7 +2796,7 @@ impl Interpreter {
                     }
                     Value::Relic(map) => {
                         // Handle module function calls like color.cyan()
-                        let func = map.get(method.as_str()).ok_or_else(|| {
+                        let func = cache.get(map, method.as_str()).ok_or_else(|| {
                             FlowError::undefined(
                                 &format!("Module has no function '{}'", method),
                                 0,
@@ -2020,19 +2804,30 @@ impl Interpreter {
                             )
                         })?;
                         
+                        let name = match object.as_ref() {
+                            Expression::Identifier(module) => format!("{}.{}", module, method),
+                            _ => method.clone(),
+                        };
+                        
                         // Call the function
                         match func {
                             Value::NativeFunction(nf) => {
-                                (nf.0)(arg_values)
+                                nf.call(&name, arg_values)
                             }
                             Value::AsyncNativeFunction(af) => {
                                 // Create async context and call the async function
                                 let ctx = AsyncContext {
                                     runtime: self.runtime.clone(),
                                 };
-                                (af.0)(arg_values, ctx).await
+                                af.call(&name, arg_values, ctx).await
+                            }
+                            Value::InterpreterNativeFunction(inf) => {
+                                inf.call(&name, arg_values, self).await
                             }
                             Value::Function { params, param_types: _, return_type, body, is_async: _, closure } => {
+                                let started = self.spell_started(&name, &arg_values);
+                                self.deprecated_called(body);
+                                let call = async {
                                 if params.len() != arg_values.len() {
                                     return Err(FlowError::runtime(
                                         &format!("Function expects {} arguments, got {}", params.len(), arg_values.len()),
@@ -2041,11 +2836,16 @@ impl Interpreter {
                                     ));
                                 }
                                 
+                                let memo_key = self.memo.key_for(body, &arg_values);
+                                if let Some(hit) = memo_key.as_ref().and_then(|key| self.memo.get(body, key)) {
+                                    return Ok(hit);
+                                }
+                                
                                 self.env.push_scope();
                                 // Restore closure bindings if present
                                 if let Some(ref captured) = closure {
                                     for (name, value) in captured.iter() {
-                                        self.env.define(name.clone(), value.clone(), true);
+                                        self.env.define_captured(name.clone(), value.clone());
                                     }
                                 }
                                 for (param, arg) in params.iter().zip(arg_values.iter()) {
@@ -2072,7 +2872,16 @@ impl Interpreter {
                                     }
                                 }
                                 
-                                Ok(result)
+                                if let Some(key) = memo_key {
+                                    self.memo.store(body, key, result.clone());
+                                }
+                                Ok::<Value, FlowError>(result)
+                                };
+                                #[cfg(feature = "tracing")]
+                                let call = tracing::Instrument::instrument(call, tracing::info_span!("spell", name = %name));
+                                let result = call.await;
+                                self.spell_finished(&name, started, &result);
+                                result
                             }
                             _ => Err(FlowError::type_error(
                                 &format!("'{}' is not a function", method),
@@ -2081,6 +2890,49 @@ impl Interpreter {
                             )),
                         }
                     }
+                    Value::Covenant(set) => stdlib::covenant::call_method(set, method, &arg_values),
+                    Value::Handle(id) => {
+                        if method == "onClose" {
+                            return match arg_values.as_slice() {
+                                [spell] if spell.type_name() == "Spell" => {
+                                    self.runtime.on_handle_close(*id, spell.clone()).await;
+                                    Ok(obj_value.clone())
+                                }
+                                _ => Err(FlowError::type_error("Handle.onClose() takes 1 Spell", 0, 0)),
+                            };
+                        }
+                        if !arg_values.is_empty() {
+                            return Err(FlowError::runtime(&format!("Handle.{}() takes no arguments", method), 0, 0));
+                        }
+                        match method.as_str() {
+                            "close" => Ok(Value::Boolean(self.runtime.close_handle(*id).await)),
+                            "isActive" => Ok(Value::Boolean(self.runtime.has_handle(*id).await)),
+                            "id" => Ok(Value::Number(*id as f64)),
+                            _ => Err(FlowError::runtime(&format!("Unknown method '{}' on Handle", method), 0, 0)),
+                        }
+                    }
+                    Value::Function { .. }
+                    | Value::NativeFunction(_)
+                    | Value::AsyncNativeFunction(_)
+                    | Value::InterpreterNativeFunction(_) => stdlib::spell::call_method(&obj_value, method, arg_values),
+                    Value::Number(n) => match (method.as_str(), arg_values.as_slice()) {
+                        ("toFixed", [Value::Number(digits)]) => {
+                            Ok(Value::String(Arc::new(stdlib::math::to_fixed(*n, *digits)?)))
+                        }
+                        ("toBase", [Value::Number(base)]) => {
+                            Ok(Value::String(Arc::new(stdlib::math::to_base(*n, *base)?)))
+                        }
+                        ("toFixed" | "toBase", _) => Err(FlowError::runtime(
+                            &format!("Ember.{}() takes 1 Ember argument", method),
+                            0,
+                            0,
+                        )),
+                        _ => Err(FlowError::runtime(
+                            &format!("Unknown method '{}' on Ember", method),
+                            0,
+                            0,
+                        )),
+                    },
                     _ => Err(FlowError::type_error(
                         &format!("Type {} has no methods", obj_value.type_name()),
                         0,
@@ -2090,45 +2942,21 @@ impl Interpreter {
             }
             
             Expression::Await { expr } => {
-                // For now, just evaluate the expression
-                // In a full implementation, this would handle async
-                self.evaluate_expression(expr).await
+                // Spells already run to completion; awaiting a Handle waits for its resource
+                match self.evaluate_expression(expr).await? {
+                    Value::Handle(id) => {
+                        self.await_handle(id).await;
+                        Ok(Value::Null)
+                    }
+                    value => Ok(value),
+                }
             }
             
             Expression::ComboChain { initial, operations } => {
                 let mut value = self.evaluate_expression(initial).await?;
-                
                 for op in operations {
-                    match op {
-                        ChainOperation::Call(name, args) => {
-                            // Apply function to current value
-                            let mut arg_values = vec![value.clone()];
-                            for arg in args {
-                                arg_values.push(self.evaluate_expression(arg).await?);
-                            }
-                            
-                            if stdlib::is_builtin(name) {
-                                value = stdlib::call_builtin(name, arg_values)?;
-                            } else {
-                                // User-defined function
-                                return Err(FlowError::runtime(
-                                    "Combo chains with user functions not yet implemented!",
-                                    0,
-                                    0,
-                                ));
-                            }
-                        }
-                        ChainOperation::Method(_name) => {
-                            // Apply method to current value
-                            return Err(FlowError::runtime(
-                                "Combo chain methods not yet implemented!",
-                                0,
-                                0,
-                            ));
-                        }
-                    }
+                    value = self.combo_step(value, op).await?;
                 }
-                
                 Ok(value)
             }
             Expression::InlineSpell { params, body, param_types, return_type, .. } => {
@@ -2179,6 +3007,24 @@ impl Interpreter {
                 }
             }
             (Value::Number(a), BinaryOp::Modulo, Value::Number(b)) => Ok(Value::Number(a % b)),
+            (Value::Number(a), BinaryOp::Power, Value::Number(b)) => Ok(Value::Number(a.powf(*b))),
+            
+            // Repetition: "ab" * 3 and [0] * 10, with the count on either side
+            (Value::String(s), BinaryOp::Multiply, Value::Number(n)) | (Value::Number(n), BinaryOp::Multiply, Value::String(s)) => {
+                let count = stdlib::array::repeat_count("Silk * n", *n, s.len())?;
+                Ok(Value::String(Arc::new(s.repeat(count))))
+            }
+            (Value::Array(items), BinaryOp::Multiply, Value::Number(n)) | (Value::Number(n), BinaryOp::Multiply, Value::Array(items)) => {
+                let count = stdlib::array::repeat_count("Constellation * n", *n, items.len())?;
+                Ok(Value::Array(Arc::new(items.iter().cycle().take(items.len() * count).cloned().collect())))
+            }
+            (Value::Number(a), BinaryOp::FloorDivide, Value::Number(b)) => {
+                if *b == 0.0 {
+                    Err(FlowError::division_by_zero(0, 0))
+                } else {
+                    Ok(Value::Number((a / b).floor()))
+                }
+            }
             
             // String concatenation
             (Value::String(a), BinaryOp::Add, Value::String(b)) => {
@@ -2196,6 +3042,9 @@ impl Interpreter {
             (Value::Number(a), BinaryOp::Less, Value::Number(b)) => Ok(Value::Boolean(a < b)),
             (Value::Number(a), BinaryOp::GreaterEq, Value::Number(b)) => Ok(Value::Boolean(a >= b)),
             (Value::Number(a), BinaryOp::LessEq, Value::Number(b)) => Ok(Value::Boolean(a <= b)),
+            (_, BinaryOp::Greater | BinaryOp::Less | BinaryOp::GreaterEq | BinaryOp::LessEq, _) => {
+                Err(ordering_error(op, left, right))
+            }
             
             // Equality
             (a, BinaryOp::IsEqual, b) => Ok(Value::Boolean(self.values_equal(a, b))),
@@ -2218,13 +3067,114 @@ impl Interpreter {
         }
     }
     
+    /// Stable bottom-up merge sort calling a FlowLang comparator, which can't run inside `slice::sort_by`
+    async fn merge_sort(&mut self, mut items: Vec<Value>, compare: &Value) -> Result<Vec<Value>, FlowError> {
+        let len = items.len();
+        let mut width = 1;
+        while width < len {
+            let mut merged = Vec::with_capacity(len);
+            for start in (0..len).step_by(2 * width) {
+                let mid = (start + width).min(len);
+                let end = (start + 2 * width).min(len);
+                let (mut i, mut j) = (start, mid);
+                while i < mid && j < end {
+                    let order = self.execute_function(compare.clone(), vec![items[i].clone(), items[j].clone()]).await?;
+                    let right_first = match order {
+                        Value::Number(n) => n > 0.0,
+                        other => return Err(FlowError::type_error(
+                            &format!("Constellation.sort() comparator must return an Ember, got {}", other.type_name()),
+                            0,
+                            0,
+                        )),
+                    };
+                    if right_first {
+                        merged.push(items[j].clone());
+                        j += 1;
+                    } else {
+                        merged.push(items[i].clone());
+                        i += 1;
+                    }
+                }
+                merged.extend_from_slice(&items[i..mid]);
+                merged.extend_from_slice(&items[j..end]);
+            }
+            items = merged;
+            width *= 2;
+        }
+        Ok(items)
+    }
+    
+    // Constellations, Relics (and so Sigil instances) and Covenants compare by structure
     fn values_equal(&self, a: &Value, b: &Value) -> bool {
-        match (a, b) {
-            (Value::Number(x), Value::Number(y)) => x == y,
-            (Value::String(x), Value::String(y)) => x == y,
-            (Value::Boolean(x), Value::Boolean(y)) => x == y,
-            (Value::Null, Value::Null) => true,
-            _ => false,
+        a.deep_equals(b)
+    }
+}
+
+/// What `rescue ... as e` binds: the sigil instance of an error declared with
+/// `sigil error`, otherwise the error's text
+fn rescued_value(err: &FlowError) -> Value {
+    match err {
+        FlowError::Custom { error, .. } => error.payload.clone(),
+        FlowError::Caused { error, .. } => rescued_value(error),
+        _ => Value::String(Arc::new(err.to_string())),
+    }
+}
+
+/// `<<`/`>>` on values that have no order, with a hint at what to compare instead
+fn ordering_error(op: BinaryOp, left: &Value, right: &Value) -> FlowError {
+    let (l, r) = (left.type_name(), right.type_name());
+    let hint = match (left, right) {
+        (Value::Number(_), Value::String(_)) | (Value::String(_), Value::Number(_)) => {
+            "turn the Silk into an Ember first with math.parseEmber()"
         }
+        (Value::String(_), Value::String(_)) => "compare their lengths with .len(), or put them in order with sort()",
+        (Value::Array(_), Value::Array(_)) => "compare their lengths with .len(), or put them in order with sortBy()",
+        (Value::Relic(_), Value::Relic(_)) => "compare one of their fields instead, such as a.score >> b.score",
+        _ => "only Embers can be ordered",
+    };
+    let subject = if l == r { format!("two {} values", l) } else { format!("{} and {}", l, r) };
+    FlowError::type_error(&format!("Cannot order {} with '{}'; {}", subject, op.symbol(), hint), 0, 0)
+}
+
+/// Where a combo chain step finds the value piped into it; not a name source can spell
+const COMBO_VALUE: &str = ">>";
+
+/// The name a Spell was called by, for profiles and panic messages
+fn callee_name(callee: &Expression) -> &str {
+    match callee {
+        Expression::Identifier(name) => name.as_str(),
+        _ => "<anonymous>",
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_evaluate_and_circle_dynamic_need_allow_eval() {
+        let source = concat!(
+            "let name = \"calc\"\n",
+            "let calc = circleDynamic(\"modules/\" + name)\n",
+            "shout(calc.add(2, 3))\n",
+            "let plugin = evaluate(\"@export let answer = 6 * 7\\nlet hidden = 1\")\n",
+            "shout(plugin.answer)\n",
+            "shout(plugin)\n",
+        );
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests");
+        let config = ProjectConfig { allow_eval: true, ..Default::default() };
+        let mut interpreter = Interpreter::with_dir(dir.clone(), config);
+        let program = crate::lexer::tokenize(source).and_then(crate::parser::parse).unwrap();
+        let (result, output) = interpreter.execute_captured(program).await;
+        result.unwrap();
+        let lines: Vec<_> = output.into_iter().map(|(_, line)| line).collect();
+        assert_eq!(lines, vec!["5", "42", "{ answer: 42 }"]);
+
+        let mut interpreter = Interpreter::with_dir(dir, ProjectConfig::default());
+        let program = crate::lexer::tokenize("evaluate(\"shout(1)\")\n").and_then(crate::parser::parse).unwrap();
+        let (result, output) = interpreter.execute_captured(program).await;
+        assert!(result.unwrap_err().to_string().contains("--allow-eval"));
+        assert!(output.is_empty());
+    }
+}
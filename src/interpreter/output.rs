@@ -0,0 +1,95 @@
+// Where the output built-ins write
+//
+// shout, whisper, chant and roar write through the OutputSink held by the
+// Interpreter (shared with its clones and imported circles) rather than straight to
+// the process streams. The default StdSink prints; CaptureSink keeps the text so
+// the test runner or code embedding FlowLang can read back what a script printed.
+
+use std::io::Write;
+use std::sync::{Mutex, PoisonError};
+
+/// Which stream a line belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    /// shout, whisper and chant
+    Stdout,
+    /// roar
+    Stderr,
+}
+
+pub trait OutputSink: Send + Sync {
+    /// Write one line; `text` has no trailing newline
+    fn write_line(&self, stream: Stream, text: &str);
+}
+
+/// Prints to the process's stdout and stderr
+pub struct StdSink;
+
+impl OutputSink for StdSink {
+    fn write_line(&self, stream: Stream, text: &str) {
+        // A closed pipe (e.g. `flowlang run x.flow | head`) is not worth a panic
+        let _ = match stream {
+            Stream::Stdout => writeln!(std::io::stdout().lock(), "{}", text),
+            Stream::Stderr => writeln!(std::io::stderr().lock(), "{}", text),
+        };
+    }
+}
+
+/// Keeps every line in memory instead of printing it
+#[derive(Default)]
+pub struct CaptureSink {
+    lines: Mutex<Vec<(Stream, String)>>,
+}
+
+impl OutputSink for CaptureSink {
+    fn write_line(&self, stream: Stream, text: &str) {
+        self.lines.lock().unwrap_or_else(PoisonError::into_inner).push((stream, text.to_string()));
+    }
+}
+
+impl CaptureSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything written to `stream` so far, one line per write
+    pub fn text(&self, stream: Stream) -> String {
+        let lines = self.lines.lock().unwrap_or_else(PoisonError::into_inner);
+        lines.iter().filter(|(s, _)| *s == stream).map(|(_, line)| format!("{}\n", line)).collect()
+    }
+
+    /// Remove and return every line so far, both streams in the order written
+    pub fn take(&self) -> Vec<(Stream, String)> {
+        std::mem::take(&mut *self.lines.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::Interpreter;
+
+    #[tokio::test]
+    async fn test_execute_captured_splits_streams() {
+        let source = "shout(\"hello\")\nroar(\"careful\")\nwhisper(1 + 1)\n";
+        let program = crate::lexer::tokenize(source).and_then(crate::parser::parse).unwrap();
+
+        let mut interpreter = Interpreter::new(Default::default());
+        let (result, output) = interpreter.execute_captured(program).await;
+        result.unwrap();
+        assert_eq!(
+            output,
+            vec![
+                (Stream::Stdout, "hello".to_string()),
+                (Stream::Stderr, "careful".to_string()),
+                (Stream::Stdout, "2".to_string()),
+            ]
+        );
+
+        let capture = CaptureSink::new();
+        capture.write_line(Stream::Stdout, "a");
+        capture.write_line(Stream::Stderr, "b");
+        capture.write_line(Stream::Stdout, "c");
+        assert_eq!(capture.text(Stream::Stdout), "a\nc\n");
+    }
+}
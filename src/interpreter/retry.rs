@@ -0,0 +1,30 @@
+//! Delays for `rescue ... retry N backoff 500ms`
+//!
+//! Each retry waits twice as long as the one before, capped at [`MAX_BACKOFF_MS`],
+//! minus a random part so clients that failed together don't all retry together.
+
+/// Longest pause between two tries
+pub const MAX_BACKOFF_MS: u64 = 30_000;
+
+/// Delay in milliseconds before retry number `retry` (counting from 1)
+pub fn backoff_delay(base: u64, retry: usize) -> u64 {
+    let exponential = base.saturating_mul(1u64 << retry.saturating_sub(1).min(32)).min(MAX_BACKOFF_MS);
+    let jitter = (exponential as f64 / 2.0 * crate::stdlib::random::unit()) as u64;
+    exponential - jitter
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubles_with_jitter_up_to_the_cap() {
+        for retry in 1..=4 {
+            let full = 100 << (retry - 1);
+            let delay = backoff_delay(100, retry);
+            assert!((full / 2..=full).contains(&delay), "retry {} waited {}ms", retry, delay);
+        }
+        assert!(backoff_delay(1000, 40) <= MAX_BACKOFF_MS);
+        assert_eq!(backoff_delay(0, 3), 0);
+    }
+}
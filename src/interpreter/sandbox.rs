@@ -0,0 +1,90 @@
+//! Limits for code run through `vm.run`
+//!
+//! A sandboxed interpreter starts from empty globals, may only import the std
+//! modules it was allowed, and stops at its deadline. The deadline is checked before
+//! every statement and every loop pass, so a runaway loop ends even if it never
+//! awaits, and a `rescue` can't keep it going: the next statement fails again.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use crate::error::FlowError;
+
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    /// Std module names (without `std:`) the code may import
+    allow: HashSet<String>,
+    deadline: Instant,
+    timeout: Duration,
+}
+
+impl Sandbox {
+    pub fn new(allow: HashSet<String>, timeout: Duration) -> Self {
+        Sandbox { allow, deadline: Instant::now() + timeout, timeout }
+    }
+
+    /// A sandbox started from inside another one can't outlive or out-import it
+    pub fn within(mut self, outer: &Sandbox) -> Self {
+        self.allow.retain(|name| outer.allow.contains(name));
+        if outer.deadline < self.deadline {
+            self.deadline = outer.deadline;
+            self.timeout = outer.timeout;
+        }
+        self
+    }
+
+    /// Time left before the deadline
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    pub fn check_time(&self) -> Result<(), FlowError> {
+        if Instant::now() >= self.deadline {
+            return Err(self.timed_out());
+        }
+        Ok(())
+    }
+
+    pub fn timed_out(&self) -> FlowError {
+        FlowError::runtime(
+            &format!("Sandboxed code ran past its {}ms timeout", self.timeout.as_millis()),
+            0,
+            0,
+        )
+    }
+
+    /// Whether the code may `circle` from `path`; only allowed std modules qualify
+    pub fn check_import(&self, path: &str) -> Result<(), FlowError> {
+        match path.strip_prefix("std:") {
+            Some(name) if self.allow.contains(name) => Ok(()),
+            Some(name) => Err(FlowError::runtime(
+                &format!("std:{} is not allowed in this sandbox; add \"{}\" to its allow list", name, name),
+                0,
+                0,
+            )),
+            None => Err(FlowError::runtime(
+                &format!("Sandboxed code can only import allowed std modules, not '{}'", path),
+                0,
+                0,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_sandbox_keeps_the_outer_limits() {
+        let outer = Sandbox::new(["math".to_string()].into(), Duration::from_millis(50));
+        let inner = Sandbox::new(["math".to_string(), "file".to_string()].into(), Duration::from_secs(60)).within(&outer);
+        assert!(inner.check_import("std:math").is_ok());
+        assert!(inner.check_import("std:file").is_err());
+        assert!(inner.check_import("./plugin.flow").is_err());
+        assert!(inner.remaining() <= Duration::from_millis(50));
+        assert!(inner.check_time().is_ok());
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(inner.check_time().unwrap_err().to_string().contains("50ms timeout"));
+    }
+}
@@ -0,0 +1,230 @@
+// Time-travel traces for `flowlang run --record-trace`
+//
+// TraceRecorder is an Instrumentation hook that writes every statement, variable
+// write, Spell call and error to a gzip-compressed file of JSON lines. The file is
+// read back by `flowlang dev trace-view`, which rebuilds the call stack and the
+// variables at any event so execution can be stepped forwards and backwards.
+// Values are stored as short display strings, not as values that could be restored.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use super::instrument::Instrumentation;
+use crate::error::FlowError;
+use crate::parser::ast::Statement;
+use crate::types::Value;
+
+/// Version of the trace format. Bump when its shape changes.
+const TRACE_VERSION: u32 = 1;
+
+/// Longest value kept in a trace, in characters
+const MAX_VALUE_CHARS: usize = 200;
+
+/// Events between flushes, so a process that exits abruptly still leaves a readable trace
+const FLUSH_EVERY: u64 = 1024;
+
+/// First line of a trace file
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    flowtrace: u32,
+    script: PathBuf,
+}
+
+/// One thing the interpreter did
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum TraceEvent {
+    /// A statement started on this line
+    Step { line: usize },
+    Write { name: String, value: String },
+    Call { name: String, args: Vec<String> },
+    /// A Spell returned `value`, or failed with `error`
+    Return {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    Error { message: String },
+}
+
+struct TraceWriter {
+    out: GzEncoder<BufWriter<File>>,
+    events: u64,
+    failed: bool,
+}
+
+/// Writes a trace while the interpreter runs
+pub struct TraceRecorder {
+    writer: Mutex<TraceWriter>,
+}
+
+impl TraceRecorder {
+    pub fn create(path: &Path, script: &Path) -> Result<Self, FlowError> {
+        let file = File::create(path)
+            .map_err(|e| FlowError::rift(&format!("Failed to create trace {}: {}", path.display(), e), 0, 0))?;
+        let mut out = GzEncoder::new(BufWriter::new(file), Compression::fast());
+        let header = Header { flowtrace: TRACE_VERSION, script: script.to_path_buf() };
+        serde_json::to_writer(&mut out, &header)
+            .map_err(|e| FlowError::rift(&format!("Failed to write trace {}: {}", path.display(), e), 0, 0))?;
+        Ok(TraceRecorder { writer: Mutex::new(TraceWriter { out, events: 0, failed: false }) })
+    }
+
+    fn push(&self, event: TraceEvent) {
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        if writer.failed {
+            return;
+        }
+        let written = writer.out.write_all(b"\n").map_err(serde_json::Error::io)
+            .and_then(|_| serde_json::to_writer(&mut writer.out, &event));
+        writer.events += 1;
+        let flushed = if writer.events.is_multiple_of(FLUSH_EVERY) { writer.out.flush() } else { Ok(()) };
+        if let Some(e) = written.err().map(|e| e.to_string()).or_else(|| flushed.err().map(|e| e.to_string())) {
+            writer.failed = true;
+            eprintln!("⚠️ Failed to write trace, recording stopped: {}", e);
+        }
+    }
+
+    /// Finish the compressed stream. Returns the number of events written.
+    pub fn finish(&self) -> Result<u64, FlowError> {
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        writer.out.write_all(b"\n")
+            .and_then(|_| writer.out.try_finish())
+            .and_then(|_| writer.out.get_mut().flush())
+            .map_err(|e| FlowError::rift(&format!("Failed to finish trace: {}", e), 0, 0))?;
+        Ok(writer.events)
+    }
+}
+
+impl Instrumentation for TraceRecorder {
+    fn statement_enter(&self, _statement: &Statement, line: usize) {
+        self.push(TraceEvent::Step { line });
+    }
+
+    fn variable_write(&self, name: &str, value: &Value) {
+        self.push(TraceEvent::Write { name: name.to_string(), value: render(value) });
+    }
+
+    fn spell_call(&self, name: &str, args: &[Value]) {
+        self.push(TraceEvent::Call { name: name.to_string(), args: args.iter().map(render).collect() });
+    }
+
+    fn spell_return(&self, name: &str, result: Result<&Value, &FlowError>, _elapsed: Duration) {
+        let (value, error) = match result {
+            Ok(value) => (Some(render(value)), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        self.push(TraceEvent::Return { name: name.to_string(), value, error });
+    }
+
+    fn error_raised(&self, error: &FlowError) {
+        self.push(TraceEvent::Error { message: error.to_string() });
+    }
+}
+
+/// Silk is quoted so `"1"` and `1` stay apart; long values are cut short
+fn render(value: &Value) -> String {
+    let text = match value {
+        Value::String(s) => format!("{:?}", s.as_str()),
+        other => other.to_string(),
+    };
+    match text.char_indices().nth(MAX_VALUE_CHARS) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text,
+    }
+}
+
+/// A trace read back from disk
+pub struct Trace {
+    pub script: PathBuf,
+    pub events: Vec<TraceEvent>,
+    /// The file ended early, e.g. the process exited without finishing it
+    pub truncated: bool,
+}
+
+impl Trace {
+    pub fn load(path: &Path) -> Result<Trace, FlowError> {
+        let file = File::open(path)
+            .map_err(|e| FlowError::rift(&format!("Failed to open trace {}: {}", path.display(), e), 0, 0))?;
+        Self::read(BufReader::new(GzDecoder::new(file)), path)
+    }
+
+    fn read(reader: impl BufRead, path: &Path) -> Result<Trace, FlowError> {
+        let invalid = |message: String| FlowError::glitch(&format!("Invalid trace {}: {}", path.display(), message), 0, 0);
+
+        let mut lines = reader.lines();
+        let header: Header = match lines.next() {
+            Some(Ok(line)) => serde_json::from_str(&line).map_err(|e| invalid(e.to_string()))?,
+            Some(Err(e)) => return Err(invalid(e.to_string())),
+            None => return Err(invalid("the file is empty".to_string())),
+        };
+        if header.flowtrace != TRACE_VERSION {
+            return Err(invalid(format!("version {} is not supported (expected {})", header.flowtrace, TRACE_VERSION)));
+        }
+
+        let mut events = Vec::new();
+        let mut truncated = false;
+        for line in lines {
+            // A cut-off stream still yields every event before the cut
+            let Ok(line) = line else {
+                truncated = true;
+                break;
+            };
+            if line.is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(event) => events.push(event),
+                Err(_) => {
+                    truncated = true;
+                    break;
+                }
+            }
+        }
+
+        Ok(Trace { script: header.script, events, truncated })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::Interpreter;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_trace_round_trip() {
+        let dir = std::env::temp_dir().join(format!("flowlang_trace_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.trace");
+
+        let source = "cast Spell add(a, b) {\n    return a + b\n}\nlet total = 0\ntotal = add(total, 2)\nlet name = \"Goku\"\n";
+        let program = crate::lexer::tokenize(source).and_then(crate::parser::parse).unwrap();
+        let recorder = Arc::new(TraceRecorder::create(&path, Path::new("demo.flow")).unwrap());
+        let mut interpreter = Interpreter::new(Default::default());
+        interpreter.add_instrumentation(recorder.clone());
+        interpreter.execute(program).await.unwrap();
+        let written = recorder.finish().unwrap();
+
+        let trace = Trace::load(&path).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(trace.events.len() as u64, written);
+        assert!(!trace.truncated);
+        assert_eq!(trace.script, PathBuf::from("demo.flow"));
+        assert!(trace.events.contains(&TraceEvent::Call { name: "add".to_string(), args: vec!["0".to_string(), "2".to_string()] }));
+        assert!(trace.events.contains(&TraceEvent::Write { name: "total".to_string(), value: "2".to_string() }));
+        assert_eq!(
+            trace.events.last(),
+            Some(&TraceEvent::Write { name: "name".to_string(), value: "\"Goku\"".to_string() })
+        );
+    }
+}
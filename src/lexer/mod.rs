@@ -1,13 +1,16 @@
 pub mod token;
+pub mod unicode;
 
 use token::{Token, TokenKind};
-use crate::error::FlowError;
+use crate::error::{FixIt, FlowError};
 
 pub struct Lexer {
     source: Vec<char>,
     current: usize,
     line: usize,
     column: usize,
+    /// Byte offset of `current`
+    offset: usize,
     interpolation_stack: Vec<usize>, // Tracks brace depth where interpolation started
     brace_depth: usize,
 }
@@ -19,6 +22,7 @@ impl Lexer {
             current: 0,
             line: 1,
             column: 1,
+            offset: 0,
             interpolation_stack: Vec::new(),
             brace_depth: 0,
         }
@@ -27,6 +31,11 @@ impl Lexer {
     pub fn tokenize(&mut self) -> Result<Vec<Token>, FlowError> {
         let mut tokens = Vec::new();
         
+        // A `#!/usr/bin/env flowlang` first line lets a script run as an executable
+        if self.current == 0 && self.peek() == '#' && self.peek_next() == '!' {
+            self.skip_line_comment();
+        }
+        
         while !self.is_at_end() {
             self.skip_whitespace();
             
@@ -45,10 +54,18 @@ impl Lexer {
                 continue;
             }
             
+            let first = tokens.len();
+            let start = self.offset;
             self.scan_token(&mut tokens)?;
+            
+            // Template literals place their own tokens; everything else covers what it consumed
+            if let Some(token) = tokens.get_mut(first).filter(|t| t.len == 0) {
+                token.offset = start;
+                token.len = self.trimmed_offset() - start;
+            }
         }
         
-        tokens.push(Token::new(TokenKind::Eof, String::new(), self.line, self.column));
+        tokens.push(Token::new(TokenKind::Eof, String::new(), self.line, self.column).at(self.offset, 0));
         Ok(tokens)
     }
     
@@ -69,32 +86,65 @@ impl Lexer {
                 if self.brace_depth > 0 {
                     self.brace_depth -= 1;
                 }
-                tokens.push(Token::new(TokenKind::RightBrace, c.to_string(), start_line, start_column));
+                tokens.push(Token::new(TokenKind::RightBrace, c.to_string(), start_line, start_column).at(self.offset - 1, 1));
                 
                 // Check if we are resuming template literal interpolation
                 if let Some(&depth) = self.interpolation_stack.last() {
                     if self.brace_depth == depth {
                         self.interpolation_stack.pop();
-                        self.scan_template_literal(tokens, self.line, self.column)?;
+                        self.scan_template_literal(tokens, self.line, self.column, self.offset)?;
                     }
                 }
             }
             '[' => tokens.push(Token::new(TokenKind::LeftBracket, c.to_string(), start_line, start_column)),
             ']' => tokens.push(Token::new(TokenKind::RightBracket, c.to_string(), start_line, start_column)),
             ',' => tokens.push(Token::new(TokenKind::Comma, c.to_string(), start_line, start_column)),
+            ';' => tokens.push(Token::new(TokenKind::Semicolon, c.to_string(), start_line, start_column)),
             '.' => tokens.push(Token::new(TokenKind::Dot, c.to_string(), start_line, start_column)),
-            '+' => tokens.push(Token::new(TokenKind::Plus, c.to_string(), start_line, start_column)),
+            '+' => {
+                if self.match_char('=') {
+                    tokens.push(Token::new(TokenKind::PlusEq, "+=".to_string(), start_line, start_column));
+                } else {
+                    tokens.push(Token::new(TokenKind::Plus, c.to_string(), start_line, start_column));
+                }
+            }
             '-' => {
                 if self.peek() == '>' {
                     self.advance();
                     tokens.push(Token::new(TokenKind::Arrow, "->".to_string(), start_line, start_column));
+                } else if self.match_char('=') {
+                    tokens.push(Token::new(TokenKind::MinusEq, "-=".to_string(), start_line, start_column));
                 } else {
                     tokens.push(Token::new(TokenKind::Minus, c.to_string(), start_line, start_column));
                 }
             }
-            '*' => tokens.push(Token::new(TokenKind::Star, c.to_string(), start_line, start_column)),
-            '/' => tokens.push(Token::new(TokenKind::Slash, c.to_string(), start_line, start_column)),
-            '%' => tokens.push(Token::new(TokenKind::Percent, c.to_string(), start_line, start_column)),
+            '*' => {
+                let (kind, lexeme) = if self.match_char('*') {
+                    if self.match_char('=') { (TokenKind::StarStarEq, "**=") } else { (TokenKind::StarStar, "**") }
+                } else if self.match_char('=') {
+                    (TokenKind::StarEq, "*=")
+                } else {
+                    (TokenKind::Star, "*")
+                };
+                tokens.push(Token::new(kind, lexeme.to_string(), start_line, start_column));
+            }
+            '/' => {
+                let (kind, lexeme) = if self.match_char('/') {
+                    if self.match_char('=') { (TokenKind::SlashSlashEq, "//=") } else { (TokenKind::SlashSlash, "//") }
+                } else if self.match_char('=') {
+                    (TokenKind::SlashEq, "/=")
+                } else {
+                    (TokenKind::Slash, "/")
+                };
+                tokens.push(Token::new(kind, lexeme.to_string(), start_line, start_column));
+            }
+            '%' => {
+                if self.match_char('=') {
+                    tokens.push(Token::new(TokenKind::PercentEq, "%=".to_string(), start_line, start_column));
+                } else {
+                    tokens.push(Token::new(TokenKind::Percent, c.to_string(), start_line, start_column));
+                }
+            }
             
             // Multi-character operators
             '>' => {
@@ -135,11 +185,20 @@ impl Lexer {
                 if self.peek() == '!' {
                     self.advance();
                     tokens.push(Token::new(TokenKind::ChainEnd, "!!".to_string(), start_line, start_column));
+                } else if self.peek() == '=' {
+                    return Err(FlowError::syntax_with_fix(
+                        "'!=' is not a FlowLang operator. Use 'not~' to compare for inequality.",
+                        start_line,
+                        start_column,
+                        FixIt::replace("replace '!=' with 'not~'", start_line, start_column, 2, "not~"),
+                    ));
                 } else {
-                    return Err(FlowError::syntax(
+                    let replacement = if self.peek().is_whitespace() { "negate!" } else { "negate! " };
+                    return Err(FlowError::syntax_with_fix(
                         "Lone '!' detected! Use 'both!', 'either!', 'negate!' or '!!' for chain end.",
                         start_line,
                         start_column,
+                        FixIt::replace("replace '!' with 'negate!'", start_line, start_column, 1, replacement),
                     ));
                 }
             }
@@ -154,7 +213,14 @@ impl Lexer {
             }
             
             '=' => {
-                if self.peek() == '>' {
+                if self.peek() == '=' {
+                    return Err(FlowError::syntax_with_fix(
+                        "'==' is not a FlowLang operator. Use 'is~' to compare for equality.",
+                        start_line,
+                        start_column,
+                        FixIt::replace("replace '==' with 'is~'", start_line, start_column, 2, "is~"),
+                    ));
+                } else if self.peek() == '>' {
                     self.advance();
                     tokens.push(Token::new(TokenKind::FatArrow, "=>".to_string(), start_line, start_column));
                 } else {
@@ -165,7 +231,8 @@ impl Lexer {
             // Strings
             '"' => self.scan_double_quote_string(tokens, start_line, start_column)?,
             '\'' => self.scan_simple_string(tokens, start_line, start_column)?,
-            '`' => self.scan_template_literal(tokens, start_line, start_column)?,
+            '`' => self.scan_template_literal(tokens, start_line, start_column, self.offset - 1)?,
+            'r' if self.peek() == '"' => self.scan_raw_string(tokens, start_line, start_column)?,
             
             // Sigils
             '@' => self.scan_sigil(tokens, start_line, start_column)?,
@@ -174,10 +241,21 @@ impl Lexer {
             c if c.is_ascii_digit() => self.scan_number(tokens, c, start_line, start_column)?,
             
             // Identifiers and keywords
-            c if c.is_alphabetic() || c == '_' => {
+            c if unicode::is_ident_start(c) => {
                 self.scan_identifier_or_keyword(tokens, c, start_line, start_column)?
             }
             
+            // Operators borrowed from other languages
+            '&' | '|' if self.peek() == c => {
+                let (keyword, operator) = if c == '&' { ("both!", "&&") } else { ("either!", "||") };
+                return Err(FlowError::syntax_with_fix(
+                    &format!("'{}' is not a FlowLang operator. Use '{}' instead.", operator, keyword),
+                    start_line,
+                    start_column,
+                    FixIt::replace(format!("replace '{}' with '{}'", operator, keyword), start_line, start_column, 2, keyword),
+                ));
+            }
+            
             _ => {
                 return Err(FlowError::syntax(
                     &format!("Unknown character '{}' encountered in the flow!", c),
@@ -209,6 +287,7 @@ impl Lexer {
             
             if self.peek() == '\\' {
                 // Handle escape sequences
+                let (line, column) = (self.line, self.column);
                 self.advance(); // consume backslash
                 
                 if self.is_at_end() {
@@ -219,22 +298,7 @@ impl Lexer {
                     ));
                 }
                 
-                let escaped_char = match self.peek() {
-                    'n' => '\n',
-                    't' => '\t',
-                    'r' => '\r',
-                    '\\' => '\\',
-                    '"' => '"',
-                    '\'' => '\'',
-                    '0' => '\0',
-                    _ => {
-                        // Unknown escape sequence - just include the character as-is
-                        self.peek()
-                    }
-                };
-                
-                value.push(escaped_char);
-                self.advance();
+                value.push(self.scan_escape(line, column)?);
             } else {
                 if self.peek() == '\n' {
                     self.line += 1;
@@ -270,6 +334,7 @@ impl Lexer {
             
             if self.peek() == '\\' {
                 // Handle escape sequences
+                let (line, column) = (self.line, self.column);
                 self.advance(); // consume backslash
                 
                 if self.is_at_end() {
@@ -280,22 +345,7 @@ impl Lexer {
                     ));
                 }
                 
-                let escaped_char = match self.peek() {
-                    'n' => '\n',
-                    't' => '\t',
-                    'r' => '\r',
-                    '\\' => '\\',
-                    '"' => '"',
-                    '\'' => '\'',
-                    '0' => '\0',
-                    _ => {
-                        // Unknown escape sequence - just include the character as-is
-                        self.peek()
-                    }
-                };
-                
-                value.push(escaped_char);
-                self.advance();
+                value.push(self.scan_escape(line, column)?);
             } else {
                 if self.peek() == '\n' {
                     self.line += 1;
@@ -312,7 +362,197 @@ impl Lexer {
         ))
     }
     
-    fn scan_template_literal(&mut self, tokens: &mut Vec<Token>, start_line: usize, start_column: usize) -> Result<(), FlowError> {
+    /// The character written by the escape whose backslash (at `line`, `column`) was
+    /// just consumed. `\$` is accepted everywhere so template literals can spell `${`.
+    fn scan_escape(&mut self, line: usize, column: usize) -> Result<char, FlowError> {
+        let c = self.advance();
+        let escaped = match c {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' | '"' | '\'' | '`' | '$' => c,
+            'x' => {
+                // \xNN names the code point U+00NN
+                let digits = self.take_hex_digits(2);
+                match u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32) {
+                    Some(ch) if digits.len() == 2 => ch,
+                    _ => return Err(FlowError::syntax(
+                        "Invalid hex escape: '\\x' must be followed by two hex digits, like '\\x41'",
+                        line,
+                        column,
+                    )),
+                }
+            }
+            'u' => {
+                let invalid = || FlowError::syntax(
+                    "Invalid unicode escape: write '\\u{...}' with 1 to 6 hex digits, like '\\u{1F30A}'",
+                    line,
+                    column,
+                );
+                if self.peek() != '{' {
+                    return Err(invalid());
+                }
+                self.advance(); // {
+                let digits = self.take_hex_digits(6);
+                if digits.is_empty() || self.peek() != '}' {
+                    return Err(invalid());
+                }
+                self.advance(); // }
+                u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32).ok_or_else(|| {
+                    FlowError::syntax(
+                        &format!("'\\u{{{}}}' is not a Unicode character", digits),
+                        line,
+                        column,
+                    )
+                })?
+            }
+            _ => return Err(FlowError::syntax_with_fix(
+                &format!(
+                    "Unknown escape sequence '\\{}'. Write '\\\\' for a backslash, or use a raw string r\"...\"",
+                    c.escape_debug()
+                ),
+                line,
+                column,
+                FixIt::replace("escape the backslash", line, column, 1, "\\\\"),
+            )),
+        };
+        Ok(escaped)
+    }
+    
+    /// Up to `max` hex digits
+    fn take_hex_digits(&mut self, max: usize) -> String {
+        let mut digits = String::new();
+        while digits.len() < max && !self.is_at_end() && self.peek().is_ascii_hexdigit() {
+            digits.push(self.advance());
+        }
+        digits
+    }
+    
+    /// `r"..."`: no escapes, so backslashes in regexes and Windows paths are written once
+    fn scan_raw_string(&mut self, tokens: &mut Vec<Token>, start_line: usize, start_column: usize) -> Result<(), FlowError> {
+        self.advance(); // "
+        let mut value = String::new();
+        
+        while !self.is_at_end() {
+            if self.peek() == '"' {
+                self.advance();
+                tokens.push(Token::new(
+                    TokenKind::String(value.clone()),
+                    format!("r\"{}\"", value),
+                    start_line,
+                    start_column,
+                ));
+                return Ok(());
+            }
+            if self.peek() == '\n' {
+                self.line += 1;
+                self.column = 0;
+            }
+            value.push(self.advance());
+        }
+        
+        Err(FlowError::syntax(
+            "Unterminated raw string! The Silk essence must be closed with \".",
+            start_line,
+            start_column,
+        ))
+    }
+    
+    /// `silk<<<TAG`, the lines up to one starting with `TAG`, and nothing escaped or
+    /// interpolated. The closing tag's indentation is removed from every line, so
+    /// the text can be indented along with the code around it.
+    fn scan_heredoc(&mut self, tokens: &mut Vec<Token>, start_line: usize, start_column: usize) -> Result<(), FlowError> {
+        let mut tag = String::new();
+        while !self.is_at_end() && unicode::is_ident_continue(self.peek()) {
+            tag.push(self.advance());
+        }
+        if tag.is_empty() {
+            return Err(FlowError::syntax(
+                "Expected a closing tag after 'silk<<<', like silk<<<END",
+                start_line,
+                start_column,
+            ));
+        }
+        while self.peek() == ' ' || self.peek() == '\t' || self.peek() == '\r' {
+            self.advance();
+        }
+        if self.peek() != '\n' {
+            return Err(FlowError::syntax(
+                &format!("The text of silk<<<{} starts on the next line", tag),
+                self.line,
+                self.column,
+            ));
+        }
+        self.advance_line();
+        
+        let tag_chars: Vec<char> = tag.chars().collect();
+        let mut lines: Vec<(usize, String)> = Vec::new();
+        loop {
+            if self.is_at_end() {
+                return Err(FlowError::syntax(
+                    &format!("Unterminated heredoc! The Silk essence must be closed with a line starting with {}.", tag),
+                    start_line,
+                    start_column,
+                ));
+            }
+            
+            let rest = &self.source[self.current..];
+            let indent = rest.iter().take_while(|&&c| c == ' ' || c == '\t').count();
+            let after = &rest[indent..];
+            let closes = after.starts_with(&tag_chars)
+                && !after.get(tag_chars.len()).is_some_and(|&c| unicode::is_ident_continue(c));
+            if closes {
+                let indent: String = rest[..indent].iter().collect();
+                for _ in 0..indent.chars().count() + tag_chars.len() {
+                    self.advance();
+                }
+                
+                let mut text = Vec::with_capacity(lines.len());
+                for (line, content) in &lines {
+                    if content.trim().is_empty() {
+                        text.push("");
+                    } else if let Some(dedented) = content.strip_prefix(indent.as_str()) {
+                        text.push(dedented);
+                    } else {
+                        return Err(FlowError::syntax(
+                            &format!("This line is indented less than the closing {} of its heredoc", tag),
+                            *line,
+                            1,
+                        ));
+                    }
+                }
+                let value = text.join("\n");
+                tokens.push(Token::new(TokenKind::String(value), format!("silk<<<{}", tag), start_line, start_column));
+                return Ok(());
+            }
+            
+            let line = self.line;
+            let mut content = String::new();
+            while !self.is_at_end() && self.peek() != '\n' {
+                content.push(self.advance());
+            }
+            if content.ends_with('\r') {
+                content.pop();
+            }
+            lines.push((line, content));
+            if !self.is_at_end() {
+                self.advance_line();
+            }
+        }
+    }
+    
+    /// Consume the newline under the cursor
+    fn advance_line(&mut self) {
+        self.advance();
+        self.line += 1;
+        self.column = 1;
+    }
+    
+    /// Scan one segment of a template literal: from the opening backtick (or the `}`
+    /// closing an interpolation) at `start_offset` up to the next `${` or the closing
+    /// backtick. Each segment's token covers exactly its own source text.
+    fn scan_template_literal(&mut self, tokens: &mut Vec<Token>, start_line: usize, start_column: usize, start_offset: usize) -> Result<(), FlowError> {
         let mut value = String::new();
         
         while !self.is_at_end() {
@@ -325,21 +565,18 @@ impl Lexer {
                     format!("`{}`", value),
                     start_line,
                     start_column,
-                ));
+                ).at(start_offset, self.offset - start_offset));
                 return Ok(());
             }
             
             if self.peek() == '$' && self.peek_next() == '{' {
-                self.advance(); // $
-                self.advance(); // {
-                
                 // Emit the string part before the interpolation
                 tokens.push(Token::new(
                     TokenKind::StringPart(value.clone()),
                     value,
                     start_line,
                     start_column,
-                ));
+                ).at(start_offset, self.offset - start_offset));
                 
                 // Emit interpolation start
                 tokens.push(Token::new(
@@ -347,15 +584,20 @@ impl Lexer {
                     "${".to_string(),
                     self.line,
                     self.column,
-                ));
+                ).at(self.offset, 2));
+                self.advance(); // $
+                self.advance(); // {
                 
-                // Push current brace depth to stack
+                // Push current brace depth to stack; the `{` of `${` opens a level
+                // that its `}` closes, so this works inside blocks too
                 self.interpolation_stack.push(self.brace_depth);
+                self.brace_depth += 1;
                 return Ok(());
             }
             
             if self.peek() == '\\' {
                 // Handle escape sequences
+                let (line, column) = (self.line, self.column);
                 self.advance(); // consume backslash
                 
                 if self.is_at_end() {
@@ -366,23 +608,7 @@ impl Lexer {
                     ));
                 }
                 
-                let escaped_char = match self.peek() {
-                    'n' => '\n',
-                    't' => '\t',
-                    'r' => '\r',
-                    '\\' => '\\',
-                    '`' => '`',
-                    '"' => '"',
-                    '\'' => '\'',
-                    '0' => '\0',
-                    _ => {
-                        // Unknown escape sequence - just include the character as-is
-                        self.peek()
-                    }
-                };
-                
-                value.push(escaped_char);
-                self.advance();
+                value.push(self.scan_escape(line, column)?);
             } else {
                 if self.peek() == '\n' {
                     self.line += 1;
@@ -400,33 +626,150 @@ impl Lexer {
     }
     
     fn scan_number(&mut self, tokens: &mut Vec<Token>, first: char, start_line: usize, start_column: usize) -> Result<(), FlowError> {
-        let mut num_str = String::from(first);
+        // `text` is the literal as written, for the lexeme and errors; `digits` drops the separators
+        let mut text = String::from(first);
+        let mut digits = String::from(first);
         
-        while !self.is_at_end() && (self.peek().is_ascii_digit() || self.peek() == '.') {
-            num_str.push(self.advance());
+        // Hex, binary and octal literals: 0xFF, 0b1010, 0o17
+        if first == '0' {
+            let radix = match self.peek() {
+                'x' | 'X' => Some(16),
+                'b' | 'B' => Some(2),
+                'o' | 'O' => Some(8),
+                _ => None,
+            };
+            if let Some(radix) = radix {
+                text.push(self.advance());
+                digits.clear();
+                self.scan_digits(&mut text, &mut digits, radix)?;
+                self.reject_stray_digit(&text, radix)?;
+                if digits.is_empty() {
+                    return Err(FlowError::syntax(
+                        &format!("Invalid Ember essence '{}': expected digits after '{}'", text, text),
+                        start_line,
+                        start_column,
+                    ));
+                }
+                let value = u64::from_str_radix(&digits, radix).map_err(|_| {
+                    FlowError::syntax(
+                        &format!("Ember essence '{}' is too large", text),
+                        start_line,
+                        start_column,
+                    )
+                })?;
+                tokens.push(Token::new(
+                    TokenKind::Number(value as f64),
+                    text,
+                    start_line,
+                    start_column,
+                ));
+                return Ok(());
+            }
+        }
+        
+        self.scan_digits(&mut text, &mut digits, 10)?;
+        
+        // A dot only belongs to the number when a digit follows, so `5.abs()` still works
+        if self.peek() == '.' && self.peek_next().is_ascii_digit() {
+            text.push(self.advance());
+            digits.push('.');
+            self.scan_digits(&mut text, &mut digits, 10)?;
+            if self.peek() == '.' && self.peek_next().is_ascii_digit() {
+                return Err(FlowError::syntax(
+                    &format!("Invalid Ember essence '{}.{}': a number has at most one decimal point", text, self.peek_next()),
+                    self.line,
+                    self.column,
+                ));
+            }
+        }
+        
+        if self.peek() == 'e' || self.peek() == 'E' {
+            text.push(self.advance());
+            digits.push('e');
+            if self.peek() == '+' || self.peek() == '-' {
+                let sign = self.advance();
+                text.push(sign);
+                digits.push(sign);
+            }
+            if !self.peek().is_ascii_digit() {
+                return Err(FlowError::syntax(
+                    &format!("Invalid Ember essence '{}': expected exponent digits, like 1e9 or 2.5e-3", text),
+                    self.line,
+                    self.column,
+                ));
+            }
+            self.scan_digits(&mut text, &mut digits, 10)?;
         }
         
-        let value: f64 = num_str.parse().map_err(|_| {
+        let value: f64 = digits.parse().map_err(|_| {
             FlowError::syntax(
-                &format!("Invalid Ember essence: '{}'", num_str),
+                &format!("Invalid Ember essence: '{}'", text),
                 start_line,
                 start_column,
             )
         })?;
+        if value.is_infinite() {
+            return Err(FlowError::syntax(
+                &format!("Ember essence '{}' is too large", text),
+                start_line,
+                start_column,
+            ));
+        }
         
         tokens.push(Token::new(
             TokenKind::Number(value),
-            num_str,
+            text,
             start_line,
             start_column,
         ));
         Ok(())
     }
     
+    /// Digits of `radix`, with `_` allowed between two of them (`1_000_000`, `0xFF_FF`)
+    fn scan_digits(&mut self, text: &mut String, digits: &mut String, radix: u32) -> Result<(), FlowError> {
+        loop {
+            let c = self.peek();
+            if c == '_' {
+                let after_digit = text.chars().last().is_some_and(|p| p.is_digit(radix));
+                if !after_digit || !self.peek_next().is_digit(radix) {
+                    return Err(FlowError::syntax(
+                        &format!("Invalid Ember essence '{}_': '_' can only separate two digits", text),
+                        self.line,
+                        self.column,
+                    ));
+                }
+                text.push(self.advance());
+            } else if c.is_digit(radix) {
+                text.push(c);
+                digits.push(self.advance());
+            } else {
+                return Ok(());
+            }
+        }
+    }
+    
+    // `0b102` and `0o19` are typos, not a number followed by another
+    fn reject_stray_digit(&self, text: &str, radix: u32) -> Result<(), FlowError> {
+        let c = self.peek();
+        let kind = match radix {
+            2 => "binary",
+            8 => "octal",
+            _ => return Ok(()),
+        };
+        if !c.is_ascii_digit() {
+            return Ok(());
+        }
+        Err(FlowError::syntax(
+            &format!("Invalid Ember essence '{}{}': '{}' is not a {} digit", text, c, c, kind),
+            self.line,
+            self.column,
+        ))
+    }
+    
     fn scan_sigil(&mut self, tokens: &mut Vec<Token>, start_line: usize, start_column: usize) -> Result<(), FlowError> {
         let mut name = String::new();
         
-        while !self.is_at_end() && (self.peek().is_alphanumeric() || self.peek() == '_') {
+        while !self.is_at_end() && unicode::is_ident_continue(self.peek()) {
             name.push(self.advance());
         }
         
@@ -451,10 +794,14 @@ impl Lexer {
         let mut ident = String::from(first);
         
         // Scan alphanumeric and underscores
-        while !self.is_at_end() && (self.peek().is_alphanumeric() || self.peek() == '_') {
+        while !self.is_at_end() && unicode::is_ident_continue(self.peek()) {
             ident.push(self.advance());
         }
         
+        if ident == "silk" && self.match_word("<<<") {
+            return self.scan_heredoc(tokens, start_line, start_column);
+        }
+        
         // Check for special operator suffixes (~ or !)
         if !self.is_at_end() && (self.peek() == '~' || self.peek() == '!') {
             ident.push(self.advance());
@@ -595,6 +942,16 @@ impl Lexer {
         Ok(())
     }
     
+    /// Consume the next character if it is `expected`
+    fn match_char(&mut self, expected: char) -> bool {
+        if !self.is_at_end() && self.peek() == expected {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+    
     fn match_word(&mut self, word: &str) -> bool {
         let chars: Vec<char> = word.chars().collect();
         
@@ -681,11 +1038,26 @@ impl Lexer {
     
     fn advance(&mut self) -> char {
         let c = self.source[self.current];
+        if !unicode::extends_cluster(&self.source[..self.current], c) {
+            self.column += 1;
+        }
         self.current += 1;
-        self.column += 1;
+        self.offset += c.len_utf8();
         c
     }
     
+    /// `offset`, less any whitespace consumed while looking ahead for a
+    /// two-word keyword like `in Stance`
+    fn trimmed_offset(&self) -> usize {
+        let trailing: usize = self.source[..self.current]
+            .iter()
+            .rev()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| c.len_utf8())
+            .sum();
+        self.offset - trailing
+    }
+    
     fn is_at_end(&self) -> bool {
         self.current >= self.source.len()
     }
@@ -695,3 +1067,83 @@ pub fn tokenize(source: &str) -> Result<Vec<Token>, FlowError> {
     let mut lexer = Lexer::new(source);
     lexer.tokenize()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(source: &str) -> Result<String, FlowError> {
+        match tokenize(source)?.remove(0).kind {
+            TokenKind::String(value) => Ok(value),
+            other => panic!("expected a string, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_escapes_and_raw_strings() {
+        assert_eq!(string(r#""\x41\u{e9}\u{1F30A}\$""#).unwrap(), "Aé🌊$");
+        assert_eq!(string(r#"r"C:\new\d+""#).unwrap(), r"C:\new\d+");
+        assert_eq!(string(r"`\${x}`").unwrap(), "${x}");
+
+        for bad in [r#""\x4""#, r#""\u{}""#, r#""\u41""#, r#""\u{D800}""#, r#""\u{1234567}""#] {
+            assert!(string(bad).is_err(), "{} should not lex", bad);
+        }
+
+        let error = string(r#""C:\dir""#).unwrap_err();
+        assert_eq!(error.position(), (1, 4));
+        assert_eq!(error.fixes()[0].apply(r#""C:\dir""#).as_deref(), Some(r#""C:\\dir""#));
+    }
+
+    #[test]
+    fn test_heredoc_dedents_to_closing_tag() {
+        let source = "shout(silk<<<SQL\n    SELECT *\n\n      FROM t\\n\n    SQL)\nlet x = 1";
+        let tokens = tokenize(source).unwrap();
+        assert_eq!(tokens[2].kind, TokenKind::String("SELECT *\n\n  FROM t\\n".to_string()));
+        assert_eq!(tokens[3].kind, TokenKind::RightParen);
+        assert_eq!((tokens[3].line, tokens[3].column), (5, 8));
+        assert_eq!(tokens[4].line, 6);
+
+        let error = tokenize("let a = silk<<<END\n  ok\n x\n  END\n").unwrap_err();
+        assert_eq!(error.position(), (3, 1));
+        assert!(tokenize("let a = silk<<<END\nnever closed\n").is_err());
+        assert!(tokenize("let a = silk<<<END text\nEND\n").is_err());
+    }
+
+    #[test]
+    fn test_shebang_line_is_skipped() {
+        let tokens = tokenize("#!/usr/bin/env flowlang\nshout(1)").unwrap();
+        assert_eq!(tokens[0].kind, TokenKind::Identifier("shout".to_string()));
+        assert_eq!(tokens[0].line, 2);
+        // Only the very first line can be a shebang
+        assert!(tokenize("shout(1)\n#!/usr/bin/env flowlang").is_err());
+    }
+
+    #[test]
+    fn test_number_literals() {
+        let number = |source: &str| match tokenize(source).unwrap().remove(0).kind {
+            TokenKind::Number(n) => n,
+            other => panic!("expected a number, got {:?}", other),
+        };
+        assert_eq!(number("1_000_000"), 1_000_000.0);
+        assert_eq!(number("1e9"), 1e9);
+        assert_eq!(number("2.5E-3"), 2.5e-3);
+        assert_eq!(number("0xFF_FF"), 65535.0);
+        assert_eq!(number("0b1010_1010"), 170.0);
+
+        // A dot followed by a name is a method call, and a name after a number is a unit
+        let kinds: Vec<TokenKind> = tokenize("5.abs 500ms").unwrap().into_iter().map(|t| t.kind).collect();
+        assert_eq!(kinds, vec![
+            TokenKind::Number(5.0),
+            TokenKind::Dot,
+            TokenKind::Identifier("abs".to_string()),
+            TokenKind::Number(500.0),
+            TokenKind::Identifier("ms".to_string()),
+            TokenKind::Eof,
+        ]);
+
+        for (bad, column) in [("1.2.3", 4), ("1__0", 2), ("1_", 2), ("1e", 3), ("1e+x", 4), ("0b102", 5), ("0x", 1), ("1e400", 1)] {
+            let error = tokenize(bad).unwrap_err();
+            assert_eq!(error.position(), (1, column), "{}", bad);
+        }
+    }
+}
@@ -1,14 +1,20 @@
+use serde::Serialize;
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Token {
     pub kind: TokenKind,
     pub lexeme: String,
     pub line: usize,
     pub column: usize,
+    /// Byte offset of the token's first character in the source
+    pub offset: usize,
+    /// Bytes of source the token covers. Differs from the lexeme's length for
+    /// strings, whose lexeme holds the unescaped text.
+    pub len: usize,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum TokenKind {
     // Keywords - Control Flow
     InStance,      // in Stance
@@ -92,6 +98,15 @@ pub enum TokenKind {
     Star,          // *
     Slash,         // /
     Percent,       // %
+    StarStar,      // ** (power)
+    SlashSlash,    // // (floor division)
+    PlusEq,        // +=
+    MinusEq,       // -=
+    StarEq,        // *=
+    SlashEq,       // /=
+    PercentEq,     // %=
+    StarStarEq,    // **=
+    SlashSlashEq,  // //=
     
     // Operators - Combo Chain
     ChainOp,       // >> (also used for greater, context-dependent)
@@ -105,6 +120,7 @@ pub enum TokenKind {
     LeftBracket,   // [
     RightBracket,  // ]
     Comma,         // ,
+    Semicolon,     // ; (ends a statement early)
     Dot,           // .
     Colon,         // :
     DoubleColon,   // ::
@@ -152,6 +168,15 @@ impl Token {
             lexeme,
             line,
             column,
+            offset: 0,
+            len: 0,
         }
     }
+
+    /// The same token covering `len` bytes from `offset`
+    pub fn at(mut self, offset: usize, len: usize) -> Self {
+        self.offset = offset;
+        self.len = len;
+        self
+    }
 }
@@ -0,0 +1,89 @@
+// Unicode rules for identifiers and columns
+//
+// Identifiers follow UAX #31 (XID_Start / XID_Continue), so `名前` and `café` are
+// valid names. Columns count user-perceived characters: a base character plus the
+// marks, joiners and modifiers that render with it (`é` spelled e + U+0301, `👍🏽`,
+// `👩‍💻`, `🇯🇵`) is one column. This follows the grapheme cluster rules closely
+// enough for diagnostics without pulling in the full segmentation tables.
+
+/// First character of an identifier
+pub fn is_ident_start(c: char) -> bool {
+    c == '_' || unicode_ident::is_xid_start(c)
+}
+
+/// Any later character of an identifier
+pub fn is_ident_continue(c: char) -> bool {
+    unicode_ident::is_xid_continue(c)
+}
+
+const ZWJ: char = '\u{200D}';
+
+/// Whether `c` joins the cluster of the characters before it on the same line
+pub fn extends_cluster(before: &[char], c: char) -> bool {
+    let Some(&prev) = before.last() else {
+        return false;
+    };
+    if prev == '\n' || c == '\n' {
+        return false;
+    }
+    if c == ZWJ || prev == ZWJ || is_extender(c) {
+        return true;
+    }
+    // Flags are pairs of regional indicators; the second of each pair extends the first
+    if is_regional_indicator(c) {
+        let run = before.iter().rev().take_while(|&&p| is_regional_indicator(p)).count();
+        return run % 2 == 1;
+    }
+    false
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+// Combining marks, variation selectors, skin tones, emoji tags and Hangul vowel/final jamo
+fn is_extender(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'
+        | '\u{0483}'..='\u{0489}'
+        | '\u{0591}'..='\u{05BD}'
+        | '\u{0610}'..='\u{061A}'
+        | '\u{064B}'..='\u{065F}'
+        | '\u{0900}'..='\u{0903}'
+        | '\u{093A}'..='\u{094F}'
+        | '\u{0E31}'
+        | '\u{0E34}'..='\u{0E3A}'
+        | '\u{0E47}'..='\u{0E4E}'
+        | '\u{1160}'..='\u{11FF}'
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{20D0}'..='\u{20FF}'
+        | '\u{3099}'..='\u{309A}'
+        | '\u{FE00}'..='\u{FE0F}'
+        | '\u{FE20}'..='\u{FE2F}'
+        | '\u{1F3FB}'..='\u{1F3FF}'
+        | '\u{E0020}'..='\u{E007F}'
+        | '\u{E0100}'..='\u{E01EF}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::token::TokenKind;
+
+    #[test]
+    fn test_identifiers_and_columns() {
+        let tokens = crate::lexer::tokenize("let 名前 = \"👩\u{200D}💻👍🏽🇯🇵\" + cafe\u{301}\nlet _x1 = ok").unwrap();
+        let names: Vec<(&str, usize, usize)> = tokens.iter()
+            .filter_map(|t| match &t.kind {
+                TokenKind::Identifier(name) => Some((name.as_str(), t.line, t.column)),
+                _ => None,
+            })
+            .collect();
+        // The string is three clusters plus its quotes, so `+` sits at column 16
+        assert_eq!(names, vec![("名前", 1, 5), ("cafe\u{301}", 1, 18), ("_x1", 2, 5), ("ok", 2, 11)]);
+
+        assert!(crate::lexer::tokenize("let 👍 = 1").is_err());
+        assert!(crate::lexer::tokenize("let x = 1\n\u{301}").is_err());
+    }
+}
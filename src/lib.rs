@@ -0,0 +1,27 @@
+//! FlowLang - a mystical anime-themed scripting language
+//!
+//! The `flowlang` binary is a thin command-line front end over this library.
+//! Benchmarks and fuzz targets use it to reach the lexer, parser and
+//! interpreter directly.
+
+pub mod lexer;
+pub mod parser;
+pub mod interpreter;
+pub mod error;
+pub mod types;
+pub mod stdlib;
+pub mod repl;
+pub mod config;
+pub mod cache;
+pub mod optimizer;
+pub mod runtime;
+pub mod package_manager;
+pub mod test_runner;
+pub mod bench_runner;
+pub mod trace_viewer;
+pub mod doc_generator;
+pub mod linter;
+pub mod scaffold;
+pub mod platform;
+pub mod semver;
+pub mod arena;
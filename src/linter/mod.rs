@@ -0,0 +1,306 @@
+// Linter for `flowlang lint`
+//
+// Rules walk the parsed AST (plus one token-level rule for mistakes the parser rejects)
+// and report findings at a default level. The "lint" section of config.flowlang.json
+// can raise or lower any rule to allow / warn / deny; deny findings fail the run.
+
+mod check;
+mod rules;
+mod todos;
+
+pub use check::run as check;
+pub use todos::run as list_todos;
+
+use colored::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{LintConfig, LintLevel};
+use crate::error::FlowError;
+
+const DEFAULT_MAX_NESTING: usize = 4;
+
+/// Every rule with its default level and a one-line description
+pub const RULES: &[(&str, LintLevel, &str)] = &[
+    (rules::UNUSED_VARIABLE, LintLevel::Warn, "a let or seal that is never read"),
+    (rules::SHADOWED_SEAL, LintLevel::Warn, "a declaration that hides a seal from an outer scope"),
+    (rules::UNREACHABLE_CODE, LintLevel::Warn, "statements after return, panic, rupture or a seal"),
+    (rules::SUSPICIOUS_ASSIGNMENT, LintLevel::Deny, "'=' where a condition expects 'is~'"),
+    (rules::DEEP_NESTING, LintLevel::Warn, "blocks nested deeper than max_nesting"),
+    (rules::DEPRECATED_CALL, LintLevel::Warn, "a call to a Spell declared @deprecated"),
+];
+
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub rule: &'static str,
+    pub level: LintLevel,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(rule: &'static str, line: usize, column: usize, message: String) -> Self {
+        let level = RULES.iter().find(|(name, ..)| *name == rule).map(|(_, level, _)| *level).unwrap_or(LintLevel::Warn);
+        Finding { rule, level, line, column, message }
+    }
+}
+
+/// Lint one source file. Findings come back sorted by position with the configured
+/// levels applied; allowed rules are dropped. A syntax error is returned as Err unless
+/// a token-level finding already explains it.
+pub fn lint_source(source: &str, config: &LintConfig) -> Result<Vec<Finding>, FlowError> {
+    lint_source_in(source, None, config)
+}
+
+// `dir` is the directory of the file being linted, so local circles it imports can
+// be read for their @deprecated Spells
+fn lint_source_in(source: &str, dir: Option<&Path>, config: &LintConfig) -> Result<Vec<Finding>, FlowError> {
+    let tokens = crate::lexer::tokenize(source)?;
+    let mut findings = rules::suspicious_assignments(&tokens);
+
+    match crate::parser::parse(tokens) {
+        Ok(program) => {
+            let max_nesting = config.max_nesting.unwrap_or(DEFAULT_MAX_NESTING);
+            let mut deprecated = rules::deprecated_spells(&program);
+            if let Some(dir) = dir {
+                deprecated.extend(imported_deprecations(dir, &program));
+            }
+            findings.extend(rules::AstRules::new(max_nesting, deprecated).check(&program));
+        }
+        Err(e) => {
+            let (line, _) = e.position();
+            if !findings.iter().any(|f| f.line == line) {
+                return Err(e);
+            }
+        }
+    }
+
+    for finding in &mut findings {
+        if let Some(level) = config.rules.get(finding.rule) {
+            finding.level = *level;
+        }
+    }
+    findings.retain(|f| f.level != LintLevel::Allow);
+    findings.sort_by_key(|f| (f.line, f.column));
+    Ok(findings)
+}
+
+// Deprecated Spells of the local circles a file imports, under the names the file
+// calls them by. Circles that can't be read or parsed are skipped; running reports them.
+fn imported_deprecations(dir: &Path, program: &crate::parser::ast::Program) -> HashMap<String, String> {
+    let mut deprecated = HashMap::new();
+    for import in program.imports.iter().filter(|import| crate::cache::is_local_import(import)) {
+        let spells = fs::read_to_string(crate::cache::import_path(dir, import))
+            .ok()
+            .and_then(|source| crate::lexer::tokenize(&source.replace("\u{feff}", "")).ok())
+            .and_then(|tokens| crate::parser::parse(tokens).ok())
+            .map(|circle| rules::deprecated_spells(&circle))
+            .unwrap_or_default();
+
+        match &import.selective {
+            Some(members) => {
+                for member in members {
+                    if let Some(message) = spells.get(&member.name) {
+                        deprecated.insert(member.alias.clone().unwrap_or(member.name.clone()), message.clone());
+                    }
+                }
+            }
+            None => {
+                let alias = import.alias.clone().unwrap_or(import.module.clone());
+                for (name, message) in spells {
+                    deprecated.insert(format!("{}.{}", alias, name), message);
+                }
+            }
+        }
+    }
+    deprecated
+}
+
+/// Lint every .flow file under `paths`. Returns true when nothing was denied.
+pub fn run(paths: Vec<PathBuf>, config: &LintConfig) -> bool {
+    for name in config.rules.keys() {
+        if !RULES.iter().any(|(rule, ..)| rule == name) {
+            eprintln!("{} unknown lint rule '{}' in config.flowlang.json", "⚠️".yellow(), name);
+        }
+    }
+
+    let files = source_files(paths);
+
+    if files.is_empty() {
+        eprintln!("{}", "❌ No .flow files found to lint".red().bold());
+        return false;
+    }
+
+    let (mut warnings, mut errors) = (0, 0);
+    for file in &files {
+        let result = fs::read_to_string(file)
+            .map_err(|e| FlowError::rift(&format!("Failed to read file: {}", e), 0, 0))
+            .and_then(|source| {
+                let dir = file.parent().unwrap_or(Path::new("."));
+                lint_source_in(&source.replace("\u{feff}", ""), Some(dir), config)
+            });
+
+        match result {
+            Ok(findings) => {
+                for finding in findings {
+                    print_finding(file, &finding);
+                    match finding.level {
+                        LintLevel::Deny => errors += 1,
+                        _ => warnings += 1,
+                    }
+                }
+            }
+            Err(e) => {
+                let (line, column) = e.position();
+                println!("{}: {}", "error[syntax]".red().bold(), e.message());
+                println!("  {} {}:{}:{}", "-->".cyan(), file.display(), line, column);
+                println!();
+                errors += 1;
+            }
+        }
+    }
+
+    let summary = format!("{} file(s) checked: {} error(s), {} warning(s)", files.len(), errors, warnings);
+    if errors > 0 {
+        println!("{} {}", "❌".red(), summary.bright_red().bold());
+    } else if warnings > 0 {
+        println!("{} {}", "⚠️".yellow(), summary.yellow().bold());
+    } else {
+        println!("{} {}", "✅".green(), summary.bright_green().bold());
+    }
+    errors == 0
+}
+
+// Same shape as `--error-style plain`, with the rule name in place of the error code
+fn print_finding(file: &Path, finding: &Finding) {
+    let label = match finding.level {
+        LintLevel::Deny => format!("error[{}]", finding.rule).red().bold(),
+        _ => format!("warning[{}]", finding.rule).yellow().bold(),
+    };
+    println!("{}: {}", label, finding.message);
+    if finding.column > 0 {
+        println!("  {} {}:{}:{}", "-->".cyan(), file.display(), finding.line, finding.column);
+    } else {
+        println!("  {} {}:{}", "-->".cyan(), file.display(), finding.line);
+    }
+    println!();
+}
+
+/// Print the rule table for `flowlang lint --rules`
+pub fn print_rules(config: &LintConfig) {
+    for (name, default, description) in RULES {
+        let level = config.rules.get(*name).unwrap_or(default);
+        let level = match level {
+            LintLevel::Allow => "allow".dimmed(),
+            LintLevel::Warn => "warn".yellow(),
+            LintLevel::Deny => "deny".red(),
+        };
+        println!("{:<24} {:<6} {}", name, level, description);
+    }
+}
+
+// The .flow files under `paths`, sorted; ./src (or . without one) by default, or at
+// a workspace root, its own ./src and each member's
+fn source_files(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    let roots = if paths.is_empty() {
+        match crate::config::Workspace::discover(Path::new(".")).ok().flatten() {
+            Some(workspace) if crate::platform::canonicalize(Path::new(".")).ok().as_ref() == Some(&workspace.root) => {
+                let mut roots = vec![PathBuf::from("src")];
+                roots.extend(workspace.members.iter().map(|(_, dir)| {
+                    if dir.join("src").is_dir() { dir.join("src") } else { dir.clone() }
+                }));
+                roots
+            }
+            _ => vec![if Path::new("src").is_dir() { PathBuf::from("src") } else { PathBuf::from(".") }],
+        }
+    } else {
+        paths
+    };
+    let mut files = Vec::new();
+    for root in &roots {
+        collect_sources(root, &mut files);
+    }
+    files.sort();
+    files
+}
+
+// Hidden directories (.flowlang/pkg) hold installed packages, not project code
+fn collect_sources(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return;
+    }
+
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if entry_path.is_dir() {
+            if !file_name.starts_with('.') && file_name != "__snapshots__" {
+                collect_sources(&entry_path, files);
+            }
+        } else if file_name.ends_with(".flow") {
+            files.push(entry_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules_hit(source: &str) -> Vec<(&'static str, usize)> {
+        lint_source(source, &LintConfig::default())
+            .unwrap()
+            .into_iter()
+            .map(|f| (f.rule, f.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_unused_and_shadowed() {
+        // `later` is declared after the Spell but before it runs, so it counts as used
+        let source = "seal LIMIT = 3\nlet unused = 1\nlet _ignored = 2\ncast Spell check(n) {\n    let LIMIT = 5\n    return n >> LIMIT + later\n}\nlet later = 4\nshout(check(LIMIT))\n";
+        assert_eq!(rules_hit(source), vec![(rules::UNUSED_VARIABLE, 2), (rules::SHADOWED_SEAL, 5)]);
+    }
+
+    #[test]
+    fn test_unreachable_nesting_and_assignment() {
+        let source = "cast Spell f() {\n    return 1\n    shout(\"never\")\n}\nf()\n";
+        assert_eq!(rules_hit(source), vec![(rules::UNREACHABLE_CODE, 3)]);
+
+        let nested = "in Stance (true) {\n in Stance (true) {\n  in Stance (true) {\n   in Stance (true) {\n    in Stance (true) { shout(1) }\n   }\n  }\n }\n}\n";
+        assert_eq!(rules_hit(nested), vec![(rules::DEEP_NESTING, 5)]);
+
+        let findings = lint_source("let x = 1\nin Stance (x = 5) { shout(x) }\n", &LintConfig::default()).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!((findings[0].rule, findings[0].level), (rules::SUSPICIOUS_ASSIGNMENT, LintLevel::Deny));
+    }
+
+    #[test]
+    fn test_deprecated_calls() {
+        // A parameter with the same name is not the deprecated Spell
+        let source = "@deprecated(\"use plus\")\ncast Spell add(a, b) {\n    return a + b\n}\ncast Spell apply(add) {\n    return add(1, 2)\n}\nshout(add(1, 2))\n";
+        let findings = lint_source(source, &LintConfig::default()).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!((findings[0].rule, findings[0].line), (rules::DEPRECATED_CALL, 8));
+        assert_eq!(findings[0].message, "'add' is deprecated: use plus");
+    }
+
+    #[test]
+    fn test_config_levels() {
+        let mut config = LintConfig::default();
+        config.rules.insert(rules::UNUSED_VARIABLE.to_string(), LintLevel::Deny);
+        config.rules.insert(rules::UNREACHABLE_CODE.to_string(), LintLevel::Allow);
+
+        let findings = lint_source("let a = 1\nreturn 2\nshout(3)\n", &config).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!((findings[0].rule, findings[0].level), (rules::UNUSED_VARIABLE, LintLevel::Deny));
+    }
+}
@@ -0,0 +1,462 @@
+// AST and token rules for `flowlang lint`
+//
+// Rules only record findings at their default level; the driver in mod.rs applies the
+// project's configuration afterwards.
+
+use std::collections::HashMap;
+
+use super::Finding;
+use crate::lexer::token::{Token, TokenKind};
+use crate::parser::ast::{ChainOperation, Expression, InlineSpellBody, PhaseKind, Program, Statement};
+
+pub const UNUSED_VARIABLE: &str = "unused-variable";
+pub const SHADOWED_SEAL: &str = "shadowed-seal";
+pub const UNREACHABLE_CODE: &str = "unreachable-code";
+pub const SUSPICIOUS_ASSIGNMENT: &str = "suspicious-assignment";
+pub const DEEP_NESTING: &str = "deep-nesting";
+pub const DEPRECATED_CALL: &str = "deprecated-call";
+
+#[derive(Debug)]
+struct Binding {
+    name: String,
+    line: usize,
+    is_seal: bool,
+    used: bool,
+    /// Exported names, parameters and loop variables are never reported as unused
+    reportable: bool,
+}
+
+#[derive(Default)]
+struct Scope {
+    bindings: Vec<Binding>,
+    /// Spell bodies see every declaration in their enclosing scope, including later
+    /// ones, so they are walked when the scope closes
+    deferred: Vec<Deferred>,
+}
+
+enum Deferred {
+    Block { params: Vec<String>, body: Vec<Statement>, line: usize },
+    Expression { params: Vec<String>, expr: Expression, line: usize },
+}
+
+pub struct AstRules {
+    scopes: Vec<Scope>,
+    depth: usize,
+    max_nesting: usize,
+    /// `@deprecated` Spells by the name they are called with ("spell" or "circle.spell")
+    deprecated: HashMap<String, String>,
+    /// Line of the statement being walked, for findings inside expressions
+    line: usize,
+    findings: Vec<Finding>,
+}
+
+impl AstRules {
+    pub fn new(max_nesting: usize, deprecated: HashMap<String, String>) -> Self {
+        AstRules { scopes: Vec::new(), depth: 0, max_nesting, deprecated, line: 0, findings: Vec::new() }
+    }
+
+    pub fn check(mut self, program: &Program) -> Vec<Finding> {
+        self.push_scope();
+        self.block(&program.statements);
+        self.pop_scope();
+        self.findings.sort_by_key(|f| (f.line, f.column));
+        self.findings
+    }
+
+    fn report(&mut self, rule: &'static str, line: usize, message: String) {
+        self.findings.push(Finding::new(rule, line, 0, message));
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push(Scope::default());
+    }
+
+    fn pop_scope(&mut self) {
+        // Deferred bodies may defer more work of their own, so drain until empty
+        loop {
+            let deferred = match self.scopes.last_mut() {
+                Some(scope) if !scope.deferred.is_empty() => std::mem::take(&mut scope.deferred),
+                _ => break,
+            };
+            for item in deferred {
+                self.walk_deferred(item);
+            }
+        }
+
+        let scope = self.scopes.pop().expect("scope stack underflow");
+        for binding in scope.bindings {
+            if binding.reportable && !binding.used && !binding.name.starts_with('_') {
+                let what = if binding.is_seal { "Seal" } else { "Variable" };
+                self.report(UNUSED_VARIABLE, binding.line, format!("{} '{}' is declared but never used", what, binding.name));
+            }
+        }
+    }
+
+    fn walk_deferred(&mut self, item: Deferred) {
+        // A Spell body starts its own nesting count
+        let outer_depth = std::mem::replace(&mut self.depth, 0);
+        self.push_scope();
+        match item {
+            Deferred::Block { params, body, line } => {
+                for name in &params {
+                    self.declare(name, line, false, false);
+                }
+                self.block(&body);
+            }
+            Deferred::Expression { params, expr, line } => {
+                for name in &params {
+                    self.declare(name, line, false, false);
+                }
+                self.line = line;
+                self.expression(&expr);
+            }
+        }
+        self.pop_scope();
+        self.depth = outer_depth;
+    }
+
+    fn declare(&mut self, name: &str, line: usize, is_seal: bool, reportable: bool) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.bindings.push(Binding { name: name.to_string(), line, is_seal, used: false, reportable });
+        }
+    }
+
+    fn check_shadowing(&mut self, name: &str, line: usize) {
+        let shadowed = self
+            .scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.bindings.iter().rev())
+            .find(|b| b.name == name)
+            .filter(|b| b.is_seal)
+            .map(|b| b.line);
+        if let Some(seal_line) = shadowed {
+            self.report(
+                SHADOWED_SEAL,
+                line,
+                format!("'{}' shadows the seal declared on line {}", name, seal_line),
+            );
+        }
+    }
+
+    // Only names declared at the top level (or imported) can be the deprecated Spell;
+    // an inner binding with the same name hides it
+    fn check_deprecated(&mut self, callee: &str, name: &str) {
+        if self.scopes.iter().skip(1).any(|scope| scope.bindings.iter().any(|b| b.name == name)) {
+            return;
+        }
+        if let Some(message) = self.deprecated.get(callee) {
+            let message = if message.is_empty() {
+                format!("'{}' is deprecated", callee)
+            } else {
+                format!("'{}' is deprecated: {}", callee, message)
+            };
+            self.report(DEPRECATED_CALL, self.line, message);
+        }
+    }
+
+    fn mark_used(&mut self, name: &str) {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(binding) = scope.bindings.iter_mut().rev().find(|b| b.name == name) {
+                binding.used = true;
+                return;
+            }
+        }
+    }
+
+    fn block(&mut self, statements: &[Statement]) {
+        let mut terminated_by: Option<&'static str> = None;
+        for stmt in statements {
+            if let Some(keyword) = terminated_by.take() {
+                self.report(
+                    UNREACHABLE_CODE,
+                    stmt.line(),
+                    format!("Unreachable code: this can never run after '{}'", keyword),
+                );
+                // One finding per block is enough; keep checking the rest for other rules
+            }
+            self.statement(stmt);
+            if terminated_by.is_none() {
+                terminated_by = terminator(stmt);
+            }
+        }
+    }
+
+    fn nested_block(&mut self, statements: &[Statement], line: usize) {
+        self.depth += 1;
+        if self.depth == self.max_nesting + 1 {
+            self.report(
+                DEEP_NESTING,
+                line,
+                format!("Blocks are nested {} levels deep (max {}); consider extracting a Spell", self.depth, self.max_nesting),
+            );
+        }
+        self.push_scope();
+        self.block(statements);
+        self.pop_scope();
+        self.depth -= 1;
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        self.line = stmt.line();
+        match stmt {
+            Statement::Let { name, value, is_exported, line, .. } => {
+                self.expression(value);
+                self.check_shadowing(name, *line);
+                self.declare(name, *line, false, !is_exported);
+            }
+            Statement::Seal { name, value, is_exported, line, .. } => {
+                self.expression(value);
+                self.check_shadowing(name, *line);
+                self.declare(name, *line, true, !is_exported);
+            }
+            Statement::Assignment { value, .. } => self.expression(value),
+            Statement::FunctionDecl { name, params, body, line, .. } | Statement::Ritual { name, params, body, line, .. } => {
+                self.declare(name, *line, false, false);
+                let params = params.iter().map(|p| p.name.clone()).collect();
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.deferred.push(Deferred::Block { params, body: body.clone(), line: *line });
+                }
+            }
+            Statement::Return { value, .. } | Statement::ShatterGrandSeal { value, .. } => {
+                if let Some(value) = value {
+                    self.expression(value);
+                }
+            }
+            Statement::Stance { condition, then_branch, shift_branches, abandon_branch, line } => {
+                self.expression(condition);
+                self.nested_block(then_branch, *line);
+                for (condition, branch) in shift_branches {
+                    self.expression(condition);
+                    self.nested_block(branch, *line);
+                }
+                if let Some(branch) = abandon_branch {
+                    self.nested_block(branch, *line);
+                }
+            }
+            Statement::Aura { value, cases, otherwise, line } => {
+                self.expression(value);
+                for (pattern, body) in cases {
+                    self.expression(pattern);
+                    self.nested_block(body, *line);
+                }
+                if let Some(body) = otherwise {
+                    self.nested_block(body, *line);
+                }
+            }
+            Statement::Phase { kind, body, line } => {
+                let variables = match kind {
+                    PhaseKind::Count { variable, from, to } => {
+                        self.expression(from);
+                        self.expression(to);
+                        vec![variable]
+                    }
+                    PhaseKind::ForEach { variable, value_variable, collection } => {
+                        self.expression(collection);
+                        std::iter::once(variable).chain(value_variable).collect()
+                    }
+                    PhaseKind::Until { condition } => {
+                        self.expression(condition);
+                        vec![]
+                    }
+                    PhaseKind::Forever => vec![],
+                };
+                // The loop variables live in their own scope around the body
+                self.push_scope();
+                for variable in variables {
+                    self.declare(variable, *line, false, false);
+                }
+                self.nested_block(body, *line);
+                self.pop_scope();
+            }
+            Statement::Expression { expr, .. } => self.expression(expr),
+            Statement::Wait { duration, .. } => self.expression(duration),
+            Statement::Perform { rituals, .. } => {
+                for ritual in rituals {
+                    self.expression(ritual);
+                }
+            }
+            Statement::Attempt { body, rescue_clauses, finally_block, line } => {
+                self.nested_block(body, *line);
+                for clause in rescue_clauses {
+                    self.push_scope();
+                    if let Some(binding) = &clause.binding {
+                        self.declare(binding, *line, false, false);
+                    }
+                    self.nested_block(&clause.body, *line);
+                    self.pop_scope();
+                }
+                if let Some(block) = finally_block {
+                    self.nested_block(block, *line);
+                }
+            }
+            Statement::Panic { message, .. } | Statement::Wound { message, .. } => {
+                self.expression(message)
+            }
+            Statement::Rupture { message, cause, .. } => {
+                self.expression(message);
+                if let Some(cause) = cause {
+                    self.expression(cause);
+                }
+            }
+            Statement::Rebound { error, .. } => {
+                if let Some(name) = error {
+                    self.mark_used(name);
+                }
+            }
+            Statement::Ward { body, binding, on_absorb, line } => {
+                self.nested_block(body, *line);
+                if let Some(handler) = on_absorb {
+                    self.push_scope();
+                    if let Some(binding) = binding {
+                        self.declare(binding, *line, false, false);
+                    }
+                    self.nested_block(handler, *line);
+                    self.pop_scope();
+                }
+            }
+            Statement::SigilDecl { .. } | Statement::BreakSeal { .. } | Statement::FractureSeal { .. } => {}
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Identifier(name) => self.mark_used(name),
+            Expression::Number(_) | Expression::String(_) | Expression::Boolean(_) => {}
+            Expression::InterpolatedString { parts, .. } => parts.iter().for_each(|p| self.expression(p)),
+            Expression::Binary { left, right, .. } => {
+                self.expression(left);
+                self.expression(right);
+            }
+            Expression::Unary { operand, .. } => self.expression(operand),
+            Expression::Call { callee, arguments } => {
+                if let Expression::Identifier(name) = &**callee {
+                    self.check_deprecated(name, name);
+                }
+                self.expression(callee);
+                arguments.iter().for_each(|a| self.expression(a));
+            }
+            Expression::MethodCall { object, method, arguments, .. } => {
+                if let Expression::Identifier(circle) = &**object {
+                    self.check_deprecated(&format!("{}.{}", circle, method), circle);
+                }
+                self.expression(object);
+                arguments.iter().for_each(|a| self.expression(a));
+            }
+            Expression::Await { expr } => self.expression(expr),
+            Expression::Array { elements } => elements.iter().for_each(|e| self.expression(e)),
+            Expression::Index { object, index, .. } => {
+                self.expression(object);
+                self.expression(index);
+            }
+            Expression::Relic { entries } => entries.iter().for_each(|(_, v)| self.expression(v)),
+            Expression::SigilInstance { fields, .. } => fields.iter().for_each(|(_, v)| self.expression(v)),
+            Expression::Attempt { expr, binding, fallback, line } => {
+                self.expression(expr);
+                self.push_scope();
+                if let Some(binding) = binding {
+                    self.declare(binding, *line, false, false);
+                }
+                self.expression(fallback);
+                self.pop_scope();
+            }
+            Expression::ComboChain { initial, operations } => {
+                self.expression(initial);
+                for operation in operations {
+                    match operation {
+                        ChainOperation::Call(name, arguments) => {
+                            self.mark_used(name);
+                            arguments.iter().for_each(|a| self.expression(a));
+                        }
+                        ChainOperation::Method(name) => self.mark_used(name),
+                        ChainOperation::Member(object, _, arguments) => {
+                            self.expression(object);
+                            arguments.iter().for_each(|a| self.expression(a));
+                        }
+                    }
+                }
+            }
+            Expression::InlineSpell { params, body, line, .. } => {
+                let deferred = match body {
+                    InlineSpellBody::Expression(expr) => {
+                        Deferred::Expression { params: params.clone(), expr: (**expr).clone(), line: *line }
+                    }
+                    InlineSpellBody::Block(body) => Deferred::Block {
+                        params: params.clone(),
+                        body: body.clone(),
+                        line: *line,
+                    },
+                };
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.deferred.push(deferred);
+                }
+            }
+        }
+    }
+}
+
+/// Top-level Spells declared `@deprecated`, with their messages. Only string literal
+/// messages can be read without running the program; others come back empty.
+pub fn deprecated_spells(program: &Program) -> HashMap<String, String> {
+    program.statements.iter()
+        .filter_map(|stmt| match stmt {
+            Statement::FunctionDecl { name, sigils, .. } => {
+                let sigil = sigils.iter().find(|s| s.name == "deprecated")?;
+                Some((name.clone(), sigil.message().unwrap_or_default().to_string()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The keyword that ends control flow in this block, if the statement always does
+fn terminator(stmt: &Statement) -> Option<&'static str> {
+    match stmt {
+        Statement::Return { .. } => Some("return"),
+        Statement::Panic { .. } => Some("panic"),
+        Statement::Rupture { .. } => Some("rupture"),
+        Statement::BreakSeal { .. } => Some("break seal"),
+        Statement::FractureSeal { .. } => Some("fracture seal"),
+        Statement::ShatterGrandSeal { .. } => Some("shatter grand_seal"),
+        _ => None,
+    }
+}
+
+/// `=` inside a Stance or until condition. The parser rejects these outright, so this
+/// runs on tokens to explain the mistake before the syntax error does.
+pub fn suspicious_assignments(tokens: &[Token]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if !matches!(token.kind, TokenKind::InStance | TokenKind::ShiftStance | TokenKind::Until) {
+            continue;
+        }
+        if tokens.get(i + 1).map(|t| &t.kind) != Some(&TokenKind::LeftParen) {
+            continue;
+        }
+
+        let mut depth = 0usize;
+        for inner in &tokens[i + 1..] {
+            match inner.kind {
+                TokenKind::LeftParen => depth += 1,
+                TokenKind::RightParen => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                TokenKind::LeftBrace | TokenKind::Eof => break,
+                TokenKind::Equals => {
+                    findings.push(Finding::new(
+                        SUSPICIOUS_ASSIGNMENT,
+                        inner.line,
+                        inner.column,
+                        "'=' in a condition assigns instead of comparing; use 'is~'".to_string(),
+                    ));
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+    findings
+}
@@ -0,0 +1,107 @@
+// `flowlang dev todos`: list declarations marked `@todo("...")`
+//
+// Works on tokens rather than the AST so half-finished files that don't parse yet
+// still show their todos.
+
+use colored::*;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::lexer::token::{Token, TokenKind};
+
+#[derive(Debug, PartialEq)]
+pub struct Todo {
+    pub line: usize,
+    /// The Spell, variable or sigil the marker sits on, if one follows it
+    pub name: Option<String>,
+    pub message: Option<String>,
+}
+
+pub fn find_todos(tokens: &[Token]) -> Vec<Todo> {
+    let mut todos = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Sigil("todo".to_string()) {
+            continue;
+        }
+
+        let mut rest = tokens[i + 1..].iter().peekable();
+        let mut message = None;
+        if rest.peek().is_some_and(|t| t.kind == TokenKind::LeftParen && t.line == token.line) {
+            rest.next();
+            if let Some(Token { kind: TokenKind::String(text), .. }) = rest.peek() {
+                message = Some(text.clone());
+            }
+            rest.find(|t| t.kind == TokenKind::RightParen);
+        }
+
+        // Other sigils, with their arguments, may sit between the marker and the declaration
+        while let Some(sigil) = rest.next_if(|t| matches!(t.kind, TokenKind::Sigil(_))) {
+            if rest.peek().is_some_and(|t| t.kind == TokenKind::LeftParen && t.line == sigil.line) {
+                rest.find(|t| t.kind == TokenKind::RightParen);
+            }
+        }
+        let name = match rest.next().map(|t| &t.kind) {
+            Some(TokenKind::CastSpell | TokenKind::Ritual | TokenKind::Let | TokenKind::Seal | TokenKind::SigilDef) => {
+                match rest.next().map(|t| &t.kind) {
+                    Some(TokenKind::Identifier(name)) => Some(name.clone()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        todos.push(Todo { line: token.line, name, message });
+    }
+    todos
+}
+
+/// Print every `@todo` under `paths` (./src or . by default)
+pub fn run(paths: Vec<PathBuf>) {
+    let files = super::source_files(paths);
+    let mut count = 0;
+    for file in &files {
+        let tokens = match fs::read_to_string(file).map(|s| crate::lexer::tokenize(&s.replace("\u{feff}", ""))) {
+            Ok(Ok(tokens)) => tokens,
+            Ok(Err(e)) => {
+                eprintln!("{} {}: {}", "⚠️".yellow(), file.display(), e.message());
+                continue;
+            }
+            Err(e) => {
+                eprintln!("{} {}: {}", "⚠️".yellow(), file.display(), e);
+                continue;
+            }
+        };
+
+        for todo in find_todos(&tokens) {
+            count += 1;
+            let location = format!("{:<32}", format!("{}:{}", file.display(), todo.line));
+            let name = format!("{:<20}", todo.name.unwrap_or_default());
+            println!("{} {} {}", location.cyan(), name.bright_white(), todo.message.unwrap_or_default());
+        }
+    }
+
+    if count == 0 {
+        println!("{} {}", "✅".green(), format!("No todos in {} file(s)", files.len()).bright_green());
+    } else {
+        println!();
+        println!("{} {}", "📝".yellow(), format!("{} todo(s) in {} file(s)", count, files.len()).yellow().bold());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_todos() {
+        let source = "@todo(\"handle paging\")\n@deprecated(\"use fetch\")\n@export\ncast Spell fetchAll() {\n    return []\n}\n@todo\nlet retries = 3\n";
+        let tokens = crate::lexer::tokenize(source).unwrap();
+        assert_eq!(
+            find_todos(&tokens),
+            vec![
+                Todo { line: 1, name: Some("fetchAll".to_string()), message: Some("handle paging".to_string()) },
+                Todo { line: 7, name: Some("retries".to_string()), message: None },
+            ]
+        );
+    }
+}
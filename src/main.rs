@@ -1,23 +1,21 @@
-mod lexer;
-mod parser;
-mod interpreter;
-mod error;
-mod types;
-mod stdlib;
-mod repl;
-mod config;
-mod cache;
-mod optimizer;
-mod runtime;
-mod package_manager;
-
-use clap::{Parser, Subcommand};
+use flowlang::{
+    bench_runner, cache, config, doc_generator, error, interpreter, lexer, linter, optimizer, package_manager, parser,
+    platform, repl, scaffold, test_runner, trace_viewer,
+};
+
+use flowlang::stdlib::flowrec;
+
+// Web handlers allocate from per-request arenas (see flowlang::arena)
+#[global_allocator]
+static ALLOCATOR: flowlang::arena::ArenaAlloc = flowlang::arena::ArenaAlloc;
+
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use colored::*;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
-#[command(name = "flowlang")]
+#[command(name = "flowlang", version)]
 #[command(about = "FlowLang - A mystical anime-themed scripting language", long_about = None)]
 struct Cli {
     #[command(subcommand)]
@@ -26,15 +24,35 @@ struct Cli {
     /// Enable verbose output for debugging
     #[arg(short, long, global = true)]
     verbose: bool,
+    
+    /// How errors are shown: "anime" (default) or "plain"
+    #[arg(long, global = true, value_name = "STYLE")]
+    error_style: Option<String>,
+    
+    /// Language for error messages (e.g. "en", "ja"; defaults to $LANG)
+    #[arg(long, global = true, value_name = "LOCALE")]
+    locale: Option<String>,
+    
+    /// Print nothing but the script's own output; errors become one line on stderr
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    
+    /// Never color output (also set by NO_COLOR; colors are off when stdout isn't a terminal)
+    #[arg(long, global = true)]
+    no_color: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Run a FlowLang script file
     Run {
-        /// Path to the .flow file (optional if config.flowlang.json exists)
+        /// Path to the .flow file, or - to read it from stdin (optional if config.flowlang.json exists)
         file: Option<PathBuf>,
         
+        /// Run the entry file of this workspace member instead
+        #[arg(short, long, value_name = "NAME", conflicts_with = "file")]
+        package: Option<String>,
+        
         /// Enable stack trace display on errors
         #[arg(long)]
         trace: bool,
@@ -47,20 +65,134 @@ enum Commands {
         #[arg(long)]
         trace_raw: bool,
         
+        /// Print call counts and timings per Spell when the script ends
+        #[arg(long)]
+        profile: bool,
+        
+        /// Record every request reaching web.serve to this .flowrec file
+        #[arg(long, value_name = "FILE")]
+        record: Option<PathBuf>,
+        
+        /// Record every statement, variable write and Spell call to a trace file
+        #[arg(long, value_name = "FILE")]
+        record_trace: Option<PathBuf>,
+        
+        /// Allow evaluate() and circleDynamic() to load code chosen at runtime
+        #[arg(long)]
+        allow_eval: bool,
+        
         /// Arguments to pass to the script
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
+    /// Re-run the script of a .flowrec recording, feeding its requests to web.serve
+    Replay {
+        /// Recording made with `flowlang run --record`
+        recording: PathBuf,
+
+        /// Script to run instead of the one named in the recording
+        #[arg(long)]
+        script: Option<PathBuf>,
+    },
+    /// Run FlowLang test files (*_test.flow)
+    Test {
+        /// Test files or directories to search (defaults to ./tests, and each member's tests in a workspace)
+        paths: Vec<PathBuf>,
+
+        /// Only test this workspace member
+        #[arg(short, long, value_name = "NAME", conflicts_with = "paths")]
+        package: Option<String>,
+
+        /// Rewrite snapshot files instead of comparing against them
+        #[arg(long)]
+        update_snapshots: bool,
+
+        /// Fail on missing snapshots instead of writing them (the default when CI is set)
+        #[arg(long, conflicts_with = "update_snapshots")]
+        ci: bool,
+
+        /// Allow evaluate() and circleDynamic() in the tests
+        #[arg(long)]
+        allow_eval: bool,
+    },
     /// Run the FlowLang REPL
     Repl,
     /// Developer commands for debugging
     #[command(subcommand)]
     Dev(DevCommands),
+    /// Generate API docs from `---` doc comments
+    Doc {
+        /// Files or directories to document (defaults to ./src)
+        paths: Vec<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = doc_generator::DocFormat::Markdown)]
+        format: doc_generator::DocFormat,
+
+        /// Directory to write the docs to
+        #[arg(short, long, default_value = "docs/api")]
+        out: PathBuf,
+
+        /// Also document declarations that are not exported
+        #[arg(long)]
+        private: bool,
+    },
+    /// Report every syntax error in FlowLang files without running them
+    Check {
+        /// Files or directories to check (defaults to ./src, and each member's src in a workspace)
+        paths: Vec<PathBuf>,
+
+        /// Only check this workspace member
+        #[arg(short, long, value_name = "NAME", conflicts_with = "paths")]
+        package: Option<String>,
+    },
+    /// Check FlowLang files for likely mistakes
+    Lint {
+        /// Files or directories to lint (defaults to ./src)
+        paths: Vec<PathBuf>,
+
+        /// List the available rules and their levels
+        #[arg(long)]
+        rules: bool,
+    },
+    /// Apply suggested fixes for common syntax mistakes
+    Fix {
+        /// File to fix
+        file: PathBuf,
+
+        /// Also apply fixes that may change meaning (e.g. '=' → 'is~' in conditions)
+        #[arg(long = "unsafe")]
+        allow_unsafe: bool,
+
+        /// Show the fixes without writing the file
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Explain an error code (e.g. `flowlang explain FL0004`)
+    Explain {
+        /// Error code such as FL0004
+        #[arg(required_unless_present = "list")]
+        code: Option<String>,
+
+        /// List every error code with a one-line summary
+        #[arg(long)]
+        list: bool,
+    },
+    /// Install the packages in config.flowlang.json at the commits recorded in flow.lock
+    Install {
+        /// Resolve every package ref again and rewrite flow.lock
+        #[arg(long)]
+        update: bool,
+    },
     /// Initialize a new FlowLang project
     Init {
         /// Name of the project (defaults to current directory name)
         #[arg(default_value = ".")]
         name: String,
+
+        /// Starter structure to generate (defaults to a hello-world script)
+        #[arg(long, value_enum)]
+        template: Option<scaffold::ProjectTemplate>,
     },
 }
 
@@ -70,43 +202,141 @@ enum DevCommands {
     Lex {
         /// Path to the .flow file
         file: PathBuf,
+
+        /// Output format: "text" (default) or "json" for external tools
+        #[arg(long, value_enum, default_value_t = DevOutput::Text, alias = "format")]
+        output: DevOutput,
     },
     /// Show parser AST for a file
     Parse {
         /// Path to the .flow file
         file: PathBuf,
+
+        /// Output format: "text" (default) or "json", the same document as `dev ast --format json`
+        #[arg(long, value_enum, default_value_t = DevOutput::Text, alias = "format")]
+        output: DevOutput,
     },
     /// Show detailed AST structure
     Ast {
         /// Path to the .flow file
         file: PathBuf,
+
+        /// Output format: "debug" (default) or "json" for external tools
+        #[arg(long, value_enum, default_value_t = AstFormat::Debug, alias = "output")]
+        format: AstFormat,
+    },
+    /// Show the AST after each optimizer pass and what it changed
+    Opt {
+        /// Path to the .flow file
+        file: PathBuf,
+
+        /// Only list the changes, without dumping the AST
+        #[arg(long)]
+        summary: bool,
+    },
+    /// List declarations marked @todo
+    Todos {
+        /// Files or directories to search (defaults to ./src)
+        paths: Vec<PathBuf>,
+    },
+    /// Time the lexer, parser and interpreter on benchmark programs
+    BenchSuite {
+        /// Benchmark programs or directories (defaults to ./benches)
+        paths: Vec<PathBuf>,
+
+        /// Timed runs per program; the median is reported
+        #[arg(short = 'n', long, default_value_t = 10)]
+        iterations: usize,
+
+        /// Save the results as a baseline file
+        #[arg(long)]
+        save: Option<PathBuf>,
+
+        /// Compare against a saved baseline and fail on regressions
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+
+        /// Slowdown in percent that counts as a regression
+        #[arg(long, default_value_t = 20.0)]
+        threshold: f64,
+    },
+    /// Step through a trace written by `run --record-trace`
+    TraceView {
+        /// Trace file
+        file: PathBuf,
+
+        /// Print the state at this event and exit
+        #[arg(long, value_name = "EVENT")]
+        at: Option<usize>,
+
+        /// Print every event and exit
+        #[arg(long, conflicts_with = "at")]
+        list: bool,
     },
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum AstFormat {
+    Debug,
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DevOutput {
+    Text,
+    Json,
+}
+
+/// Version of the `dev ast --format json` document. Bump when the AST shape changes
+/// in a way that breaks existing consumers.
+const AST_JSON_VERSION: u32 = 3;
+
+/// Version of the `dev lex --output json` document, bumped the same way
+const TOKENS_JSON_VERSION: u32 = 1;
+
 #[tokio::main]
 async fn main() {
-    let cli = Cli::parse();
+    let cli = Cli::parse_from(script_mode_args());
+    // Panics in native Spells are reported as Panic errors, not on stderr as well
+    flowlang::types::install_panic_hook();
+    platform::init_terminal(cli.no_color);
+    platform::set_quiet(cli.quiet);
+    #[cfg(feature = "tracing")]
+    init_tracing();
+    if let Err(e) = error::catalog::configure(cli.error_style.as_deref(), cli.locale.as_deref()) {
+        eprintln!("{} {}", "❌".red(), e.red().bold());
+        std::process::exit(2);
+    }
     let verbose = cli.verbose;
     
     match cli.command {
-        Some(Commands::Run { file, trace, trace_depth, trace_raw, args }) => {
-            let (file_path, project_config) = match file {
-                Some(path) => {
-                    // Try to load config if it exists in current dir, otherwise default
-                    let config_path = PathBuf::from("config.flowlang.json");
-                    let config = if config_path.exists() {
-                        config::ProjectConfig::load(&config_path).unwrap_or_default()
-                    } else {
-                        config::ProjectConfig::default()
-                    };
-                    (path, config)
-                },
-                None => {
+        Some(Commands::Run { file, package, trace, trace_depth, trace_raw, profile, record, record_trace, allow_eval, args }) => {
+            let (file_path, mut project_config) = match (file, package) {
+                (Some(path), _) => (path, load_config(Path::new("."))),
+                (None, Some(name)) => {
+                    let dir = member_dir(&name);
+                    let config = load_config(&dir);
+                    (dir.join(&config.entry), config)
+                }
+                (None, None) => {
                     // Look for config file
                     let config_path = PathBuf::from("config.flowlang.json");
                     if config_path.exists() {
-                        match config::ProjectConfig::load(&config_path) {
-                            Ok(config) => (PathBuf::from(config.entry.clone()), config),
+                        match config::ProjectConfig::for_dir(Path::new(".")) {
+                            // A workspace root usually has no entry of its own
+                            Ok(config) if !config.members.is_empty() && !Path::new(&config.entry).exists() => {
+                                eprintln!("{}", "❌ This is a workspace; choose a member to run.".red().bold());
+                                eprintln!("   Usage: flowlang run --package <name>");
+                                eprintln!("   Members: {}", member_names(&config));
+                                std::process::exit(2);
+                            }
+                            Ok(config) => {
+                                if let Err(e) = config.check_flowlang("This project") {
+                                    error::print_error(&e);
+                                    std::process::exit(1);
+                                }
+                                (PathBuf::from(config.entry.clone()), config)
+                            }
                             Err(e) => {
                                 error::print_error(&e);
                                 return;
@@ -121,38 +351,212 @@ async fn main() {
                 }
             };
             
-            // Set script arguments in environment for cli.args() to access
-            std::env::set_var("FLOWLANG_SCRIPT_ARGS", args.join("\x1F")); // Use unit separator
+            if allow_eval {
+                project_config.allow_eval = true;
+            }
+            
+            // Make script arguments available to cli.args()
+            platform::set_script_args(args);
             
-            run_file(file_path, project_config, verbose, trace, trace_depth, trace_raw).await;
+            if let Some(record) = record {
+                if let Err(e) = flowrec::start_recording(&record, &file_path) {
+                    error::print_error(&e);
+                    std::process::exit(1);
+                }
+            }
+            
+            let trace_options = error::TraceOptions {
+                enabled: trace,
+                max_depth: trace_depth,
+                raw_mode: trace_raw,
+                compact: error::get_terminal_width() < 60,
+            };
+            let recorder = match &record_trace {
+                Some(trace_path) => match interpreter::time_travel::TraceRecorder::create(trace_path, &file_path) {
+                    Ok(recorder) => Some(std::sync::Arc::new(recorder)),
+                    Err(e) => {
+                        error::print_error(&e);
+                        std::process::exit(1);
+                    }
+                },
+                None => None,
+            };
+            let hooks = recorder.iter().map(|r| r.clone() as std::sync::Arc<dyn interpreter::instrument::Instrumentation>).collect();
+            
+            let exit_code = run_file(file_path, project_config, verbose, trace_options, profile, hooks).await;
+            
+            if let (Some(recorder), Some(trace_path)) = (recorder, record_trace) {
+                match recorder.finish() {
+                    Ok(_) if platform::is_quiet() => {}
+                    Ok(events) => eprintln!(
+                        "{} {} events to {} (view with `flowlang dev trace-view {}`)",
+                        "📼 Trace saved:".bright_cyan(),
+                        events,
+                        trace_path.display(),
+                        trace_path.display()
+                    ),
+                    Err(e) => error::print_error(&e),
+                }
+            }
+            if exit_code != 0 {
+                std::process::exit(exit_code);
+            }
+        }
+        Some(Commands::Replay { recording, script }) => {
+            let loaded = match flowrec::load(&recording) {
+                Ok(loaded) => loaded,
+                Err(e) => {
+                    error::print_error(&e);
+                    std::process::exit(1);
+                }
+            };
+            let config = load_config(Path::new("."));
+            
+            let file_path = script.unwrap_or(loaded.script);
+            flowrec::start_replay(loaded.requests);
+            let trace_options = error::TraceOptions {
+                enabled: false,
+                max_depth: 50,
+                raw_mode: false,
+                compact: error::get_terminal_width() < 60,
+            };
+            let exit_code = run_file(file_path, config, verbose, trace_options, false, Vec::new()).await;
+            if exit_code != 0 {
+                std::process::exit(exit_code);
+            }
+        }
+        Some(Commands::Test { paths, package, update_snapshots, ci, allow_eval }) => {
+            let (paths, mut config) = match package {
+                Some(name) => {
+                    let dir = member_dir(&name);
+                    (vec![dir.join("tests")], load_config(&dir))
+                }
+                None => (paths, load_config(Path::new("."))),
+            };
+            if allow_eval {
+                config.allow_eval = true;
+            }
+
+            let snapshots = test_runner::snapshot_mode(update_snapshots, ci);
+            if !test_runner::run(paths, snapshots, config, verbose).await {
+                std::process::exit(1);
+            }
         }
         Some(Commands::Repl) => {
             repl::run().await;
         }
         Some(Commands::Dev(dev_cmd)) => {
             match dev_cmd {
-                DevCommands::Lex { file } => {
+                DevCommands::Lex { file, output: DevOutput::Text } => {
                     dev_lex(file).await;
                 }
-                DevCommands::Parse { file } => {
+                DevCommands::Lex { file, output: DevOutput::Json } => {
+                    if !dev_lex_json(file) {
+                        std::process::exit(1);
+                    }
+                }
+                DevCommands::Parse { file, output: DevOutput::Text } => {
                     dev_parse(file).await;
                 }
-                DevCommands::Ast { file } => {
+                DevCommands::Parse { file, output: DevOutput::Json } => {
+                    if !dev_ast_json(file) {
+                        std::process::exit(1);
+                    }
+                }
+                DevCommands::Ast { file, format: AstFormat::Debug } => {
                     dev_ast(file).await;
                 }
+                DevCommands::Ast { file, format: AstFormat::Json } => {
+                    if !dev_ast_json(file) {
+                        std::process::exit(1);
+                    }
+                }
+                DevCommands::Opt { file, summary } => {
+                    dev_opt(file, summary).await;
+                }
+                DevCommands::TraceView { file, at, list } => {
+                    if !trace_viewer::run(file, at, list) {
+                        std::process::exit(1);
+                    }
+                }
+                DevCommands::Todos { paths } => {
+                    linter::list_todos(paths);
+                }
+                DevCommands::BenchSuite { paths, iterations, save, baseline, threshold } => {
+                    let options = bench_runner::BenchOptions { iterations, save, baseline, threshold };
+                    if !bench_runner::run(paths, options).await {
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+        Some(Commands::Doc { paths, format, out, private }) => {
+            if !doc_generator::run(paths, format, &out, private) {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Check { paths, package }) => {
+            let paths = match package {
+                Some(name) => vec![member_dir(&name)],
+                None => paths,
+            };
+            if !linter::check(paths) {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Lint { paths, rules }) => {
+            let config = load_config(Path::new("."));
+
+            if rules {
+                linter::print_rules(&config.lint);
+            } else if !linter::run(paths, &config.lint) {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Fix { file, allow_unsafe, dry_run }) => {
+            if !run_fix(&file, allow_unsafe, dry_run) {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Explain { code, list }) => {
+            match code {
+                Some(code) if !list => {
+                    if !error::explain::print_explanation(&code) {
+                        std::process::exit(1);
+                    }
+                }
+                _ => error::explain::print_code_list(),
+            }
+        }
+        Some(Commands::Install { update }) => {
+            let config = load_config(Path::new("."));
+            let manager = package_manager::PackageManager::new(config::project_root().to_path_buf());
+            match manager.install_all(&config, update) {
+                Ok(installed) if !platform::is_quiet() => {
+                    println!("{}", format!("✅ {} package(s) installed, locked in {}", installed.len(), package_manager::LOCK_FILE).green());
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    error::print_error(&e);
+                    std::process::exit(1);
+                }
             }
         }
-        Some(Commands::Init { name }) => {
-            run_init(name).await;
+        Some(Commands::Init { name, template }) => {
+            run_init(name, template).await;
         }
+        None if platform::is_quiet() => {}
         None => {
-            print_banner();
+            // The box art is only for people; pipes get the hint alone
+            if std::io::IsTerminal::is_terminal(&std::io::stdout()) {
+                print_banner();
+            }
             println!("{}", "Use --help to see available commands".yellow());
         }
     }
 }
 
-async fn run_init(name: String) {
+async fn run_init(name: String, template: Option<scaffold::ProjectTemplate>) {
     use std::path::Path;
     
     let (project_name, project_path) = if name == "." {
@@ -176,18 +580,11 @@ async fn run_init(name: String) {
         println!("{} {}", "📂 Created directory:".green(), project_path.display());
     }
     
-    // Create src directory
-    let src_path = project_path.join("src");
-    if !src_path.exists() {
-        if let Err(e) = fs::create_dir(&src_path) {
-            eprintln!("{} {}", "❌ Failed to create src directory:".red().bold(), e);
-            return;
-        }
-        println!("{} {}", "📂 Created directory:".green(), src_path.display());
-    }
+    let scaffold = scaffold::scaffold(template, &project_name);
     
     // Create config file
-    let config = config::ProjectConfig::new(&project_name);
+    let mut config = config::ProjectConfig::new(&project_name);
+    config.entry = scaffold.entry.to_string();
     let config_path = project_path.join("config.flowlang.json");
     
     if !config_path.exists() {
@@ -200,25 +597,27 @@ async fn run_init(name: String) {
         println!("{} {}", "⚠️  Config file already exists:".yellow(), config_path.display());
     }
     
-    // Create main.flow
-    let main_flow_path = src_path.join("main.flow");
-    if !main_flow_path.exists() {
-        let main_content = r#"-- Welcome to FlowLang!
--- This is your entry point.
-
-shout("✨ The Flow has begun!")
-
-cast Spell greet(name) {
-    return "Hello, " + name + "!"
-}
-
-shout(greet("World"))
-"#;
-        if let Err(e) = fs::write(&main_flow_path, main_content) {
-            eprintln!("{} {}", "❌ Failed to create main.flow:".red().bold(), e);
+    // Create the template's source files, keeping any that already exist
+    for (relative, content) in &scaffold.files {
+        let file_path = project_path.join(relative);
+        if file_path.exists() {
+            println!("{} {}", "⚠️  File already exists:".yellow(), file_path.display());
+            continue;
+        }
+        if let Some(parent) = file_path.parent() {
+            if !parent.exists() {
+                if let Err(e) = fs::create_dir_all(parent) {
+                    eprintln!("{} {}", "❌ Failed to create directory:".red().bold(), e);
+                    return;
+                }
+                println!("{} {}", "📂 Created directory:".green(), parent.display());
+            }
+        }
+        if let Err(e) = fs::write(&file_path, content) {
+            eprintln!("{} {} {}", "❌ Failed to create".red().bold(), relative, e);
             return;
         }
-        println!("{} {}", "📜 Created file:".green(), main_flow_path.display());
+        println!("{} {}", "📜 Created file:".green(), file_path.display());
     }
     
     // Create .gitignore
@@ -235,7 +634,69 @@ shout(greet("World"))
     println!();
     println!("{}", "🎉 Project initialized successfully!".bright_green().bold());
     println!("   cd {}", project_path.display());
-    println!("   flowlang run src/main.flow");
+    for step in &scaffold.next_steps {
+        println!("   {}", step);
+    }
+}
+
+/// Send spans to stderr when FLOWLANG_TRACING is set to a level ("info" shows Spell
+/// calls, "trace" also shows statements). APM exporters can replace this subscriber.
+#[cfg(feature = "tracing")]
+fn init_tracing() {
+    let level = match std::env::var("FLOWLANG_TRACING") {
+        Ok(level) => level,
+        Err(_) => return,
+    };
+    let level = level.parse().unwrap_or(tracing::Level::INFO);
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .init();
+}
+
+/// config.flowlang.json in `dir` (the defaults if it is missing or unreadable), joined
+/// with the workspace around it. A broken workspace ends the command instead.
+fn load_config(dir: &Path) -> config::ProjectConfig {
+    let config_path = dir.join("config.flowlang.json");
+    let mut config = if config_path.exists() {
+        config::ProjectConfig::load(&config_path).unwrap_or_default()
+    } else {
+        config::ProjectConfig::default()
+    };
+    let joined = config::Workspace::discover(dir).map(|workspace| {
+        if let Some(workspace) = workspace {
+            workspace.apply(&mut config);
+        }
+    });
+    if let Err(e) = joined.and_then(|_| config.check_flowlang("This project")) {
+        error::print_error(&e);
+        std::process::exit(1);
+    }
+    config
+}
+
+/// The directory of workspace member `name`, or exit naming the members there are
+fn member_dir(name: &str) -> PathBuf {
+    let config = load_config(Path::new("."));
+    match config.workspace_members.get(name) {
+        Some(dir) => dir.clone(),
+        None if config.workspace_members.is_empty() => {
+            eprintln!("{}", "❌ --package needs a workspace, and none contains this directory.".red().bold());
+            std::process::exit(2);
+        }
+        None => {
+            eprintln!("{} {}", "❌ No workspace member is named".red().bold(), name.red().bold());
+            eprintln!("   Members: {}", member_names(&config));
+            std::process::exit(2);
+        }
+    }
+}
+
+fn member_names(config: &config::ProjectConfig) -> String {
+    let mut names: Vec<&str> = config.workspace_members.keys().map(String::as_str).collect();
+    names.sort();
+    names.join(", ")
 }
 
 fn print_banner() {
@@ -246,18 +707,33 @@ fn print_banner() {
     println!();
 }
 
-async fn run_file(path: PathBuf, config: config::ProjectConfig, verbose: bool, trace: bool, trace_depth: usize, trace_raw: bool) {
+/// `flowlang script.flow args...` means `flowlang run script.flow args...`, which is
+/// what a `#!/usr/bin/env flowlang` line runs
+fn script_mode_args() -> Vec<std::ffi::OsString> {
+    let mut args: Vec<_> = std::env::args_os().collect();
+    let is_script = args.get(1).is_some_and(|first| {
+        !first.to_string_lossy().starts_with('-')
+            && Cli::command().find_subcommand(first).is_none()
+            && std::path::Path::new(first).is_file()
+    });
+    if is_script {
+        args.insert(1, "run".into());
+    }
+    args
+}
+
+async fn run_file(
+    path: PathBuf,
+    config: config::ProjectConfig,
+    verbose: bool,
+    trace_options: error::TraceOptions,
+    profile: bool,
+    hooks: Vec<std::sync::Arc<dyn interpreter::instrument::Instrumentation>>,
+) -> i32 {
     use std::time::Instant;
     
     let start_time = Instant::now();
-    
-    // Create trace options
-    let trace_options = error::TraceOptions {
-        enabled: trace,
-        max_depth: trace_depth,
-        raw_mode: trace_raw,
-        compact: error::get_terminal_width() < 60,
-    };
+    let trace = trace_options.enabled;
     
     if verbose {
         println!("{}", "═══ VERBOSE MODE ═══".bright_yellow().bold());
@@ -267,8 +743,13 @@ async fn run_file(path: PathBuf, config: config::ProjectConfig, verbose: bool, t
         }
     }
     
+    // `flowlang run -` reads the script from stdin
+    let from_stdin = path.as_os_str() == "-";
+    let filename = if from_stdin { Some("<stdin>") } else { path.file_name().and_then(|n| n.to_str()) };
+    
     // Read the source file
-    let source = match fs::read_to_string(&path) {
+    let read = if from_stdin { std::io::read_to_string(std::io::stdin()) } else { fs::read_to_string(&path) };
+    let source = match read {
         Ok(content) => {
             // Strip BOM if present
             let content = content.replace("\u{feff}", "");
@@ -280,7 +761,7 @@ async fn run_file(path: PathBuf, config: config::ProjectConfig, verbose: bool, t
         }
         Err(e) => {
             eprintln!("{} {}", "❌ Failed to read file:".red().bold(), e);
-            return;
+            return 1;
         }
     };
     
@@ -288,7 +769,8 @@ async fn run_file(path: PathBuf, config: config::ProjectConfig, verbose: bool, t
     let cache_manager = cache::CacheManager::new();
     let mut ast = None;
     
-    if let Some(cached_ast) = cache_manager.load(&path, &source) {
+    // Piped source has no file to key the cache on
+    if let Some(cached_ast) = (!from_stdin).then(|| cache_manager.load(&path, &source)).flatten() {
         if verbose {
             println!("{}", "⚡ AST loaded from cache!".bright_green());
         }
@@ -316,8 +798,8 @@ async fn run_file(path: PathBuf, config: config::ProjectConfig, verbose: bool, t
                 tokens
             }
             Err(e) => {
-                error::print_error_with_episode(&e, trace, &trace_options, path.file_name().and_then(|n| n.to_str()));
-                return;
+                error::print_error_with_episode(&e, trace, &trace_options, filename);
+                return e.exit_code();
             }
         };
         
@@ -341,7 +823,9 @@ async fn run_file(path: PathBuf, config: config::ProjectConfig, verbose: bool, t
                 }
                 
                 // Save to cache
-                if let Err(e) = cache_manager.save(&path, &source, &parsed_ast) {
+                if from_stdin {
+                    // Piped source is parsed fresh every time
+                } else if let Err(e) = cache_manager.save(&path, &source, &parsed_ast) {
                     if verbose {
                         eprintln!("{} {}", "⚠️ Failed to save AST cache:".yellow(), e);
                     }
@@ -352,8 +836,8 @@ async fn run_file(path: PathBuf, config: config::ProjectConfig, verbose: bool, t
                 ast = Some(parsed_ast);
             }
             Err(e) => {
-                error::print_error_with_episode(&e, trace, &trace_options, path.file_name().and_then(|n| n.to_str()));
-                return;
+                error::print_error_with_episode(&e, trace, &trace_options, filename);
+                return e.exit_code();
             }
         }
     }
@@ -385,13 +869,31 @@ async fn run_file(path: PathBuf, config: config::ProjectConfig, verbose: bool, t
     let exec_start = Instant::now();
     
     // Interpretation
-    let script_dir = path.parent().unwrap_or_else(|| std::path::Path::new(".")).to_path_buf();
+    let script_dir = match path.parent() {
+        Some(dir) if !from_stdin && !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
     let mut interpreter = interpreter::Interpreter::with_dir(script_dir, config);
+    let profiler = profile.then(|| std::sync::Arc::new(interpreter::instrument::SpellProfiler::default()));
+    if let Some(profiler) = &profiler {
+        interpreter.add_instrumentation(profiler.clone());
+    }
+    if verbose {
+        interpreter.add_instrumentation(std::sync::Arc::new(interpreter::instrument::DeprecationWarnings::default()));
+    }
+    for hook in hooks {
+        interpreter.add_instrumentation(hook);
+    }
     
     if let Err(e) = interpreter.execute(ast).await {
-        let filename = path.file_name().and_then(|n| n.to_str());
         error::print_error_with_episode(&e, trace, &trace_options, filename);
-        return;
+        if let Err(handler_error) = interpreter.report_uncaught(&e).await {
+            eprintln!("{} {}", "⚠️ os.onUncaught handler failed:".yellow(), handler_error);
+        }
+        if let Some(profiler) = &profiler {
+            profiler.print_report();
+        }
+        return e.exit_code();
     }
     
     let exec_time = exec_start.elapsed();
@@ -447,74 +949,39 @@ if handle_count > 0 {
             break;
         }
         
-        // Check handle count
+        // Check handle count. Handles queue their last callback before they
+        // unregister (e.g. a firing timeout), so drain the queue before leaving
         let count = runtime.active_handle_count().await;
-        if count == 0 {
-            if verbose {
-                println!("{}", "✨ All handles closed".bright_green());
-            }
-            break;
-        }
         
         // Process pending timer callbacks (fire-and-forget)
+        let mut ran_callbacks = false;
         while let Some(request) = runtime.run_event_loop_tick().await {
-            if let Err(e) = interpreter.execute_function(request.callback, request.args).await {
+            ran_callbacks = true;
+            let result = flowlang::types::catch_panic(
+                "<callback>",
+                interpreter.execute_function(request.callback, request.args),
+            ).await;
+            runtime.metrics().callback_ran(result.is_err());
+            if let Err(e) = result {
                 eprintln!("{} {}", "⚠️ Callback error:".yellow(), e);
             }
         }
         
-        // Process web callbacks concurrently
-        let semaphore = runtime.web_handler_semaphore();
-        
-        // Loop until there are no more requests OR we hit a reasonable batch size per tick
-        // But since we are spawning tasks, we can process as many as the channel gives us
-        // limited by the semaphore if we want to throttle spawning (or the tasks throttle themselves)
-        let mut loop_batch = 0;
-        
-        while loop_batch < batch_size {
-            // Check if we have permit for new handler
-            // If semaphore is closed/zero, we skip this tick to handle other events
-            if semaphore.available_permits() > 0 {
-                match runtime.get_web_callback().await {
-                    Some(web_request) => {
-                        // Clone interpreter for this task
-                        // This uses our new Arc<Mutex> shared state for modules
-                        let mut task_interpreter = interpreter.clone();
-                        let permit = semaphore.clone().acquire_owned().await.unwrap();
-                        
-                        tokio::spawn(async move {
-                            // The permit is held for the duration of this block
-                            let _permit = permit;
-                            
-                            // Execute the handler
-                            let result = match task_interpreter.execute_function(
-                                web_request.callback, 
-                                web_request.args
-                            ).await {
-                                Ok(value) => value,
-                                Err(e) => {
-                                    eprintln!("{} {}", "⚠️ Web handler error:".yellow(), e);
-                                    crate::types::Value::String(std::sync::Arc::new(format!("Error: {}", e)))
-                                }
-                            };
-                            
-                            // Send response back
-                            let _ = web_request.response_tx.send(result);
-                        });
-                        
-                        total_requests += 1;
-                        loop_batch += 1;
-                    }
-                    None => {
-                        break;
-                    }
-                }
-            } else {
-                // Max concurrent handlers reached, wait for next tick
-                break;
+        if count == 0 {
+            // Callbacks may have registered new handles
+            if ran_callbacks {
+                continue;
             }
+            if verbose {
+                println!("{}", "✨ All handles closed".bright_green());
+            }
+            break;
         }
         
+        // Process web callbacks concurrently
+        let loop_batch = interpreter::handlers::dispatch_web_requests(&interpreter, batch_size).await;
+        total_requests += loop_batch as u64;
+        
         // Brief sleep only if we didn't process a full batch
         if loop_batch == 0 {
             tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
@@ -537,6 +1004,10 @@ if handle_count > 0 {
 
 let total_time = start_time.elapsed();
 
+if let Some(profiler) = &profiler {
+    profiler.print_report();
+}
+
 if verbose {
     println!("{}", "─".repeat(50).dimmed());
     println!("{}", "✅ Execution completed successfully".bright_green().bold());
@@ -544,6 +1015,7 @@ if verbose {
     println!("   Execution: {:.2}ms", exec_time.as_secs_f64() * 1000.0);
     println!("   Total:     {:.2}ms", total_time.as_secs_f64() * 1000.0);
 }
+0
 }
 
 async fn dev_lex(path: PathBuf) {
@@ -643,3 +1115,169 @@ async fn dev_ast(path: PathBuf) {
         }
     }
 }
+
+/// `flowlang fix`: apply fix-its until the file parses. Returns false if errors remain.
+fn run_fix(path: &PathBuf, allow_unsafe: bool, dry_run: bool) -> bool {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("{} {}", "❌ Failed to read file:".red().bold(), e);
+            return false;
+        }
+    };
+
+    let result = error::fixit::fix_source(&source, allow_unsafe);
+    for fix in &result.applied {
+        let line = fix.edits.first().map(|edit| edit.line).unwrap_or(0);
+        println!("  {} {}:{} {}", "🔧".green(), path.display(), line, fix.message);
+    }
+
+    if result.applied.is_empty() {
+        if result.remaining.is_none() {
+            println!("{}", "✨ Nothing to fix".bright_green().bold());
+        }
+    } else if dry_run {
+        println!("{}", format!("{} fix(es) available (dry run, file not written)", result.applied.len()).yellow().bold());
+    } else if let Err(e) = fs::write(path, &result.source) {
+        eprintln!("{} {}", "❌ Failed to write file:".red().bold(), e);
+        return false;
+    } else {
+        println!("{}", format!("✅ Applied {} fix(es) to {}", result.applied.len(), path.display()).bright_green().bold());
+    }
+
+    match result.remaining {
+        Some(e) => {
+            let (line, column) = e.position();
+            println!("{}: {}", "error[syntax]".red().bold(), e.message());
+            println!("  {} {}:{}:{}", "-->".cyan(), path.display(), line, column);
+            if e.fixes().iter().any(|fix| !fix.safe) {
+                println!("  {} {}", "=".cyan(), "an unsafe fix is available; rerun with --unsafe to apply it".dimmed());
+            }
+            false
+        }
+        None => true,
+    }
+}
+
+fn dev_ast_json(path: PathBuf) -> bool {
+    print_dev_json(&path, AST_JSON_VERSION, "program", |source| {
+        let program = parser::parse(lexer::tokenize(source)?)?;
+        Ok(serde_json::json!(program))
+    })
+}
+
+fn dev_lex_json(path: PathBuf) -> bool {
+    print_dev_json(&path, TOKENS_JSON_VERSION, "tokens", |source| Ok(serde_json::json!(lexer::tokenize(source)?)))
+}
+
+// Machine-readable output for linters, code generators and doc tools. Everything goes
+// to stdout as a single JSON document, with the result under `key`; failures become
+// {"error": ...} so consumers only ever have to parse one shape of output.
+fn print_dev_json(
+    path: &Path,
+    version: u32,
+    key: &str,
+    build: impl FnOnce(&str) -> Result<serde_json::Value, error::FlowError>,
+) -> bool {
+    let file = path.display().to_string();
+    let result = fs::read_to_string(path)
+        .map_err(|e| error::FlowError::rift(&format!("Failed to read file: {}", e), 0, 0))
+        .and_then(|source| build(&source));
+
+    let mut document = serde_json::json!({
+        "version": version,
+        "file": file,
+    });
+    let ok = match result {
+        Ok(value) => {
+            document[key] = value;
+            true
+        }
+        Err(e) => {
+            let (line, column) = e.position();
+            document["error"] = serde_json::json!({
+                "code": e.code(),
+                "kind": e.error_type_name(),
+                "message": e.message(),
+                "line": line,
+                "column": column,
+            });
+            false
+        }
+    };
+
+    match serde_json::to_string_pretty(&document) {
+        // A reader that stops early (`| head`) is not an error worth a panic
+        Ok(text) => {
+            let _ = std::io::Write::write_all(&mut std::io::stdout(), format!("{}\n", text).as_bytes());
+        }
+        Err(e) => {
+            eprintln!("{} {}", "❌ Failed to serialize output:".red().bold(), e);
+            return false;
+        }
+    }
+    ok
+}
+
+async fn dev_opt(path: PathBuf, summary: bool) {
+    println!("{}", "🔧 OPTIMIZER PASSES".bright_yellow().bold());
+    println!("{}", "═".repeat(60).yellow());
+    
+    let source = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("{} {}", "❌ Failed to read file:".red().bold(), e);
+            return;
+        }
+    };
+    
+    let tokens = match lexer::tokenize(&source) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            error::print_error(&e);
+            return;
+        }
+    };
+    
+    let ast = match parser::parse(tokens) {
+        Ok(ast) => ast,
+        Err(e) => {
+            error::print_error(&e);
+            return;
+        }
+    };
+    
+    // Passes are compared by their debug dump, which is exactly what gets printed
+    let mut previous = format!("{:#?}", ast);
+    let reports = optimizer::Optimizer::new().optimize_with_report(ast);
+    
+    for (index, report) in reports.iter().enumerate() {
+        println!();
+        println!("{}", format!("Pass {}: {}", index + 1, report.name).bright_cyan().bold());
+        println!("{}", "─".repeat(60).dimmed());
+        
+        if report.changes.is_empty() {
+            println!("{}", "  no changes".dimmed());
+        } else {
+            for change in &report.changes {
+                println!("  {} {}", "•".green(), change);
+            }
+        }
+        
+        let dump = format!("{:#?}", report.program);
+        if !summary && dump != previous {
+            println!();
+            println!("{}", dump);
+        }
+        previous = dump;
+    }
+    
+    let total: usize = reports.iter().map(|r| r.changes.len()).sum();
+    println!();
+    println!("{}", "═".repeat(60).yellow());
+    for report in &reports {
+        println!("  {:<20} {}", report.name, report.changes.len());
+    }
+    println!("  {:<20} {}", "total", total.to_string().bold());
+    println!("{}", "Inline caching runs at execution time and is not shown here.".dimmed());
+}
@@ -1,12 +1,27 @@
 use crate::parser::ast::*;
+use std::cell::RefCell;
 use std::sync::Arc;
 
 /// Constant folding optimizer - evaluates constant expressions at compile time
-pub struct ConstantFolder;
+pub struct ConstantFolder {
+    /// Every fold performed, as "2 + 3 → 5", for `flowlang dev opt`
+    folded: RefCell<Vec<String>>,
+}
+
+impl Default for ConstantFolder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl ConstantFolder {
     pub fn new() -> Self {
-        ConstantFolder
+        ConstantFolder { folded: RefCell::new(Vec::new()) }
+    }
+
+    /// Folds performed so far, in the order they happened
+    pub fn take_folded(&self) -> Vec<String> {
+        self.folded.take()
     }
 
     pub fn fold(&self, program: Program) -> Program {
@@ -103,9 +118,10 @@ impl ConstantFolder {
                             condition: self.fold_expression(condition),
                         }
                     }
-                    PhaseKind::ForEach { variable, collection } => {
+                    PhaseKind::ForEach { variable, value_variable, collection } => {
                         PhaseKind::ForEach {
                             variable,
+                            value_variable,
                             collection: self.fold_expression(collection),
                         }
                     }
@@ -135,6 +151,7 @@ impl ConstantFolder {
                             error_type: clause.error_type,
                             binding: clause.binding,
                             retry_count: clause.retry_count,
+                            backoff_ms: clause.backoff_ms,
                             body: clause.body.into_iter().map(|s| self.fold_statement(s)).collect(),
                         }
                     }).collect(),
@@ -142,9 +159,11 @@ impl ConstantFolder {
                     line,
                 }
             }
-            Statement::Ward { body, line } => {
+            Statement::Ward { body, binding, on_absorb, line } => {
                 Statement::Ward {
                     body: body.into_iter().map(|s| self.fold_statement(s)).collect(),
+                    binding,
+                    on_absorb: on_absorb.map(|block| block.into_iter().map(|s| self.fold_statement(s)).collect()),
                     line,
                 }
             }
@@ -162,6 +181,7 @@ impl ConstantFolder {
 
                 // Try to evaluate if both sides are constants
                 if let Some(result) = self.try_fold_binary(&left, operator, &right) {
+                    self.record(format!("{} {} {}", literal(&left), operator.symbol(), literal(&right)), &result);
                     return result;
                 }
 
@@ -177,6 +197,11 @@ impl ConstantFolder {
                 let operand = self.fold_expression(*operand);
 
                 if let Some(result) = self.try_fold_unary(operator, &operand) {
+                    let before = match operator {
+                        UnaryOp::Negate => format!("{} {}", operator.symbol(), literal(&operand)),
+                        UnaryOp::Minus => format!("{}({})", operator.symbol(), literal(&operand)),
+                    };
+                    self.record(before, &result);
                     return result;
                 }
 
@@ -209,6 +234,15 @@ impl ConstantFolder {
                 }
             }
 
+            Expression::Attempt { expr, binding, fallback, line } => {
+                Expression::Attempt {
+                    expr: Box::new(self.fold_expression(*expr)),
+                    binding,
+                    fallback: Box::new(self.fold_expression(*fallback)),
+                    line,
+                }
+            }
+
             // Function calls
             Expression::Call { callee, arguments } => {
                 Expression::Call {
@@ -218,19 +252,21 @@ impl ConstantFolder {
             }
 
             // Method calls
-            Expression::MethodCall { object, method, arguments } => {
+            Expression::MethodCall { object, method, arguments, cache } => {
                 Expression::MethodCall {
                     object: Box::new(self.fold_expression(*object)),
                     method,
                     arguments: arguments.into_iter().map(|a| self.fold_expression(a)).collect(),
+                    cache,
                 }
             }
 
             // Index access
-            Expression::Index { object, index } => {
+            Expression::Index { object, index, cache } => {
                 Expression::Index {
                     object: Box::new(self.fold_expression(*object)),
                     index: Box::new(self.fold_expression(*index)),
+                    cache,
                 }
             }
 
@@ -258,6 +294,10 @@ impl ConstantFolder {
         }
     }
 
+    fn record(&self, before: String, after: &Expression) {
+        self.folded.borrow_mut().push(format!("{} → {}", before, literal(after)));
+    }
+
     /// Try to fold a binary operation if both operands are constants
     fn try_fold_binary(&self, left: &Expression, op: BinaryOp, right: &Expression) -> Option<Expression> {
         use BinaryOp::*;
@@ -281,14 +321,27 @@ impl ConstantFolder {
                         }
                         a % b
                     }
+                    Power => a.powf(*b),
+                    FloorDivide => {
+                        if *b == 0.0 {
+                            return None;
+                        }
+                        (a / b).floor()
+                    }
                     Greater => return Some(Expression::Boolean(a > b)),
                     Less => return Some(Expression::Boolean(a < b)),
                     GreaterEq => return Some(Expression::Boolean(a >= b)),
                     LessEq => return Some(Expression::Boolean(a <= b)),
-                    IsEqual => return Some(Expression::Boolean((a - b).abs() < f64::EPSILON)),
-                    NotEqual => return Some(Expression::Boolean((a - b).abs() >= f64::EPSILON)),
+                    // Exact, like `is~` at runtime; an epsilon would call 1e-20 and 2e-20 equal
+                    IsEqual => return Some(Expression::Boolean(a == b)),
+                    NotEqual => return Some(Expression::Boolean(a != b)),
                     _ => return None,
                 };
+                // `1e308 * 10` overflows; leave it to runtime rather than bake in a literal
+                // no source could spell
+                if !result.is_finite() {
+                    return None;
+                }
                 Some(Expression::Number(result))
             }
 
@@ -322,3 +375,36 @@ impl ConstantFolder {
         }
     }
 }
+
+// Folding only ever combines literals, so they are all this needs to print
+fn literal(expr: &Expression) -> String {
+    match expr {
+        Expression::Number(n) => n.to_string(),
+        Expression::String(s) => format!("{:?}", s),
+        Expression::Boolean(b) => b.to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folded(source: &str) -> Expression {
+        let program = crate::parser::parse(crate::lexer::tokenize(source).unwrap()).unwrap();
+        match ConstantFolder::new().fold(program).statements.remove(0) {
+            Statement::Let { value, .. } => value,
+            other => panic!("expected a let, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn folds_like_the_runtime() {
+        assert!(matches!(folded("let x = 1_000 * 2.5e3"), Expression::Number(n) if n == 2_500_000.0));
+        assert!(matches!(folded("let x = 1e-20 is~ 2e-20"), Expression::Boolean(false)));
+        assert!(matches!(folded("let x = 1e308 * 10"), Expression::Binary { .. }));
+        assert!(matches!(folded("let x = -2 ** 2"), Expression::Number(n) if n == -4.0));
+        assert!(matches!(folded("let x = -7 // 2"), Expression::Number(n) if n == -4.0));
+        assert!(matches!(folded("let x = 1 // 0"), Expression::Binary { .. }));
+    }
+}
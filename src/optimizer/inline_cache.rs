@@ -1,194 +1,77 @@
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
-use crate::types::Value;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::types::{RelicMap, Value};
 
-/// Inline cache entry for property/method lookups
-#[derive(Clone, Debug)]
-pub enum CacheEntry {
-    /// Monomorphic cache - single type cached
-    Monomorphic {
-        type_name: String,
-        cached_value: CachedLookup,
-    },
-    /// Polymorphic cache - multiple types cached (up to 4)
-    Polymorphic {
-        entries: Vec<(String, CachedLookup)>,
-    },
-    /// Megamorphic - too many types, fall back to slow path
-    Megamorphic,
-}
-
-#[derive(Clone, Debug)]
-pub enum CachedLookup {
-    /// Property access result
-    PropertyIndex(usize),
-    /// Method pointer (for native functions)
-    MethodPointer(String),
-}
-
-/// Global inline cache manager
+/// Inline cache for one property or method lookup site (`user.name`, `module.fn()`)
+///
+/// Relics keep their keys in insertion order, so Relics built the same way -
+/// the same keys in the same order, i.e. the same shape - hold a field in the
+/// same slot. The cache remembers the slot the key was found in last time; a
+/// hit costs one key comparison instead of hashing the key. When a Relic of
+/// another shape comes through, the lookup falls back to hashing and the cache
+/// moves to the new slot.
+#[derive(Debug, Default)]
 pub struct InlineCache {
-    /// Cache storage: (file, line, column) -> CacheEntry
-    caches: Arc<RwLock<HashMap<(String, usize, usize), CacheEntry>>>,
-    /// Statistics
-    hits: Arc<RwLock<u64>>,
-    misses: Arc<RwLock<u64>>,
+    /// Slot index + 1 of the last hit, 0 while empty
+    slot: AtomicUsize,
 }
 
 impl InlineCache {
     pub fn new() -> Self {
-        InlineCache {
-            caches: Arc::new(RwLock::new(HashMap::new())),
-            hits: Arc::new(RwLock::new(0)),
-            misses: Arc::new(RwLock::new(0)),
-        }
+        Self::default()
     }
 
-    /// Try to lookup a property using the inline cache
-    pub fn lookup_property(
-        &self,
-        location: (String, usize, usize),
-        object: &Value,
-        property: &str,
-    ) -> Option<Value> {
-        let type_name = object.type_name().to_string();
-        
-        // Try to read from cache
-        if let Ok(caches) = self.caches.read() {
-            if let Some(entry) = caches.get(&location) {
-                match entry {
-                    CacheEntry::Monomorphic { type_name: cached_type, cached_value } => {
-                        if &type_name == cached_type {
-                            // Cache hit!
-                            if let Ok(mut hits) = self.hits.write() {
-                                *hits += 1;
-                            }
-                            return self.extract_property(object, property, cached_value);
-                        }
-                    }
-                    CacheEntry::Polymorphic { entries } => {
-                        for (cached_type, cached_value) in entries {
-                            if &type_name == cached_type {
-                                if let Ok(mut hits) = self.hits.write() {
-                                    *hits += 1;
-                                }
-                                return self.extract_property(object, property, cached_value);
-                            }
-                        }
-                    }
-                    CacheEntry::Megamorphic => {
-                        // Too polymorphic, skip caching
-                        return None;
-                    }
+    /// Look up `key` in `map`, trying the cached slot first
+    pub fn get<'a>(&self, map: &'a RelicMap, key: &str) -> Option<&'a Value> {
+        let slot = self.slot.load(Ordering::Relaxed);
+        if slot != 0 {
+            if let Some((cached_key, value)) = map.get_index(slot - 1) {
+                if cached_key == key {
+                    return Some(value);
                 }
             }
         }
 
-        // Cache miss
-        if let Ok(mut misses) = self.misses.write() {
-            *misses += 1;
-        }
-
-        None
+        let (index, _, value) = map.get_full(key)?;
+        self.slot.store(index + 1, Ordering::Relaxed);
+        Some(value)
     }
 
-    /// Update the inline cache with a new lookup result
-    pub fn update_cache(
-        &self,
-        location: (String, usize, usize),
-        type_name: String,
-        cached_value: CachedLookup,
-    ) {
-        if let Ok(mut caches) = self.caches.write() {
-            let entry = caches.entry(location).or_insert_with(|| {
-                CacheEntry::Monomorphic {
-                    type_name: type_name.clone(),
-                    cached_value: cached_value.clone(),
-                }
-            });
-
-            match entry {
-                CacheEntry::Monomorphic { type_name: existing_type, .. } => {
-                    if existing_type != &type_name {
-                        // Upgrade to polymorphic
-                        let old_entry = entry.clone();
-                        if let CacheEntry::Monomorphic { type_name: old_type, cached_value: old_value } = old_entry {
-                            *entry = CacheEntry::Polymorphic {
-                                entries: vec![
-                                    (old_type, old_value),
-                                    (type_name, cached_value),
-                                ],
-                            };
-                        }
-                    }
-                }
-                CacheEntry::Polymorphic { entries } => {
-                    // Check if type already exists
-                    if !entries.iter().any(|(t, _)| t == &type_name) {
-                        if entries.len() < 4 {
-                            // Add new type
-                            entries.push((type_name, cached_value));
-                        } else {
-                            // Too many types, upgrade to megamorphic
-                            *entry = CacheEntry::Megamorphic;
-                        }
-                    }
-                }
-                CacheEntry::Megamorphic => {
-                    // Already megamorphic, do nothing
-                }
-            }
-        }
+    /// The slot the last lookup found its key in
+    pub fn cached_slot(&self) -> Option<usize> {
+        self.slot.load(Ordering::Relaxed).checked_sub(1)
     }
+}
 
-    /// Extract property value from cached lookup
-    fn extract_property(&self, object: &Value, property: &str, cached: &CachedLookup) -> Option<Value> {
-        match cached {
-            CachedLookup::PropertyIndex(index) => {
-                // For arrays, relics, etc.
-                match object {
-                    Value::Relic(map) => {
-                        map.get(property).cloned()
-                    }
-                    _ => None,
-                }
-            }
-            CachedLookup::MethodPointer(method_name) => {
-                // Methods are handled differently - this is just a hint
-                None
-            }
+// Cloned ASTs (e.g. per web request) start from the same guess but update independently
+impl Clone for InlineCache {
+    fn clone(&self) -> Self {
+        InlineCache {
+            slot: AtomicUsize::new(self.slot.load(Ordering::Relaxed)),
         }
     }
+}
 
-    /// Get cache statistics
-    pub fn get_stats(&self) -> (u64, u64, f64) {
-        let hits = self.hits.read().map(|h| *h).unwrap_or(0);
-        let misses = self.misses.read().map(|m| *m).unwrap_or(0);
-        let total = hits + misses;
-        let hit_rate = if total > 0 {
-            (hits as f64 / total as f64) * 100.0
-        } else {
-            0.0
-        };
-        (hits, misses, hit_rate)
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    /// Clear all caches (useful for testing)
-    pub fn clear(&self) {
-        if let Ok(mut caches) = self.caches.write() {
-            caches.clear();
-        }
-        if let Ok(mut hits) = self.hits.write() {
-            *hits = 0;
-        }
-        if let Ok(mut misses) = self.misses.write() {
-            *misses = 0;
-        }
+    fn relic(keys: &[&str]) -> RelicMap {
+        keys.iter().enumerate().map(|(i, k)| (k.to_string(), Value::Number(i as f64))).collect()
     }
-}
 
-impl Default for InlineCache {
-    fn default() -> Self {
-        Self::new()
+    #[test]
+    fn follows_the_shape_of_the_relic() {
+        let cache = InlineCache::new();
+        let user = relic(&["id", "name"]);
+        assert!(matches!(cache.get(&user, "name"), Some(Value::Number(n)) if *n == 1.0));
+        assert_eq!(cache.cached_slot(), Some(1));
+
+        // Another shape: still correct, and the cache moves to the new slot
+        let other = relic(&["name", "id"]);
+        assert!(matches!(cache.get(&other, "name"), Some(Value::Number(n)) if *n == 0.0));
+        assert_eq!(cache.cached_slot(), Some(0));
+
+        assert!(cache.get(&other, "missing").is_none());
+        assert_eq!(cache.cached_slot(), Some(0));
     }
 }
@@ -8,6 +8,13 @@ pub use super_instructions::SuperInstructionOptimizer;
 
 use crate::parser::ast::Program;
 
+/// The AST after one optimization pass and what the pass changed
+pub struct PassReport {
+    pub name: &'static str,
+    pub program: Program,
+    pub changes: Vec<String>,
+}
+
 /// Main optimizer that applies all optimization passes
 pub struct Optimizer {
     enable_constant_folding: bool,
@@ -50,6 +57,34 @@ impl Optimizer {
         
         program
     }
+
+    /// Run the enabled passes like `optimize`, keeping a snapshot of the AST after
+    /// each one. Used by `flowlang dev opt` to debug the optimizer.
+    pub fn optimize_with_report(&self, mut program: Program) -> Vec<PassReport> {
+        let mut reports = Vec::new();
+
+        if self.enable_constant_folding {
+            let folder = ConstantFolder::new();
+            program = folder.fold(program);
+            reports.push(PassReport {
+                name: "constant folding",
+                program: program.clone(),
+                changes: folder.take_folded(),
+            });
+        }
+
+        if self.enable_super_instructions {
+            let super_opt = SuperInstructionOptimizer::new();
+            program = super_opt.optimize(program);
+            reports.push(PassReport {
+                name: "super-instructions",
+                program,
+                changes: super_opt.take_rewritten(),
+            });
+        }
+
+        reports
+    }
 }
 
 impl Default for Optimizer {
@@ -1,11 +1,20 @@
 use crate::parser::ast::*;
+use std::cell::RefCell;
 
 /// Super-instruction optimizer - fuses common AST patterns into optimized nodes
-pub struct SuperInstructionOptimizer;
+pub struct SuperInstructionOptimizer {
+    /// Every rewrite performed, for `flowlang dev opt`
+    rewritten: RefCell<Vec<String>>,
+}
 
 impl SuperInstructionOptimizer {
     pub fn new() -> Self {
-        SuperInstructionOptimizer
+        SuperInstructionOptimizer { rewritten: RefCell::new(Vec::new()) }
+    }
+
+    /// Rewrites performed so far, in the order they happened
+    pub fn take_rewritten(&self) -> Vec<String> {
+        self.rewritten.take()
     }
 
     pub fn optimize(&self, program: Program) -> Program {
@@ -66,6 +75,7 @@ impl SuperInstructionOptimizer {
                             error_type: clause.error_type,
                             binding: clause.binding,
                             retry_count: clause.retry_count,
+                            backoff_ms: clause.backoff_ms,
                             body: self.optimize_block(clause.body),
                         }
                     }).collect(),
@@ -84,6 +94,7 @@ impl SuperInstructionOptimizer {
         while i < stmts.len() {
             // Try to match patterns and create super-instructions
             if let Some((super_stmt, consumed)) = self.try_create_super_instruction(&stmts[i..]) {
+                self.rewritten.borrow_mut().push(format!("{} statements fused into one", consumed));
                 optimized.push(super_stmt);
                 i += consumed;
             } else {
@@ -120,21 +131,22 @@ impl SuperInstructionOptimizer {
     fn optimize_expression(&self, expr: Expression) -> Expression {
         match expr {
             // Pattern: object.method(args) - already optimal in AST
-            Expression::MethodCall { object, method, arguments } => {
+            Expression::MethodCall { object, method, arguments, cache } => {
                 // This is already a fused operation in our AST
                 // No need to optimize further
                 Expression::MethodCall {
                     object,
                     method,
                     arguments,
+                    cache,
                 }
             }
 
             // Pattern: object[index] - array/relic access
-            Expression::Index { object, index } => {
+            Expression::Index { object, index, cache } => {
                 // Check if this is followed by an assignment
                 // (This would need to be detected at statement level)
-                Expression::Index { object, index }
+                Expression::Index { object, index, cache }
             }
 
             // Recursive optimization
@@ -181,6 +193,15 @@ impl SuperInstructionOptimizer {
                 }
             }
 
+            Expression::Attempt { expr, binding, fallback, line } => {
+                Expression::Attempt {
+                    expr: Box::new(self.optimize_expression(*expr)),
+                    binding,
+                    fallback: Box::new(self.optimize_expression(*fallback)),
+                    line,
+                }
+            }
+
             // Literals don't need optimization
             other => other,
         }
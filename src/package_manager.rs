@@ -1,26 +1,50 @@
 //! Package Manager for FlowLang
 //!
 //! Handles downloading and managing packages from Git repositories.
-//! Packages are stored locally in .flowlang/pkg/<host>/<user>/<repo>
+//! Packages are stored locally in .flowlang/pkg/<host>/<user>/<repo>, and the commit
+//! and content hash each one resolved to are recorded in flow.lock next to the config.
 
-use crate::config::ProjectConfig;
+use crate::config::{ProjectConfig, RegistryConfig};
 use crate::error::FlowError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::io::{self, Cursor};
 use std::path::{Path, PathBuf};
 use std::fs;
 
 /// Parsed package specification from URL
 #[derive(Debug, Clone)]
 pub struct PackageSpec {
-    pub host: String,      // e.g., "github.com"
-    pub owner: String,     // e.g., "flowlang-exe"
+    pub host: String,      // e.g., "github.com", or the registry name
+    pub owner: String,     // e.g., "flowlang-exe"; empty for registry packages
     pub repo: String,      // e.g., "http"
-    pub git_ref: String,   // branch, tag, or commit SHA
+    pub git_ref: String,   // branch, tag, or commit SHA; a version range for registry packages
+    pub kind: SourceKind,
+}
+
+/// Where a package comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceKind {
+    /// `host/owner/repo@ref`, cloned over HTTPS
+    Hosted,
+    /// `git+<scheme>://...#ref`, cloned from the URL after `git+` (ssh, https, file)
+    Git { remote: String },
+    /// `<registry>:<name>@<range>`, downloaded from a registry in config
+    Registry,
 }
 
 impl PackageSpec {
-    /// Parse a package URL like "github.com/user/repo@ref"
+    /// Parse a package URL like "github.com/user/repo@ref",
+    /// "git+ssh://git@host/team/utils.flow#v1.2.0" or "acme:utils@^1.2"
     pub fn parse(url: &str) -> Result<Self, FlowError> {
+        if let Some(rest) = url.strip_prefix("git+") {
+            return Self::parse_git(url, rest);
+        }
+        if let Some((registry, package)) = url.split_once(':').filter(|(registry, _)| !registry.contains('/')) {
+            return Self::parse_registry(url, registry, package);
+        }
+
         // Split by @ to get ref
         let (base, git_ref) = if let Some(idx) = url.find('@') {
             (&url[..idx], url[idx + 1..].to_string())
@@ -42,20 +66,386 @@ impl PackageSpec {
             owner: parts[1].to_string(),
             repo: parts[2].to_string(),
             git_ref,
+            kind: SourceKind::Hosted,
+        })
+    }
+
+    fn parse_git(url: &str, rest: &str) -> Result<Self, FlowError> {
+        let invalid = || FlowError::runtime(
+            &format!("Invalid package URL '{}'. Expected format: git+<scheme>://[user@]host/path[#ref]", url),
+            0, 0
+        );
+        let (remote, git_ref) = match rest.split_once('#') {
+            Some((remote, git_ref)) if !git_ref.is_empty() => (remote, git_ref.to_string()),
+            Some(_) => return Err(invalid()),
+            None => (rest, "main".to_string()),
+        };
+        let (_, location) = remote.split_once("://").ok_or_else(invalid)?;
+        let (authority, path) = location.split_once('/').unwrap_or((location, ""));
+        // user@host:port -> host; file:// URLs have no host
+        let host = authority.rsplit('@').next().unwrap_or_default();
+        let host = host.split(':').next().unwrap_or_default();
+        let mut segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+        let repo = segments.pop().ok_or_else(invalid)?;
+
+        Ok(PackageSpec {
+            host: if host.is_empty() { "local".to_string() } else { host.to_string() },
+            owner: segments.join("/"),
+            repo: repo.strip_suffix(".git").unwrap_or(repo).to_string(),
+            git_ref,
+            kind: SourceKind::Git { remote: remote.to_string() },
+        })
+    }
+
+    fn parse_registry(url: &str, registry: &str, package: &str) -> Result<Self, FlowError> {
+        let (name, range) = package.split_once('@').unwrap_or((package, "*"));
+        let valid_name = |name: &str| !name.is_empty() && name.split('/').all(|part| !part.is_empty() && part != "..");
+        if registry.is_empty() || registry.contains('/') || !valid_name(name) {
+            return Err(FlowError::runtime(
+                &format!("Invalid package URL '{}'. Expected format: <registry>:<name>[@<range>]", url),
+                0, 0
+            ));
+        }
+        crate::semver::VersionReq::parse(range).map_err(|e| {
+            FlowError::glitch(&format!("Package '{}' has an invalid version range: {}", url, e), 0, 0)
+        })?;
+
+        Ok(PackageSpec {
+            host: registry.to_string(),
+            owner: String::new(),
+            repo: name.to_string(),
+            git_ref: range.to_string(),
+            kind: SourceKind::Registry,
         })
     }
 
     /// Get the Git clone URL
     pub fn clone_url(&self) -> String {
-        format!("https://{}/{}/{}.git", self.host, self.owner, self.repo)
+        match &self.kind {
+            SourceKind::Git { remote } => remote.clone(),
+            _ => format!("https://{}/{}/{}.git", self.host, self.owner, self.repo),
+        }
     }
 
     /// Get local path relative to .flowlang/pkg/
     pub fn local_path(&self) -> PathBuf {
-        PathBuf::from(&self.host)
-            .join(&self.owner)
-            .join(&self.repo)
+        let mut path = PathBuf::from(&self.host);
+        path.extend(self.owner.split('/').chain(self.repo.split('/')).filter(|part| !part.is_empty()));
+        path
+    }
+
+    /// The spec with its ref spelled out, as flow.lock records it
+    pub fn source(&self) -> String {
+        match &self.kind {
+            SourceKind::Hosted => format!("{}/{}/{}@{}", self.host, self.owner, self.repo, self.git_ref),
+            SourceKind::Git { remote } => format!("git+{}#{}", remote, self.git_ref),
+            SourceKind::Registry => format!("{}:{}@{}", self.host, self.repo, self.git_ref),
+        }
+    }
+
+    /// `owner/repo`, or the bare name of a registry package, for messages
+    fn display_name(&self) -> String {
+        if self.owner.is_empty() {
+            self.repo.clone()
+        } else {
+            format!("{}/{}", self.owner, self.repo)
+        }
+    }
+}
+
+pub const LOCK_FILE: &str = "flow.lock";
+const LOCK_VERSION: u32 = 1;
+
+/// flow.lock: the exact commit and content of every remote package, so every
+/// checkout of a project runs the same code even when a branch ref moves
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LockedPackage {
+    /// The package URL as written in config, e.g. `github.com/user/repo@main`
+    pub source: String,
+    /// `version` from the package's config
+    pub version: String,
+    /// The commit the ref resolved to; registry packages have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// SHA-256 over the package's files, see `content_checksum`
+    pub checksum: String,
+}
+
+impl Lockfile {
+    /// The lockfile in `project_root`, or an empty one if there is none yet
+    pub fn load(project_root: &Path) -> Result<Self, FlowError> {
+        let path = project_root.join(LOCK_FILE);
+        if !path.exists() {
+            return Ok(Lockfile { version: LOCK_VERSION, packages: Vec::new() });
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| FlowError::rift(&format!("Failed to read {}: {}", LOCK_FILE, e), 0, 0))?;
+        let lock: Lockfile = toml::from_str(&content)
+            .map_err(|e| FlowError::glitch(&format!("Failed to parse {}: {}", LOCK_FILE, e.to_string().trim()), 0, 0))?;
+        if lock.version > LOCK_VERSION {
+            return Err(FlowError::glitch(
+                &format!("{} is version {}; this FlowLang reads up to version {}", LOCK_FILE, lock.version, LOCK_VERSION),
+                0,
+                0,
+            ));
+        }
+        Ok(lock)
+    }
+
+    pub fn save(&mut self, project_root: &Path) -> Result<(), FlowError> {
+        self.version = LOCK_VERSION;
+        self.packages.sort_by(|a, b| a.source.cmp(&b.source));
+        let body = toml::to_string(self)
+            .map_err(|e| FlowError::rift(&format!("Failed to serialize {}: {}", LOCK_FILE, e), 0, 0))?;
+        let content = format!("# Generated by FlowLang; commit it, and don't edit it by hand.\n\n{}", body);
+        fs::write(project_root.join(LOCK_FILE), content)
+            .map_err(|e| FlowError::rift(&format!("Failed to write {}: {}", LOCK_FILE, e), 0, 0))
+    }
+
+    pub fn get(&self, source: &str) -> Option<&LockedPackage> {
+        self.packages.iter().find(|package| package.source == source)
+    }
+
+    fn insert(&mut self, locked: LockedPackage) {
+        self.packages.retain(|package| package.source != locked.source);
+        self.packages.push(locked);
+    }
+}
+
+/// SHA-256 over every file of a package except `.git`: each relative path (with `/`
+/// separators, in sorted order) followed by the file's bytes
+pub fn content_checksum(dir: &Path) -> Result<String, FlowError> {
+    fn collect(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.file_name().is_some_and(|name| name == ".git") {
+                continue;
+            }
+            if path.is_dir() {
+                collect(&path, files)?;
+            } else {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let read_error = |e: std::io::Error| FlowError::rift(&format!("Failed to read package {}: {}", dir.display(), e), 0, 0);
+    let mut files = Vec::new();
+    collect(dir, &mut files).map_err(read_error)?;
+    let mut entries: Vec<(String, PathBuf)> = files
+        .into_iter()
+        .map(|path| {
+            let relative = path.strip_prefix(dir).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            (relative, path)
+        })
+        .collect();
+    entries.sort();
+
+    let mut hasher = Sha256::new();
+    for (relative, path) in entries {
+        hasher.update(relative.as_bytes());
+        hasher.update([0]);
+        hasher.update(fs::read(&path).map_err(read_error)?);
+        hasher.update([0]);
     }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Credentials for private Git remotes: ssh-agent, `FLOWLANG_SSH_KEY` and the usual
+/// `~/.ssh` keys for SSH, and Git's credential helpers for HTTPS
+fn fetch_options() -> git2::FetchOptions<'static> {
+    let mut attempts = 0;
+    let mut callbacks = git2::RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        // libgit2 asks again after every rejected credential, so each attempt tries the next one
+        attempts += 1;
+        let user = username.unwrap_or("git");
+        if allowed.contains(git2::CredentialType::SSH_KEY) {
+            if attempts == 1 {
+                return git2::Cred::ssh_key_from_agent(user);
+            }
+            let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(PathBuf::from);
+            let keys = std::env::var_os("FLOWLANG_SSH_KEY").map(PathBuf::from).into_iter().chain(
+                ["id_ed25519", "id_ecdsa", "id_rsa"]
+                    .iter()
+                    .filter_map(|name| home.as_ref().map(|home| home.join(".ssh").join(name))),
+            );
+            return match keys.filter(|key| key.is_file()).nth(attempts - 2) {
+                Some(key) => git2::Cred::ssh_key(user, None, &key, None),
+                None => Err(git2::Error::from_str("no SSH key was accepted (tried ssh-agent and ~/.ssh)")),
+            };
+        }
+        if allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) && attempts == 1 {
+            let config = git2::Config::open_default()?;
+            return git2::Cred::credential_helper(&config, url, username);
+        }
+        if allowed.contains(git2::CredentialType::DEFAULT) && attempts == 1 {
+            return git2::Cred::default();
+        }
+        Err(git2::Error::from_str("no credentials were accepted"))
+    });
+    let mut options = git2::FetchOptions::new();
+    options.remote_callbacks(callbacks);
+    options
+}
+
+/// An authenticated connection to a private registry. A registry serves
+/// `GET <url>/<name>` as `{"versions": ["1.2.0", ...]}` and `GET <url>/<name>/<version>.zip`
+/// as the package directory, zipped.
+struct RegistryClient<'a> {
+    name: &'a str,
+    url: String,
+    token: Option<String>,
+    token_env: Option<&'a str>,
+}
+
+impl<'a> RegistryClient<'a> {
+    fn new(name: &'a str, registry: &'a RegistryConfig) -> Result<Self, FlowError> {
+        let token = match &registry.token_env {
+            Some(var) => match std::env::var(var) {
+                Ok(token) if !token.is_empty() => Some(token),
+                _ => {
+                    return Err(FlowError::glitch(
+                        &format!("Registry '{}' needs a token: set {} in the environment", name, var),
+                        0,
+                        0,
+                    ))
+                }
+            },
+            None => None,
+        };
+        Ok(RegistryClient {
+            name,
+            url: registry.url.trim_end_matches('/').to_string(),
+            token,
+            token_env: registry.token_env.as_deref(),
+        })
+    }
+
+    fn get(&self, path: &str) -> Result<Vec<u8>, FlowError> {
+        let url = format!("{}/{}", self.url, path);
+        // reqwest's blocking client can't be used on a thread that is running Tokio
+        let result = std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let mut request = reqwest::blocking::Client::new().get(&url);
+                    if let Some(token) = &self.token {
+                        request = request.bearer_auth(token);
+                    }
+                    let response = request.send()?;
+                    let status = response.status();
+                    response.bytes().map(|body| (status, body.to_vec()))
+                })
+                .join()
+                .expect("registry request panicked")
+        });
+        let (status, body) = result.map_err(|e| {
+            FlowError::runtime(&format!("Failed to reach registry '{}': {}", self.name, e), 0, 0)
+        })?;
+
+        let refused = match self.token_env {
+            Some(var) => format!("check the token in {}", var),
+            None => "add token_env to its entry in registries".to_string(),
+        };
+        match status.as_u16() {
+            _ if status.is_success() => Ok(body),
+            401 | 403 => Err(FlowError::glitch(
+                &format!("Registry '{}' refused {} ({}); {}", self.name, url, status, refused),
+                0,
+                0,
+            )),
+            404 => Err(FlowError::glitch(&format!("Registry '{}' has no {}", self.name, path), 0, 0)),
+            _ => Err(FlowError::runtime(
+                &format!("Registry '{}' answered {} for {}", self.name, status, url),
+                0,
+                0,
+            )),
+        }
+    }
+
+    /// The newest published version inside the spec's range
+    fn newest_version(&self, spec: &PackageSpec) -> Result<String, FlowError> {
+        let body = self.get(&spec.repo)?;
+        let index: serde_json::Value = serde_json::from_slice(&body).map_err(|e| {
+            FlowError::glitch(&format!("Registry '{}' sent an invalid index for {}: {}", self.name, spec.repo, e), 0, 0)
+        })?;
+        let req = crate::semver::VersionReq::parse(&spec.git_ref)
+            .map_err(|e| FlowError::glitch(&format!("Package {} has an invalid version range: {}", spec.source(), e), 0, 0))?;
+        index["versions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|version| crate::semver::Version::parse(version.as_str()?).ok())
+            .filter(|version| req.matches(version))
+            .max()
+            .map(|version| version.to_string())
+            .ok_or_else(|| {
+                FlowError::glitch(
+                    &format!("Registry '{}' has no version of {} matching {}", self.name, spec.repo, spec.git_ref),
+                    0,
+                    0,
+                )
+            })
+    }
+}
+
+/// Unpack a registry archive into `target`, replacing what was there. A zipped package
+/// folder has a single top-level directory, which is dropped.
+fn extract_package(archive: &[u8], target: &Path) -> Result<(), FlowError> {
+    let corrupt = |e: zip::result::ZipError| FlowError::glitch(&format!("Corrupt package archive: {}", e), 0, 0);
+    let write_error = |e: io::Error| FlowError::rift(&format!("Failed to unpack package into {}: {}", target.display(), e), 0, 0);
+    let mut archive = zip::ZipArchive::new(Cursor::new(archive)).map_err(corrupt)?;
+
+    let names: Vec<PathBuf> = archive.file_names().map(PathBuf::from).collect();
+    let first = |name: &PathBuf| name.components().next().map(|part| part.as_os_str().to_os_string());
+    let prefix = match names.first().and_then(first) {
+        Some(top) if !names.iter().any(|name| name == Path::new("config.flowlang.json"))
+            && names.iter().all(|name| first(name).as_ref() == Some(&top)) => PathBuf::from(top),
+        _ => PathBuf::new(),
+    };
+
+    // Unpack beside the old copy, so a failed download leaves it in place
+    let mut staging = target.as_os_str().to_os_string();
+    staging.push(".download");
+    let staging = PathBuf::from(staging);
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging).map_err(write_error)?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(corrupt)?;
+        let relative = entry.enclosed_name().map(|path| path.to_path_buf()).ok_or_else(|| {
+            FlowError::glitch(&format!("Refusing to extract unsafe entry '{}'", entry.name()), 0, 0)
+        })?;
+        let path = staging.join(relative.strip_prefix(&prefix).unwrap_or(&relative));
+        if entry.is_dir() {
+            fs::create_dir_all(&path).map_err(write_error)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(write_error)?;
+        }
+        let mut output = fs::File::create(&path).map_err(write_error)?;
+        io::copy(&mut entry, &mut output).map_err(write_error)?;
+    }
+
+    if target.exists() {
+        fs::remove_dir_all(target).map_err(write_error)?;
+    }
+    fs::rename(&staging, target).map_err(write_error)
+}
+
+/// The commit checked out in a package directory
+fn head_commit(path: &Path) -> Result<String, FlowError> {
+    git2::Repository::open(path)
+        .and_then(|repo| repo.head()?.peel_to_commit().map(|commit| commit.id().to_string()))
+        .map_err(|e| FlowError::runtime(&format!("Failed to read the commit of {}: {}", path.display(), e), 0, 0))
 }
 
 /// Package Manager handles downloading and resolving packages
@@ -89,9 +479,27 @@ impl PackageManager {
         }
     }
 
-    /// Fetch a single package
+    /// Fetch a single package, at its locked commit if flow.lock has one
     pub fn fetch_package(&self, spec: &PackageSpec) -> Result<PathBuf, FlowError> {
+        let mut lock = Lockfile::load(&self.project_root)?;
+        let path = self.install(spec, &mut lock, &HashMap::new())?;
+        lock.save(&self.project_root)?;
+        Ok(path)
+    }
+
+    /// Clone, update or download a package. A package in `lock` is checked out at its
+    /// locked commit (or version) and must still match its checksum; any other is added
+    /// to `lock`.
+    fn install(
+        &self,
+        spec: &PackageSpec,
+        lock: &mut Lockfile,
+        registries: &HashMap<String, RegistryConfig>,
+    ) -> Result<PathBuf, FlowError> {
         let target_path = self.pkg_dir.join(spec.local_path());
+        let locked = lock.get(&spec.source()).cloned();
+        // A locked commit wins over wherever the ref points now
+        let git_ref = locked.as_ref().and_then(|locked| locked.commit.as_deref()).unwrap_or(&spec.git_ref);
 
         // Create parent directories
         if let Some(parent) = target_path.parent() {
@@ -100,30 +508,109 @@ impl PackageManager {
             })?;
         }
 
-        // If already exists, try to update
-        if target_path.exists() {
-            return self.update_package(spec, &target_path);
-        }
+        if spec.kind == SourceKind::Registry {
+            let version = locked.as_ref().map(|locked| locked.version.as_str());
+            self.download(spec, &target_path, version, registries)?;
+        } else if target_path.exists() {
+            // If already exists, try to update
+            self.update_package(spec, &target_path, git_ref)?;
+            self.validate_package(&target_path, spec)?;
+        } else {
+            // Clone the repository
+            if !crate::platform::is_quiet() {
+                println!("📦 Downloading {}...", spec.clone_url());
+            }
+
+            let repo = git2::build::RepoBuilder::new()
+                .fetch_options(fetch_options())
+                .clone(&spec.clone_url(), &target_path)
+                .map_err(|e| FlowError::runtime(&format!("Failed to clone package: {}", e), 0, 0))?;
 
-        // Clone the repository
-        println!("📦 Downloading {}...", spec.clone_url());
+            // Checkout the specified ref
+            self.checkout_ref(&repo, git_ref)?;
 
-        let repo = git2::Repository::clone(&spec.clone_url(), &target_path)
-            .map_err(|e| FlowError::runtime(&format!("Failed to clone package: {}", e), 0, 0))?;
+            // Validate package has config.flowlang.json
+            self.validate_package(&target_path, spec)?;
 
-        // Checkout the specified ref
-        self.checkout_ref(&repo, &spec.git_ref)?;
+            if !crate::platform::is_quiet() {
+                println!("✅ Installed {}", spec.display_name());
+            }
+        }
 
-        // Validate package has config.flowlang.json
-        self.validate_package(&target_path, spec)?;
+        let current = self.lock_entry(spec, &target_path)?;
+        match locked {
+            Some(locked) if locked.checksum != current.checksum => Err(FlowError::glitch(
+                &format!(
+                    "Package {} at {} doesn't match its checksum in {}. If the change is expected, run 'flowlang install --update'.",
+                    spec.source(), current.commit.as_deref().unwrap_or(&current.version), LOCK_FILE
+                ),
+                0,
+                0,
+            )),
+            _ => {
+                lock.insert(current);
+                Ok(target_path)
+            }
+        }
+    }
 
-        println!("✅ Installed {}/{}", spec.owner, spec.repo);
-        Ok(target_path)
+    /// Check an installed package against flow.lock before it runs. A package installed
+    /// before the project had a lockfile is locked as it is now.
+    pub fn verify(&self, spec: &PackageSpec, path: &Path) -> Result<(), FlowError> {
+        let mut lock = Lockfile::load(&self.project_root)?;
+        let current = self.lock_entry(spec, path)?;
+        match lock.get(&spec.source()) {
+            Some(locked) if locked.commit != current.commit => Err(FlowError::glitch(
+                &format!(
+                    "Package {} is at commit {}, but {} has {}. Run 'flowlang install' to check out the locked commit.",
+                    spec.source(),
+                    current.commit.as_deref().unwrap_or_default(),
+                    LOCK_FILE,
+                    locked.commit.as_deref().unwrap_or_default()
+                ),
+                0,
+                0,
+            )),
+            Some(locked) if locked.checksum != current.checksum => Err(FlowError::glitch(
+                &format!(
+                    "Package {} has changed since it was locked in {}. Delete {} and run 'flowlang install' to restore it.",
+                    spec.source(), LOCK_FILE, path.display()
+                ),
+                0,
+                0,
+            )),
+            Some(_) => Ok(()),
+            None => {
+                lock.insert(current);
+                lock.save(&self.project_root)
+            }
+        }
+    }
+
+    fn lock_entry(&self, spec: &PackageSpec, path: &Path) -> Result<LockedPackage, FlowError> {
+        let config_path = path.join("config.flowlang.json");
+        let version = if config_path.exists() {
+            ProjectConfig::load(&config_path)?.version
+        } else {
+            ProjectConfig::default().version
+        };
+        let commit = match spec.kind {
+            SourceKind::Registry => None,
+            _ => Some(head_commit(path)?),
+        };
+        Ok(LockedPackage {
+            source: spec.source(),
+            version,
+            commit,
+            checksum: content_checksum(path)?,
+        })
     }
 
     /// Update an existing package
-    fn update_package(&self, spec: &PackageSpec, path: &Path) -> Result<PathBuf, FlowError> {
-        println!("🔄 Updating {}/{}...", spec.owner, spec.repo);
+    fn update_package(&self, spec: &PackageSpec, path: &Path, git_ref: &str) -> Result<PathBuf, FlowError> {
+        if !crate::platform::is_quiet() {
+            println!("🔄 Updating {}...", spec.display_name());
+        }
 
         let repo = git2::Repository::open(path)
             .map_err(|e| FlowError::runtime(&format!("Failed to open package repo: {}", e), 0, 0))?;
@@ -132,13 +619,15 @@ impl PackageManager {
         let mut remote = repo.find_remote("origin")
             .map_err(|e| FlowError::runtime(&format!("Failed to find remote: {}", e), 0, 0))?;
 
-        remote.fetch(&[&spec.git_ref], None, None)
+        remote.fetch(&[&spec.git_ref], Some(&mut fetch_options()), None)
             .map_err(|e| FlowError::runtime(&format!("Failed to fetch updates: {}", e), 0, 0))?;
 
         // Checkout the ref
-        self.checkout_ref(&repo, &spec.git_ref)?;
+        self.checkout_ref(&repo, git_ref)?;
 
-        println!("✅ Updated {}/{}", spec.owner, spec.repo);
+        if !crate::platform::is_quiet() {
+            println!("✅ Updated {}", spec.display_name());
+        }
         Ok(path.to_path_buf())
     }
 
@@ -176,31 +665,80 @@ impl PackageManager {
         Ok(())
     }
 
+    /// Download a registry package: the newest version in its range, or `version` when
+    /// flow.lock already names one
+    fn download(
+        &self,
+        spec: &PackageSpec,
+        target_path: &Path,
+        version: Option<&str>,
+        registries: &HashMap<String, RegistryConfig>,
+    ) -> Result<(), FlowError> {
+        let registry = registries.get(&spec.host).ok_or_else(|| {
+            FlowError::glitch(
+                &format!(
+                    "Package {} needs registry '{}', which the registries section of config.flowlang.json doesn't define",
+                    spec.source(), spec.host
+                ),
+                0,
+                0,
+            )
+        })?;
+        let client = RegistryClient::new(&spec.host, registry)?;
+        let version = match version {
+            Some(version) => version.to_string(),
+            None => client.newest_version(spec)?,
+        };
+
+        if !crate::platform::is_quiet() {
+            println!("📦 Downloading {}@{} from {}...", spec.repo, version, client.url);
+        }
+        let archive = client.get(&format!("{}/{}.zip", spec.repo, version))?;
+        extract_package(&archive, target_path)?;
+        self.validate_package(target_path, spec)?;
+
+        if !crate::platform::is_quiet() {
+            println!("✅ Installed {}@{}", spec.repo, version);
+        }
+        Ok(())
+    }
+
     /// Validate that a package has config.flowlang.json
     fn validate_package(&self, path: &Path, spec: &PackageSpec) -> Result<(), FlowError> {
         let config_path = path.join("config.flowlang.json");
         if !config_path.exists() {
             return Err(FlowError::runtime(
                 &format!(
-                    "Package {}/{} is not a valid FlowLang package (missing config.flowlang.json)",
-                    spec.owner, spec.repo
+                    "Package {} is not a valid FlowLang package (missing config.flowlang.json)",
+                    spec.display_name()
                 ),
                 0, 0
             ));
         }
-        Ok(())
+        ProjectConfig::load(&config_path)?.check_package(&spec.repo)
     }
 
-    /// Install all packages from config
-    pub fn install_all(&self, config: &ProjectConfig) -> Result<HashMap<String, PathBuf>, FlowError> {
+    /// Install all packages from config at the commits in flow.lock, then rewrite the
+    /// lockfile without packages the config no longer lists. With `update`, every ref
+    /// is resolved again instead.
+    pub fn install_all(&self, config: &ProjectConfig, update: bool) -> Result<HashMap<String, PathBuf>, FlowError> {
+        let mut lock = if update { Lockfile::default() } else { Lockfile::load(&self.project_root)? };
         let mut installed = HashMap::new();
+        let mut sources = Vec::new();
 
-        for (alias, url) in &config.packages {
+        let mut aliases: Vec<_> = config.packages.iter().collect();
+        aliases.sort();
+        for (alias, url) in aliases {
             let spec = PackageSpec::parse(url)?;
-            let path = self.fetch_package(&spec)?;
+            let path = self.install(&spec, &mut lock, &config.registries)?;
+            sources.push(spec.source());
             installed.insert(alias.clone(), path);
         }
 
+        lock.packages.retain(|package| sources.contains(&package.source));
+        if !lock.packages.is_empty() || self.project_root.join(LOCK_FILE).exists() {
+            lock.save(&self.project_root)?;
+        }
         Ok(installed)
     }
 }
@@ -229,4 +767,60 @@ mod tests {
         let spec = PackageSpec::parse("github.com/user/repo").unwrap();
         assert_eq!(spec.git_ref, "main");
     }
+
+    #[test]
+    fn test_parse_git_and_registry_specs() {
+        let spec = PackageSpec::parse("git+ssh://git@git.acme.internal/team/utils.flow#v1.2.0").unwrap();
+        assert_eq!(spec.clone_url(), "ssh://git@git.acme.internal/team/utils.flow");
+        assert_eq!(spec.git_ref, "v1.2.0");
+        assert_eq!(spec.local_path(), PathBuf::from("git.acme.internal/team/utils.flow"));
+        assert_eq!(spec.source(), "git+ssh://git@git.acme.internal/team/utils.flow#v1.2.0");
+
+        let spec = PackageSpec::parse("git+https://git.acme.internal:8443/a/b/tools.git").unwrap();
+        assert_eq!(spec.git_ref, "main");
+        assert_eq!(spec.local_path(), PathBuf::from("git.acme.internal/a/b/tools"));
+
+        let spec = PackageSpec::parse("acme:utils@^1.2").unwrap();
+        assert_eq!(spec.kind, SourceKind::Registry);
+        assert_eq!((spec.host.as_str(), spec.repo.as_str(), spec.git_ref.as_str()), ("acme", "utils", "^1.2"));
+        assert_eq!(spec.local_path(), PathBuf::from("acme/utils"));
+        assert_eq!(PackageSpec::parse("acme:utils").unwrap().source(), "acme:utils@*");
+
+        assert!(PackageSpec::parse("git+ssh://host#v1").is_err());
+        assert!(PackageSpec::parse("acme:../utils").is_err());
+        assert!(PackageSpec::parse("acme:utils@banana").is_err());
+    }
+
+    #[test]
+    fn test_lockfile_round_trip_and_checksum() {
+        let dir = std::env::temp_dir().join(format!("flowlang_lock_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("pkg/src")).unwrap();
+        fs::create_dir_all(dir.join("pkg/.git")).unwrap();
+        fs::write(dir.join("pkg/src/main.flow"), "@export let v = 1\n").unwrap();
+
+        let checksum = content_checksum(&dir.join("pkg")).unwrap();
+        // .git is not part of the package's content
+        fs::write(dir.join("pkg/.git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        assert_eq!(content_checksum(&dir.join("pkg")).unwrap(), checksum);
+        fs::write(dir.join("pkg/src/main.flow"), "@export let v = 2\n").unwrap();
+        assert_ne!(content_checksum(&dir.join("pkg")).unwrap(), checksum);
+
+        let mut lock = Lockfile::load(&dir).unwrap();
+        assert!(lock.packages.is_empty());
+        let spec = PackageSpec::parse("github.com/user/repo").unwrap();
+        lock.insert(LockedPackage {
+            source: spec.source(),
+            version: "1.0.0".to_string(),
+            commit: Some("0123abcd".to_string()),
+            checksum: checksum.clone(),
+        });
+        lock.save(&dir).unwrap();
+
+        let loaded = Lockfile::load(&dir).unwrap();
+        assert_eq!(loaded, lock);
+        assert_eq!(loaded.get("github.com/user/repo@main").unwrap().checksum, checksum);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
\ No newline at end of file
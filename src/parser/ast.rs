@@ -1,3 +1,4 @@
+use crate::optimizer::InlineCache;
 use crate::types::EssenceType;
 use serde::{Serialize, Deserialize};
 
@@ -47,7 +48,7 @@ pub enum Statement {
         params: Vec<Parameter>,
         return_type: Option<EssenceType>,
         body: Vec<Statement>,
-        sigils: Vec<String>,
+        sigils: Vec<SigilTag>,
         is_exported: bool,  // NEW: Track if exported
         line: usize,
     },
@@ -111,6 +112,11 @@ pub enum Statement {
     },
     Ward {
         body: Vec<Statement>,
+        /// `ward as e { ... } on_absorb { ... }`: runs with the absorbed error bound
+        #[serde(default)]
+        binding: Option<String>,
+        #[serde(default)]
+        on_absorb: Option<Vec<Statement>>,
         line: usize,
     },
     BreakSeal {
@@ -130,11 +136,17 @@ pub enum Statement {
     Rupture {
         error_type: String,  // "Rift", "Glitch", "VoidTear", "Spirit"
         message: Expression,
+        /// `caused_by e`: the error this one was raised while handling
+        #[serde(default)]
+        cause: Option<Expression>,
         line: usize,
     },
     SigilDecl {
         name: String,
         fields: Vec<SigilField>,
+        /// Declared `sigil error Name { ... }`, so it can be ruptured and rescued
+        #[serde(default)]
+        is_error: bool,
         is_exported: bool,
         line: usize,
     },
@@ -145,6 +157,8 @@ pub struct RescueClause {
     pub error_type: Option<String>, // e.g., "Rift", "Glitch", None for catch-all
     pub binding: Option<String>,     // Variable to bind error to (e.g., "e")
     pub retry_count: Option<usize>,  // For "rescue retry 3"
+    #[serde(default)]
+    pub backoff_ms: Option<u64>,     // For "retry 3 backoff 500ms": first delay, doubled each retry
     pub body: Vec<Statement>,
 }
 
@@ -157,6 +171,9 @@ pub enum PhaseKind {
     },
     ForEach {
         variable: String,
+        /// Second binding in `enter Phase key, value in relic` (or `index, item in arr`)
+        #[serde(default)]
+        value_variable: Option<String>,
         collection: Expression,
     },
     Until {
@@ -165,12 +182,49 @@ pub enum PhaseKind {
     Forever,
 }
 
+/// A sigil written above a declaration: `@export`, `@memo`, `@deprecated("use render")`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigilTag {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Vec<Expression>,
+}
+
+impl SigilTag {
+    /// The first argument when it is a Silk literal, e.g. the message of `@deprecated("...")`
+    pub fn message(&self) -> Option<&str> {
+        match self.arguments.first() {
+            Some(Expression::String(text)) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+/// Sigils with a meaning of their own. Any other sigil on a Spell names a decorator
+/// Spell, if one is in scope.
+pub const BUILTIN_SIGILS: &[&str] = &["export", "memo", "deprecated", "todo"];
+
+/// Whether `name` is among `sigils`
+pub fn has_sigil(sigils: &[SigilTag], name: &str) -> bool {
+    sigils.iter().any(|s| s.name == name)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Parameter {
     pub name: String,
     pub type_annotation: Option<EssenceType>,
 }
 
+/// A stretch of source: where it starts (1-based line and character column, like
+/// token positions) and the bytes it covers
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+    pub len: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SigilField {
     pub name: String,
@@ -181,7 +235,12 @@ pub struct SigilField {
 pub enum Expression {
     Number(f64),
     String(String),
-    InterpolatedString(Vec<Expression>),
+    /// Literal text and `${...}` expressions in source order. `spans[i]` is where
+    /// `parts[i]` sits: the text segment with its delimiters, or the expression alone.
+    InterpolatedString {
+        parts: Vec<Expression>,
+        spans: Vec<Span>,
+    },
     Boolean(bool),
     Identifier(String),
     
@@ -205,6 +264,9 @@ pub enum Expression {
         object: Box<Expression>,
         method: String,
         arguments: Vec<Expression>,
+        /// Where `method` was found last time the object was a Relic
+        #[serde(skip)]
+        cache: InlineCache,
     },
     
     Await {
@@ -218,6 +280,9 @@ pub enum Expression {
     Index {
         object: Box<Expression>,
         index: Box<Expression>,
+        /// Where the key was found last time the object was a Relic
+        #[serde(skip)]
+        cache: InlineCache,
     },
     
     Relic {
@@ -244,6 +309,14 @@ pub enum Expression {
         fields: Vec<(String, Expression)>,
         line: usize,
     },
+    
+    /// `attempt expr otherwise [as e ->] fallback`: the fallback's value if `expr` raises
+    Attempt {
+        expr: Box<Expression>,
+        binding: Option<String>,
+        fallback: Box<Expression>,
+        line: usize,
+    },
 }
 
 // NEW: Body type for inline Spells
@@ -255,8 +328,12 @@ pub enum InlineSpellBody {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ChainOperation {
+    /// `>> name(args)`
     Call(String, Vec<Expression>),
+    /// `>> name`
     Method(String),
+    /// `>> mod.name` or `>> mod.name(args)`: the module, the function and its arguments
+    Member(Expression, String, Vec<Expression>),
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -266,6 +343,8 @@ pub enum BinaryOp {
     Multiply,
     Divide,
     Modulo,
+    Power,
+    FloorDivide,
     
     IsEqual,
     NotEqual,
@@ -283,3 +362,66 @@ pub enum UnaryOp {
     Negate,
     Minus,
 }
+
+impl Statement {
+    /// Source line the statement starts on
+    pub fn line(&self) -> usize {
+        match self {
+            Statement::Let { line, .. }
+            | Statement::Seal { line, .. }
+            | Statement::Assignment { line, .. }
+            | Statement::FunctionDecl { line, .. }
+            | Statement::Ritual { line, .. }
+            | Statement::Return { line, .. }
+            | Statement::Stance { line, .. }
+            | Statement::Aura { line, .. }
+            | Statement::Phase { line, .. }
+            | Statement::Expression { line, .. }
+            | Statement::Wait { line, .. }
+            | Statement::Perform { line, .. }
+            | Statement::Attempt { line, .. }
+            | Statement::Panic { line, .. }
+            | Statement::Rebound { line, .. }
+            | Statement::Ward { line, .. }
+            | Statement::BreakSeal { line }
+            | Statement::FractureSeal { line }
+            | Statement::ShatterGrandSeal { line, .. }
+            | Statement::Wound { line, .. }
+            | Statement::Rupture { line, .. }
+            | Statement::SigilDecl { line, .. } => *line,
+        }
+    }
+}
+
+impl BinaryOp {
+    /// Source spelling of the operator
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Subtract => "-",
+            BinaryOp::Multiply => "*",
+            BinaryOp::Divide => "/",
+            BinaryOp::Modulo => "%",
+            BinaryOp::Power => "**",
+            BinaryOp::FloorDivide => "//",
+            BinaryOp::IsEqual => "is~",
+            BinaryOp::NotEqual => "not~",
+            BinaryOp::Greater => ">>",
+            BinaryOp::Less => "<<",
+            BinaryOp::GreaterEq => ">>=",
+            BinaryOp::LessEq => "<<=",
+            BinaryOp::Both => "both!",
+            BinaryOp::Either => "either!",
+        }
+    }
+}
+
+impl UnaryOp {
+    /// Source spelling of the operator
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            UnaryOp::Negate => "negate!",
+            UnaryOp::Minus => "-",
+        }
+    }
+}
@@ -1,18 +1,42 @@
 pub mod ast;
 
 use ast::*;
-use crate::error::FlowError;
+use crate::error::{FixIt, FlowError};
 use crate::lexer::token::{Token, TokenKind};
+use crate::optimizer::InlineCache;
 use crate::types::EssenceType;
 
+/// Deepest nesting of blocks, brackets and prefix operators the parser accepts.
+/// Each level recurses, so without a limit a hostile file overflows the stack
+/// instead of producing a syntax error.
+const MAX_NESTING_DEPTH: usize = 64;
+
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    depth: usize,
+    /// Errors of statements skipped so far; only `parse_all` collects them
+    recovered: Option<Vec<FlowError>>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Parser { tokens, current: 0 }
+        Parser { tokens, current: 0, depth: 0, recovered: None }
+    }
+    
+    /// Run `parse` one nesting level deeper, failing once the limit is reached
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, FlowError>) -> Result<T, FlowError> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(FlowError::syntax(
+                &format!("The flow is nested too deeply! More than {} levels of blocks, brackets or operators.", MAX_NESTING_DEPTH),
+                self.peek().line,
+                self.peek().column,
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
     }
     
     pub fn parse(&mut self) -> Result<Program, FlowError> {
@@ -26,12 +50,71 @@ impl Parser {
         
         // Parse statements
         while !self.is_at_end() {
-            statements.push(self.parse_statement()?);
+            if let Some(stmt) = self.parse_statement_recovering()? {
+                statements.push(stmt);
+            }
         }
         
         Ok(Program { imports, statements })
     }
     
+    /// A statement, or None if it failed and the error was recorded (`parse_all` only)
+    /// or there was only a stray `;`
+    fn parse_statement_recovering(&mut self) -> Result<Option<Statement>, FlowError> {
+        if self.match_token(&TokenKind::Semicolon) {
+            return Ok(None);
+        }
+        let start = self.current;
+        match self.parse_statement().and_then(|stmt| self.end_statement().map(|_| stmt)) {
+            Ok(stmt) => Ok(Some(stmt)),
+            Err(e) => match self.recovered.as_mut() {
+                Some(errors) => {
+                    errors.push(e);
+                    self.synchronize(start);
+                    Ok(None)
+                }
+                None => Err(e),
+            },
+        }
+    }
+    
+    // A statement ends at a line break, a `;`, the `}` closing its block or the end of
+    // the file. Without this, `let a = 1 let b = 2` would quietly read as two statements
+    // and a slip like `shout(a) b` would go unreported.
+    fn end_statement(&mut self) -> Result<(), FlowError> {
+        if self.match_token(&TokenKind::Semicolon) || self.check(&TokenKind::RightBrace) || self.is_at_end() {
+            return Ok(());
+        }
+        let token = self.peek();
+        if token.line > self.previous().line {
+            return Ok(());
+        }
+        Err(FlowError::syntax_with_fix(
+            &format!("Expected a new line or ';' before '{}'; only one statement fits on a line without one", token.lexeme),
+            token.line,
+            token.column,
+            FixIt::replace("insert ';' to end the statement", token.line, token.column, 0, "; "),
+        ))
+    }
+    
+    // Panic-mode recovery: skip the rest of a failed statement, up to the first token of
+    // a line (or a ';') back at the depth the statement started at. A '}' closing the
+    // enclosing block is left for that block.
+    fn synchronize(&mut self, start: usize) {
+        let mut depth: i32 = self.tokens[start..self.current].iter().map(|t| bracket_depth(&t.kind)).sum();
+        while !self.is_at_end() {
+            let token = self.peek();
+            let made_progress = self.current > start;
+            let starts_line = self.current == 0 || token.line > self.tokens[self.current - 1].line;
+            let ends_statement = matches!(token.kind, TokenKind::RightBrace | TokenKind::Semicolon);
+            if made_progress && depth <= 0 && (starts_line || ends_statement) {
+                break;
+            }
+            depth += bracket_depth(&token.kind);
+            self.advance();
+        }
+    }
+    
     fn parse_import(&mut self) -> Result<Import, FlowError> {
         let line = self.previous().line;
         
@@ -177,18 +260,29 @@ impl Parser {
     fn parse_statement(&mut self) -> Result<Statement, FlowError> {
         let line = self.peek().line;
         
-        // Skip sigils and store them
+        // Collect sigils; `@name(args)` takes arguments when '(' follows on the same line
         let mut sigils = Vec::new();
         while let TokenKind::Sigil(name) = &self.peek().kind {
-            sigils.push(name.clone());
-            self.advance();
+            let name = name.clone();
+            let sigil_line = self.advance().line;
+            let mut arguments = Vec::new();
+            if self.check(&TokenKind::LeftParen) && self.peek().line == sigil_line {
+                self.advance(); // consume '('
+                arguments = self.parse_arguments()?;
+                self.expect(&TokenKind::RightParen, "Expected ')' after sigil arguments")?;
+            }
+            sigils.push(SigilTag { name, arguments });
+        }
+        
+        if let Some(error) = self.foreign_keyword_error() {
+            return Err(error);
         }
         
         match &self.peek().kind {
-            TokenKind::Let => self.parse_let(sigils.clone()),
-            TokenKind::Seal => self.parse_seal(sigils.clone()),
+            TokenKind::Let => self.parse_let(&sigils),
+            TokenKind::Seal => self.parse_seal(&sigils),
             TokenKind::CastSpell => self.parse_function(sigils),
-            TokenKind::Ritual => self.parse_ritual(sigils.clone()),
+            TokenKind::Ritual => self.parse_ritual(&sigils),
             TokenKind::Return => self.parse_return(),
             TokenKind::InStance => self.parse_stance(),
             TokenKind::InvokeAura => self.parse_aura(),
@@ -199,13 +293,13 @@ impl Parser {
             TokenKind::Panic => self.parse_panic(),
             TokenKind::Wound => self.parse_wound(),
             TokenKind::Rupture => self.parse_rupture(),
-            TokenKind::Attempt => self.parse_attempt(),
+            TokenKind::Attempt if self.peek_at(1).kind == TokenKind::LeftBrace => self.parse_attempt(),
             TokenKind::Rebound => self.parse_rebound(),
             TokenKind::Ward => self.parse_ward(),
             TokenKind::Break => self.parse_break_seal(),
             TokenKind::Fracture => self.parse_fracture_seal(),
             TokenKind::Shatter => self.parse_shatter_grand_seal(),
-            TokenKind::SigilDef => self.parse_sigil_def(sigils.clone()),
+            TokenKind::SigilDef => self.parse_sigil_def(&sigils),
             _ => {
                 // Check if this is an assignment (identifier = expression)
                 if let TokenKind::Identifier(name) = &self.peek().kind {
@@ -221,6 +315,19 @@ impl Parser {
                             value,
                             line,
                         });
+                    } else if let Some(operator) = compound_operator(&self.peek().kind) {
+                        // `x += 1` is `x = x + 1`
+                        self.advance();
+                        let value = self.parse_expression()?;
+                        return Ok(Statement::Assignment {
+                            value: Expression::Binary {
+                                left: Box::new(Expression::Identifier(var_name.clone())),
+                                operator,
+                                right: Box::new(value),
+                            },
+                            name: var_name,
+                            line,
+                        });
                     } else {
                         // Not an assignment, backtrack and parse as expression
                         self.current = start_pos;
@@ -233,7 +340,7 @@ impl Parser {
         }
     }
     
-    fn parse_let(&mut self, sigils: Vec<String>) -> Result<Statement, FlowError> {
+    fn parse_let(&mut self, sigils: &[SigilTag]) -> Result<Statement, FlowError> {
         let line = self.peek().line;
         self.advance(); // consume 'let'
         
@@ -249,7 +356,7 @@ impl Parser {
         let value = self.parse_expression()?;
         
         // Check if @export sigil is present
-        let is_exported = sigils.contains(&"export".to_string());
+        let is_exported = has_sigil(sigils, "export");
         
         Ok(Statement::Let {
             name,
@@ -260,7 +367,7 @@ impl Parser {
         })
     }
     
-    fn parse_seal(&mut self, sigils: Vec<String>) -> Result<Statement, FlowError> {
+    fn parse_seal(&mut self, sigils: &[SigilTag]) -> Result<Statement, FlowError> {
         let line = self.peek().line;
         self.advance(); // consume 'seal'
         
@@ -276,7 +383,7 @@ impl Parser {
         let value = self.parse_expression()?;
         
         // Check if @export sigil is present
-        let is_exported = sigils.contains(&"export".to_string());
+        let is_exported = has_sigil(sigils, "export");
         
         Ok(Statement::Seal {
             name,
@@ -287,11 +394,16 @@ impl Parser {
         })
     }
     
-    fn parse_function(&mut self, sigils: Vec<String>) -> Result<Statement, FlowError> {
+    fn parse_function(&mut self, sigils: Vec<SigilTag>) -> Result<Statement, FlowError> {
         let line = self.peek().line;
         self.advance(); // consume 'cast Spell'
         
-        let name = self.expect_identifier("Expected function name after 'cast Spell'")?;
+        // `retry` is only a keyword inside rescue clauses, so it can name a decorator Spell
+        let name = if self.match_token(&TokenKind::Retry) {
+            "retry".to_string()
+        } else {
+            self.expect_identifier("Expected function name after 'cast Spell'")?
+        };
         
         self.expect(&TokenKind::LeftParen, "Expected '(' after function name")?;
         
@@ -312,7 +424,7 @@ impl Parser {
         self.expect(&TokenKind::RightBrace, "Expected '}' after function body")?;
         
         // Check if @export sigil is present
-        let is_exported = sigils.contains(&"export".to_string());
+        let is_exported = has_sigil(&sigils, "export");
         
         Ok(Statement::FunctionDecl {
             name,
@@ -325,7 +437,7 @@ impl Parser {
         })
     }
     
-    fn parse_ritual(&mut self, sigils: Vec<String>) -> Result<Statement, FlowError> {
+    fn parse_ritual(&mut self, sigils: &[SigilTag]) -> Result<Statement, FlowError> {
         let line = self.peek().line;
         self.advance(); // consume 'ritual'
         
@@ -344,13 +456,13 @@ impl Parser {
         
         let mut body = Vec::new();
         while !self.check(&TokenKind::End) && !self.is_at_end() {
-            body.push(self.parse_statement()?);
+            body.push(self.nested(Self::parse_statement)?);
         }
         
         self.expect(&TokenKind::End, "Expected 'end' after ritual body")?;
         
         // Check if @export sigil is present
-        let is_exported = sigils.contains(&"export".to_string());
+        let is_exported = has_sigil(sigils, "export");
         
         Ok(Statement::Ritual {
             name,
@@ -362,10 +474,17 @@ impl Parser {
         })
     }
     
-    fn parse_sigil_def(&mut self, sigils: Vec<String>) -> Result<Statement, FlowError> {
+    fn parse_sigil_def(&mut self, sigils: &[SigilTag]) -> Result<Statement, FlowError> {
         let line = self.peek().line;
         self.advance(); // consume 'sigil'
         
+        // `sigil error Name { ... }` declares an error type
+        let is_error = matches!(&self.peek().kind, TokenKind::Identifier(word) if word == "error")
+            && matches!(self.peek_at(1).kind, TokenKind::Identifier(_));
+        if is_error {
+            self.advance();
+        }
+        
         // Get sigil name
         let name = if let TokenKind::Identifier(n) = &self.peek().kind {
             let name = n.clone();
@@ -436,11 +555,12 @@ impl Parser {
         }
         
         // Check for @export sigil
-        let is_exported = sigils.contains(&"export".to_string());
+        let is_exported = has_sigil(sigils, "export");
         
         Ok(Statement::SigilDecl {
             name,
             fields,
+            is_error,
             is_exported,
             line,
         })
@@ -508,7 +628,7 @@ impl Parser {
         
         self.expect(&TokenKind::LeftParen, "Expected '(' after 'in Stance'")?;
         let condition = self.parse_expression()?;
-        self.expect(&TokenKind::RightParen, "Expected ')' after condition")?;
+        self.expect_condition_end()?;
         
         self.expect(&TokenKind::LeftBrace, "Expected '{' after condition")?;
         let then_branch = self.parse_block()?;
@@ -518,7 +638,7 @@ impl Parser {
         while self.match_token(&TokenKind::ShiftStance) {
             self.expect(&TokenKind::LeftParen, "Expected '(' after 'shift Stance'")?;
             let shift_condition = self.parse_expression()?;
-            self.expect(&TokenKind::RightParen, "Expected ')' after condition")?;
+            self.expect_condition_end()?;
             
             self.expect(&TokenKind::LeftBrace, "Expected '{' after condition")?;
             let shift_body = self.parse_block()?;
@@ -534,7 +654,8 @@ impl Parser {
             Some(abandon_body)
         } else if self.check(&TokenKind::Otherwise) {
             // User mistakenly used 'otherwise' instead of 'abandon Stance'
-            return Err(FlowError::syntax(
+            let token = self.peek();
+            return Err(FlowError::syntax_with_fix(
                 "'otherwise' cannot be used here!\n\n\
                  💡 TIP: Use 'abandon Stance' for else in if statements:\n\
                     } abandon Stance {\n\
@@ -544,9 +665,12 @@ impl Parser {
                         when 1 -> shout(\"one\")\n\
                         otherwise -> shout(\"default\")\n\
                     }",
-                self.peek().line,
-                self.peek().column,
+                token.line,
+                token.column,
+                FixIt::replace("replace 'otherwise' with 'abandon Stance'", token.line, token.column, token.lexeme.chars().count(), "abandon Stance"),
             ));
+        } else if let Some(error) = self.else_branch_error() {
+            return Err(error);
         } else {
             None
         };
@@ -628,16 +752,30 @@ impl Parser {
         } else if self.match_token(&TokenKind::Until) {
             self.expect(&TokenKind::LeftParen, "Expected '(' after 'until'")?;
             let condition = self.parse_expression()?;
-            self.expect(&TokenKind::RightParen, "Expected ')' after condition")?;
+            self.expect_condition_end()?;
             PhaseKind::Until { condition }
         } else {
             // Could be: enter Phase i from 0 to 5  OR  enter Phase item in collection
+            //      OR  enter Phase key, value in relic
             let variable = self.expect_identifier("Expected loop variable")?;
+            let value_variable = if self.match_token(&TokenKind::Comma) {
+                let value_variable = self.expect_identifier("Expected a second loop variable after ','")?;
+                if !self.check(&TokenKind::In) {
+                    return Err(FlowError::syntax(
+                        "Expected 'in' after two loop variables",
+                        self.peek().line,
+                        self.peek().column,
+                    ));
+                }
+                Some(value_variable)
+            } else {
+                None
+            };
             
             if self.match_token(&TokenKind::In) {
                 // For-each loop: enter Phase item in collection
                 let collection = self.parse_expression()?;
-                PhaseKind::ForEach { variable, collection }
+                PhaseKind::ForEach { variable, value_variable, collection }
             } else {
                 // Count loop: enter Phase i from 0 to 5
                 self.expect(&TokenKind::From, "Expected 'from' or 'in' in phase loop")?;
@@ -659,7 +797,9 @@ impl Parser {
         let mut statements = Vec::new();
         
         while !self.check(&TokenKind::RightBrace) && !self.is_at_end() {
-            statements.push(self.parse_statement()?);
+            if let Some(stmt) = self.nested(Self::parse_statement_recovering)? {
+                statements.push(stmt);
+            }
         }
         
         Ok(statements)
@@ -758,49 +898,90 @@ impl Parser {
     }
     
     fn parse_expression(&mut self) -> Result<Expression, FlowError> {
-        self.parse_combo_chain()
+        self.nested(Self::parse_combo_chain)
     }
     
     fn parse_combo_chain(&mut self) -> Result<Expression, FlowError> {
-        let mut expr = self.parse_logical_or()?;
+        let expr = self.parse_logical_or()?;
+        if !self.is_combo_chain() {
+            return Ok(expr);
+        }
         
-        // Check for combo chain operator >>
-        if self.check(&TokenKind::Greater) {
-            let mut operations = Vec::new();
+        let mut operations = Vec::new();
+        while self.match_token(&TokenKind::Greater) {
+            let name = self.expect_identifier("Expected a Spell or method name after '>>'")?;
             
-            while self.match_token(&TokenKind::Greater) {
-                // Parse chain operation (function call or method)
-                if let TokenKind::Identifier(name) = &self.peek().kind {
-                    let op_name = name.clone();
-                    self.advance();
-                    
-                    // Check if it's a function call with arguments
-                    if self.match_token(&TokenKind::LeftParen) {
-                        let args = self.parse_arguments()?;
-                        self.expect(&TokenKind::RightParen, "Expected ')' after arguments")?;
-                        operations.push(ChainOperation::Call(op_name, args));
-                    } else {
-                        operations.push(ChainOperation::Method(op_name));
-                    }
-                } else {
-                    break;
-                }
-                
-                // Check for chain end !!
-                if self.match_token(&TokenKind::ChainEnd) {
-                    break;
-                }
+            // `>> mymod.clean` names a module function; the last name is the one called
+            let mut path = Vec::new();
+            let mut method = name;
+            while self.match_token(&TokenKind::Dot) {
+                path.push(method);
+                method = self.expect_identifier("Expected a name after '.'")?;
             }
             
-            if !operations.is_empty() {
-                expr = Expression::ComboChain {
-                    initial: Box::new(expr),
-                    operations,
-                };
+            let arguments = if self.match_token(&TokenKind::LeftParen) {
+                let arguments = self.parse_arguments()?;
+                self.expect(&TokenKind::RightParen, "Expected ')' after arguments")?;
+                Some(arguments)
+            } else {
+                None
+            };
+            
+            let mut names = path.into_iter();
+            operations.push(match (names.next(), arguments) {
+                (Some(first), arguments) => {
+                    let object = names.fold(Expression::Identifier(first), |object, name| Expression::Index {
+                        object: Box::new(object),
+                        index: Box::new(Expression::String(name)),
+                        cache: InlineCache::new(),
+                    });
+                    ChainOperation::Member(object, method, arguments.unwrap_or_default())
+                }
+                (None, Some(arguments)) => ChainOperation::Call(method, arguments),
+                (None, None) => ChainOperation::Method(method),
+            });
+            
+            if self.match_token(&TokenKind::ChainEnd) {
+                break;
             }
         }
         
-        Ok(expr)
+        Ok(Expression::ComboChain {
+            initial: Box::new(expr),
+            operations,
+        })
+    }
+    
+    /// Whether the `>>` ahead starts a combo chain rather than a comparison: a run of
+    /// `>> name`, `>> mod.name` or `>> name(args)` steps closed by `!!`
+    fn is_combo_chain(&self) -> bool {
+        let mut i = self.current;
+        let kind = |i: usize| self.tokens.get(i).map(|t| &t.kind);
+        loop {
+            if !matches!(kind(i), Some(TokenKind::Greater)) || !matches!(kind(i + 1), Some(TokenKind::Identifier(_))) {
+                return false;
+            }
+            i += 2;
+            while matches!(kind(i), Some(TokenKind::Dot)) && matches!(kind(i + 1), Some(TokenKind::Identifier(_))) {
+                i += 2;
+            }
+            if matches!(kind(i), Some(TokenKind::LeftParen)) {
+                let mut depth = 0;
+                loop {
+                    match kind(i) {
+                        Some(TokenKind::Eof) | None => return false,
+                        Some(k) => depth += bracket_depth(k),
+                    }
+                    i += 1;
+                    if depth <= 0 {
+                        break;
+                    }
+                }
+            }
+            if matches!(kind(i), Some(TokenKind::ChainEnd)) {
+                return true;
+            }
+        }
     }
     
     fn parse_logical_or(&mut self) -> Result<Expression, FlowError> {
@@ -857,12 +1038,16 @@ impl Parser {
     fn parse_comparison(&mut self) -> Result<Expression, FlowError> {
         let mut expr = self.parse_term()?;
         
-        while let Some(op) = self.match_tokens(&[
-            TokenKind::Greater,
-            TokenKind::Less,
-            TokenKind::GreaterEq,
-            TokenKind::LessEq,
-        ]) {
+        // A `>>` that starts a combo chain is left for `parse_combo_chain`
+        while !self.is_combo_chain() {
+            let Some(op) = self.match_tokens(&[
+                TokenKind::Greater,
+                TokenKind::Less,
+                TokenKind::GreaterEq,
+                TokenKind::LessEq,
+            ]) else {
+                break;
+            };
             let operator = match op {
                 TokenKind::Greater => BinaryOp::Greater,
                 TokenKind::Less => BinaryOp::Less,
@@ -906,10 +1091,11 @@ impl Parser {
     fn parse_factor(&mut self) -> Result<Expression, FlowError> {
         let mut expr = self.parse_unary()?;
         
-        while let Some(op) = self.match_tokens(&[TokenKind::Star, TokenKind::Slash, TokenKind::Percent]) {
+        while let Some(op) = self.match_tokens(&[TokenKind::Star, TokenKind::Slash, TokenKind::SlashSlash, TokenKind::Percent]) {
             let operator = match op {
                 TokenKind::Star => BinaryOp::Multiply,
                 TokenKind::Slash => BinaryOp::Divide,
+                TokenKind::SlashSlash => BinaryOp::FloorDivide,
                 TokenKind::Percent => BinaryOp::Modulo,
                 _ => unreachable!(),
             };
@@ -933,7 +1119,7 @@ impl Parser {
                 _ => unreachable!(),
             };
             
-            let operand = self.parse_unary()?;
+            let operand = self.nested(Self::parse_unary)?;
             return Ok(Expression::Unary {
                 operator,
                 operand: Box::new(operand),
@@ -941,38 +1127,57 @@ impl Parser {
         }
         
         if self.match_token(&TokenKind::Await) {
-            let expr = self.parse_unary()?;
+            let expr = self.nested(Self::parse_unary)?;
             return Ok(Expression::Await {
                 expr: Box::new(expr),
             });
         }
         
-        self.parse_postfix()
+        self.parse_power()
+    }
+    
+    // `**` binds tighter than a leading minus and groups to the right, as in maths:
+    // -2 ** 2 is -4 and 2 ** 3 ** 2 is 2 ** 9
+    fn parse_power(&mut self) -> Result<Expression, FlowError> {
+        let base = self.parse_postfix()?;
+        if !self.match_token(&TokenKind::StarStar) {
+            return Ok(base);
+        }
+        let exponent = self.nested(Self::parse_unary)?;
+        Ok(Expression::Binary {
+            left: Box::new(base),
+            operator: BinaryOp::Power,
+            right: Box::new(exponent),
+        })
     }
     
     fn parse_postfix(&mut self) -> Result<Expression, FlowError> {
         let mut expr = self.parse_primary()?;
         
         loop {
-            if self.match_token(&TokenKind::LeftParen) {
+            // `(` or `[` starting a line begins a new statement rather than calling or
+            // indexing the line above
+            let same_line = self.peek().line == self.previous().line;
+            if same_line && self.match_token(&TokenKind::LeftParen) {
                 let arguments = self.parse_arguments()?;
                 self.expect(&TokenKind::RightParen, "Expected ')' after arguments")?;
                 expr = Expression::Call {
                     callee: Box::new(expr),
                     arguments,
                 };
-            } else if self.match_token(&TokenKind::LeftBracket) {
+            } else if same_line && self.match_token(&TokenKind::LeftBracket) {
                 let index = self.parse_expression()?;
                 self.expect(&TokenKind::RightBracket, "Expected ']' after index")?;
                 expr = Expression::Index {
                     object: Box::new(expr),
                     index: Box::new(index),
+                    cache: InlineCache::new(),
                 };
             } else if self.match_token(&TokenKind::Dot) {
                 let name = self.expect_identifier("Expected property name after '.'")?;
                 
-                // Check if this is a method call (followed by '(')
-                if self.check(&TokenKind::LeftParen) {
+                // Check if this is a method call (followed by '(' on the same line)
+                if self.check(&TokenKind::LeftParen) && self.peek().line == self.previous().line {
                     self.advance(); // consume '('
                     let arguments = self.parse_arguments()?;
                     self.expect(&TokenKind::RightParen, "Expected ')' after method arguments")?;
@@ -980,12 +1185,14 @@ impl Parser {
                         object: Box::new(expr),
                         method: name,
                         arguments,
+                        cache: InlineCache::new(),
                     };
                 } else {
                     // Property access
                     expr = Expression::Index {
                         object: Box::new(expr),
                         index: Box::new(Expression::String(name)),
+                        cache: InlineCache::new(),
                     };
                 }
             } else {
@@ -1010,22 +1217,26 @@ impl Parser {
             }
             TokenKind::StringPart(s) => {
                 let mut parts = Vec::new();
+                let mut spans = Vec::new();
                 parts.push(Expression::String(s.clone()));
-                self.advance();
+                spans.push(span_of(self.advance()));
                 
                 loop {
                     self.expect(&TokenKind::InterpolationStart, "Expected '${' in interpolated string")?;
+                    let first = span_of(self.peek());
                     parts.push(self.parse_expression()?);
+                    let last = span_of(self.previous());
+                    spans.push(Span { len: last.offset + last.len - first.offset, ..first });
                     self.expect(&TokenKind::RightBrace, "Expected '}' after interpolation")?;
                     
                     match &self.peek().kind {
                         TokenKind::StringPart(s) => {
                             parts.push(Expression::String(s.clone()));
-                            self.advance();
+                            spans.push(span_of(self.advance()));
                         }
                         TokenKind::String(s) => {
                             parts.push(Expression::String(s.clone()));
-                            self.advance();
+                            spans.push(span_of(self.advance()));
                             break;
                         }
                         _ => return Err(FlowError::syntax(
@@ -1035,7 +1246,7 @@ impl Parser {
                         )),
                     }
                 }
-                Ok(Expression::InterpolatedString(parts))
+                Ok(Expression::InterpolatedString { parts, spans })
             }
             TokenKind::True => {
                 self.advance();
@@ -1054,6 +1265,11 @@ impl Parser {
                 self.advance();
                 Ok(Expression::Boolean(false))
             }
+            TokenKind::Retry => {
+                self.advance();
+                Ok(Expression::Identifier("retry".to_string()))
+            }
+            TokenKind::Attempt => self.parse_attempt_expression(),
             TokenKind::Identifier(name) => {
                 let sigil_name = name.clone();
                 let line = self.peek().line;
@@ -1220,6 +1436,120 @@ impl Parser {
         None
     }
     
+    fn identifier_at(&self, offset: usize) -> Option<&str> {
+        match self.tokens.get(self.current + offset).map(|t| &t.kind) {
+            Some(TokenKind::Identifier(name)) => Some(name),
+            _ => None,
+        }
+    }
+    
+    fn kind_at(&self, offset: usize) -> Option<&TokenKind> {
+        self.tokens.get(self.current + offset).map(|t| &t.kind)
+    }
+    
+    // ')' closing a condition. '=' here is almost always a comparison written the
+    // C way, but rewriting it changes meaning, so the fix-it is not applied by default.
+    fn expect_condition_end(&mut self) -> Result<(), FlowError> {
+        if self.check(&TokenKind::Equals) {
+            let token = self.peek();
+            return Err(FlowError::syntax_with_fix(
+                "'=' assigns, it doesn't compare. Use 'is~' in conditions.",
+                token.line,
+                token.column,
+                FixIt::replace("replace '=' with 'is~'", token.line, token.column, 1, "is~").unsafe_fix(),
+            ));
+        }
+        self.expect(&TokenKind::RightParen, "Expected ')' after condition")
+    }
+    
+    // `else { ... }` / `else if (...) { ... }` after a Stance
+    fn else_branch_error(&self) -> Option<FlowError> {
+        if self.identifier_at(0) != Some("else") {
+            return None;
+        }
+        let token = self.peek();
+        if self.identifier_at(1) == Some("if") && matches!(self.kind_at(2), Some(TokenKind::LeftParen)) {
+            let if_token = &self.tokens[self.current + 1];
+            if if_token.line != token.line {
+                return None;
+            }
+            let len = if_token.column + 2 - token.column;
+            return Some(FlowError::syntax_with_fix(
+                "'else if' is written 'shift Stance' in FlowLang.",
+                token.line,
+                token.column,
+                FixIt::replace("replace 'else if' with 'shift Stance'", token.line, token.column, len, "shift Stance"),
+            ));
+        }
+        if matches!(self.kind_at(1), Some(TokenKind::LeftBrace)) {
+            return Some(FlowError::syntax_with_fix(
+                "'else' is written 'abandon Stance' in FlowLang.",
+                token.line,
+                token.column,
+                FixIt::replace("replace 'else' with 'abandon Stance'", token.line, token.column, 4, "abandon Stance"),
+            ));
+        }
+        None
+    }
+    
+    // Keywords from other languages at the start of a statement: `if (...) {`,
+    // `function name(`, `var name =`. Only shapes that can't be valid FlowLang match.
+    fn foreign_keyword_error(&self) -> Option<FlowError> {
+        let token = self.peek();
+        let word = self.identifier_at(0)?;
+        let same_line = |offset: usize| {
+            self.tokens.get(self.current + offset).is_some_and(|t| t.line == token.line)
+        };
+        
+        let (message, replacement) = match word {
+            "if" if matches!(self.kind_at(1), Some(TokenKind::LeftParen)) => {
+                // Find the matching ')' and require a '{' after it
+                let mut depth = 0;
+                let mut offset = 1;
+                loop {
+                    match self.kind_at(offset)? {
+                        TokenKind::LeftParen => depth += 1,
+                        TokenKind::RightParen => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        TokenKind::Eof => return None,
+                        _ => {}
+                    }
+                    offset += 1;
+                }
+                if !matches!(self.kind_at(offset + 1), Some(TokenKind::LeftBrace)) {
+                    return None;
+                }
+                ("'if' is written 'in Stance' in FlowLang.", "in Stance")
+            }
+            "function" | "fn" | "def" | "func"
+                if self.identifier_at(1).is_some() && matches!(self.kind_at(2), Some(TokenKind::LeftParen)) && same_line(1) =>
+            {
+                ("Spells are declared with 'cast Spell'.", "cast Spell")
+            }
+            "var" | "const"
+                if self.identifier_at(1).is_some() && matches!(self.kind_at(2), Some(TokenKind::Equals)) && same_line(1) =>
+            {
+                if word == "var" {
+                    ("Variables are declared with 'let'.", "let")
+                } else {
+                    ("Constants are declared with 'seal'.", "seal")
+                }
+            }
+            _ => return None,
+        };
+        
+        Some(FlowError::syntax_with_fix(
+            message,
+            token.line,
+            token.column,
+            FixIt::replace(format!("replace '{}' with '{}'", word, replacement), token.line, token.column, word.chars().count(), replacement),
+        ))
+    }
+    
     fn check(&self, kind: &TokenKind) -> bool {
         if self.is_at_end() {
             return false;
@@ -1238,6 +1568,12 @@ impl Parser {
         matches!(self.peek().kind, TokenKind::Eof)
     }
     
+    /// The token `offset` places ahead of the current one (Eof past the end)
+    fn peek_at(&self, offset: usize) -> &Token {
+        let last = self.tokens.len() - 1;
+        &self.tokens[(self.current + offset).min(last)]
+    }
+    
     fn peek(&self) -> &Token {
         &self.tokens[self.current]
     }
@@ -1297,15 +1633,21 @@ impl Parser {
         let line = self.peek().line;
         self.advance(); // consume 'rupture'
         
-        // Expect error type: Rift, Glitch, VoidTear, or Spirit
+        // Expect error type: Rift, Glitch, VoidTear, Spirit or a `sigil error` type
         let error_type = if let TokenKind::Identifier(name) = &self.peek().kind {
             if matches!(name.as_str(), "Rift" | "Glitch" | "VoidTear" | "Spirit") {
                 let err_type = name.clone();
                 self.advance();
                 err_type
+            } else if is_type_name(name) {
+                // The payload is the sigil instance: `rupture DatabaseDown { host: "db1" }`
+                let err_type = name.clone();
+                let message = self.parse_error_instance()?;
+                let cause = self.parse_cause()?;
+                return Ok(Statement::Rupture { error_type: err_type, message, cause, line });
             } else {
                 return Err(FlowError::syntax(
-                    "Expected error type (Rift, Glitch, VoidTear, or Spirit) after 'rupture'",
+                    "Expected error type (Rift, Glitch, VoidTear, Spirit or a sigil error) after 'rupture'",
                     self.peek().line,
                     self.peek().column,
                 ));
@@ -1319,14 +1661,64 @@ impl Parser {
         };
         
         let message = self.parse_expression()?;
+        let cause = self.parse_cause()?;
         
         Ok(Statement::Rupture {
             error_type,
             message,
+            cause,
             line,
         })
     }
     
+    // Optional `caused_by <expr>` after a rupture's message
+    fn parse_cause(&mut self) -> Result<Option<Expression>, FlowError> {
+        if !self.at_caused_by() {
+            return Ok(None);
+        }
+        self.advance(); // consume 'caused_by'
+        Ok(Some(self.parse_expression()?))
+    }
+    
+    fn at_caused_by(&self) -> bool {
+        matches!(&self.peek().kind, TokenKind::Identifier(word) if word == "caused_by")
+    }
+    
+    // `Name { field: value }`, `Name {}` or a bare `Name` for an error sigil without fields
+    fn parse_error_instance(&mut self) -> Result<Expression, FlowError> {
+        let token = self.peek().clone();
+        let TokenKind::Identifier(sigil_name) = token.kind else {
+            unreachable!("called on an identifier");
+        };
+        let braces_follow = self.peek_at(1).kind == TokenKind::LeftBrace && self.peek_at(1).line == token.line;
+        if braces_follow && self.peek_at(2).kind != TokenKind::RightBrace {
+            return match self.parse_expression()? {
+                instance @ Expression::SigilInstance { .. } => Ok(instance),
+                _ => Err(FlowError::syntax(
+                    &format!("Expected '{} {{ field: value }}' after 'rupture'", sigil_name),
+                    token.line,
+                    token.column,
+                )),
+            };
+        }
+        
+        self.advance(); // the name
+        if braces_follow {
+            self.advance(); // '{'
+            self.advance(); // '}'
+        } else if self.peek().line == token.line
+            && !matches!(self.peek().kind, TokenKind::RightBrace | TokenKind::Eof)
+            && !self.at_caused_by()
+        {
+            return Err(FlowError::syntax(
+                &format!("Expected '{{' with the fields of '{}' after the error type", sigil_name),
+                self.peek().line,
+                self.peek().column,
+            ));
+        }
+        Ok(Expression::SigilInstance { sigil_name, fields: Vec::new(), line: token.line })
+    }
+    
     fn parse_attempt(&mut self) -> Result<Statement, FlowError> {
         let line = self.peek().line;
         self.advance(); // consume 'attempt'
@@ -1361,14 +1753,15 @@ impl Parser {
     }
     
     fn parse_rescue_clause(&mut self) -> Result<ast::RescueClause, FlowError> {
-        // rescue [ErrorType] [as binding] [retry N] { body }
+        // rescue [ErrorType] [as binding] [retry N [backoff 500ms]] { body }
         let mut error_type = None;
         let mut binding = None;
         let mut retry_count = None;
+        let mut backoff_ms = None;
         
-        // Check for error type (e.g., "Rift", "Glitch")
+        // Check for error type (e.g., "Rift", "Glitch", or a built-in kind such as "Panic")
         if let TokenKind::Identifier(name) = &self.peek().kind {
-            if matches!(name.as_str(), "Rift" | "Glitch" | "VoidTear" | "Spirit") {
+            if RESCUABLE_ERRORS.contains(&name.as_str()) || is_type_name(name) {
                 error_type = Some(name.clone());
                 self.advance();
             }
@@ -1391,6 +1784,11 @@ impl Parser {
                     self.peek().column,
                 ));
             }
+            
+            if matches!(&self.peek().kind, TokenKind::Identifier(word) if word == "backoff") {
+                self.advance();
+                backoff_ms = Some(self.parse_backoff()?);
+            }
         }
         
         self.expect(&TokenKind::LeftBrace, "Expected '{' after rescue clause")?;
@@ -1401,10 +1799,70 @@ impl Parser {
             error_type,
             binding,
             retry_count,
+            backoff_ms,
             body,
         })
     }
     
+    // `backoff 500ms`, `backoff 2s` or `backoff 1m`; a bare number is milliseconds like `wait`
+    fn parse_backoff(&mut self) -> Result<u64, FlowError> {
+        let amount = match &self.peek().kind {
+            TokenKind::Number(n) if *n >= 0.0 => *n,
+            _ => {
+                return Err(FlowError::syntax(
+                    "Expected a duration such as 500ms after 'backoff'",
+                    self.peek().line,
+                    self.peek().column,
+                ));
+            }
+        };
+        let line = self.advance().line;
+        
+        let factor = match &self.peek().kind {
+            TokenKind::Identifier(unit) if self.peek().line == line => {
+                let factor = match unit.as_str() {
+                    "ms" => 1.0,
+                    "s" => 1000.0,
+                    "m" => 60000.0,
+                    _ => {
+                        return Err(FlowError::syntax(
+                            &format!("Unknown time unit '{}' after 'backoff' (use ms, s or m)", unit),
+                            self.peek().line,
+                            self.peek().column,
+                        ));
+                    }
+                };
+                self.advance();
+                factor
+            }
+            _ => 1.0,
+        };
+        Ok((amount * factor) as u64)
+    }
+    
+    // attempt expr otherwise fallback | attempt expr otherwise as e -> fallback
+    fn parse_attempt_expression(&mut self) -> Result<Expression, FlowError> {
+        let line = self.advance().line; // consume 'attempt'
+        let expr = self.parse_expression()?;
+        
+        self.expect(&TokenKind::Otherwise, "Expected 'otherwise' after the attempted expression")?;
+        let binding = if self.match_token(&TokenKind::As) {
+            let name = self.expect_identifier("Expected variable name after 'as'")?;
+            self.expect(&TokenKind::Arrow, "Expected '->' after the error name")?;
+            Some(name)
+        } else {
+            None
+        };
+        let fallback = self.parse_expression()?;
+        
+        Ok(Expression::Attempt {
+            expr: Box::new(expr),
+            binding,
+            fallback: Box::new(fallback),
+            line,
+        })
+    }
+    
     fn parse_rebound(&mut self) -> Result<Statement, FlowError> {
         let line = self.peek().line;
         self.advance(); // consume 'rebound'
@@ -1425,11 +1883,34 @@ impl Parser {
         let line = self.peek().line;
         self.advance(); // consume 'ward'
         
+        let binding = if self.match_token(&TokenKind::As) {
+            Some(self.expect_identifier("Expected variable name after 'as'")?)
+        } else {
+            None
+        };
+        
         self.expect(&TokenKind::LeftBrace, "Expected '{' after 'ward'")?;
         let body = self.parse_block()?;
         self.expect(&TokenKind::RightBrace, "Expected '}' after ward block")?;
         
-        Ok(Statement::Ward { body, line })
+        // Optional handler for the absorbed error
+        let on_absorb = if matches!(&self.peek().kind, TokenKind::Identifier(word) if word == "on_absorb") {
+            self.advance(); // consume 'on_absorb'
+            self.expect(&TokenKind::LeftBrace, "Expected '{' after 'on_absorb'")?;
+            let handler = self.parse_block()?;
+            self.expect(&TokenKind::RightBrace, "Expected '}' after on_absorb block")?;
+            Some(handler)
+        } else if binding.is_some() {
+            return Err(FlowError::syntax(
+                "'ward as e' needs an 'on_absorb { ... }' block to use the error in",
+                self.peek().line,
+                self.peek().column,
+            ));
+        } else {
+            None
+        };
+        
+        Ok(Statement::Ward { body, binding, on_absorb, line })
     }
     
     
@@ -1563,3 +2044,240 @@ pub fn parse(tokens: Vec<Token>) -> Result<Program, FlowError> {
     let mut parser = Parser::new(tokens);
     parser.parse()
 }
+
+/// Parse a whole file, skipping past each broken statement so every syntax error is
+/// reported at once instead of only the first (`flowlang check`)
+pub fn parse_all(tokens: Vec<Token>) -> Result<Program, Vec<FlowError>> {
+    let mut parser = Parser::new(tokens);
+    parser.recovered = Some(Vec::new());
+    let result = parser.parse();
+    let mut errors = parser.recovered.take().unwrap_or_default();
+    match result {
+        Ok(program) if errors.is_empty() => return Ok(program),
+        Ok(_) => {}
+        Err(e) => errors.push(e),
+    }
+    errors.sort_by_key(|e| e.position());
+    Err(errors)
+}
+
+/// The operator of a compound assignment token (`+=`, `**=`, ...)
+fn compound_operator(kind: &TokenKind) -> Option<BinaryOp> {
+    match kind {
+        TokenKind::PlusEq => Some(BinaryOp::Add),
+        TokenKind::MinusEq => Some(BinaryOp::Subtract),
+        TokenKind::StarEq => Some(BinaryOp::Multiply),
+        TokenKind::SlashEq => Some(BinaryOp::Divide),
+        TokenKind::PercentEq => Some(BinaryOp::Modulo),
+        TokenKind::StarStarEq => Some(BinaryOp::Power),
+        TokenKind::SlashSlashEq => Some(BinaryOp::FloorDivide),
+        _ => None,
+    }
+}
+
+fn span_of(token: &Token) -> Span {
+    Span { line: token.line, column: token.column, offset: token.offset, len: token.len }
+}
+
+// How a token changes bracket nesting
+fn bracket_depth(kind: &TokenKind) -> i32 {
+    match kind {
+        TokenKind::LeftBrace | TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::InterpolationStart => 1,
+        TokenKind::RightBrace | TokenKind::RightParen | TokenKind::RightBracket => -1,
+        _ => 0,
+    }
+}
+
+/// Error kinds a rescue clause can name, as reported by `FlowError::error_type_name`
+const RESCUABLE_ERRORS: &[&str] = &[
+    "Rift", "Glitch", "VoidTear", "Spirit", "Panic", "Wound",
+    "Type", "Runtime", "Undefined", "OutOfRange", "DivisionByZero",
+];
+
+// Error sigils are named like types, which keeps `rescue e { }` a mistake rather than
+// a clause for an error called `e`
+fn is_type_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::tokenize;
+
+    fn parse_source(source: &str) -> Result<Program, FlowError> {
+        parse(tokenize(source)?)
+    }
+
+    #[test]
+    fn test_deep_nesting_is_a_syntax_error() {
+        let parens = format!("let x = {}1{}", "(".repeat(10_000), ")".repeat(10_000));
+        let blocks = format!("{}{}", "in Stance (1) {".repeat(10_000), "}".repeat(10_000));
+        let negations = format!("let x = {}1", "negate! ".repeat(10_000));
+        let templates = format!("let s = `{}{}`", "${`".repeat(10_000), "`}".repeat(10_000));
+        for source in [parens, blocks, negations, templates] {
+            match parse_source(&source) {
+                Err(FlowError::Syntax { message, .. }) => assert!(message.contains("nested too deeply"), "{}", message),
+                other => panic!("expected a nesting error, got {:?}", other.map(|_| ())),
+            }
+        }
+
+        // Reasonable nesting still parses
+        assert!(parse_source(&format!("let x = {}1{}", "(".repeat(50), ")".repeat(50))).is_ok());
+    }
+
+    #[test]
+    fn test_phase_over_pairs() {
+        let program = parse_source("enter Phase key, value in stats {\n    shout(key)\n}\n").unwrap();
+        match &program.statements[0] {
+            Statement::Phase { kind: PhaseKind::ForEach { variable, value_variable, .. }, .. } => {
+                assert_eq!(variable, "key");
+                assert_eq!(value_variable.as_deref(), Some("value"));
+            }
+            other => panic!("expected a phase loop, got {:?}", other),
+        }
+        assert!(parse_source("enter Phase a, b from 1 to 3 {\n}\n").is_err());
+    }
+
+    #[test]
+    fn test_attempt_expression() {
+        let program = parse_source("let cfg = attempt json.parse(text) otherwise as e -> {}\nattempt {\n    shout(1)\n} rescue {\n}\n").unwrap();
+        match &program.statements[0] {
+            Statement::Let { value: Expression::Attempt { binding, fallback, .. }, .. } => {
+                assert_eq!(binding.as_deref(), Some("e"));
+                assert!(matches!(**fallback, Expression::Relic { .. }));
+            }
+            other => panic!("expected an attempt expression, got {:?}", other),
+        }
+        assert!(matches!(program.statements[1], Statement::Attempt { .. }));
+        assert!(parse_source("let x = attempt risky()\n").is_err());
+    }
+
+    #[test]
+    fn test_error_sigils() {
+        let source = "sigil error DatabaseDown {\n    host: Silk\n}\nrupture DatabaseDown { host: \"db1\" }\nattempt {\n    rupture Timeout\n} rescue DatabaseDown as e {\n}\n";
+        let program = parse_source(source).unwrap();
+        assert!(matches!(&program.statements[0], Statement::SigilDecl { is_error: true, .. }));
+        match &program.statements[1] {
+            Statement::Rupture { error_type, message: Expression::SigilInstance { fields, .. }, .. } => {
+                assert_eq!(error_type, "DatabaseDown");
+                assert_eq!(fields.len(), 1);
+            }
+            other => panic!("expected a rupture, got {:?}", other),
+        }
+        match &program.statements[2] {
+            Statement::Attempt { rescue_clauses, .. } => assert_eq!(rescue_clauses[0].error_type.as_deref(), Some("DatabaseDown")),
+            other => panic!("expected an attempt, got {:?}", other),
+        }
+
+        // Error types are capitalized, and take their fields in braces
+        assert!(parse_source("rupture oops \"x\"\n").is_err());
+        assert!(parse_source("rupture Timeout \"x\"\n").is_err());
+        assert!(parse_source("attempt {\n} rescue e {\n}\n").is_err());
+    }
+
+    #[test]
+    fn test_ward_on_absorb() {
+        let program = parse_source("ward as e {\n    risky()\n} on_absorb {\n    shout(e)\n}\nward {\n    risky()\n}\n").unwrap();
+        match &program.statements[0] {
+            Statement::Ward { binding, on_absorb: Some(handler), .. } => {
+                assert_eq!(binding.as_deref(), Some("e"));
+                assert_eq!(handler.len(), 1);
+            }
+            other => panic!("expected a ward with a handler, got {:?}", other),
+        }
+        assert!(matches!(&program.statements[1], Statement::Ward { binding: None, on_absorb: None, .. }));
+        assert!(parse_source("ward as e {\n    risky()\n}\n").is_err());
+    }
+
+    #[test]
+    fn test_rupture_caused_by() {
+        let program = parse_source("rupture Spirit \"config failed\" caused_by e\nrupture Timeout caused_by e\nrupture Rift \"down\"\n").unwrap();
+        let causes: Vec<bool> = program.statements.iter()
+            .map(|stmt| matches!(stmt, Statement::Rupture { cause: Some(Expression::Identifier(name)), .. } if name == "e"))
+            .collect();
+        assert_eq!(causes, vec![true, true, false]);
+    }
+
+    #[test]
+    fn test_interpolation_spans() {
+        let source = "let s = `ñ ${a + b}\n${ c }`\n";
+        let program = parse_source(source).unwrap();
+        let Statement::Let { value: Expression::InterpolatedString { spans, .. }, .. } = &program.statements[0] else {
+            panic!("expected an interpolated string");
+        };
+        let pieces: Vec<(usize, usize, &str)> = spans.iter()
+            .map(|span| (span.line, span.column, &source[span.offset..span.offset + span.len]))
+            .collect();
+        assert_eq!(pieces, vec![(1, 9, "`ñ "), (1, 14, "a + b"), (1, 20, "\n"), (2, 4, "c"), (2, 7, "`")]);
+    }
+
+    #[test]
+    fn test_newlines_end_statements() {
+        // `(` and `[` on a new line start a statement instead of calling or indexing
+        let program = parse_source("let x = f\n(1 + 2)\nlet y = x\n[1, 2].len()\n").unwrap();
+        assert_eq!(program.statements.len(), 4);
+        assert!(matches!(&program.statements[0], Statement::Let { value: Expression::Identifier(_), .. }));
+
+        // A leading '.' still continues a chain
+        let program = parse_source("let s = items\n    .first()\n").unwrap();
+        assert!(matches!(&program.statements[0], Statement::Let { value: Expression::MethodCall { .. }, .. }));
+
+        let program = parse_source("let a = 1; let b = 2;\n;shout(a)\n").unwrap();
+        assert_eq!(program.statements.len(), 3);
+
+        let error = parse_source("let a = 1 let b = 2\n").unwrap_err();
+        assert_eq!(error.position(), (1, 11));
+        assert_eq!(error.fixes()[0].apply("let a = 1 let b = 2\n").as_deref(), Some("let a = 1 ; let b = 2\n"));
+
+        let errors = parse_all(tokenize("let = 1; let b = 2\nshout(b c)\n").unwrap()).unwrap_err();
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn test_combo_chain_needs_chain_end() {
+        let program = parse_source("let a = x + 1 >> f >> m.g(1) >> h(2) !!\nlet b = x >> f\n").unwrap();
+        match &program.statements[0] {
+            Statement::Let { value: Expression::ComboChain { initial, operations }, .. } => {
+                assert!(matches!(initial.as_ref(), Expression::Binary { operator: BinaryOp::Add, .. }));
+                assert!(matches!(&operations[0], ChainOperation::Method(name) if name == "f"));
+                assert!(matches!(&operations[1], ChainOperation::Member(Expression::Identifier(m), g, args) if m == "m" && g == "g" && args.len() == 1));
+                assert!(matches!(&operations[2], ChainOperation::Call(name, args) if name == "h" && args.len() == 1));
+            }
+            other => panic!("expected a combo chain, got {:?}", other),
+        }
+        assert!(matches!(&program.statements[1], Statement::Let { value: Expression::Binary { operator: BinaryOp::Greater, .. }, .. }));
+    }
+
+    #[test]
+    fn test_power_and_compound_assignment() {
+        // -2 ** 2 is -(2 ** 2), and 2 ** 3 ** 2 is 2 ** (3 ** 2)
+        let program = parse_source("let a = -2 ** 2\nlet b = 2 ** 3 ** 2\nb //= 4\n").unwrap();
+        assert!(matches!(&program.statements[0], Statement::Let { value: Expression::Unary { .. }, .. }));
+        match &program.statements[1] {
+            Statement::Let { value: Expression::Binary { operator: BinaryOp::Power, right, .. }, .. } => {
+                assert!(matches!(right.as_ref(), Expression::Binary { operator: BinaryOp::Power, .. }));
+            }
+            other => panic!("expected a power, got {:?}", other),
+        }
+        match &program.statements[2] {
+            Statement::Assignment { name, value: Expression::Binary { left, operator: BinaryOp::FloorDivide, .. }, .. } => {
+                assert_eq!(name, "b");
+                assert!(matches!(left.as_ref(), Expression::Identifier(n) if n == "b"));
+            }
+            other => panic!("expected a compound assignment, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rescue_names_builtin_error_kinds() {
+        for kind in ["Spirit", "Panic", "OutOfRange"] {
+            let source = format!("attempt {{\n    shout(1)\n}} rescue {} as e {{\n    shout(e)\n}}\n", kind);
+            let program = parse_source(&source).unwrap();
+            match &program.statements[0] {
+                Statement::Attempt { rescue_clauses, .. } => assert_eq!(rescue_clauses[0].error_type.as_deref(), Some(kind)),
+                other => panic!("expected an attempt, got {:?}", other),
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Platform compatibility layer
+//!
+//! Everything that behaves differently on Windows goes through here, so the rest of
+//! the interpreter can stay platform-agnostic:
+//! - Canonical paths without the `\\?\` verbatim prefix
+//! - ANSI color support in the Windows console, and whether to color at all
+//! - `--quiet`, which keeps the CLI's own status lines out of script output
+//! - Script arguments passed to `cli.args()` without an environment round-trip
+//! - Finding `.cmd`/`.bat` programs (npm, yarn, ...) that Windows won't launch by bare name
+
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+static SCRIPT_ARGS: OnceLock<Vec<String>> = OnceLock::new();
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Canonicalize a path for use as a module or cache key.
+///
+/// On Windows `fs::canonicalize` returns verbatim paths (`\\?\C:\src\app.flow`) which
+/// can't be joined with `/` segments and read badly in error messages. The prefix is
+/// dropped whenever the path is still valid without it.
+pub fn canonicalize(path: &Path) -> io::Result<PathBuf> {
+    let canonical = std::fs::canonicalize(path)?;
+    Ok(strip_verbatim(canonical))
+}
+
+#[cfg(windows)]
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    let text = path.to_string_lossy().into_owned();
+    if let Some(rest) = text.strip_prefix(r"\\?\UNC\") {
+        return PathBuf::from(format!(r"\\{}", rest));
+    }
+    match text.strip_prefix(r"\\?\") {
+        // Paths over MAX_PATH need the prefix to stay usable
+        Some(rest) if rest.len() < 260 && rest.as_bytes().get(1) == Some(&b':') => PathBuf::from(rest),
+        _ => path,
+    }
+}
+
+#[cfg(not(windows))]
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    path
+}
+
+/// Prepare the terminal for colored output.
+///
+/// Colors are off with `--no-color` or a non-empty `NO_COLOR`, on with
+/// `CLICOLOR_FORCE`, and otherwise only when stdout is a terminal, so piped output
+/// and JSON printed by scripts never carry escape codes. The choice is made once and
+/// applies to std:color, std:cli, error display and the banner alike.
+///
+/// The Windows console only interprets ANSI escapes once virtual terminal processing is
+/// enabled. If that fails (old consoles), colors are turned off instead of printing
+/// raw escape codes.
+pub fn init_terminal(no_color: bool) {
+    let env = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+    let disabled = no_color || env("NO_COLOR").is_some();
+    let forced = env("CLICOLOR_FORCE").is_some_and(|value| value != "0");
+    #[allow(unused_mut)]
+    let mut enabled = !disabled && (forced || io::stdout().is_terminal());
+    #[cfg(windows)]
+    {
+        if enabled && colored::control::set_virtual_terminal(true).is_err() {
+            enabled = false;
+        }
+    }
+    colored::control::set_override(enabled);
+}
+
+/// Whether output is being colored, as decided by `init_terminal`
+pub fn colors_enabled() -> bool {
+    colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+/// With `--quiet`, only the script writes to stdout. Banners, progress notes and
+/// reports from flowlang itself are dropped, and an uncaught error is a single line
+/// on stderr.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Resolve a program name the way a shell would.
+///
+/// `Command::new("npm")` fails on Windows because npm is `npm.cmd`, and only `.exe`
+/// files are found automatically. Names without an extension are looked up on `PATH`
+/// using `PATHEXT`. Elsewhere (or if nothing matches) the name is returned unchanged.
+pub fn resolve_program(program: &str) -> PathBuf {
+    #[cfg(windows)]
+    {
+        let name = Path::new(program);
+        if name.extension().is_none() && name.components().count() == 1 {
+            let extensions = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+            if let Some(paths) = std::env::var_os("PATH") {
+                for dir in std::env::split_paths(&paths) {
+                    for ext in extensions.split(';').filter(|e| !e.is_empty()) {
+                        let candidate = dir.join(format!("{}{}", program, ext.to_lowercase()));
+                        if candidate.is_file() {
+                            return candidate;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    PathBuf::from(program)
+}
+
+/// Record the arguments after the script path for `cli.args()`
+pub fn set_script_args(args: Vec<String>) {
+    let _ = SCRIPT_ARGS.set(args);
+}
+
+/// Arguments passed to the running script
+pub fn script_args() -> &'static [String] {
+    SCRIPT_ARGS.get().map(|args| args.as_slice()).unwrap_or(&[])
+}
@@ -1,84 +1,167 @@
 use std::io::{self, Write};
 use colored::*;
+use crate::error::FlowError;
 use crate::interpreter::Interpreter;
-use crate::parser::ast::Statement;
+use crate::parser::ast::{Program, Statement};
+use crate::types::Value;
+
+/// What the lines typed so far add up to
+enum Entry {
+    Ready(Program),
+    /// An open block or expression; keep reading lines
+    Incomplete,
+    Invalid(FlowError),
+}
+
+fn parse_entry(lines: &[String]) -> Entry {
+    let source = lines.join("\n") + "\n";
+    match crate::lexer::tokenize(&source).and_then(crate::parser::parse) {
+        Ok(program) => Entry::Ready(program),
+        // Errors past the last line mean the parser ran out of input, not that it's wrong
+        Err(e) if e.position().0 > lines.len() => Entry::Incomplete,
+        Err(e) => Entry::Invalid(e),
+    }
+}
+
+/// After a syntax error, keep what was typed before the offending line so a
+/// half-written block doesn't have to be retyped. Returns whether anything was kept.
+fn recover(lines: &mut Vec<String>, error: &FlowError) -> bool {
+    if lines.len() > 1 && error.position().0 == lines.len() {
+        lines.pop();
+        true
+    } else {
+        lines.clear();
+        false
+    }
+}
 
 pub async fn run() {
     println!("{}", "🌊 FlowLang REPL".cyan().bold());
-    println!("{}", "Type 'exit' to quit.".black().italic());
+    println!("{}", "Type 'exit' to quit, '.cancel' to drop an unfinished block. '_' holds the last result.".black().italic());
 
     let mut interpreter = Interpreter::with_dir(
         std::env::current_dir().unwrap(),
         crate::config::ProjectConfig::default()
     );
+    let mut lines: Vec<String> = Vec::new();
 
     loop {
-        print!("{}", "flow> ".green().bold());
+        let prompt = if lines.is_empty() { "flow> " } else { "  ... " };
+        print!("{}", prompt.green().bold());
         io::stdout().flush().unwrap();
 
         let mut input = String::new();
-        if io::stdin().read_line(&mut input).is_err() {
-            break;
+        match io::stdin().read_line(&mut input) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
         }
 
-        let input = input.trim();
-        if input == "exit" {
-            break;
-        }
-        if input.is_empty() {
+        let input = input.trim_end();
+        if lines.is_empty() {
+            match input.trim() {
+                "exit" => break,
+                "" => continue,
+                _ => {}
+            }
+        } else if input.trim() == ".cancel" {
+            lines.clear();
             continue;
         }
+        lines.push(input.to_string());
 
-        // Tokenize
-        let tokens = match crate::lexer::tokenize(input) {
-            Ok(t) => t,
-            Err(e) => {
-                crate::error::print_error(&e);
-                continue;
+        let program = match parse_entry(&lines) {
+            Entry::Ready(program) => {
+                lines.clear();
+                program
             }
-        };
-
-        // Parse
-        let program = match crate::parser::parse(tokens) {
-            Ok(p) => p,
-            Err(e) => {
+            Entry::Incomplete => continue,
+            Entry::Invalid(e) => {
                 crate::error::print_error(&e);
+                if recover(&mut lines, &e) {
+                    println!("{}", format!("Line {} discarded; retype it or '.cancel'", lines.len() + 1).black().italic());
+                }
                 continue;
             }
         };
 
-        // Execute
-        let mut statements = program.statements;
-        
-        // Handle imports first
-        for import in program.imports {
-             if let Err(e) = interpreter.execute_import(&import).await {
-                 crate::error::print_error(&e);
-             }
-        }
+        execute(&mut interpreter, program).await;
+    }
+}
 
-        if let Some(last_stmt) = statements.pop() {
-            // Execute all preceding statements
-            for stmt in statements {
-                if let Err(e) = interpreter.execute_statement(&stmt).await {
-                    crate::error::print_error(&e);
-                }
-            }
+async fn execute(interpreter: &mut Interpreter, program: Program) {
+    let mut statements = program.statements;
 
-            // Handle last statement
-            match last_stmt {
-                Statement::Expression { expr, .. } => {
-                    match interpreter.evaluate_expression(&expr).await {
-                        Ok(val) => println!("{}", val.to_string().yellow()),
-                        Err(e) => crate::error::print_error(&e),
-                    }
-                }
-                _ => {
-                    if let Err(e) = interpreter.execute_statement(&last_stmt).await {
-                        crate::error::print_error(&e);
+    // Handle imports first
+    for import in program.imports {
+         if let Err(e) = interpreter.execute_import(&import).await {
+             crate::error::print_error(&e);
+         }
+    }
+
+    let Some(last_stmt) = statements.pop() else {
+        return;
+    };
+
+    // Execute all preceding statements
+    for stmt in statements {
+        if let Err(e) = interpreter.execute_statement(&stmt).await {
+            crate::error::print_error(&e);
+        }
+    }
+
+    // Handle last statement
+    match last_stmt {
+        Statement::Expression { expr, .. } => {
+            match interpreter.evaluate_expression(&expr).await {
+                Ok(val) => {
+                    // shout() and friends return Hollow; echoing it is noise
+                    if !matches!(val, Value::Null) {
+                        println!("{} {}", val.to_string().yellow(), format!(": {}", val.type_name()).dimmed());
                     }
+                    interpreter.define("_", val);
                 }
+                Err(e) => crate::error::print_error(&e),
             }
         }
+        _ => {
+            if let Err(e) = interpreter.execute_statement(&last_stmt).await {
+                crate::error::print_error(&e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(source: &[&str]) -> Vec<String> {
+        source.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_blocks_span_lines_and_recover() {
+        let mut typed = lines(&["cast Spell double(x) {"]);
+        assert!(matches!(parse_entry(&typed), Entry::Incomplete));
+
+        typed.push("    return x * * 2".to_string());
+        let Entry::Invalid(error) = parse_entry(&typed) else {
+            panic!("expected a syntax error on the second line");
+        };
+        assert!(recover(&mut typed, &error));
+        assert_eq!(typed.len(), 1);
+
+        typed.push("    return x * 2".to_string());
+        assert!(matches!(parse_entry(&typed), Entry::Incomplete));
+        typed.push("}".to_string());
+        assert!(matches!(parse_entry(&typed), Entry::Ready(_)));
+
+        // A bad single line leaves nothing to keep
+        let mut single = lines(&["let = 3"]);
+        let Entry::Invalid(error) = parse_entry(&single) else {
+            panic!("expected a syntax error");
+        };
+        assert!(!recover(&mut single, &error));
+        assert!(single.is_empty());
     }
 }
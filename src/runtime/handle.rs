@@ -1,14 +1,19 @@
 //! Handle types and registry for the FlowLang event loop
 //!
 //! Handles represent active resources that keep the process alive:
-//! - Timers (interval, timeout)
-//! - Servers (HTTP, WebSocket, TCP)
+//! - Timers (interval, timeout, cron)
+//! - Servers (HTTP on ports or Unix sockets, WebSocket, TCP)
 //! - Connections
 //! - File watchers
+//! - Pub/sub subscriptions
+//! - Background processes
 
 use std::collections::HashMap;
 use std::time::Instant;
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc, oneshot};
+
+use super::CallbackRequest;
+use crate::types::Value;
 
 /// Unique identifier for a handle
 pub type HandleId = u64;
@@ -30,6 +35,13 @@ pub enum HandleType {
         cancel_tx: Option<oneshot::Sender<()>>,
     },
     
+    /// Cron schedule that fires on matching wall-clock minutes
+    Cron {
+        expression: String,
+        /// Channel to signal cancellation
+        cancel_tx: Option<oneshot::Sender<()>>,
+    },
+    
     /// HTTP server listening on a port
     HttpServer {
         port: u16,
@@ -37,6 +49,13 @@ pub enum HandleType {
         shutdown_tx: Option<oneshot::Sender<()>>,
     },
     
+    /// HTTP server listening on a Unix domain socket
+    UnixServer {
+        path: String,
+        /// Channel to signal shutdown
+        shutdown_tx: Option<oneshot::Sender<()>>,
+    },
+    
     /// TCP server listening on a port
     TcpServer {
         port: u16,
@@ -49,6 +68,26 @@ pub enum HandleType {
         shutdown_tx: Option<oneshot::Sender<()>>,
     },
     
+    /// File system watcher on a file or directory
+    FileWatcher {
+        path: String,
+        /// Channel to signal the watcher to stop
+        cancel_tx: Option<oneshot::Sender<()>>,
+    },
+    
+    /// Pub/sub subscription delivering messages to a callback
+    Subscription {
+        /// Channel to signal the subscription should end
+        cancel_tx: Option<oneshot::Sender<()>>,
+    },
+    
+    /// Child process started in the background
+    Process {
+        pid: u32,
+        /// Channel to signal the process should be killed
+        kill_tx: Option<oneshot::Sender<()>>,
+    },
+    
     /// Generic handle for future extensions
     Generic {
         name: String,
@@ -61,12 +100,45 @@ impl HandleType {
         match self {
             HandleType::Interval { .. } => "Interval",
             HandleType::Timeout { .. } => "Timeout",
+            HandleType::Cron { .. } => "Cron",
             HandleType::HttpServer { .. } => "HttpServer",
+            HandleType::UnixServer { .. } => "UnixServer",
             HandleType::TcpServer { .. } => "TcpServer",
             HandleType::WebSocketServer { .. } => "WebSocketServer",
+            HandleType::FileWatcher { .. } => "FileWatcher",
+            HandleType::Subscription { .. } => "Subscription",
+            HandleType::Process { .. } => "Process",
             HandleType::Generic { .. } => "Generic",
         }
     }
+    
+    /// Signal the resource behind the handle to stop. Returns whether the handle can
+    /// leave the registry now; a process stays until its task has reaped it, so
+    /// `onExit` still fires, and a Unix socket server until it has removed its socket
+    /// file, so the file is gone once the process can exit.
+    pub fn cancel(&mut self) -> bool {
+        let tx = match self {
+            HandleType::Interval { cancel_tx, .. }
+            | HandleType::Timeout { cancel_tx, .. }
+            | HandleType::Cron { cancel_tx, .. }
+            | HandleType::FileWatcher { cancel_tx, .. }
+            | HandleType::Subscription { cancel_tx } => cancel_tx.take(),
+            HandleType::HttpServer { shutdown_tx, .. }
+            | HandleType::TcpServer { shutdown_tx, .. }
+            | HandleType::WebSocketServer { shutdown_tx, .. } => shutdown_tx.take(),
+            HandleType::Process { kill_tx: tx, .. } | HandleType::UnixServer { shutdown_tx: tx, .. } => {
+                if let Some(tx) = tx.take() {
+                    let _ = tx.send(());
+                }
+                return false;
+            }
+            HandleType::Generic { .. } => None,
+        };
+        if let Some(tx) = tx {
+            let _ = tx.send(());
+        }
+        true
+    }
 }
 
 /// A registered handle with metadata
@@ -78,6 +150,8 @@ pub struct Handle {
     pub handle_type: HandleType,
     /// When this handle was created
     pub created_at: Instant,
+    /// Spells queued with the handle as argument once it leaves the registry
+    pub on_close: Vec<Value>,
 }
 
 impl Handle {
@@ -87,6 +161,7 @@ impl Handle {
             id,
             handle_type,
             created_at: Instant::now(),
+            on_close: Vec::new(),
         }
     }
     
@@ -103,6 +178,8 @@ pub struct HandleRegistry {
     handles: HashMap<HandleId, Handle>,
     /// Counter for generating unique IDs
     next_id: HandleId,
+    /// Where `onClose` Spells are queued for the event loop
+    close_tx: Option<mpsc::UnboundedSender<CallbackRequest>>,
 }
 
 impl HandleRegistry {
@@ -111,6 +188,15 @@ impl HandleRegistry {
         HandleRegistry {
             handles: HashMap::new(),
             next_id: 1,
+            close_tx: None,
+        }
+    }
+    
+    /// Create a registry that queues `onClose` Spells on `close_tx`
+    pub fn with_close_callbacks(close_tx: mpsc::UnboundedSender<CallbackRequest>) -> Self {
+        HandleRegistry {
+            close_tx: Some(close_tx),
+            ..Self::new()
         }
     }
     
@@ -125,9 +211,30 @@ impl HandleRegistry {
         id
     }
     
-    /// Remove a handle by ID, returns true if it existed
+    /// Remove a handle by ID, returns true if it existed. Its `onClose` Spells are
+    /// queued however it ended: closed, finished or failed.
     pub fn remove(&mut self, id: HandleId) -> bool {
-        self.handles.remove(&id).is_some()
+        let Some(handle) = self.handles.remove(&id) else {
+            return false;
+        };
+        for callback in handle.on_close {
+            self.queue_close_callback(id, callback);
+        }
+        true
+    }
+    
+    /// Run `callback` when the handle leaves the registry; right away if it already has
+    pub fn on_close(&mut self, id: HandleId, callback: Value) {
+        match self.handles.get_mut(&id) {
+            Some(handle) => handle.on_close.push(callback),
+            None => self.queue_close_callback(id, callback),
+        }
+    }
+    
+    fn queue_close_callback(&self, id: HandleId, callback: Value) {
+        if let Some(tx) = &self.close_tx {
+            let _ = tx.send(CallbackRequest { callback, args: vec![Value::Handle(id)] });
+        }
     }
     
     /// Get a handle by ID
@@ -140,6 +247,17 @@ impl HandleRegistry {
         self.handles.get_mut(&id)
     }
     
+    /// Stop a handle's resource, whatever its type; false if it was not active
+    pub fn close(&mut self, id: HandleId) -> bool {
+        let Some(handle) = self.handles.get_mut(&id) else {
+            return false;
+        };
+        if handle.handle_type.cancel() {
+            self.remove(id);
+        }
+        true
+    }
+    
     /// Get the number of active handles
     pub fn count(&self) -> usize {
         self.handles.len()
@@ -155,6 +273,15 @@ impl HandleRegistry {
         self.handles.keys().cloned().collect()
     }
     
+    /// Number of active handles per type name
+    pub fn counts_by_type(&self) -> std::collections::BTreeMap<&'static str, usize> {
+        let mut counts = std::collections::BTreeMap::new();
+        for handle in self.handles.values() {
+            *counts.entry(handle.handle_type.type_name()).or_insert(0) += 1;
+        }
+        counts
+    }
+    
     /// Get summary of all handles for debugging
     pub fn summary(&self) -> String {
         if self.is_empty() {
@@ -196,4 +323,44 @@ mod tests {
         
         assert!(!registry.remove(id1)); // Already removed
     }
+    
+    #[test]
+    fn test_close_signals_and_removes() {
+        let mut registry = HandleRegistry::new();
+        let (tx, mut rx) = oneshot::channel();
+        let server = registry.add(HandleType::HttpServer { port: 8080, shutdown_tx: Some(tx) });
+        assert!(registry.close(server));
+        assert!(rx.try_recv().is_ok());
+        assert!(registry.get(server).is_none());
+        assert!(!registry.close(server));
+        
+        // A process is killed but stays registered until it is reaped
+        let (tx, mut rx) = oneshot::channel();
+        let process = registry.add(HandleType::Process { pid: 1, kill_tx: Some(tx) });
+        assert!(registry.close(process));
+        assert!(rx.try_recv().is_ok());
+        assert!(registry.get(process).is_some());
+    }
+    
+    #[test]
+    fn test_on_close_queues_callbacks_once() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut registry = HandleRegistry::with_close_callbacks(tx);
+        let id = registry.add(HandleType::Generic { name: "test".into() });
+        registry.on_close(id, Value::Number(1.0));
+        registry.on_close(id, Value::Number(2.0));
+        assert!(rx.try_recv().is_err());
+        
+        assert!(registry.close(id));
+        assert!(!registry.remove(id));
+        let queued: Vec<String> = std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|r| format!("{} {}", r.callback, r.args[0]))
+            .collect();
+        assert_eq!(queued.len(), 2);
+        assert!(queued[0].starts_with("1 "));
+        
+        // Too late to wait for the close: the Spell is queued at once
+        registry.on_close(id, Value::Number(3.0));
+        assert!(rx.try_recv().is_ok());
+    }
 }
@@ -0,0 +1,195 @@
+//! Prometheus metrics for FlowLang services
+//!
+//! Web servers record every request here; the event loop records handler errors
+//! and callbacks. `web.metrics(port)` serves the text exposition format so a
+//! Prometheus scraper can watch a deployed service.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Upper bounds of the request latency histogram, in seconds
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Counters shared by every clone of a Runtime
+pub struct Metrics {
+    started_at: Instant,
+    /// (method, status) -> count
+    requests: Mutex<BTreeMap<(String, u16), u64>>,
+    latency: Mutex<Histogram>,
+    in_flight: AtomicI64,
+    handler_errors: AtomicU64,
+    callbacks: AtomicU64,
+    callback_errors: AtomicU64,
+}
+
+#[derive(Default)]
+struct Histogram {
+    /// Non-cumulative counts per bucket; the last slot is +Inf
+    counts: [u64; LATENCY_BUCKETS.len() + 1],
+    sum: f64,
+    count: u64,
+}
+
+/// Event loop state sampled when metrics are rendered
+#[derive(Default)]
+pub struct RuntimeStats {
+    /// Active handles by type name
+    pub handles: BTreeMap<&'static str, usize>,
+    /// Web requests waiting for a handler, and what overflowed
+    pub web_queue: super::queue::QueueStats,
+    /// Timer, watcher and process callbacks waiting to run
+    pub callback_queue_depth: usize,
+    /// Web handlers currently running
+    pub web_handlers_busy: usize,
+    pub web_handlers_max: usize,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Metrics {
+            started_at: Instant::now(),
+            requests: Mutex::new(BTreeMap::new()),
+            latency: Mutex::new(Histogram::default()),
+            in_flight: AtomicI64::new(0),
+            handler_errors: AtomicU64::new(0),
+            callbacks: AtomicU64::new(0),
+            callback_errors: AtomicU64::new(0),
+        }
+    }
+
+    /// A web request was received
+    pub fn request_started(&self) {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A web request was answered
+    pub fn request_finished(&self, method: &str, status: u16, elapsed: Duration) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+        *self.requests.lock().unwrap_or_else(PoisonError::into_inner).entry((method.to_string(), status)).or_insert(0) += 1;
+
+        let seconds = elapsed.as_secs_f64();
+        let mut latency = self.latency.lock().unwrap_or_else(PoisonError::into_inner);
+        let bucket = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound).unwrap_or(LATENCY_BUCKETS.len());
+        latency.counts[bucket] += 1;
+        latency.sum += seconds;
+        latency.count += 1;
+    }
+
+    /// A web handler Spell failed
+    pub fn handler_error(&self) {
+        self.handler_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The event loop ran a fire-and-forget callback
+    pub fn callback_ran(&self, failed: bool) {
+        self.callbacks.fetch_add(1, Ordering::Relaxed);
+        if failed {
+            self.callback_errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Prometheus text exposition format (version 0.0.4)
+    pub fn render(&self, stats: &RuntimeStats) -> String {
+        let mut out = String::new();
+
+        header(&mut out, "flowlang_http_requests_total", "counter", "HTTP requests answered, by method and status");
+        for ((method, status), count) in self.requests.lock().unwrap_or_else(PoisonError::into_inner).iter() {
+            let _ = writeln!(out, "flowlang_http_requests_total{{method=\"{}\",status=\"{}\"}} {}", method, status, count);
+        }
+
+        header(&mut out, "flowlang_http_request_duration_seconds", "histogram", "Time from receiving a request to sending its response");
+        {
+            let latency = self.latency.lock().unwrap_or_else(PoisonError::into_inner);
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(latency.counts.iter()) {
+                cumulative += count;
+                let _ = writeln!(out, "flowlang_http_request_duration_seconds_bucket{{le=\"{}\"}} {}", bound, cumulative);
+            }
+            let _ = writeln!(out, "flowlang_http_request_duration_seconds_bucket{{le=\"+Inf\"}} {}", latency.count);
+            let _ = writeln!(out, "flowlang_http_request_duration_seconds_sum {}", latency.sum);
+            let _ = writeln!(out, "flowlang_http_request_duration_seconds_count {}", latency.count);
+        }
+
+        gauge(&mut out, "flowlang_http_requests_in_flight", "HTTP requests received but not yet answered", self.in_flight.load(Ordering::Relaxed));
+        counter(&mut out, "flowlang_web_handler_errors_total", "Web handler Spells that raised an error", self.handler_errors.load(Ordering::Relaxed));
+        gauge(&mut out, "flowlang_web_handlers_busy", "Web handlers currently running", stats.web_handlers_busy);
+        gauge(&mut out, "flowlang_web_handlers_max", "Maximum concurrent web handlers", stats.web_handlers_max);
+        gauge(&mut out, "flowlang_web_queue_depth", "Web requests waiting for a handler", stats.web_queue.depth);
+        gauge(&mut out, "flowlang_web_queue_capacity", "Web requests that can wait before the overflow policy applies", stats.web_queue.capacity);
+        counter(&mut out, "flowlang_web_queue_rejected_total", "Web requests refused with 503 because the queue was full", stats.web_queue.rejected);
+        counter(&mut out, "flowlang_web_queue_dropped_total", "Queued web requests dropped to make room for newer ones", stats.web_queue.dropped);
+        counter(&mut out, "flowlang_web_queue_blocked_total", "Web requests that waited for space in the queue", stats.web_queue.blocked);
+        gauge(&mut out, "flowlang_callback_queue_depth", "Timer, watcher and process callbacks waiting to run", stats.callback_queue_depth);
+        counter(&mut out, "flowlang_callbacks_total", "Callbacks run by the event loop", self.callbacks.load(Ordering::Relaxed));
+        counter(&mut out, "flowlang_callback_errors_total", "Callbacks that raised an error", self.callback_errors.load(Ordering::Relaxed));
+
+        header(&mut out, "flowlang_active_handles", "gauge", "Handles keeping the event loop alive, by type");
+        for (kind, count) in &stats.handles {
+            let _ = writeln!(out, "flowlang_active_handles{{type=\"{}\"}} {}", kind, count);
+        }
+
+        gauge(&mut out, "flowlang_uptime_seconds", "Seconds since the runtime started", self.started_at.elapsed().as_secs_f64());
+        out
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: impl std::fmt::Display) {
+    header(out, name, "gauge", help);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: u64) {
+    header(out, name, "counter", help);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_histogram_and_counters() {
+        let metrics = Metrics::new();
+        metrics.request_started();
+        metrics.request_finished("GET", 200, Duration::from_millis(3));
+        metrics.request_started();
+        metrics.request_finished("GET", 200, Duration::from_millis(300));
+        metrics.request_started();
+        metrics.request_finished("POST", 500, Duration::from_secs(20));
+        metrics.request_started();
+        metrics.handler_error();
+
+        let mut stats = RuntimeStats::default();
+        stats.web_queue.depth = 2;
+        stats.web_queue.rejected = 3;
+        stats.handles.insert("HttpServer", 1);
+        let text = metrics.render(&stats);
+
+        assert!(text.contains("flowlang_http_requests_total{method=\"GET\",status=\"200\"} 2\n"));
+        assert!(text.contains("flowlang_http_requests_total{method=\"POST\",status=\"500\"} 1\n"));
+        assert!(text.contains("flowlang_http_request_duration_seconds_bucket{le=\"0.005\"} 1\n"));
+        assert!(text.contains("flowlang_http_request_duration_seconds_bucket{le=\"0.5\"} 2\n"));
+        assert!(text.contains("flowlang_http_request_duration_seconds_bucket{le=\"10\"} 2\n"));
+        assert!(text.contains("flowlang_http_request_duration_seconds_bucket{le=\"+Inf\"} 3\n"));
+        assert!(text.contains("flowlang_http_request_duration_seconds_count 3\n"));
+        assert!(text.contains("flowlang_http_requests_in_flight 1\n"));
+        assert!(text.contains("flowlang_web_handler_errors_total 1\n"));
+        assert!(text.contains("flowlang_web_queue_depth 2\n"));
+        assert!(text.contains("flowlang_web_queue_rejected_total 3\n"));
+        assert!(text.contains("flowlang_active_handles{type=\"HttpServer\"} 1\n"));
+    }
+}
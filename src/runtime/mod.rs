@@ -4,8 +4,12 @@
 //! instead of sequentially through a single interpreter lock
 
 pub mod handle;
+pub mod metrics;
+pub mod queue;
 
 use handle::{HandleId, HandleRegistry, HandleType};
+use metrics::{Metrics, RuntimeStats};
+use queue::{OverflowPolicy, WebQueue};
 use crate::types::Value;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -30,6 +34,9 @@ pub struct WebCallbackRequest {
 pub struct RuntimeConfig {
     /// Maximum concurrent web request handlers
     pub max_concurrent_web_handlers: usize,
+    /// Web requests that may wait for a handler before the overflow policy applies
+    pub web_queue_capacity: usize,
+    pub web_queue_overflow: OverflowPolicy,
 }
 
 impl Default for RuntimeConfig {
@@ -37,6 +44,20 @@ impl Default for RuntimeConfig {
         RuntimeConfig {
             // Match the benchmark's connection count for optimal throughput
             max_concurrent_web_handlers: 250,
+            web_queue_capacity: 1024,
+            web_queue_overflow: OverflowPolicy::Reject,
+        }
+    }
+}
+
+impl RuntimeConfig {
+    /// Defaults overridden by the "web" section of config.flowlang.json
+    pub fn from_web_config(web: &crate::config::WebConfig) -> Self {
+        let defaults = Self::default();
+        RuntimeConfig {
+            max_concurrent_web_handlers: web.max_handlers.unwrap_or(defaults.max_concurrent_web_handlers).max(1),
+            web_queue_capacity: web.queue_capacity.unwrap_or(defaults.web_queue_capacity),
+            web_queue_overflow: web.overflow.unwrap_or(defaults.web_queue_overflow),
         }
     }
 }
@@ -51,12 +72,16 @@ pub struct Runtime {
     callback_tx: mpsc::UnboundedSender<CallbackRequest>,
     /// Channel receiver for callback requests
     callback_rx: Arc<Mutex<mpsc::UnboundedReceiver<CallbackRequest>>>,
-    /// Channel sender for web callback requests (wait for response)
-    web_callback_tx: mpsc::UnboundedSender<WebCallbackRequest>,
-    /// Channel receiver for web callback requests
-    web_callback_rx: Arc<Mutex<mpsc::UnboundedReceiver<WebCallbackRequest>>>,
+    /// Bounded queue of web callback requests (wait for response)
+    web_queue: Arc<WebQueue>,
     /// Semaphore to limit concurrent web handler execution
     web_handler_semaphore: Arc<Semaphore>,
+    /// Size of the web handler pool
+    max_web_handlers: usize,
+    /// Request, handler and callback counters for `web.metrics`
+    metrics: Arc<Metrics>,
+    /// Spell registered with `os.onUncaught`, called before the process exits on an error
+    uncaught_handler: Arc<std::sync::Mutex<Option<Value>>>,
 }
 
 impl Runtime {
@@ -68,15 +93,16 @@ impl Runtime {
     /// Create a new Runtime instance with custom config
     pub fn with_config(config: RuntimeConfig) -> Self {
         let (tx, rx) = mpsc::unbounded_channel();
-        let (web_tx, web_rx) = mpsc::unbounded_channel();
         Runtime {
-            handles: Arc::new(Mutex::new(HandleRegistry::new())),
+            handles: Arc::new(Mutex::new(HandleRegistry::with_close_callbacks(tx.clone()))),
             shutdown: Arc::new(AtomicBool::new(false)),
             callback_tx: tx,
             callback_rx: Arc::new(Mutex::new(rx)),
-            web_callback_tx: web_tx,
-            web_callback_rx: Arc::new(Mutex::new(web_rx)),
+            web_queue: Arc::new(WebQueue::new(config.web_queue_capacity, config.web_queue_overflow)),
             web_handler_semaphore: Arc::new(Semaphore::new(config.max_concurrent_web_handlers)),
+            max_web_handlers: config.max_concurrent_web_handlers,
+            metrics: Arc::new(Metrics::new()),
+            uncaught_handler: Arc::default(),
         }
     }
     
@@ -90,9 +116,9 @@ impl Runtime {
         self.callback_tx.clone()
     }
     
-    /// Get a clone of the web callback sender for web handlers (waits for response)
-    pub fn web_callback_sender(&self) -> mpsc::UnboundedSender<WebCallbackRequest> {
-        self.web_callback_tx.clone()
+    /// Get the web callback queue for web handlers (waits for response)
+    pub fn web_callback_sender(&self) -> Arc<WebQueue> {
+        self.web_queue.clone()
     }
     
     /// Get a clone of the shutdown signal Arc
@@ -105,21 +131,44 @@ impl Runtime {
         self.web_handler_semaphore.clone()
     }
     
+    /// Replace the `os.onUncaught` Spell
+    pub fn set_uncaught_handler(&self, handler: Value) {
+        *self.uncaught_handler.lock().unwrap() = Some(handler);
+    }
+    
+    /// The `os.onUncaught` Spell, if one was registered
+    pub fn uncaught_handler(&self) -> Option<Value> {
+        self.uncaught_handler.lock().unwrap().clone()
+    }
+    
+    /// Get the metrics shared by every clone of this runtime
+    pub fn metrics(&self) -> Arc<Metrics> {
+        self.metrics.clone()
+    }
+    
+    /// Render metrics in the Prometheus text format, sampling the event loop state
+    pub async fn render_metrics(&self) -> String {
+        let stats = RuntimeStats {
+            handles: self.handles.lock().await.counts_by_type(),
+            web_queue: self.web_queue.stats(),
+            callback_queue_depth: self.callback_rx.lock().await.len(),
+            web_handlers_busy: self.max_web_handlers.saturating_sub(self.web_handler_semaphore.available_permits()),
+            web_handlers_max: self.max_web_handlers,
+        };
+        self.metrics.render(&stats)
+    }
+    
     /// Process web callbacks (returns callback with its response channel)
     /// This now supports concurrent processing via the semaphore
     pub async fn get_web_callback(&self) -> Option<WebCallbackRequest> {
-        let mut rx = self.web_callback_rx.lock().await;
-        rx.try_recv().ok()
+        self.web_queue.pop()
     }
     
     /// Get web callback with semaphore acquisition (for concurrent processing)
     /// Returns (permit, request) where permit must be held during execution
     pub async fn get_web_callback_with_permit(&self) -> Option<(tokio::sync::SemaphorePermit<'_>, WebCallbackRequest)> {
         // Try to get a request first (non-blocking)
-        let request = {
-            let mut rx = self.web_callback_rx.lock().await;
-            rx.try_recv().ok()?
-        };
+        let request = self.web_queue.pop()?;
         
         // Acquire a permit (this may block if at max concurrency)
         let permit = self.web_handler_semaphore.acquire().await.ok()?;
@@ -139,6 +188,24 @@ impl Runtime {
         registry.remove(id)
     }
     
+    /// Stop a handle's resource (timer, server, watcher, ...); false if it was not active
+    pub async fn close_handle(&self, id: HandleId) -> bool {
+        self.handles.lock().await.close(id)
+    }
+    
+    /// Stop every handle's resource, e.g. those a `vm.run` sandbox left open
+    pub async fn close_all_handles(&self) {
+        let mut registry = self.handles.lock().await;
+        for id in registry.ids() {
+            registry.close(id);
+        }
+    }
+    
+    /// Queue `callback` for the event loop when the handle leaves the registry
+    pub async fn on_handle_close(&self, id: HandleId, callback: Value) {
+        self.handles.lock().await.on_close(id, callback);
+    }
+    
     /// Get the count of active handles
     pub async fn active_handle_count(&self) -> usize {
         let registry = self.handles.lock().await;
@@ -233,9 +300,11 @@ impl Clone for Runtime {
             shutdown: self.shutdown.clone(),
             callback_tx: self.callback_tx.clone(),
             callback_rx: self.callback_rx.clone(),
-            web_callback_tx: self.web_callback_tx.clone(),
-            web_callback_rx: self.web_callback_rx.clone(),
+            web_queue: self.web_queue.clone(),
             web_handler_semaphore: self.web_handler_semaphore.clone(),
+            max_web_handlers: self.max_web_handlers,
+            metrics: self.metrics.clone(),
+            uncaught_handler: self.uncaught_handler.clone(),
         }
     }
 }
@@ -0,0 +1,198 @@
+//! Bounded queue between web servers and the event loop
+//!
+//! Servers push a request for every incoming call and the event loop pops them
+//! as handler permits free up. The queue has a fixed capacity so a traffic spike
+//! can't buffer unbounded requests in memory; what happens when it is full is
+//! the overflow policy:
+//!
+//! - `reject` - the new request is refused and its server answers 503
+//! - `drop-oldest` - the longest-waiting request is refused instead
+//! - `block` - the server waits for space, pushing back on its clients
+
+use super::WebCallbackRequest;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+use tokio::sync::Notify;
+
+/// What to do with a request that arrives when the queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverflowPolicy {
+    #[default]
+    Reject,
+    DropOldest,
+    Block,
+}
+
+impl OverflowPolicy {
+    pub fn name(self) -> &'static str {
+        match self {
+            OverflowPolicy::Reject => "reject",
+            OverflowPolicy::DropOldest => "drop-oldest",
+            OverflowPolicy::Block => "block",
+        }
+    }
+}
+
+/// The request was refused because the queue is full
+#[derive(Debug)]
+pub struct QueueFull;
+
+/// Overflow counters sampled for metrics
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QueueStats {
+    pub depth: usize,
+    pub capacity: usize,
+    /// Requests refused on arrival (reject)
+    pub rejected: u64,
+    /// Queued requests refused to make room (drop-oldest)
+    pub dropped: u64,
+    /// Requests that had to wait for space (block)
+    pub blocked: u64,
+}
+
+pub struct WebQueue {
+    items: Mutex<VecDeque<WebCallbackRequest>>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    /// Signalled whenever a request is popped
+    space: Notify,
+    rejected: AtomicU64,
+    dropped: AtomicU64,
+    blocked: AtomicU64,
+}
+
+impl WebQueue {
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        WebQueue {
+            items: Mutex::new(VecDeque::new()),
+            capacity: capacity.max(1),
+            policy,
+            space: Notify::new(),
+            rejected: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            blocked: AtomicU64::new(0),
+        }
+    }
+
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Queue a request, applying the overflow policy if the queue is full.
+    /// A request dropped to make room has its response channel closed, which
+    /// its server reports as 503.
+    pub async fn send(&self, request: WebCallbackRequest) -> Result<(), QueueFull> {
+        let mut waited = false;
+        loop {
+            let notified = self.space.notified();
+            tokio::pin!(notified);
+            // Register for the wakeup before checking, so a pop in between isn't missed
+            notified.as_mut().enable();
+            {
+                let mut items = self.items.lock().unwrap_or_else(PoisonError::into_inner);
+                if items.len() < self.capacity {
+                    items.push_back(request);
+                    return Ok(());
+                }
+                match self.policy {
+                    OverflowPolicy::Reject => {
+                        self.rejected.fetch_add(1, Ordering::Relaxed);
+                        return Err(QueueFull);
+                    }
+                    OverflowPolicy::DropOldest => {
+                        items.pop_front();
+                        items.push_back(request);
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        return Ok(());
+                    }
+                    OverflowPolicy::Block => {
+                        if !waited {
+                            waited = true;
+                            self.blocked.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                }
+            }
+            notified.await;
+        }
+    }
+
+    /// Take the oldest request, if any
+    pub fn pop(&self) -> Option<WebCallbackRequest> {
+        let request = self.items.lock().unwrap_or_else(PoisonError::into_inner).pop_front();
+        if request.is_some() {
+            self.space.notify_one();
+        }
+        request
+    }
+
+    fn len(&self) -> usize {
+        self.items.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        QueueStats {
+            depth: self.len(),
+            capacity: self.capacity,
+            rejected: self.rejected.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            blocked: self.blocked.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Value;
+    use std::sync::Arc;
+    use tokio::sync::oneshot;
+
+    fn request(n: f64) -> (WebCallbackRequest, oneshot::Receiver<Value>) {
+        let (response_tx, response_rx) = oneshot::channel();
+        (WebCallbackRequest { callback: Value::Null, args: vec![Value::Number(n)], response_tx }, response_rx)
+    }
+
+    fn first_arg(request: WebCallbackRequest) -> String {
+        request.args[0].to_string()
+    }
+
+    #[tokio::test]
+    async fn test_overflow_policies() {
+        let queue = WebQueue::new(2, OverflowPolicy::Reject);
+        queue.send(request(1.0).0).await.unwrap();
+        queue.send(request(2.0).0).await.unwrap();
+        assert!(queue.send(request(3.0).0).await.is_err());
+        assert_eq!((queue.stats().depth, queue.stats().rejected), (2, 1));
+
+        let queue = WebQueue::new(2, OverflowPolicy::DropOldest);
+        let (oldest, mut oldest_rx) = request(1.0);
+        queue.send(oldest).await.unwrap();
+        queue.send(request(2.0).0).await.unwrap();
+        queue.send(request(3.0).0).await.unwrap();
+        // The dropped request's server sees its response channel close
+        assert!(oldest_rx.try_recv().is_err());
+        assert_eq!(first_arg(queue.pop().unwrap()), "2");
+        assert_eq!(first_arg(queue.pop().unwrap()), "3");
+        assert_eq!(queue.stats().dropped, 1);
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_space() {
+        let queue = Arc::new(WebQueue::new(1, OverflowPolicy::Block));
+        queue.send(request(1.0).0).await.unwrap();
+
+        let sender = queue.clone();
+        let blocked = tokio::spawn(async move { sender.send(request(2.0).0).await });
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert!(!blocked.is_finished());
+        assert_eq!(queue.stats().blocked, 1);
+
+        assert_eq!(first_arg(queue.pop().unwrap()), "1");
+        blocked.await.unwrap().unwrap();
+        assert_eq!(first_arg(queue.pop().unwrap()), "2");
+    }
+}
@@ -0,0 +1,329 @@
+// Starter projects for `flowlang init --template`
+//
+// Every template gets config.flowlang.json and .gitignore from run_init; this module
+// only supplies the source files (paths relative to the project root) and the entry.
+
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProjectTemplate {
+    /// HTTP server with a routes/ folder
+    Web,
+    /// Command-line tool with argument parsing
+    Cli,
+    /// Library circle with exports and tests
+    Lib,
+    /// Chat bot answering commands on a webhook
+    Bot,
+}
+
+pub struct Scaffold {
+    /// Entry point written to config.flowlang.json
+    pub entry: &'static str,
+    pub files: Vec<(&'static str, String)>,
+    /// Commands shown after "cd <project>"
+    pub next_steps: Vec<&'static str>,
+}
+
+/// Files for `template`, or the hello-world project when there is none
+pub fn scaffold(template: Option<ProjectTemplate>, project_name: &str) -> Scaffold {
+    match template {
+        None => Scaffold {
+            entry: "src/main.flow",
+            files: vec![("src/main.flow", HELLO_MAIN.to_string())],
+            next_steps: vec!["flowlang run src/main.flow"],
+        },
+        Some(ProjectTemplate::Web) => Scaffold {
+            entry: "src/main.flow",
+            files: vec![
+                ("src/main.flow", WEB_MAIN.replace("{name}", project_name)),
+                ("src/routes/home.flow", WEB_HOME.replace("{name}", project_name)),
+                ("src/routes/users.flow", WEB_USERS.to_string()),
+                ("public/index.html", WEB_INDEX.replace("{name}", project_name)),
+            ],
+            next_steps: vec!["flowlang run src/main.flow", "open http://localhost:3000"],
+        },
+        Some(ProjectTemplate::Cli) => Scaffold {
+            entry: "src/main.flow",
+            files: vec![
+                ("src/main.flow", CLI_MAIN.replace("{name}", project_name)),
+                ("src/args.flow", CLI_ARGS.to_string()),
+            ],
+            next_steps: vec!["flowlang run src/main.flow -- --name Aria --loud", "flowlang run src/main.flow -- --help"],
+        },
+        Some(ProjectTemplate::Lib) => Scaffold {
+            entry: "src/lib.flow",
+            files: vec![
+                ("src/lib.flow", LIB_MAIN.replace("{name}", project_name)),
+                ("tests/lib_test.flow", LIB_TEST.to_string()),
+                ("examples/usage.flow", LIB_EXAMPLE.to_string()),
+            ],
+            next_steps: vec!["flowlang test", "flowlang run examples/usage.flow", "flowlang doc"],
+        },
+        Some(ProjectTemplate::Bot) => Scaffold {
+            entry: "src/main.flow",
+            files: vec![
+                ("src/main.flow", BOT_MAIN.replace("{name}", project_name)),
+                ("src/commands.flow", BOT_COMMANDS.replace("{name}", project_name)),
+                ("tests/commands_test.flow", BOT_TEST.to_string()),
+            ],
+            next_steps: vec![
+                "flowlang run src/main.flow",
+                "curl -X POST localhost:3000/webhook -d '{\"user\": \"Aria\", \"text\": \"/ping\"}'",
+            ],
+        },
+    }
+}
+
+const HELLO_MAIN: &str = r#"-- Welcome to FlowLang!
+-- This is your entry point.
+
+shout("✨ The Flow has begun!")
+
+cast Spell greet(name) {
+    return "Hello, " + name + "!"
+}
+
+shout(greet("World"))
+"#;
+
+const WEB_MAIN: &str = r#"-- {name} - web server entry point
+-- Each route lives in its own circle under routes/
+
+circle web from "std:web"
+circle home from "routes/home.flow"
+circle users from "routes/users.flow"
+
+seal PORT = 3000
+
+cast Spell handler(req, res) {
+    shout("📨 " + req.method + " " + req.pathname)
+
+    in Stance (req.pathname is~ "/") {
+        return home.index(req, res)
+    }
+    in Stance (req.pathname is~ "/health") {
+        return res.json({"status": "ok"})
+    }
+    in Stance (req.pathname is~ "/api/users") {
+        return users.list(req, res)
+    }
+
+    return res.notFound("No route for " + req.pathname)
+}
+
+shout("🌐 Listening on http://localhost:" + PORT)
+web.serve(PORT, handler)
+"#;
+
+const WEB_HOME: &str = r#"-- GET /
+
+--- Serves the landing page
+@export cast Spell index(req, res) {
+    return res.file("public/index.html")
+}
+"#;
+
+const WEB_USERS: &str = r#"-- /api/users
+
+let users = [
+    {"id": 1, "name": "Aria"},
+    {"id": 2, "name": "Kaito"}
+]
+
+--- GET lists every user
+@export cast Spell list(req, res) {
+    in Stance (req.method not~ "GET") {
+        return res.status(405, "Method not allowed")
+    }
+    return res.json(users)
+}
+"#;
+
+const WEB_INDEX: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>{name}</title></head>
+<body>
+    <h1>🌊 {name}</h1>
+    <p>Served by FlowLang. Try <a href="/api/users">/api/users</a>.</p>
+</body>
+</html>
+"#;
+
+const CLI_MAIN: &str = r#"-- {name} - command-line entry point
+-- Usage: flowlang run src/main.flow -- [--name NAME] [--loud] [--help]
+
+circle cli from "std:cli"
+circle args from "args.flow"
+
+let options = args.parse(cli.args())
+
+in Stance (options.help) {
+    shout(args.usage("{name}"))
+    cli.exit(0)
+}
+
+let greeting = "Hello, " + options.name + "!"
+in Stance (options.loud) {
+    greeting = greeting.upper()
+}
+shout(greeting)
+"#;
+
+const CLI_ARGS: &str = r#"-- Argument parsing for the command line
+
+--- Turns the raw argument list into options
+--- @param argv Arguments from cli.args()
+--- @returns A Relic with name, loud and help
+@export cast Spell parse(argv) {
+    let name = "World"
+    let loud = false
+    let help = false
+    let expecting_name = false
+
+    enter Phase arg in argv {
+        in Stance (expecting_name) {
+            name = arg
+            expecting_name = false
+        } shift Stance (arg is~ "--name" either! arg is~ "-n") {
+            expecting_name = true
+        } shift Stance (arg is~ "--loud") {
+            loud = true
+        } shift Stance (arg is~ "--help" either! arg is~ "-h") {
+            help = true
+        } abandon Stance {
+            rupture Spirit "Unknown argument: " + arg
+        }
+    }
+
+    return {"name": name, "loud": loud, "help": help}
+}
+
+--- Help text shown for --help
+@export cast Spell usage(program) {
+    return "Usage: " + program + " [--name NAME] [--loud] [--help]"
+}
+"#;
+
+const LIB_MAIN: &str = r#"--- {name}: a FlowLang library circle.
+---
+--- Import it with `circle lib from "src/lib.flow"`.
+
+--- Clamps `value` between `low` and `high`
+--- @param value The number to clamp
+--- @param low Lower bound
+--- @param high Upper bound
+--- @returns The clamped number
+@export cast Spell clamp(value, low, high) {
+    in Stance (value << low) {
+        return low
+    }
+    in Stance (value >> high) {
+        return high
+    }
+    return value
+}
+
+--- Adds up every number in a list
+--- @param numbers A list of Embers
+@export cast Spell sum(numbers) {
+    return numbers.reduce(cast Spell (total, n) -> total + n, 0)
+}
+
+--- Library version
+@export seal VERSION = "0.1.0"
+"#;
+
+const LIB_TEST: &str = r#"-- Tests for src/lib.flow (run with `flowlang test`)
+
+circle assert from "std:assert"
+circle lib from "../src/lib.flow"
+
+assert.equal(lib.clamp(5, 0, 10), 5)
+assert.equal(lib.clamp(-3, 0, 10), 0)
+assert.equal(lib.clamp(42, 0, 10), 10)
+
+assert.equal(lib.sum([1, 2, 3]), 6)
+assert.equal(lib.sum([]), 0)
+
+assert.ok(lib.VERSION is~ "0.1.0")
+"#;
+
+const LIB_EXAMPLE: &str = r#"-- Using the library from another scroll
+
+circle lib from "../src/lib.flow"
+
+shout(lib.clamp(150, 0, 100))
+shout(lib.sum([1, 2, 3, 4]))
+"#;
+
+const BOT_MAIN: &str = r#"-- {name} - chat bot entry point
+-- POST /webhook with {"user": "...", "text": "/ping"} and the bot replies in JSON.
+
+circle web from "std:web"
+circle json from "std:json"
+circle commands from "commands.flow"
+
+seal PORT = 3000
+
+cast Spell handler(req, res) {
+    in Stance (req.method not~ "POST" either! req.pathname not~ "/webhook") {
+        return res.notFound("POST /webhook to talk to the bot")
+    }
+
+    let message = json.parse(req.body)
+    let reply = commands.handle(message.user, message.text)
+    return res.json({"reply": reply})
+}
+
+shout("🤖 Bot listening on http://localhost:" + PORT + "/webhook")
+web.serve(PORT, handler)
+"#;
+
+const BOT_COMMANDS: &str = r#"-- Bot commands. Add a `when` branch to handle a new one.
+
+--- Replies to one message
+--- @param user Who sent the message
+--- @param text The message text
+--- @returns The reply text
+@export cast Spell handle(user, text) {
+    invoke Aura text {
+        when "/ping" -> { return "pong" }
+        when "/hello" -> { return "Hello, " + user + "! ✨" }
+        when "/help" -> { return help() }
+        otherwise -> { return "I don't know '" + text + "'. Try /help" }
+    }
+}
+
+cast Spell help() {
+    return "{name} commands: /ping, /hello, /help"
+}
+"#;
+
+const BOT_TEST: &str = r#"-- Tests for src/commands.flow (run with `flowlang test`)
+
+circle assert from "std:assert"
+circle commands from "../src/commands.flow"
+
+assert.equal(commands.handle("Aria", "/ping"), "pong")
+assert.equal(commands.handle("Aria", "/hello"), "Hello, Aria! ✨")
+assert.ok(commands.handle("Aria", "/dance") not~ "pong")
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_templates_parse() {
+        let templates = [None, Some(ProjectTemplate::Web), Some(ProjectTemplate::Cli), Some(ProjectTemplate::Lib), Some(ProjectTemplate::Bot)];
+        for template in templates {
+            let scaffold = scaffold(template, "demo");
+            assert!(scaffold.files.iter().any(|(path, _)| *path == scaffold.entry), "{:?} has no entry file", template);
+            for (path, source) in scaffold.files.iter().filter(|(path, _)| path.ends_with(".flow")) {
+                let parsed = crate::lexer::tokenize(source).and_then(crate::parser::parse);
+                assert!(parsed.is_ok(), "{:?} {} does not parse: {:?}", template, path, parsed.err());
+            }
+        }
+    }
+}
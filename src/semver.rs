@@ -0,0 +1,260 @@
+//! Semantic versions and version ranges
+//!
+//! Used for the `version` and `flowlang` fields of config.flowlang.json. Ranges use
+//! Cargo's syntax: `^1.2` (the default for a bare version), `~1.2`, `=1.2.3`,
+//! comparisons like `>=1.0, <2.0`, `*`, and alternatives joined with `||`.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// The FlowLang version of this interpreter
+pub const FLOWLANG_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A `MAJOR.MINOR.PATCH[-pre][+build]` version; build metadata is ignored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Vec<String>,
+}
+
+impl Version {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let core = text.split('+').next().unwrap_or_default();
+        let (numbers, pre) = match core.split_once('-') {
+            Some((numbers, pre)) => (numbers, Some(pre)),
+            None => (core, None),
+        };
+        let parts = numbers.split('.').map(parse_number).collect::<Result<Vec<_>, _>>();
+        let pre = match pre {
+            Some(pre) if pre.is_empty() || pre.split('.').any(str::is_empty) => {
+                return Err(format!("'{}' has an empty pre-release part", text))
+            }
+            Some(pre) => pre.split('.').map(String::from).collect(),
+            None => Vec::new(),
+        };
+        match parts {
+            Ok(parts) if parts.len() == 3 => Ok(Version { major: parts[0], minor: parts[1], patch: parts[2], pre }),
+            _ => Err(format!("'{}' is not a version like 1.2.3", text)),
+        }
+    }
+
+    fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Version { major, minor, patch, pre: Vec::new() }
+    }
+}
+
+fn parse_number(part: &str) -> Result<u64, String> {
+    // No leading zeros, as in semver itself
+    if part.is_empty() || (part.len() > 1 && part.starts_with('0')) || !part.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("'{}' is not a version number", part));
+    }
+    part.parse().map_err(|_| format!("'{}' is too large", part))
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| compare_pre(&self.pre, &other.pre))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A pre-release sorts before its release; identifiers compare numerically when both are numbers
+fn compare_pre(a: &[String], b: &[String]) -> Ordering {
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        _ => {}
+    }
+    for (x, y) in a.iter().zip(b) {
+        let order = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            _ => x.cmp(y),
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+}
+
+/// One comparison; missing minor/patch parts are wildcards (`^1` is `>=1.0.0, <2.0.0`)
+#[derive(Debug, Clone)]
+struct Comparator {
+    op: Op,
+    major: u64,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Vec<String>,
+}
+
+impl Comparator {
+    fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (op, rest) = [(">=", Op::GreaterEq), ("<=", Op::LessEq), (">", Op::Greater), ("<", Op::Less), ("=", Op::Exact), ("~", Op::Tilde), ("^", Op::Caret)]
+            .iter()
+            .find_map(|(prefix, op)| text.strip_prefix(prefix).map(|rest| (*op, rest.trim_start())))
+            .unwrap_or((Op::Caret, text));
+        let rest = rest.strip_prefix('v').unwrap_or(rest);
+        let (numbers, pre) = match rest.split_once('-') {
+            Some((numbers, pre)) => (numbers, pre.split('.').map(String::from).collect()),
+            None => (rest, Vec::new()),
+        };
+        let mut parts = numbers.split('.').filter(|part| !matches!(*part, "*" | "x" | "X"));
+        let major = parts.next().ok_or_else(|| format!("'{}' has no version", text)).and_then(parse_number)?;
+        let minor = parts.next().map(parse_number).transpose()?;
+        let patch = parts.next().map(parse_number).transpose()?;
+        if parts.next().is_some() || (!pre.is_empty() && patch.is_none()) {
+            return Err(format!("'{}' is not a version range", text));
+        }
+        Ok(Comparator { op, major, minor, patch, pre })
+    }
+
+    fn lowest(&self) -> Version {
+        Version { pre: self.pre.clone(), ..Version::new(self.major, self.minor.unwrap_or(0), self.patch.unwrap_or(0)) }
+    }
+
+    /// The first version past everything a partial version covers: `1.2` -> `1.3.0`
+    fn past_partial(&self) -> Version {
+        match (self.minor, self.patch) {
+            (None, _) => Version::new(self.major + 1, 0, 0),
+            (Some(minor), None) => Version::new(self.major, minor + 1, 0),
+            (Some(minor), Some(patch)) => Version::new(self.major, minor, patch + 1),
+        }
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        let lowest = self.lowest();
+        match self.op {
+            Op::Exact => *version >= lowest && *version < self.past_partial(),
+            Op::Greater => *version >= self.past_partial(),
+            Op::GreaterEq => *version >= lowest,
+            Op::Less => *version < lowest,
+            Op::LessEq => *version < self.past_partial(),
+            Op::Tilde => {
+                let upper = match self.minor {
+                    Some(minor) => Version::new(self.major, minor + 1, 0),
+                    None => Version::new(self.major + 1, 0, 0),
+                };
+                *version >= lowest && *version < upper
+            }
+            Op::Caret => {
+                // The leftmost non-zero part may not change
+                let upper = match (self.major, self.minor, self.patch) {
+                    (0, Some(0), Some(patch)) => Version::new(0, 0, patch + 1),
+                    (0, Some(minor), _) => Version::new(0, minor + 1, 0),
+                    (major, _, _) => Version::new(major + 1, 0, 0),
+                };
+                *version >= lowest && *version < upper
+            }
+        }
+    }
+}
+
+/// A version range such as `>=1.0, <2.0` or `^1.4 || ^2`
+#[derive(Debug, Clone)]
+pub struct VersionReq {
+    /// Any one group matching is enough; every comparator in a group must match
+    alternatives: Vec<Vec<Comparator>>,
+}
+
+impl VersionReq {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let alternatives = text
+            .split("||")
+            .map(|group| {
+                let group = group.trim();
+                if group == "*" {
+                    return Ok(Vec::new());
+                }
+                if group.is_empty() {
+                    return Err(format!("'{}' has an empty range", text.trim()));
+                }
+                group.split(',').map(Comparator::parse).collect()
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(VersionReq { alternatives })
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        self.alternatives.iter().any(|group| group.iter().all(|comparator| comparator.matches(version)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(req: &str, version: &str) -> bool {
+        VersionReq::parse(req).unwrap().matches(&Version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn test_version_ordering() {
+        let ordered = ["1.0.0-alpha", "1.0.0-alpha.1", "1.0.0-beta", "1.0.0-beta.2", "1.0.0-beta.11", "1.0.0", "1.2.0", "1.10.0"];
+        for pair in ordered.windows(2) {
+            assert!(Version::parse(pair[0]).unwrap() < Version::parse(pair[1]).unwrap(), "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(Version::parse("2.1.0+build.5").unwrap(), Version::parse("2.1.0").unwrap());
+        for bad in ["1.0", "1.0.0.0", "01.0.0", "1.0.x", "1.0.0-", "v"] {
+            assert!(Version::parse(bad).is_err(), "{} should not parse", bad);
+        }
+    }
+
+    #[test]
+    fn test_ranges() {
+        assert!(matches("1.2", "1.9.0"));
+        assert!(!matches("1.2", "2.0.0"));
+        assert!(matches("^0.3", "0.3.7"));
+        assert!(!matches("^0.3", "0.4.0"));
+        assert!(!matches("^0.0.3", "0.0.4"));
+        assert!(matches("~1.2", "1.2.9"));
+        assert!(!matches("~1.2", "1.3.0"));
+        assert!(matches(">=1.0, <2.0", "1.5.0"));
+        assert!(!matches(">=1.0, <2.0", "2.0.0"));
+        assert!(matches(">1.0", "1.1.0"));
+        assert!(!matches(">1.0", "1.0.5"));
+        assert!(matches("<=1.0", "1.0.5"));
+        assert!(matches("=1.0.0", "1.0.0"));
+        assert!(matches("^1 || ^3", "3.1.0"));
+        assert!(!matches("^1 || ^3", "2.0.0"));
+        assert!(matches("*", "9.9.9"));
+        assert!(matches("1.x", "1.4.0"));
+        assert!(!matches(">=1.0.0", "1.0.0-rc.1"));
+        assert!(VersionReq::parse(">=").is_err());
+        assert!(VersionReq::parse("^1 ||").is_err());
+    }
+}
@@ -1,10 +1,9 @@
-use crate::types::{Value, NativeFn};
+use crate::types::{Value, NativeFn, RelicMap};
 use crate::error::FlowError;
-use std::collections::HashMap;
 use std::sync::Arc;
 
-pub fn get_module() -> HashMap<String, Value> {
-    let mut module = HashMap::new();
+pub fn get_module() -> RelicMap {
+    let mut module = RelicMap::new();
     
     module.insert("len".to_string(), Value::NativeFunction(NativeFn(Arc::new(|args| {
         if args.len() != 1 {
@@ -65,5 +64,121 @@ pub fn get_module() -> HashMap<String, Value> {
         }
     }))));
     
+    module.insert("fill".to_string(), Value::NativeFunction(NativeFn(Arc::new(|args| {
+        match args.as_slice() {
+            [Value::Number(n), value] => {
+                let count = repeat_count("array.fill()", *n, 1)?;
+                Ok(Value::Array(Arc::new(vec![value.clone(); count])))
+            }
+            [_, _] => Err(FlowError::type_error("fill() expects an Ember count first", 0, 0)),
+            _ => Err(FlowError::runtime("fill() expects 2 arguments (n, value)", 0, 0)),
+        }
+    }))));
+    
     module
 }
+
+/// Most elements (or Silk bytes) that repetition builds in one go
+const MAX_REPEATED: usize = 100_000_000;
+
+/// How many times `*`, `fill()` or `repeat()` copies something `len` long: a whole
+/// Ember of at least 0, refused when the result would be unreasonably large
+pub fn repeat_count(what: &str, n: f64, len: usize) -> Result<usize, FlowError> {
+    if n.fract() != 0.0 || n < 0.0 {
+        return Err(FlowError::out_of_range(
+            &format!("{} needs a whole count of at least 0, got {}", what, n),
+            0,
+            0,
+        ));
+    }
+    if n * len as f64 > MAX_REPEATED as f64 {
+        return Err(FlowError::out_of_range(
+            &format!("{} would build {} items, more than the limit of {}", what, n * len as f64, MAX_REPEATED),
+            0,
+            0,
+        ));
+    }
+    Ok(n as usize)
+}
+
+/// Order used by `sort()`, `min()` and `max()`: Embers by value, Silk alphabetically,
+/// both! after either!. Other pairs can't be ordered.
+pub fn natural_order(a: &Value, b: &Value) -> Result<std::cmp::Ordering, FlowError> {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => Ok(x.total_cmp(y)),
+        (Value::String(x), Value::String(y)) => Ok(x.cmp(y)),
+        (Value::Boolean(x), Value::Boolean(y)) => Ok(x.cmp(y)),
+        _ => Err(FlowError::type_error(
+            &format!("Cannot order {} against {}", a.type_name(), b.type_name()),
+            0,
+            0,
+        )),
+    }
+}
+
+/// The smallest (or with `Greater`, largest) element, first one on ties; Hollow when empty
+pub fn extreme(items: &[Value], wanted: std::cmp::Ordering) -> Result<Value, FlowError> {
+    let mut best: Option<&Value> = None;
+    for item in items {
+        if best.map_or(Ok(true), |b| natural_order(item, b).map(|o| o == wanted))? {
+            best = Some(item);
+        }
+    }
+    Ok(best.cloned().unwrap_or(Value::Null))
+}
+
+/// Elements in first-seen order, dropping later ones `deepEquals` to an earlier one
+pub fn unique(items: &[Value]) -> Vec<Value> {
+    let mut kept: Vec<Value> = Vec::new();
+    for item in items {
+        if !kept.iter().any(|k| k.deep_equals(item)) {
+            kept.push(item.clone());
+        }
+    }
+    kept
+}
+
+/// Splice nested Constellations into their parent, `depth` levels down
+pub fn flatten(items: &[Value], depth: usize) -> Vec<Value> {
+    let mut flat = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            Value::Array(inner) if depth > 0 => flat.extend(flatten(inner, depth - 1)),
+            other => flat.push(other.clone()),
+        }
+    }
+    flat
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(values: &[f64]) -> Vec<Value> {
+        values.iter().map(|n| Value::Number(*n)).collect()
+    }
+
+    #[test]
+    fn test_collection_helpers() {
+        let items = numbers(&[3.0, 1.0, 2.0, 1.0]);
+        assert_eq!(extreme(&items, std::cmp::Ordering::Less).unwrap().to_string(), "1");
+        assert_eq!(extreme(&items, std::cmp::Ordering::Greater).unwrap().to_string(), "3");
+        assert!(matches!(extreme(&[], std::cmp::Ordering::Less).unwrap(), Value::Null));
+        assert!(extreme(&[Value::Number(1.0), Value::Boolean(true)], std::cmp::Ordering::Less).is_err());
+
+        assert_eq!(Value::Array(Arc::new(unique(&items))).to_string(), "[3, 1, 2]");
+
+        let nested = vec![
+            Value::Number(1.0),
+            Value::Array(Arc::new(vec![Value::Number(2.0), Value::Array(Arc::new(numbers(&[3.0])))])),
+        ];
+        assert_eq!(Value::Array(Arc::new(flatten(&nested, 1))).to_string(), "[1, 2, [3]]");
+        assert_eq!(Value::Array(Arc::new(flatten(&nested, usize::MAX))).to_string(), "[1, 2, 3]");
+
+        assert_eq!(repeat_count("fill()", 3.0, 2).unwrap(), 3);
+        assert_eq!(repeat_count("fill()", 1e12, 0).unwrap(), 1_000_000_000_000);
+        assert!(repeat_count("fill()", -1.0, 1).is_err());
+        assert!(repeat_count("fill()", 1.5, 1).is_err());
+        assert!(repeat_count("fill()", 1e9, 1).is_err());
+    }
+}
@@ -0,0 +1,663 @@
+//! std:assert - Assertions and snapshot testing for FlowLang
+//!
+//! Provides the building blocks used by `flowlang test`:
+//! - `assert.ok(condition, message?)` - Truthiness check
+//! - `assert.equal(actual, expected, message?)` - Structural equality
+//! - `assert.notEqual(actual, expected, message?)` - Structural inequality
+//! - `assert.matchesSnapshot(value, name)` - Golden-file comparison
+//! - `assert.forall(generator, spell, options?)` - Property-based testing
+//!
+//! Snapshots live in `tests/__snapshots__/<name>.snap` unless the test runner
+//! gives the interpreter other `Snapshots` settings. Missing snapshots are written
+//! on first run, or fail the assertion with `flowlang test --ci` (and under CI);
+//! `flowlang test --update-snapshots` rewrites existing ones.
+
+use crate::error::FlowError;
+use crate::interpreter::Interpreter;
+use crate::stdlib::json::{value_to_json_string, value_to_pretty_json};
+use crate::stdlib::random::{time_seed, SplitMix64};
+use crate::types::{InterpreterFuture, InterpreterNativeFn, NativeFn, Value, RelicMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub fn load_assert_module() -> Vec<(&'static str, Value)> {
+    vec![
+        ("ok", Value::NativeFunction(NativeFn::new(assert_ok))),
+        ("equal", Value::NativeFunction(NativeFn::new(assert_equal))),
+        ("notEqual", Value::NativeFunction(NativeFn::new(assert_not_equal))),
+        ("matchesSnapshot", Value::InterpreterNativeFunction(InterpreterNativeFn::new(assert_matches_snapshot))),
+
+        // Property-based testing
+        ("forall", Value::InterpreterNativeFunction(InterpreterNativeFn::new(assert_forall))),
+        ("ember", Value::NativeFunction(NativeFn::new(gen_ember))),
+        ("silk", Value::NativeFunction(NativeFn::new(gen_silk))),
+        ("pulse", Value::NativeFunction(NativeFn::new(gen_pulse))),
+        ("constellation", Value::NativeFunction(NativeFn::new(gen_constellation))),
+        ("relic", Value::NativeFunction(NativeFn::new(gen_relic))),
+    ]
+}
+
+/// assert.ok(condition, message?) -> Hollow
+/// Fails with a Spirit error when the condition is not truthy.
+fn assert_ok(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(FlowError::runtime(
+            "assert.ok expects 1 or 2 arguments (condition, message?)",
+            0, 0,
+        ));
+    }
+
+    if args[0].is_truthy() {
+        return Ok(Value::Null);
+    }
+
+    Err(FlowError::spirit(
+        &failure_message(args.get(1), &format!("expected a truthy value, got {}", describe(&args[0]))),
+        0, 0,
+    ))
+}
+
+/// assert.equal(actual, expected, message?) -> Hollow
+/// Compares Constellations and Relics element by element.
+fn assert_equal(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(FlowError::runtime(
+            "assert.equal expects 2 or 3 arguments (actual, expected, message?)",
+            0, 0,
+        ));
+    }
+
+    if args[0].deep_equals(&args[1]) {
+        return Ok(Value::Null);
+    }
+
+    Err(FlowError::spirit(
+        &failure_message(
+            args.get(2),
+            &format!("expected {} but got {}", describe(&args[1]), describe(&args[0])),
+        ),
+        0, 0,
+    ))
+}
+
+/// assert.notEqual(actual, expected, message?) -> Hollow
+fn assert_not_equal(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(FlowError::runtime(
+            "assert.notEqual expects 2 or 3 arguments (actual, expected, message?)",
+            0, 0,
+        ));
+    }
+
+    if !args[0].deep_equals(&args[1]) {
+        return Ok(Value::Null);
+    }
+
+    Err(FlowError::spirit(
+        &failure_message(args.get(2), &format!("expected a value different from {}", describe(&args[1]))),
+        0, 0,
+    ))
+}
+
+/// Where `assert.matchesSnapshot` keeps its files and what it does when one is
+/// missing or different. The test runner sets these per file on the interpreter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshots {
+    pub dir: PathBuf,
+    pub mode: SnapshotMode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotMode {
+    /// Write missing snapshots, compare existing ones
+    Record,
+    /// Rewrite every snapshot (`--update-snapshots`)
+    Update,
+    /// Fail on missing snapshots instead of writing them (`--ci`, or under CI)
+    Check,
+}
+
+impl Default for Snapshots {
+    fn default() -> Self {
+        Snapshots { dir: PathBuf::from("tests").join("__snapshots__"), mode: SnapshotMode::Record }
+    }
+}
+
+/// assert.matchesSnapshot(value, name) -> Hollow
+/// Serializes the value and compares it with `<snapshot dir>/<name>.snap`.
+/// Silks are stored verbatim so plain-text golden files stay readable;
+/// everything else is stored as indented JSON with sorted keys.
+fn assert_matches_snapshot(args: Vec<Value>, interp: &mut Interpreter) -> InterpreterFuture<'_> {
+    let snapshots = interp.snapshots().clone();
+    Box::pin(async move { match_snapshot(args, &snapshots) })
+}
+
+fn match_snapshot(args: Vec<Value>, snapshots: &Snapshots) -> Result<Value, FlowError> {
+    if args.len() != 2 {
+        return Err(FlowError::runtime(
+            "assert.matchesSnapshot expects 2 arguments (value, name)",
+            0, 0,
+        ));
+    }
+
+    let name = match &args[1] {
+        Value::String(s) if !s.is_empty() => s.clone(),
+        _ => return Err(FlowError::type_error(
+            "assert.matchesSnapshot expects a non-empty Silk (string) as name",
+            0, 0,
+        )),
+    };
+
+    let actual = serialize_snapshot(&args[0]);
+    let path = snapshots.dir.join(format!("{}.snap", sanitize_name(&name)));
+
+    if snapshots.mode == SnapshotMode::Check && !path.exists() {
+        return Err(FlowError::spirit(
+            &format!(
+                "Snapshot '{}' is missing ({})\nRun `flowlang test` locally to record it and commit the .snap file.",
+                name,
+                path.display(),
+            ),
+            0, 0,
+        ));
+    }
+
+    if snapshots.mode == SnapshotMode::Update || !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                FlowError::rift(&format!("Failed to create snapshot directory '{}': {}", parent.display(), e), 0, 0)
+            })?;
+        }
+        fs::write(&path, &actual).map_err(|e| {
+            FlowError::rift(&format!("Failed to write snapshot '{}': {}", path.display(), e), 0, 0)
+        })?;
+        return Ok(Value::Null);
+    }
+
+    let expected = fs::read_to_string(&path).map_err(|e| {
+        FlowError::rift(&format!("Failed to read snapshot '{}': {}", path.display(), e), 0, 0)
+    })?;
+
+    // Editors like to add or strip a trailing newline; don't fail over it
+    if expected.trim_end_matches('\n') == actual.trim_end_matches('\n') {
+        return Ok(Value::Null);
+    }
+
+    Err(FlowError::spirit(
+        &format!(
+            "Snapshot '{}' does not match {}\n{}\nRun `flowlang test --update-snapshots` to accept the new output.",
+            name,
+            path.display(),
+            first_difference(&expected, &actual),
+        ),
+        0, 0,
+    ))
+}
+
+fn serialize_snapshot(value: &Value) -> String {
+    let mut text = match value {
+        Value::String(s) => (**s).clone(),
+        other => value_to_pretty_json(&sort_keys(other), 2),
+    };
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
+
+/// Snapshots sort Relic keys, so building the same Relic in another order still matches
+fn sort_keys(value: &Value) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(Arc::new(items.iter().map(sort_keys).collect())),
+        Value::Relic(map) => {
+            let mut sorted: RelicMap = map.iter().map(|(k, v)| (k.clone(), sort_keys(v))).collect();
+            sorted.sort_keys();
+            Value::Relic(Arc::new(sorted))
+        }
+        other => other.clone(),
+    }
+}
+
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect()
+}
+
+fn first_difference(expected: &str, actual: &str) -> String {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    let max = expected_lines.len().max(actual_lines.len());
+
+    for i in 0..max {
+        let e = expected_lines.get(i).copied();
+        let a = actual_lines.get(i).copied();
+        if e != a {
+            return format!(
+                "  first difference at line {}:\n  - {}\n  + {}",
+                i + 1,
+                e.unwrap_or("<end of snapshot>"),
+                a.unwrap_or("<end of output>"),
+            );
+        }
+    }
+
+    "  (outputs differ only in whitespace)".to_string()
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("\"{}\" (Silk)", s),
+        other => format!("{} ({})", other.to_string(), other.type_name()),
+    }
+}
+
+fn failure_message(custom: Option<&Value>, default: &str) -> String {
+    match custom {
+        Some(Value::String(s)) => format!("Assertion failed: {} — {}", s, default),
+        _ => format!("Assertion failed: {}", default),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Property-based testing
+// ═══════════════════════════════════════════════════════════════
+
+const GENERATOR_KEY: &str = "__generator";
+const DEFAULT_RUNS: usize = 100;
+const MAX_SHRINK_STEPS: usize = 500;
+
+/// Parsed form of a generator Relic
+#[derive(Debug, Clone)]
+enum Generator {
+    Ember { min: i64, max: i64 },
+    Silk { max_len: usize },
+    Pulse,
+    Constellation { item: Box<Generator>, max_len: usize },
+    Relic { fields: Vec<(String, Generator)> },
+}
+
+/// assert.ember(min?, max?) -> Relic
+/// Generator for whole Embers in [min, max] (default -1000..1000).
+fn gen_ember(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() > 2 {
+        return Err(FlowError::runtime("assert.ember expects 0-2 arguments (min?, max?)", 0, 0));
+    }
+    let min = optional_number(&args, 0, -1000.0, "assert.ember")?;
+    let max = optional_number(&args, 1, 1000.0, "assert.ember")?;
+    if min > max {
+        return Err(FlowError::runtime("assert.ember min must not exceed max", 0, 0));
+    }
+    Ok(descriptor("ember", vec![("min", Value::Number(min)), ("max", Value::Number(max))]))
+}
+
+/// assert.silk(maxLength?) -> Relic
+/// Generator for printable Silks up to maxLength characters (default 20).
+fn gen_silk(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() > 1 {
+        return Err(FlowError::runtime("assert.silk expects 0-1 arguments (maxLength?)", 0, 0));
+    }
+    let max_len = optional_number(&args, 0, 20.0, "assert.silk")?;
+    Ok(descriptor("silk", vec![("maxLength", Value::Number(max_len))]))
+}
+
+/// assert.pulse() -> Relic
+fn gen_pulse(args: Vec<Value>) -> Result<Value, FlowError> {
+    if !args.is_empty() {
+        return Err(FlowError::runtime("assert.pulse takes no arguments", 0, 0));
+    }
+    Ok(descriptor("pulse", vec![]))
+}
+
+/// assert.constellation(itemGenerator, maxLength?) -> Relic
+fn gen_constellation(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(FlowError::runtime(
+            "assert.constellation expects 1-2 arguments (itemGenerator, maxLength?)",
+            0, 0,
+        ));
+    }
+    parse_generator(&args[0])?;
+    let max_len = optional_number(&args, 1, 10.0, "assert.constellation")?;
+    Ok(descriptor("constellation", vec![("item", args[0].clone()), ("maxLength", Value::Number(max_len))]))
+}
+
+/// assert.relic(shape) -> Relic
+/// Generator for Relics whose fields come from the generators in `shape`.
+fn gen_relic(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime("assert.relic expects 1 argument (shape)", 0, 0));
+    }
+    match &args[0] {
+        Value::Relic(_) => {
+            parse_generator(&args[0])?;
+            Ok(descriptor("relic", vec![("shape", args[0].clone())]))
+        }
+        _ => Err(FlowError::type_error("assert.relic expects a Relic of generators", 0, 0)),
+    }
+}
+
+/// assert.forall(generator, spell, options?) -> Hollow
+/// Calls the Spell with generated inputs `runs` times (default 100). The
+/// property fails when the Spell returns false or raises; the failing input
+/// is then shrunk to a minimal counterexample. A Constellation of generators
+/// passes one argument per generator. Options: `{ runs, seed }`.
+fn assert_forall(args: Vec<Value>, interp: &mut Interpreter) -> InterpreterFuture<'_> {
+    Box::pin(async move {
+        if args.len() < 2 || args.len() > 3 {
+            return Err(FlowError::runtime(
+                "assert.forall expects 2 or 3 arguments (generator, spell, options?)",
+                0, 0,
+            ));
+        }
+
+        let generators = match &args[0] {
+            Value::Array(items) => items.iter().map(parse_generator).collect::<Result<Vec<_>, _>>()?,
+            other => vec![parse_generator(other)?],
+        };
+
+        let spell = args[1].clone();
+        if spell.type_name() != "Spell" {
+            return Err(FlowError::type_error("assert.forall expects a Spell as second argument", 0, 0));
+        }
+
+        let (runs, seed) = match args.get(2) {
+            Some(Value::Relic(opts)) => {
+                let runs = match opts.get("runs") {
+                    Some(Value::Number(n)) if *n >= 1.0 => *n as usize,
+                    Some(_) => return Err(FlowError::type_error("assert.forall option 'runs' must be a positive Ember", 0, 0)),
+                    None => DEFAULT_RUNS,
+                };
+                let seed = match opts.get("seed") {
+                    Some(Value::Number(n)) => *n as u64,
+                    Some(_) => return Err(FlowError::type_error("assert.forall option 'seed' must be an Ember", 0, 0)),
+                    None => time_seed(),
+                };
+                (runs, seed)
+            }
+            Some(_) => return Err(FlowError::type_error("assert.forall options must be a Relic", 0, 0)),
+            None => (DEFAULT_RUNS, time_seed()),
+        };
+
+        let mut rng = SplitMix64::new(seed);
+
+        for run in 0..runs {
+            // Start with small inputs and grow, so simple failures show up first
+            let size = 1 + run * 100 / runs;
+            let inputs: Vec<Value> = generators.iter().map(|g| generate(g, &mut rng, size)).collect();
+
+            let failure = match check_property(interp, &spell, inputs.clone()).await {
+                Some(reason) => reason,
+                None => continue,
+            };
+
+            let (minimal, reason, steps) = shrink_failure(interp, &spell, &generators, inputs.clone(), failure).await;
+
+            let shown: Vec<String> = minimal.iter().map(value_to_json_string).collect();
+            let original: Vec<String> = inputs.iter().map(value_to_json_string).collect();
+            return Err(FlowError::spirit(
+                &format!(
+                    "Property failed after {} run(s) (seed {})\n  counterexample: {}\n  original input: {} (shrunk in {} step(s))\n  cause: {}",
+                    run + 1,
+                    seed,
+                    shown.join(", "),
+                    original.join(", "),
+                    steps,
+                    reason,
+                ),
+                0, 0,
+            ));
+        }
+
+        Ok(Value::Null)
+    })
+}
+
+/// Runs the property once. Returns the failure reason, if any.
+async fn check_property(interp: &mut Interpreter, spell: &Value, inputs: Vec<Value>) -> Option<String> {
+    match interp.execute_function(spell.clone(), inputs).await {
+        Ok(Value::Boolean(false)) => Some("Spell returned false".to_string()),
+        Ok(_) => None,
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+/// Greedily replaces the failing inputs with simpler ones that still fail.
+async fn shrink_failure(
+    interp: &mut Interpreter,
+    spell: &Value,
+    generators: &[Generator],
+    mut current: Vec<Value>,
+    mut reason: String,
+) -> (Vec<Value>, String, usize) {
+    let mut steps = 0;
+
+    'outer: while steps < MAX_SHRINK_STEPS {
+        for (i, generator) in generators.iter().enumerate() {
+            for candidate in shrink(generator, &current[i]) {
+                let mut trial = current.clone();
+                trial[i] = candidate;
+                if let Some(new_reason) = check_property(interp, spell, trial.clone()).await {
+                    current = trial;
+                    reason = new_reason;
+                    steps += 1;
+                    continue 'outer;
+                }
+            }
+        }
+        break;
+    }
+
+    (current, reason, steps)
+}
+
+fn parse_generator(value: &Value) -> Result<Generator, FlowError> {
+    let map = match value {
+        Value::Relic(map) => map,
+        _ => return Err(FlowError::type_error(
+            &format!("Expected a generator (e.g. assert.ember()), got {}", value.type_name()),
+            0, 0,
+        )),
+    };
+
+    let kind = match map.get(GENERATOR_KEY) {
+        Some(Value::String(kind)) => kind.as_str().to_string(),
+        // A plain Relic of generators describes the shape of generated Relics
+        _ => {
+            let mut fields = Vec::new();
+            for (key, field) in map.iter() {
+                fields.push((key.clone(), parse_generator(field)?));
+            }
+            fields.sort_by(|a, b| a.0.cmp(&b.0));
+            return Ok(Generator::Relic { fields });
+        }
+    };
+
+    let number = |key: &str| match map.get(key) {
+        Some(Value::Number(n)) => Ok(*n),
+        _ => Err(FlowError::glitch(&format!("Malformed {} generator: missing '{}'", kind, key), 0, 0)),
+    };
+
+    match kind.as_str() {
+        "ember" => Ok(Generator::Ember { min: number("min")? as i64, max: number("max")? as i64 }),
+        "silk" => Ok(Generator::Silk { max_len: number("maxLength")? as usize }),
+        "pulse" => Ok(Generator::Pulse),
+        "constellation" => {
+            let item = map.get("item").ok_or_else(|| FlowError::glitch("Malformed constellation generator: missing 'item'", 0, 0))?;
+            Ok(Generator::Constellation {
+                item: Box::new(parse_generator(item)?),
+                max_len: number("maxLength")? as usize,
+            })
+        }
+        "relic" => match map.get("shape") {
+            Some(shape @ Value::Relic(_)) => parse_generator(shape),
+            _ => Err(FlowError::glitch("Malformed relic generator: missing 'shape'", 0, 0)),
+        },
+        other => Err(FlowError::glitch(&format!("Unknown generator kind '{}'", other), 0, 0)),
+    }
+}
+
+fn generate(generator: &Generator, rng: &mut SplitMix64, size: usize) -> Value {
+    match generator {
+        Generator::Ember { min, max } => {
+            // Keep early runs close to zero, then widen towards the full range
+            let scale = (size as i64).max(1) * ((max - min) / 100).max(1);
+            let low = (*min).max(shrink_target(*min, *max) - scale);
+            let high = (*max).min(shrink_target(*min, *max) + scale);
+            Value::Number(rng.range_i64(low, high) as f64)
+        }
+        Generator::Silk { max_len } => {
+            const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 _-.,!?:/";
+            let len = rng.range_i64(0, (*max_len).min(size) as i64) as usize;
+            let text: String = (0..len)
+                .map(|_| ALPHABET[rng.range_i64(0, ALPHABET.len() as i64 - 1) as usize] as char)
+                .collect();
+            Value::String(Arc::new(text))
+        }
+        Generator::Pulse => Value::Boolean(rng.next_u64() & 1 == 0),
+        Generator::Constellation { item, max_len } => {
+            let len = rng.range_i64(0, (*max_len).min(size) as i64) as usize;
+            Value::Array(Arc::new((0..len).map(|_| generate(item, rng, size)).collect()))
+        }
+        Generator::Relic { fields } => {
+            let mut map = RelicMap::new();
+            for (key, field) in fields {
+                map.insert(key.clone(), generate(field, rng, size));
+            }
+            Value::Relic(Arc::new(map))
+        }
+    }
+}
+
+/// Simpler candidates for a failing value, most aggressive first.
+fn shrink(generator: &Generator, value: &Value) -> Vec<Value> {
+    let mut candidates = Vec::new();
+
+    match (generator, value) {
+        (Generator::Ember { min, max }, Value::Number(n)) => {
+            let n = *n as i64;
+            let target = shrink_target(*min, *max);
+            if n != target {
+                let mut numbers = vec![target];
+                let half = n - (n - target) / 2;
+                if half != n && half != target {
+                    numbers.push(half);
+                }
+                let step = n - (n - target).signum();
+                if step != target && step != half {
+                    numbers.push(step);
+                }
+                candidates.extend(numbers.into_iter().map(|c| Value::Number(c as f64)));
+            }
+        }
+        (Generator::Silk { .. }, Value::String(s)) => {
+            let chars: Vec<char> = s.chars().collect();
+            if chars.is_empty() {
+                return candidates;
+            }
+            candidates.push(Value::String(Arc::new(String::new())));
+            if chars.len() > 1 {
+                candidates.push(Value::String(Arc::new(chars[..chars.len() / 2].iter().collect())));
+            }
+            for i in 0..chars.len() {
+                let mut shorter = chars.clone();
+                shorter.remove(i);
+                candidates.push(Value::String(Arc::new(shorter.into_iter().collect())));
+            }
+            for i in 0..chars.len() {
+                if chars[i] != 'a' {
+                    let mut simpler = chars.clone();
+                    simpler[i] = 'a';
+                    candidates.push(Value::String(Arc::new(simpler.into_iter().collect())));
+                }
+            }
+        }
+        (Generator::Pulse, Value::Boolean(true)) => candidates.push(Value::Boolean(false)),
+        (Generator::Constellation { item, .. }, Value::Array(items)) => {
+            if items.is_empty() {
+                return candidates;
+            }
+            candidates.push(Value::Array(Arc::new(Vec::new())));
+            if items.len() > 1 {
+                candidates.push(Value::Array(Arc::new(items[..items.len() / 2].to_vec())));
+            }
+            for i in 0..items.len() {
+                let mut fewer = items.as_ref().clone();
+                fewer.remove(i);
+                candidates.push(Value::Array(Arc::new(fewer)));
+            }
+            for i in 0..items.len() {
+                for simpler in shrink(item, &items[i]) {
+                    let mut next = items.as_ref().clone();
+                    next[i] = simpler;
+                    candidates.push(Value::Array(Arc::new(next)));
+                }
+            }
+        }
+        (Generator::Relic { fields }, Value::Relic(map)) => {
+            for (key, field) in fields {
+                if let Some(current) = map.get(key) {
+                    for simpler in shrink(field, current) {
+                        let mut next = map.as_ref().clone();
+                        next.insert(key.clone(), simpler);
+                        candidates.push(Value::Relic(Arc::new(next)));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    candidates
+}
+
+/// The value shrinking moves towards: zero when in range, else the nearest bound
+fn shrink_target(min: i64, max: i64) -> i64 {
+    0.clamp(min, max)
+}
+
+fn descriptor(kind: &str, fields: Vec<(&str, Value)>) -> Value {
+    let mut map = RelicMap::new();
+    map.insert(GENERATOR_KEY.to_string(), Value::String(Arc::new(kind.to_string())));
+    for (key, value) in fields {
+        map.insert(key.to_string(), value);
+    }
+    Value::Relic(Arc::new(map))
+}
+
+fn optional_number(args: &[Value], index: usize, default: f64, name: &str) -> Result<f64, FlowError> {
+    match args.get(index) {
+        None => Ok(default),
+        Some(Value::Number(n)) => Ok(*n),
+        Some(other) => Err(FlowError::type_error(
+            &format!("{} expects Ember arguments, got {}", name, other.type_name()),
+            0, 0,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_modes() {
+        let dir = std::env::temp_dir().join(format!("flowlang_snapshots_{}", std::process::id()));
+        let at = |mode| Snapshots { dir: dir.clone(), mode };
+        let args = |text: &str| vec![Value::String(Arc::new(text.to_string())), Value::String(Arc::new("greeting".to_string()))];
+
+        // Checking never writes a missing snapshot
+        let missing = match_snapshot(args("hello"), &at(SnapshotMode::Check)).unwrap_err();
+        assert!(missing.to_string().contains("Snapshot 'greeting' is missing"));
+        assert!(!dir.join("greeting.snap").exists());
+
+        match_snapshot(args("hello"), &at(SnapshotMode::Record)).unwrap();
+        match_snapshot(args("hello"), &at(SnapshotMode::Check)).unwrap();
+        assert!(match_snapshot(args("goodbye"), &at(SnapshotMode::Record)).is_err());
+        match_snapshot(args("goodbye"), &at(SnapshotMode::Update)).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("greeting.snap")).unwrap(), "goodbye\n");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,92 @@
+use crate::types::{Value, NativeFn, RelicMap};
+use crate::error::FlowError;
+use std::sync::Arc;
+
+/// Largest whole Ember that converts to and from an integer without loss (2^53 - 1)
+const MAX_WHOLE: i64 = (1 << 53) - 1;
+
+pub fn get_module() -> RelicMap {
+    let mut module = RelicMap::new();
+
+    module.insert("and".to_string(), create_bits_fn("and", |a, b| Ok(a & b)));
+    module.insert("or".to_string(), create_bits_fn("or", |a, b| Ok(a | b)));
+    module.insert("xor".to_string(), create_bits_fn("xor", |a, b| Ok(a ^ b)));
+    module.insert("shl".to_string(), create_bits_fn("shl", shift_left));
+    module.insert("shr".to_string(), create_bits_fn("shr", |a, n| Ok(a >> shift_count("shr", n)?)));
+
+    module.insert("not".to_string(), Value::NativeFunction(NativeFn(Arc::new(|args| {
+        match args.as_slice() {
+            [n] => Ok(Value::Number(!whole("not", n)? as f64)),
+            _ => Err(FlowError::runtime("not() expects 1 argument", 0, 0)),
+        }
+    }))));
+
+    module.insert("popcount".to_string(), Value::NativeFunction(NativeFn(Arc::new(|args| {
+        match args.as_slice() {
+            [n] => Ok(Value::Number(whole("popcount", n)?.count_ones() as f64)),
+            _ => Err(FlowError::runtime("popcount() expects 1 argument", 0, 0)),
+        }
+    }))));
+
+    module
+}
+
+/// A whole Ember as a 64-bit integer; fractions and values past 2^53 would lose bits
+fn whole(name: &str, value: &Value) -> Result<i64, FlowError> {
+    match value {
+        Value::Number(n) if n.fract() == 0.0 && n.abs() <= MAX_WHOLE as f64 => Ok(*n as i64),
+        Value::Number(n) => Err(FlowError::type_error(
+            &format!("{}() needs whole Embers no larger than 2^53, got {}", name, n),
+            0,
+            0,
+        )),
+        other => Err(FlowError::type_error(
+            &format!("{}() expects Embers, got {}", name, other.type_name()),
+            0,
+            0,
+        )),
+    }
+}
+
+fn shift_count(name: &str, n: i64) -> Result<u32, FlowError> {
+    if !(0..64).contains(&n) {
+        return Err(FlowError::out_of_range(&format!("{}() shift must be from 0 to 63, got {}", name, n), 0, 0));
+    }
+    Ok(n as u32)
+}
+
+/// `a << n`, refusing results an Ember can't hold exactly
+pub fn shift_left(a: i64, n: i64) -> Result<i64, FlowError> {
+    let n = shift_count("shl", n)?;
+    let shifted = a.checked_shl(n).filter(|s| s >> n == a && s.unsigned_abs() <= MAX_WHOLE as u64);
+    shifted.ok_or_else(|| {
+        FlowError::out_of_range(&format!("shl() result of {} << {} is larger than 2^53", a, n), 0, 0)
+    })
+}
+
+fn create_bits_fn(name: &'static str, op: fn(i64, i64) -> Result<i64, FlowError>) -> Value {
+    Value::NativeFunction(NativeFn(Arc::new(move |args| {
+        match args.as_slice() {
+            [a, b] => Ok(Value::Number(op(whole(name, a)?, whole(name, b)?)? as f64)),
+            _ => Err(FlowError::runtime(&format!("{}() expects 2 arguments", name), 0, 0)),
+        }
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whole_numbers_only() {
+        assert_eq!(whole("and", &Value::Number(-1.0)).unwrap(), -1);
+        assert!(whole("and", &Value::Number(1.5)).is_err());
+        assert!(whole("and", &Value::Number(2f64.powi(60))).is_err());
+        assert_eq!(shift_left(1, 52).unwrap(), 1 << 52);
+        assert_eq!(shift_left(-3, 4).unwrap(), -48);
+        assert!(shift_left(1, 53).is_err());
+        assert!(shift_left(-(1 << 52), 11).is_err());
+        assert!(shift_left(1, 64).is_err());
+        assert!(shift_left(1, -1).is_err());
+    }
+}
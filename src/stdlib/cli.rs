@@ -1,114 +1,320 @@
 use crate::error::FlowError;
-use crate::types::{NativeFn, Value};
-use std::io::{self, Write};
-use std::sync::Arc;
+use crate::interpreter::Interpreter;
+use crate::types::{AsyncContext, AsyncNativeFn, InterpreterFuture, InterpreterNativeFn, NativeFn, Value, RelicMap};
+use colored::Colorize;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 pub fn load_cli_module() -> Vec<(&'static str, Value)> {
     vec![
-        ("input", Value::NativeFunction(NativeFn::new(cli_input))),
+        ("input", Value::AsyncNativeFunction(AsyncNativeFn::new(cli_prompt))),
+        ("prompt", Value::AsyncNativeFunction(AsyncNativeFn::new(cli_prompt))),
+        ("password", Value::AsyncNativeFunction(AsyncNativeFn::new(cli_password))),
+        ("confirm", Value::AsyncNativeFunction(AsyncNativeFn::new(cli_confirm))),
+        ("select", Value::AsyncNativeFunction(AsyncNativeFn::new(cli_select))),
         ("args", Value::NativeFunction(NativeFn::new(cli_args))),
-        ("confirm", Value::NativeFunction(NativeFn::new(cli_confirm))),
-        ("select", Value::NativeFunction(NativeFn::new(cli_select))),
+        ("progress", Value::NativeFunction(NativeFn::new(cli_progress))),
+        ("spinner", Value::NativeFunction(NativeFn::new(cli_spinner))),
+        ("table", Value::NativeFunction(NativeFn::new(cli_table))),
         ("clear", Value::NativeFunction(NativeFn::new(cli_clear))),
         ("exit", Value::NativeFunction(NativeFn::new(cli_exit))),
+        ("stdin", Value::InterpreterNativeFunction(InterpreterNativeFn::new(cli_stdin))),
+        ("isTTY", Value::NativeFunction(NativeFn::new(cli_is_tty))),
     ]
 }
 
-// cli::input(prompt: Silk) -> Silk
-fn cli_input(args: Vec<Value>) -> Result<Value, FlowError> {
+// cli::prompt(question: Silk, default?: Silk) -> Silk
+// Also exported as cli::input. Returns the default (or "") on an empty answer or closed stdin.
+async fn cli_prompt(args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(FlowError::runtime(
+            "cli::prompt expects 1-2 arguments (question, default?)",
+            0,
+            0,
+        ));
+    }
+
+    let question = prompt_arg(&args[0], "prompt")?;
+    let default = args.get(1).map(|d| d.to_string()).unwrap_or_default();
+
+    let shown = if default.is_empty() {
+        question
+    } else {
+        format!("{}[{}] ", question, default)
+    };
+
+    let answer = match read_line(shown).await? {
+        Some(line) if !line.is_empty() => line,
+        _ => default,
+    };
+    Ok(Value::String(Arc::new(answer)))
+}
+
+// cli::password(prompt: Silk) -> Silk
+async fn cli_password(args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
     if args.len() != 1 {
         return Err(FlowError::runtime(
-            "cli::input expects 1 argument (prompt)",
+            "cli::password expects 1 argument (prompt)",
             0,
             0,
         ));
     }
 
-    let prompt = match &args[0] {
-        Value::String(s) => s.clone(),
-        _ => {
-            return Err(FlowError::type_error(
-                "cli::input expects a Silk prompt",
-                0,
-                0,
-            ))
+    let prompt = prompt_arg(&args[0], "password")?;
+
+    let result = tokio::task::spawn_blocking(move || {
+        if io::stdin().is_terminal() {
+            rpassword::prompt_password(prompt)
+        } else {
+            // Nothing to hide when input is piped in
+            print!("{}", prompt);
+            io::stdout().flush()?;
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            Ok(input.trim_end_matches(['\r', '\n']).to_string())
         }
-    };
+    })
+    .await
+    .map_err(|e| FlowError::runtime(&format!("cli::password was interrupted: {}", e), 0, 0))?;
 
-    print!("{}", prompt);
-    io::stdout().flush().unwrap();
+    match result {
+        Ok(password) => Ok(Value::String(Arc::new(password))),
+        Err(e) => Err(FlowError::rift(
+            &format!("Failed to read password: {}", e),
+            0,
+            0,
+        )),
+    }
+}
 
-    let mut input = String::new();
-    match io::stdin().read_line(&mut input) {
-        Ok(_) => Ok(Value::String(Arc::new(input.trim().to_string()))),
-        Err(e) => Err(FlowError::runtime(
-            &format!("Failed to read input: {}", e),
+// cli::args(spec?: Relic) -> Constellation<Silk> | Relic
+// With a spec, parses the arguments into a Relic of options plus `positional`.
+fn cli_args(args: Vec<Value>) -> Result<Value, FlowError> {
+    // Set by main.rs before the script runs
+    let script_args = crate::platform::script_args();
+    match args.as_slice() {
+        [] => Ok(Value::Array(Arc::new(
+            script_args.iter().map(|arg| Value::String(Arc::new(arg.clone()))).collect(),
+        ))),
+        [Value::Relic(spec)] => parse_args(script_args, spec).map(|parsed| Value::Relic(Arc::new(parsed))),
+        _ => Err(FlowError::type_error(
+            "cli::args expects no arguments, or a Relic of options and their defaults",
             0,
             0,
         )),
     }
 }
 
-// cli::args() -> Constellation<Silk>
-fn cli_args(_args: Vec<Value>) -> Result<Value, FlowError> {
-    // Read from environment variable set by main.rs
-    let args: Vec<Value> = if let Ok(args_str) = std::env::var("FLOWLANG_SCRIPT_ARGS") {
-        if args_str.is_empty() {
-            Vec::new()
+// Each spec key is an option and its default decides how it is read: a Pulse is a
+// flag (`--verbose`, `--no-verbose`), an Ember or Silk takes a value (`--port 80`,
+// `--port=80`), Hollow is a Silk with no default, and a Constellation collects every
+// use. `--dry-run` fills `dryRun`, a single letter can be given as `-v`, and
+// everything after `--` is positional.
+fn parse_args(script_args: &[String], spec: &RelicMap) -> Result<RelicMap, FlowError> {
+    if spec.contains_key("positional") {
+        return Err(FlowError::runtime("cli::args: 'positional' is reserved for the leftover arguments", 0, 0));
+    }
+    let mut parsed = spec.clone();
+    let mut positional = Vec::new();
+    let mut args = script_args.iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            positional.extend(args.by_ref().map(|arg| Value::String(Arc::new(arg.clone()))));
+            break;
+        }
+        let Some(option) = arg.strip_prefix("--").or_else(|| {
+            arg.strip_prefix('-').filter(|name| name.chars().next().is_some_and(|c| c.is_alphabetic()))
+        }) else {
+            positional.push(Value::String(Arc::new(arg.clone())));
+            continue;
+        };
+
+        let (written, inline) = match option.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (option, None),
+        };
+        let (name, negated) = match option_key(spec, written) {
+            Some(name) => (name, false),
+            None => match written.strip_prefix("no-").and_then(|rest| option_key(spec, rest)) {
+                Some(name) if matches!(spec[&name], Value::Boolean(_)) && inline.is_none() => (name, true),
+                _ => return Err(FlowError::runtime(&format!("Unknown option '{}'", arg), 0, 0)),
+            },
+        };
+
+        let value = match &spec[&name] {
+            Value::Boolean(_) => match inline.as_deref() {
+                None => Value::Boolean(!negated),
+                Some("true") => Value::Boolean(true),
+                Some("false") => Value::Boolean(false),
+                Some(other) => return Err(FlowError::type_error(
+                    &format!("Option '--{}' is a flag and takes true or false, not '{}'", written, other),
+                    0,
+                    0,
+                )),
+            },
+            default => {
+                let text = match inline.or_else(|| args.next().cloned()) {
+                    Some(text) => text,
+                    None => return Err(FlowError::runtime(&format!("Option '--{}' needs a value", written), 0, 0)),
+                };
+                match default {
+                    Value::Number(_) => match text.trim().parse::<f64>() {
+                        Ok(n) => Value::Number(n),
+                        Err(_) => return Err(FlowError::type_error(
+                            &format!("Option '--{}' expects an Ember, got '{}'", written, text),
+                            0,
+                            0,
+                        )),
+                    },
+                    Value::Array(_) => {
+                        let mut values = match &parsed[&name] {
+                            // The first use replaces the default
+                            current if current.deep_equals(default) => Vec::new(),
+                            Value::Array(current) => current.to_vec(),
+                            _ => Vec::new(),
+                        };
+                        values.push(Value::String(Arc::new(text)));
+                        Value::Array(Arc::new(values))
+                    }
+                    _ => Value::String(Arc::new(text)),
+                }
+            }
+        };
+        parsed.insert(name, value);
+    }
+
+    parsed.insert("positional".to_string(), Value::Array(Arc::new(positional)));
+    Ok(parsed)
+}
+
+// The spec key an option was written for: as-is, or `dry-run` as `dryRun`
+fn option_key(spec: &RelicMap, written: &str) -> Option<String> {
+    if spec.contains_key(written) {
+        return Some(written.to_string());
+    }
+    let mut camel = String::new();
+    let mut upper = false;
+    for c in written.chars() {
+        if c == '-' {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
         } else {
-            args_str
-                .split('\x1F') // Unit separator
-                .map(|arg| Value::String(Arc::new(arg.to_string())))
-                .collect()
+            camel.push(c);
         }
-    } else {
-        Vec::new()
-    };
+    }
+    spec.contains_key(&camel).then_some(camel)
+}
+
+// cli::stdin(spell?: Spell) -> Silk | Ember
+// Without a Spell, reads all of stdin. With one, calls it with each line as it
+// arrives and returns how many lines were read; returning false stops early.
+fn cli_stdin(args: Vec<Value>, interp: &mut Interpreter) -> InterpreterFuture<'_> {
+    Box::pin(async move {
+        match args.as_slice() {
+            [] => {
+                let text = tokio::task::spawn_blocking(|| io::read_to_string(io::stdin()))
+                    .await
+                    .map_err(|e| FlowError::runtime(&format!("Reading stdin was interrupted: {}", e), 0, 0))?
+                    .map_err(|e| FlowError::rift(&format!("Failed to read stdin: {}", e), 0, 0))?;
+                Ok(Value::String(Arc::new(text)))
+            }
+            [spell] if spell.type_name() == "Spell" => {
+                let mut count = 0;
+                while let Some(line) = read_stdin_line().await? {
+                    count += 1;
+                    let result = interp.execute_function(spell.clone(), vec![Value::String(Arc::new(line))]).await?;
+                    if matches!(result, Value::Boolean(false)) {
+                        break;
+                    }
+                }
+                Ok(Value::Number(count as f64))
+            }
+            _ => Err(FlowError::type_error("cli::stdin expects no arguments, or a Spell to call with each line", 0, 0)),
+        }
+    })
+}
 
-    Ok(Value::Array(Arc::new(args)))
+// One line of stdin without its line ending, or None at the end of input
+async fn read_stdin_line() -> Result<Option<String>, FlowError> {
+    let result = tokio::task::spawn_blocking(|| -> io::Result<Option<String>> {
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let len = line.trim_end_matches(['\r', '\n']).len();
+        line.truncate(len);
+        Ok(Some(line))
+    })
+    .await
+    .map_err(|e| FlowError::runtime(&format!("Reading stdin was interrupted: {}", e), 0, 0))?;
+
+    result.map_err(|e| FlowError::rift(&format!("Failed to read stdin: {}", e), 0, 0))
 }
 
-// cli::confirm(prompt: Silk) -> Pulse
-fn cli_confirm(args: Vec<Value>) -> Result<Value, FlowError> {
-    if args.len() != 1 {
+// cli::isTTY(stream?: "stdin" | "stdout" | "stderr") -> Pulse
+fn cli_is_tty(args: Vec<Value>) -> Result<Value, FlowError> {
+    let stream = match args.as_slice() {
+        [] => "stdout".to_string(),
+        [Value::String(name)] => name.to_string(),
+        _ => return Err(FlowError::runtime("cli::isTTY expects 0-1 arguments (stream?)", 0, 0)),
+    };
+    let is_tty = match stream.as_str() {
+        "stdin" => io::stdin().is_terminal(),
+        "stdout" => io::stdout().is_terminal(),
+        "stderr" => io::stderr().is_terminal(),
+        other => return Err(FlowError::runtime(
+            &format!("cli::isTTY expects \"stdin\", \"stdout\" or \"stderr\", got '{}'", other),
+            0,
+            0,
+        )),
+    };
+    Ok(Value::Boolean(is_tty))
+}
+
+// cli::confirm(prompt: Silk, default?: Pulse) -> Pulse
+async fn cli_confirm(args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
+    if args.is_empty() || args.len() > 2 {
         return Err(FlowError::runtime(
-            "cli::confirm expects 1 argument (prompt)",
+            "cli::confirm expects 1-2 arguments (prompt, default?)",
             0,
             0,
         ));
     }
 
-    let prompt = match &args[0] {
-        Value::String(s) => s.clone(),
-        _ => {
+    let prompt = prompt_arg(&args[0], "confirm")?;
+    let default = match args.get(1) {
+        None => false,
+        Some(Value::Boolean(b)) => *b,
+        Some(_) => {
             return Err(FlowError::type_error(
-                "cli::confirm expects a Silk prompt",
+                "cli::confirm expects a Pulse default",
                 0,
                 0,
             ))
         }
     };
 
-    print!("{}", prompt);
-    io::stdout().flush().unwrap();
-
-    let mut input = String::new();
-    match io::stdin().read_line(&mut input) {
-        Ok(_) => {
-            let response = input.trim().to_lowercase();
-            let is_yes = response == "y" || response == "yes";
-            Ok(Value::Boolean(is_yes))
+    loop {
+        let response = match read_line(prompt.clone()).await? {
+            Some(line) => line.to_lowercase(),
+            None => return Ok(Value::Boolean(default)),
+        };
+        match response.as_str() {
+            "" => return Ok(Value::Boolean(default)),
+            "y" | "yes" => return Ok(Value::Boolean(true)),
+            "n" | "no" => return Ok(Value::Boolean(false)),
+            _ => println!("Please answer y or n."),
         }
-        Err(e) => Err(FlowError::runtime(
-            &format!("Failed to read input: {}", e),
-            0,
-            0,
-        )),
     }
 }
 
 // cli::select(prompt: Silk, options: Constellation<Silk>) -> Silk
-fn cli_select(args: Vec<Value>) -> Result<Value, FlowError> {
+async fn cli_select(args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
     if args.len() != 2 {
         return Err(FlowError::runtime(
             "cli::select expects 2 arguments (prompt, options)",
@@ -117,16 +323,7 @@ fn cli_select(args: Vec<Value>) -> Result<Value, FlowError> {
         ));
     }
 
-    let prompt = match &args[0] {
-        Value::String(s) => s.clone(),
-        _ => {
-            return Err(FlowError::type_error(
-                "cli::select expects a Silk prompt",
-                0,
-                0,
-            ))
-        }
-    };
+    let prompt = prompt_arg(&args[0], "select")?;
 
     let options = match &args[1] {
         Value::Array(arr) => arr.clone(),
@@ -150,60 +347,520 @@ fn cli_select(args: Vec<Value>) -> Result<Value, FlowError> {
     // Display prompt and options
     println!("{}", prompt);
     for (i, option) in options.iter().enumerate() {
-        if let Value::String(s) = option {
-            println!("  {}. {}", i + 1, s);
-        }
+        println!("  {}. {}", i + 1, option);
     }
 
     // Get user selection
     loop {
-        print!("Enter choice (1-{}): ", options.len());
-        io::stdout().flush().unwrap();
-
-        let mut input = String::new();
-        if io::stdin().read_line(&mut input).is_err() {
-            continue;
-        }
+        let input = match read_line(format!("Enter choice (1-{}): ", options.len())).await? {
+            Some(line) => line,
+            None => {
+                return Err(FlowError::rift(
+                    "cli::select: input closed before a choice was made",
+                    0,
+                    0,
+                ))
+            }
+        };
 
-        if let Ok(choice) = input.trim().parse::<usize>() {
+        if let Ok(choice) = input.parse::<usize>() {
             if choice > 0 && choice <= options.len() {
-                if let Value::String(s) = &options[choice - 1] {
-                    return Ok(Value::String(s.clone()));
-                }
+                return Ok(options[choice - 1].clone());
             }
         }
         println!("Invalid choice. Please try again.");
     }
 }
 
+fn prompt_arg(value: &Value, func: &str) -> Result<String, FlowError> {
+    match value {
+        Value::String(s) => Ok(s.to_string()),
+        _ => Err(FlowError::type_error(
+            &format!("cli::{} expects a Silk prompt", func),
+            0,
+            0,
+        )),
+    }
+}
+
+// Print the prompt and read one line on a blocking thread, so servers and
+// timers keep running while the user types. Returns None once stdin is closed.
+async fn read_line(prompt: String) -> Result<Option<String>, FlowError> {
+    let result = tokio::task::spawn_blocking(move || -> io::Result<Option<String>> {
+        print!("{}", prompt);
+        io::stdout().flush()?;
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(input.trim().to_string()))
+    })
+    .await
+    .map_err(|e| FlowError::runtime(&format!("Input was interrupted: {}", e), 0, 0))?;
+
+    result.map_err(|e| FlowError::rift(&format!("Failed to read input: {}", e), 0, 0))
+}
+
 // cli::clear() -> Hollow
 fn cli_clear(_args: Vec<Value>) -> Result<Value, FlowError> {
-    // Clear screen using ANSI escape codes (works on most terminals)
-    print!("\x1B[2J\x1B[1;1H");
-    io::stdout().flush().unwrap();
+    // Clear screen using ANSI escape codes (works on most terminals); a pipe has no screen
+    if io::stdout().is_terminal() {
+        print!("\x1B[2J\x1B[1;1H");
+        io::stdout().flush().unwrap();
+    }
     Ok(Value::Null)
 }
 
-// cli::exit(code: Ember) -> Hollow
+// cli::exit(code?: Ember) -> Hollow
+// Ends the process at once with the given status (default 0), after flushing output.
 fn cli_exit(args: Vec<Value>) -> Result<Value, FlowError> {
+    let code = match args.as_slice() {
+        [] => 0,
+        [Value::Number(n)] if n.fract() == 0.0 && (0.0..=255.0).contains(n) => *n as i32,
+        [Value::Number(n)] => {
+            return Err(FlowError::out_of_range(
+                &format!("cli::exit expects a whole exit code from 0 to 255, got {}", n),
+                0,
+                0,
+            ))
+        }
+        [_] => {
+            return Err(FlowError::type_error(
+                "cli::exit expects an Ember exit code",
+                0,
+                0,
+            ))
+        }
+        _ => {
+            return Err(FlowError::runtime(
+                "cli::exit expects 0-1 arguments (exit code?)",
+                0,
+                0,
+            ))
+        }
+    };
+
+    let _ = io::stdout().flush();
+    let _ = io::stderr().flush();
+    std::process::exit(code);
+}
+
+// cli::progress(total: Ember, options?: Relic { label, width }) -> Relic
+// Returns { tick(n?), set(n), finish(), total }. Off a terminal the bar is only drawn by finish().
+fn cli_progress(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(FlowError::runtime(
+            "cli::progress expects 1-2 arguments (total, options?)",
+            0,
+            0,
+        ));
+    }
+
+    let total = match &args[0] {
+        Value::Number(n) if *n > 0.0 => *n,
+        _ => {
+            return Err(FlowError::type_error(
+                "cli::progress expects a positive Ember total",
+                0,
+                0,
+            ))
+        }
+    };
+
+    let mut label = String::new();
+    let mut width = 30;
+    match args.get(1) {
+        Some(Value::Relic(options)) => {
+            if let Some(l) = options.get("label") {
+                label = l.to_string();
+            }
+            match options.get("width") {
+                Some(Value::Number(w)) if *w >= 1.0 => width = *w as usize,
+                Some(_) => {
+                    return Err(FlowError::type_error(
+                        "cli::progress 'width' must be a positive Ember",
+                        0,
+                        0,
+                    ))
+                }
+                None => {}
+            }
+        }
+        Some(_) => {
+            return Err(FlowError::type_error(
+                "cli::progress expects an options Relic",
+                0,
+                0,
+            ))
+        }
+        None => {}
+    }
+
+    let bar = Arc::new(Mutex::new(ProgressBar {
+        current: 0.0,
+        total,
+        width,
+        label,
+        live: io::stdout().is_terminal(),
+        finished: false,
+    }));
+
+    let tick_bar = bar.clone();
+    let tick = move |args: Vec<Value>| -> Result<Value, FlowError> {
+        let step = match args.first() {
+            None => 1.0,
+            Some(Value::Number(n)) => *n,
+            Some(_) => return Err(FlowError::type_error("progress.tick expects an Ember step", 0, 0)),
+        };
+        let mut bar = tick_bar.lock().unwrap();
+        let current = bar.current + step;
+        Ok(Value::Number(bar.update(current)))
+    };
+
+    let set_bar = bar.clone();
+    let set = move |args: Vec<Value>| -> Result<Value, FlowError> {
+        let current = match args.first() {
+            Some(Value::Number(n)) => *n,
+            _ => return Err(FlowError::type_error("progress.set expects an Ember value", 0, 0)),
+        };
+        Ok(Value::Number(set_bar.lock().unwrap().update(current)))
+    };
+
+    let finish = move |_args: Vec<Value>| -> Result<Value, FlowError> {
+        bar.lock().unwrap().finish();
+        Ok(Value::Null)
+    };
+
+    let mut progress = RelicMap::new();
+    progress.insert("tick".to_string(), Value::NativeFunction(NativeFn::new(tick)));
+    progress.insert("set".to_string(), Value::NativeFunction(NativeFn::new(set)));
+    progress.insert("finish".to_string(), Value::NativeFunction(NativeFn::new(finish)));
+    progress.insert("total".to_string(), Value::Number(total));
+    Ok(Value::Relic(Arc::new(progress)))
+}
+
+struct ProgressBar {
+    current: f64,
+    total: f64,
+    width: usize,
+    label: String,
+    /// Redraw in place on every update (only when stdout is a terminal)
+    live: bool,
+    finished: bool,
+}
+
+impl ProgressBar {
+    fn update(&mut self, current: f64) -> f64 {
+        self.current = current.clamp(0.0, self.total);
+        if self.live && !self.finished {
+            print!("\r{}", self.render());
+            let _ = io::stdout().flush();
+        }
+        self.current
+    }
+
+    fn finish(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+        if self.live {
+            println!("\r{}", self.render());
+        } else {
+            println!("{}", self.render());
+        }
+    }
+
+    fn render(&self) -> String {
+        let ratio = self.current / self.total;
+        let filled = (ratio * self.width as f64).round() as usize;
+        let mut line = format!(
+            "{}{} {:>3}% {}/{}",
+            "█".repeat(filled).green(),
+            "░".repeat(self.width - filled).dimmed(),
+            (ratio * 100.0).floor(),
+            self.current,
+            self.total
+        );
+        if !self.label.is_empty() {
+            line = format!("{} {}", self.label, line);
+        }
+        line
+    }
+}
+
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+// cli::spinner(text: Silk) -> Relic
+// Returns { update(text), succeed(text?), fail(text?), stop() }. The animation runs on its
+// own thread so it keeps moving while the script is busy.
+fn cli_spinner(args: Vec<Value>) -> Result<Value, FlowError> {
     if args.len() != 1 {
         return Err(FlowError::runtime(
-            "cli::exit expects 1 argument (exit code)",
+            "cli::spinner expects 1 argument (text)",
+            0,
+            0,
+        ));
+    }
+
+    let text = Arc::new(Mutex::new(args[0].to_string()));
+    let running = Arc::new(AtomicBool::new(true));
+    let live = io::stdout().is_terminal();
+
+    let thread = if live {
+        let text = text.clone();
+        let running = running.clone();
+        Some(std::thread::spawn(move || {
+            let mut frame = 0;
+            while running.load(Ordering::SeqCst) {
+                print!("\r\x1B[2K{} {}", SPINNER_FRAMES[frame].cyan(), text.lock().unwrap());
+                let _ = io::stdout().flush();
+                frame = (frame + 1) % SPINNER_FRAMES.len();
+                std::thread::sleep(Duration::from_millis(80));
+            }
+        }))
+    } else {
+        println!("{}", text.lock().unwrap());
+        None
+    };
+    let thread = Arc::new(Mutex::new(thread));
+
+    // Stop the animation and replace the spinner line with a final status line
+    let stopper = {
+        let text = text.clone();
+        move |symbol: Option<String>, message: Option<&Value>| {
+            if !running.swap(false, Ordering::SeqCst) {
+                return;
+            }
+            if let Some(handle) = thread.lock().unwrap().take() {
+                let _ = handle.join();
+                print!("\r\x1B[2K");
+            }
+            if let Some(symbol) = symbol {
+                let message = message.map(|m| m.to_string()).unwrap_or_else(|| text.lock().unwrap().clone());
+                println!("{} {}", symbol, message);
+            }
+            let _ = io::stdout().flush();
+        }
+    };
+    let stopper = Arc::new(stopper);
+
+    let update_text = text.clone();
+    let update = move |args: Vec<Value>| -> Result<Value, FlowError> {
+        match args.first() {
+            Some(text) => *update_text.lock().unwrap() = text.to_string(),
+            None => return Err(FlowError::runtime("spinner.update expects 1 argument (text)", 0, 0)),
+        }
+        Ok(Value::Null)
+    };
+
+    let succeed_stop = stopper.clone();
+    let succeed = move |args: Vec<Value>| -> Result<Value, FlowError> {
+        succeed_stop(Some("✔".green().to_string()), args.first());
+        Ok(Value::Null)
+    };
+
+    let fail_stop = stopper.clone();
+    let fail = move |args: Vec<Value>| -> Result<Value, FlowError> {
+        fail_stop(Some("✖".red().to_string()), args.first());
+        Ok(Value::Null)
+    };
+
+    let stop = move |_args: Vec<Value>| -> Result<Value, FlowError> {
+        stopper(None, None);
+        Ok(Value::Null)
+    };
+
+    let mut spinner = RelicMap::new();
+    spinner.insert("update".to_string(), Value::NativeFunction(NativeFn::new(update)));
+    spinner.insert("succeed".to_string(), Value::NativeFunction(NativeFn::new(succeed)));
+    spinner.insert("fail".to_string(), Value::NativeFunction(NativeFn::new(fail)));
+    spinner.insert("stop".to_string(), Value::NativeFunction(NativeFn::new(stop)));
+    Ok(Value::Relic(Arc::new(spinner)))
+}
+
+// cli::table(rows: Constellation, headers?: Constellation<Silk>) -> Silk
+// Rows are Relics (columns from headers, or every key in first-seen order) or Constellations.
+fn cli_table(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(FlowError::runtime(
+            "cli::table expects 1-2 arguments (rows, headers?)",
             0,
             0,
         ));
     }
 
-    let code = match &args[0] {
-        Value::Number(n) => *n as i32,
+    let rows = match &args[0] {
+        Value::Array(rows) => rows.clone(),
         _ => {
             return Err(FlowError::type_error(
-                "cli::exit expects an Ember exit code",
+                "cli::table expects a Constellation of rows",
                 0,
                 0,
             ))
         }
     };
 
-    std::process::exit(code);
+    let mut headers: Vec<String> = match args.get(1) {
+        Some(Value::Array(headers)) => headers.iter().map(|h| h.to_string()).collect(),
+        Some(_) => {
+            return Err(FlowError::type_error(
+                "cli::table expects a Constellation of headers",
+                0,
+                0,
+            ))
+        }
+        None => Vec::new(),
+    };
+
+    let has_relics = rows.iter().any(|row| matches!(row, Value::Relic(_)));
+    if headers.is_empty() && has_relics {
+        for row in rows.iter() {
+            if let Value::Relic(map) = row {
+                for key in map.keys() {
+                    if !headers.contains(key) {
+                        headers.push(key.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    let mut cells: Vec<Vec<(String, bool)>> = Vec::with_capacity(rows.len());
+    for row in rows.iter() {
+        let values: Vec<Option<&Value>> = match row {
+            Value::Relic(map) => headers.iter().map(|h| map.get(h)).collect(),
+            Value::Array(items) => items.iter().map(Some).collect(),
+            _ => {
+                return Err(FlowError::type_error(
+                    "cli::table rows must be Relics or Constellations",
+                    0,
+                    0,
+                ))
+            }
+        };
+        cells.push(
+            values
+                .into_iter()
+                .map(|v| match v {
+                    Some(Value::Null) | None => (String::new(), false),
+                    Some(v) => (v.to_string(), matches!(v, Value::Number(_))),
+                })
+                .collect(),
+        );
+    }
+
+    let columns = cells.iter().map(|row| row.len()).max().unwrap_or(0).max(headers.len());
+    let mut widths = vec![0; columns];
+    for (i, header) in headers.iter().enumerate() {
+        widths[i] = display_width(header);
+    }
+    for row in &cells {
+        for (i, (cell, _)) in row.iter().enumerate() {
+            widths[i] = widths[i].max(display_width(cell));
+        }
+    }
+
+    let border = |left: &str, mid: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+        format!("{}{}{}", left, segments.join(mid), right).dimmed().to_string()
+    };
+    let bar = "│".dimmed().to_string();
+
+    let mut lines = vec![border("┌", "┬", "┐")];
+    if !headers.is_empty() {
+        let header_cells: Vec<String> = (0..columns)
+            .map(|i| {
+                let header = headers.get(i).map(|h| h.as_str()).unwrap_or("");
+                format!(" {} ", pad(&header.bold().cyan().to_string(), widths[i], false))
+            })
+            .collect();
+        lines.push(format!("{}{}{}", bar, header_cells.join(&bar), bar));
+        lines.push(border("├", "┼", "┤"));
+    }
+    for row in &cells {
+        let row_cells: Vec<String> = (0..columns)
+            .map(|i| {
+                let (cell, numeric) = row.get(i).map(|(c, n)| (c.as_str(), *n)).unwrap_or(("", false));
+                format!(" {} ", pad(cell, widths[i], numeric))
+            })
+            .collect();
+        lines.push(format!("{}{}{}", bar, row_cells.join(&bar), bar));
+    }
+    lines.push(border("└", "┴", "┘"));
+
+    Ok(Value::String(Arc::new(lines.join("\n"))))
+}
+
+// Width of a cell as shown on screen, ignoring ANSI color codes
+fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1B' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+// Numbers are right-aligned, everything else left-aligned
+fn pad(text: &str, width: usize, right: bool) -> String {
+    let fill = " ".repeat(width.saturating_sub(display_width(text)));
+    if right {
+        format!("{}{}", fill, text)
+    } else {
+        format!("{}{}", text, fill)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(entries: &[(&str, Value)]) -> RelicMap {
+        let mut spec = RelicMap::new();
+        for (name, default) in entries {
+            spec.insert(name.to_string(), default.clone());
+        }
+        spec
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args_reads_options_by_default_type() {
+        let spec = spec(&[
+            ("port", Value::Number(8080.0)),
+            ("dryRun", Value::Boolean(false)),
+            ("color", Value::Boolean(true)),
+            ("v", Value::Boolean(false)),
+            ("name", Value::Null),
+            ("tag", Value::Array(Arc::new(vec![]))),
+        ]);
+        let parsed = parse_args(
+            &args(&["build", "--port=9000", "--dry-run", "--no-color", "-v", "--tag", "a", "--tag=b", "--", "--port"]),
+            &spec,
+        )
+        .unwrap();
+        assert_eq!(parsed["port"].to_string(), "9000");
+        assert_eq!(parsed["dryRun"].to_string(), "true");
+        assert_eq!(parsed["color"].to_string(), "false");
+        assert_eq!(parsed["v"].to_string(), "true");
+        assert!(matches!(parsed["name"], Value::Null));
+        assert_eq!(parsed["tag"].to_string(), "[a, b]");
+        assert_eq!(parsed["positional"].to_string(), "[build, --port]");
+
+        assert!(parse_args(&args(&["--nope"]), &spec).is_err());
+        assert!(parse_args(&args(&["--port"]), &spec).is_err());
+        assert!(parse_args(&args(&["--port", "high"]), &spec).is_err());
+    }
 }
@@ -0,0 +1,184 @@
+//! std:collections - Mutable queues, stacks and deques
+//!
+//! - `collections.queue(items?)` - First in, first out
+//! - `collections.stack(items?)` - Last in, first out
+//! - `collections.deque(items?)` - Push and pop at both ends
+//!
+//! Constellations are immutable, so every push or pop copies them. These
+//! collections change in place instead: pushes and pops at either end are O(1),
+//! and every copy of a queue points at the same storage.
+
+use crate::error::FlowError;
+use crate::types::{NativeFn, Value, RelicMap};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+pub fn load_collections_module() -> Vec<(&'static str, Value)> {
+    vec![
+        ("queue", Value::NativeFunction(NativeFn::new(collections_queue))),
+        ("stack", Value::NativeFunction(NativeFn::new(collections_stack))),
+        ("deque", Value::NativeFunction(NativeFn::new(collections_deque))),
+    ]
+}
+
+type Items = Arc<Mutex<VecDeque<Value>>>;
+
+/// Which end of the storage a method works on
+#[derive(Clone, Copy)]
+enum End {
+    Front,
+    Back,
+}
+
+/// Lock the storage, even if a panic elsewhere poisoned the lock; every change
+/// here is a single push or pop, so the data is never left half-updated
+fn lock(items: &Items) -> MutexGuard<'_, VecDeque<Value>> {
+    items.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn method(f: impl Fn(Vec<Value>) -> Result<Value, FlowError> + Send + Sync + 'static) -> Value {
+    Value::NativeFunction(NativeFn::new(f))
+}
+
+fn initial_items(args: &[Value], func: &str) -> Result<Items, FlowError> {
+    let items = match args.first() {
+        Some(Value::Array(items)) => items.iter().cloned().collect(),
+        None | Some(Value::Null) => VecDeque::new(),
+        Some(other) => {
+            return Err(FlowError::type_error(
+                &format!("{} expects a Constellation of initial items, got {}", func, other.type_name()),
+                0,
+                0,
+            ))
+        }
+    };
+    Ok(Arc::new(Mutex::new(items)))
+}
+
+/// Push every argument onto one end and return the new length
+fn push(items: &Items, end: End) -> Value {
+    let items = items.clone();
+    method(move |args| {
+        let mut items = lock(&items);
+        for value in args {
+            match end {
+                End::Front => items.push_front(value),
+                End::Back => items.push_back(value),
+            }
+        }
+        Ok(Value::Number(items.len() as f64))
+    })
+}
+
+/// Remove and return the item at one end, or Hollow when empty
+fn pop(items: &Items, end: End) -> Value {
+    let items = items.clone();
+    method(move |_| {
+        let mut items = lock(&items);
+        let value = match end {
+            End::Front => items.pop_front(),
+            End::Back => items.pop_back(),
+        };
+        Ok(value.unwrap_or(Value::Null))
+    })
+}
+
+/// Return the item at one end without removing it, or Hollow when empty
+fn peek(items: &Items, end: End) -> Value {
+    let items = items.clone();
+    method(move |_| {
+        let items = lock(&items);
+        let value = match end {
+            End::Front => items.front(),
+            End::Back => items.back(),
+        };
+        Ok(value.cloned().unwrap_or(Value::Null))
+    })
+}
+
+/// The methods every collection shares: len, isEmpty, values, clear
+fn common_methods(items: &Items) -> RelicMap {
+    let mut map = RelicMap::new();
+
+    let i = items.clone();
+    map.insert("len".to_string(), method(move |_| Ok(Value::Number(lock(&i).len() as f64))));
+
+    let i = items.clone();
+    map.insert("isEmpty".to_string(), method(move |_| Ok(Value::Boolean(lock(&i).is_empty()))));
+
+    let i = items.clone();
+    map.insert("values".to_string(), method(move |_| {
+        Ok(Value::Array(Arc::new(lock(&i).iter().cloned().collect())))
+    }));
+
+    let i = items.clone();
+    map.insert("clear".to_string(), method(move |_| {
+        lock(&i).clear();
+        Ok(Value::Null)
+    }));
+
+    map
+}
+
+/// collections.queue(items?) -> Relic {push, pop, peek, len, isEmpty, values, clear}
+fn collections_queue(args: Vec<Value>) -> Result<Value, FlowError> {
+    let items = initial_items(&args, "collections.queue")?;
+    let mut map = common_methods(&items);
+    map.insert("push".to_string(), push(&items, End::Back));
+    map.insert("pop".to_string(), pop(&items, End::Front));
+    map.insert("peek".to_string(), peek(&items, End::Front));
+    Ok(Value::Relic(Arc::new(map)))
+}
+
+/// collections.stack(items?) -> Relic {push, pop, peek, len, isEmpty, values, clear}
+fn collections_stack(args: Vec<Value>) -> Result<Value, FlowError> {
+    let items = initial_items(&args, "collections.stack")?;
+    let mut map = common_methods(&items);
+    map.insert("push".to_string(), push(&items, End::Back));
+    map.insert("pop".to_string(), pop(&items, End::Back));
+    map.insert("peek".to_string(), peek(&items, End::Back));
+    Ok(Value::Relic(Arc::new(map)))
+}
+
+/// collections.deque(items?) -> Relic {pushFront, pushBack, popFront, popBack,
+/// peekFront, peekBack, len, isEmpty, values, clear}
+fn collections_deque(args: Vec<Value>) -> Result<Value, FlowError> {
+    let items = initial_items(&args, "collections.deque")?;
+    let mut map = common_methods(&items);
+    map.insert("pushFront".to_string(), push(&items, End::Front));
+    map.insert("pushBack".to_string(), push(&items, End::Back));
+    map.insert("popFront".to_string(), pop(&items, End::Front));
+    map.insert("popBack".to_string(), pop(&items, End::Back));
+    map.insert("peekFront".to_string(), peek(&items, End::Front));
+    map.insert("peekBack".to_string(), peek(&items, End::Back));
+    Ok(Value::Relic(Arc::new(map)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(collection: &Value, name: &str, args: Vec<Value>) -> Value {
+        match collection {
+            Value::Relic(map) => match map.get(name) {
+                Some(Value::NativeFunction(f)) => (f.0)(args).unwrap(),
+                _ => panic!("missing method {}", name),
+            },
+            _ => panic!("not a Relic"),
+        }
+    }
+
+    #[test]
+    fn queue_and_stack_pop_from_opposite_ends() {
+        let seed = || vec![Value::Array(Arc::new(vec![Value::Number(1.0), Value::Number(2.0)]))];
+        let queue = collections_queue(seed()).unwrap();
+        let stack = collections_stack(seed()).unwrap();
+        call(&queue, "push", vec![Value::Number(3.0)]);
+        call(&stack, "push", vec![Value::Number(3.0)]);
+        assert!(matches!(call(&queue, "pop", vec![]), Value::Number(n) if n == 1.0));
+        assert!(matches!(call(&stack, "pop", vec![]), Value::Number(n) if n == 3.0));
+        assert!(matches!(call(&queue, "len", vec![]), Value::Number(n) if n == 2.0));
+        call(&stack, "clear", vec![]);
+        assert!(matches!(call(&stack, "pop", vec![]), Value::Null));
+    }
+}
@@ -29,6 +29,8 @@ pub fn load_color_module() -> Vec<(&'static str, Value)> {
         ("underline", Value::NativeFunction(NativeFn::new(style_underline))),
         ("dimmed", Value::NativeFunction(NativeFn::new(style_dimmed))),
         ("strikethrough", Value::NativeFunction(NativeFn::new(style_strikethrough))),
+
+        ("enabled", Value::NativeFunction(NativeFn::new(color_enabled))),
     ]
 }
 
@@ -149,3 +151,10 @@ fn style_strikethrough(args: Vec<Value>) -> Result<Value, FlowError> {
     let text = get_string_arg(&args, "strikethrough")?;
     Ok(Value::String(Arc::new(text.strikethrough().to_string())))
 }
+
+// color::enabled() -> Pulse
+// False when output is piped, NO_COLOR is set or flowlang ran with --no-color;
+// every function above then returns its text unchanged.
+fn color_enabled(_args: Vec<Value>) -> Result<Value, FlowError> {
+    Ok(Value::Boolean(crate::platform::colors_enabled()))
+}
@@ -0,0 +1,146 @@
+//! std:covenant - sets of unique values
+//!
+//! A Covenant holds each value at most once, in the order first added. Membership
+//! uses the same structural equality as `deepEquals`, so `[1, 2]` and `[1, 2]` are
+//! one member. Like Constellations, Covenants are never changed in place: `add`,
+//! `remove` and the set operations return a new Covenant.
+
+use std::sync::Arc;
+
+use crate::error::FlowError;
+use crate::types::{CovenantSet, NativeFn, RelicMap, Value};
+
+pub fn get_module() -> RelicMap {
+    let mut module = RelicMap::new();
+
+    module.insert("of".to_string(), Value::NativeFunction(NativeFn::new(|args| {
+        match args.as_slice() {
+            [] => Ok(Value::Covenant(Arc::new(CovenantSet::new()))),
+            [values] => Ok(Value::Covenant(Arc::new(members(values, "covenant.of")?))),
+            _ => Err(FlowError::runtime("covenant.of() expects at most 1 argument (a Constellation)", 0, 0)),
+        }
+    })));
+
+    module
+}
+
+/// Key under which `value` is stored; deeply equal values share a key
+pub fn member_key(value: &Value) -> Result<String, FlowError> {
+    Ok(match value {
+        Value::Null => "null".to_string(),
+        Value::Boolean(b) => format!("b{}", b),
+        // 0 and -0 are equal Embers, so they must be one member
+        Value::Number(n) if *n == 0.0 => "n0".to_string(),
+        Value::Number(n) => format!("n{}", n),
+        Value::String(s) => format!("s{:?}", s.as_str()),
+        Value::Handle(id) => format!("h{}", id),
+        Value::Array(items) => {
+            let keys = items.iter().map(member_key).collect::<Result<Vec<_>, _>>()?;
+            format!("[{}]", keys.join(","))
+        }
+        // deepEquals ignores Relic key order, so the key does too
+        Value::Relic(map) => {
+            let mut entries = map
+                .iter()
+                .map(|(k, v)| Ok(format!("{:?}={}", k, member_key(v)?)))
+                .collect::<Result<Vec<_>, FlowError>>()?;
+            entries.sort();
+            format!("{{{}}}", entries.join(","))
+        }
+        Value::Covenant(set) => {
+            let mut keys: Vec<&String> = set.keys().collect();
+            keys.sort();
+            format!("<{}>", keys.iter().map(|k| k.as_str()).collect::<Vec<_>>().join(","))
+        }
+        Value::Function { .. }
+        | Value::NativeFunction(_)
+        | Value::AsyncNativeFunction(_)
+        | Value::InterpreterNativeFunction(_) => {
+            return Err(FlowError::type_error("A Covenant cannot hold a Spell", 0, 0))
+        }
+    })
+}
+
+/// The members of a Constellation or Covenant, duplicates dropped
+fn members(values: &Value, spell: &str) -> Result<CovenantSet, FlowError> {
+    match values {
+        Value::Covenant(set) => Ok(set.as_ref().clone()),
+        Value::Array(items) => {
+            let mut set = CovenantSet::with_capacity(items.len());
+            for item in items.iter() {
+                set.entry(member_key(item)?).or_insert_with(|| item.clone());
+            }
+            Ok(set)
+        }
+        other => Err(FlowError::type_error(
+            &format!("{}() expects a Constellation or Covenant, got {}", spell, other.type_name()),
+            0,
+            0,
+        )),
+    }
+}
+
+/// Methods called on a Covenant value, e.g. `seen.has(id)`
+pub fn call_method(set: &CovenantSet, method: &str, args: &[Value]) -> Result<Value, FlowError> {
+    let covenant = |set: CovenantSet| Ok(Value::Covenant(Arc::new(set)));
+    let name = format!("Covenant.{}", method);
+    match (method, args) {
+        ("len", []) => Ok(Value::Number(set.len() as f64)),
+        ("values", []) => Ok(Value::Array(Arc::new(set.values().cloned().collect()))),
+        ("has", [value]) => Ok(Value::Boolean(set.contains_key(&member_key(value)?))),
+        ("add", [value]) => {
+            let mut grown = set.clone();
+            grown.entry(member_key(value)?).or_insert_with(|| value.clone());
+            covenant(grown)
+        }
+        ("remove", [value]) => {
+            let mut shrunk = set.clone();
+            shrunk.shift_remove(&member_key(value)?);
+            covenant(shrunk)
+        }
+        ("union", [other]) => {
+            let mut joined = set.clone();
+            for (key, value) in members(other, &name)? {
+                joined.entry(key).or_insert(value);
+            }
+            covenant(joined)
+        }
+        ("intersect", [other]) => {
+            let other = members(other, &name)?;
+            covenant(set.iter().filter(|(k, _)| other.contains_key(*k)).map(|(k, v)| (k.clone(), v.clone())).collect())
+        }
+        ("difference", [other]) => {
+            let other = members(other, &name)?;
+            covenant(set.iter().filter(|(k, _)| !other.contains_key(*k)).map(|(k, v)| (k.clone(), v.clone())).collect())
+        }
+        ("len" | "values", _) => Err(FlowError::runtime(&format!("{}() takes no arguments", name), 0, 0)),
+        ("has" | "add" | "remove" | "union" | "intersect" | "difference", _) => {
+            Err(FlowError::runtime(&format!("{}() takes exactly 1 argument", name), 0, 0))
+        }
+        _ => Err(FlowError::runtime(&format!("Unknown method '{}' on Covenant", method), 0, 0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn of(values: Vec<Value>) -> CovenantSet {
+        members(&Value::Array(Arc::new(values)), "test").unwrap()
+    }
+
+    #[test]
+    fn test_members_compare_by_structure() {
+        let pair = || Value::Array(Arc::new(vec![Value::Number(1.0), Value::Number(2.0)]));
+        let set = of(vec![pair(), pair(), Value::Number(0.0), Value::Number(-0.0), Value::String(Arc::new("0".into()))]);
+        assert_eq!(set.len(), 3);
+
+        let a = of(vec![Value::Number(1.0), Value::Number(2.0), Value::Number(3.0)]);
+        let b = Value::Array(Arc::new(vec![Value::Number(2.0), Value::Number(4.0)]));
+        let show = |v: Value| v.to_string();
+        assert_eq!(show(call_method(&a, "union", std::slice::from_ref(&b)).unwrap()), "Covenant(1, 2, 3, 4)");
+        assert_eq!(show(call_method(&a, "intersect", std::slice::from_ref(&b)).unwrap()), "Covenant(2)");
+        assert_eq!(show(call_method(&a, "difference", std::slice::from_ref(&b)).unwrap()), "Covenant(1, 3)");
+        assert!(member_key(&Value::NativeFunction(NativeFn::new(|_| Ok(Value::Null)))).is_err());
+    }
+}
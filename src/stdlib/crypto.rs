@@ -1,8 +1,14 @@
 use crate::error::FlowError;
-use crate::types::{NativeFn, Value};
+use crate::types::{AsyncContext, AsyncNativeFn, NativeFn, Value, RelicMap};
 use std::sync::Arc;
 use sha2::{Sha256, Sha512, Digest};
 use md5::Md5;
+use hmac::{Hmac, Mac};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+
+type HmacSha256 = Hmac<Sha256>;
 
 pub fn load_crypto_module() -> Vec<(&'static str, Value)> {
     vec![
@@ -18,9 +24,28 @@ pub fn load_crypto_module() -> Vec<(&'static str, Value)> {
         // Hex encoding
         ("hex_encode", Value::NativeFunction(NativeFn::new(crypto_hex_encode))),
         ("hex_decode", Value::NativeFunction(NativeFn::new(crypto_hex_decode))),
+
+        // Message authentication
+        ("hmacSha256", Value::NativeFunction(NativeFn::new(crypto_hmac_sha256))),
+
+        // Password hashing
+        ("bcryptHash", Value::AsyncNativeFunction(AsyncNativeFn::new(crypto_bcrypt_hash))),
+        ("bcryptVerify", Value::AsyncNativeFunction(AsyncNativeFn::new(crypto_bcrypt_verify))),
+        ("argon2Hash", Value::AsyncNativeFunction(AsyncNativeFn::new(crypto_argon2_hash))),
+        ("argon2Verify", Value::AsyncNativeFunction(AsyncNativeFn::new(crypto_argon2_verify))),
+
+        // JSON Web Tokens (HS256)
+        ("jwt", load_jwt_relic()),
     ]
 }
 
+fn load_jwt_relic() -> Value {
+    let mut jwt = RelicMap::new();
+    jwt.insert("sign".to_string(), Value::NativeFunction(NativeFn::new(jwt_sign)));
+    jwt.insert("verify".to_string(), Value::NativeFunction(NativeFn::new(jwt_verify)));
+    Value::Relic(Arc::new(jwt))
+}
+
 // MD5 hash
 fn crypto_md5(args: Vec<Value>) -> Result<Value, FlowError> {
     if args.len() != 1 {
@@ -190,3 +215,249 @@ fn crypto_hex_decode(args: Vec<Value>) -> Result<Value, FlowError> {
         Err(_) => Err(FlowError::runtime("Invalid hex string", 0, 0)),
     }
 }
+
+// HMAC-SHA256, hex encoded
+fn crypto_hmac_sha256(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 2 {
+        return Err(FlowError::runtime(
+            "crypto::hmacSha256 expects 2 arguments (key, message)",
+            0,
+            0,
+        ));
+    }
+
+    let (key, message) = match (&args[0], &args[1]) {
+        (Value::String(k), Value::String(m)) => (k.clone(), m.clone()),
+        _ => return Err(FlowError::type_error("crypto::hmacSha256 expects Silk (string) key and message", 0, 0)),
+    };
+
+    let mut mac = HmacSha256::new_from_slice(key.as_bytes())
+        .map_err(|e| FlowError::runtime(&format!("Invalid HMAC key: {}", e), 0, 0))?;
+    mac.update(message.as_bytes());
+
+    Ok(Value::String(Arc::new(hex::encode(mac.finalize().into_bytes()))))
+}
+
+/// Run a password hash on the blocking pool; one takes tens to hundreds of
+/// milliseconds, which would otherwise stall every task on its worker
+async fn off_runtime<T: Send + 'static>(
+    name: &str,
+    work: impl FnOnce() -> Result<T, FlowError> + Send + 'static,
+) -> Result<T, FlowError> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| FlowError::runtime(&format!("crypto::{} was interrupted: {}", name, e), 0, 0))?
+}
+
+// bcrypt password hash (cost defaults to 12)
+async fn crypto_bcrypt_hash(args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(FlowError::runtime(
+            "crypto::bcryptHash expects 1 or 2 arguments (password, cost?)",
+            0,
+            0,
+        ));
+    }
+
+    let password = match &args[0] {
+        Value::String(s) => s.clone(),
+        _ => return Err(FlowError::type_error("crypto::bcryptHash expects a Silk (string)", 0, 0)),
+    };
+
+    let cost = match args.get(1) {
+        None => bcrypt::DEFAULT_COST,
+        Some(Value::Number(n)) if (4.0..=31.0).contains(n) => *n as u32,
+        Some(_) => return Err(FlowError::out_of_range("crypto::bcryptHash cost must be an Ember between 4 and 31", 0, 0)),
+    };
+
+    off_runtime("bcryptHash", move || {
+        bcrypt::hash(password.as_bytes(), cost)
+            .map(|hash| Value::String(Arc::new(hash)))
+            .map_err(|e| FlowError::runtime(&format!("bcrypt hashing failed: {}", e), 0, 0))
+    })
+    .await
+}
+
+// bcrypt verification
+async fn crypto_bcrypt_verify(args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
+    if args.len() != 2 {
+        return Err(FlowError::runtime(
+            "crypto::bcryptVerify expects 2 arguments (password, hash)",
+            0,
+            0,
+        ));
+    }
+
+    let (password, hash) = match (&args[0], &args[1]) {
+        (Value::String(p), Value::String(h)) => (p.clone(), h.clone()),
+        _ => return Err(FlowError::type_error("crypto::bcryptVerify expects Silk (string) password and hash", 0, 0)),
+    };
+
+    off_runtime("bcryptVerify", move || {
+        bcrypt::verify(password.as_bytes(), &hash)
+            .map(Value::Boolean)
+            .map_err(|e| FlowError::glitch(&format!("Invalid bcrypt hash: {}", e), 0, 0))
+    })
+    .await
+}
+
+// Argon2id password hash in PHC string format
+async fn crypto_argon2_hash(args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime(
+            "crypto::argon2Hash expects 1 argument (password)",
+            0,
+            0,
+        ));
+    }
+
+    let password = match &args[0] {
+        Value::String(s) => s.clone(),
+        _ => return Err(FlowError::type_error("crypto::argon2Hash expects a Silk (string)", 0, 0)),
+    };
+
+    let mut salt_bytes = [0u8; 16];
+    getrandom::getrandom(&mut salt_bytes)
+        .map_err(|e| FlowError::runtime(&format!("Failed to generate salt: {}", e), 0, 0))?;
+    let salt = SaltString::encode_b64(&salt_bytes)
+        .map_err(|e| FlowError::runtime(&format!("Failed to encode salt: {}", e), 0, 0))?;
+
+    off_runtime("argon2Hash", move || {
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| Value::String(Arc::new(hash.to_string())))
+            .map_err(|e| FlowError::runtime(&format!("argon2 hashing failed: {}", e), 0, 0))
+    })
+    .await
+}
+
+// Argon2 verification
+async fn crypto_argon2_verify(args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
+    if args.len() != 2 {
+        return Err(FlowError::runtime(
+            "crypto::argon2Verify expects 2 arguments (password, hash)",
+            0,
+            0,
+        ));
+    }
+
+    let (password, hash) = match (&args[0], &args[1]) {
+        (Value::String(p), Value::String(h)) => (p.clone(), h.clone()),
+        _ => return Err(FlowError::type_error("crypto::argon2Verify expects Silk (string) password and hash", 0, 0)),
+    };
+
+    off_runtime("argon2Verify", move || {
+        let parsed = PasswordHash::new(&hash)
+            .map_err(|e| FlowError::glitch(&format!("Invalid argon2 hash: {}", e), 0, 0))?;
+        Ok(Value::Boolean(Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok()))
+    })
+    .await
+}
+
+// jwt.sign(payload: Relic, secret: Silk, options?: { expiresIn: seconds }) -> Silk
+fn jwt_sign(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(FlowError::runtime(
+            "crypto::jwt.sign expects 2 or 3 arguments (payload, secret, options?)",
+            0,
+            0,
+        ));
+    }
+
+    let mut claims = match &args[0] {
+        Value::Relic(map) => map.as_ref().clone(),
+        _ => return Err(FlowError::type_error("crypto::jwt.sign expects a Relic payload", 0, 0)),
+    };
+
+    let secret = match &args[1] {
+        Value::String(s) => s.clone(),
+        _ => return Err(FlowError::type_error("crypto::jwt.sign expects a Silk (string) secret", 0, 0)),
+    };
+
+    if let Some(options) = args.get(2) {
+        let options = match options {
+            Value::Relic(map) => map.clone(),
+            _ => return Err(FlowError::type_error("crypto::jwt.sign options must be a Relic", 0, 0)),
+        };
+        if let Some(expires_in) = options.get("expiresIn") {
+            let seconds = match expires_in {
+                Value::Number(n) => *n,
+                _ => return Err(FlowError::type_error("crypto::jwt.sign expiresIn must be an Ember (seconds)", 0, 0)),
+            };
+            let now = chrono::Utc::now().timestamp() as f64;
+            claims.insert("iat".to_string(), Value::Number(now));
+            claims.insert("exp".to_string(), Value::Number(now + seconds));
+        }
+    }
+
+    let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#);
+    let payload = URL_SAFE_NO_PAD.encode(crate::stdlib::json::value_to_json_string(&Value::Relic(Arc::new(claims))));
+    let signing_input = format!("{}.{}", header, payload);
+
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .map_err(|e| FlowError::runtime(&format!("Invalid JWT secret: {}", e), 0, 0))?;
+    mac.update(signing_input.as_bytes());
+    let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+
+    Ok(Value::String(Arc::new(format!("{}.{}", signing_input, signature))))
+}
+
+// jwt.verify(token: Silk, secret: Silk) -> Relic
+// Raises a Spirit error for bad signatures and expired tokens
+fn jwt_verify(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 2 {
+        return Err(FlowError::runtime(
+            "crypto::jwt.verify expects 2 arguments (token, secret)",
+            0,
+            0,
+        ));
+    }
+
+    let (token, secret) = match (&args[0], &args[1]) {
+        (Value::String(t), Value::String(s)) => (t.clone(), s.clone()),
+        _ => return Err(FlowError::type_error("crypto::jwt.verify expects Silk (string) token and secret", 0, 0)),
+    };
+
+    let parts: Vec<&str> = token.split('.').collect();
+    if parts.len() != 3 {
+        return Err(FlowError::glitch("Malformed JWT: expected header.payload.signature", 0, 0));
+    }
+
+    let decode = |part: &str, what: &str| -> Result<String, FlowError> {
+        URL_SAFE_NO_PAD
+            .decode(part)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(|| FlowError::glitch(&format!("Malformed JWT {}", what), 0, 0))
+    };
+
+    let header = crate::stdlib::json::parse_json_value(&decode(parts[0], "header")?)?;
+    let alg = match &header {
+        Value::Relic(map) => map.get("alg").map(|v| v.to_string()),
+        _ => None,
+    };
+    if alg.as_deref() != Some("HS256") {
+        return Err(FlowError::spirit("Unsupported JWT algorithm (only HS256 is accepted)", 0, 0));
+    }
+
+    let signature = URL_SAFE_NO_PAD
+        .decode(parts[2])
+        .map_err(|_| FlowError::glitch("Malformed JWT signature", 0, 0))?;
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .map_err(|e| FlowError::runtime(&format!("Invalid JWT secret: {}", e), 0, 0))?;
+    mac.update(format!("{}.{}", parts[0], parts[1]).as_bytes());
+    if mac.verify_slice(&signature).is_err() {
+        return Err(FlowError::spirit("Invalid JWT signature", 0, 0));
+    }
+
+    let payload = crate::stdlib::json::parse_json_value(&decode(parts[1], "payload")?)?;
+    if let Value::Relic(claims) = &payload {
+        if let Some(Value::Number(exp)) = claims.get("exp") {
+            if (chrono::Utc::now().timestamp() as f64) >= *exp {
+                return Err(FlowError::spirit("JWT has expired", 0, 0));
+            }
+        }
+    }
+
+    Ok(payload)
+}
@@ -0,0 +1,346 @@
+//! std:csv - CSV parsing and writing
+//!
+//! - `csv.parse(text, options?)` - Rows as a Constellation of Relics (or of
+//!   Constellations when `headers: false`)
+//! - `csv.stringify(rows, options?)` - CSV text from Relics or Constellations
+//!
+//! Follows RFC 4180: fields may be quoted, quotes inside quoted fields are
+//! doubled, and quoted fields may span lines.
+
+use crate::error::FlowError;
+use crate::types::{NativeFn, Value, RelicMap};
+use std::sync::Arc;
+
+pub fn load_csv_module() -> Vec<(&'static str, Value)> {
+    vec![
+        ("parse", Value::NativeFunction(NativeFn::new(csv_parse))),
+        ("stringify", Value::NativeFunction(NativeFn::new(csv_stringify))),
+    ]
+}
+
+struct CsvOptions {
+    headers: bool,
+    delimiter: char,
+    trim: bool,
+    typed: bool,
+    columns: Option<Vec<String>>,
+}
+
+impl CsvOptions {
+    fn from_value(value: Option<&Value>, func: &str) -> Result<Self, FlowError> {
+        let mut options = CsvOptions {
+            headers: true,
+            delimiter: ',',
+            trim: false,
+            typed: false,
+            columns: None,
+        };
+
+        let map = match value {
+            None => return Ok(options),
+            Some(Value::Relic(map)) => map,
+            Some(_) => return Err(FlowError::type_error(&format!("{} options must be a Relic", func), 0, 0)),
+        };
+
+        if let Some(v) = map.get("headers") {
+            match v {
+                Value::Boolean(b) => options.headers = *b,
+                Value::Array(cols) => options.columns = Some(cols.iter().map(|c| c.to_string()).collect()),
+                _ => return Err(FlowError::type_error(
+                    &format!("{} option 'headers' must be a Pulse or a Constellation of column names", func),
+                    0, 0,
+                )),
+            }
+        }
+        if let Some(v) = map.get("delimiter") {
+            let mut chars = match v {
+                Value::String(s) => s.chars(),
+                _ => return Err(FlowError::type_error(&format!("{} option 'delimiter' must be a Silk", func), 0, 0)),
+            };
+            options.delimiter = match (chars.next(), chars.next()) {
+                (Some(c), None) if c != '"' && c != '\n' && c != '\r' => c,
+                _ => return Err(FlowError::runtime(
+                    &format!("{} option 'delimiter' must be a single character other than a quote or newline", func),
+                    0, 0,
+                )),
+            };
+        }
+        if let Some(v) = map.get("trim") {
+            options.trim = v.is_truthy();
+        }
+        if let Some(v) = map.get("typed") {
+            options.typed = v.is_truthy();
+        }
+
+        Ok(options)
+    }
+}
+
+/// csv.parse(text, options?) -> Constellation
+/// Options: `headers` (true, false, or a Constellation of column names),
+/// `delimiter` (default ","), `trim` (strip unquoted whitespace) and
+/// `typed` (turn numeric/boolean fields into Embers/Pulses).
+fn csv_parse(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(FlowError::runtime(
+            "csv.parse expects 1 or 2 arguments (text, options?)",
+            0, 0,
+        ));
+    }
+
+    let text = match &args[0] {
+        Value::String(s) => s.clone(),
+        _ => return Err(FlowError::type_error("csv.parse expects a Silk (string)", 0, 0)),
+    };
+    let options = CsvOptions::from_value(args.get(1), "csv.parse")?;
+
+    let mut records = parse_records(&text, options.delimiter, options.trim)?.into_iter();
+
+    let columns = match (&options.columns, options.headers) {
+        (Some(cols), _) => Some(cols.clone()),
+        (None, true) => match records.next() {
+            Some(header) => Some(header.into_iter().map(|f| f.text).collect()),
+            None => return Ok(Value::Array(Arc::new(Vec::new()))),
+        },
+        (None, false) => None,
+    };
+
+    let mut rows = Vec::new();
+    for (index, record) in records.enumerate() {
+        let values: Vec<Value> = record.into_iter().map(|f| field_value(f, options.typed)).collect();
+
+        match &columns {
+            Some(cols) => {
+                if values.len() > cols.len() {
+                    return Err(FlowError::glitch(
+                        &format!(
+                            "csv.parse: row {} has {} fields but there are only {} columns",
+                            index + 1, values.len(), cols.len()
+                        ),
+                        0, 0,
+                    ));
+                }
+                let mut row = RelicMap::new();
+                let mut values = values.into_iter();
+                for col in cols {
+                    // Short rows are padded with Hollow
+                    row.insert(col.clone(), values.next().unwrap_or(Value::Null));
+                }
+                rows.push(Value::Relic(Arc::new(row)));
+            }
+            None => rows.push(Value::Array(Arc::new(values))),
+        }
+    }
+
+    Ok(Value::Array(Arc::new(rows)))
+}
+
+/// csv.stringify(rows, options?) -> Silk
+/// Relic rows use `headers` (a Constellation) for column order, otherwise the
+/// keys of all rows sorted alphabetically. `headers: false` omits the header line.
+fn csv_stringify(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(FlowError::runtime(
+            "csv.stringify expects 1 or 2 arguments (rows, options?)",
+            0, 0,
+        ));
+    }
+
+    let rows = match &args[0] {
+        Value::Array(rows) => rows.clone(),
+        _ => return Err(FlowError::type_error("csv.stringify expects a Constellation of rows", 0, 0)),
+    };
+    let options = CsvOptions::from_value(args.get(1), "csv.stringify")?;
+    let delimiter = options.delimiter.to_string();
+
+    let relic_rows = rows.iter().any(|row| matches!(row, Value::Relic(_)));
+    let mut lines = Vec::new();
+
+    if relic_rows {
+        let columns = match options.columns {
+            Some(cols) => cols,
+            None => {
+                let mut cols: Vec<String> = Vec::new();
+                for row in rows.iter() {
+                    if let Value::Relic(map) = row {
+                        for key in map.keys() {
+                            if !cols.contains(key) {
+                                cols.push(key.clone());
+                            }
+                        }
+                    }
+                }
+                cols
+            }
+        };
+
+        if options.headers {
+            lines.push(columns.iter().map(|c| quote_field(c, options.delimiter)).collect::<Vec<_>>().join(&delimiter));
+        }
+
+        for row in rows.iter() {
+            let map = match row {
+                Value::Relic(map) => map,
+                other => return Err(FlowError::type_error(
+                    &format!("csv.stringify cannot mix Relic rows with {} rows", other.type_name()),
+                    0, 0,
+                )),
+            };
+            let fields: Vec<String> = columns.iter()
+                .map(|col| map.get(col).map(|v| quote_field(&field_text(v), options.delimiter)).unwrap_or_default())
+                .collect();
+            lines.push(fields.join(&delimiter));
+        }
+    } else {
+        if let Some(cols) = &options.columns {
+            lines.push(cols.iter().map(|c| quote_field(c, options.delimiter)).collect::<Vec<_>>().join(&delimiter));
+        }
+        for row in rows.iter() {
+            let fields = match row {
+                Value::Array(fields) => fields,
+                other => return Err(FlowError::type_error(
+                    &format!("csv.stringify expects rows to be Relics or Constellations, got {}", other.type_name()),
+                    0, 0,
+                )),
+            };
+            lines.push(fields.iter().map(|v| quote_field(&field_text(v), options.delimiter)).collect::<Vec<_>>().join(&delimiter));
+        }
+    }
+
+    let mut out = lines.join("\n");
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    Ok(Value::String(Arc::new(out)))
+}
+
+struct Field {
+    text: String,
+    quoted: bool,
+}
+
+fn parse_records(text: &str, delimiter: char, trim: bool) -> Result<Vec<Vec<Field>>, FlowError> {
+    let mut records = Vec::new();
+    let mut record: Vec<Field> = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut chars = text.chars().peekable();
+
+    let finish_field = |field: &mut String, quoted: &mut bool, record: &mut Vec<Field>| {
+        let text = if trim && !*quoted { field.trim().to_string() } else { std::mem::take(field) };
+        field.clear();
+        record.push(Field { text, quoted: *quoted });
+        *quoted = false;
+    };
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.trim().is_empty() && !quoted => {
+                field.clear();
+                in_quotes = true;
+                quoted = true;
+            }
+            '"' => {
+                return Err(FlowError::glitch(
+                    &format!("csv.parse: unexpected quote inside unquoted field on line {}", line),
+                    0, 0,
+                ));
+            }
+            c if c == delimiter => finish_field(&mut field, &mut quoted, &mut record),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                finish_field(&mut field, &mut quoted, &mut record);
+                // Skip completely blank lines
+                if !(record.len() == 1 && record[0].text.is_empty() && !record[0].quoted) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+                line += 1;
+            }
+            _ => {
+                if quoted {
+                    // Only whitespace may follow a closing quote
+                    if !c.is_whitespace() {
+                        return Err(FlowError::glitch(
+                            &format!("csv.parse: unexpected '{}' after closing quote on line {}", c, line),
+                            0, 0,
+                        ));
+                    }
+                } else {
+                    field.push(c);
+                }
+            }
+        }
+    }
+
+    if in_quotes {
+        return Err(FlowError::glitch(
+            &format!("csv.parse: unterminated quoted field starting before line {}", line),
+            0, 0,
+        ));
+    }
+
+    if !field.is_empty() || quoted || !record.is_empty() {
+        finish_field(&mut field, &mut quoted, &mut record);
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+fn field_value(field: Field, typed: bool) -> Value {
+    if typed && !field.quoted {
+        match field.text.as_str() {
+            "" => return Value::Null,
+            "true" => return Value::Boolean(true),
+            "false" => return Value::Boolean(false),
+            text => {
+                if let Ok(n) = text.parse::<f64>() {
+                    if n.is_finite() {
+                        return Value::Number(n);
+                    }
+                }
+            }
+        }
+    }
+    Value::String(Arc::new(field.text))
+}
+
+fn field_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn quote_field(text: &str, delimiter: char) -> String {
+    let needs_quotes = text.contains(delimiter)
+        || text.contains('"')
+        || text.contains('\n')
+        || text.contains('\r')
+        || text.starts_with(' ')
+        || text.ends_with(' ');
+    if needs_quotes {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
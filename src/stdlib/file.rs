@@ -1,8 +1,14 @@
 use crate::error::FlowError;
-use crate::types::{NativeFn, Value};
+use crate::interpreter::Interpreter;
+use crate::runtime::handle::HandleType;
+use crate::types::{AsyncContext, AsyncNativeFn, InterpreterFuture, InterpreterNativeFn, NativeFn, Value, RelicMap};
+use notify::{EventKind, RecursiveMode, Watcher};
 use std::fs;
-use std::path::Path;
-use std::sync::Arc;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+use tokio::sync::{mpsc, oneshot};
 
 pub fn load_file_module() -> Vec<(&'static str, Value)> {
     vec![
@@ -12,6 +18,15 @@ pub fn load_file_module() -> Vec<(&'static str, Value)> {
         ("delete", Value::NativeFunction(NativeFn::new(file_delete))),
         ("list", Value::NativeFunction(NativeFn::new(file_list))),
         ("create_dir", Value::NativeFunction(NativeFn::new(file_create_dir))),
+        ("lines", Value::NativeFunction(NativeFn::new(file_lines))),
+        ("glob", Value::NativeFunction(NativeFn::new(file_glob))),
+        ("walk", Value::InterpreterNativeFunction(InterpreterNativeFn::new(file_walk))),
+        ("copy", Value::NativeFunction(NativeFn::new(file_copy))),
+        ("moveTo", Value::NativeFunction(NativeFn::new(file_move_to))),
+        ("size", Value::NativeFunction(NativeFn::new(file_size))),
+        ("modified", Value::NativeFunction(NativeFn::new(file_modified))),
+        ("watch", Value::AsyncNativeFunction(AsyncNativeFn::new(file_watch))),
+        ("unwatch", Value::AsyncNativeFunction(AsyncNativeFn::new(file_unwatch))),
     ]
 }
 
@@ -233,3 +248,419 @@ fn file_create_dir(args: Vec<Value>) -> Result<Value, FlowError> {
         )),
     }
 }
+
+// Shared argument check for the path-taking functions below
+fn path_arg(args: &[Value], index: usize, func: &str) -> Result<Arc<String>, FlowError> {
+    match args.get(index) {
+        Some(Value::String(s)) => Ok(s.clone()),
+        _ => Err(FlowError::type_error(
+            &format!("file::{} expects a string path", func),
+            0,
+            0,
+        )),
+    }
+}
+
+type LineReader = std::io::Lines<BufReader<fs::File>>;
+
+// file::lines(path: Silk) -> Relic { next, close }
+// Lazy line iterator: `next()` reads one line at a time and returns Hollow at
+// the end of the file, which also closes it. Works with `enter Phase ... in`.
+fn file_lines(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime(
+            "file::lines expects 1 argument (path)",
+            0,
+            0,
+        ));
+    }
+
+    let path = path_arg(&args, 0, "lines")?;
+    let file = fs::File::open(&*path).map_err(|e| {
+        FlowError::runtime(&format!("Failed to open file '{}': {}", path, e), 0, 0)
+    })?;
+
+    let reader: Arc<Mutex<Option<LineReader>>> = Arc::new(Mutex::new(Some(BufReader::new(file).lines())));
+
+    let next_reader = reader.clone();
+    let next = move |_args: Vec<Value>| -> Result<Value, FlowError> {
+        let mut guard = next_reader.lock().unwrap();
+        let line = match guard.as_mut() {
+            Some(lines) => lines.next(),
+            None => return Ok(Value::Null),
+        };
+        match line {
+            Some(Ok(line)) => Ok(Value::String(Arc::new(line))),
+            Some(Err(e)) => {
+                *guard = None;
+                Err(FlowError::runtime(&format!("Failed to read line: {}", e), 0, 0))
+            }
+            None => {
+                *guard = None;
+                Ok(Value::Null)
+            }
+        }
+    };
+
+    let close = move |_args: Vec<Value>| -> Result<Value, FlowError> {
+        *reader.lock().unwrap() = None;
+        Ok(Value::Boolean(true))
+    };
+
+    let mut iterator = RelicMap::new();
+    iterator.insert("next".to_string(), Value::NativeFunction(NativeFn::new(next)));
+    iterator.insert("close".to_string(), Value::NativeFunction(NativeFn::new(close)));
+    iterator.insert("path".to_string(), Value::String(path));
+    Ok(Value::Relic(Arc::new(iterator)))
+}
+
+// file::glob(pattern: Silk) -> Constellation<Silk>
+// Supports `*`, `?`, `[abc]` and `**` for any number of directories.
+fn file_glob(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime(
+            "file::glob expects 1 argument (pattern)",
+            0,
+            0,
+        ));
+    }
+
+    let pattern = path_arg(&args, 0, "glob")?;
+    let paths = glob::glob(&pattern).map_err(|e| {
+        FlowError::runtime(&format!("Invalid glob pattern '{}': {}", pattern, e), 0, 0)
+    })?;
+
+    let mut matches: Vec<String> = paths
+        .filter_map(|entry| entry.ok())
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect();
+    matches.sort();
+
+    Ok(Value::Array(Arc::new(
+        matches.into_iter().map(|p| Value::String(Arc::new(p))).collect(),
+    )))
+}
+
+// file::walk(dir: Silk, spell: Spell) -> Ember
+// Visits every file and directory below `dir` depth-first, in name order.
+// The Spell receives an entry Relic { path, name, isFile, isDir, size };
+// returning `false` stops the walk. Returns the number of entries visited.
+fn file_walk(args: Vec<Value>, interp: &mut Interpreter) -> InterpreterFuture<'_> {
+    Box::pin(async move {
+        if args.len() != 2 {
+            return Err(FlowError::runtime(
+                "file::walk expects 2 arguments (dir, spell)",
+                0,
+                0,
+            ));
+        }
+
+        let dir = path_arg(&args, 0, "walk")?;
+        let spell = args[1].clone();
+        if spell.type_name() != "Spell" {
+            return Err(FlowError::type_error("file::walk expects a Spell as second argument", 0, 0));
+        }
+        if !Path::new(&*dir).is_dir() {
+            return Err(FlowError::runtime(
+                &format!("Path '{}' is not a directory", dir),
+                0,
+                0,
+            ));
+        }
+
+        // Explicit stack instead of recursion so the future stays Sized.
+        // Children are pushed in reverse so they pop in name order.
+        let mut stack = sorted_children(Path::new(&*dir))?;
+        stack.reverse();
+        let mut visited = 0;
+
+        while let Some(entry) = stack.pop() {
+            let metadata = match fs::metadata(&entry) {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+
+            let mut info = RelicMap::new();
+            info.insert("path".to_string(), Value::String(Arc::new(entry.to_string_lossy().into_owned())));
+            info.insert(
+                "name".to_string(),
+                Value::String(Arc::new(entry.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default())),
+            );
+            info.insert("isFile".to_string(), Value::Boolean(metadata.is_file()));
+            info.insert("isDir".to_string(), Value::Boolean(metadata.is_dir()));
+            info.insert("size".to_string(), Value::Number(metadata.len() as f64));
+
+            visited += 1;
+            let result = interp.execute_function(spell.clone(), vec![Value::Relic(Arc::new(info))]).await?;
+            if matches!(result, Value::Boolean(false)) {
+                break;
+            }
+
+            if metadata.is_dir() {
+                stack.extend(sorted_children(&entry)?.into_iter().rev());
+            }
+        }
+
+        Ok(Value::Number(visited as f64))
+    })
+}
+
+fn sorted_children(dir: &Path) -> Result<Vec<PathBuf>, FlowError> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| {
+            FlowError::runtime(
+                &format!("Failed to list directory '{}': {}", dir.display(), e),
+                0,
+                0,
+            )
+        })?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    entries.sort();
+    Ok(entries)
+}
+
+// file::copy(src: Silk, dest: Silk) -> Pulse
+// Directories are copied recursively.
+fn file_copy(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 2 {
+        return Err(FlowError::runtime(
+            "file::copy expects 2 arguments (src, dest)",
+            0,
+            0,
+        ));
+    }
+
+    let src = path_arg(&args, 0, "copy")?;
+    let dest = path_arg(&args, 1, "copy")?;
+
+    copy_recursive(Path::new(&*src), Path::new(&*dest)).map_err(|e| {
+        FlowError::runtime(&format!("Failed to copy '{}' to '{}': {}", src, dest, e), 0, 0)
+    })?;
+    Ok(Value::Boolean(true))
+}
+
+fn copy_recursive(src: &Path, dest: &Path) -> std::io::Result<()> {
+    if src.is_dir() {
+        fs::create_dir_all(dest)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(src, dest)?;
+    }
+    Ok(())
+}
+
+// file::moveTo(src: Silk, dest: Silk) -> Pulse
+fn file_move_to(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 2 {
+        return Err(FlowError::runtime(
+            "file::moveTo expects 2 arguments (src, dest)",
+            0,
+            0,
+        ));
+    }
+
+    let src = path_arg(&args, 0, "moveTo")?;
+    let dest = path_arg(&args, 1, "moveTo")?;
+    let fail = |e: std::io::Error| {
+        FlowError::runtime(&format!("Failed to move '{}' to '{}': {}", src, dest, e), 0, 0)
+    };
+
+    if fs::rename(&*src, &*dest).is_err() {
+        // rename cannot cross filesystems; fall back to copy + delete
+        copy_recursive(Path::new(&*src), Path::new(&*dest)).map_err(fail)?;
+        if Path::new(&*src).is_dir() {
+            fs::remove_dir_all(&*src).map_err(fail)?;
+        } else {
+            fs::remove_file(&*src).map_err(fail)?;
+        }
+    }
+    Ok(Value::Boolean(true))
+}
+
+// file::size(path: Silk) -> Ember
+fn file_size(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime(
+            "file::size expects 1 argument (path)",
+            0,
+            0,
+        ));
+    }
+
+    let path = path_arg(&args, 0, "size")?;
+    match fs::metadata(&*path) {
+        Ok(metadata) => Ok(Value::Number(metadata.len() as f64)),
+        Err(e) => Err(FlowError::runtime(
+            &format!("Failed to read metadata for '{}': {}", path, e),
+            0,
+            0,
+        )),
+    }
+}
+
+// file::modified(path: Silk) -> Ember
+// Seconds since the Unix epoch, like time.timestamp().
+fn file_modified(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime(
+            "file::modified expects 1 argument (path)",
+            0,
+            0,
+        ));
+    }
+
+    let path = path_arg(&args, 0, "modified")?;
+    let modified = fs::metadata(&*path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|e| {
+            FlowError::runtime(&format!("Failed to read metadata for '{}': {}", path, e), 0, 0)
+        })?;
+
+    let seconds = modified
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0);
+    Ok(Value::Number(seconds))
+}
+
+// file::watch(path: Silk, callback: Spell) -> Handle
+// Watches a file, or a directory recursively, and calls the callback with
+// { type, path } for each "create", "modify" and "delete" event. The handle
+// keeps the process alive until it is passed to file.unwatch.
+async fn file_watch(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    if args.len() != 2 {
+        return Err(FlowError::runtime(
+            "file::watch expects 2 arguments (path, callback)",
+            0,
+            0,
+        ));
+    }
+
+    let path = path_arg(&args, 0, "watch")?;
+    let callback = args[1].clone();
+    if callback.type_name() != "Spell" {
+        return Err(FlowError::type_error(
+            "file::watch expects a Spell (function) as callback",
+            0,
+            0,
+        ));
+    }
+
+    // notify delivers events on its own thread; forward them into tokio
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel::<notify::Result<notify::Event>>();
+    let mut watcher = notify::recommended_watcher(move |res| {
+        let _ = event_tx.send(res);
+    })
+    .map_err(|e| FlowError::runtime(&format!("Failed to create watcher: {}", e), 0, 0))?;
+
+    watcher
+        .watch(Path::new(&*path), RecursiveMode::Recursive)
+        .map_err(|e| FlowError::runtime(&format!("Failed to watch '{}': {}", path, e), 0, 0))?;
+
+    let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+    let handle_id = ctx
+        .runtime
+        .register_handle(HandleType::FileWatcher {
+            path: path.to_string(),
+            cancel_tx: Some(cancel_tx),
+        })
+        .await;
+
+    let callback_tx = ctx.runtime.callback_sender();
+    let runtime = ctx.runtime.clone();
+
+    tokio::spawn(async move {
+        // The watcher stops when dropped, so it lives inside the task
+        let _watcher = watcher;
+
+        loop {
+            tokio::select! {
+                event = event_rx.recv() => {
+                    let event = match event {
+                        Some(Ok(event)) => event,
+                        Some(Err(_)) => continue,
+                        None => break,
+                    };
+
+                    let kind = match event.kind {
+                        EventKind::Create(_) => "create",
+                        EventKind::Modify(_) => "modify",
+                        EventKind::Remove(_) => "delete",
+                        _ => continue,
+                    };
+
+                    for changed in event.paths {
+                        let mut info = RelicMap::new();
+                        info.insert("type".to_string(), Value::String(Arc::new(kind.to_string())));
+                        info.insert("path".to_string(), Value::String(Arc::new(changed.to_string_lossy().into_owned())));
+
+                        let request = crate::runtime::CallbackRequest {
+                            callback: callback.clone(),
+                            args: vec![Value::Relic(Arc::new(info))],
+                        };
+                        let _ = callback_tx.send(request);
+                    }
+                }
+                _ = &mut cancel_rx => {
+                    break;
+                }
+            }
+        }
+
+        runtime.unregister_handle(handle_id).await;
+    });
+
+    Ok(Value::Handle(handle_id))
+}
+
+// file::unwatch(handle: Handle) -> Pulse
+// Stops a watcher started by file.watch. Returns false if it was not active.
+async fn file_unwatch(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime(
+            "file::unwatch expects 1 argument (handle)",
+            0,
+            0,
+        ));
+    }
+
+    let handle_id = match &args[0] {
+        Value::Handle(id) => *id,
+        _ => {
+            return Err(FlowError::type_error(
+                "file::unwatch expects a Handle",
+                0,
+                0,
+            ))
+        }
+    };
+
+    let handles = ctx.runtime.handles();
+    let mut registry = handles.lock().await;
+
+    match registry.get_mut(handle_id) {
+        Some(handle) => {
+            match &mut handle.handle_type {
+                HandleType::FileWatcher { cancel_tx, .. } => {
+                    if let Some(tx) = cancel_tx.take() {
+                        let _ = tx.send(());
+                    }
+                }
+                _ => {
+                    return Err(FlowError::type_error(
+                        "file::unwatch expects a handle returned by file.watch",
+                        0,
+                        0,
+                    ))
+                }
+            }
+            registry.remove(handle_id);
+            Ok(Value::Boolean(true))
+        }
+        None => Ok(Value::Boolean(false)),
+    }
+}
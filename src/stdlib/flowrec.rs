@@ -0,0 +1,200 @@
+//! Record and replay of web requests (`.flowrec` files)
+//!
+//! `flowlang run --record requests.flowrec app.flow` writes every request that
+//! reaches a `web.serve` handler to a file, one JSON object per line after a
+//! header naming the script. `flowlang replay requests.flowrec` runs the script
+//! again with `web.serve` in replay mode: instead of binding its port, each server
+//! feeds the requests recorded for that port through its handler, in order, and
+//! closes once they are done. A bug seen in production can be reproduced locally
+//! without live traffic.
+
+use crate::error::FlowError;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+/// Version of the file format. Bump when its shape changes.
+const FLOWREC_VERSION: u32 = 1;
+
+static RECORDER: OnceLock<Mutex<BufWriter<File>>> = OnceLock::new();
+static REPLAY: OnceLock<Mutex<HashMap<u16, Vec<RecordedRequest>>>> = OnceLock::new();
+
+/// First line of a `.flowrec` file
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    flowrec: u32,
+    script: PathBuf,
+    recorded: String,
+}
+
+/// One request as it reached the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub time: String,
+    pub port: u16,
+    pub method: String,
+    /// Path including the query string
+    pub path: String,
+    /// In arrival order; a header sent twice appears twice
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: String,
+    /// Set instead of `body` when the body is not valid UTF-8
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_base64: Option<String>,
+    pub ip: String,
+}
+
+impl RecordedRequest {
+    pub fn new(port: u16, method: &str, path: &str, headers: Vec<(String, String)>, body: &[u8], ip: &str) -> Self {
+        let (body, body_base64) = match std::str::from_utf8(body) {
+            Ok(text) => (text.to_string(), None),
+            Err(_) => (String::new(), Some(base64::engine::general_purpose::STANDARD.encode(body))),
+        };
+        RecordedRequest {
+            time: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            port,
+            method: method.to_string(),
+            path: path.to_string(),
+            headers,
+            body,
+            body_base64,
+            ip: ip.to_string(),
+        }
+    }
+
+    /// The body bytes as they were received
+    pub fn body_bytes(&self) -> Vec<u8> {
+        match &self.body_base64 {
+            Some(encoded) => base64::engine::general_purpose::STANDARD.decode(encoded).unwrap_or_default(),
+            None => self.body.clone().into_bytes(),
+        }
+    }
+}
+
+/// A loaded `.flowrec` file
+pub struct Recording {
+    pub script: PathBuf,
+    pub requests: Vec<RecordedRequest>,
+}
+
+/// Record requests to `path` for the rest of the process
+pub fn start_recording(path: &Path, script: &Path) -> Result<(), FlowError> {
+    let file = File::create(path)
+        .map_err(|e| FlowError::rift(&format!("Failed to create recording {}: {}", path.display(), e), 0, 0))?;
+    let mut writer = BufWriter::new(file);
+    let header = Header {
+        flowrec: FLOWREC_VERSION,
+        script: script.to_path_buf(),
+        recorded: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+    };
+    write_line(&mut writer, &header)
+        .map_err(|e| FlowError::rift(&format!("Failed to write recording {}: {}", path.display(), e), 0, 0))?;
+    let _ = RECORDER.set(Mutex::new(writer));
+    Ok(())
+}
+
+/// Whether requests are being recorded, so servers can skip the copy otherwise
+pub fn is_recording() -> bool {
+    RECORDER.get().is_some()
+}
+
+/// Append a request to the recording. Each line is flushed right away, so the
+/// request that crashed the process is still in the file.
+pub fn record(request: &RecordedRequest) {
+    if let Some(recorder) = RECORDER.get() {
+        let mut writer = recorder.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(e) = write_line(&mut writer, request) {
+            eprintln!("⚠️ Failed to record request: {}", e);
+        }
+    }
+}
+
+fn write_line<T: Serialize>(writer: &mut BufWriter<File>, value: &T) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *writer, value)?;
+    writer.write_all(b"\n")?;
+    writer.flush()
+}
+
+/// Read a `.flowrec` file
+pub fn load(path: &Path) -> Result<Recording, FlowError> {
+    let file = File::open(path)
+        .map_err(|e| FlowError::rift(&format!("Failed to open recording {}: {}", path.display(), e), 0, 0))?;
+    parse(BufReader::new(file), path)
+}
+
+fn parse(reader: impl BufRead, path: &Path) -> Result<Recording, FlowError> {
+    let invalid = |line: usize, message: String| {
+        FlowError::glitch(&format!("Invalid recording {} (line {}): {}", path.display(), line, message), 0, 0)
+    };
+
+    let mut lines = reader.lines().enumerate();
+    let header: Header = match lines.next() {
+        Some((_, Ok(line))) => serde_json::from_str(&line).map_err(|e| invalid(1, e.to_string()))?,
+        Some((_, Err(e))) => return Err(invalid(1, e.to_string())),
+        None => return Err(invalid(1, "the file is empty".to_string())),
+    };
+    if header.flowrec != FLOWREC_VERSION {
+        return Err(invalid(1, format!("version {} is not supported (expected {})", header.flowrec, FLOWREC_VERSION)));
+    }
+
+    let mut requests = Vec::new();
+    for (index, line) in lines {
+        let line = line.map_err(|e| invalid(index + 1, e.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        requests.push(serde_json::from_str(&line).map_err(|e| invalid(index + 1, e.to_string()))?);
+    }
+
+    Ok(Recording { script: header.script, requests })
+}
+
+/// Put `web.serve` in replay mode with these requests
+pub fn start_replay(requests: Vec<RecordedRequest>) {
+    let mut by_port: HashMap<u16, Vec<RecordedRequest>> = HashMap::new();
+    for request in requests {
+        by_port.entry(request.port).or_default().push(request);
+    }
+    let _ = REPLAY.set(Mutex::new(by_port));
+}
+
+/// In replay mode, the recorded requests for a server on `port` (possibly none);
+/// `None` when serving live traffic
+pub fn take_replay(port: u16) -> Option<Vec<RecordedRequest>> {
+    let replay = REPLAY.get()?;
+    Some(replay.lock().unwrap_or_else(PoisonError::into_inner).remove(&port).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_recording() {
+        let request = RecordedRequest::new(
+            8080,
+            "POST",
+            "/users?page=2",
+            vec![("host".to_string(), "localhost".to_string())],
+            &[0xff, 0x00],
+            "127.0.0.1",
+        );
+        assert_eq!(request.body_bytes(), vec![0xff, 0x00]);
+
+        let text = format!(
+            "{{\"flowrec\":1,\"script\":\"app.flow\",\"recorded\":\"now\"}}\n{}\n\n",
+            serde_json::to_string(&request).unwrap()
+        );
+        let recording = parse(text.as_bytes(), Path::new("test.flowrec")).unwrap();
+        assert_eq!(recording.script, PathBuf::from("app.flow"));
+        assert_eq!(recording.requests, vec![request]);
+
+        let error = parse("{\"flowrec\":9,\"script\":\"a\",\"recorded\":\"\"}".as_bytes(), Path::new("old.flowrec"));
+        assert!(error.is_err());
+    }
+}
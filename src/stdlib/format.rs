@@ -0,0 +1,258 @@
+//! Format strings for the `forge` built-in and `Silk.format`
+//!
+//! `{0}` and `{}` take positional arguments, `{name}` (or `{user.name}`) reads
+//! the Relic passed as the last argument, and `{{` / `}}` are literal braces.
+//! After a colon comes a spec in the order `[[fill]align][+][0][width][.precision][type]`:
+//! align is `<`, `>` or `^`; type is `x`, `X`, `o`, `b` (integers), `e`
+//! (exponent) or `%` (times 100 with a percent sign).
+
+use crate::error::FlowError;
+use crate::types::Value;
+
+#[derive(Debug, Default, PartialEq)]
+struct Spec {
+    fill: Option<char>,
+    align: Option<char>,
+    plus: bool,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    kind: Option<char>,
+}
+
+/// Fill `template` with `args`
+pub fn forge(template: &str, args: &[Value]) -> Result<String, FlowError> {
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    let mut next_auto = 0;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '}' => return Err(error("a single '}' must be written '}}'")),
+            '{' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => inner.push(c),
+                        None => return Err(error(&format!("'{{{}' is never closed", inner))),
+                    }
+                }
+                let (key, spec) = match inner.split_once(':') {
+                    Some((key, spec)) => (key.trim(), parse_spec(spec)?),
+                    None => (inner.trim(), Spec::default()),
+                };
+                let value = lookup(key, args, &mut next_auto)?;
+                out.push_str(&render(value, &spec)?);
+            }
+            c => out.push(c),
+        }
+    }
+
+    Ok(out)
+}
+
+fn error(message: &str) -> FlowError {
+    FlowError::runtime(&format!("forge: {}", message), 0, 0)
+}
+
+fn lookup<'a>(key: &str, args: &'a [Value], next_auto: &mut usize) -> Result<&'a Value, FlowError> {
+    let index = if key.is_empty() {
+        *next_auto += 1;
+        Some(*next_auto - 1)
+    } else {
+        key.parse::<usize>().ok()
+    };
+
+    if let Some(index) = index {
+        return args.get(index).ok_or_else(|| {
+            FlowError::out_of_range(
+                &format!("forge: placeholder {{{}}} has no argument ({} given)", index, args.len()),
+                0,
+                0,
+            )
+        });
+    }
+
+    let mut value = match args.last() {
+        Some(relic @ Value::Relic(_)) => relic,
+        _ => return Err(error(&format!("{{{}}} needs a Relic of named values as the last argument", key))),
+    };
+    for part in key.split('.') {
+        value = match value {
+            Value::Relic(map) => map.get(part),
+            _ => None,
+        }
+        .ok_or_else(|| FlowError::undefined(&format!("forge: no value named '{}'", key), 0, 0))?;
+    }
+    Ok(value)
+}
+
+fn parse_spec(spec: &str) -> Result<Spec, FlowError> {
+    let chars: Vec<char> = spec.chars().collect();
+    let mut parsed = Spec::default();
+    let mut i = 0;
+
+    let is_align = |c: Option<&char>| matches!(c, Some('<' | '>' | '^'));
+    if chars.len() >= 2 && is_align(chars.get(1)) {
+        parsed.fill = Some(chars[0]);
+        parsed.align = Some(chars[1]);
+        i = 2;
+    } else if is_align(chars.first()) {
+        parsed.align = Some(chars[0]);
+        i = 1;
+    }
+    if chars.get(i) == Some(&'+') {
+        parsed.plus = true;
+        i += 1;
+    }
+    if chars.get(i) == Some(&'0') {
+        parsed.zero = true;
+        i += 1;
+    }
+    let digits = |i: &mut usize| {
+        let start = *i;
+        while chars.get(*i).is_some_and(|c| c.is_ascii_digit()) {
+            *i += 1;
+        }
+        chars[start..*i].iter().collect::<String>().parse::<usize>().ok()
+    };
+    parsed.width = digits(&mut i).unwrap_or(0);
+    if chars.get(i) == Some(&'.') {
+        i += 1;
+        parsed.precision = Some(digits(&mut i).ok_or_else(|| error(&format!("'.' needs a precision in '{}'", spec)))?);
+    }
+    if let Some(&kind) = chars.get(i) {
+        if !matches!(kind, 'x' | 'X' | 'o' | 'b' | 'e' | '%') {
+            return Err(error(&format!("unknown format '{}' in '{}'", kind, spec)));
+        }
+        parsed.kind = Some(kind);
+        i += 1;
+    }
+    if i != chars.len() {
+        return Err(error(&format!("invalid format spec '{}'", spec)));
+    }
+    Ok(parsed)
+}
+
+fn render(value: &Value, spec: &Spec) -> Result<String, FlowError> {
+    let (sign, body) = match value {
+        Value::Number(n) => {
+            let body = number(n.abs(), spec)?;
+            let sign = if *n < 0.0 && body.chars().any(|c| c.is_ascii_digit() && c != '0') {
+                "-"
+            } else if spec.plus {
+                "+"
+            } else {
+                ""
+            };
+            (sign, body)
+        }
+        other => {
+            if spec.kind.is_some() || spec.plus {
+                return Err(FlowError::type_error(
+                    &format!("forge: numeric format used on a {}", other.type_name()),
+                    0,
+                    0,
+                ));
+            }
+            let text = other.to_string();
+            let text = match spec.precision {
+                Some(max) => text.chars().take(max).collect(),
+                None => text,
+            };
+            ("", text)
+        }
+    };
+
+    let len = sign.chars().count() + body.chars().count();
+    if len >= spec.width {
+        return Ok(format!("{}{}", sign, body));
+    }
+    let pad = spec.width - len;
+
+    // Zero padding goes between the sign and the digits
+    if spec.zero && spec.align.is_none() && matches!(value, Value::Number(_)) {
+        return Ok(format!("{}{}{}", sign, "0".repeat(pad), body));
+    }
+
+    let fill = spec.fill.unwrap_or(' ').to_string();
+    let default_align = if matches!(value, Value::Number(_)) { '>' } else { '<' };
+    let text = format!("{}{}", sign, body);
+    Ok(match spec.align.unwrap_or(default_align) {
+        '>' => format!("{}{}", fill.repeat(pad), text),
+        '^' => format!("{}{}{}", fill.repeat(pad / 2), text, fill.repeat(pad - pad / 2)),
+        _ => format!("{}{}", text, fill.repeat(pad)),
+    })
+}
+
+/// A non-negative number in the spec's notation
+fn number(n: f64, spec: &Spec) -> Result<String, FlowError> {
+    let integer = || {
+        if n.fract() != 0.0 {
+            return Err(FlowError::type_error(
+                &format!("forge: '{}' needs a whole Ember, got {}", spec.kind.unwrap_or('x'), n),
+                0,
+                0,
+            ));
+        }
+        Ok(n as u64)
+    };
+    Ok(match (spec.kind, spec.precision) {
+        (Some('x'), _) => format!("{:x}", integer()?),
+        (Some('X'), _) => format!("{:X}", integer()?),
+        (Some('o'), _) => format!("{:o}", integer()?),
+        (Some('b'), _) => format!("{:b}", integer()?),
+        (Some('e'), Some(p)) => format!("{:.*e}", p, n),
+        (Some('e'), None) => format!("{:e}", n),
+        (Some('%'), p) => format!("{:.*}%", p.unwrap_or(0), n * 100.0),
+        (_, Some(p)) => format!("{:.*}", p, n),
+        _ => Value::Number(n).to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RelicMap;
+    use std::sync::Arc;
+
+    fn silk(s: &str) -> Value {
+        Value::String(Arc::new(s.to_string()))
+    }
+
+    #[test]
+    fn test_forge_placeholders_and_specs() {
+        let args = [silk("Goku"), Value::Number(9001.0)];
+        assert_eq!(forge("Hello {0}, you have {1} points", &args).unwrap(), "Hello Goku, you have 9001 points");
+        assert_eq!(forge("{} then {}", &args).unwrap(), "Goku then 9001");
+        assert_eq!(forge("{{{0}}}", &args).unwrap(), "{Goku}");
+
+        let mut user = RelicMap::new();
+        user.insert("name".to_string(), silk("Vegeta"));
+        let mut named = RelicMap::new();
+        named.insert("user".to_string(), Value::Relic(Arc::new(user)));
+        named.insert("ratio".to_string(), Value::Number(0.4567));
+        let named = [Value::Relic(Arc::new(named))];
+        assert_eq!(forge("{user.name} {ratio:.1%}", &named).unwrap(), "Vegeta 45.7%");
+
+        let pi = [Value::Number(-3.14159)];
+        assert_eq!(forge("{0:.2}|{0:8.1}|{0:08.1}|{0:<8.1}|", &pi).unwrap(), "-3.14|    -3.1|-00003.1|-3.1    |");
+        assert_eq!(forge("{0:*^9}|{0:.2}|{0:>5}", &[silk("mid")]).unwrap(), "***mid***|mi|  mid");
+        assert_eq!(forge("{0:x} {0:#>6b} {0:+}", &[Value::Number(10.0)]).unwrap(), "a ##1010 +10");
+
+        assert!(forge("{2}", &args).is_err());
+        assert!(forge("{name}", &args).is_err());
+        assert!(forge("{0", &args).is_err());
+        assert!(forge("{0:x}", &[Value::Number(1.5)]).is_err());
+        assert!(forge("{0:q}", &args).is_err());
+    }
+}
@@ -1,10 +1,14 @@
 //! std:git - Git operations module using git2
 //!
 //! Provides Git repository operations powered by libgit2.
+//!
+//! `status`, `diff`, `commit`, `branch` and `log` return Relics so release
+//! automation can inspect a repository without parsing git's text output.
+//! They find the repository from the current directory unless given a
+//! `repo` option (or, for `status`, a path).
 
-use crate::types::{NativeFn, Value};
+use crate::types::{NativeFn, Value, RelicMap};
 use crate::error::FlowError;
-use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Load the git module
@@ -15,6 +19,10 @@ pub fn load_git_module() -> Vec<(&'static str, Value)> {
         ("checkout", Value::NativeFunction(NativeFn::new(git_checkout))),
         ("status", Value::NativeFunction(NativeFn::new(git_status))),
         ("init", Value::NativeFunction(NativeFn::new(git_init))),
+        ("diff", Value::NativeFunction(NativeFn::new(git_diff))),
+        ("commit", Value::NativeFunction(NativeFn::new(git_commit))),
+        ("branch", Value::NativeFunction(NativeFn::new(git_branch))),
+        ("log", Value::NativeFunction(NativeFn::new(git_log))),
     ]
 }
 
@@ -94,16 +102,13 @@ fn git_checkout(args: Vec<Value>) -> Result<Value, FlowError> {
 }
 
 /// Get repository status
-/// git.status(repo_path) -> Relic {branch, dirty, files}
+/// git.status(repo_path?) -> Relic {branch, head, dirty, files, staged, unstaged, untracked, conflicted, upstream, ahead, behind}
 fn git_status(args: Vec<Value>) -> Result<Value, FlowError> {
-    if args.is_empty() {
-        return Err(FlowError::runtime("git.status() requires repository path", 0, 0));
-    }
-
-    let repo_path = args[0].to_string();
-
-    let repo = git2::Repository::open(&repo_path)
-        .map_err(|e| FlowError::runtime(&format!("Failed to open repo: {}", e), 0, 0))?;
+    let repo = match args.first() {
+        Some(Value::String(path)) => open_repo(Some(path.as_str()))?,
+        Some(Value::Relic(options)) => open_repo(repo_option(options).as_deref())?,
+        _ => open_repo(None)?,
+    };
 
     // Get current branch
     let head = repo.head().ok();
@@ -113,22 +118,71 @@ fn git_status(args: Vec<Value>) -> Result<Value, FlowError> {
         .to_string();
 
     // Check for changes
-    let statuses = repo.statuses(None)
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true).include_ignored(false);
+    let statuses = repo.statuses(Some(&mut options))
         .map_err(|e| FlowError::runtime(&format!("Status failed: {}", e), 0, 0))?;
 
     let dirty = !statuses.is_empty();
     
     let mut files: Vec<Value> = Vec::new();
+    let mut staged = Vec::new();
+    let mut unstaged = Vec::new();
+    let mut untracked = Vec::new();
+    let mut conflicted = Vec::new();
     for entry in statuses.iter() {
-        if let Some(path) = entry.path() {
-            files.push(Value::String(Arc::new(path.to_string())));
+        let Some(path) = entry.path() else { continue };
+        files.push(silk(path));
+        let status = entry.status();
+        if status.is_conflicted() {
+            conflicted.push(silk(path));
+            continue;
+        }
+        if status.is_wt_new() {
+            untracked.push(silk(path));
+        }
+        let index_change = [
+            (git2::Status::INDEX_NEW, "added"),
+            (git2::Status::INDEX_MODIFIED, "modified"),
+            (git2::Status::INDEX_DELETED, "deleted"),
+            (git2::Status::INDEX_RENAMED, "renamed"),
+            (git2::Status::INDEX_TYPECHANGE, "typechange"),
+        ].into_iter().find(|(flag, _)| status.contains(*flag));
+        if let Some((_, change)) = index_change {
+            staged.push(file_change(path, change));
+        }
+        let workdir_change = [
+            (git2::Status::WT_MODIFIED, "modified"),
+            (git2::Status::WT_DELETED, "deleted"),
+            (git2::Status::WT_RENAMED, "renamed"),
+            (git2::Status::WT_TYPECHANGE, "typechange"),
+        ].into_iter().find(|(flag, _)| status.contains(*flag));
+        if let Some((_, change)) = workdir_change {
+            unstaged.push(file_change(path, change));
         }
     }
 
-    let mut result = HashMap::new();
+    let mut result = RelicMap::new();
     result.insert("branch".to_string(), Value::String(Arc::new(branch)));
+    result.insert("head".to_string(), head_id(&repo));
     result.insert("dirty".to_string(), Value::Boolean(dirty));
     result.insert("files".to_string(), Value::Array(Arc::new(files)));
+    result.insert("staged".to_string(), Value::Array(Arc::new(staged)));
+    result.insert("unstaged".to_string(), Value::Array(Arc::new(unstaged)));
+    result.insert("untracked".to_string(), Value::Array(Arc::new(untracked)));
+    result.insert("conflicted".to_string(), Value::Array(Arc::new(conflicted)));
+
+    let tracking = head.as_ref()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.shorthand())
+        .and_then(|name| upstream_of(&repo, name));
+    let (upstream, ahead, behind) = match tracking {
+        Some((name, ahead, behind)) => (silk(&name), ahead, behind),
+        None => (Value::Null, 0, 0),
+    };
+    result.insert("upstream".to_string(), upstream);
+    result.insert("ahead".to_string(), Value::Number(ahead as f64));
+    result.insert("behind".to_string(), Value::Number(behind as f64));
 
     Ok(Value::Relic(Arc::new(result)))
 }
@@ -147,3 +201,294 @@ fn git_init(args: Vec<Value>) -> Result<Value, FlowError> {
         Err(e) => Err(FlowError::runtime(&format!("git init failed: {}", e), 0, 0)),
     }
 }
+
+/// Show changes as structured file diffs
+/// git.diff(paths?, options?) -> Relic {files, additions, deletions}
+/// Options: repo, staged (compare the index to HEAD instead of the working tree to the index).
+fn git_diff(args: Vec<Value>) -> Result<Value, FlowError> {
+    let (paths, options) = match args.first() {
+        Some(Value::Array(paths)) => (paths.iter().map(|p| p.to_string()).collect(), args.get(1)),
+        Some(Value::String(path)) => (vec![path.to_string()], args.get(1)),
+        Some(Value::Relic(_)) => (Vec::new(), args.first()),
+        None | Some(Value::Null) => (Vec::new(), args.get(1)),
+        Some(_) => return Err(FlowError::type_error("git.diff() expects paths as a Constellation", 0, 0)),
+    };
+    let options = options_of(options, "diff")?;
+    let repo = open_repo(repo_option(&options).as_deref())?;
+
+    let mut diff_options = git2::DiffOptions::new();
+    for path in &paths {
+        diff_options.pathspec(path);
+    }
+    let mut diff = if flag(&options, "staged") {
+        let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+        repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut diff_options))
+    } else {
+        repo.diff_index_to_workdir(None, Some(&mut diff_options))
+    }.map_err(|e| FlowError::runtime(&format!("Diff failed: {}", e), 0, 0))?;
+    diff.find_similar(None)
+        .map_err(|e| FlowError::runtime(&format!("Diff failed: {}", e), 0, 0))?;
+
+    let mut files = Vec::new();
+    let (mut total_additions, mut total_deletions) = (0, 0);
+    for index in 0..diff.deltas().len() {
+        let mut patch = git2::Patch::from_diff(&diff, index)
+            .map_err(|e| FlowError::runtime(&format!("Diff failed: {}", e), 0, 0))?;
+        let (additions, deletions, text) = match patch.as_mut() {
+            Some(patch) => {
+                let (_, additions, deletions) = patch.line_stats()
+                    .map_err(|e| FlowError::runtime(&format!("Diff failed: {}", e), 0, 0))?;
+                let text = patch.to_buf().ok().and_then(|buf| buf.as_str().map(str::to_string)).unwrap_or_default();
+                (additions, deletions, text)
+            }
+            None => (0, 0, String::new()),
+        };
+        total_additions += additions;
+        total_deletions += deletions;
+
+        let delta = diff.get_delta(index).expect("delta index is in range");
+        let path_of = |file: git2::DiffFile| file.path().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+        let status = match delta.status() {
+            git2::Delta::Added | git2::Delta::Untracked => "added",
+            git2::Delta::Deleted => "deleted",
+            git2::Delta::Renamed => "renamed",
+            git2::Delta::Copied => "copied",
+            git2::Delta::Typechange => "typechange",
+            _ => "modified",
+        };
+        let mut file = RelicMap::new();
+        file.insert("path".to_string(), silk(&path_of(delta.new_file())));
+        file.insert("oldPath".to_string(), silk(&path_of(delta.old_file())));
+        file.insert("status".to_string(), silk(status));
+        file.insert("binary".to_string(), Value::Boolean(delta.flags().is_binary()));
+        file.insert("additions".to_string(), Value::Number(additions as f64));
+        file.insert("deletions".to_string(), Value::Number(deletions as f64));
+        file.insert("patch".to_string(), silk(&text));
+        files.push(Value::Relic(Arc::new(file)));
+    }
+
+    let mut result = RelicMap::new();
+    result.insert("files".to_string(), Value::Array(Arc::new(files)));
+    result.insert("additions".to_string(), Value::Number(total_additions as f64));
+    result.insert("deletions".to_string(), Value::Number(total_deletions as f64));
+    Ok(Value::Relic(Arc::new(result)))
+}
+
+/// Record staged changes as a new commit on HEAD
+/// git.commit(message, options?) -> Relic (the new commit, as in git.log)
+/// Options: repo, paths (stage these first), all (stage every tracked change), author {name, email}, allowEmpty.
+fn git_commit(args: Vec<Value>) -> Result<Value, FlowError> {
+    let message = match args.first() {
+        Some(Value::String(message)) if !message.trim().is_empty() => message.to_string(),
+        _ => return Err(FlowError::runtime("git.commit() requires a message", 0, 0)),
+    };
+    let options = options_of(args.get(1), "commit")?;
+    let repo = open_repo(repo_option(&options).as_deref())?;
+    let index_error = |e: git2::Error| FlowError::runtime(&format!("Failed to stage changes: {}", e), 0, 0);
+
+    let mut index = repo.index().map_err(index_error)?;
+    if let Some(Value::Array(paths)) = options.get("paths") {
+        let paths: Vec<String> = paths.iter().map(|p| p.to_string()).collect();
+        index.add_all(paths.iter(), git2::IndexAddOption::DEFAULT, None).map_err(index_error)?;
+        // add_all skips deleted files; update_all stages their removal
+        index.update_all(paths.iter(), None).map_err(index_error)?;
+    }
+    if flag(&options, "all") {
+        index.update_all(["*"].iter(), None).map_err(index_error)?;
+    }
+    index.write().map_err(index_error)?;
+    let tree_id = index.write_tree().map_err(index_error)?;
+    let tree = repo.find_tree(tree_id).map_err(index_error)?;
+
+    let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let unchanged = match &parent {
+        Some(parent) => parent.tree_id() == tree_id,
+        None => index.is_empty(),
+    };
+    if unchanged && !flag(&options, "allowEmpty") {
+        return Err(FlowError::runtime("git.commit(): nothing to commit (stage changes or pass paths/all)", 0, 0));
+    }
+
+    let signature = match options.get("author") {
+        Some(Value::Relic(author)) => {
+            let field = |name: &str| author.get(name).map(|v| v.to_string()).unwrap_or_default();
+            git2::Signature::now(&field("name"), &field("email"))
+        }
+        _ => repo.signature(),
+    }.map_err(|e| FlowError::runtime(
+        &format!("No commit author ({}); set user.name and user.email in git config or pass an author option", e.message()),
+        0, 0,
+    ))?;
+
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let id = repo.commit(Some("HEAD"), &signature, &signature, &message, &tree, &parents)
+        .map_err(|e| FlowError::runtime(&format!("Commit failed: {}", e), 0, 0))?;
+    let commit = repo.find_commit(id)
+        .map_err(|e| FlowError::runtime(&format!("Commit failed: {}", e), 0, 0))?;
+    Ok(commit_value(&commit))
+}
+
+/// List branches, optionally creating one at HEAD
+/// git.branch(options?) -> Relic {current, detached, head, branches}
+/// Options: repo, create (new branch name), remotes (include remote-tracking branches).
+fn git_branch(args: Vec<Value>) -> Result<Value, FlowError> {
+    let options = options_of(args.first(), "branch")?;
+    let repo = open_repo(repo_option(&options).as_deref())?;
+
+    if let Some(Value::String(name)) = options.get("create") {
+        let head = repo.head().and_then(|h| h.peel_to_commit())
+            .map_err(|e| FlowError::runtime(&format!("Cannot create a branch without a commit: {}", e), 0, 0))?;
+        repo.branch(name, &head, false)
+            .map_err(|e| FlowError::runtime(&format!("Failed to create branch '{}': {}", name, e), 0, 0))?;
+    }
+
+    let head = repo.head().ok();
+    let detached = repo.head_detached().unwrap_or(false);
+    let current = head.as_ref()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.shorthand())
+        .map(str::to_string)
+        // A fresh repository's HEAD names a branch with no commits yet
+        .or_else(|| repo.find_reference("HEAD").ok()?.symbolic_target()?.strip_prefix("refs/heads/").map(str::to_string));
+
+    let filter = if flag(&options, "remotes") { None } else { Some(git2::BranchType::Local) };
+    let list = repo.branches(filter)
+        .map_err(|e| FlowError::runtime(&format!("Failed to list branches: {}", e), 0, 0))?;
+    let mut branches = Vec::new();
+    for item in list {
+        let (branch, kind) = item.map_err(|e| FlowError::runtime(&format!("Failed to list branches: {}", e), 0, 0))?;
+        let Ok(Some(name)) = branch.name() else { continue };
+        let name = name.to_string();
+        let mut info = RelicMap::new();
+        info.insert("name".to_string(), silk(&name));
+        info.insert("remote".to_string(), Value::Boolean(kind == git2::BranchType::Remote));
+        info.insert("current".to_string(), Value::Boolean(kind == git2::BranchType::Local && branch.is_head()));
+        info.insert("commit".to_string(), branch.get().target().map_or(Value::Null, |oid| silk(&oid.to_string())));
+        let tracking = if kind == git2::BranchType::Local { upstream_of(&repo, &name) } else { None };
+        let (upstream, ahead, behind) = match tracking {
+            Some((upstream, ahead, behind)) => (silk(&upstream), ahead, behind),
+            None => (Value::Null, 0, 0),
+        };
+        info.insert("upstream".to_string(), upstream);
+        info.insert("ahead".to_string(), Value::Number(ahead as f64));
+        info.insert("behind".to_string(), Value::Number(behind as f64));
+        branches.push(Value::Relic(Arc::new(info)));
+    }
+
+    let mut result = RelicMap::new();
+    result.insert("current".to_string(), current.map_or(Value::Null, |name| silk(&name)));
+    result.insert("detached".to_string(), Value::Boolean(detached));
+    result.insert("head".to_string(), head_id(&repo));
+    result.insert("branches".to_string(), Value::Array(Arc::new(branches)));
+    Ok(Value::Relic(Arc::new(result)))
+}
+
+/// Recent commits, newest first
+/// git.log(n?, options?) -> Constellation of {id, shortId, summary, message, author, email, date, timestamp, parents}
+/// Options: repo, ref (start from this branch, tag or commit instead of HEAD).
+fn git_log(args: Vec<Value>) -> Result<Value, FlowError> {
+    let (limit, options) = match args.first() {
+        Some(Value::Number(n)) if *n >= 0.0 => (*n as usize, args.get(1)),
+        Some(Value::Relic(_)) => (10, args.first()),
+        None => (10, None),
+        Some(_) => return Err(FlowError::type_error("git.log() expects a commit count", 0, 0)),
+    };
+    let options = options_of(options, "log")?;
+    let repo = open_repo(repo_option(&options).as_deref())?;
+    let log_error = |e: git2::Error| FlowError::runtime(&format!("Log failed: {}", e), 0, 0);
+
+    let start = match options.get("ref") {
+        Some(Value::String(name)) => Some(
+            repo.revparse_single(name).and_then(|o| o.peel_to_commit())
+                .map_err(|e| FlowError::runtime(&format!("Ref '{}' not found: {}", name, e), 0, 0))?
+                .id(),
+        ),
+        _ => repo.head().ok().and_then(|h| h.target()),
+    };
+    // A repository with no commits has an empty history
+    let Some(start) = start else {
+        return Ok(Value::Array(Arc::new(Vec::new())));
+    };
+
+    let mut walk = repo.revwalk().map_err(log_error)?;
+    walk.push(start).map_err(log_error)?;
+    walk.set_sorting(git2::Sort::TIME).map_err(log_error)?;
+    let mut commits = Vec::new();
+    for oid in walk.take(limit) {
+        let commit = repo.find_commit(oid.map_err(log_error)?).map_err(log_error)?;
+        commits.push(commit_value(&commit));
+    }
+    Ok(Value::Array(Arc::new(commits)))
+}
+
+fn silk(s: &str) -> Value {
+    Value::String(Arc::new(s.to_string()))
+}
+
+/// Finds the repository containing `path` (default: the current directory)
+fn open_repo(path: Option<&str>) -> Result<git2::Repository, FlowError> {
+    git2::Repository::discover(path.unwrap_or("."))
+        .map_err(|e| FlowError::runtime(&format!("Failed to open repo: {}", e), 0, 0))
+}
+
+fn options_of(value: Option<&Value>, func: &str) -> Result<RelicMap, FlowError> {
+    match value {
+        Some(Value::Relic(options)) => Ok(options.as_ref().clone()),
+        None | Some(Value::Null) => Ok(RelicMap::new()),
+        Some(_) => Err(FlowError::type_error(&format!("git.{}() options must be a Relic", func), 0, 0)),
+    }
+}
+
+fn repo_option(options: &RelicMap) -> Option<String> {
+    match options.get("repo") {
+        Some(Value::String(path)) => Some(path.to_string()),
+        _ => None,
+    }
+}
+
+fn flag(options: &RelicMap, name: &str) -> bool {
+    matches!(options.get(name), Some(Value::Boolean(true)))
+}
+
+fn file_change(path: &str, status: &str) -> Value {
+    let mut change = RelicMap::new();
+    change.insert("path".to_string(), silk(path));
+    change.insert("status".to_string(), silk(status));
+    Value::Relic(Arc::new(change))
+}
+
+fn head_id(repo: &git2::Repository) -> Value {
+    repo.head().ok().and_then(|h| h.target()).map_or(Value::Null, |oid| silk(&oid.to_string()))
+}
+
+/// The upstream of a local branch with how far ahead and behind it is
+fn upstream_of(repo: &git2::Repository, branch: &str) -> Option<(String, usize, usize)> {
+    let local = repo.find_branch(branch, git2::BranchType::Local).ok()?;
+    let upstream = local.upstream().ok()?;
+    let name = upstream.name().ok()??.to_string();
+    let (ahead, behind) = repo.graph_ahead_behind(local.get().target()?, upstream.get().target()?).ok()?;
+    Some((name, ahead, behind))
+}
+
+fn commit_value(commit: &git2::Commit) -> Value {
+    let id = commit.id().to_string();
+    let author = commit.author();
+    let time = commit.time();
+    let date = chrono::FixedOffset::east_opt(time.offset_minutes() * 60)
+        .and_then(|offset| chrono::DateTime::from_timestamp(time.seconds(), 0).map(|d| d.with_timezone(&offset)))
+        .map(|d| d.to_rfc3339())
+        .unwrap_or_default();
+
+    let mut result = RelicMap::new();
+    result.insert("shortId".to_string(), silk(&id[..7]));
+    result.insert("id".to_string(), silk(&id));
+    result.insert("summary".to_string(), silk(commit.summary().unwrap_or("")));
+    result.insert("message".to_string(), silk(commit.message().unwrap_or("").trim_end()));
+    result.insert("author".to_string(), silk(author.name().unwrap_or("")));
+    result.insert("email".to_string(), silk(author.email().unwrap_or("")));
+    result.insert("date".to_string(), silk(&date));
+    result.insert("timestamp".to_string(), Value::Number(time.seconds() as f64));
+    let parents = commit.parent_ids().map(|oid| silk(&oid.to_string())).collect();
+    result.insert("parents".to_string(), Value::Array(Arc::new(parents)));
+    Value::Relic(Arc::new(result))
+}
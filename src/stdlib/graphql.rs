@@ -0,0 +1,1243 @@
+//! GraphQL engine behind `web.graphql(schema, resolvers)`
+//!
+//! Covers the parts of GraphQL an API needs: schema SDL (types, inputs, enums,
+//! interfaces, unions, scalars), queries and mutations with arguments, variables,
+//! aliases, fragments and @skip/@include, and null propagation for non-null
+//! fields. Introspection and subscriptions are not supported.
+//!
+//! Root resolvers are called as `(args, context)`, field resolvers on other types
+//! as `(parent, args, context)`. Fields without a resolver read the parent Relic.
+
+use crate::error::FlowError;
+use crate::interpreter::Interpreter;
+use crate::types::{InterpreterFuture, InterpreterNativeFn, Value, RelicMap};
+use serde_json::{json, Map, Value as Json};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+// ============================================================================
+// Lexer
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Punct(char),
+    Spread,
+    Name(String),
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+fn lex(source: &str) -> Result<Vec<(Tok, usize)>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let mut line = 1;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\n' => {
+                line += 1;
+                i += 1;
+            }
+            ' ' | '\t' | '\r' | ',' | '\u{feff}' => i += 1,
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '.' => {
+                if chars.get(i + 1) == Some(&'.') && chars.get(i + 2) == Some(&'.') {
+                    tokens.push((Tok::Spread, line));
+                    i += 3;
+                } else {
+                    return Err(format!("line {}: unexpected '.'", line));
+                }
+            }
+            '!' | '$' | '(' | ')' | ':' | '=' | '@' | '[' | ']' | '{' | '}' | '|' | '&' => {
+                tokens.push((Tok::Punct(c), line));
+                i += 1;
+            }
+            '"' => {
+                // Block strings ("""...""") only appear as descriptions; keep their text as is
+                if chars.get(i + 1) == Some(&'"') && chars.get(i + 2) == Some(&'"') {
+                    let start = i + 3;
+                    let mut end = start;
+                    while end + 2 < chars.len() && !(chars[end] == '"' && chars[end + 1] == '"' && chars[end + 2] == '"') {
+                        end += 1;
+                    }
+                    if end + 2 >= chars.len() {
+                        return Err(format!("line {}: unterminated block string", line));
+                    }
+                    let text: String = chars[start..end].iter().collect();
+                    line += text.matches('\n').count();
+                    tokens.push((Tok::Str(text), line));
+                    i = end + 3;
+                    continue;
+                }
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None | Some('\n') => return Err(format!("line {}: unterminated string", line)),
+                        Some('"') => break,
+                        Some('\\') => {
+                            let escaped = match chars.get(i + 1) {
+                                Some('n') => '\n',
+                                Some('t') => '\t',
+                                Some('r') => '\r',
+                                Some('u') => {
+                                    let hex: String = chars.iter().skip(i + 2).take(4).collect();
+                                    i += 4;
+                                    u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32).unwrap_or('\u{fffd}')
+                                }
+                                Some(other) => *other,
+                                None => return Err(format!("line {}: unterminated string", line)),
+                            };
+                            text.push(escaped);
+                            i += 2;
+                        }
+                        Some(other) => {
+                            text.push(*other);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push((Tok::Str(text), line));
+                i += 1;
+            }
+            c if c == '-' || c.is_ascii_digit() => {
+                let start = i;
+                i += 1;
+                let mut float = false;
+                while i < chars.len() && (chars[i].is_ascii_digit() || matches!(chars[i], '.' | 'e' | 'E' | '+' | '-')) {
+                    if matches!(chars[i], '.' | 'e' | 'E') {
+                        float = true;
+                    }
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                let token = if float {
+                    text.parse().map(Tok::Float).map_err(|_| format!("line {}: invalid number '{}'", line, text))?
+                } else {
+                    text.parse().map(Tok::Int).map_err(|_| format!("line {}: invalid number '{}'", line, text))?
+                };
+                tokens.push((token, line));
+            }
+            c if c == '_' || c.is_ascii_alphabetic() => {
+                let start = i;
+                while i < chars.len() && (chars[i] == '_' || chars[i].is_ascii_alphanumeric()) {
+                    i += 1;
+                }
+                tokens.push((Tok::Name(chars[start..i].iter().collect()), line));
+            }
+            other => return Err(format!("line {}: unexpected character '{}'", line, other)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Tok, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Result<Self, String> {
+        Ok(Parser { tokens: lex(source)?, pos: 0 })
+    }
+
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens.get(self.pos).or(self.tokens.last()).map(|(_, l)| *l).unwrap_or(1)
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, String> {
+        let found = match self.peek() {
+            Some(Tok::Name(n)) => format!("'{}'", n),
+            Some(Tok::Punct(c)) => format!("'{}'", c),
+            Some(Tok::Spread) => "'...'".to_string(),
+            Some(Tok::Str(_)) => "a string".to_string(),
+            Some(Tok::Int(_)) | Some(Tok::Float(_)) => "a number".to_string(),
+            None => "end of input".to_string(),
+        };
+        Err(format!("line {}: {}, found {}", self.line(), message, found))
+    }
+
+    fn next(&mut self) -> Option<Tok> {
+        let token = self.tokens.get(self.pos).map(|(t, _)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn is(&self, c: char) -> bool {
+        self.peek() == Some(&Tok::Punct(c))
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.is(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            self.error(&format!("expected '{}'", c))
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(Tok::Name(n)) => {
+                let n = n.clone();
+                self.pos += 1;
+                Ok(n)
+            }
+            _ => self.error("expected a name"),
+        }
+    }
+
+    fn is_name(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Tok::Name(n)) if n == keyword)
+    }
+
+    fn skip_description(&mut self) {
+        if let Some(Tok::Str(_)) = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn type_ref(&mut self) -> Result<TypeRef, String> {
+        let base = if self.eat('[') {
+            let inner = self.type_ref()?;
+            self.expect(']')?;
+            TypeRef::List(Box::new(inner))
+        } else {
+            TypeRef::Named(self.name()?)
+        };
+        Ok(if self.eat('!') { TypeRef::NonNull(Box::new(base)) } else { base })
+    }
+
+    fn value(&mut self, constant: bool) -> Result<GqlValue, String> {
+        match self.next() {
+            Some(Tok::Punct('$')) if !constant => Ok(GqlValue::Var(self.name()?)),
+            Some(Tok::Int(n)) => Ok(GqlValue::Int(n)),
+            Some(Tok::Float(f)) => Ok(GqlValue::Float(f)),
+            Some(Tok::Str(s)) => Ok(GqlValue::Str(s)),
+            Some(Tok::Name(n)) => Ok(match n.as_str() {
+                "true" => GqlValue::Bool(true),
+                "false" => GqlValue::Bool(false),
+                "null" => GqlValue::Null,
+                _ => GqlValue::Enum(n),
+            }),
+            Some(Tok::Punct('[')) => {
+                let mut items = Vec::new();
+                while !self.eat(']') {
+                    items.push(self.value(constant)?);
+                }
+                Ok(GqlValue::List(items))
+            }
+            Some(Tok::Punct('{')) => {
+                let mut fields = Vec::new();
+                while !self.eat('}') {
+                    let name = self.name()?;
+                    self.expect(':')?;
+                    fields.push((name, self.value(constant)?));
+                }
+                Ok(GqlValue::Object(fields))
+            }
+            _ => {
+                self.pos -= 1;
+                self.error("expected a value")
+            }
+        }
+    }
+
+    fn arguments(&mut self, constant: bool) -> Result<Vec<(String, GqlValue)>, String> {
+        let mut args = Vec::new();
+        if self.eat('(') {
+            while !self.eat(')') {
+                let name = self.name()?;
+                self.expect(':')?;
+                args.push((name, self.value(constant)?));
+            }
+        }
+        Ok(args)
+    }
+
+    fn directives(&mut self, constant: bool) -> Result<Vec<Directive>, String> {
+        let mut directives = Vec::new();
+        while self.eat('@') {
+            let name = self.name()?;
+            let args = self.arguments(constant)?;
+            directives.push(Directive { name, args });
+        }
+        Ok(directives)
+    }
+}
+
+// ============================================================================
+// Schema
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum TypeRef {
+    Named(String),
+    List(Box<TypeRef>),
+    NonNull(Box<TypeRef>),
+}
+
+impl std::fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TypeRef::Named(n) => write!(f, "{}", n),
+            TypeRef::List(inner) => write!(f, "[{}]", inner),
+            TypeRef::NonNull(inner) => write!(f, "{}!", inner),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct InputValue {
+    name: String,
+    ty: TypeRef,
+    default: Option<GqlValue>,
+}
+
+#[derive(Debug)]
+struct FieldDef {
+    name: String,
+    args: Vec<InputValue>,
+    ty: TypeRef,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Object,
+    Interface,
+    Input,
+}
+
+#[derive(Debug)]
+struct ObjectDef {
+    kind: Kind,
+    fields: Vec<FieldDef>,
+    implements: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Schema {
+    objects: HashMap<String, ObjectDef>,
+    enums: HashMap<String, Vec<String>>,
+    unions: HashMap<String, Vec<String>>,
+    scalars: HashSet<String>,
+    query: String,
+    mutation: String,
+}
+
+const BUILTIN_SCALARS: [&str; 5] = ["Int", "Float", "String", "Boolean", "ID"];
+
+impl Schema {
+    pub fn parse(source: &str) -> Result<Schema, String> {
+        let mut p = Parser::new(source)?;
+        let mut schema = Schema { query: "Query".to_string(), mutation: "Mutation".to_string(), ..Default::default() };
+
+        while p.peek().is_some() {
+            p.skip_description();
+            let extend = p.is_name("extend");
+            if extend {
+                p.pos += 1;
+            }
+            let keyword = p.name()?;
+            match keyword.as_str() {
+                "type" | "interface" | "input" => {
+                    let kind = match keyword.as_str() {
+                        "type" => Kind::Object,
+                        "interface" => Kind::Interface,
+                        _ => Kind::Input,
+                    };
+                    let name = p.name()?;
+                    let mut implements = Vec::new();
+                    if p.is_name("implements") {
+                        p.pos += 1;
+                        p.eat('&');
+                        implements.push(p.name()?);
+                        while p.eat('&') {
+                            implements.push(p.name()?);
+                        }
+                    }
+                    p.directives(true)?;
+                    let mut fields = Vec::new();
+                    if p.eat('{') {
+                        while !p.eat('}') {
+                            p.skip_description();
+                            let field_name = p.name()?;
+                            let mut args = Vec::new();
+                            if p.eat('(') {
+                                while !p.eat(')') {
+                                    args.push(input_value(&mut p)?);
+                                }
+                            }
+                            p.expect(':')?;
+                            let ty = p.type_ref()?;
+                            if kind == Kind::Input && p.eat('=') {
+                                args.push(InputValue { name: String::new(), ty: ty.clone(), default: Some(p.value(true)?) });
+                            }
+                            p.directives(true)?;
+                            fields.push(FieldDef { name: field_name, args, ty });
+                        }
+                    }
+                    match schema.objects.get_mut(&name) {
+                        Some(existing) if extend => {
+                            existing.fields.extend(fields);
+                            existing.implements.extend(implements);
+                        }
+                        Some(_) => return Err(format!("Type '{}' is defined twice", name)),
+                        None => {
+                            schema.objects.insert(name, ObjectDef { kind, fields, implements });
+                        }
+                    }
+                }
+                "enum" => {
+                    let name = p.name()?;
+                    p.directives(true)?;
+                    p.expect('{')?;
+                    let mut values = Vec::new();
+                    while !p.eat('}') {
+                        p.skip_description();
+                        values.push(p.name()?);
+                        p.directives(true)?;
+                    }
+                    schema.enums.entry(name).or_default().extend(values);
+                }
+                "union" => {
+                    let name = p.name()?;
+                    p.directives(true)?;
+                    p.expect('=')?;
+                    p.eat('|');
+                    let mut members = vec![p.name()?];
+                    while p.eat('|') {
+                        members.push(p.name()?);
+                    }
+                    schema.unions.entry(name).or_default().extend(members);
+                }
+                "scalar" => {
+                    let name = p.name()?;
+                    p.directives(true)?;
+                    schema.scalars.insert(name);
+                }
+                "schema" => {
+                    p.directives(true)?;
+                    p.expect('{')?;
+                    while !p.eat('}') {
+                        let operation = p.name()?;
+                        p.expect(':')?;
+                        let type_name = p.name()?;
+                        match operation.as_str() {
+                            "query" => schema.query = type_name,
+                            "mutation" => schema.mutation = type_name,
+                            _ => return Err(format!("'{}' operations are not supported", operation)),
+                        }
+                    }
+                }
+                other => return Err(format!("line {}: unsupported definition '{}'", p.line(), other)),
+            }
+        }
+
+        if !schema.objects.contains_key(&schema.query) {
+            return Err(format!("Schema has no '{}' type", schema.query));
+        }
+        schema.check_types()?;
+        Ok(schema)
+    }
+
+    /// Every referenced type must be defined
+    fn check_types(&self) -> Result<(), String> {
+        for (type_name, object) in &self.objects {
+            for field in &object.fields {
+                let mut refs = vec![&field.ty];
+                refs.extend(field.args.iter().map(|a| &a.ty));
+                for ty in refs {
+                    let named = named_type(ty);
+                    if !self.is_known(named) {
+                        return Err(format!("{}.{} uses unknown type '{}'", type_name, field.name, named));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn is_known(&self, name: &str) -> bool {
+        BUILTIN_SCALARS.contains(&name)
+            || self.objects.contains_key(name)
+            || self.enums.contains_key(name)
+            || self.unions.contains_key(name)
+            || self.scalars.contains(name)
+    }
+
+    fn field(&self, type_name: &str, field: &str) -> Option<&FieldDef> {
+        self.objects.get(type_name)?.fields.iter().find(|f| f.name == field)
+    }
+
+    /// Whether an object of `type_name` matches a fragment condition
+    fn satisfies(&self, type_name: &str, condition: &str) -> bool {
+        type_name == condition
+            || self.objects.get(type_name).is_some_and(|o| o.implements.iter().any(|i| i == condition))
+            || self.unions.get(condition).is_some_and(|members| members.iter().any(|m| m == type_name))
+    }
+}
+
+fn input_value(p: &mut Parser) -> Result<InputValue, String> {
+    p.skip_description();
+    let name = p.name()?;
+    p.expect(':')?;
+    let ty = p.type_ref()?;
+    let default = if p.eat('=') { Some(p.value(true)?) } else { None };
+    p.directives(true)?;
+    Ok(InputValue { name, ty, default })
+}
+
+fn named_type(ty: &TypeRef) -> &str {
+    match ty {
+        TypeRef::Named(n) => n,
+        TypeRef::List(inner) | TypeRef::NonNull(inner) => named_type(inner),
+    }
+}
+
+// ============================================================================
+// Query documents
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum GqlValue {
+    Var(String),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+    Null,
+    Enum(String),
+    List(Vec<GqlValue>),
+    Object(Vec<(String, GqlValue)>),
+}
+
+#[derive(Debug)]
+struct Directive {
+    name: String,
+    args: Vec<(String, GqlValue)>,
+}
+
+#[derive(Debug)]
+enum Selection {
+    Field(FieldSelection),
+    Spread { name: String, directives: Vec<Directive> },
+    Inline { condition: Option<String>, directives: Vec<Directive>, selections: Vec<Selection> },
+}
+
+#[derive(Debug)]
+struct FieldSelection {
+    alias: Option<String>,
+    name: String,
+    args: Vec<(String, GqlValue)>,
+    directives: Vec<Directive>,
+    selections: Vec<Selection>,
+}
+
+#[derive(Debug)]
+struct Operation {
+    kind: String,
+    name: Option<String>,
+    variables: Vec<InputValue>,
+    selections: Vec<Selection>,
+}
+
+#[derive(Debug)]
+struct Fragment {
+    condition: String,
+    selections: Vec<Selection>,
+}
+
+struct Document {
+    operations: Vec<Operation>,
+    fragments: HashMap<String, Fragment>,
+}
+
+fn parse_document(source: &str) -> Result<Document, String> {
+    let mut p = Parser::new(source)?;
+    let mut document = Document { operations: Vec::new(), fragments: HashMap::new() };
+
+    while p.peek().is_some() {
+        if p.is('{') {
+            let selections = selection_set(&mut p)?;
+            document.operations.push(Operation { kind: "query".to_string(), name: None, variables: Vec::new(), selections });
+            continue;
+        }
+        let keyword = p.name()?;
+        match keyword.as_str() {
+            "query" | "mutation" | "subscription" => {
+                let name = match p.peek() {
+                    Some(Tok::Name(_)) => Some(p.name()?),
+                    _ => None,
+                };
+                let mut variables = Vec::new();
+                if p.eat('(') {
+                    while !p.eat(')') {
+                        p.expect('$')?;
+                        variables.push(input_value(&mut p)?);
+                    }
+                }
+                p.directives(false)?;
+                let selections = selection_set(&mut p)?;
+                document.operations.push(Operation { kind: keyword, name, variables, selections });
+            }
+            "fragment" => {
+                let name = p.name()?;
+                if !p.is_name("on") {
+                    return p.error("expected 'on'");
+                }
+                p.pos += 1;
+                let condition = p.name()?;
+                p.directives(false)?;
+                let selections = selection_set(&mut p)?;
+                document.fragments.insert(name, Fragment { condition, selections });
+            }
+            other => return Err(format!("line {}: unexpected '{}'", p.line(), other)),
+        }
+    }
+    Ok(document)
+}
+
+fn selection_set(p: &mut Parser) -> Result<Vec<Selection>, String> {
+    p.expect('{')?;
+    let mut selections = Vec::new();
+    while !p.eat('}') {
+        if p.peek().is_none() {
+            return p.error("expected '}'");
+        }
+        if matches!(p.peek(), Some(Tok::Spread)) {
+            p.pos += 1;
+            if p.is_name("on") {
+                p.pos += 1;
+                let condition = Some(p.name()?);
+                let directives = p.directives(false)?;
+                selections.push(Selection::Inline { condition, directives, selections: selection_set(p)? });
+            } else if matches!(p.peek(), Some(Tok::Name(_))) {
+                let name = p.name()?;
+                let directives = p.directives(false)?;
+                selections.push(Selection::Spread { name, directives });
+            } else {
+                let directives = p.directives(false)?;
+                selections.push(Selection::Inline { condition: None, directives, selections: selection_set(p)? });
+            }
+            continue;
+        }
+
+        let first = p.name()?;
+        let (alias, name) = if p.eat(':') { (Some(first), p.name()?) } else { (None, first) };
+        let args = p.arguments(false)?;
+        let directives = p.directives(false)?;
+        let selections_inner = if p.is('{') { selection_set(p)? } else { Vec::new() };
+        selections.push(Selection::Field(FieldSelection { alias, name, args, directives, selections: selections_inner }));
+    }
+    Ok(selections)
+}
+
+// ============================================================================
+// Execution
+// ============================================================================
+
+/// A GraphQL endpoint: schema plus resolvers, shared by every request
+pub struct Endpoint {
+    schema: Schema,
+    /// Type name -> field name -> resolver (a Spell or a plain value)
+    resolvers: HashMap<String, RelicMap>,
+}
+
+struct Execution<'a> {
+    endpoint: &'a Endpoint,
+    fragments: &'a HashMap<String, Fragment>,
+    variables: RelicMap,
+    context: Value,
+    errors: Vec<Json>,
+}
+
+impl Endpoint {
+    pub fn new(schema: &str, resolvers: &Value) -> Result<Endpoint, FlowError> {
+        let schema = Schema::parse(schema).map_err(|e| FlowError::runtime(&format!("Invalid GraphQL schema: {}", e), 0, 0))?;
+        let mut by_type = HashMap::new();
+        match resolvers {
+            Value::Relic(types) => {
+                for (type_name, fields) in types.iter() {
+                    let Value::Relic(fields) = fields else {
+                        return Err(FlowError::type_error(&format!("Resolvers for '{}' must be a Relic of fields", type_name), 0, 0));
+                    };
+                    for field in fields.keys() {
+                        if schema.field(type_name, field).is_none() {
+                            return Err(FlowError::runtime(&format!("Resolver '{}.{}' is not in the schema", type_name, field), 0, 0));
+                        }
+                    }
+                    by_type.insert(type_name.clone(), (**fields).clone());
+                }
+            }
+            _ => return Err(FlowError::type_error("web.graphql expects a Relic of resolvers", 0, 0)),
+        }
+        Ok(Endpoint { schema, resolvers: by_type })
+    }
+
+    /// Run one request. `variables` are already FlowLang values; `context` is
+    /// passed to every resolver. Returns the response body and whether the
+    /// request itself was invalid (HTTP 400).
+    pub async fn execute(
+        &self,
+        interp: &mut Interpreter,
+        query: &str,
+        operation_name: Option<&str>,
+        variables: RelicMap,
+        context: Value,
+    ) -> (Json, bool) {
+        let request_error = |message: String| (json!({ "errors": [{ "message": message }] }), true);
+
+        let document = match parse_document(query) {
+            Ok(document) => document,
+            Err(e) => return request_error(format!("Syntax Error: {}", e)),
+        };
+        let operation = match operation_name {
+            Some(name) => document.operations.iter().find(|o| o.name.as_deref() == Some(name)),
+            None if document.operations.len() == 1 => document.operations.first(),
+            None => return request_error("Must provide operationName when the document has several operations".to_string()),
+        };
+        let Some(operation) = operation else {
+            return request_error(format!("Unknown operation '{}'", operation_name.unwrap_or("")));
+        };
+        let root = match operation.kind.as_str() {
+            "query" => self.schema.query.clone(),
+            "mutation" if self.schema.objects.contains_key(&self.schema.mutation) => self.schema.mutation.clone(),
+            "mutation" => return request_error("Schema does not define mutations".to_string()),
+            _ => return request_error("Subscriptions are not supported".to_string()),
+        };
+
+        // Variables: given value, else the declared default; required ones must be present
+        let mut resolved = RelicMap::new();
+        for definition in &operation.variables {
+            match variables.get(&definition.name) {
+                Some(value) if !matches!(value, Value::Null) => {
+                    resolved.insert(definition.name.clone(), value.clone());
+                }
+                _ => match &definition.default {
+                    Some(default) => {
+                        resolved.insert(definition.name.clone(), literal(default, &RelicMap::new()));
+                    }
+                    None if matches!(definition.ty, TypeRef::NonNull(_)) => {
+                        return request_error(format!("Variable '${}' of required type '{}' was not provided", definition.name, definition.ty));
+                    }
+                    None => {}
+                },
+            }
+        }
+
+        let mut execution = Execution { endpoint: self, fragments: &document.fragments, variables: resolved, context, errors: Vec::new() };
+        let data = execution.execute_fields(interp, &root, &Value::Null, &operation.selections, &[]).await;
+
+        let mut response = Map::new();
+        if !execution.errors.is_empty() {
+            response.insert("errors".to_string(), Json::Array(execution.errors));
+        }
+        response.insert("data".to_string(), data.map(Json::Object).unwrap_or(Json::Null));
+        (Json::Object(response), false)
+    }
+}
+
+/// Error raised where a non-null field got null; the nearest nullable parent becomes null
+struct NullPropagation;
+
+impl<'a> Execution<'a> {
+    fn error(&mut self, message: String, path: &[Json]) {
+        self.errors.push(json!({ "message": message, "path": path }));
+    }
+
+    fn included(&self, directives: &[Directive]) -> bool {
+        directives.iter().all(|d| {
+            let condition = d.args.iter().find(|(n, _)| n == "if").map(|(_, v)| literal(v, &self.variables).is_truthy());
+            match d.name.as_str() {
+                "skip" => condition != Some(true),
+                "include" => condition != Some(false),
+                _ => true,
+            }
+        })
+    }
+
+    /// Fields to resolve on `type_name`, grouped by response key in query order
+    fn collect<'s>(&self, type_name: &str, selections: &'s [Selection], out: &mut Vec<(String, Vec<&'s FieldSelection>)>, seen: &mut HashSet<String>)
+    where
+        'a: 's,
+    {
+        for selection in selections {
+            match selection {
+                Selection::Field(field) => {
+                    if !self.included(&field.directives) {
+                        continue;
+                    }
+                    let key = field.alias.clone().unwrap_or_else(|| field.name.clone());
+                    match out.iter_mut().find(|(k, _)| *k == key) {
+                        Some((_, fields)) => fields.push(field),
+                        None => out.push((key, vec![field])),
+                    }
+                }
+                Selection::Spread { name, directives } => {
+                    if !self.included(directives) || !seen.insert(name.clone()) {
+                        continue;
+                    }
+                    if let Some(fragment) = self.fragments.get(name) {
+                        if self.endpoint.schema.satisfies(type_name, &fragment.condition) {
+                            self.collect(type_name, &fragment.selections, out, seen);
+                        }
+                    }
+                }
+                Selection::Inline { condition, directives, selections } => {
+                    let matches = condition.as_deref().is_none_or(|c| self.endpoint.schema.satisfies(type_name, c));
+                    if self.included(directives) && matches {
+                        self.collect(type_name, selections, out, seen);
+                    }
+                }
+            }
+        }
+    }
+
+    #[async_recursion::async_recursion]
+    async fn execute_fields(
+        &mut self,
+        interp: &mut Interpreter,
+        type_name: &str,
+        parent: &Value,
+        selections: &[Selection],
+        path: &[Json],
+    ) -> Result<Map<String, Json>, NullPropagation> {
+        let mut groups = Vec::new();
+        self.collect(type_name, selections, &mut groups, &mut HashSet::new());
+
+        let mut out = Map::new();
+        for (key, fields) in groups {
+            let field = fields[0];
+            let mut field_path = path.to_vec();
+            field_path.push(Json::String(key.clone()));
+
+            if field.name == "__typename" {
+                out.insert(key, Json::String(type_name.to_string()));
+                continue;
+            }
+            let Some(definition) = self.endpoint.schema.field(type_name, &field.name) else {
+                self.error(format!("Cannot query field '{}' on type '{}'", field.name, type_name), &field_path);
+                out.insert(key, Json::Null);
+                continue;
+            };
+
+            let value = match self.arguments(definition, field) {
+                Ok(args) => self.resolve(interp, type_name, definition, parent, args).await,
+                Err(message) => Err(message),
+            };
+            let value = match value {
+                Ok(value) => value,
+                Err(message) => {
+                    self.error(message, &field_path);
+                    Value::Null
+                }
+            };
+
+            // Other fields under the same response key add their sub-selections
+            let json = self.complete(interp, &definition.ty, value, &field.selections, &fields[1..], &field_path).await?;
+            out.insert(key, json);
+        }
+        Ok(out)
+    }
+
+    fn arguments(&self, definition: &FieldDef, field: &FieldSelection) -> Result<Value, String> {
+        let mut args = RelicMap::new();
+        for (name, _) in &field.args {
+            if !definition.args.iter().any(|a| &a.name == name) {
+                return Err(format!("Unknown argument '{}' on field '{}'", name, definition.name));
+            }
+        }
+        for arg in &definition.args {
+            let given = field.args.iter().find(|(n, _)| *n == arg.name).map(|(_, v)| literal(v, &self.variables));
+            let value = match given {
+                Some(Value::Null) | None => arg.default.as_ref().map(|d| literal(d, &self.variables)),
+                some => some,
+            };
+            match value {
+                Some(value) => {
+                    args.insert(arg.name.clone(), value);
+                }
+                None if matches!(arg.ty, TypeRef::NonNull(_)) => {
+                    return Err(format!("Argument '{}' of required type '{}' was not provided", arg.name, arg.ty));
+                }
+                // Present but empty, so resolvers can test `in Stance (args.name)`
+                None => {
+                    args.insert(arg.name.clone(), Value::Null);
+                }
+            }
+        }
+        Ok(Value::Relic(Arc::new(args)))
+    }
+
+    async fn resolve(&self, interp: &mut Interpreter, type_name: &str, definition: &FieldDef, parent: &Value, args: Value) -> Result<Value, String> {
+        let resolver = self.endpoint.resolvers.get(type_name).and_then(|fields| fields.get(&definition.name));
+        match resolver {
+            Some(spell) if spell.type_name() == "Spell" => {
+                let call_args = if matches!(parent, Value::Null) {
+                    vec![args, self.context.clone()]
+                } else {
+                    vec![parent.clone(), args, self.context.clone()]
+                };
+                interp.execute_function(spell.clone(), call_args).await.map_err(|e| error_message(&e))
+            }
+            Some(value) => Ok(value.clone()),
+            None => Ok(match parent {
+                Value::Relic(map) => map.get(&definition.name).cloned().unwrap_or(Value::Null),
+                _ => Value::Null,
+            }),
+        }
+    }
+
+    #[async_recursion::async_recursion]
+    async fn complete(
+        &mut self,
+        interp: &mut Interpreter,
+        ty: &TypeRef,
+        value: Value,
+        selections: &[Selection],
+        extra: &[&FieldSelection],
+        path: &[Json],
+    ) -> Result<Json, NullPropagation> {
+        match ty {
+            TypeRef::NonNull(inner) => {
+                let json = self.complete_nullable(interp, inner, value, selections, extra, path).await?;
+                if json.is_null() {
+                    // Only report it when the null isn't already explained by an error here
+                    if !self.errors.iter().any(|e| e["path"] == json!(path)) {
+                        self.error(format!("Cannot return null for non-nullable field of type '{}'", ty), path);
+                    }
+                    return Err(NullPropagation);
+                }
+                Ok(json)
+            }
+            _ => Ok(self.complete_nullable(interp, ty, value, selections, extra, path).await.unwrap_or(Json::Null)),
+        }
+    }
+
+    #[async_recursion::async_recursion]
+    async fn complete_nullable(
+        &mut self,
+        interp: &mut Interpreter,
+        ty: &TypeRef,
+        value: Value,
+        selections: &[Selection],
+        extra: &[&FieldSelection],
+        path: &[Json],
+    ) -> Result<Json, NullPropagation> {
+        if matches!(value, Value::Null) {
+            return Ok(Json::Null);
+        }
+        match ty {
+            TypeRef::NonNull(_) => self.complete(interp, ty, value, selections, extra, path).await,
+            TypeRef::List(inner) => {
+                let Value::Array(items) = value else {
+                    self.error(format!("Expected a Constellation for type '{}'", ty), path);
+                    return Ok(Json::Null);
+                };
+                let mut out = Vec::with_capacity(items.len());
+                for (i, item) in items.iter().enumerate() {
+                    let mut item_path = path.to_vec();
+                    item_path.push(json!(i));
+                    out.push(self.complete(interp, inner, item.clone(), selections, extra, &item_path).await?);
+                }
+                Ok(Json::Array(out))
+            }
+            TypeRef::Named(name) => {
+                let schema = &self.endpoint.schema;
+                if let Some(leaf) = self.leaf(name, &value) {
+                    return match leaf {
+                        Ok(json) => Ok(json),
+                        Err(message) => {
+                            self.error(message, path);
+                            Ok(Json::Null)
+                        }
+                    };
+                }
+
+                // Abstract types name their concrete type in __typename
+                let concrete = if schema.objects.get(name.as_str()).is_some_and(|o| o.kind == Kind::Object) {
+                    name.clone()
+                } else {
+                    match &value {
+                        Value::Relic(map) => match map.get("__typename") {
+                            Some(Value::String(t)) if schema.satisfies(t, name) => t.to_string(),
+                            _ => {
+                                self.error(format!("Abstract type '{}' must resolve to an object with a '__typename'", name), path);
+                                return Ok(Json::Null);
+                            }
+                        },
+                        _ => {
+                            self.error(format!("Expected a Relic for type '{}'", name), path);
+                            return Ok(Json::Null);
+                        }
+                    }
+                };
+
+                let mut merged: Vec<Selection> = Vec::new();
+                let selections: &[Selection] = if extra.is_empty() {
+                    selections
+                } else {
+                    // Same response key selected twice: resolve the union of their sub-fields
+                    for field in extra {
+                        merged.push(Selection::Inline { condition: None, directives: Vec::new(), selections: clone_selections(&field.selections) });
+                    }
+                    merged.push(Selection::Inline { condition: None, directives: Vec::new(), selections: clone_selections(selections) });
+                    merged.rotate_right(1);
+                    &merged
+                };
+                let fields = self.execute_fields(interp, &concrete, &value, selections, path).await?;
+                Ok(Json::Object(fields))
+            }
+        }
+    }
+
+    /// Scalars and enums; None for object types
+    fn leaf(&self, name: &str, value: &Value) -> Option<Result<Json, String>> {
+        let schema = &self.endpoint.schema;
+        Some(match name {
+            "Int" => match value {
+                Value::Number(n) if n.fract() == 0.0 && n.abs() <= i32::MAX as f64 => Ok(json!(*n as i64)),
+                other => Err(format!("Int cannot represent {}", other)),
+            },
+            "Float" => match value {
+                Value::Number(n) => Ok(json!(n)),
+                other => Err(format!("Float cannot represent {}", other)),
+            },
+            "Boolean" => match value {
+                Value::Boolean(b) => Ok(json!(b)),
+                other => Err(format!("Boolean cannot represent {}", other)),
+            },
+            "String" | "ID" => Ok(Json::String(value.to_string())),
+            _ if schema.enums.contains_key(name) => {
+                let text = value.to_string();
+                if schema.enums[name].contains(&text) {
+                    Ok(Json::String(text))
+                } else {
+                    Err(format!("Enum '{}' has no value '{}'", name, text))
+                }
+            }
+            _ if schema.scalars.contains(name) => Ok(to_json(value)),
+            _ => return None,
+        })
+    }
+}
+
+fn clone_selections(selections: &[Selection]) -> Vec<Selection> {
+    selections.iter().map(|s| match s {
+        Selection::Field(f) => Selection::Field(FieldSelection {
+            alias: f.alias.clone(),
+            name: f.name.clone(),
+            args: f.args.clone(),
+            directives: f.directives.iter().map(|d| Directive { name: d.name.clone(), args: d.args.clone() }).collect(),
+            selections: clone_selections(&f.selections),
+        }),
+        Selection::Spread { name, directives } => Selection::Spread {
+            name: name.clone(),
+            directives: directives.iter().map(|d| Directive { name: d.name.clone(), args: d.args.clone() }).collect(),
+        },
+        Selection::Inline { condition, directives, selections } => Selection::Inline {
+            condition: condition.clone(),
+            directives: directives.iter().map(|d| Directive { name: d.name.clone(), args: d.args.clone() }).collect(),
+            selections: clone_selections(selections),
+        },
+    }).collect()
+}
+
+/// A literal from the query, with variables substituted
+fn literal(value: &GqlValue, variables: &RelicMap) -> Value {
+    match value {
+        GqlValue::Var(name) => variables.get(name).cloned().unwrap_or(Value::Null),
+        GqlValue::Int(n) => Value::Number(*n as f64),
+        GqlValue::Float(f) => Value::Number(*f),
+        GqlValue::Str(s) | GqlValue::Enum(s) => Value::String(Arc::new(s.clone())),
+        GqlValue::Bool(b) => Value::Boolean(*b),
+        GqlValue::Null => Value::Null,
+        GqlValue::List(items) => Value::Array(Arc::new(items.iter().map(|v| literal(v, variables)).collect())),
+        GqlValue::Object(fields) => Value::Relic(Arc::new(fields.iter().map(|(k, v)| (k.clone(), literal(v, variables))).collect())),
+    }
+}
+
+fn to_json(value: &Value) -> Json {
+    serde_json::from_str(&crate::stdlib::json::value_to_json_string(value)).unwrap_or(Json::Null)
+}
+
+/// Resolver errors show their message, not the full FlowLang error banner
+fn error_message(error: &FlowError) -> String {
+    let text = error.to_string();
+    match text.split_once(" - ") {
+        Some((_, message)) => message.to_string(),
+        None => text,
+    }
+}
+
+/// The `(req, res)` handler returned by web.graphql. Accepts POST with a JSON body
+/// `{query, variables, operationName}` or GET with the same fields in the query string.
+pub fn handler(endpoint: Arc<Endpoint>) -> Value {
+    Value::InterpreterNativeFunction(InterpreterNativeFn::new(move |args, interp| -> InterpreterFuture<'_> {
+        let endpoint = endpoint.clone();
+        Box::pin(async move {
+            let request = args.first().cloned().unwrap_or(Value::Null);
+            let field = |name: &str| match &request {
+                Value::Relic(map) => map.get(name).map(|v| v.to_string()).unwrap_or_default(),
+                _ => String::new(),
+            };
+
+            let params: Json = if field("method") == "GET" {
+                let path = field("path");
+                let query = path.split_once('?').map(|(_, q)| q).unwrap_or("");
+                let mut params = Map::new();
+                if let Value::Relic(map) = crate::stdlib::url::parse_query_to_relic(query) {
+                    for (key, value) in map.iter() {
+                        let text = value.to_string();
+                        let parsed = if key == "variables" { serde_json::from_str(&text).unwrap_or(Json::Null) } else { Json::String(text) };
+                        params.insert(key.clone(), parsed);
+                    }
+                }
+                Json::Object(params)
+            } else {
+                match serde_json::from_str(&field("body")) {
+                    Ok(params) => params,
+                    Err(e) => return Ok(json_response(400, &json!({ "errors": [{ "message": format!("Body is not JSON: {}", e) }] }))),
+                }
+            };
+
+            let Some(query) = params.get("query").and_then(Json::as_str) else {
+                return Ok(json_response(400, &json!({ "errors": [{ "message": "Missing 'query'" }] })));
+            };
+            let mut variables = RelicMap::new();
+            if let Some(Json::Object(given)) = params.get("variables") {
+                for (name, value) in given {
+                    variables.insert(name.clone(), crate::stdlib::json::parse_json_value(&value.to_string())?);
+                }
+            }
+            let operation_name = params.get("operationName").and_then(Json::as_str);
+
+            let mut context = RelicMap::new();
+            context.insert("req".to_string(), request.clone());
+            let (body, invalid) = endpoint.execute(interp, query, operation_name, variables, Value::Relic(Arc::new(context))).await;
+            Ok(json_response(if invalid { 400 } else { 200 }, &body))
+        })
+    }))
+}
+
+fn json_response(status: u16, body: &Json) -> Value {
+    let mut map = RelicMap::new();
+    map.insert("status".to_string(), Value::Number(status as f64));
+    map.insert("body".to_string(), Value::String(Arc::new(body.to_string())));
+    map.insert("contentType".to_string(), Value::String(Arc::new("application/json".to_string())));
+    Value::Relic(Arc::new(map))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"
+        "A guild member"
+        type Member {
+            id: ID!
+            name: String!
+            rank: Rank
+            friends: [Member!]!
+        }
+        enum Rank { NOVICE MASTER }
+        type Query {
+            member(id: ID!): Member
+            members(limit: Int = 10): [Member!]!
+        }
+        type Mutation {
+            rename(id: ID!, name: String!): Member
+        }
+    "#;
+
+    const MEMBERS: &str = r#"
+let aria = {"id": "1", "name": "Aria", "rank": "MASTER", "friends": []}
+let kaito = {"id": "2", "name": "Kaito", "rank": "NOVICE", "friends": [aria]}
+cast Spell member(args) {
+    in Stance (args.id is~ "1") {
+        return aria
+    }
+    return kaito
+}
+cast Spell members(args) {
+    return [aria, kaito].slice(0, args.limit)
+}
+cast Spell broken(args) {
+    rupture Spirit "no such member"
+}
+"#;
+
+    /// Runs `query` against SCHEMA with the resolvers Relic written in FlowLang
+    fn run(query: &str, resolvers: &str) -> Json {
+        let program = crate::lexer::tokenize(MEMBERS).and_then(crate::parser::parse).unwrap();
+        let lookup = crate::lexer::tokenize(resolvers).and_then(crate::parser::parse).unwrap();
+        let Some(crate::parser::ast::Statement::Expression { expr, .. }) = lookup.statements.first() else {
+            panic!("resolvers must be an expression");
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(async {
+            let mut interp = Interpreter::new(Default::default());
+            interp.execute(program).await.unwrap();
+            let resolvers = interp.evaluate_expression(expr).await.unwrap();
+            let endpoint = Endpoint::new(SCHEMA, &resolvers).unwrap();
+            endpoint.execute(&mut interp, query, None, RelicMap::new(), Value::Null).await.0
+        })
+    }
+
+    #[test]
+    fn test_query_with_aliases_fragments_and_args() {
+        let resolvers = r#"{"Query": {"member": member, "members": members}}"#;
+        let data = run(
+            r#"query {
+                first: member(id: "1") { ...card }
+                members(limit: 1) { name __typename }
+                second: member(id: "2") { name friends { name } rank @skip(if: true) }
+            }
+            fragment card on Member { id name rank }"#,
+            resolvers,
+        );
+        assert_eq!(data, json!({
+            "data": {
+                "first": { "id": "1", "name": "Aria", "rank": "MASTER" },
+                "members": [{ "name": "Aria", "__typename": "Member" }],
+                "second": { "name": "Kaito", "friends": [{ "name": "Aria" }] }
+            }
+        }));
+    }
+
+    #[test]
+    fn test_errors_and_null_propagation() {
+        let resolvers = r#"{"Query": {"member": broken, "members": [{"id": "3"}]}}"#;
+        let data = run("{ member(id: \"9\") { name } members { id name } }", resolvers);
+        assert_eq!(data["data"], Json::Null);
+        let errors = data["errors"].as_array().unwrap();
+        assert_eq!(errors[0]["message"], "no such member");
+        assert_eq!(errors[0]["path"], json!(["member"]));
+        assert_eq!(errors[1]["path"], json!(["members", 0, "name"]));
+
+        let data = run("{ member { nope } }", resolvers);
+        assert!(data["errors"][0]["message"].as_str().unwrap().contains("required type 'ID!'"));
+        assert!(Schema::parse("type Query { a: Missing }").is_err());
+    }
+}
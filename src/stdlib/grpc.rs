@@ -0,0 +1,1757 @@
+//! std:grpc - gRPC clients and servers from .proto files
+//!
+//! `.proto` files are parsed at runtime, so there is no code generation step:
+//! - `grpc.load(path)` returns the package, with `client` and `serve` Spells
+//! - a client stub is a Relic with one async Spell per RPC method
+//! - `serve` answers unary RPCs with FlowLang handlers over cleartext HTTP/2
+//!
+//! Messages are Relics keyed by the field names in the .proto file. Fields missing
+//! on the wire decode to their proto3 defaults, enums decode to value names and
+//! `bytes` fields are base64 Silk. Streaming RPCs load but can't be called yet.
+
+use crate::error::FlowError;
+use crate::runtime::handle::HandleType;
+use crate::types::{AsyncContext, AsyncNativeFn, NativeFn, Value, RelicMap};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use hyper::body::HttpBody;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+pub fn load_grpc_module() -> Vec<(&'static str, Value)> {
+    let codes = STATUS_CODES
+        .iter()
+        .enumerate()
+        .map(|(code, name)| (name.to_string(), Value::Number(code as f64)))
+        .collect();
+    vec![
+        ("load", Value::NativeFunction(NativeFn::new(grpc_load))),
+        ("status", Value::NativeFunction(NativeFn::new(grpc_status))),
+        ("codes", Value::Relic(Arc::new(codes))),
+    ]
+}
+
+/// Canonical gRPC status names, indexed by code
+const STATUS_CODES: [&str; 17] = [
+    "OK",
+    "CANCELLED",
+    "UNKNOWN",
+    "INVALID_ARGUMENT",
+    "DEADLINE_EXCEEDED",
+    "NOT_FOUND",
+    "ALREADY_EXISTS",
+    "PERMISSION_DENIED",
+    "RESOURCE_EXHAUSTED",
+    "FAILED_PRECONDITION",
+    "ABORTED",
+    "OUT_OF_RANGE",
+    "UNIMPLEMENTED",
+    "INTERNAL",
+    "UNAVAILABLE",
+    "DATA_LOSS",
+    "UNAUTHENTICATED",
+];
+
+const UNKNOWN: u32 = 2;
+const INVALID_ARGUMENT: u32 = 3;
+const DEADLINE_EXCEEDED: u32 = 4;
+const UNIMPLEMENTED: u32 = 12;
+const INTERNAL: u32 = 13;
+const UNAVAILABLE: u32 = 14;
+
+fn status_name(code: u32) -> &'static str {
+    STATUS_CODES.get(code as usize).copied().unwrap_or("UNKNOWN")
+}
+
+fn status_error(code: u32, message: &str) -> FlowError {
+    FlowError::runtime(&format!("gRPC {} ({}): {}", status_name(code), code, message), 0, 0)
+}
+
+// ============================================================================
+// Descriptors
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scalar {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+}
+
+impl Scalar {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "double" => Scalar::Double,
+            "float" => Scalar::Float,
+            "int32" => Scalar::Int32,
+            "int64" => Scalar::Int64,
+            "uint32" => Scalar::Uint32,
+            "uint64" => Scalar::Uint64,
+            "sint32" => Scalar::Sint32,
+            "sint64" => Scalar::Sint64,
+            "fixed32" => Scalar::Fixed32,
+            "fixed64" => Scalar::Fixed64,
+            "sfixed32" => Scalar::Sfixed32,
+            "sfixed64" => Scalar::Sfixed64,
+            "bool" => Scalar::Bool,
+            "string" => Scalar::String,
+            "bytes" => Scalar::Bytes,
+            _ => return None,
+        })
+    }
+
+    fn wire_type(self) -> u8 {
+        match self {
+            Scalar::Double | Scalar::Fixed64 | Scalar::Sfixed64 => WIRE_FIXED64,
+            Scalar::Float | Scalar::Fixed32 | Scalar::Sfixed32 => WIRE_FIXED32,
+            Scalar::String | Scalar::Bytes => WIRE_LEN,
+            _ => WIRE_VARINT,
+        }
+    }
+
+    fn default_value(self) -> Value {
+        match self {
+            Scalar::Bool => Value::Boolean(false),
+            Scalar::String | Scalar::Bytes => Value::String(Arc::new(String::new())),
+            _ => Value::Number(0.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FieldType {
+    Scalar(Scalar),
+    Message(String),
+    Enum(String),
+    /// Type name as written, resolved once every import is loaded
+    Named(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Label {
+    /// proto3 implicit presence: defaults are not sent and decode as the default
+    Singular,
+    /// `optional`, proto2 fields and oneof members: missing decodes as Null
+    Optional,
+    Repeated,
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    name: String,
+    number: u32,
+    ty: FieldType,
+    label: Label,
+    /// Key and value types of a `map<K, V>` field, which is repeated on the wire
+    map: Option<(Scalar, FieldType)>,
+    packed: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Message {
+    fields: Vec<Field>,
+}
+
+#[derive(Debug, Clone)]
+struct EnumType {
+    values: Vec<(String, i32)>,
+}
+
+#[derive(Debug, Clone)]
+struct Method {
+    name: String,
+    input: String,
+    output: String,
+    client_streaming: bool,
+    server_streaming: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Service {
+    name: String,
+    methods: Vec<Method>,
+}
+
+/// Every message, enum and service from a .proto file and its imports, by full name
+#[derive(Debug, Default)]
+pub struct Proto {
+    package: String,
+    messages: HashMap<String, Message>,
+    enums: HashMap<String, EnumType>,
+    services: Vec<Service>,
+    /// Scope each message's field types are resolved against
+    scopes: HashMap<String, String>,
+}
+
+impl Proto {
+    /// Parses `path` and everything it imports
+    pub fn load(path: &Path) -> Result<Self, FlowError> {
+        let mut proto = Proto::default();
+        let mut seen = HashSet::new();
+        proto.load_file(path, &mut seen, true)?;
+        proto.resolve()?;
+        Ok(proto)
+    }
+
+    /// Parses a single self-contained file from source
+    #[cfg(test)]
+    fn parse(source: &str, file: &str) -> Result<Self, FlowError> {
+        let mut proto = Proto::default();
+        let mut parser = Parser::new(source, file)?;
+        let file_package = parser.parse_file(&mut proto)?;
+        proto.package = file_package;
+        if !parser.imports.is_empty() {
+            return Err(FlowError::runtime(&format!("{}: imports need grpc.load with a file path", file), 0, 0));
+        }
+        proto.resolve()?;
+        Ok(proto)
+    }
+
+    fn load_file(&mut self, path: &Path, seen: &mut HashSet<PathBuf>, root: bool) -> Result<(), FlowError> {
+        let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if !seen.insert(key) {
+            return Ok(());
+        }
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            FlowError::runtime(&format!("Failed to read proto file '{}': {}", path.display(), e), 0, 0)
+        })?;
+        let mut parser = Parser::new(&source, &path.display().to_string())?;
+        let package = parser.parse_file(self)?;
+        if root {
+            self.package = package;
+        }
+
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        for import in std::mem::take(&mut parser.imports) {
+            let candidates = [dir.join(&import), PathBuf::from(&import)];
+            match candidates.iter().find(|p| p.exists()) {
+                Some(found) => self.load_file(found, seen, false)?,
+                None if import.starts_with("google/protobuf/") => self.load_well_known(&import)?,
+                None => return Err(FlowError::runtime(
+                    &format!("{}: import \"{}\" not found", path.display(), import),
+                    0, 0,
+                )),
+            }
+        }
+        Ok(())
+    }
+
+    /// The common google.protobuf types, for protos that import them without
+    /// shipping a copy
+    fn load_well_known(&mut self, import: &str) -> Result<(), FlowError> {
+        let source = match import {
+            "google/protobuf/empty.proto" => "message Empty {}",
+            "google/protobuf/timestamp.proto" => "message Timestamp { int64 seconds = 1; int32 nanos = 2; }",
+            "google/protobuf/duration.proto" => "message Duration { int64 seconds = 1; int32 nanos = 2; }",
+            "google/protobuf/wrappers.proto" => {
+                "message DoubleValue { double value = 1; } message FloatValue { float value = 1; }
+                 message Int64Value { int64 value = 1; } message UInt64Value { uint64 value = 1; }
+                 message Int32Value { int32 value = 1; } message UInt32Value { uint32 value = 1; }
+                 message BoolValue { bool value = 1; } message StringValue { string value = 1; }
+                 message BytesValue { bytes value = 1; }"
+            }
+            _ => return Err(FlowError::runtime(&format!("import \"{}\" not found", import), 0, 0)),
+        };
+        let source = format!("syntax = \"proto3\"; package google.protobuf; {}", source);
+        Parser::new(&source, import)?.parse_file(self)?;
+        Ok(())
+    }
+
+    /// Turns every `Named` field type into a message or enum reference
+    fn resolve(&mut self) -> Result<(), FlowError> {
+        let mut resolved = HashMap::new();
+        for (name, message) in &self.messages {
+            let scope = &self.scopes[name];
+            let mut fields = message.fields.clone();
+            for field in &mut fields {
+                field.ty = self.resolve_type(&field.ty, scope, name)?;
+                if let Some((key, value)) = &field.map {
+                    field.map = Some((*key, self.resolve_type(value, scope, name)?));
+                }
+            }
+            resolved.insert(name.clone(), fields);
+        }
+        for (name, fields) in resolved {
+            self.messages.get_mut(&name).unwrap().fields = fields;
+        }
+
+        let mut services = std::mem::take(&mut self.services);
+        for service in &mut services {
+            let scope = service.name.rsplit_once('.').map(|(p, _)| p).unwrap_or("").to_string();
+            for method in &mut service.methods {
+                for ty in [&mut method.input, &mut method.output] {
+                    match self.lookup(ty, &scope) {
+                        Some(full) if self.messages.contains_key(&full) => *ty = full,
+                        _ => return Err(FlowError::runtime(
+                            &format!("{}.{}: unknown message type '{}'", service.name, method.name, ty),
+                            0, 0,
+                        )),
+                    }
+                }
+            }
+        }
+        self.services = services;
+        Ok(())
+    }
+
+    fn resolve_type(&self, ty: &FieldType, scope: &str, owner: &str) -> Result<FieldType, FlowError> {
+        let name = match ty {
+            FieldType::Named(name) => name,
+            other => return Ok(other.clone()),
+        };
+        match self.lookup(name, scope) {
+            Some(full) if self.messages.contains_key(&full) => Ok(FieldType::Message(full)),
+            Some(full) => Ok(FieldType::Enum(full)),
+            None => Err(FlowError::runtime(&format!("{}: unknown type '{}'", owner, name), 0, 0)),
+        }
+    }
+
+    /// Protobuf scoping: look in the innermost scope first, then each enclosing one
+    fn lookup(&self, name: &str, scope: &str) -> Option<String> {
+        let known = |full: &str| self.messages.contains_key(full) || self.enums.contains_key(full);
+        if let Some(absolute) = name.strip_prefix('.') {
+            return known(absolute).then(|| absolute.to_string());
+        }
+        let mut scope = scope.to_string();
+        loop {
+            let full = if scope.is_empty() { name.to_string() } else { format!("{}.{}", scope, name) };
+            if known(&full) {
+                return Some(full);
+            }
+            if scope.is_empty() {
+                return None;
+            }
+            scope = scope.rsplit_once('.').map(|(outer, _)| outer.to_string()).unwrap_or_default();
+        }
+    }
+
+    /// Finds a service by full name, or by its short name when that is unambiguous
+    fn service(&self, name: &str) -> Result<&Service, FlowError> {
+        if let Some(service) = self.services.iter().find(|s| s.name == name) {
+            return Ok(service);
+        }
+        let suffix = format!(".{}", name);
+        let matches: Vec<_> = self.services.iter().filter(|s| s.name.ends_with(&suffix)).collect();
+        match matches.as_slice() {
+            [service] => Ok(service),
+            _ => {
+                let known: Vec<_> = self.services.iter().map(|s| s.name.as_str()).collect();
+                Err(FlowError::runtime(
+                    &format!("Unknown gRPC service '{}' (known: {})", name, known.join(", ")),
+                    0, 0,
+                ))
+            }
+        }
+    }
+}
+
+// ============================================================================
+// .proto parser
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Identifiers, including dotted names like `google.protobuf.Empty`
+    Ident(String),
+    Int(String),
+    Str(String),
+    Symbol(char),
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    file: String,
+    proto2: bool,
+    imports: Vec<String>,
+}
+
+fn tokenize(source: &str, file: &str) -> Result<Vec<(Token, usize)>, FlowError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' || c == '\'' {
+            let mut text = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    i += 1;
+                }
+                text.push(chars[i]);
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err(FlowError::runtime(&format!("{}:{}: unterminated string", file, line), 0, 0));
+            }
+            i += 1;
+            tokens.push((Token::Str(text), line));
+        } else if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())) {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            tokens.push((Token::Int(chars[start..i].iter().collect()), line));
+        } else if c.is_alphabetic() || c == '_' || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect()), line));
+        } else {
+            tokens.push((Token::Symbol(c), line));
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+impl Parser {
+    fn new(source: &str, file: &str) -> Result<Self, FlowError> {
+        Ok(Parser {
+            tokens: tokenize(source, file)?,
+            pos: 0,
+            file: file.to_string(),
+            proto2: false,
+            imports: Vec::new(),
+        })
+    }
+
+    fn error(&self, message: &str) -> FlowError {
+        let line = self.tokens.get(self.pos).or(self.tokens.last()).map(|(_, l)| *l).unwrap_or(1);
+        FlowError::runtime(&format!("{}:{}: {}", self.file, line, message), 0, 0)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(t, _)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), FlowError> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", symbol)))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, FlowError> {
+        match self.next() {
+            Some(Token::Ident(name)) => Ok(name),
+            _ => {
+                self.pos -= 1;
+                Err(self.error("expected a name"))
+            }
+        }
+    }
+
+    fn keyword(&mut self, word: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(w)) if w == word) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn integer(&mut self) -> Result<i64, FlowError> {
+        let text = match self.next() {
+            Some(Token::Int(text)) => text,
+            Some(Token::Ident(word)) if word == "max" => return Ok(536_870_911),
+            _ => {
+                self.pos -= 1;
+                return Err(self.error("expected a number"));
+            }
+        };
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.as_str()),
+        };
+        let parsed = if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+            i64::from_str_radix(hex, 16)
+        } else if digits.len() > 1 && digits.starts_with('0') {
+            i64::from_str_radix(&digits[1..], 8)
+        } else {
+            digits.parse()
+        };
+        let value = parsed.map_err(|_| self.error(&format!("invalid number '{}'", text)))?;
+        Ok(if negative { -value } else { value })
+    }
+
+    /// Skips an option, reserved or extensions statement, including `{ ... }` values
+    fn skip_statement(&mut self) -> Result<(), FlowError> {
+        let mut depth = 0;
+        loop {
+            match self.next() {
+                None => return Err(self.error("unexpected end of file")),
+                Some(Token::Symbol('{')) | Some(Token::Symbol('[')) => depth += 1,
+                Some(Token::Symbol('}')) | Some(Token::Symbol(']')) => depth -= 1,
+                Some(Token::Symbol(';')) if depth == 0 => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+
+    /// Skips a `{ ... }` block such as `extend Foo { ... }`
+    fn skip_block(&mut self) -> Result<(), FlowError> {
+        while !self.eat('{') {
+            if self.next().is_none() {
+                return Err(self.error("unexpected end of file"));
+            }
+        }
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next() {
+                None => return Err(self.error("unexpected end of file")),
+                Some(Token::Symbol('{')) => depth += 1,
+                Some(Token::Symbol('}')) => depth -= 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses one file into `proto`, returning its package
+    fn parse_file(&mut self, proto: &mut Proto) -> Result<String, FlowError> {
+        let mut package = String::new();
+        while let Some(token) = self.next() {
+            match token {
+                Token::Symbol(';') => {}
+                Token::Ident(word) => match word.as_str() {
+                    "syntax" => {
+                        self.expect('=')?;
+                        match self.next() {
+                            Some(Token::Str(syntax)) => self.proto2 = syntax == "proto2",
+                            _ => return Err(self.error("expected a syntax name")),
+                        }
+                        self.expect(';')?;
+                    }
+                    "edition" => return Err(self.error("protobuf editions are not supported; use syntax = \"proto3\"")),
+                    "package" => {
+                        package = self.ident()?;
+                        self.expect(';')?;
+                    }
+                    "import" => {
+                        let _ = self.keyword("public") || self.keyword("weak");
+                        match self.next() {
+                            Some(Token::Str(path)) => self.imports.push(path),
+                            _ => return Err(self.error("expected an import path")),
+                        }
+                        self.expect(';')?;
+                    }
+                    "option" => self.skip_statement()?,
+                    "message" => self.parse_message(proto, &package)?,
+                    "enum" => self.parse_enum(proto, &package)?,
+                    "service" => self.parse_service(proto, &package)?,
+                    "extend" => self.skip_block()?,
+                    other => return Err(self.error(&format!("unexpected '{}'", other))),
+                },
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.error("unexpected token"));
+                }
+            }
+        }
+        Ok(package)
+    }
+
+    fn parse_message(&mut self, proto: &mut Proto, scope: &str) -> Result<(), FlowError> {
+        let name = qualify(scope, &self.ident()?);
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        while !self.eat('}') {
+            if self.eat(';') {
+                continue;
+            }
+            if self.keyword("option") || self.keyword("reserved") || self.keyword("extensions") {
+                self.skip_statement()?;
+            } else if self.keyword("message") {
+                self.parse_message(proto, &name)?;
+            } else if self.keyword("enum") {
+                self.parse_enum(proto, &name)?;
+            } else if self.keyword("extend") {
+                self.skip_block()?;
+            } else if self.keyword("oneof") {
+                self.ident()?;
+                self.expect('{')?;
+                while !self.eat('}') {
+                    if self.eat(';') {
+                        continue;
+                    }
+                    if self.keyword("option") {
+                        self.skip_statement()?;
+                        continue;
+                    }
+                    fields.push(self.parse_field(Label::Optional)?);
+                }
+            } else {
+                let label = if self.keyword("repeated") {
+                    Label::Repeated
+                } else if self.keyword("optional") || self.keyword("required") || self.proto2 {
+                    Label::Optional
+                } else {
+                    Label::Singular
+                };
+                fields.push(self.parse_field(label)?);
+            }
+        }
+        proto.scopes.insert(name.clone(), name.clone());
+        proto.messages.insert(name, Message { fields });
+        Ok(())
+    }
+
+    fn parse_field(&mut self, label: Label) -> Result<Field, FlowError> {
+        let type_name = self.ident()?;
+        let (ty, map, label) = if type_name == "map" {
+            self.expect('<')?;
+            let key = Scalar::from_name(&self.ident()?).ok_or_else(|| self.error("map keys must be a scalar type"))?;
+            self.expect(',')?;
+            let value = self.ident()?;
+            self.expect('>')?;
+            let value = Scalar::from_name(&value).map(FieldType::Scalar).unwrap_or(FieldType::Named(value));
+            (FieldType::Scalar(key), Some((key, value)), Label::Repeated)
+        } else {
+            let ty = Scalar::from_name(&type_name).map(FieldType::Scalar).unwrap_or(FieldType::Named(type_name));
+            (ty, None, label)
+        };
+        let name = self.ident()?;
+        self.expect('=')?;
+        let number = self.integer()?;
+        if !(1..=536_870_911).contains(&number) {
+            return Err(self.error(&format!("field number {} out of range", number)));
+        }
+
+        // proto3 packs repeated numbers by default, proto2 only when asked
+        let mut packed = !self.proto2;
+        if self.eat('[') {
+            loop {
+                let option = self.ident()?;
+                self.expect('=')?;
+                let value = self.next();
+                if option == "packed" {
+                    packed = value == Some(Token::Ident("true".to_string()));
+                }
+                if self.eat(']') {
+                    break;
+                }
+                self.expect(',')?;
+            }
+        }
+        self.expect(';')?;
+        Ok(Field { name, number: number as u32, ty, label, map, packed })
+    }
+
+    fn parse_enum(&mut self, proto: &mut Proto, scope: &str) -> Result<(), FlowError> {
+        let name = qualify(scope, &self.ident()?);
+        self.expect('{')?;
+        let mut values = Vec::new();
+        while !self.eat('}') {
+            if self.eat(';') {
+                continue;
+            }
+            if self.keyword("option") || self.keyword("reserved") {
+                self.skip_statement()?;
+                continue;
+            }
+            let value_name = self.ident()?;
+            self.expect('=')?;
+            let number = self.integer()?;
+            if self.eat('[') {
+                while !self.eat(']') {
+                    if self.next().is_none() {
+                        return Err(self.error("unexpected end of file"));
+                    }
+                }
+            }
+            self.expect(';')?;
+            values.push((value_name, number as i32));
+        }
+        proto.enums.insert(name, EnumType { values });
+        Ok(())
+    }
+
+    fn parse_service(&mut self, proto: &mut Proto, scope: &str) -> Result<(), FlowError> {
+        let name = qualify(scope, &self.ident()?);
+        self.expect('{')?;
+        let mut methods = Vec::new();
+        while !self.eat('}') {
+            if self.eat(';') {
+                continue;
+            }
+            if self.keyword("option") {
+                self.skip_statement()?;
+                continue;
+            }
+            if !self.keyword("rpc") {
+                return Err(self.error("expected 'rpc'"));
+            }
+            let method_name = self.ident()?;
+            self.expect('(')?;
+            let client_streaming = self.keyword("stream");
+            let input = self.ident()?;
+            self.expect(')')?;
+            if !self.keyword("returns") {
+                return Err(self.error("expected 'returns'"));
+            }
+            self.expect('(')?;
+            let server_streaming = self.keyword("stream");
+            let output = self.ident()?;
+            self.expect(')')?;
+            if self.eat('{') {
+                while !self.eat('}') {
+                    if self.keyword("option") {
+                        self.skip_statement()?;
+                    } else if !self.eat(';') {
+                        return Err(self.error("expected an option"));
+                    }
+                }
+            } else {
+                self.expect(';')?;
+            }
+            methods.push(Method { name: method_name, input, output, client_streaming, server_streaming });
+        }
+        proto.services.push(Service { name, methods });
+        Ok(())
+    }
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", scope, name)
+    }
+}
+
+// ============================================================================
+// Wire format
+// ============================================================================
+
+const WIRE_VARINT: u8 = 0;
+const WIRE_FIXED64: u8 = 1;
+const WIRE_LEN: u8 = 2;
+const WIRE_FIXED32: u8 = 5;
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn put_tag(out: &mut Vec<u8>, number: u32, wire_type: u8) {
+    put_varint(out, ((number as u64) << 3) | wire_type as u64);
+}
+
+fn put_len(out: &mut Vec<u8>, number: u32, bytes: &[u8]) {
+    put_tag(out, number, WIRE_LEN);
+    put_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes, pos: 0 }
+    }
+
+    fn done(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn truncated() -> FlowError {
+        FlowError::runtime("Malformed protobuf message: unexpected end of data", 0, 0)
+    }
+
+    fn varint(&mut self) -> Result<u64, FlowError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.bytes.get(self.pos).ok_or_else(Self::truncated)?;
+            self.pos += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte < 0x80 {
+                return Ok(value);
+            }
+        }
+        Err(FlowError::runtime("Malformed protobuf message: varint too long", 0, 0))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], FlowError> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.bytes.len()).ok_or_else(Self::truncated)?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn fixed32(&mut self) -> Result<u32, FlowError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn fixed64(&mut self) -> Result<u64, FlowError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn len_delimited(&mut self) -> Result<&'a [u8], FlowError> {
+        let len = self.varint()? as usize;
+        self.take(len)
+    }
+
+    fn skip(&mut self, wire_type: u8) -> Result<(), FlowError> {
+        match wire_type {
+            WIRE_VARINT => self.varint().map(|_| ()),
+            WIRE_FIXED64 => self.take(8).map(|_| ()),
+            WIRE_LEN => self.len_delimited().map(|_| ()),
+            WIRE_FIXED32 => self.take(4).map(|_| ()),
+            other => Err(FlowError::runtime(&format!("Malformed protobuf message: wire type {}", other), 0, 0)),
+        }
+    }
+}
+
+impl Proto {
+    /// Encodes a Relic as the message `name`
+    pub fn encode(&self, name: &str, value: &Value) -> Result<Vec<u8>, FlowError> {
+        let message = self.messages.get(name).ok_or_else(|| {
+            FlowError::runtime(&format!("Unknown message type '{}'", name), 0, 0)
+        })?;
+        let map = match value {
+            Value::Relic(map) => map.clone(),
+            Value::Null => Arc::new(RelicMap::new()),
+            other => return Err(FlowError::type_error(
+                &format!("{} expects a Relic, got {}", name, other.type_name()),
+                0, 0,
+            )),
+        };
+        if let Some(unknown) = map.keys().find(|k| !message.fields.iter().any(|f| &f.name == *k)) {
+            return Err(FlowError::runtime(&format!("Unknown field '{}' for message {}", unknown, name), 0, 0));
+        }
+
+        let mut out = Vec::new();
+        for field in &message.fields {
+            let value = match map.get(&field.name) {
+                None | Some(Value::Null) => continue,
+                Some(value) => value,
+            };
+            let context = || format!("{}.{}", name, field.name);
+            if let Some((key_type, value_type)) = &field.map {
+                let entries = match value {
+                    Value::Relic(entries) => entries,
+                    _ => return Err(FlowError::type_error(&format!("{} expects a Relic", context()), 0, 0)),
+                };
+                let mut keys: Vec<_> = entries.keys().collect();
+                keys.sort();
+                for key in keys {
+                    let key_value = match key_type {
+                        Scalar::String => Value::String(Arc::new(key.clone())),
+                        Scalar::Bool => Value::Boolean(key == "true"),
+                        _ => Value::Number(key.parse().map_err(|_| {
+                            FlowError::type_error(&format!("{} key '{}' is not a number", context(), key), 0, 0)
+                        })?),
+                    };
+                    let mut entry = Vec::new();
+                    self.encode_value(&mut entry, 1, &FieldType::Scalar(*key_type), &key_value, &context())?;
+                    self.encode_value(&mut entry, 2, value_type, &entries[key], &context())?;
+                    put_len(&mut out, field.number, &entry);
+                }
+            } else if field.label == Label::Repeated {
+                let items = match value {
+                    Value::Array(items) => items,
+                    _ => return Err(FlowError::type_error(&format!("{} expects a Constellation", context()), 0, 0)),
+                };
+                let packable = match &field.ty {
+                    FieldType::Scalar(s) => s.wire_type() != WIRE_LEN,
+                    FieldType::Enum(_) => true,
+                    _ => false,
+                };
+                if packable && field.packed {
+                    if items.is_empty() {
+                        continue;
+                    }
+                    let mut packed = Vec::new();
+                    for item in items.iter() {
+                        self.encode_raw(&mut packed, &field.ty, item, &context())?;
+                    }
+                    put_len(&mut out, field.number, &packed);
+                } else {
+                    for item in items.iter() {
+                        self.encode_value(&mut out, field.number, &field.ty, item, &context())?;
+                    }
+                }
+            } else {
+                if field.label == Label::Singular && self.is_default(&field.ty, value) {
+                    continue;
+                }
+                self.encode_value(&mut out, field.number, &field.ty, value, &context())?;
+            }
+        }
+        Ok(out)
+    }
+
+    fn is_default(&self, ty: &FieldType, value: &Value) -> bool {
+        match (ty, value) {
+            (FieldType::Enum(name), value) => self.enum_number(name, value, "").ok() == Some(0),
+            (FieldType::Scalar(_), Value::Number(n)) => *n == 0.0,
+            (FieldType::Scalar(_), Value::String(s)) => s.is_empty(),
+            (FieldType::Scalar(_), Value::Boolean(b)) => !b,
+            _ => false,
+        }
+    }
+
+    fn encode_value(&self, out: &mut Vec<u8>, number: u32, ty: &FieldType, value: &Value, context: &str) -> Result<(), FlowError> {
+        match ty {
+            FieldType::Message(name) => put_len(out, number, &self.encode(name, value)?),
+            FieldType::Scalar(s) if s.wire_type() == WIRE_LEN => {
+                let mut bytes = Vec::new();
+                self.encode_raw(&mut bytes, ty, value, context)?;
+                put_len(out, number, &bytes);
+            }
+            FieldType::Scalar(s) => {
+                put_tag(out, number, s.wire_type());
+                self.encode_raw(out, ty, value, context)?;
+            }
+            FieldType::Enum(_) => {
+                put_tag(out, number, WIRE_VARINT);
+                self.encode_raw(out, ty, value, context)?;
+            }
+            FieldType::Named(name) => return Err(FlowError::runtime(&format!("Unresolved type '{}'", name), 0, 0)),
+        }
+        Ok(())
+    }
+
+    /// Writes a scalar or enum value without its tag
+    fn encode_raw(&self, out: &mut Vec<u8>, ty: &FieldType, value: &Value, context: &str) -> Result<(), FlowError> {
+        let scalar = match ty {
+            FieldType::Enum(name) => {
+                put_varint(out, self.enum_number(name, value, context)? as i64 as u64);
+                return Ok(());
+            }
+            FieldType::Scalar(scalar) => *scalar,
+            _ => unreachable!("messages are length-delimited"),
+        };
+        let expected = |what: &str| FlowError::type_error(
+            &format!("{} expects {}, got {}", context, what, value.type_name()),
+            0, 0,
+        );
+        match scalar {
+            Scalar::String => match value {
+                Value::String(s) => out.extend_from_slice(s.as_bytes()),
+                _ => return Err(expected("a Silk")),
+            },
+            Scalar::Bytes => match value {
+                Value::String(s) => out.extend(STANDARD.decode(s.as_bytes()).map_err(|_| {
+                    FlowError::type_error(&format!("{} expects base64 Silk for bytes", context), 0, 0)
+                })?),
+                _ => return Err(expected("base64 Silk")),
+            },
+            Scalar::Bool => match value {
+                Value::Boolean(b) => put_varint(out, *b as u64),
+                _ => return Err(expected("a Pulse")),
+            },
+            _ => {
+                // 64-bit integers may arrive as Silk, as in the protobuf JSON mapping
+                let n = match value {
+                    Value::Number(n) => *n,
+                    Value::String(s) => s.parse::<f64>().map_err(|_| expected("an Ember"))?,
+                    _ => return Err(expected("an Ember")),
+                };
+                match scalar {
+                    Scalar::Double => out.extend_from_slice(&n.to_le_bytes()),
+                    Scalar::Float => out.extend_from_slice(&(n as f32).to_le_bytes()),
+                    Scalar::Int32 | Scalar::Int64 => put_varint(out, n as i64 as u64),
+                    Scalar::Uint32 | Scalar::Uint64 => put_varint(out, n as u64),
+                    Scalar::Sint32 | Scalar::Sint64 => {
+                        let n = n as i64;
+                        put_varint(out, ((n << 1) ^ (n >> 63)) as u64)
+                    }
+                    Scalar::Fixed32 => out.extend_from_slice(&(n as u32).to_le_bytes()),
+                    Scalar::Sfixed32 => out.extend_from_slice(&(n as i32).to_le_bytes()),
+                    Scalar::Fixed64 => out.extend_from_slice(&(n as u64).to_le_bytes()),
+                    Scalar::Sfixed64 => out.extend_from_slice(&(n as i64).to_le_bytes()),
+                    Scalar::String | Scalar::Bytes | Scalar::Bool => unreachable!(),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn enum_number(&self, name: &str, value: &Value, context: &str) -> Result<i32, FlowError> {
+        match value {
+            Value::Number(n) => Ok(*n as i32),
+            Value::String(s) => self.enums[name]
+                .values
+                .iter()
+                .find(|(value_name, _)| value_name == s.as_str())
+                .map(|(_, number)| *number)
+                .ok_or_else(|| FlowError::runtime(&format!("{}: '{}' is not a value of {}", context, s, name), 0, 0)),
+            other => Err(FlowError::type_error(
+                &format!("{} expects an enum name or Ember, got {}", context, other.type_name()),
+                0, 0,
+            )),
+        }
+    }
+
+    /// Decodes the message `name` into a Relic with every field present
+    pub fn decode(&self, name: &str, bytes: &[u8]) -> Result<Value, FlowError> {
+        let message = self.messages.get(name).ok_or_else(|| {
+            FlowError::runtime(&format!("Unknown message type '{}'", name), 0, 0)
+        })?;
+        let mut map = RelicMap::new();
+        let mut repeated: HashMap<u32, Vec<Value>> = HashMap::new();
+        let mut entries: HashMap<u32, RelicMap> = HashMap::new();
+
+        let mut reader = Reader::new(bytes);
+        while !reader.done() {
+            let key = reader.varint()?;
+            let (number, wire_type) = ((key >> 3) as u32, (key & 7) as u8);
+            let field = match message.fields.iter().find(|f| f.number == number) {
+                Some(field) => field,
+                None => {
+                    reader.skip(wire_type)?;
+                    continue;
+                }
+            };
+            if let Some((key_type, value_type)) = &field.map {
+                let mut entry = Reader::new(reader.len_delimited()?);
+                let mut key = Scalar::default_value(*key_type);
+                let mut value = self.default_for(value_type, Label::Singular);
+                while !entry.done() {
+                    let tag = entry.varint()?;
+                    match tag >> 3 {
+                        1 => key = self.decode_value(&mut entry, &FieldType::Scalar(*key_type))?,
+                        2 => value = self.decode_value(&mut entry, value_type)?,
+                        _ => entry.skip((tag & 7) as u8)?,
+                    }
+                }
+                entries.entry(number).or_default().insert(value_to_key(&key), value);
+            } else if field.label == Label::Repeated {
+                let items = repeated.entry(number).or_default();
+                let scalar_wire = match &field.ty {
+                    FieldType::Scalar(s) => Some(s.wire_type()),
+                    FieldType::Enum(_) => Some(WIRE_VARINT),
+                    _ => None,
+                };
+                // Packed and unpacked encodings must both be accepted
+                if wire_type == WIRE_LEN && scalar_wire.is_some_and(|w| w != WIRE_LEN) {
+                    let mut packed = Reader::new(reader.len_delimited()?);
+                    while !packed.done() {
+                        items.push(self.decode_value(&mut packed, &field.ty)?);
+                    }
+                } else {
+                    items.push(self.decode_value(&mut reader, &field.ty)?);
+                }
+            } else {
+                map.insert(field.name.clone(), self.decode_value(&mut reader, &field.ty)?);
+            }
+        }
+
+        for field in &message.fields {
+            if field.map.is_some() {
+                let entries = entries.remove(&field.number).unwrap_or_default();
+                map.insert(field.name.clone(), Value::Relic(Arc::new(entries)));
+            } else if field.label == Label::Repeated {
+                let items = repeated.remove(&field.number).unwrap_or_default();
+                map.insert(field.name.clone(), Value::Array(Arc::new(items)));
+            } else if !map.contains_key(&field.name) {
+                map.insert(field.name.clone(), self.default_for(&field.ty, field.label));
+            }
+        }
+        Ok(Value::Relic(Arc::new(map)))
+    }
+
+    fn default_for(&self, ty: &FieldType, label: Label) -> Value {
+        match (ty, label) {
+            (_, Label::Optional) | (FieldType::Message(_), _) | (FieldType::Named(_), _) => Value::Null,
+            (FieldType::Scalar(s), _) => s.default_value(),
+            (FieldType::Enum(name), _) => self.enum_value(name, 0),
+        }
+    }
+
+    fn enum_value(&self, name: &str, number: i32) -> Value {
+        match self.enums[name].values.iter().find(|(_, n)| *n == number) {
+            Some((value_name, _)) => Value::String(Arc::new(value_name.clone())),
+            // Values added by a newer peer stay visible as numbers
+            None => Value::Number(number as f64),
+        }
+    }
+
+    fn decode_value(&self, reader: &mut Reader, ty: &FieldType) -> Result<Value, FlowError> {
+        let scalar = match ty {
+            FieldType::Message(name) => return self.decode(name, reader.len_delimited()?),
+            FieldType::Enum(name) => return Ok(self.enum_value(name, reader.varint()? as i32)),
+            FieldType::Scalar(scalar) => *scalar,
+            FieldType::Named(name) => return Err(FlowError::runtime(&format!("Unresolved type '{}'", name), 0, 0)),
+        };
+        Ok(match scalar {
+            Scalar::String => Value::String(Arc::new(String::from_utf8_lossy(reader.len_delimited()?).into_owned())),
+            Scalar::Bytes => Value::String(Arc::new(STANDARD.encode(reader.len_delimited()?))),
+            Scalar::Bool => Value::Boolean(reader.varint()? != 0),
+            Scalar::Double => Value::Number(f64::from_bits(reader.fixed64()?)),
+            Scalar::Float => Value::Number(f32::from_bits(reader.fixed32()?) as f64),
+            Scalar::Int32 => Value::Number(reader.varint()? as i32 as f64),
+            Scalar::Int64 => Value::Number(reader.varint()? as i64 as f64),
+            Scalar::Uint32 => Value::Number(reader.varint()? as u32 as f64),
+            Scalar::Uint64 => Value::Number(reader.varint()? as f64),
+            Scalar::Sint32 | Scalar::Sint64 => {
+                let n = reader.varint()?;
+                Value::Number(((n >> 1) as i64 ^ -((n & 1) as i64)) as f64)
+            }
+            Scalar::Fixed32 => Value::Number(reader.fixed32()? as f64),
+            Scalar::Sfixed32 => Value::Number(reader.fixed32()? as i32 as f64),
+            Scalar::Fixed64 => Value::Number(reader.fixed64()? as f64),
+            Scalar::Sfixed64 => Value::Number(reader.fixed64()? as i64 as f64),
+        })
+    }
+}
+
+fn value_to_key(value: &Value) -> String {
+    match value {
+        Value::Number(n) => format!("{}", n),
+        other => other.to_string(),
+    }
+}
+
+/// Wraps a message in gRPC's length-prefixed framing (uncompressed)
+fn frame(payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + 5);
+    out.push(0);
+    out.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    out.extend_from_slice(payload);
+    out
+}
+
+/// Returns the single message in a unary request or response body
+fn unframe(body: &[u8]) -> Result<&[u8], (u32, String)> {
+    if body.len() < 5 {
+        return Err((INTERNAL, "missing gRPC message frame".to_string()));
+    }
+    if body[0] != 0 {
+        return Err((UNIMPLEMENTED, "compressed messages are not supported".to_string()));
+    }
+    let len = u32::from_be_bytes([body[1], body[2], body[3], body[4]]) as usize;
+    body.get(5..5 + len).ok_or((INTERNAL, "truncated gRPC message frame".to_string()))
+}
+
+/// grpc-message is percent-encoded on the wire
+fn encode_grpc_message(message: &str) -> String {
+    message
+        .bytes()
+        .map(|b| if (0x20..0x7f).contains(&b) && b != b'%' { (b as char).to_string() } else { format!("%{:02X}", b) })
+        .collect()
+}
+
+fn decode_grpc_message(message: &str) -> String {
+    let bytes = message.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(b) = std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// ============================================================================
+// Module functions
+// ============================================================================
+
+/// grpc.load(path) -> Relic with package, services, messages, client, serve, encode, decode
+fn grpc_load(args: Vec<Value>) -> Result<Value, FlowError> {
+    let path = match args.first() {
+        Some(Value::String(s)) => PathBuf::from(s.as_str()),
+        _ => return Err(FlowError::type_error("grpc.load expects a .proto file path", 0, 0)),
+    };
+    Ok(package_value(Arc::new(Proto::load(&path)?)))
+}
+
+fn package_value(proto: Arc<Proto>) -> Value {
+    let mut map = RelicMap::new();
+    map.insert("package".to_string(), Value::String(Arc::new(proto.package.clone())));
+
+    let services = proto
+        .services
+        .iter()
+        .map(|s| {
+            let methods = s.methods.iter().map(|m| Value::String(Arc::new(m.name.clone()))).collect();
+            (s.name.clone(), Value::Array(Arc::new(methods)))
+        })
+        .collect();
+    map.insert("services".to_string(), Value::Relic(Arc::new(services)));
+
+    let mut messages: Vec<_> = proto.messages.keys().cloned().collect();
+    messages.sort();
+    let messages = messages.into_iter().map(|m| Value::String(Arc::new(m))).collect();
+    map.insert("messages".to_string(), Value::Array(Arc::new(messages)));
+
+    let client_proto = proto.clone();
+    map.insert("client".to_string(), Value::NativeFunction(NativeFn::new(move |args| {
+        grpc_client(&client_proto, args)
+    })));
+    let serve_proto = proto.clone();
+    map.insert("serve".to_string(), Value::AsyncNativeFunction(AsyncNativeFn::new(move |args, ctx| {
+        grpc_serve(serve_proto.clone(), args, ctx)
+    })));
+    let encode_proto = proto.clone();
+    map.insert("encode".to_string(), Value::NativeFunction(NativeFn::new(move |args| {
+        match (args.first(), args.get(1)) {
+            (Some(Value::String(name)), Some(value)) => {
+                let bytes = encode_proto.encode(&qualify_message(&encode_proto, name), value)?;
+                Ok(Value::String(Arc::new(STANDARD.encode(bytes))))
+            }
+            _ => Err(FlowError::type_error("encode expects a message name and a Relic", 0, 0)),
+        }
+    })));
+    map.insert("decode".to_string(), Value::NativeFunction(NativeFn::new(move |args| {
+        match (args.first(), args.get(1)) {
+            (Some(Value::String(name)), Some(Value::String(data))) => {
+                let bytes = STANDARD.decode(data.as_bytes())
+                    .map_err(|_| FlowError::type_error("decode expects base64 Silk", 0, 0))?;
+                proto.decode(&qualify_message(&proto, name), &bytes)
+            }
+            _ => Err(FlowError::type_error("decode expects a message name and base64 Silk", 0, 0)),
+        }
+    })));
+    Value::Relic(Arc::new(map))
+}
+
+/// Message names may leave out the file's own package
+fn qualify_message(proto: &Proto, name: &str) -> String {
+    proto.lookup(name, &proto.package).unwrap_or_else(|| name.to_string())
+}
+
+/// grpc.status(code, message?) -> Relic a serve handler can return to fail the call
+fn grpc_status(args: Vec<Value>) -> Result<Value, FlowError> {
+    let code = match args.first() {
+        Some(Value::Number(n)) if (0.0..17.0).contains(n) => *n as u32,
+        Some(Value::String(name)) => STATUS_CODES
+            .iter()
+            .position(|c| *c == name.as_str())
+            .ok_or_else(|| FlowError::runtime(&format!("Unknown gRPC status '{}'", name), 0, 0))? as u32,
+        _ => return Err(FlowError::type_error("grpc.status expects a status name or code", 0, 0)),
+    };
+    let message = args.get(1).map(|m| m.to_string()).unwrap_or_default();
+    let mut map = RelicMap::new();
+    map.insert("__grpcStatus".to_string(), Value::Number(code as f64));
+    map.insert("message".to_string(), Value::String(Arc::new(message)));
+    Ok(Value::Relic(Arc::new(map)))
+}
+
+// ============================================================================
+// Client
+// ============================================================================
+
+/// proto.client(service, address, options?) -> Relic of async Spells
+/// Options: timeout (ms, sent as grpc-timeout) and metadata (headers for every call).
+fn grpc_client(proto: &Arc<Proto>, args: Vec<Value>) -> Result<Value, FlowError> {
+    let (service, address) = match (args.first(), args.get(1)) {
+        (Some(Value::String(service)), Some(Value::String(address))) => (proto.service(service)?, address),
+        _ => return Err(FlowError::type_error("client expects a service name and an address", 0, 0)),
+    };
+    let base = if address.starts_with("https://") {
+        return Err(FlowError::runtime("grpc clients only speak cleartext HTTP/2 (h2c); use http:// or host:port", 0, 0));
+    } else if address.starts_with("http://") {
+        address.trim_end_matches('/').to_string()
+    } else {
+        format!("http://{}", address.trim_end_matches('/'))
+    };
+
+    let (timeout, metadata) = match args.get(2) {
+        Some(Value::Relic(options)) => {
+            let timeout = match options.get("timeout") {
+                Some(Value::Number(ms)) => Some(Duration::from_millis(*ms as u64)),
+                _ => None,
+            };
+            (timeout, metadata_pairs(options.get("metadata")))
+        }
+        Some(_) => return Err(FlowError::type_error("client options must be a Relic", 0, 0)),
+        None => (None, Vec::new()),
+    };
+
+    let client = hyper::Client::builder().http2_only(true).build_http::<hyper::Body>();
+    let mut stub = RelicMap::new();
+    for method in &service.methods {
+        let call = Arc::new(Call {
+            proto: proto.clone(),
+            client: client.clone(),
+            method: method.clone(),
+            uri: format!("{}/{}/{}", base, service.name, method.name),
+            timeout,
+            metadata: metadata.clone(),
+        });
+        stub.insert(method.name.clone(), Value::AsyncNativeFunction(AsyncNativeFn::new(move |args, _ctx| {
+            let call = call.clone();
+            async move { call.invoke(args).await }
+        })));
+    }
+    Ok(Value::Relic(Arc::new(stub)))
+}
+
+fn metadata_pairs(value: Option<&Value>) -> Vec<(String, String)> {
+    match value {
+        Some(Value::Relic(map)) => {
+            let mut pairs: Vec<_> = map.iter().map(|(k, v)| (k.to_lowercase(), v.to_string())).collect();
+            pairs.sort();
+            pairs
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// One RPC method bound to a connection
+struct Call {
+    proto: Arc<Proto>,
+    client: hyper::Client<hyper::client::HttpConnector>,
+    method: Method,
+    uri: String,
+    timeout: Option<Duration>,
+    metadata: Vec<(String, String)>,
+}
+
+impl Call {
+    /// stub.Method(request?, metadata?) -> response Relic
+    async fn invoke(&self, args: Vec<Value>) -> Result<Value, FlowError> {
+        if self.method.client_streaming || self.method.server_streaming {
+            return Err(status_error(UNIMPLEMENTED, &format!("{} is a streaming RPC", self.method.name)));
+        }
+        let request = args.first().cloned().unwrap_or(Value::Null);
+        let payload = self.proto.encode(&self.method.input, &request)?;
+        let mut metadata = self.metadata.clone();
+        metadata.extend(metadata_pairs(args.get(1)));
+
+        match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.send(payload, metadata))
+                .await
+                .unwrap_or_else(|_| Err(status_error(
+                    DEADLINE_EXCEEDED,
+                    &format!("no response within {}ms", timeout.as_millis()),
+                ))),
+            None => self.send(payload, metadata).await,
+        }
+    }
+
+    async fn send(&self, payload: Vec<u8>, metadata: Vec<(String, String)>) -> Result<Value, FlowError> {
+        let mut builder = hyper::Request::post(&self.uri)
+            .header("content-type", "application/grpc")
+            .header("te", "trailers")
+            .header("user-agent", "flowlang-grpc");
+        if let Some(timeout) = self.timeout {
+            builder = builder.header("grpc-timeout", format!("{}m", timeout.as_millis()));
+        }
+        for (name, value) in &metadata {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let request = builder
+            .body(hyper::Body::from(frame(&payload)))
+            .map_err(|e| FlowError::runtime(&format!("Invalid gRPC request: {}", e), 0, 0))?;
+
+        let response = self.client.request(request).await
+            .map_err(|e| status_error(UNAVAILABLE, &e.to_string()))?;
+        if response.status() != hyper::StatusCode::OK {
+            return Err(status_error(UNAVAILABLE, &format!("server answered HTTP {}", response.status())));
+        }
+
+        let headers = response.headers().clone();
+        let mut body = response.into_body();
+        let mut data = Vec::new();
+        while let Some(chunk) = body.data().await {
+            data.extend_from_slice(&chunk.map_err(|e| status_error(UNAVAILABLE, &e.to_string()))?);
+        }
+        let trailers = body.trailers().await
+            .map_err(|e| status_error(UNAVAILABLE, &e.to_string()))?
+            .unwrap_or_default();
+
+        // Errors may come back as trailers-only responses, with the status in the headers
+        let status_of = |map: &hyper::HeaderMap| map.get("grpc-status").and_then(|v| v.to_str().ok()?.parse::<u32>().ok());
+        let code = status_of(&trailers).or_else(|| status_of(&headers)).unwrap_or(UNKNOWN);
+        if code != 0 {
+            let message = trailers.get("grpc-message").or_else(|| headers.get("grpc-message"))
+                .and_then(|v| v.to_str().ok())
+                .map(decode_grpc_message)
+                .unwrap_or_default();
+            return Err(status_error(code, &message));
+        }
+        let message = unframe(&data).map_err(|(code, message)| status_error(code, &message))?;
+        self.proto.decode(&self.method.output, message)
+    }
+}
+
+// ============================================================================
+// Server
+// ============================================================================
+
+struct Route {
+    method: Method,
+    handler: Value,
+}
+
+/// proto.serve(port, {"pkg.Service": {"Method": handler}}) -> Handle
+/// Handlers are called as `handler(request, call)` and return the response Relic,
+/// or grpc.status(...) to fail the call.
+async fn grpc_serve(proto: Arc<Proto>, args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    let (port, services) = match (args.first(), args.get(1)) {
+        (Some(Value::Number(n)), Some(Value::Relic(services))) => (*n as u16, services),
+        _ => return Err(FlowError::type_error(
+            "serve expects a port and a Relic of service handlers",
+            0, 0,
+        )),
+    };
+
+    let mut routes = HashMap::new();
+    for (service_name, handlers) in services.iter() {
+        let service = proto.service(service_name)?;
+        let handlers = match handlers {
+            Value::Relic(handlers) => handlers,
+            _ => return Err(FlowError::type_error(
+                &format!("serve expects a Relic of method handlers for {}", service_name),
+                0, 0,
+            )),
+        };
+        for (method_name, handler) in handlers.iter() {
+            let method = service.methods.iter().find(|m| &m.name == method_name).ok_or_else(|| {
+                FlowError::runtime(&format!("{} has no method '{}'", service.name, method_name), 0, 0)
+            })?;
+            if method.client_streaming || method.server_streaming {
+                return Err(FlowError::runtime(
+                    &format!("{}.{} is a streaming RPC; only unary methods can be served", service.name, method.name),
+                    0, 0,
+                ));
+            }
+            if handler.type_name() != "Spell" {
+                return Err(FlowError::type_error(&format!("Handler for {} must be a Spell", method_name), 0, 0));
+            }
+            routes.insert(
+                format!("/{}/{}", service.name, method.name),
+                Route { method: method.clone(), handler: handler.clone() },
+            );
+        }
+    }
+
+    let addr = std::net::SocketAddr::from(([0, 0, 0, 0], port));
+    let builder = hyper::Server::try_bind(&addr).map_err(|e| {
+        FlowError::runtime(&format!("grpc.serve failed to bind port {}: {}", port, e), 0, 0)
+    })?;
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let handle_id = ctx.runtime.register_handle(HandleType::HttpServer {
+        port,
+        shutdown_tx: Some(shutdown_tx),
+    }).await;
+
+    let routes = Arc::new(routes);
+    let runtime = ctx.runtime.clone();
+    let make_service = hyper::service::make_service_fn(move |conn: &hyper::server::conn::AddrStream| {
+        let peer = conn.remote_addr().to_string();
+        let routes = routes.clone();
+        let proto = proto.clone();
+        let runtime = runtime.clone();
+        async move {
+            Ok::<_, std::convert::Infallible>(hyper::service::service_fn(move |request| {
+                let dispatch = Dispatch {
+                    routes: routes.clone(),
+                    proto: proto.clone(),
+                    runtime: runtime.clone(),
+                    peer: peer.clone(),
+                };
+                async move { Ok::<_, std::convert::Infallible>(dispatch.handle(request).await) }
+            }))
+        }
+    });
+
+    let runtime = ctx.runtime.clone();
+    tokio::spawn(async move {
+        let server = builder
+            .http2_only(true)
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            });
+        if let Err(e) = server.await {
+            eprintln!("grpc.serve on port {} stopped: {}", port, e);
+        }
+        runtime.unregister_handle(handle_id).await;
+    });
+
+    Ok(Value::Handle(handle_id))
+}
+
+struct Dispatch {
+    routes: Arc<HashMap<String, Route>>,
+    proto: Arc<Proto>,
+    runtime: Arc<crate::runtime::Runtime>,
+    peer: String,
+}
+
+impl Dispatch {
+    async fn handle(self, request: hyper::Request<hyper::Body>) -> hyper::Response<hyper::Body> {
+        let metrics = self.runtime.metrics();
+        metrics.request_started();
+        let started = Instant::now();
+        let response = match self.call(request).await {
+            Ok(payload) => {
+                let (mut sender, body) = hyper::Body::channel();
+                tokio::spawn(async move {
+                    let mut trailers = hyper::HeaderMap::new();
+                    trailers.insert("grpc-status", hyper::header::HeaderValue::from_static("0"));
+                    if sender.send_data(frame(&payload).into()).await.is_ok() {
+                        let _ = sender.send_trailers(trailers).await;
+                    }
+                });
+                grpc_response(body)
+            }
+            Err((code, message)) => {
+                let mut response = grpc_response(hyper::Body::empty());
+                let headers = response.headers_mut();
+                headers.insert("grpc-status", code.into());
+                if let Ok(value) = hyper::header::HeaderValue::from_str(&encode_grpc_message(&message)) {
+                    headers.insert("grpc-message", value);
+                }
+                response
+            }
+        };
+        metrics.request_finished("POST", response.status().as_u16(), started.elapsed());
+        response
+    }
+
+    async fn call(&self, request: hyper::Request<hyper::Body>) -> Result<Vec<u8>, (u32, String)> {
+        let path = request.uri().path().to_string();
+        let route = self.routes.get(&path).ok_or((UNIMPLEMENTED, format!("unknown method {}", path)))?;
+
+        let mut metadata = RelicMap::new();
+        for (name, value) in request.headers() {
+            if let Ok(value) = value.to_str() {
+                metadata.insert(name.as_str().to_string(), Value::String(Arc::new(value.to_string())));
+            }
+        }
+        let body = hyper::body::to_bytes(request.into_body()).await
+            .map_err(|e| (INTERNAL, e.to_string()))?;
+        let message = unframe(&body)?;
+        let decoded = self.proto.decode(&route.method.input, message)
+            .map_err(|e| (INVALID_ARGUMENT, e.to_string()))?;
+
+        let mut call = RelicMap::new();
+        call.insert("method".to_string(), Value::String(Arc::new(path)));
+        call.insert("metadata".to_string(), Value::Relic(Arc::new(metadata)));
+        call.insert("peer".to_string(), Value::String(Arc::new(self.peer.clone())));
+
+        let (response_tx, response_rx) = oneshot::channel();
+        self.runtime.web_callback_sender()
+            .send(crate::runtime::WebCallbackRequest {
+                callback: route.handler.clone(),
+                args: vec![decoded, Value::Relic(Arc::new(call))],
+                response_tx,
+            })
+            .await
+            .map_err(|_| (UNAVAILABLE, "server is overloaded".to_string()))?;
+        let result = response_rx.await.map_err(|_| (UNAVAILABLE, "server is overloaded".to_string()))?;
+
+        match &result {
+            Value::Relic(map) => {
+                if let Some(Value::Number(code)) = map.get("__grpcStatus") {
+                    let message = map.get("message").map(|m| m.to_string()).unwrap_or_default();
+                    return Err((*code as u32, message));
+                }
+            }
+            // The event loop reports handler errors as an "Error: ..." Silk
+            Value::String(error) => {
+                return Err((UNKNOWN, error.strip_prefix("Error: ").unwrap_or(error).to_string()));
+            }
+            _ => {}
+        }
+        self.proto.encode(&route.method.output, &result).map_err(|e| (INTERNAL, e.to_string()))
+    }
+}
+
+fn grpc_response(body: hyper::Body) -> hyper::Response<hyper::Body> {
+    let mut response = hyper::Response::new(body);
+    response.headers_mut().insert("content-type", hyper::header::HeaderValue::from_static("application/grpc"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROTO: &str = r#"
+        syntax = "proto3";
+        package guild.v1;
+
+        import "google/protobuf/empty.proto";
+        option go_package = "example.com/guild";
+
+        // A guild member
+        message Member {
+            string name = 1;
+            int32 level = 2;
+            Rank rank = 3;
+            repeated sint64 scores = 4;
+            map<string, Member> allies = 5;
+            optional bytes sigil = 6;
+            Stats stats = 7;
+            oneof origin {
+                string village = 8;
+                int32 realm = 9;
+            }
+
+            enum Rank {
+                NOVICE = 0;
+                MASTER = 1;
+            }
+            message Stats { double power = 1; bool awakened = 2; }
+        }
+
+        message GetMemberRequest { string name = 1; }
+
+        service Guild {
+            rpc GetMember (GetMemberRequest) returns (Member) {
+                option (google.api.http) = { get: "/v1/members/{name}" };
+            }
+            rpc Watch (GetMemberRequest) returns (stream Member);
+        }
+    "#;
+
+    fn relic(pairs: Vec<(&str, Value)>) -> Value {
+        Value::Relic(Arc::new(pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect()))
+    }
+
+    fn text(s: &str) -> Value {
+        Value::String(Arc::new(s.to_string()))
+    }
+
+    fn json(value: &Value) -> String {
+        crate::stdlib::json::value_to_json_string(value)
+    }
+
+    #[test]
+    fn test_parse_proto() {
+        let proto = Proto::parse(&PROTO.replace("import \"google/protobuf/empty.proto\";", ""), "guild.proto").unwrap();
+        assert_eq!(proto.package, "guild.v1");
+        let service = proto.service("Guild").unwrap();
+        assert_eq!(service.name, "guild.v1.Guild");
+        assert_eq!(service.methods[0].input, "guild.v1.GetMemberRequest");
+        assert!(service.methods[1].server_streaming);
+
+        let member = &proto.messages["guild.v1.Member"];
+        let rank = member.fields.iter().find(|f| f.name == "rank").unwrap();
+        assert_eq!(rank.ty, FieldType::Enum("guild.v1.Member.Rank".to_string()));
+        let village = member.fields.iter().find(|f| f.name == "village").unwrap();
+        assert_eq!(village.label, Label::Optional);
+
+        let err = Proto::parse("syntax = \"proto3\";\nmessage A { Missing b = 1; }", "bad.proto").unwrap_err();
+        assert!(err.to_string().contains("unknown type 'Missing'"), "{}", err);
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let proto = Proto::parse(&PROTO.replace("import \"google/protobuf/empty.proto\";", ""), "guild.proto").unwrap();
+        let ally = relic(vec![("name", text("Kaito")), ("level", Value::Number(3.0))]);
+        let member = relic(vec![
+            ("name", text("Aria")),
+            ("level", Value::Number(-7.0)),
+            ("rank", text("MASTER")),
+            ("scores", Value::Array(Arc::new(vec![Value::Number(-1.0), Value::Number(300.0)]))),
+            ("allies", relic(vec![("kaito", ally)])),
+            ("sigil", text("AAEC")),
+            ("village", text("Leaf")),
+        ]);
+
+        let bytes = proto.encode("guild.v1.Member", &member).unwrap();
+        // Field 1 (name) comes first, as a length-delimited string
+        assert_eq!(&bytes[..6], &[0x0a, 4, b'A', b'r', b'i', b'a']);
+
+        let decoded = match proto.decode("guild.v1.Member", &bytes).unwrap() {
+            Value::Relic(map) => map,
+            other => panic!("expected a Relic, got {:?}", other),
+        };
+        assert_eq!(json(&decoded["name"]), json(&text("Aria")));
+        assert_eq!(json(&decoded["level"]), json(&Value::Number(-7.0)));
+        assert_eq!(json(&decoded["rank"]), json(&text("MASTER")));
+        assert_eq!(json(&decoded["scores"]), json(&Value::Array(Arc::new(vec![Value::Number(-1.0), Value::Number(300.0)]))));
+        assert_eq!(json(&decoded["sigil"]), json(&text("AAEC")));
+        assert_eq!(json(&decoded["village"]), json(&text("Leaf")));
+        assert_eq!(json(&decoded["realm"]), json(&Value::Null));
+        assert_eq!(json(&decoded["stats"]), json(&Value::Null));
+        match &decoded["allies"] {
+            Value::Relic(allies) => match &allies["kaito"] {
+                Value::Relic(kaito) => {
+                    assert_eq!(json(&kaito["level"]), json(&Value::Number(3.0)));
+                    assert_eq!(json(&kaito["rank"]), json(&text("NOVICE")));
+                    assert_eq!(json(&kaito["scores"]), json(&Value::Array(Arc::new(vec![]))));
+                }
+                other => panic!("expected a Relic, got {:?}", other),
+            },
+            other => panic!("expected a Relic, got {:?}", other),
+        }
+
+        let err = proto.encode("guild.v1.Member", &relic(vec![("nmae", text("x"))])).unwrap_err();
+        assert!(err.to_string().contains("Unknown field 'nmae'"), "{}", err);
+    }
+
+    #[test]
+    fn test_framing_and_status_messages() {
+        let framed = frame(&[8, 1]);
+        assert_eq!(framed, vec![0, 0, 0, 0, 2, 8, 1]);
+        assert_eq!(unframe(&framed).unwrap(), &[8, 1]);
+        assert!(unframe(&[0, 0, 0, 0, 9, 1]).is_err());
+        assert_eq!(encode_grpc_message("50% done ✨"), "50%25 done %E2%9C%A8");
+        assert_eq!(decode_grpc_message("50%25 done %E2%9C%A8"), "50% done ✨");
+    }
+}
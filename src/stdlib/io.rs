@@ -1,15 +1,17 @@
-pub fn whisper(message: &str) {
-    println!("{}", message);
+use crate::interpreter::output::{OutputSink, Stream};
+
+pub fn whisper(out: &dyn OutputSink, message: &str) {
+    out.write_line(Stream::Stdout, message);
 }
 
-pub fn shout(message: &str) {
-    println!("{}", message);
+pub fn shout(out: &dyn OutputSink, message: &str) {
+    out.write_line(Stream::Stdout, message);
 }
 
-pub fn roar(message: &str) {
-    println!("{}", message);
+pub fn roar(out: &dyn OutputSink, message: &str) {
+    out.write_line(Stream::Stderr, message);
 }
 
-pub fn chant(message: &str) {
-    println!("{}", message);
+pub fn chant(out: &dyn OutputSink, message: &str) {
+    out.write_line(Stream::Stdout, message);
 }
@@ -0,0 +1,239 @@
+//! std:ipc - messages between FlowLang processes on the same machine
+//!
+//! `ipc.listen(name, handler)` listens on a Unix socket named after `name`, and
+//! `ipc.send(name, value)` from any other script connects to it, packs the value with
+//! std:serial, and returns what the handler returned. Handlers run like web handlers:
+//! on the event loop of the listening process, with its globals read-only.
+//!
+//! Each message is a 4-byte big-endian length followed by packed bytes. A reply
+//! starts with a status byte: 0 and a packed value, or 1 and the handler's error as
+//! UTF-8 text.
+
+// Windows has no Unix sockets yet, so only the socket naming is used there
+#![cfg_attr(not(unix), allow(dead_code, unused_imports))]
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::error::FlowError;
+use crate::types::{AsyncContext, AsyncNativeFn, NativeFn, RelicMap, Value};
+
+/// How long `send` waits for a reply when no `timeout` is given
+const DEFAULT_TIMEOUT_MS: f64 = 30_000.0;
+/// Messages larger than this are refused rather than buffered
+const MAX_MESSAGE: usize = 64 * 1024 * 1024;
+
+const REPLY_OK: u8 = 0;
+const REPLY_ERROR: u8 = 1;
+
+pub fn get_module() -> RelicMap {
+    let mut module = RelicMap::new();
+    module.insert("listen".to_string(), Value::AsyncNativeFunction(AsyncNativeFn::new(ipc_listen)));
+    module.insert("send".to_string(), Value::AsyncNativeFunction(AsyncNativeFn::new(ipc_send)));
+    module.insert("path".to_string(), Value::NativeFunction(NativeFn::new(ipc_path)));
+    module
+}
+
+/// ipc.path(name) -> Silk
+fn ipc_path(args: Vec<Value>) -> Result<Value, FlowError> {
+    match args.as_slice() {
+        [Value::String(name)] => Ok(Value::String(Arc::new(socket_path(name)?.display().to_string()))),
+        _ => Err(FlowError::runtime("ipc.path expects 1 argument (name: Silk)", 0, 0)),
+    }
+}
+
+/// Where the socket for `name` lives: `$FLOWLANG_IPC_DIR`, else `$XDG_RUNTIME_DIR`,
+/// else the temp directory
+fn socket_path(name: &str) -> Result<PathBuf, FlowError> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !name.starts_with('.');
+    if !valid {
+        return Err(FlowError::runtime(
+            &format!("'{}' is not a valid ipc name; use up to 64 letters, digits, '-', '_' and '.'", name),
+            0,
+            0,
+        ));
+    }
+    let dir = ["FLOWLANG_IPC_DIR", "XDG_RUNTIME_DIR"]
+        .iter()
+        .find_map(|var| std::env::var_os(var).filter(|dir| !dir.is_empty()))
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    Ok(dir.join(format!("flowlang-{}.sock", name)))
+}
+
+async fn read_message<R: AsyncRead + Unpin>(reader: &mut R) -> std::io::Result<Vec<u8>> {
+    let len = reader.read_u32().await? as usize;
+    if len > MAX_MESSAGE {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("message of {} bytes is too large", len)));
+    }
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body).await?;
+    Ok(body)
+}
+
+async fn write_message<W: AsyncWrite + Unpin>(writer: &mut W, body: &[u8]) -> std::io::Result<()> {
+    writer.write_u32(body.len() as u32).await?;
+    writer.write_all(body).await?;
+    writer.flush().await
+}
+
+/// ipc.listen(name, handler) -> Handle
+/// The handler is called as `handler(value)` for each message; what it returns is
+/// sent back to the sender.
+#[cfg(unix)]
+async fn ipc_listen(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    use crate::runtime::handle::HandleType;
+    use tokio::sync::oneshot;
+
+    let (name, handler) = match args.as_slice() {
+        [Value::String(name), handler] if handler.type_name() == "Spell" => (name.clone(), handler.clone()),
+        _ => return Err(FlowError::type_error("ipc.listen expects a name (Silk) and a handler Spell", 0, 0)),
+    };
+    let path = socket_path(&name)?;
+    let listener = super::web::bind_unix("ipc.listen", &path)?;
+    let listener = listener.set_nonblocking(true)
+        .and_then(|_| tokio::net::UnixListener::from_std(listener))
+        .map_err(|e| FlowError::rift(&format!("ipc.listen could not listen on {}: {}", path.display(), e), 0, 0))?;
+
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+    let handle_id = ctx.runtime.register_handle(HandleType::UnixServer {
+        path: path.display().to_string(),
+        shutdown_tx: Some(shutdown_tx),
+    }).await;
+    let runtime = ctx.runtime.clone();
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+                accepted = listener.accept() => {
+                    let Ok((stream, _)) = accepted else { continue };
+                    tokio::spawn(serve_connection(stream, handler.clone(), runtime.clone()));
+                }
+            }
+        }
+        let _ = std::fs::remove_file(&path);
+        runtime.unregister_handle(handle_id).await;
+    });
+
+    Ok(Value::Handle(handle_id))
+}
+
+#[cfg(not(unix))]
+async fn ipc_listen(_args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
+    Err(FlowError::rift("ipc.listen needs Unix domain sockets, which this platform doesn't have", 0, 0))
+}
+
+/// Answer the messages on one connection, in order, until the sender hangs up
+#[cfg(unix)]
+async fn serve_connection(mut stream: tokio::net::UnixStream, handler: Value, runtime: Arc<crate::runtime::Runtime>) {
+    while let Ok(body) = read_message(&mut stream).await {
+        let reply = match super::serial::from_bytes(&body) {
+            Ok(value) => call_handler(&handler, value, &runtime).await,
+            Err(e) => Err(e.to_string()),
+        };
+        let (status, body) = match reply {
+            Ok(bytes) => (REPLY_OK, bytes),
+            Err(message) => (REPLY_ERROR, message.into_bytes()),
+        };
+        if stream.write_u8(status).await.is_err() || write_message(&mut stream, &body).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Run the handler on the event loop and pack its result
+#[cfg(unix)]
+async fn call_handler(handler: &Value, value: Value, runtime: &Arc<crate::runtime::Runtime>) -> Result<Vec<u8>, String> {
+    let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+    runtime.web_callback_sender()
+        .send(crate::runtime::WebCallbackRequest { callback: handler.clone(), args: vec![value], response_tx })
+        .await
+        .map_err(|_| "the listener is overloaded".to_string())?;
+    let result = response_rx.await.map_err(|_| "the listener stopped before replying".to_string())?;
+    // The event loop reports handler errors as an "Error: ..." Silk
+    if let Value::String(text) = &result {
+        if let Some(error) = text.strip_prefix("Error: ") {
+            return Err(error.to_string());
+        }
+    }
+    super::serial::to_bytes(&result, "reply").map_err(|e| e.to_string())
+}
+
+/// ipc.send(name, value, {timeout}?) -> Flux
+#[cfg(unix)]
+async fn ipc_send(args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
+    let (name, value, options) = match args.as_slice() {
+        [Value::String(name), value] => (name, value, None),
+        [Value::String(name), value, Value::Relic(options)] => (name, value, Some(options)),
+        _ => return Err(FlowError::runtime("ipc.send expects (name: Silk, value, options?: Relic)", 0, 0)),
+    };
+    let timeout = match options.and_then(|o| o.get("timeout")) {
+        None => DEFAULT_TIMEOUT_MS,
+        Some(Value::Number(ms)) if *ms > 0.0 && ms.is_finite() => *ms,
+        Some(other) => return Err(FlowError::type_error(
+            &format!("ipc.send timeout must be a positive Ember of milliseconds, got {}", other),
+            0,
+            0,
+        )),
+    };
+    let path = socket_path(name)?;
+    let body = super::serial::to_bytes(value, "value")?;
+
+    let exchange = async {
+        let mut stream = tokio::net::UnixStream::connect(&path).await.map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused => {
+                FlowError::rift(&format!("No FlowLang process is listening on '{}'", name), 0, 0)
+            }
+            _ => FlowError::rift(&format!("ipc.send could not reach '{}': {}", name, e), 0, 0),
+        })?;
+        let lost = |e: std::io::Error| FlowError::rift(&format!("ipc.send lost the connection to '{}': {}", name, e), 0, 0);
+        write_message(&mut stream, &body).await.map_err(lost)?;
+        let status = stream.read_u8().await.map_err(lost)?;
+        let reply = read_message(&mut stream).await.map_err(lost)?;
+        match status {
+            REPLY_OK => super::serial::from_bytes(&reply),
+            _ => Err(FlowError::runtime(
+                &format!("'{}' failed to handle the message: {}", name, String::from_utf8_lossy(&reply)),
+                0,
+                0,
+            )),
+        }
+    };
+    match tokio::time::timeout(Duration::from_secs_f64(timeout / 1000.0), exchange).await {
+        Ok(result) => result,
+        Err(_) => Err(FlowError::rift(&format!("'{}' did not reply within {}ms", name, timeout), 0, 0)),
+    }
+}
+
+#[cfg(not(unix))]
+async fn ipc_send(_args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
+    Err(FlowError::rift("ipc.send needs Unix domain sockets, which this platform doesn't have", 0, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_socket_path_rejects_unsafe_names() {
+        assert!(socket_path("worker-1.jobs").unwrap().ends_with("flowlang-worker-1.jobs.sock"));
+        assert!(socket_path("../etc").is_err());
+        assert!(socket_path(".hidden").is_err());
+        assert!(socket_path("").is_err());
+        assert!(socket_path("a/b").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_messages_are_length_prefixed() {
+        let (mut a, mut b) = tokio::io::duplex(64);
+        write_message(&mut a, b"hello").await.unwrap();
+        assert_eq!(read_message(&mut b).await.unwrap(), b"hello");
+    }
+}
@@ -1,12 +1,17 @@
 use crate::error::FlowError;
+use crate::interpreter::Interpreter;
 use crate::types::Value;
-use crate::types::NativeFn;
+use crate::types::{InterpreterFuture, InterpreterNativeFn, NativeFn, RelicMap};
+use std::io::{BufRead, BufReader};
 use std::sync::Arc;
 
 pub fn load_json_module() -> Vec<(&'static str, Value)> {
     vec![
         ("parse", Value::NativeFunction(NativeFn::new(json_parse))),
         ("stringify", Value::NativeFunction(NativeFn::new(json_stringify))),
+        ("pretty", Value::NativeFunction(NativeFn::new(json_pretty))),
+        ("get", Value::NativeFunction(NativeFn::new(json_get))),
+        ("stream", Value::InterpreterNativeFunction(InterpreterNativeFn::new(json_stream))),
     ]
 }
 
@@ -48,8 +53,299 @@ fn json_stringify(args: Vec<Value>) -> Result<Value, FlowError> {
     Ok(Value::String(Arc::new(json_string)))
 }
 
+// json::pretty(value: Flux, indent?: Ember) -> Silk
+fn json_pretty(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(FlowError::runtime(
+            "json::pretty expects 1 or 2 arguments (value, indent?)",
+            0,
+            0,
+        ));
+    }
+
+    let indent = match args.get(1) {
+        None => 2,
+        Some(Value::Number(n)) if *n >= 0.0 && n.fract() == 0.0 => *n as usize,
+        Some(_) => {
+            return Err(FlowError::type_error(
+                "json::pretty expects indent to be a non-negative whole Ember",
+                0,
+                0,
+            ))
+        }
+    };
+
+    Ok(Value::String(Arc::new(value_to_pretty_json(&args[0], indent))))
+}
+
+// json::get(value: Flux, path: Silk, default?: Flux) -> Flux
+// Path syntax: `a.b[2].c`, with `["key.with.dots"]` for awkward keys.
+// Missing keys and out-of-range indexes yield the default (Hollow).
+fn json_get(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(FlowError::runtime(
+            "json::get expects 2 or 3 arguments (value, path, default?)",
+            0,
+            0,
+        ));
+    }
+
+    let path = match &args[1] {
+        Value::String(s) => s.clone(),
+        _ => return Err(FlowError::type_error("json::get expects path to be a Silk", 0, 0)),
+    };
+    let fallback = args.get(2).cloned().unwrap_or(Value::Null);
+
+    let mut current = args[0].clone();
+    for segment in parse_json_path(&path)? {
+        let next = match (&current, segment) {
+            (Value::Relic(map), PathSegment::Key(key)) => map.get(&key).cloned(),
+            (Value::Array(arr), PathSegment::Index(i)) => arr.get(i).cloned(),
+            // Allow `items.0` as well as `items[0]`
+            (Value::Array(arr), PathSegment::Key(key)) => key.parse::<usize>().ok().and_then(|i| arr.get(i).cloned()),
+            _ => None,
+        };
+        match next {
+            Some(v) => current = v,
+            None => return Ok(fallback),
+        }
+    }
+
+    Ok(current)
+}
+
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+fn parse_json_path(path: &str) -> Result<Vec<PathSegment>, FlowError> {
+    let invalid = || FlowError::glitch(&format!("json::get: invalid path '{}'", path), 0, 0);
+    let mut segments = Vec::new();
+    let mut key = String::new();
+    let mut chars = path.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '.' => {
+                if !key.is_empty() {
+                    segments.push(PathSegment::Key(std::mem::take(&mut key)));
+                }
+            }
+            '[' => {
+                if !key.is_empty() {
+                    segments.push(PathSegment::Key(std::mem::take(&mut key)));
+                }
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(c) => inner.push(c),
+                        None => return Err(invalid()),
+                    }
+                }
+                let inner = inner.trim();
+                if let Some(quoted) = inner
+                    .strip_prefix('"')
+                    .and_then(|s| s.strip_suffix('"'))
+                    .or_else(|| inner.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))
+                {
+                    segments.push(PathSegment::Key(quoted.to_string()));
+                } else {
+                    segments.push(PathSegment::Index(inner.parse().map_err(|_| invalid())?));
+                }
+            }
+            ']' => return Err(invalid()),
+            c => key.push(c),
+        }
+    }
+    if !key.is_empty() {
+        segments.push(PathSegment::Key(key));
+    }
+
+    Ok(segments)
+}
+
+// json::stream(path: Silk, spell: Spell) -> Ember
+// Reads a large JSON file incrementally and calls the Spell once per item:
+// each element of a top-level array, or each value of a newline-delimited
+// (or concatenated) JSON file. Returning `false` from the Spell stops early.
+// Returns the number of items delivered.
+fn json_stream(args: Vec<Value>, interp: &mut Interpreter) -> InterpreterFuture<'_> {
+    Box::pin(async move {
+        if args.len() != 2 {
+            return Err(FlowError::runtime(
+                "json::stream expects 2 arguments (path, spell)",
+                0,
+                0,
+            ));
+        }
+
+        let path = match &args[0] {
+            Value::String(s) => s.clone(),
+            _ => return Err(FlowError::type_error("json::stream expects path to be a Silk", 0, 0)),
+        };
+        let spell = args[1].clone();
+        if spell.type_name() != "Spell" {
+            return Err(FlowError::type_error("json::stream expects a Spell as second argument", 0, 0));
+        }
+
+        let file = std::fs::File::open(path.as_str())
+            .map_err(|e| FlowError::rift(&format!("json::stream cannot open '{}': {}", path, e), 0, 0))?;
+        let mut scanner = JsonItemScanner::new(BufReader::new(file));
+
+        let mut count = 0;
+        while let Some(item) = scanner.next_item()? {
+            let value = parse_json_value(&item)?;
+            count += 1;
+            let result = interp.execute_function(spell.clone(), vec![value]).await?;
+            if matches!(result, Value::Boolean(false)) {
+                break;
+            }
+        }
+
+        Ok(Value::Number(count as f64))
+    })
+}
+
+// Splits a JSON byte stream into the source text of individual items without
+// holding more than one item in memory. Structural characters are ASCII, so
+// scanning bytes is safe for UTF-8 input.
+struct JsonItemScanner<R: BufRead> {
+    bytes: std::iter::Peekable<std::io::Bytes<R>>,
+    // None until the first non-whitespace byte tells us the layout
+    in_array: Option<bool>,
+    finished: bool,
+}
+
+impl<R: BufRead> JsonItemScanner<R> {
+    fn new(reader: R) -> Self {
+        JsonItemScanner { bytes: reader.bytes().peekable(), in_array: None, finished: false }
+    }
+
+    fn next_byte(&mut self) -> Result<Option<u8>, FlowError> {
+        match self.bytes.next() {
+            Some(Ok(b)) => Ok(Some(b)),
+            Some(Err(e)) => Err(FlowError::rift(&format!("json::stream read failed: {}", e), 0, 0)),
+            None => Ok(None),
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> Result<Option<u8>, FlowError> {
+        loop {
+            match self.next_byte()? {
+                Some(b) if b.is_ascii_whitespace() => continue,
+                other => return Ok(other),
+            }
+        }
+    }
+
+    fn next_item(&mut self) -> Result<Option<String>, FlowError> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let mut first = self.skip_whitespace()?;
+        if self.in_array.is_none() {
+            if first == Some(b'[') {
+                self.in_array = Some(true);
+                first = self.skip_whitespace()?;
+                if first == Some(b']') {
+                    self.finished = true;
+                    return Ok(None);
+                }
+            } else {
+                self.in_array = Some(false);
+            }
+        }
+        let in_array = self.in_array == Some(true);
+
+        let first = match first {
+            Some(b) => b,
+            None if in_array => {
+                return Err(FlowError::glitch("json::stream: unexpected end of input inside array", 0, 0))
+            }
+            None => {
+                self.finished = true;
+                return Ok(None);
+            }
+        };
+
+        let mut item = vec![first];
+        let mut depth: i64 = 0;
+        let mut in_string = first == b'"';
+        let mut escaped = false;
+        match first {
+            b'{' | b'[' => depth = 1,
+            b'}' | b']' | b',' => {
+                return Err(FlowError::glitch(
+                    &format!("json::stream: unexpected '{}'", first as char),
+                    0,
+                    0,
+                ))
+            }
+            _ => {}
+        }
+
+        loop {
+            if depth == 0 && !in_string {
+                // Containers and strings are complete once closed; bare
+                // scalars run until the next separator, which is left unread
+                if matches!(first, b'{' | b'[' | b'"') {
+                    break;
+                }
+                match self.bytes.peek() {
+                    Some(Ok(b)) if b.is_ascii_whitespace() || matches!(*b, b',' | b']' | b'{' | b'[' | b'"') => break,
+                    None => break,
+                    _ => {}
+                }
+            }
+
+            let b = match self.next_byte()? {
+                Some(b) => b,
+                None => break,
+            };
+            item.push(b);
+
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if b == b'\\' {
+                    escaped = true;
+                } else if b == b'"' {
+                    in_string = false;
+                }
+                continue;
+            }
+
+            match b {
+                b'"' => in_string = true,
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' => depth -= 1,
+                _ => {}
+            }
+        }
+
+        if in_string || depth != 0 {
+            return Err(FlowError::glitch("json::stream: unexpected end of input inside an item", 0, 0));
+        }
+
+        if in_array {
+            match self.skip_whitespace()? {
+                Some(b',') => {}
+                Some(b']') => self.finished = true,
+                _ => return Err(FlowError::glitch("json::stream: expected ',' or ']' between array items", 0, 0)),
+            }
+        }
+
+        String::from_utf8(item)
+            .map(Some)
+            .map_err(|_| FlowError::glitch("json::stream: input is not valid UTF-8", 0, 0))
+    }
+}
+
 // Helper: Parse JSON string to FlowLang Value
-fn parse_json_value(json_str: &str) -> Result<Value, FlowError> {
+pub fn parse_json_value(json_str: &str) -> Result<Value, FlowError> {
     let trimmed = json_str.trim();
 
     // null
@@ -123,14 +419,13 @@ fn parse_json_value(json_str: &str) -> Result<Value, FlowError> {
 
     // object - parse into Relic (HashMap)
     if trimmed.starts_with('{') && trimmed.ends_with('}') {
-        use std::collections::HashMap;
         
         let content = &trimmed[1..trimmed.len() - 1];
         if content.trim().is_empty() {
-            return Ok(Value::Relic(Arc::new(HashMap::new())));
+            return Ok(Value::Relic(Arc::new(RelicMap::new())));
         }
 
-        let mut map: HashMap<String, Value> = HashMap::new();
+        let mut map: RelicMap = RelicMap::new();
         let mut depth = 0;
         let mut current = String::new();
         let mut in_string = false;
@@ -169,7 +464,7 @@ fn parse_json_value(json_str: &str) -> Result<Value, FlowError> {
 }
 
 // Helper: Parse a single "key": value pair
-fn parse_json_key_value(pair: &str, map: &mut std::collections::HashMap<String, Value>) -> Result<(), FlowError> {
+fn parse_json_key_value(pair: &str, map: &mut RelicMap) -> Result<(), FlowError> {
     let pair = pair.trim();
     
     // Find the colon separating key from value
@@ -230,12 +525,18 @@ pub fn value_to_json_string(value: &Value) -> String {
             let elements: Vec<String> = arr.iter().map(value_to_json_string).collect();
             format!("[{}]", elements.join(","))
         }
+        // Covenants have no JSON form of their own; they are written as arrays
+        Value::Covenant(set) => {
+            let elements: Vec<String> = set.values().map(value_to_json_string).collect();
+            format!("[{}]", elements.join(","))
+        }
         Value::Function { .. } => "null".to_string(), // Functions can't be serialized
         Value::NativeFunction(_) => "null".to_string(),
         Value::AsyncNativeFunction(_) => "null".to_string(),
+        Value::InterpreterNativeFunction(_) => "null".to_string(),
         Value::Handle(id) => format!("{}", id), // Handles serialize as their ID number
         Value::Relic(map) => {
-            let mut entries: Vec<String> = map.iter()
+            let entries: Vec<String> = map.iter()
                 .map(|(k, v)| {
                     let escaped_key = k
                         .replace('\\', "\\\\")
@@ -246,8 +547,52 @@ pub fn value_to_json_string(value: &Value) -> String {
                     format!("\"{}\":{}", escaped_key, value_to_json_string(v))
                 })
                 .collect();
-            entries.sort(); // Sort for deterministic output
             format!("{{{}}}", entries.join(","))
         }
     }
 }
+
+// Helper: Convert FlowLang Value to indented JSON string
+pub fn value_to_pretty_json(value: &Value, indent: usize) -> String {
+    let mut out = String::new();
+    write_pretty_json(value, indent, 0, &mut out);
+    out
+}
+
+fn write_pretty_json(value: &Value, indent: usize, depth: usize, out: &mut String) {
+    let pad = |level: usize| " ".repeat(indent * level);
+    match value {
+        Value::Array(arr) if !arr.is_empty() => {
+            out.push_str("[\n");
+            for (i, item) in arr.iter().enumerate() {
+                out.push_str(&pad(depth + 1));
+                write_pretty_json(item, indent, depth + 1, out);
+                if i + 1 < arr.len() {
+                    out.push(',');
+                }
+                out.push('\n');
+            }
+            out.push_str(&pad(depth));
+            out.push(']');
+        }
+        Value::Relic(map) if !map.is_empty() => {
+            out.push_str("{\n");
+            for (i, (key, item)) in map.iter().enumerate() {
+                out.push_str(&pad(depth + 1));
+                out.push_str(&value_to_json_string(&Value::String(Arc::new(key.clone()))));
+                out.push_str(": ");
+                write_pretty_json(item, indent, depth + 1, out);
+                if i + 1 < map.len() {
+                    out.push(',');
+                }
+                out.push('\n');
+            }
+            out.push_str(&pad(depth));
+            out.push('}');
+        }
+        Value::Covenant(set) if !set.is_empty() => {
+            write_pretty_json(&Value::Array(Arc::new(set.values().cloned().collect())), indent, depth, out)
+        }
+        _ => out.push_str(&value_to_json_string(value)),
+    }
+}
@@ -0,0 +1,197 @@
+//! std:log - Leveled logging with configurable sinks
+//!
+//! Log lines go to every configured sink (the console by default). The sinks are
+//! shared by the whole process, so web access logs and `log.info` calls from any
+//! handler end up in the same place.
+
+use crate::error::FlowError;
+use crate::types::{NativeFn, Value};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Level> {
+        match name.to_lowercase().as_str() {
+            "debug" => Some(Level::Debug),
+            "info" => Some(Level::Info),
+            "warn" | "warning" => Some(Level::Warn),
+            "error" => Some(Level::Error),
+            _ => None,
+        }
+    }
+}
+
+enum Sink {
+    Stdout,
+    Stderr,
+    File(File),
+}
+
+struct Logger {
+    level: Level,
+    json: bool,
+    /// None until configured: the console
+    sinks: Option<Vec<Sink>>,
+}
+
+static LOGGER: Mutex<Logger> = Mutex::new(Logger { level: Level::Info, json: false, sinks: None });
+
+pub fn load_log_module() -> Vec<(&'static str, Value)> {
+    vec![
+        ("debug", Value::NativeFunction(NativeFn::new(|args| log_at(Level::Debug, args)))),
+        ("info", Value::NativeFunction(NativeFn::new(|args| log_at(Level::Info, args)))),
+        ("warn", Value::NativeFunction(NativeFn::new(|args| log_at(Level::Warn, args)))),
+        ("error", Value::NativeFunction(NativeFn::new(|args| log_at(Level::Error, args)))),
+        ("level", Value::NativeFunction(NativeFn::new(log_level))),
+        ("format", Value::NativeFunction(NativeFn::new(log_format))),
+        ("toFile", Value::NativeFunction(NativeFn::new(log_to_file))),
+        ("toConsole", Value::NativeFunction(NativeFn::new(log_to_console))),
+        ("reset", Value::NativeFunction(NativeFn::new(log_reset))),
+    ]
+}
+
+/// Write an already formatted line to every sink, if `level` is enabled.
+/// Used by std:web for access logs, which have formats of their own.
+pub fn write_line(level: Level, line: &str) {
+    let mut logger = LOGGER.lock().unwrap();
+    if level < logger.level {
+        return;
+    }
+    match &mut logger.sinks {
+        None => println!("{}", line),
+        Some(sinks) => {
+            for sink in sinks.iter_mut() {
+                match sink {
+                    Sink::Stdout => println!("{}", line),
+                    Sink::Stderr => eprintln!("{}", line),
+                    Sink::File(file) => {
+                        let _ = writeln!(file, "{}", line);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// log.info(message, fields?) and friends
+fn log_at(level: Level, args: Vec<Value>) -> Result<Value, FlowError> {
+    let message = match args.first() {
+        Some(Value::String(s)) => s.to_string(),
+        Some(other) => other.to_string(),
+        None => return Err(FlowError::runtime(&format!("log.{} expects a message", level.name()), 0, 0)),
+    };
+    let fields = match args.get(1) {
+        Some(Value::Relic(map)) => {
+            let mut fields: Vec<(&String, &Value)> = map.iter().collect();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+            fields.into_iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+        }
+        Some(_) => return Err(FlowError::type_error(&format!("log.{} expects a Relic of fields", level.name()), 0, 0)),
+        None => Vec::new(),
+    };
+
+    let json = LOGGER.lock().unwrap().json;
+    let time = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false);
+    let line = if json {
+        let mut object = serde_json::Map::new();
+        object.insert("time".to_string(), serde_json::Value::String(time));
+        object.insert("level".to_string(), serde_json::Value::String(level.name().to_string()));
+        object.insert("msg".to_string(), serde_json::Value::String(message));
+        for (key, value) in fields {
+            let text = crate::stdlib::json::value_to_json_string(&value);
+            object.insert(key, serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text)));
+        }
+        serde_json::Value::Object(object).to_string()
+    } else {
+        let mut line = format!("{} {:<5} {}", time, level.name().to_uppercase(), message);
+        for (key, value) in fields {
+            line.push_str(&format!(" {}={}", key, value));
+        }
+        line
+    };
+    write_line(level, &line);
+    Ok(Value::Null)
+}
+
+/// log.level(name) - "debug", "info", "warn" or "error"; lower levels are dropped
+fn log_level(args: Vec<Value>) -> Result<Value, FlowError> {
+    let level = match args.first() {
+        Some(Value::String(name)) => Level::parse(name),
+        _ => None,
+    };
+    match level {
+        Some(level) => {
+            LOGGER.lock().unwrap().level = level;
+            Ok(Value::Null)
+        }
+        None => Err(FlowError::runtime("log.level expects \"debug\", \"info\", \"warn\" or \"error\"", 0, 0)),
+    }
+}
+
+/// log.format(name) - "text" or "json"
+fn log_format(args: Vec<Value>) -> Result<Value, FlowError> {
+    let json = match args.first() {
+        Some(Value::String(s)) if s.as_str() == "text" => false,
+        Some(Value::String(s)) if s.as_str() == "json" => true,
+        _ => return Err(FlowError::runtime("log.format expects \"text\" or \"json\"", 0, 0)),
+    };
+    LOGGER.lock().unwrap().json = json;
+    Ok(Value::Null)
+}
+
+/// log.toFile(path) - append log lines to a file, in addition to other sinks
+fn log_to_file(args: Vec<Value>) -> Result<Value, FlowError> {
+    let path = match args.first() {
+        Some(Value::String(s)) => s.to_string(),
+        _ => return Err(FlowError::type_error("log.toFile expects a path", 0, 0)),
+    };
+    let file = OpenOptions::new().create(true).append(true).open(&path).map_err(|e| {
+        FlowError::runtime(&format!("Cannot open log file '{}': {}", path, e), 0, 0)
+    })?;
+    add_sink(Sink::File(file));
+    Ok(Value::Null)
+}
+
+/// log.toConsole(stream?) - "stdout" (default) or "stderr"
+fn log_to_console(args: Vec<Value>) -> Result<Value, FlowError> {
+    let sink = match args.first() {
+        None => Sink::Stdout,
+        Some(Value::String(s)) if s.as_str() == "stdout" => Sink::Stdout,
+        Some(Value::String(s)) if s.as_str() == "stderr" => Sink::Stderr,
+        _ => return Err(FlowError::runtime("log.toConsole expects \"stdout\" or \"stderr\"", 0, 0)),
+    };
+    add_sink(sink);
+    Ok(Value::Null)
+}
+
+/// log.reset() - back to info level, text format, console only
+fn log_reset(_args: Vec<Value>) -> Result<Value, FlowError> {
+    let mut logger = LOGGER.lock().unwrap();
+    logger.level = Level::Info;
+    logger.json = false;
+    logger.sinks = None;
+    Ok(Value::Null)
+}
+
+// The first configured sink replaces the default console
+fn add_sink(sink: Sink) {
+    LOGGER.lock().unwrap().sinks.get_or_insert_with(Vec::new).push(sink);
+}
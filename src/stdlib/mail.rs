@@ -0,0 +1,212 @@
+//! std:mail - Sending email over SMTP
+//!
+//! - `mail.send(message, server)` - Deliver a message through an SMTP server
+//! - `mail.format(message)` - Render the raw RFC 5322 message without sending
+//!
+//! `message` is a Relic `{ to, from, subject, body, html?, cc?, bcc?, replyTo?,
+//! attachments? }`; `server` is `{ host, port?, username?, password?, tls?,
+//! timeout? }` where `tls` is "starttls" (default), "tls" or "none".
+
+use crate::error::FlowError;
+use crate::stdlib::stream::get_mime_type;
+use crate::types::{AsyncContext, AsyncNativeFn, NativeFn, Value, RelicMap};
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub fn load_mail_module() -> Vec<(&'static str, Value)> {
+    vec![
+        ("send", Value::AsyncNativeFunction(AsyncNativeFn::new(mail_send))),
+        ("format", Value::NativeFunction(NativeFn::new(mail_format))),
+    ]
+}
+
+/// mail.send(message, server) -> Relic { code, message }
+/// Raises a Rift if the server cannot be reached or rejects the message.
+async fn mail_send(args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
+    if args.len() != 2 {
+        return Err(FlowError::runtime(
+            "mail.send expects 2 arguments (message, server)",
+            0, 0,
+        ));
+    }
+
+    let message = build_message(&args[0])?;
+    let transport = build_transport(&args[1])?;
+
+    let response = transport
+        .send(message)
+        .await
+        .map_err(|e| FlowError::rift(&format!("mail.send failed: {}", e), 0, 0))?;
+
+    let mut result = RelicMap::new();
+    result.insert("code".to_string(), Value::Number(response.code().to_string().parse().unwrap_or(0.0)));
+    result.insert(
+        "message".to_string(),
+        Value::String(Arc::new(response.message().collect::<Vec<_>>().join("\n"))),
+    );
+    Ok(Value::Relic(Arc::new(result)))
+}
+
+/// mail.format(message) -> Silk
+/// Useful for previewing a message or writing it to a .eml file.
+fn mail_format(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime("mail.format expects 1 argument (message)", 0, 0));
+    }
+
+    let message = build_message(&args[0])?;
+    Ok(Value::String(Arc::new(String::from_utf8_lossy(&message.formatted()).into_owned())))
+}
+
+fn build_message(value: &Value) -> Result<Message, FlowError> {
+    let fields = match value {
+        Value::Relic(map) => map,
+        _ => return Err(FlowError::type_error("mail message must be a Relic", 0, 0)),
+    };
+
+    let from = match fields.get("from") {
+        Some(from) => parse_mailbox(from, "from")?,
+        None => return Err(FlowError::runtime("mail message is missing 'from'", 0, 0)),
+    };
+    let to = match fields.get("to") {
+        Some(to) => parse_mailboxes(to, "to")?,
+        None => return Err(FlowError::runtime("mail message is missing 'to'", 0, 0)),
+    };
+    if to.is_empty() {
+        return Err(FlowError::runtime("mail message needs at least one 'to' address", 0, 0));
+    }
+
+    let mut builder = Message::builder()
+        .from(from)
+        .subject(fields.get("subject").map(|s| s.to_string()).unwrap_or_default());
+    for mailbox in to {
+        builder = builder.to(mailbox);
+    }
+    if let Some(cc) = fields.get("cc") {
+        for mailbox in parse_mailboxes(cc, "cc")? {
+            builder = builder.cc(mailbox);
+        }
+    }
+    if let Some(bcc) = fields.get("bcc") {
+        for mailbox in parse_mailboxes(bcc, "bcc")? {
+            builder = builder.bcc(mailbox);
+        }
+    }
+    if let Some(reply_to) = fields.get("replyTo") {
+        builder = builder.reply_to(parse_mailbox(reply_to, "replyTo")?);
+    }
+
+    let text = fields.get("body").map(|b| b.to_string()).unwrap_or_default();
+    let html = fields.get("html").map(|h| h.to_string());
+
+    let attachments = match fields.get("attachments") {
+        Some(Value::Array(items)) => items.iter().map(build_attachment).collect::<Result<Vec<_>, _>>()?,
+        Some(_) => return Err(FlowError::type_error("mail 'attachments' must be a Constellation", 0, 0)),
+        None => Vec::new(),
+    };
+
+    let result = match (html, attachments.is_empty()) {
+        (None, true) => builder.header(ContentType::TEXT_PLAIN).body(text),
+        (Some(html), true) => builder.multipart(MultiPart::alternative_plain_html(text, html)),
+        (html, false) => {
+            let mut mixed = match html {
+                Some(html) => MultiPart::mixed().multipart(MultiPart::alternative_plain_html(text, html)),
+                None => MultiPart::mixed().singlepart(SinglePart::plain(text)),
+            };
+            for attachment in attachments {
+                mixed = mixed.singlepart(attachment);
+            }
+            builder.multipart(mixed)
+        }
+    };
+
+    result.map_err(|e| FlowError::glitch(&format!("Invalid mail message: {}", e), 0, 0))
+}
+
+fn parse_mailbox(value: &Value, field: &str) -> Result<Mailbox, FlowError> {
+    value.to_string().parse::<Mailbox>().map_err(|e| {
+        FlowError::glitch(&format!("Invalid '{}' address '{}': {}", field, value, e), 0, 0)
+    })
+}
+
+fn parse_mailboxes(value: &Value, field: &str) -> Result<Vec<Mailbox>, FlowError> {
+    match value {
+        Value::Array(items) => items.iter().map(|item| parse_mailbox(item, field)).collect(),
+        other => Ok(vec![parse_mailbox(other, field)?]),
+    }
+}
+
+// An attachment is a file path, or a Relic { path, name?, contentType? }
+fn build_attachment(value: &Value) -> Result<SinglePart, FlowError> {
+    let (path, name, content_type) = match value {
+        Value::String(path) => (path.to_string(), None, None),
+        Value::Relic(map) => match map.get("path") {
+            Some(path) => (
+                path.to_string(),
+                map.get("name").map(|n| n.to_string()),
+                map.get("contentType").map(|c| c.to_string()),
+            ),
+            None => return Err(FlowError::runtime("mail attachment Relic is missing 'path'", 0, 0)),
+        },
+        _ => return Err(FlowError::type_error("mail attachment must be a path or a Relic", 0, 0)),
+    };
+
+    let bytes = std::fs::read(&path)
+        .map_err(|e| FlowError::rift(&format!("Failed to read attachment '{}': {}", path, e), 0, 0))?;
+    let name = name.unwrap_or_else(|| {
+        Path::new(&path).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_else(|| path.clone())
+    });
+    let content_type = content_type.unwrap_or_else(|| get_mime_type(&path));
+    let content_type = ContentType::parse(&content_type)
+        .map_err(|_| FlowError::glitch(&format!("Invalid attachment content type '{}'", content_type), 0, 0))?;
+
+    Ok(Attachment::new(name).body(bytes, content_type))
+}
+
+fn build_transport(value: &Value) -> Result<AsyncSmtpTransport<Tokio1Executor>, FlowError> {
+    let server = match value {
+        Value::Relic(map) => map,
+        _ => return Err(FlowError::type_error("mail server settings must be a Relic", 0, 0)),
+    };
+
+    let host = match server.get("host") {
+        Some(host) => host.to_string(),
+        None => return Err(FlowError::runtime("mail server settings are missing 'host'", 0, 0)),
+    };
+    let tls = server.get("tls").map(|t| t.to_string()).unwrap_or_else(|| "starttls".to_string());
+
+    let mut builder = match tls.as_str() {
+        "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host),
+        "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&host),
+        "none" => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host)),
+        other => {
+            return Err(FlowError::runtime(
+                &format!("Unknown mail tls mode '{}' (expected \"starttls\", \"tls\" or \"none\")", other),
+                0, 0,
+            ))
+        }
+    }
+    .map_err(|e| FlowError::rift(&format!("Invalid mail server '{}': {}", host, e), 0, 0))?;
+
+    match server.get("port") {
+        Some(Value::Number(port)) => builder = builder.port(*port as u16),
+        Some(_) => return Err(FlowError::type_error("mail server 'port' must be an Ember", 0, 0)),
+        None => {}
+    }
+    if let Some(username) = server.get("username") {
+        let password = server.get("password").map(|p| p.to_string()).unwrap_or_default();
+        builder = builder.credentials(Credentials::new(username.to_string(), password));
+    }
+    match server.get("timeout") {
+        Some(Value::Number(ms)) => builder = builder.timeout(Some(Duration::from_millis(*ms as u64))),
+        Some(_) => return Err(FlowError::type_error("mail server 'timeout' must be an Ember (ms)", 0, 0)),
+        None => {}
+    }
+
+    Ok(builder.build())
+}
@@ -1,10 +1,9 @@
-use crate::types::{Value, NativeFn};
+use crate::types::{Value, NativeFn, RelicMap};
 use crate::error::FlowError;
-use std::collections::HashMap;
 use std::sync::Arc;
 
-pub fn get_module() -> HashMap<String, Value> {
-    let mut module = HashMap::new();
+pub fn get_module() -> RelicMap {
+    let mut module = RelicMap::new();
     
     // Constants
     module.insert("PI".to_string(), Value::Number(std::f64::consts::PI));
@@ -16,7 +15,6 @@ pub fn get_module() -> HashMap<String, Value> {
     module.insert("tan".to_string(), create_math_fn("tan", |n| n.tan()));
     module.insert("sqrt".to_string(), create_math_fn("sqrt", |n| n.sqrt()));
     module.insert("abs".to_string(), create_math_fn("abs", |n| n.abs()));
-    module.insert("round".to_string(), create_math_fn("round", |n| n.round()));
     module.insert("floor".to_string(), create_math_fn("floor", |n| n.floor()));
     module.insert("ceil".to_string(), create_math_fn("ceil", |n| n.ceil()));
     
@@ -50,9 +48,135 @@ pub fn get_module() -> HashMap<String, Value> {
         }
     }))));
     
+    module.insert("round".to_string(), Value::NativeFunction(NativeFn(Arc::new(|args| {
+        match args.as_slice() {
+            [Value::Number(n)] => Ok(Value::Number(n.round())),
+            [Value::Number(n), Value::Number(digits)] => Ok(Value::Number(round_to(*n, *digits)?)),
+            [_] | [_, _] => Err(FlowError::type_error("round() expects Embers", 0, 0)),
+            _ => Err(FlowError::runtime("round() expects 1 or 2 arguments (x, digits)", 0, 0)),
+        }
+    }))));
+    
+    module.insert("floorDiv".to_string(), Value::NativeFunction(NativeFn(Arc::new(|args| {
+        if args.len() != 2 {
+            return Err(FlowError::runtime("floorDiv() expects 2 arguments", 0, 0));
+        }
+        match (&args[0], &args[1]) {
+            (Value::Number(_), Value::Number(b)) if *b == 0.0 => Err(FlowError::division_by_zero(0, 0)),
+            (Value::Number(a), Value::Number(b)) => Ok(Value::Number((a / b).floor())),
+            _ => Err(FlowError::type_error("floorDiv() expects Embers", 0, 0)),
+        }
+    }))));
+    
+    module.insert("clamp".to_string(), Value::NativeFunction(NativeFn(Arc::new(|args| {
+        if args.len() != 3 {
+            return Err(FlowError::runtime("clamp() expects 3 arguments (x, low, high)", 0, 0));
+        }
+        match (&args[0], &args[1], &args[2]) {
+            (Value::Number(_), Value::Number(low), Value::Number(high)) if low > high => Err(FlowError::runtime(
+                &format!("clamp() low bound {} is above high bound {}", low, high),
+                0,
+                0,
+            )),
+            (Value::Number(x), Value::Number(low), Value::Number(high)) => Ok(Value::Number(x.clamp(*low, *high))),
+            _ => Err(FlowError::type_error("clamp() expects Embers", 0, 0)),
+        }
+    }))));
+    
+    module.insert("toFixed".to_string(), Value::NativeFunction(NativeFn(Arc::new(|args| {
+        match args.as_slice() {
+            [Value::Number(n), Value::Number(digits)] => Ok(Value::String(Arc::new(to_fixed(*n, *digits)?))),
+            [_, _] => Err(FlowError::type_error("toFixed() expects Embers", 0, 0)),
+            _ => Err(FlowError::runtime("toFixed() expects 2 arguments (x, digits)", 0, 0)),
+        }
+    }))));
+    
+    module.insert("toBase".to_string(), Value::NativeFunction(NativeFn(Arc::new(|args| {
+        match args.as_slice() {
+            [Value::Number(n), Value::Number(base)] => Ok(Value::String(Arc::new(to_base(*n, *base)?))),
+            [_, _] => Err(FlowError::type_error("toBase() expects Embers", 0, 0)),
+            _ => Err(FlowError::runtime("toBase() expects 2 arguments (n, base)", 0, 0)),
+        }
+    }))));
+    
+    module.insert("parseEmber".to_string(), Value::NativeFunction(NativeFn(Arc::new(|args| {
+        match args.as_slice() {
+            [Value::String(s)] => Ok(Value::Number(parse_ember(s, "en")?)),
+            [Value::String(s), Value::String(locale)] => Ok(Value::Number(parse_ember(s, locale)?)),
+            [_] | [_, _] => Err(FlowError::type_error("parseEmber() expects Silk (text, locale)", 0, 0)),
+            _ => Err(FlowError::runtime("parseEmber() expects 1 or 2 arguments (text, locale)", 0, 0)),
+        }
+    }))));
+    
     module
 }
 
+/// A whole number of digits in `0..=max`
+fn digit_count(name: &str, digits: f64, max: f64) -> Result<usize, FlowError> {
+    if digits.fract() != 0.0 || !(0.0..=max).contains(&digits) {
+        return Err(FlowError::out_of_range(
+            &format!("{}() digits must be a whole Ember from 0 to {}, got {}", name, max, digits),
+            0,
+            0,
+        ));
+    }
+    Ok(digits as usize)
+}
+
+/// Round half away from zero at `digits` decimal places
+pub fn round_to(n: f64, digits: f64) -> Result<f64, FlowError> {
+    let factor = 10f64.powi(digit_count("round", digits, 15.0)? as i32);
+    Ok((n * factor).round() / factor)
+}
+
+/// `n` with exactly `digits` decimal places
+pub fn to_fixed(n: f64, digits: f64) -> Result<String, FlowError> {
+    Ok(format!("{:.*}", digit_count("toFixed", digits, 100.0)?, n))
+}
+
+/// A whole Ember written in `base` (2 to 36), lowercase letters for digits above 9
+pub fn to_base(n: f64, base: f64) -> Result<String, FlowError> {
+    if base.fract() != 0.0 || !(2.0..=36.0).contains(&base) {
+        return Err(FlowError::out_of_range(&format!("toBase() base must be from 2 to 36, got {}", base), 0, 0));
+    }
+    if n.fract() != 0.0 || n.abs() > u64::MAX as f64 {
+        return Err(FlowError::type_error(&format!("toBase() needs a whole Ember, got {}", n), 0, 0));
+    }
+    let mut rest = n.abs() as u64;
+    let mut digits = Vec::new();
+    loop {
+        digits.push(std::char::from_digit((rest % base as u64) as u32, base as u32).unwrap_or('?'));
+        rest /= base as u64;
+        if rest == 0 {
+            break;
+        }
+    }
+    if n < 0.0 {
+        digits.push('-');
+    }
+    Ok(digits.iter().rev().collect())
+}
+
+/// Languages that write 1.234,5 rather than 1,234.5
+const DECIMAL_COMMA: &[&str] = &[
+    "de", "fr", "es", "it", "pt", "nl", "ru", "pl", "tr", "sv", "da", "nb", "fi", "cs", "id", "uk",
+];
+
+/// Parse `text` with the group and decimal separators of `locale` (e.g. "en", "de-DE")
+pub fn parse_ember(text: &str, locale: &str) -> Result<f64, FlowError> {
+    let language = locale.split(['-', '_']).next().unwrap_or("").to_lowercase();
+    let (group, decimal) = if DECIMAL_COMMA.contains(&language.as_str()) { ('.', ',') } else { (',', '.') };
+    let normalized: String = text
+        .trim()
+        .chars()
+        .filter(|&c| c != group && c != '\'' && !c.is_whitespace())
+        .map(|c| if c == decimal { '.' } else { c })
+        .collect();
+    normalized.parse::<f64>().ok().filter(|n| n.is_finite()).ok_or_else(|| {
+        FlowError::type_error(&format!("parseEmber() cannot read '{}' as an Ember ({})", text, locale), 0, 0)
+    })
+}
+
 fn create_math_fn(name: &str, op: fn(f64) -> f64) -> Value {
     let name = name.to_string();
     Value::NativeFunction(NativeFn(Arc::new(move |args| {
@@ -65,3 +189,26 @@ fn create_math_fn(name: &str, op: fn(f64) -> f64) -> Value {
         }
     })))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_helpers() {
+        assert_eq!(round_to(3.14159, 2.0).unwrap(), 3.14);
+        assert_eq!(round_to(2.5, 0.0).unwrap(), 3.0);
+        assert!(round_to(1.0, 1.5).is_err());
+        assert_eq!(to_fixed(1.005, 1.0).unwrap(), "1.0");
+        assert_eq!(to_fixed(2.0, 3.0).unwrap(), "2.000");
+        assert_eq!(to_base(255.0, 16.0).unwrap(), "ff");
+        assert_eq!(to_base(-10.0, 2.0).unwrap(), "-1010");
+        assert_eq!(to_base(0.0, 36.0).unwrap(), "0");
+        assert!(to_base(1.5, 2.0).is_err());
+        assert!(to_base(10.0, 1.0).is_err());
+        assert_eq!(parse_ember("1,234.5", "en").unwrap(), 1234.5);
+        assert_eq!(parse_ember("1.234,5", "de-DE").unwrap(), 1234.5);
+        assert_eq!(parse_ember("1 234,5", "fr").unwrap(), 1234.5);
+        assert!(parse_ember("12abc", "en").is_err());
+    }
+}
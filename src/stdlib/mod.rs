@@ -1,7 +1,10 @@
 pub mod io;
 pub mod math;
+pub mod bits;
 pub mod string;
 pub mod array;
+pub mod covenant;
+pub mod collections;
 pub mod file;
 pub mod json;
 pub mod requesty;
@@ -17,26 +20,57 @@ pub mod stream;
 pub mod path;
 pub mod process;
 pub mod git;
+pub mod assert;
+pub mod random;
+pub mod zip;
+pub mod csv;
+pub mod yaml;
+pub mod toml;
+pub mod mail;
+pub mod log;
+pub mod template;
+pub mod graphql;
+pub mod grpc;
+pub mod redis;
+pub mod sql;
+pub mod s3;
+pub mod sync;
+pub mod format;
+pub mod flowrec;
+pub mod socket_activation;
+pub mod spell;
+pub mod vm;
+pub mod reflect;
+pub mod serial;
+pub mod ipc;
+pub mod state;
 
-use std::collections::HashMap;
+use std::sync::Arc;
 
-use crate::types::Value;
+use crate::types::{Value, RelicMap};
 use crate::error::FlowError;
 
-pub fn load_module(name: &str) -> Option<HashMap<String, Value>> {
+pub fn load_module(name: &str) -> Option<RelicMap> {
     match name {
         "math" => Some(math::get_module()),
+        "bits" => Some(bits::get_module()),
+        "vm" => Some(vm::get_module()),
+        "reflect" => Some(reflect::get_module()),
+        "serial" => Some(serial::get_module()),
+        "ipc" => Some(ipc::get_module()),
+        "state" => Some(state::get_module()),
         "string" => Some(string::get_module()),
         "array" => Some(array::get_module()),
+        "covenant" => Some(covenant::get_module()),
         "file" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in file::load_file_module() {
                 map.insert(key.to_string(), value);
             }
             Some(map)
         }
         "json" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in json::load_json_module() {
                 map.insert(key.to_string(), value);
             }
@@ -44,91 +78,203 @@ pub fn load_module(name: &str) -> Option<HashMap<String, Value>> {
         }
 
         "time" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in time::load_time_module() {
                 map.insert(key.to_string(), value);
             }
             Some(map)
         }
         "cli" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in cli::load_cli_module() {
                 map.insert(key.to_string(), value);
             }
             Some(map)
         }
         "color" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in color::load_color_module() {
                 map.insert(key.to_string(), value);
             }
             Some(map)
         }
         "crypto" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in crypto::load_crypto_module() {
                 map.insert(key.to_string(), value);
             }
             Some(map)
         }
         "os" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in os::load_os_module() {
                 map.insert(key.to_string(), value);
             }
             Some(map)
         }
         "timer" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in timer::load_timer_module() {
                 map.insert(key.to_string(), value);
             }
             Some(map)
         }
         "web" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in web::load_web_module() {
                 map.insert(key.to_string(), value);
             }
             Some(map)
         }
         "url" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in url::load_url_module() {
                 map.insert(key.to_string(), value);
             }
             Some(map)
         }
         "stream" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in stream::load_stream_module() {
                 map.insert(key.to_string(), value);
             }
             Some(map)
         }
         "path" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in path::load_path_module() {
                 map.insert(key.to_string(), value);
             }
             Some(map)
         }
         "process" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in process::load_process_module() {
                 map.insert(key.to_string(), value);
             }
             Some(map)
         }
         "git" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in git::load_git_module() {
                 map.insert(key.to_string(), value);
             }
             Some(map)
         }
+        "assert" => {
+            let mut map = RelicMap::new();
+            for (key, value) in assert::load_assert_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "random" => {
+            let mut map = RelicMap::new();
+            for (key, value) in random::load_random_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "zip" => {
+            let mut map = RelicMap::new();
+            for (key, value) in zip::load_zip_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "gzip" => {
+            let mut map = RelicMap::new();
+            for (key, value) in zip::load_gzip_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "csv" => {
+            let mut map = RelicMap::new();
+            for (key, value) in csv::load_csv_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "yaml" => {
+            let mut map = RelicMap::new();
+            for (key, value) in yaml::load_yaml_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "toml" => {
+            let mut map = RelicMap::new();
+            for (key, value) in toml::load_toml_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "mail" => {
+            let mut map = RelicMap::new();
+            for (key, value) in mail::load_mail_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "log" => {
+            let mut map = RelicMap::new();
+            for (key, value) in log::load_log_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "template" => {
+            let mut map = RelicMap::new();
+            for (key, value) in template::load_template_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "grpc" => {
+            let mut map = RelicMap::new();
+            for (key, value) in grpc::load_grpc_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "redis" => {
+            let mut map = RelicMap::new();
+            for (key, value) in redis::load_redis_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "sql" => {
+            let mut map = RelicMap::new();
+            for (key, value) in sql::load_sql_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "s3" => {
+            let mut map = RelicMap::new();
+            for (key, value) in s3::load_s3_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "collections" => {
+            let mut map = RelicMap::new();
+            for (key, value) in collections::load_collections_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
+        "sync" => {
+            let mut map = RelicMap::new();
+            for (key, value) in sync::load_sync_module() {
+                map.insert(key.to_string(), value);
+            }
+            Some(map)
+        }
         "requesty" => {
-            let mut map = HashMap::new();
+            let mut map = RelicMap::new();
             for (key, value) in requesty::load_requesty_module() {
                 map.insert(key.to_string(), value);
             }
@@ -138,7 +284,7 @@ pub fn load_module(name: &str) -> Option<HashMap<String, Value>> {
     }
 }
 
-pub fn call_builtin(name: &str, args: Vec<Value>) -> Result<Value, FlowError> {
+pub fn call_builtin(name: &str, args: Vec<Value>, out: &dyn crate::interpreter::output::OutputSink) -> Result<Value, FlowError> {
     match name {
         "whisper" => {
             if args.len() != 1 {
@@ -148,7 +294,7 @@ pub fn call_builtin(name: &str, args: Vec<Value>) -> Result<Value, FlowError> {
                     0,
                 ));
             }
-            io::whisper(&args[0].to_string());
+            io::whisper(out, &args[0].to_string());
             Ok(Value::Null)
         }
         "shout" => {
@@ -159,7 +305,7 @@ pub fn call_builtin(name: &str, args: Vec<Value>) -> Result<Value, FlowError> {
                     0,
                 ));
             }
-            io::shout(&args[0].to_string());
+            io::shout(out, &args[0].to_string());
             Ok(Value::Null)
         }
         "roar" => {
@@ -170,7 +316,7 @@ pub fn call_builtin(name: &str, args: Vec<Value>) -> Result<Value, FlowError> {
                     0,
                 ));
             }
-            io::roar(&args[0].to_string());
+            io::roar(out, &args[0].to_string());
             Ok(Value::Null)
         }
         "chant" => {
@@ -181,9 +327,35 @@ pub fn call_builtin(name: &str, args: Vec<Value>) -> Result<Value, FlowError> {
                     0,
                 ));
             }
-            io::chant(&args[0].to_string());
+            io::chant(out, &args[0].to_string());
             Ok(Value::Null)
         }
+        "forge" => match args.split_first() {
+            Some((Value::String(template), rest)) => Ok(Value::String(Arc::new(format::forge(template, rest)?))),
+            Some(_) => Err(FlowError::type_error("forge() expects a Silk template first", 0, 0)),
+            None => Err(FlowError::runtime("forge() expects a template and its values", 0, 0)),
+        },
+        "deepEquals" => match args.as_slice() {
+            [a, b] => Ok(Value::Boolean(a.deep_equals(b))),
+            _ => Err(FlowError::runtime("deepEquals() expects 2 arguments", 0, 0)),
+        },
+        "compose" => spell::compose(args),
+        "pipe" => spell::pipe(args),
+        "clone" => match args.as_slice() {
+            [value] => Ok(value.deep_clone()),
+            _ => Err(FlowError::runtime("clone() expects 1 argument", 0, 0)),
+        },
+        "freeze" => match args.as_slice() {
+            [value] => match value.find_live_part("value") {
+                Some((path, kind)) => Err(FlowError::type_error(
+                    &format!("freeze() only takes plain data, but {} is a {}", path, kind),
+                    0,
+                    0,
+                )),
+                None => Ok(value.deep_clone()),
+            },
+            _ => Err(FlowError::runtime("freeze() expects 1 argument", 0, 0)),
+        },
         _ => Err(FlowError::undefined(
             &format!("Unknown built-in function: {}", name),
             0,
@@ -193,5 +365,9 @@ pub fn call_builtin(name: &str, args: Vec<Value>) -> Result<Value, FlowError> {
 }
 
 pub fn is_builtin(name: &str) -> bool {
-    matches!(name, "whisper" | "shout" | "roar" | "chant" | "drift" | "strike")
+    matches!(
+        name,
+        "whisper" | "shout" | "roar" | "chant" | "forge" | "deepEquals" | "compose" | "pipe" | "clone" | "freeze" | "drift" | "strike"
+            | "evaluate" | "circleDynamic"
+    )
 }
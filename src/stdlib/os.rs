@@ -1,7 +1,8 @@
 use crate::error::FlowError;
-use crate::types::{NativeFn, Value};
+use crate::types::{AsyncContext, AsyncNativeFn, NativeFn, Value, RelicMap};
 use std::sync::Arc;
 use std::env;
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
 
 pub fn load_os_module() -> Vec<(&'static str, Value)> {
     vec![
@@ -10,15 +11,25 @@ pub fn load_os_module() -> Vec<(&'static str, Value)> {
         ("arch", Value::NativeFunction(NativeFn::new(os_arch))),
         ("family", Value::NativeFunction(NativeFn::new(os_family))),
         ("version", Value::NativeFunction(NativeFn::new(os_version))),
+        ("hostname", Value::NativeFunction(NativeFn::new(os_hostname))),
+        ("uptime", Value::NativeFunction(NativeFn::new(os_uptime))),
+        
+        // Hardware
+        ("cpus", Value::NativeFunction(NativeFn::new(os_cpus))),
+        ("totalMemory", Value::NativeFunction(NativeFn::new(os_total_memory))),
+        ("freeMemory", Value::NativeFunction(NativeFn::new(os_free_memory))),
         
         // Environment
         ("env", Value::NativeFunction(NativeFn::new(os_env))),
         ("set_env", Value::NativeFunction(NativeFn::new(os_set_env))),
         ("cwd", Value::NativeFunction(NativeFn::new(os_cwd))),
         ("home_dir", Value::NativeFunction(NativeFn::new(os_home_dir))),
+        ("homeDir", Value::NativeFunction(NativeFn::new(os_home_dir))),
+        ("tempDir", Value::NativeFunction(NativeFn::new(os_temp_dir))),
         
         // Process
         ("pid", Value::NativeFunction(NativeFn::new(os_pid))),
+        ("onUncaught", Value::AsyncNativeFunction(AsyncNativeFn::new(os_on_uncaught))),
     ]
 }
 
@@ -59,6 +70,53 @@ fn os_version(_args: Vec<Value>) -> Result<Value, FlowError> {
     }
 }
 
+// Get the machine's hostname
+fn os_hostname(_args: Vec<Value>) -> Result<Value, FlowError> {
+    match System::host_name() {
+        Some(name) => Ok(Value::String(Arc::new(name))),
+        None => Err(FlowError::runtime("Failed to get hostname", 0, 0)),
+    }
+}
+
+// Get seconds since the machine booted
+fn os_uptime(_args: Vec<Value>) -> Result<Value, FlowError> {
+    Ok(Value::Number(System::uptime() as f64))
+}
+
+// Get logical CPUs as Relics { model, speed } (speed in MHz)
+fn os_cpus(_args: Vec<Value>) -> Result<Value, FlowError> {
+    let system = System::new_with_specifics(
+        RefreshKind::new().with_cpu(CpuRefreshKind::new().with_frequency()),
+    );
+
+    let cpus = system
+        .cpus()
+        .iter()
+        .map(|cpu| {
+            let mut info = RelicMap::new();
+            info.insert("model".to_string(), Value::String(Arc::new(cpu.brand().trim().to_string())));
+            info.insert("speed".to_string(), Value::Number(cpu.frequency() as f64));
+            Value::Relic(Arc::new(info))
+        })
+        .collect();
+
+    Ok(Value::Array(Arc::new(cpus)))
+}
+
+fn memory_snapshot() -> System {
+    System::new_with_specifics(RefreshKind::new().with_memory(MemoryRefreshKind::new().with_ram()))
+}
+
+// Get total physical memory in bytes
+fn os_total_memory(_args: Vec<Value>) -> Result<Value, FlowError> {
+    Ok(Value::Number(memory_snapshot().total_memory() as f64))
+}
+
+// Get memory available to new processes in bytes
+fn os_free_memory(_args: Vec<Value>) -> Result<Value, FlowError> {
+    Ok(Value::Number(memory_snapshot().available_memory() as f64))
+}
+
 // Get environment variable
 fn os_env(args: Vec<Value>) -> Result<Value, FlowError> {
     if args.len() != 1 {
@@ -120,7 +178,48 @@ fn os_home_dir(_args: Vec<Value>) -> Result<Value, FlowError> {
     }
 }
 
+// Get the system temporary directory
+fn os_temp_dir(_args: Vec<Value>) -> Result<Value, FlowError> {
+    Ok(Value::String(Arc::new(env::temp_dir().to_string_lossy().to_string())))
+}
+
 // Get process ID
 fn os_pid(_args: Vec<Value>) -> Result<Value, FlowError> {
     Ok(Value::Number(std::process::id() as f64))
 }
+
+// Register a Spell to call with an error nothing rescued, just before the process
+// exits with that error's exit code. A later call replaces it.
+async fn os_on_uncaught(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    match args.as_slice() {
+        [handler] if handler.type_name() == "Spell" => {
+            ctx.runtime.set_uncaught_handler(handler.clone());
+            Ok(Value::Null)
+        }
+        [other] => Err(FlowError::type_error(
+            &format!("os.onUncaught expects a Spell, got {}", other.type_name()),
+            0, 0,
+        )),
+        _ => Err(FlowError::runtime("os.onUncaught expects 1 argument (spell)", 0, 0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::Interpreter;
+
+    #[tokio::test]
+    async fn test_on_uncaught_sees_the_error() {
+        let source = "circle os from \"std:os\"\nos.onUncaught(cast Spell (err) {\n    os.set_env(\"FLOWLANG_UNCAUGHT_TEST\", err.type + \" \" + err.exitCode + \" \" + err.line)\n})\nrupture Rift \"offline\"\n";
+        let program = crate::lexer::tokenize(source).and_then(crate::parser::parse).unwrap();
+
+        let mut interpreter = Interpreter::new(Default::default());
+        let (result, _) = interpreter.execute_captured(program).await;
+        let err = result.unwrap_err();
+        assert_eq!(err.exit_code(), 10);
+        assert!(std::env::var("FLOWLANG_UNCAUGHT_TEST").is_err());
+
+        interpreter.report_uncaught(&err).await.unwrap();
+        assert_eq!(std::env::var("FLOWLANG_UNCAUGHT_TEST").unwrap(), "Rift 10 5");
+    }
+}
@@ -1,9 +1,12 @@
 //! std:path - Path manipulation module (Node.js-style)
+//!
+//! Paths are handled as strings rather than through `std::path`, so both `/` and `\`
+//! are accepted as separators and Windows roots (`C:\`, `\\server\share`) are
+//! understood on every platform. Results always use the platform separator.
 
 use crate::error::FlowError;
-use crate::types::{NativeFn, Value};
-use std::collections::HashMap;
-use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+use crate::types::{NativeFn, Value, RelicMap};
+use std::path::{MAIN_SEPARATOR, MAIN_SEPARATOR_STR};
 use std::sync::Arc;
 
 pub fn load_path_module() -> Vec<(&'static str, Value)> {
@@ -18,23 +21,164 @@ pub fn load_path_module() -> Vec<(&'static str, Value)> {
         ("normalize", Value::NativeFunction(NativeFn::new(path_normalize))),
         ("isAbsolute", Value::NativeFunction(NativeFn::new(path_is_absolute))),
         ("relative", Value::NativeFunction(NativeFn::new(path_relative))),
+        ("toPosix", Value::NativeFunction(NativeFn::new(path_to_posix))),
+        ("toNative", Value::NativeFunction(NativeFn::new(path_to_native))),
         ("sep", Value::String(Arc::new(MAIN_SEPARATOR.to_string()))),
+        ("delimiter", Value::String(Arc::new(if cfg!(windows) { ";" } else { ":" }.to_string()))),
     ]
 }
 
-/// path.join(...parts) -> Silk
-/// Join path segments with platform separator
-fn path_join(args: Vec<Value>) -> Result<Value, FlowError> {
-    if args.is_empty() {
-        return Ok(Value::String(Arc::new(String::new())));
+fn is_sep(c: char) -> bool {
+    c == '/' || c == '\\'
+}
+
+/// A path split into its root and the segments after it.
+/// `root` is "", "/", "C:", "C:/" or "//server/share/" (always with forward slashes).
+struct SplitPath<'a> {
+    root: String,
+    segments: Vec<&'a str>,
+}
+
+impl<'a> SplitPath<'a> {
+    fn new(path: &'a str) -> Self {
+        let (root, rest) = split_root(path);
+        SplitPath {
+            root,
+            segments: rest.split(is_sep).filter(|s| !s.is_empty()).collect(),
+        }
+    }
+
+    fn is_absolute(&self) -> bool {
+        self.root.ends_with('/')
+    }
+
+    /// Resolve "." and ".." segments. ".." never climbs above an absolute root.
+    fn normalized(&self) -> Vec<&'a str> {
+        let mut out: Vec<&str> = Vec::new();
+        for segment in &self.segments {
+            match *segment {
+                "." => {}
+                ".." => match out.last() {
+                    Some(&last) if last != ".." => {
+                        out.pop();
+                    }
+                    _ if self.is_absolute() => {}
+                    _ => out.push(".."),
+                },
+                other => out.push(other),
+            }
+        }
+        out
     }
+}
+
+fn split_root(path: &str) -> (String, &str) {
+    let bytes = path.as_bytes();
+
+    // UNC: \\server\share
+    if bytes.len() > 2 && is_sep(bytes[0] as char) && is_sep(bytes[1] as char) && !is_sep(bytes[2] as char) {
+        let mut parts = path[2..].splitn(3, is_sep);
+        let server = parts.next().unwrap_or("");
+        if let Some(share) = parts.next().filter(|s| !s.is_empty()) {
+            let rest = parts.next().unwrap_or("");
+            return (format!("//{}/{}/", server, share), rest);
+        }
+    }
+
+    // Drive letter: C:\ or drive-relative C:
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        let drive = &path[..2];
+        return match path[2..].chars().next() {
+            Some(c) if is_sep(c) => (format!("{}/", drive), &path[3..]),
+            _ => (drive.to_string(), &path[2..]),
+        };
+    }
+
+    match path.chars().next() {
+        Some(c) if is_sep(c) => ("/".to_string(), path.trim_start_matches(is_sep)),
+        _ => (String::new(), path),
+    }
+}
+
+fn native(path: &str) -> String {
+    path.replace(['/', '\\'], MAIN_SEPARATOR_STR)
+}
 
-    let mut path = PathBuf::new();
-    for arg in args {
-        path.push(arg.to_string());
+/// Build a normalized path string using the platform separator
+fn build(root: &str, segments: &[&str]) -> String {
+    let body = segments.join("/");
+    let path = match (root.is_empty(), body.is_empty()) {
+        (true, true) => ".".to_string(),
+        (true, false) => body,
+        (false, _) => format!("{}{}", root, body),
+    };
+    native(&path)
+}
+
+fn normalize_str(path: &str) -> String {
+    let split = SplitPath::new(path);
+    build(&split.root, &split.normalized())
+}
+
+fn resolve_str(paths: &[String]) -> String {
+    let mut current = std::env::current_dir()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "/".to_string());
+    for path in paths {
+        if path.is_empty() {
+            continue;
+        }
+        if SplitPath::new(path).is_absolute() {
+            current = path.clone();
+        } else {
+            current = format!("{}/{}", current, path);
+        }
+    }
+    normalize_str(&current)
+}
+
+/// Last segment of a path, ignoring trailing separators
+fn base_of(path: &str) -> &str {
+    let (_, rest) = split_root(path);
+    rest.trim_end_matches(is_sep).rsplit(is_sep).next().unwrap_or("")
+}
+
+/// Extension including the dot. Dotfiles like ".bashrc" have none.
+fn ext_of(base: &str) -> &str {
+    match base.rfind('.') {
+        Some(0) | None => "",
+        Some(_) if base == ".." => "",
+        Some(i) => &base[i..],
+    }
+}
+
+fn dir_of(path: &str) -> String {
+    let (root, rest) = split_root(path);
+    let rest = rest.trim_end_matches(is_sep);
+    match rest.rfind(is_sep) {
+        Some(i) => native(&format!("{}{}", root, rest[..i].trim_end_matches(is_sep))),
+        None if !root.is_empty() => native(&root),
+        None => ".".to_string(),
+    }
+}
+
+fn string_arg(args: &[Value], index: usize, func: &str) -> Result<String, FlowError> {
+    match args.get(index) {
+        Some(Value::String(s)) => Ok(s.to_string()),
+        Some(_) => Err(FlowError::type_error(&format!("path.{} expects Silk paths", func), 0, 0)),
+        None => Err(FlowError::runtime(&format!("path.{} is missing a path argument", func), 0, 0)),
+    }
+}
+
+/// path.join(...parts) -> Silk
+/// Join path segments with platform separator and normalize the result
+fn path_join(args: Vec<Value>) -> Result<Value, FlowError> {
+    let parts: Vec<String> = args.iter().map(|a| a.to_string()).filter(|p| !p.is_empty()).collect();
+    if parts.is_empty() {
+        return Ok(Value::String(Arc::new(".".to_string())));
     }
 
-    Ok(Value::String(Arc::new(path.to_string_lossy().to_string())))
+    Ok(Value::String(Arc::new(normalize_str(&parts.join("/")))))
 }
 
 /// path.dirname(path) -> Silk
@@ -44,13 +188,8 @@ fn path_dirname(args: Vec<Value>) -> Result<Value, FlowError> {
         return Err(FlowError::runtime("path.dirname expects 1 argument (path)", 0, 0));
     }
 
-    let path_str = args[0].to_string();
-    let path = Path::new(&path_str);
-    let dirname = path.parent()
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|| ".".to_string());
-
-    Ok(Value::String(Arc::new(dirname)))
+    let path_str = string_arg(&args, 0, "dirname")?;
+    Ok(Value::String(Arc::new(dir_of(&path_str))))
 }
 
 /// path.basename(path, ext?) -> Silk
@@ -60,21 +199,18 @@ fn path_basename(args: Vec<Value>) -> Result<Value, FlowError> {
         return Err(FlowError::runtime("path.basename expects 1-2 arguments (path, ext?)", 0, 0));
     }
 
-    let path_str = args[0].to_string();
-    let path = Path::new(&path_str);
-    let basename = path.file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_default();
+    let path_str = string_arg(&args, 0, "basename")?;
+    let basename = base_of(&path_str);
 
     // If extension provided, remove it
     if args.len() > 1 {
         let ext = args[1].to_string();
-        if basename.ends_with(&ext) {
+        if basename.ends_with(&ext) && basename != ext {
             return Ok(Value::String(Arc::new(basename[..basename.len() - ext.len()].to_string())));
         }
     }
 
-    Ok(Value::String(Arc::new(basename)))
+    Ok(Value::String(Arc::new(basename.to_string())))
 }
 
 /// path.extname(path) -> Silk
@@ -84,13 +220,8 @@ fn path_extname(args: Vec<Value>) -> Result<Value, FlowError> {
         return Err(FlowError::runtime("path.extname expects 1 argument (path)", 0, 0));
     }
 
-    let path_str = args[0].to_string();
-    let path = Path::new(&path_str);
-    let ext = path.extension()
-        .map(|e| format!(".{}", e.to_string_lossy()))
-        .unwrap_or_default();
-
-    Ok(Value::String(Arc::new(ext)))
+    let path_str = string_arg(&args, 0, "extname")?;
+    Ok(Value::String(Arc::new(ext_of(base_of(&path_str)).to_string())))
 }
 
 /// path.parse(path) -> Relic
@@ -100,48 +231,22 @@ fn path_parse(args: Vec<Value>) -> Result<Value, FlowError> {
         return Err(FlowError::runtime("path.parse expects 1 argument (path)", 0, 0));
     }
 
-    let path_str = args[0].to_string();
-    let path = Path::new(&path_str);
-
-    let mut map = HashMap::new();
+    let path_str = string_arg(&args, 0, "parse")?;
+    let (root, _) = split_root(&path_str);
 
-    // root (e.g., "/" on Unix, "C:\" on Windows)
-    let root = if path.is_absolute() {
-        #[cfg(windows)]
-        {
-            path.components().next()
-                .map(|c| c.as_os_str().to_string_lossy().to_string())
-                .unwrap_or_default()
-        }
-        #[cfg(not(windows))]
-        {
-            "/".to_string()
-        }
-    } else {
+    let base = base_of(&path_str).to_string();
+    let ext = ext_of(&base).to_string();
+    let name = base[..base.len() - ext.len()].to_string();
+    let dir = if base.is_empty() { native(&root) } else { dir_of(&path_str) };
+    // A bare file name has no directory (Node returns "" here, not ".")
+    let dir = if root.is_empty() && !path_str.trim_end_matches(is_sep).contains(is_sep) {
         String::new()
+    } else {
+        dir
     };
 
-    // dir (directory portion)
-    let dir = path.parent()
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_default();
-
-    // base (filename with extension)
-    let base = path.file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_default();
-
-    // ext (extension with dot)
-    let ext = path.extension()
-        .map(|e| format!(".{}", e.to_string_lossy()))
-        .unwrap_or_default();
-
-    // name (filename without extension)
-    let name = path.file_stem()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_default();
-
-    map.insert("root".to_string(), Value::String(Arc::new(root)));
+    let mut map = RelicMap::new();
+    map.insert("root".to_string(), Value::String(Arc::new(native(&root))));
     map.insert("dir".to_string(), Value::String(Arc::new(dir)));
     map.insert("base".to_string(), Value::String(Arc::new(base)));
     map.insert("ext".to_string(), Value::String(Arc::new(ext)));
@@ -162,94 +267,55 @@ fn path_format(args: Vec<Value>) -> Result<Value, FlowError> {
         _ => return Err(FlowError::type_error("path.format expects a Relic", 0, 0)),
     };
 
-    // If dir and base are provided, use them
-    let dir = map.get("dir").map(|v| v.to_string()).unwrap_or_default();
-    let base = map.get("base").map(|v| v.to_string()).unwrap_or_default();
+    let field = |key: &str| map.get(key).map(|v| v.to_string()).unwrap_or_default();
 
-    if !base.is_empty() {
-        if dir.is_empty() {
-            return Ok(Value::String(Arc::new(base)));
-        }
-        let mut path = PathBuf::from(&dir);
-        path.push(&base);
-        return Ok(Value::String(Arc::new(path.to_string_lossy().to_string())));
-    }
-
-    // Otherwise use name + ext
-    let name = map.get("name").map(|v| v.to_string()).unwrap_or_default();
-    let ext = map.get("ext").map(|v| v.to_string()).unwrap_or_default();
-
-    let filename = format!("{}{}", name, ext);
-    if dir.is_empty() {
-        return Ok(Value::String(Arc::new(filename)));
+    // base wins over name + ext, dir wins over root
+    let mut base = field("base");
+    if base.is_empty() {
+        base = format!("{}{}", field("name"), field("ext"));
     }
+    let dir = match field("dir") {
+        dir if dir.is_empty() => field("root"),
+        dir => dir,
+    };
 
-    let mut path = PathBuf::from(&dir);
-    path.push(&filename);
-    Ok(Value::String(Arc::new(path.to_string_lossy().to_string())))
+    let path = if dir.is_empty() {
+        base
+    } else if dir.ends_with(is_sep) {
+        format!("{}{}", dir, base)
+    } else {
+        format!("{}{}{}", dir, MAIN_SEPARATOR, base)
+    };
+    Ok(Value::String(Arc::new(native(&path))))
 }
 
 /// path.resolve(...paths) -> Silk
-/// Resolve path segments to absolute path
+/// Resolve path segments to a normalized absolute path
 fn path_resolve(args: Vec<Value>) -> Result<Value, FlowError> {
-    let mut path = std::env::current_dir().unwrap_or_default();
-
-    for arg in args {
-        let segment = arg.to_string();
-        let segment_path = Path::new(&segment);
-        
-        if segment_path.is_absolute() {
-            path = PathBuf::from(segment);
-        } else {
-            path.push(segment);
-        }
-    }
-
-    // Normalize the path
-    Ok(Value::String(Arc::new(path.to_string_lossy().to_string())))
+    let paths: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    Ok(Value::String(Arc::new(resolve_str(&paths))))
 }
 
 /// path.normalize(path) -> Silk
-/// Normalize a path, resolving '..' and '.'
+/// Normalize a path, resolving '..' and '.' without touching the file system
 fn path_normalize(args: Vec<Value>) -> Result<Value, FlowError> {
     if args.is_empty() {
         return Err(FlowError::runtime("path.normalize expects 1 argument (path)", 0, 0));
     }
 
-    let path_str = args[0].to_string();
-    let path = PathBuf::from(&path_str);
-
-    // Simple normalization using canonicalize if exists, otherwise just clean up
-    let normalized = if path.exists() {
-        path.canonicalize()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or(path_str)
-    } else {
-        // Manual normalization for non-existent paths
-        let mut components: Vec<&str> = Vec::new();
-        for part in path_str.split(['/', '\\']) {
-            match part {
-                "" | "." => continue,
-                ".." => { components.pop(); }
-                p => components.push(p),
-            }
-        }
-        components.join(&MAIN_SEPARATOR.to_string())
-    };
-
-    Ok(Value::String(Arc::new(normalized)))
+    let path_str = string_arg(&args, 0, "normalize")?;
+    Ok(Value::String(Arc::new(normalize_str(&path_str))))
 }
 
 /// path.isAbsolute(path) -> Pulse
-/// Check if path is absolute
+/// Check if path is absolute ("/x", "\x", "C:\x" and "\\server\share" all count)
 fn path_is_absolute(args: Vec<Value>) -> Result<Value, FlowError> {
     if args.is_empty() {
         return Err(FlowError::runtime("path.isAbsolute expects 1 argument (path)", 0, 0));
     }
 
-    let path_str = args[0].to_string();
-    let path = Path::new(&path_str);
-    Ok(Value::Boolean(path.is_absolute()))
+    let path_str = string_arg(&args, 0, "isAbsolute")?;
+    Ok(Value::Boolean(SplitPath::new(&path_str).is_absolute()))
 }
 
 /// path.relative(from, to) -> Silk
@@ -259,58 +325,58 @@ fn path_relative(args: Vec<Value>) -> Result<Value, FlowError> {
         return Err(FlowError::runtime("path.relative expects 2 arguments (from, to)", 0, 0));
     }
 
-    let from = PathBuf::from(args[0].to_string());
-    let to = PathBuf::from(args[1].to_string());
+    let from = resolve_str(&[string_arg(&args, 0, "relative")?]);
+    let to = resolve_str(&[string_arg(&args, 1, "relative")?]);
+    Ok(Value::String(Arc::new(relative_str(&from, &to))))
+}
 
-    // Simple relative path calculation
-    let from_abs = if from.is_absolute() {
-        from
-    } else {
-        std::env::current_dir().unwrap_or_default().join(&from)
-    };
+fn relative_str(from: &str, to: &str) -> String {
+    let from = SplitPath::new(from);
+    let to = SplitPath::new(to);
 
-    let to_abs = if to.is_absolute() {
-        to
-    } else {
-        std::env::current_dir().unwrap_or_default().join(&to)
-    };
+    // Windows compares paths case-insensitively
+    let same = |a: &str, b: &str| if cfg!(windows) { a.eq_ignore_ascii_case(b) } else { a == b };
 
-    // Try to use pathdiff crate logic manually
-    let relative = pathdiff_relative(&from_abs, &to_abs);
-    Ok(Value::String(Arc::new(relative)))
-}
+    // Different drives or shares have no relative path between them
+    if !same(&from.root, &to.root) {
+        return build(&to.root, &to.segments);
+    }
 
-/// Simple relative path calculation
-fn pathdiff_relative(from: &Path, to: &Path) -> String {
-    let from_components: Vec<_> = from.components().collect();
-    let to_components: Vec<_> = to.components().collect();
+    let common = from
+        .segments
+        .iter()
+        .zip(to.segments.iter())
+        .take_while(|(a, b)| same(a, b))
+        .count();
 
-    // Find common prefix
-    let mut common_len = 0;
-    for (a, b) in from_components.iter().zip(to_components.iter()) {
-        if a == b {
-            common_len += 1;
-        } else {
-            break;
-        }
-    }
+    let mut result: Vec<&str> = vec![".."; from.segments.len() - common];
+    result.extend(&to.segments[common..]);
 
-    // Build relative path
-    let mut result = PathBuf::new();
-    
-    // Add ".." for each remaining component in "from"
-    for _ in common_len..from_components.len() {
-        result.push("..");
+    if result.is_empty() {
+        String::new()
+    } else {
+        native(&result.join("/"))
     }
-    
-    // Add remaining components from "to"
-    for component in &to_components[common_len..] {
-        result.push(component);
+}
+
+/// path.toPosix(path) -> Silk
+/// Convert every separator to '/'
+fn path_to_posix(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.is_empty() {
+        return Err(FlowError::runtime("path.toPosix expects 1 argument (path)", 0, 0));
     }
 
-    if result.as_os_str().is_empty() {
-        ".".to_string()
-    } else {
-        result.to_string_lossy().to_string()
+    let path_str = string_arg(&args, 0, "toPosix")?;
+    Ok(Value::String(Arc::new(path_str.replace('\\', "/"))))
+}
+
+/// path.toNative(path) -> Silk
+/// Convert every separator to the platform separator
+fn path_to_native(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.is_empty() {
+        return Err(FlowError::runtime("path.toNative expects 1 argument (path)", 0, 0));
     }
+
+    let path_str = string_arg(&args, 0, "toNative")?;
+    Ok(Value::String(Arc::new(native(&path_str))))
 }
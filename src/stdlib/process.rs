@@ -1,12 +1,19 @@
 //! std:process - Process/Command execution module
 //!
 //! Provides functions for running external commands and processes.
+//! Every command accepts an options Relic `{ cwd, env, stdin }`; `stream`,
+//! `pipe` and `spawn` cover line-by-line output, pipelines and background
+//! processes that can be stopped with `kill(handle)`.
 
-use crate::types::{NativeFn, Value};
+use crate::types::{AsyncContext, AsyncNativeFn, InterpreterFuture, InterpreterNativeFn, NativeFn, Value, RelicMap};
 use crate::error::FlowError;
-use std::collections::HashMap;
+use crate::interpreter::Interpreter;
+use crate::runtime::handle::HandleType;
+use std::io::Write;
 use std::sync::Arc;
 use std::process::{Command, Stdio};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::{mpsc, oneshot};
 
 /// Load the process module
 pub fn load_process_module() -> Vec<(&'static str, Value)> {
@@ -14,38 +21,156 @@ pub fn load_process_module() -> Vec<(&'static str, Value)> {
         ("exec", Value::NativeFunction(NativeFn::new(process_exec))),
         ("run", Value::NativeFunction(NativeFn::new(process_run))),
         ("output", Value::NativeFunction(NativeFn::new(process_output))),
+        ("pipe", Value::NativeFunction(NativeFn::new(process_pipe))),
+        ("stream", Value::InterpreterNativeFunction(InterpreterNativeFn::new(process_stream))),
+        ("spawn", Value::AsyncNativeFunction(AsyncNativeFn::new(process_spawn))),
+        ("kill", Value::AsyncNativeFunction(AsyncNativeFn::new(process_kill))),
     ]
 }
 
-/// Execute a command string and return the output
-/// proc.exec("ls -la") -> Silk (stdout)
-fn process_exec(args: Vec<Value>) -> Result<Value, FlowError> {
+/// Options shared by all commands: working directory, extra environment
+/// variables and text to write to stdin
+#[derive(Default)]
+struct ProcessOptions {
+    cwd: Option<String>,
+    env: Vec<(String, String)>,
+    stdin: Option<String>,
+    // Callbacks, only used by stream() and spawn()
+    on_stdout: Option<Value>,
+    on_stderr: Option<Value>,
+    on_exit: Option<Value>,
+}
+
+impl ProcessOptions {
+    fn parse(value: Option<&Value>, func: &str) -> Result<Self, FlowError> {
+        let map = match value {
+            None => return Ok(Self::default()),
+            Some(Value::Relic(map)) => map,
+            Some(_) => return Err(FlowError::type_error(&format!("{}() options must be a Relic", func), 0, 0)),
+        };
+
+        let mut options = Self::default();
+        if let Some(cwd) = map.get("cwd") {
+            options.cwd = Some(cwd.to_string());
+        }
+        match map.get("env") {
+            Some(Value::Relic(env)) => {
+                options.env = env.iter().map(|(k, v)| (k.clone(), v.to_string())).collect();
+            }
+            Some(_) => return Err(FlowError::type_error(&format!("{}() option 'env' must be a Relic", func), 0, 0)),
+            None => {}
+        }
+        if let Some(stdin) = map.get("stdin") {
+            options.stdin = Some(stdin.to_string());
+        }
+        for (key, slot) in [
+            ("onStdout", &mut options.on_stdout),
+            ("onStderr", &mut options.on_stderr),
+            ("onExit", &mut options.on_exit),
+        ] {
+            if let Some(callback) = map.get(key) {
+                if callback.type_name() != "Spell" {
+                    return Err(FlowError::type_error(&format!("{}() option '{}' must be a Spell", func, key), 0, 0));
+                }
+                *slot = Some(callback.clone());
+            }
+        }
+
+        Ok(options)
+    }
+
+    fn apply(&self, cmd: &mut Command) {
+        if let Some(cwd) = &self.cwd {
+            cmd.current_dir(cwd);
+        }
+        cmd.envs(self.env.iter().map(|(k, v)| (k, v)));
+    }
+
+    fn apply_async(&self, cmd: &mut tokio::process::Command) {
+        if let Some(cwd) = &self.cwd {
+            cmd.current_dir(cwd);
+        }
+        cmd.envs(self.env.iter().map(|(k, v)| (k, v)));
+    }
+}
+
+/// Split `(program, args?, options?)`, also accepting `(program, options)`
+fn command_parts<'a>(args: &'a [Value], func: &str) -> Result<(String, Vec<String>, Option<&'a Value>), FlowError> {
     if args.is_empty() {
-        return Err(FlowError::runtime("exec() requires a command string", 0, 0));
+        return Err(FlowError::runtime(&format!("{}() requires a program name", func), 0, 0));
     }
 
-    let cmd_str = args[0].to_string();
+    let program = args[0].to_string();
+    let (cmd_args, options) = match args.get(1) {
+        Some(Value::Array(arr)) => (arr.iter().map(|v| v.to_string()).collect(), args.get(2)),
+        Some(opts @ Value::Relic(_)) => (vec![], Some(opts)),
+        Some(other) => (vec![other.to_string()], args.get(2)),
+        None => (vec![], None),
+    };
+
+    Ok((program, cmd_args, options))
+}
 
-    // Determine shell based on OS
+fn shell_command(cmd_str: &str) -> Command {
     #[cfg(windows)]
-    let output = Command::new("cmd")
-        .args(["/C", &cmd_str])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output();
+    {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", cmd_str]);
+        cmd
+    }
 
     #[cfg(not(windows))]
-    let output = Command::new("sh")
-        .args(["-c", &cmd_str])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output();
+    {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", cmd_str]);
+        cmd
+    }
+}
+
+/// Run a command to completion, feeding `stdin` if given, and capture its output
+fn run_captured(mut cmd: Command, options: &ProcessOptions) -> std::io::Result<std::process::Output> {
+    options.apply(&mut cmd);
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    cmd.stdin(if options.stdin.is_some() { Stdio::piped() } else { Stdio::inherit() });
 
-    match output {
+    let mut child = cmd.spawn()?;
+    if let (Some(input), Some(mut stdin)) = (options.stdin.clone(), child.stdin.take()) {
+        // Write from a thread so a full stdout pipe can't deadlock us
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+    }
+    child.wait_with_output()
+}
+
+fn output_relic(output: &std::process::Output) -> Value {
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let code = output.status.code().unwrap_or(-1) as f64;
+
+    let mut result = RelicMap::new();
+    result.insert("stdout".to_string(), Value::String(Arc::new(stdout)));
+    result.insert("stderr".to_string(), Value::String(Arc::new(stderr)));
+    result.insert("code".to_string(), Value::Number(code));
+    result.insert("success".to_string(), Value::Boolean(output.status.success()));
+    Value::Relic(Arc::new(result))
+}
+
+/// Execute a command string and return the output
+/// proc.exec("ls -la", options?) -> Silk (stdout)
+fn process_exec(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.is_empty() {
+        return Err(FlowError::runtime("exec() requires a command string", 0, 0));
+    }
+
+    let cmd_str = args[0].to_string();
+    let options = ProcessOptions::parse(args.get(1), "exec")?;
+
+    match run_captured(shell_command(&cmd_str), &options) {
         Ok(output) => {
             let stdout = String::from_utf8_lossy(&output.stdout).to_string();
             let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            
+
             if output.status.success() {
                 Ok(Value::String(Arc::new(stdout)))
             } else {
@@ -62,32 +187,27 @@ fn process_exec(args: Vec<Value>) -> Result<Value, FlowError> {
 }
 
 /// Run a program with arguments array, return exit code
-/// proc.run("git", ["clone", url, dest]) -> Ember (exit code)
+/// proc.run("git", ["clone", url, dest], options?) -> Ember (exit code)
 fn process_run(args: Vec<Value>) -> Result<Value, FlowError> {
-    if args.is_empty() {
-        return Err(FlowError::runtime("run() requires a program name", 0, 0));
-    }
+    let (program, cmd_args, options) = command_parts(&args, "run")?;
+    let options = ProcessOptions::parse(options, "run")?;
 
-    let program = args[0].to_string();
-    
-    // Get arguments array
-    let cmd_args: Vec<String> = if args.len() > 1 {
-        match &args[1] {
-            Value::Array(arr) => {
-                arr.iter().map(|v| v.to_string()).collect()
+    let mut cmd = Command::new(crate::platform::resolve_program(&program));
+    cmd.args(&cmd_args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+    options.apply(&mut cmd);
+
+    let status = match &options.stdin {
+        Some(input) => cmd.stdin(Stdio::piped()).spawn().and_then(|mut child| {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(input.as_bytes())?;
             }
-            _ => vec![args[1].to_string()],
-        }
-    } else {
-        vec![]
+            child.wait()
+        }),
+        None => cmd.status(),
     };
 
-    let status = Command::new(&program)
-        .args(&cmd_args)
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .status();
-
     match status {
         Ok(status) => {
             let code = status.code().unwrap_or(-1) as f64;
@@ -98,46 +218,320 @@ fn process_run(args: Vec<Value>) -> Result<Value, FlowError> {
 }
 
 /// Run a program and capture output as a Relic {stdout, stderr, code}
-/// proc.output("git", ["status"]) -> Relic
+/// proc.output("git", ["status"], options?) -> Relic
 fn process_output(args: Vec<Value>) -> Result<Value, FlowError> {
-    if args.is_empty() {
-        return Err(FlowError::runtime("output() requires a program name", 0, 0));
+    let (program, cmd_args, options) = command_parts(&args, "output")?;
+    let options = ProcessOptions::parse(options, "output")?;
+
+    let mut cmd = Command::new(crate::platform::resolve_program(&program));
+    cmd.args(&cmd_args);
+
+    match run_captured(cmd, &options) {
+        Ok(output) => Ok(output_relic(&output)),
+        Err(e) => Err(FlowError::runtime(&format!("Failed to run '{}': {}", program, e), 0, 0)),
     }
+}
 
-    let program = args[0].to_string();
-    
-    // Get arguments array
-    let cmd_args: Vec<String> = if args.len() > 1 {
-        match &args[1] {
-            Value::Array(arr) => {
-                arr.iter().map(|v| v.to_string()).collect()
+/// Connect commands stdout-to-stdin, like a shell pipeline
+/// proc.pipe([["cat", "log.txt"], ["grep", "ERROR"], ["wc", "-l"]], options?) -> Relic
+/// The result has the last command's stdout and code; stderr is collected from all of them.
+fn process_pipe(args: Vec<Value>) -> Result<Value, FlowError> {
+    let commands = match args.first() {
+        Some(Value::Array(commands)) if !commands.is_empty() => commands.clone(),
+        _ => return Err(FlowError::type_error(
+            "pipe() expects a Constellation of commands, e.g. [[\"ls\"], [\"grep\", \"flow\"]]",
+            0, 0,
+        )),
+    };
+    let options = ProcessOptions::parse(args.get(1), "pipe")?;
+
+    let mut children = Vec::new();
+    let mut previous_stdout: Option<std::process::ChildStdout> = None;
+
+    for (i, command) in commands.iter().enumerate() {
+        let parts = match command {
+            Value::Array(parts) if !parts.is_empty() => parts,
+            _ => return Err(FlowError::type_error(
+                "pipe() expects each command to be a Constellation [program, ...args]",
+                0, 0,
+            )),
+        };
+        let program = parts[0].to_string();
+
+        let mut cmd = Command::new(crate::platform::resolve_program(&program));
+        cmd.args(parts[1..].iter().map(|v| v.to_string()));
+        options.apply(&mut cmd);
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        match previous_stdout.take() {
+            Some(stdout) => { cmd.stdin(Stdio::from(stdout)); }
+            None if options.stdin.is_some() => { cmd.stdin(Stdio::piped()); }
+            None => { cmd.stdin(Stdio::inherit()); }
+        }
+
+        let mut child = cmd.spawn().map_err(|e| {
+            FlowError::runtime(&format!("Failed to run '{}': {}", program, e), 0, 0)
+        })?;
+
+        if i == 0 {
+            if let (Some(input), Some(mut stdin)) = (options.stdin.clone(), child.stdin.take()) {
+                std::thread::spawn(move || {
+                    let _ = stdin.write_all(input.as_bytes());
+                });
             }
-            _ => vec![args[1].to_string()],
         }
-    } else {
-        vec![]
-    };
+        if i + 1 < commands.len() {
+            previous_stdout = child.stdout.take();
+        }
+        children.push(child);
+    }
+
+    // Drain stderr of the earlier stages in threads so none of them block
+    let last = children.pop().expect("pipe() has at least one command");
+    let mut stderr_readers = Vec::new();
+    for child in children.iter_mut() {
+        if let Some(mut stderr) = child.stderr.take() {
+            stderr_readers.push(std::thread::spawn(move || {
+                let mut buf = Vec::new();
+                let _ = std::io::Read::read_to_end(&mut stderr, &mut buf);
+                buf
+            }));
+        }
+    }
+
+    let mut output = last.wait_with_output().map_err(|e| {
+        FlowError::runtime(&format!("Failed to run pipeline: {}", e), 0, 0)
+    })?;
+    for child in children.iter_mut() {
+        let _ = child.wait();
+    }
+
+    let mut stderr = Vec::new();
+    for reader in stderr_readers {
+        stderr.extend(reader.join().unwrap_or_default());
+    }
+    stderr.append(&mut output.stderr);
+    output.stderr = stderr;
+
+    Ok(output_relic(&output))
+}
+
+/// Which stream a line of output came from
+enum OutputLine {
+    Stdout(String),
+    Stderr(String),
+}
+
+fn forward_lines<R>(reader: R, tx: mpsc::UnboundedSender<OutputLine>, wrap: fn(String) -> OutputLine)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if tx.send(wrap(line)).is_err() {
+                break;
+            }
+        }
+    });
+}
 
-    let output = Command::new(&program)
-        .args(&cmd_args)
+fn spawn_async(program: &str, cmd_args: &[String], options: &ProcessOptions) -> Result<tokio::process::Child, FlowError> {
+    let mut cmd = tokio::process::Command::new(crate::platform::resolve_program(program));
+    cmd.args(cmd_args)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .output();
+        .stdin(if options.stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .kill_on_drop(true);
+    options.apply_async(&mut cmd);
 
-    match output {
-        Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            let code = output.status.code().unwrap_or(-1) as f64;
+    let mut child = cmd.spawn().map_err(|e| {
+        FlowError::runtime(&format!("Failed to run '{}': {}", program, e), 0, 0)
+    })?;
+
+    if let (Some(input), Some(mut stdin)) = (options.stdin.clone(), child.stdin.take()) {
+        tokio::spawn(async move {
+            use tokio::io::AsyncWriteExt;
+            let _ = stdin.write_all(input.as_bytes()).await;
+        });
+    }
+
+    Ok(child)
+}
+
+/// Start a program and send each stdout line through a channel that holds at most
+/// `capacity` lines. When the reader falls behind, the pipe fills and the program
+/// waits; dropping the receiver kills it. A non-zero exit arrives as a final error.
+pub(crate) fn stdout_lines(args: &[Value], func: &str, capacity: usize) -> Result<mpsc::Receiver<Result<String, FlowError>>, FlowError> {
+    let (program, cmd_args, options) = command_parts(args, func)?;
+    let options = ProcessOptions::parse(options, func)?;
 
-            let mut result = HashMap::new();
-            result.insert("stdout".to_string(), Value::String(Arc::new(stdout)));
-            result.insert("stderr".to_string(), Value::String(Arc::new(stderr)));
-            result.insert("code".to_string(), Value::Number(code));
-            result.insert("success".to_string(), Value::Boolean(output.status.success()));
+    let mut child = spawn_async(&program, &cmd_args, &options)?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let (tx, rx) = mpsc::channel(capacity);
 
-            Ok(Value::Relic(Arc::new(result)))
+    tokio::spawn(async move {
+        // Keep stderr drained so the program can't block on it; the text goes into the exit error
+        let stderr_text = tokio::spawn(async move {
+            let mut text = String::new();
+            if let Some(stderr) = stderr {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    text.push_str(&line);
+                    text.push('\n');
+                }
+            }
+            text
+        });
+
+        if let Some(stdout) = stdout {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if tx.send(Ok(line)).await.is_err() {
+                    // Nobody is reading any more; dropping the child kills it
+                    return;
+                }
+            }
         }
-        Err(e) => Err(FlowError::runtime(&format!("Failed to run '{}': {}", program, e), 0, 0)),
+
+        let code = child.wait().await.ok().and_then(|s| s.code()).unwrap_or(-1);
+        if code != 0 {
+            let stderr_text = stderr_text.await.unwrap_or_default();
+            let mut message = format!("'{}' exited with code {}", program, code);
+            if !stderr_text.trim().is_empty() {
+                message.push_str(&format!(": {}", stderr_text.trim()));
+            }
+            let _ = tx.send(Err(FlowError::runtime(&message, 0, 0))).await;
+        }
+    });
+
+    Ok(rx)
+}
+
+/// Run a program and call Spells with each line of output as it arrives
+/// proc.stream("npm", ["install"], { onStdout, onStderr, cwd, env }) -> Relic { code, success }
+fn process_stream(args: Vec<Value>, interp: &mut Interpreter) -> InterpreterFuture<'_> {
+    Box::pin(async move {
+        let (program, cmd_args, options) = command_parts(&args, "stream")?;
+        let options = ProcessOptions::parse(options, "stream")?;
+
+        let mut child = spawn_async(&program, &cmd_args, &options)?;
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, tx.clone(), OutputLine::Stdout);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, tx, OutputLine::Stderr);
+        }
+
+        // The channel closes once both readers hit end of file
+        while let Some(line) = rx.recv().await {
+            let (callback, text) = match line {
+                OutputLine::Stdout(text) => (&options.on_stdout, text),
+                OutputLine::Stderr(text) => (&options.on_stderr, text),
+            };
+            if let Some(callback) = callback {
+                interp.execute_function(callback.clone(), vec![Value::String(Arc::new(text))]).await?;
+            }
+        }
+
+        let status = child.wait().await.map_err(|e| {
+            FlowError::runtime(&format!("Failed to wait for '{}': {}", program, e), 0, 0)
+        })?;
+
+        let mut result = RelicMap::new();
+        result.insert("code".to_string(), Value::Number(status.code().unwrap_or(-1) as f64));
+        result.insert("success".to_string(), Value::Boolean(status.success()));
+        Ok(Value::Relic(Arc::new(result)))
+    })
+}
+
+/// Start a program in the background and return its Handle
+/// proc.spawn("python3", ["-m", "http.server"], { onStdout, onStderr, onExit }) -> Handle
+/// Callbacks run on the event loop; the handle stays active until the process exits.
+async fn process_spawn(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    let (program, cmd_args, options) = command_parts(&args, "spawn")?;
+    let options = ProcessOptions::parse(options, "spawn")?;
+
+    let mut child = spawn_async(&program, &cmd_args, &options)?;
+    let pid = child.id().unwrap_or(0);
+
+    let (kill_tx, kill_rx) = oneshot::channel::<()>();
+    let handle_id = ctx.runtime.register_handle(HandleType::Process {
+        pid,
+        kill_tx: Some(kill_tx),
+    }).await;
+
+    let callback_tx = ctx.runtime.callback_sender();
+    let runtime = ctx.runtime.clone();
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(stdout, tx.clone(), OutputLine::Stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(stderr, tx, OutputLine::Stderr);
+    }
+
+    tokio::spawn(async move {
+        let send = |callback: &Option<Value>, arg: Value| {
+            if let Some(callback) = callback {
+                let _ = callback_tx.send(crate::runtime::CallbackRequest {
+                    callback: callback.clone(),
+                    args: vec![arg],
+                });
+            }
+        };
+
+        // Forward output until both pipes close
+        let forward = async {
+            while let Some(line) = rx.recv().await {
+                match line {
+                    OutputLine::Stdout(text) => send(&options.on_stdout, Value::String(Arc::new(text))),
+                    OutputLine::Stderr(text) => send(&options.on_stderr, Value::String(Arc::new(text))),
+                }
+            }
+        };
+
+        let status = tokio::select! {
+            _ = kill_rx => {
+                let _ = child.kill().await;
+                child.wait().await
+            }
+            (_, status) = async { tokio::join!(forward, child.wait()) } => status,
+        };
+
+        let code = status.ok().and_then(|s| s.code()).map(|c| c as f64).unwrap_or(-1.0);
+        send(&options.on_exit, Value::Number(code));
+
+        runtime.unregister_handle(handle_id).await;
+    });
+
+    Ok(Value::Handle(handle_id))
+}
+
+/// Stop a process started with spawn()
+/// proc.kill(handle) -> Pulse (false if it already exited)
+async fn process_kill(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    let handle_id = match args.first() {
+        Some(Value::Handle(id)) => *id,
+        _ => return Err(FlowError::type_error("kill() expects a Handle returned by spawn()", 0, 0)),
+    };
+
+    let handles = ctx.runtime.handles();
+    let mut registry = handles.lock().await;
+
+    match registry.get_mut(handle_id).map(|h| &mut h.handle_type) {
+        Some(HandleType::Process { kill_tx, .. }) => {
+            if let Some(tx) = kill_tx.take() {
+                let _ = tx.send(());
+            }
+            // The spawn task unregisters the handle once the process is reaped,
+            // so onExit still fires
+            Ok(Value::Boolean(true))
+        }
+        Some(_) => Err(FlowError::type_error("kill() expects a Handle returned by spawn()", 0, 0)),
+        None => Ok(Value::Boolean(false)),
     }
 }
@@ -0,0 +1,198 @@
+//! std:random - Random numbers with a seedable generator
+//!
+//! - `random.number()` - Ember in [0, 1)
+//! - `random.int(min, max)` - Whole Ember in [min, max]
+//! - `random.choice(arr)` - Random element of a Constellation
+//! - `random.shuffle(arr)` - Shuffled copy of a Constellation
+//! - `random.uuid()` - Random (v4) UUID Silk
+//! - `random.seed(n)` - Make all following results reproducible
+//!
+//! The generator is shared by the whole process and seeded from the clock
+//! until `random.seed` is called. UUIDs don't use it: they come from the OS
+//! random source, so they can't be predicted or repeated by seeding.
+
+use crate::error::FlowError;
+use crate::types::{NativeFn, Value};
+use std::sync::{Arc, Mutex};
+
+static GENERATOR: Mutex<Option<SplitMix64>> = Mutex::new(None);
+
+pub fn load_random_module() -> Vec<(&'static str, Value)> {
+    vec![
+        ("number", Value::NativeFunction(NativeFn::new(random_number))),
+        ("int", Value::NativeFunction(NativeFn::new(random_int))),
+        ("choice", Value::NativeFunction(NativeFn::new(random_choice))),
+        ("shuffle", Value::NativeFunction(NativeFn::new(random_shuffle))),
+        ("uuid", Value::NativeFunction(NativeFn::new(random_uuid))),
+        ("seed", Value::NativeFunction(NativeFn::new(random_seed))),
+    ]
+}
+
+/// random.number() -> Ember
+fn random_number(args: Vec<Value>) -> Result<Value, FlowError> {
+    if !args.is_empty() {
+        return Err(FlowError::runtime("random.number takes no arguments", 0, 0));
+    }
+    Ok(Value::Number(with_generator(|rng| rng.next_f64())))
+}
+
+/// random.int(min, max) -> Ember
+/// Both bounds are inclusive.
+fn random_int(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 2 {
+        return Err(FlowError::runtime(
+            "random.int expects 2 arguments (min, max)",
+            0, 0,
+        ));
+    }
+
+    let (min, max) = match (&args[0], &args[1]) {
+        (Value::Number(a), Value::Number(b)) => (a.ceil() as i64, b.floor() as i64),
+        _ => return Err(FlowError::type_error("random.int expects Embers (numbers)", 0, 0)),
+    };
+
+    if min > max {
+        return Err(FlowError::out_of_range(
+            &format!("random.int range is empty ({} > {})", min, max),
+            0, 0,
+        ));
+    }
+
+    Ok(Value::Number(with_generator(|rng| rng.range_i64(min, max)) as f64))
+}
+
+/// random.choice(arr) -> Flux
+fn random_choice(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime("random.choice expects 1 argument (array)", 0, 0));
+    }
+
+    match &args[0] {
+        Value::Array(arr) if arr.is_empty() => Err(FlowError::void_tear(
+            "random.choice cannot pick from an empty Constellation",
+            0, 0,
+        )),
+        Value::Array(arr) => {
+            let index = with_generator(|rng| rng.range_i64(0, arr.len() as i64 - 1)) as usize;
+            Ok(arr[index].clone())
+        }
+        _ => Err(FlowError::type_error("random.choice expects a Constellation (array)", 0, 0)),
+    }
+}
+
+/// random.shuffle(arr) -> Constellation
+/// Returns a new Constellation; the original is left untouched.
+fn random_shuffle(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime("random.shuffle expects 1 argument (array)", 0, 0));
+    }
+
+    let mut items = match &args[0] {
+        Value::Array(arr) => arr.as_ref().clone(),
+        _ => return Err(FlowError::type_error("random.shuffle expects a Constellation (array)", 0, 0)),
+    };
+
+    // Fisher-Yates
+    with_generator(|rng| {
+        for i in (1..items.len()).rev() {
+            let j = rng.range_i64(0, i as i64) as usize;
+            items.swap(i, j);
+        }
+    });
+
+    Ok(Value::Array(Arc::new(items)))
+}
+
+/// random.uuid() -> Silk
+fn random_uuid(args: Vec<Value>) -> Result<Value, FlowError> {
+    if !args.is_empty() {
+        return Err(FlowError::runtime("random.uuid takes no arguments", 0, 0));
+    }
+
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| FlowError::runtime(&format!("random.uuid could not get random bytes: {}", e), 0, 0))?;
+    bytes[6] = (bytes[6] & 0x0f) | 0x40; // version 4
+    bytes[8] = (bytes[8] & 0x3f) | 0x80; // RFC 4122 variant
+
+    let hex = hex::encode(bytes);
+    Ok(Value::String(Arc::new(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))))
+}
+
+/// random.seed(n) -> Hollow
+fn random_seed(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime("random.seed expects 1 argument (seed)", 0, 0));
+    }
+
+    let seed = match &args[0] {
+        Value::Number(n) => *n as i64 as u64,
+        _ => return Err(FlowError::type_error("random.seed expects an Ember (number)", 0, 0)),
+    };
+
+    *GENERATOR.lock().unwrap_or_else(|e| e.into_inner()) = Some(SplitMix64::new(seed));
+    Ok(Value::Null)
+}
+
+/// A number in [0, 1) from the shared generator, so `random.seed` also fixes
+/// randomness used by the runtime (e.g. retry jitter)
+pub fn unit() -> f64 {
+    with_generator(|rng| rng.next_f64())
+}
+
+fn with_generator<T>(f: impl FnOnce(&mut SplitMix64) -> T) -> T {
+    let mut guard = GENERATOR.lock().unwrap_or_else(|e| e.into_inner());
+    let rng = guard.get_or_insert_with(|| SplitMix64::new(time_seed()));
+    f(rng)
+}
+
+pub fn time_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0x2545_F491_4F6C_DD1D)
+}
+
+/// Small, fast, deterministic PRNG (SplitMix64). Not for cryptography.
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform integer in [low, high]
+    pub fn range_i64(&mut self, low: i64, high: i64) -> i64 {
+        if high <= low {
+            return low;
+        }
+        let span = (high.wrapping_sub(low) as u64).wrapping_add(1);
+        if span == 0 {
+            // The full i64 range
+            return self.next_u64() as i64;
+        }
+        low.wrapping_add((self.next_u64() % span) as i64)
+    }
+}
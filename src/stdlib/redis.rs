@@ -0,0 +1,365 @@
+//! std:redis - Redis client
+//!
+//! - `redis.connect(url?)` - Open a connection ("redis://[:password@]host[:port][/db]", or rediss:// for TLS)
+//! - `redis.unsubscribe(handle)` - Stop a subscription started by `conn.subscribe`
+//!
+//! Built on the `redis` crate. Commands on a connection are pipelined over one
+//! multiplexed socket, so a command whose caller gives up never leaves its reply
+//! for the next one. A subscription opens its own connection, since a subscribed
+//! connection can't run other commands, and keeps the process alive as a Handle.
+
+use crate::error::FlowError;
+use crate::runtime::handle::HandleType;
+use crate::types::{AsyncContext, AsyncNativeFn, Value, RelicMap};
+use futures_util::StreamExt;
+use redis::aio::MultiplexedConnection;
+use std::sync::Arc;
+use tokio::sync::{oneshot, Mutex};
+
+pub fn load_redis_module() -> Vec<(&'static str, Value)> {
+    vec![
+        ("connect", Value::AsyncNativeFunction(AsyncNativeFn::new(redis_connect))),
+        ("unsubscribe", Value::AsyncNativeFunction(AsyncNativeFn::new(redis_unsubscribe))),
+    ]
+}
+
+const DEFAULT_URL: &str = "redis://127.0.0.1:6379";
+
+fn client(url: &str) -> Result<redis::Client, FlowError> {
+    if !url.starts_with("redis://") && !url.starts_with("rediss://") {
+        return Err(FlowError::runtime(&format!("Invalid Redis URL '{}': expected redis:// or rediss://", url), 0, 0));
+    }
+    redis::Client::open(url)
+        .map_err(|e| FlowError::runtime(&format!("Invalid Redis URL '{}': {}", url, e), 0, 0))
+}
+
+fn redis_error(e: redis::RedisError) -> FlowError {
+    let message = match (e.code(), e.detail()) {
+        (Some(code), Some(detail)) => format!("Redis error: {} {}", code, detail),
+        _ if e.is_io_error() || e.is_connection_dropped() => format!("Redis connection error: {}", e),
+        _ => format!("Redis error: {}", e),
+    };
+    FlowError::runtime(&message, 0, 0)
+}
+
+fn into_value(reply: redis::Value) -> Value {
+    match reply {
+        redis::Value::Nil => Value::Null,
+        redis::Value::Int(n) => Value::Number(n as f64),
+        redis::Value::Data(bytes) => Value::String(Arc::new(String::from_utf8_lossy(&bytes).into_owned())),
+        redis::Value::Bulk(items) => Value::Array(Arc::new(items.into_iter().map(into_value).collect())),
+        redis::Value::Status(status) => Value::String(Arc::new(status)),
+        redis::Value::Okay => Value::String(Arc::new("OK".to_string())),
+    }
+}
+
+/// Connects, authenticates and selects the database
+async fn open(client: &redis::Client) -> Result<MultiplexedConnection, FlowError> {
+    let info = client.get_connection_info();
+    client.get_multiplexed_tokio_connection().await.map_err(|e| {
+        FlowError::runtime(&format!("Failed to connect to Redis at {}: {}", info.addr, e), 0, 0)
+    })
+}
+
+// ============================================================================
+// Connection
+// ============================================================================
+
+struct Connection {
+    client: redis::Client,
+    /// None after close() or a dropped connection; the next command reconnects
+    conn: Mutex<Option<MultiplexedConnection>>,
+}
+
+impl Connection {
+    async fn connection(&self) -> Result<MultiplexedConnection, FlowError> {
+        let mut guard = self.conn.lock().await;
+        if let Some(conn) = guard.as_ref() {
+            return Ok(conn.clone());
+        }
+        let conn = open(&self.client).await?;
+        *guard = Some(conn.clone());
+        Ok(conn)
+    }
+
+    async fn command(&self, args: Vec<String>) -> Result<Value, FlowError> {
+        let mut conn = self.connection().await?;
+        let mut command = redis::cmd(&args[0]);
+        for arg in &args[1..] {
+            command.arg(arg);
+        }
+        match command.query_async::<_, redis::Value>(&mut conn).await {
+            Ok(reply) => Ok(into_value(reply)),
+            Err(e) => {
+                if e.is_unrecoverable_error() {
+                    *self.conn.lock().await = None;
+                }
+                Err(redis_error(e))
+            }
+        }
+    }
+}
+
+/// redis.connect(url?) -> connection Relic
+async fn redis_connect(args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
+    let client = match args.first() {
+        Some(Value::String(url)) => client(url)?,
+        None => client(DEFAULT_URL)?,
+        Some(_) => return Err(FlowError::type_error("redis.connect expects a URL Silk", 0, 0)),
+    };
+    let first = open(&client).await?;
+    let conn = Arc::new(Connection { client, conn: Mutex::new(Some(first)) });
+
+    let mut map = RelicMap::new();
+    let mut method = |name: &str, f: fn(Arc<Connection>, Vec<Value>, AsyncContext) -> MethodFuture| {
+        let conn = conn.clone();
+        map.insert(name.to_string(), Value::AsyncNativeFunction(AsyncNativeFn::new(move |args, ctx| {
+            f(conn.clone(), args, ctx)
+        })));
+    };
+    method("get", |conn, args, _| Box::pin(async move {
+        let key = arg(&args, 0, "get", "key")?;
+        conn.command(vec!["GET".into(), key]).await
+    }));
+    method("set", |conn, args, _| Box::pin(async move { redis_set(conn, args).await }));
+    method("del", |conn, args, _| Box::pin(async move {
+        if args.is_empty() {
+            return Err(FlowError::runtime("del expects at least one key", 0, 0));
+        }
+        let mut command = vec!["DEL".to_string()];
+        for i in 0..args.len() {
+            command.push(arg(&args, i, "del", "key")?);
+        }
+        conn.command(command).await
+    }));
+    method("exists", |conn, args, _| Box::pin(async move {
+        let key = arg(&args, 0, "exists", "key")?;
+        let count = conn.command(vec!["EXISTS".into(), key]).await?;
+        Ok(Value::Boolean(matches!(count, Value::Number(n) if n > 0.0)))
+    }));
+    method("expire", |conn, args, _| Box::pin(async move {
+        let key = arg(&args, 0, "expire", "key")?;
+        let seconds = arg(&args, 1, "expire", "seconds")?;
+        let set = conn.command(vec!["EXPIRE".into(), key, seconds]).await?;
+        Ok(Value::Boolean(matches!(set, Value::Number(n) if n == 1.0)))
+    }));
+    method("ttl", |conn, args, _| Box::pin(async move {
+        let key = arg(&args, 0, "ttl", "key")?;
+        conn.command(vec!["TTL".into(), key]).await
+    }));
+    method("incr", |conn, args, _| Box::pin(async move {
+        let key = arg(&args, 0, "incr", "key")?;
+        let by = if args.len() > 1 { arg(&args, 1, "incr", "amount")? } else { "1".to_string() };
+        conn.command(vec!["INCRBY".into(), key, by]).await
+    }));
+    method("publish", |conn, args, _| Box::pin(async move {
+        let channel = arg(&args, 0, "publish", "channel")?;
+        let message = arg(&args, 1, "publish", "message")?;
+        conn.command(vec!["PUBLISH".into(), channel, message]).await
+    }));
+    method("command", |conn, args, _| Box::pin(async move {
+        if args.is_empty() {
+            return Err(FlowError::runtime("command expects a command name", 0, 0));
+        }
+        let command = (0..args.len()).map(|i| arg(&args, i, "command", "argument")).collect::<Result<_, _>>()?;
+        conn.command(command).await
+    }));
+    method("subscribe", |conn, args, ctx| Box::pin(async move { redis_subscribe(conn, args, ctx).await }));
+    method("close", |conn, _, _| Box::pin(async move {
+        Ok(Value::Boolean(conn.conn.lock().await.take().is_some()))
+    }));
+    Ok(Value::Relic(Arc::new(map)))
+}
+
+type MethodFuture = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Value, FlowError>> + Send>>;
+
+/// Argument `index` as a command word; Embers and Pulses are sent as text
+fn arg(args: &[Value], index: usize, method: &str, what: &str) -> Result<String, FlowError> {
+    match args.get(index) {
+        Some(Value::String(s)) => Ok(s.to_string()),
+        Some(value @ (Value::Number(_) | Value::Boolean(_))) => Ok(value.to_string()),
+        Some(other) => Err(FlowError::type_error(
+            &format!("{} expects a Silk or Ember {}, got {} (use json.stringify for structured values)", method, what, other.type_name()),
+            0, 0,
+        )),
+        None => Err(FlowError::runtime(&format!("{} is missing the {} argument", method, what), 0, 0)),
+    }
+}
+
+/// conn.set(key, value, options?) -> Pulse
+/// Options: ex (seconds), px (milliseconds), nx (only if missing), xx (only if present).
+async fn redis_set(conn: Arc<Connection>, args: Vec<Value>) -> Result<Value, FlowError> {
+    let mut command = vec!["SET".to_string(), arg(&args, 0, "set", "key")?, arg(&args, 1, "set", "value")?];
+    match args.get(2) {
+        Some(Value::Relic(options)) => {
+            for (option, word) in [("ex", "EX"), ("px", "PX")] {
+                match options.get(option) {
+                    Some(Value::Number(n)) => command.extend([word.to_string(), (*n as u64).to_string()]),
+                    Some(_) => return Err(FlowError::type_error(&format!("set option '{}' expects an Ember", option), 0, 0)),
+                    None => {}
+                }
+            }
+            for (option, word) in [("nx", "NX"), ("xx", "XX")] {
+                if matches!(options.get(option), Some(Value::Boolean(true))) {
+                    command.push(word.to_string());
+                }
+            }
+        }
+        Some(_) => return Err(FlowError::type_error("set options must be a Relic", 0, 0)),
+        None => {}
+    }
+    // NX and XX answer nil when the condition stops the write
+    Ok(Value::Boolean(!matches!(conn.command(command).await?, Value::Null)))
+}
+
+/// conn.subscribe(channels, callback) -> Handle
+/// Calls `callback(message, channel)` for each message until redis.unsubscribe(handle).
+async fn redis_subscribe(conn: Arc<Connection>, args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    let channels: Vec<String> = match args.first() {
+        Some(Value::String(channel)) => vec![channel.to_string()],
+        Some(Value::Array(list)) if !list.is_empty() => list.iter().map(|c| c.to_string()).collect(),
+        _ => return Err(FlowError::type_error("subscribe expects a channel Silk or a Constellation of channels", 0, 0)),
+    };
+    let callback = match args.get(1) {
+        Some(callback) if callback.type_name() == "Spell" => callback.clone(),
+        _ => return Err(FlowError::type_error("subscribe expects a Spell (function) as callback", 0, 0)),
+    };
+
+    let mut pubsub = conn.client.get_async_pubsub().await.map_err(redis_error)?;
+    // Failures surface here rather than in the task
+    for channel in &channels {
+        pubsub.subscribe(channel).await.map_err(redis_error)?;
+    }
+    let mut messages = pubsub.into_on_message();
+
+    let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+    let handle_id = ctx.runtime.register_handle(HandleType::Subscription {
+        cancel_tx: Some(cancel_tx),
+    }).await;
+
+    let callback_tx = ctx.runtime.callback_sender();
+    let runtime = ctx.runtime.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                message = messages.next() => {
+                    let Some(message) = message else {
+                        eprintln!("Redis subscription to {} ended: connection closed", channels.join(", "));
+                        break;
+                    };
+                    let _ = callback_tx.send(crate::runtime::CallbackRequest {
+                        callback: callback.clone(),
+                        args: vec![
+                            Value::String(Arc::new(String::from_utf8_lossy(message.get_payload_bytes()).into_owned())),
+                            Value::String(Arc::new(message.get_channel_name().to_string())),
+                        ],
+                    });
+                }
+                _ = &mut cancel_rx => break,
+            }
+        }
+        runtime.unregister_handle(handle_id).await;
+    });
+
+    Ok(Value::Handle(handle_id))
+}
+
+/// redis.unsubscribe(handle) -> Pulse
+/// Returns false if the subscription was not active.
+async fn redis_unsubscribe(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    let handle_id = match args.first() {
+        Some(Value::Handle(id)) => *id,
+        _ => return Err(FlowError::type_error("redis.unsubscribe expects a Handle", 0, 0)),
+    };
+
+    let handles = ctx.runtime.handles();
+    let mut registry = handles.lock().await;
+    match registry.get_mut(handle_id) {
+        Some(handle) => {
+            match &mut handle.handle_type {
+                HandleType::Subscription { cancel_tx, .. } => {
+                    if let Some(tx) = cancel_tx.take() {
+                        let _ = tx.send(());
+                    }
+                }
+                other => return Err(FlowError::type_error(
+                    &format!("redis.unsubscribe expects a subscription Handle, got a {} handle", other.type_name()),
+                    0, 0,
+                )),
+            }
+            registry.remove(handle_id);
+            Ok(Value::Boolean(true))
+        }
+        None => Ok(Value::Boolean(false)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+    #[test]
+    fn test_parse_url() {
+        let info = client("redis://:s3cret@cache.internal:6380/2").unwrap().get_connection_info().clone();
+        assert_eq!(info.addr, redis::ConnectionAddr::Tcp("cache.internal".to_string(), 6380));
+        assert_eq!((info.redis.username, info.redis.password, info.redis.db), (None, Some("s3cret".to_string()), 2));
+        let info = client("redis://aria:pw@localhost").unwrap().get_connection_info().clone();
+        assert_eq!((info.redis.username.as_deref(), info.redis.db), (Some("aria"), 0));
+        let info = client("rediss://cache.internal").unwrap().get_connection_info().clone();
+        assert!(matches!(info.addr, redis::ConnectionAddr::TcpTls { ref host, port: 6379, insecure: false, .. } if host == "cache.internal"));
+        assert!(client("localhost:6379").is_err());
+    }
+
+    /// One command from a fake server's client, e.g. ["GET", "rank"]
+    async fn read_command<R: tokio::io::AsyncBufRead + Unpin>(reader: &mut R) -> Option<Vec<String>> {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.ok().filter(|&n| n > 0)?;
+        let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+        let mut words = Vec::new();
+        for _ in 0..count {
+            line.clear();
+            reader.read_line(&mut line).await.ok()?;
+            let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+            let mut word = vec![0; len + 2];
+            reader.read_exact(&mut word).await.ok()?;
+            words.push(String::from_utf8_lossy(&word[..len]).into_owned());
+        }
+        Some(words)
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_command_keeps_replies_in_step() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let (read, mut write) = socket.into_split();
+            let mut reader = BufReader::new(read);
+            while let Some(command) = read_command(&mut reader).await {
+                let reply = match command.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+                    ["GET", key] => {
+                        if *key == "slow" {
+                            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                        }
+                        format!("${}\r\n{}\r\n", key.len(), key)
+                    }
+                    ["TYPO", ..] => "-ERR unknown command 'TYPO'\r\n".to_string(),
+                    _ => "+OK\r\n".to_string(),
+                };
+                write.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+
+        let client = client(&format!("redis://127.0.0.1:{}", port)).unwrap();
+        let conn = Connection { conn: Mutex::new(Some(open(&client).await.unwrap())), client };
+        let get = |key: &str| conn.command(vec!["GET".to_string(), key.to_string()]);
+        let gave_up = tokio::time::timeout(std::time::Duration::from_millis(50), get("slow")).await;
+        assert!(gave_up.is_err());
+        // The slow reply arrives later and is dropped, not handed to the next command
+        assert_eq!(get("fast").await.unwrap().to_string(), "fast");
+        let error = conn.command(vec!["TYPO".to_string()]).await.unwrap_err();
+        assert!(error.to_string().contains("ERR unknown command 'TYPO'"));
+        assert_eq!(get("rank").await.unwrap().to_string(), "rank");
+    }
+}
@@ -0,0 +1,190 @@
+//! std:reflect - look at Spells, values, modules and Sigils from FlowLang
+//!
+//! Types are described as Relics: `{"name": "Constellation", "essence":
+//! "Constellation<Ember>", "of": {...}}`, where `essence` is the annotation you would
+//! write and Constellations and Relics describe what they hold under `of` and
+//! `key`/`value`. A parameter or field without an annotation is described as Flux.
+
+use std::sync::Arc;
+
+use crate::error::FlowError;
+use crate::interpreter::Interpreter;
+use crate::types::{EssenceType, InterpreterFuture, InterpreterNativeFn, NativeFn, RelicMap, Value};
+
+pub fn get_module() -> RelicMap {
+    let mut module = RelicMap::new();
+    module.insert("params".to_string(), Value::NativeFunction(NativeFn::new(reflect_params)));
+    module.insert("typeOf".to_string(), Value::NativeFunction(NativeFn::new(reflect_type_of)));
+    module.insert("exports".to_string(), Value::NativeFunction(NativeFn::new(reflect_exports)));
+    module.insert(
+        "sigilFields".to_string(),
+        Value::InterpreterNativeFunction(InterpreterNativeFn::new(reflect_sigil_fields)),
+    );
+    module
+}
+
+/// `reflect.params(spell)`: `[{name, type}]` in declaration order
+fn reflect_params(args: Vec<Value>) -> Result<Value, FlowError> {
+    match args.as_slice() {
+        [Value::Function { params, param_types, .. }] => Ok(members(
+            params.iter().zip(param_types).map(|(name, ty)| (name.as_str(), essence_info(ty.as_ref().unwrap_or(&EssenceType::Flux)))),
+        )),
+        [spell] if spell.type_name() == "Spell" => Err(FlowError::type_error(
+            "reflect.params can only read Spells written in FlowLang; built-in, module and bound Spells don't declare their parameters",
+            0,
+            0,
+        )),
+        [other] => Err(FlowError::type_error(
+            &format!("reflect.params expects a Spell, got {}", other.type_name()),
+            0,
+            0,
+        )),
+        _ => Err(FlowError::runtime("reflect.params expects 1 argument", 0, 0)),
+    }
+}
+
+/// `reflect.typeOf(value)`: the essence a value has, worked out from its contents
+fn reflect_type_of(args: Vec<Value>) -> Result<Value, FlowError> {
+    match args.as_slice() {
+        [value] => Ok(type_info(value)),
+        _ => Err(FlowError::runtime("reflect.typeOf expects 1 argument", 0, 0)),
+    }
+}
+
+/// `reflect.exports(module)`: `[{name, type}]` for an imported module or a `std:` path
+fn reflect_exports(args: Vec<Value>) -> Result<Value, FlowError> {
+    let module = match args.as_slice() {
+        [Value::Relic(module)] => module.clone(),
+        [Value::String(path)] => match path.strip_prefix("std:").and_then(super::load_module) {
+            Some(module) => Arc::new(module),
+            None => return Err(FlowError::runtime(
+                &format!("reflect.exports takes a module Relic or a std: path, not '{}'", path),
+                0,
+                0,
+            )),
+        },
+        [other] => return Err(FlowError::type_error(
+            &format!("reflect.exports expects a module Relic, got {}", other.type_name()),
+            0,
+            0,
+        )),
+        _ => return Err(FlowError::runtime("reflect.exports expects 1 argument", 0, 0)),
+    };
+    Ok(members(module.iter().map(|(name, value)| (name.as_str(), type_info(value)))))
+}
+
+/// `reflect.sigilFields(User)`: `[{name, type}]` in declaration order
+fn reflect_sigil_fields(args: Vec<Value>, interp: &mut Interpreter) -> InterpreterFuture<'_> {
+    Box::pin(async move {
+        let name = match args.as_slice() {
+            [Value::String(name)] => name.clone(),
+            _ => return Err(FlowError::type_error("reflect.sigilFields expects a Sigil", 0, 0)),
+        };
+        let fields = interp.sigil_fields(&name).await.ok_or_else(|| {
+            FlowError::undefined(&format!("Unknown Sigil type: '{}'", name), 0, 0)
+        })?;
+        Ok(members(fields.iter().map(|field| (field.name.as_str(), essence_info(&field.field_type)))))
+    })
+}
+
+/// A Constellation of `{name, type}` Relics
+fn members<'a>(entries: impl Iterator<Item = (&'a str, Value)>) -> Value {
+    let list = entries
+        .map(|(name, ty)| {
+            let mut member = RelicMap::new();
+            member.insert("name".to_string(), Value::String(Arc::new(name.to_string())));
+            member.insert("type".to_string(), ty);
+            Value::Relic(Arc::new(member))
+        })
+        .collect();
+    Value::Array(Arc::new(list))
+}
+
+fn type_info(value: &Value) -> Value {
+    match essence_of(value) {
+        Some(essence) => essence_info(&essence),
+        // Covenants and Handles have no annotation of their own
+        None => {
+            let name = Value::String(Arc::new(value.type_name().to_string()));
+            let mut info = RelicMap::new();
+            info.insert("name".to_string(), name.clone());
+            info.insert("essence".to_string(), name);
+            Value::Relic(Arc::new(info))
+        }
+    }
+}
+
+fn essence_info(essence: &EssenceType) -> Value {
+    let name = match essence {
+        EssenceType::Constellation(_) => "Constellation".to_string(),
+        EssenceType::Relic(_, _) => "Relic".to_string(),
+        other => other.to_string(),
+    };
+    let mut info = RelicMap::new();
+    info.insert("name".to_string(), Value::String(Arc::new(name)));
+    info.insert("essence".to_string(), Value::String(Arc::new(essence.to_string())));
+    match essence {
+        EssenceType::Constellation(inner) => {
+            info.insert("of".to_string(), essence_info(inner));
+        }
+        EssenceType::Relic(key, value) => {
+            info.insert("key".to_string(), essence_info(key));
+            info.insert("value".to_string(), essence_info(value));
+        }
+        _ => {}
+    }
+    Value::Relic(Arc::new(info))
+}
+
+/// Containers hold their elements' essence when they all share one, and Flux otherwise
+fn essence_of(value: &Value) -> Option<EssenceType> {
+    Some(match value {
+        Value::Number(_) => EssenceType::Ember,
+        Value::String(_) => EssenceType::Silk,
+        Value::Boolean(_) => EssenceType::Pulse,
+        Value::Null => EssenceType::Hollow,
+        Value::Array(items) => EssenceType::Constellation(Box::new(common_essence(items.iter()))),
+        Value::Relic(map) => EssenceType::Relic(Box::new(EssenceType::Silk), Box::new(common_essence(map.values()))),
+        Value::Function { .. }
+        | Value::NativeFunction(_)
+        | Value::AsyncNativeFunction(_)
+        | Value::InterpreterNativeFunction(_) => EssenceType::Spell,
+        Value::Covenant(_) | Value::Handle(_) => return None,
+    })
+}
+
+fn common_essence<'a>(mut values: impl Iterator<Item = &'a Value>) -> EssenceType {
+    let Some(first) = values.next().and_then(essence_of) else {
+        return EssenceType::Flux;
+    };
+    if values.all(|value| essence_of(value).as_ref() == Some(&first)) {
+        first
+    } else {
+        EssenceType::Flux
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn essence(value: &Value) -> String {
+        match type_info(value) {
+            Value::Relic(info) => info["essence"].to_string(),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_type_of_infers_container_essences() {
+        let numbers = Value::Array(Arc::new(vec![Value::Number(1.0), Value::Number(2.0)]));
+        let mixed = Value::Array(Arc::new(vec![Value::Number(1.0), Value::String(Arc::new("a".to_string()))]));
+        let mut relic = RelicMap::new();
+        relic.insert("xs".to_string(), numbers.clone());
+        assert_eq!(essence(&numbers), "Constellation<Ember>");
+        assert_eq!(essence(&mixed), "Constellation<Flux>");
+        assert_eq!(essence(&Value::Array(Arc::new(vec![]))), "Constellation<Flux>");
+        assert_eq!(essence(&Value::Relic(Arc::new(relic))), "Relic<Silk, Constellation<Ember>>");
+        assert_eq!(essence(&Value::Handle(1)), "Handle");
+    }
+}
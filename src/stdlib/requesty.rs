@@ -1,5 +1,5 @@
 use crate::error::FlowError;
-use crate::types::{NativeFn, Value};
+use crate::types::{NativeFn, Value, RelicMap};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -118,14 +118,14 @@ fn execute_request(url: String, opts: RequestOptions) -> Result<Value, FlowError
         Ok(resp) => {
             let status = resp.status().as_u16() as f64;
             let status_text = resp.status().canonical_reason().unwrap_or("").to_string();
-            let headers_map: HashMap<String, Value> = resp.headers()
+            let headers_map: RelicMap = resp.headers()
                 .iter()
                 .map(|(k, v)| (k.to_string(), Value::String(Arc::new(v.to_str().unwrap_or("").to_string()))))
                 .collect();
             
             let text = resp.text().unwrap_or_default();
             
-            let mut response_map = HashMap::new();
+            let mut response_map = RelicMap::new();
             response_map.insert("status".to_string(), Value::Number(status));
             response_map.insert("statusText".to_string(), Value::String(Arc::new(status_text)));
             response_map.insert("headers".to_string(), Value::Relic(Arc::new(headers_map)));
@@ -159,7 +159,7 @@ fn json_to_value(v: serde_json::Value) -> Value {
             Value::Array(Arc::new(a.into_iter().map(json_to_value).collect()))
         },
         serde_json::Value::Object(o) => {
-            let map: HashMap<String, Value> = o.into_iter().map(|(k, v)| (k, json_to_value(v))).collect();
+            let map: RelicMap = o.into_iter().map(|(k, v)| (k, json_to_value(v))).collect();
             Value::Relic(Arc::new(map))
         }
     }
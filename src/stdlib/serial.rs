@@ -0,0 +1,181 @@
+//! std:serial - compact binary encoding of plain FlowLang values
+//!
+//! `serial.pack(value)` turns Embers, Silk, Pulses, Hollow, Constellations, Relics
+//! (and so Sigil instances) and Covenants into bytes, and `serial.unpack` turns them
+//! back into an equal value, keeping Relic key order. The bytes are a short header
+//! followed by bincode, and are base64 Silk in FlowLang like other binary data.
+//! Spells and Handles only mean something inside the process that made them, so
+//! packing one is an error. `to_bytes`/`from_bytes` are the same encoding for Rust
+//! callers that move values between processes or runs.
+
+use std::sync::Arc;
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FlowError;
+use crate::types::{CovenantSet, NativeFn, RelicMap, Value};
+
+/// Identifies packed data and its format version
+const MAGIC: &[u8; 4] = b"FLS1";
+
+/// The packed form of a plain value
+#[derive(Serialize, Deserialize)]
+enum Packed {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Packed>),
+    /// Entries in order, so key order survives the trip
+    Relic(Vec<(String, Packed)>),
+    Covenant(Vec<Packed>),
+}
+
+pub fn get_module() -> RelicMap {
+    let mut module = RelicMap::new();
+    module.insert("pack".to_string(), Value::NativeFunction(NativeFn::new(serial_pack)));
+    module.insert("unpack".to_string(), Value::NativeFunction(NativeFn::new(serial_unpack)));
+    module.insert("packFile".to_string(), Value::NativeFunction(NativeFn::new(serial_pack_file)));
+    module.insert("unpackFile".to_string(), Value::NativeFunction(NativeFn::new(serial_unpack_file)));
+    module
+}
+
+/// Encode a plain value; `what` names it in the error for a Spell or Handle inside
+pub fn to_bytes(value: &Value, what: &str) -> Result<Vec<u8>, FlowError> {
+    if let Some((path, kind)) = value.find_live_part(what) {
+        return Err(FlowError::type_error(
+            &format!("Only plain data can be packed, but {} is a {}", path, kind),
+            0,
+            0,
+        ));
+    }
+    let mut bytes = MAGIC.to_vec();
+    bincode::serialize_into(&mut bytes, &pack_value(value))
+        .map_err(|e| FlowError::runtime(&format!("Failed to pack value: {}", e), 0, 0))?;
+    Ok(bytes)
+}
+
+/// Decode bytes made by `to_bytes`
+pub fn from_bytes(bytes: &[u8]) -> Result<Value, FlowError> {
+    let body = bytes
+        .strip_prefix(MAGIC.as_slice())
+        .ok_or_else(|| FlowError::glitch("Data was not made by serial.pack", 0, 0))?;
+    let packed: Packed = bincode::deserialize(body)
+        .map_err(|e| FlowError::glitch(&format!("Packed data is corrupt: {}", e), 0, 0))?;
+    unpack_value(packed)
+}
+
+fn pack_value(value: &Value) -> Packed {
+    match value {
+        Value::Number(n) => Packed::Number(*n),
+        Value::String(s) => Packed::String(s.to_string()),
+        Value::Boolean(b) => Packed::Boolean(*b),
+        Value::Array(items) => Packed::Array(items.iter().map(pack_value).collect()),
+        Value::Relic(map) => Packed::Relic(map.iter().map(|(k, v)| (k.clone(), pack_value(v))).collect()),
+        Value::Covenant(set) => Packed::Covenant(set.values().map(pack_value).collect()),
+        // `to_bytes` rejects values holding live parts before packing them
+        _ => Packed::Null,
+    }
+}
+
+fn unpack_value(packed: Packed) -> Result<Value, FlowError> {
+    Ok(match packed {
+        Packed::Null => Value::Null,
+        Packed::Boolean(b) => Value::Boolean(b),
+        Packed::Number(n) => Value::Number(n),
+        Packed::String(s) => Value::String(Arc::new(s)),
+        Packed::Array(items) => Value::Array(Arc::new(items.into_iter().map(unpack_value).collect::<Result<_, _>>()?)),
+        Packed::Relic(entries) => {
+            let mut map = RelicMap::new();
+            for (key, value) in entries {
+                map.insert(key, unpack_value(value)?);
+            }
+            Value::Relic(Arc::new(map))
+        }
+        Packed::Covenant(members) => {
+            let mut set = CovenantSet::new();
+            for member in members {
+                let member = unpack_value(member)?;
+                set.insert(super::covenant::member_key(&member)?, member);
+            }
+            Value::Covenant(Arc::new(set))
+        }
+    })
+}
+
+/// serial.pack(value) -> Silk (base64)
+fn serial_pack(args: Vec<Value>) -> Result<Value, FlowError> {
+    match args.as_slice() {
+        [value] => Ok(Value::String(Arc::new(general_purpose::STANDARD.encode(to_bytes(value, "value")?)))),
+        _ => Err(FlowError::runtime("serial.pack expects 1 argument (value)", 0, 0)),
+    }
+}
+
+/// serial.unpack(data) -> Flux
+/// Accepts the base64 output of serial.pack.
+fn serial_unpack(args: Vec<Value>) -> Result<Value, FlowError> {
+    let data = match args.as_slice() {
+        [Value::String(data)] => data,
+        [other] => return Err(FlowError::type_error(
+            &format!("serial.unpack expects Silk data, got {}", other.type_name()),
+            0,
+            0,
+        )),
+        _ => return Err(FlowError::runtime("serial.unpack expects 1 argument (data)", 0, 0)),
+    };
+    let bytes = general_purpose::STANDARD
+        .decode(data.trim())
+        .map_err(|_| FlowError::glitch("serial.unpack expects base64 data (from serial.pack)", 0, 0))?;
+    from_bytes(&bytes)
+}
+
+/// serial.packFile(path, value) -> Silk
+/// Writes the raw packed bytes and returns the path.
+fn serial_pack_file(args: Vec<Value>) -> Result<Value, FlowError> {
+    let (path, value) = match args.as_slice() {
+        [Value::String(path), value] => (path.clone(), value),
+        _ => return Err(FlowError::runtime("serial.packFile expects 2 arguments (path: Silk, value)", 0, 0)),
+    };
+    let bytes = to_bytes(value, "value")?;
+    std::fs::write(path.as_str(), bytes)
+        .map_err(|e| FlowError::runtime(&format!("Failed to write '{}': {}", path, e), 0, 0))?;
+    Ok(Value::String(path))
+}
+
+/// serial.unpackFile(path) -> Flux
+fn serial_unpack_file(args: Vec<Value>) -> Result<Value, FlowError> {
+    let path = match args.as_slice() {
+        [Value::String(path)] => path,
+        _ => return Err(FlowError::runtime("serial.unpackFile expects 1 argument (path: Silk)", 0, 0)),
+    };
+    let bytes = std::fs::read(path.as_str())
+        .map_err(|e| FlowError::runtime(&format!("Failed to read '{}': {}", path, e), 0, 0))?;
+    from_bytes(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_keeps_structure_and_rejects_spells() {
+        let mut relic = RelicMap::new();
+        relic.insert("zeta".to_string(), Value::Number(1.5));
+        relic.insert("alpha".to_string(), Value::Array(Arc::new(vec![Value::Null, Value::Boolean(true)])));
+        let mut set = CovenantSet::new();
+        set.insert(super::super::covenant::member_key(&Value::Number(3.0)).unwrap(), Value::Number(3.0));
+        relic.insert("set".to_string(), Value::Covenant(Arc::new(set)));
+        let value = Value::Relic(Arc::new(relic));
+
+        let restored = from_bytes(&to_bytes(&value, "value").unwrap()).unwrap();
+        assert!(restored.deep_equals(&value));
+        assert_eq!(restored.to_string(), value.to_string());
+
+        let mut live = RelicMap::new();
+        live.insert("run".to_string(), Value::NativeFunction(NativeFn::new(|_| Ok(Value::Null))));
+        let err = to_bytes(&Value::Relic(Arc::new(live)), "value").unwrap_err();
+        assert!(err.to_string().contains("value.run is a Spell"));
+        assert!(from_bytes(b"not packed").is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! systemd socket activation
+//!
+//! With a `.socket` unit, systemd opens the listening sockets itself and starts the
+//! service with them as file descriptors 3, 4, ...; `LISTEN_FDS` says how many there
+//! are and `LISTEN_PID` which process they were meant for. `web.serve(port)` and
+//! `web.serveUnix(path)` take the inherited socket bound to the same port or path
+//! instead of binding a new one, so a script runs unchanged with or without
+//! activation. Each socket is handed out once.
+
+#[cfg(unix)]
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+/// The first descriptor systemd passes (SD_LISTEN_FDS_START)
+const FIRST_FD: i32 = 3;
+
+#[cfg(unix)]
+static INHERITED: OnceLock<Mutex<Vec<i32>>> = OnceLock::new();
+
+/// Descriptors passed to this process, from the values of LISTEN_PID and LISTEN_FDS
+#[cfg_attr(not(unix), allow(dead_code))]
+fn listen_fds(listen_pid: Option<&str>, listen_fds: Option<&str>, own_pid: u32) -> Vec<i32> {
+    // Sockets meant for another process (e.g. our parent) are not ours to take
+    if listen_pid.and_then(|pid| pid.trim().parse::<u32>().ok()) != Some(own_pid) {
+        return Vec::new();
+    }
+    let count = listen_fds.and_then(|n| n.trim().parse::<i32>().ok()).unwrap_or(0);
+    (FIRST_FD..FIRST_FD + count.max(0)).collect()
+}
+
+#[cfg(unix)]
+fn inherited() -> &'static Mutex<Vec<i32>> {
+    INHERITED.get_or_init(|| {
+        let pid = std::env::var("LISTEN_PID").ok();
+        let fds = listen_fds(pid.as_deref(), std::env::var("LISTEN_FDS").ok().as_deref(), std::process::id());
+        // Child processes must not think the sockets are theirs
+        for name in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+            std::env::remove_var(name);
+        }
+        Mutex::new(fds)
+    })
+}
+
+/// Take the first inherited socket that `accept` recognizes
+#[cfg(unix)]
+fn take<T>(accept: impl Fn(i32) -> Option<T>) -> Option<T> {
+    let mut fds = inherited().lock().unwrap_or_else(PoisonError::into_inner);
+    let (index, found) = fds.iter().enumerate().find_map(|(i, &fd)| accept(fd).map(|found| (i, found)))?;
+    fds.remove(index);
+    Some(found)
+}
+
+/// The inherited TCP listener on `port`, if systemd passed one
+#[cfg(unix)]
+pub fn take_tcp(port: u16) -> Option<std::net::TcpListener> {
+    use std::os::unix::io::{FromRawFd, IntoRawFd};
+    take(|fd| {
+        // Safety: the descriptor was passed to this process and is only adopted once;
+        // a socket that doesn't match is released again without being closed
+        let listener = unsafe { std::net::TcpListener::from_raw_fd(fd) };
+        match listener.local_addr() {
+            Ok(addr) if addr.port() == port => Some(listener),
+            _ => {
+                let _ = listener.into_raw_fd();
+                None
+            }
+        }
+    })
+}
+
+/// The inherited Unix socket listener bound to `path`, if systemd passed one
+#[cfg(unix)]
+pub fn take_unix(path: &std::path::Path) -> Option<std::os::unix::net::UnixListener> {
+    use std::os::unix::io::{FromRawFd, IntoRawFd};
+    take(|fd| {
+        // Safety: as in take_tcp
+        let listener = unsafe { std::os::unix::net::UnixListener::from_raw_fd(fd) };
+        match listener.local_addr() {
+            Ok(addr) if addr.as_pathname() == Some(path) => Some(listener),
+            _ => {
+                let _ = listener.into_raw_fd();
+                None
+            }
+        }
+    })
+}
+
+/// Socket activation is a systemd feature; elsewhere every server binds its own socket
+#[cfg(not(unix))]
+pub fn take_tcp(_port: u16) -> Option<std::net::TcpListener> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listen_fds_for_this_process_only() {
+        assert_eq!(listen_fds(Some("42"), Some("2"), 42), vec![3, 4]);
+        assert!(listen_fds(Some("41"), Some("2"), 42).is_empty());
+        assert!(listen_fds(None, Some("2"), 42).is_empty());
+        assert!(listen_fds(Some("42"), None, 42).is_empty());
+        assert!(listen_fds(Some("42"), Some("-1"), 42).is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+//! Spells built from other Spells: `spell.bind(...)`, `compose(...)` and `pipe(...)`
+//!
+//! The Spells they return are interpreter-aware natives, so the Spells they wrap can
+//! be user Spells as well as built-in and module functions.
+
+use crate::error::FlowError;
+use crate::interpreter::Interpreter;
+use crate::types::{InterpreterFuture, InterpreterNativeFn, Value};
+
+/// Methods on a Spell value
+pub fn call_method(spell: &Value, method: &str, args: Vec<Value>) -> Result<Value, FlowError> {
+    match method {
+        "bind" => bind(spell.clone(), args),
+        _ => Err(FlowError::runtime(&format!("Unknown method '{}' on Spell", method), 0, 0)),
+    }
+}
+
+/// `spell.bind(a, b)`: a Spell that calls `spell(a, b, ...its own arguments)`
+pub fn bind(spell: Value, bound: Vec<Value>) -> Result<Value, FlowError> {
+    if let Value::Function { params, .. } = &spell {
+        if bound.len() > params.len() {
+            return Err(FlowError::runtime(
+                &format!("bind() was given {} essences, but the Spell only takes {}", bound.len(), params.len()),
+                0,
+                0,
+            ));
+        }
+    }
+    Ok(Value::InterpreterNativeFunction(InterpreterNativeFn::new(
+        move |args: Vec<Value>, interp: &mut Interpreter| -> InterpreterFuture<'_> {
+            let spell = spell.clone();
+            let mut all = bound.clone();
+            Box::pin(async move {
+                all.extend(args);
+                call(interp, spell, all).await
+            })
+        },
+    )))
+}
+
+/// `compose(f, g)`: a Spell computing `f(g(...))`, so the last Spell runs first
+pub fn compose(mut spells: Vec<Value>) -> Result<Value, FlowError> {
+    check_spells(&spells, "compose")?;
+    spells.reverse();
+    Ok(chain(spells))
+}
+
+/// `pipe(f, g)`: a Spell computing `g(f(...))`, so the first Spell runs first
+pub fn pipe(spells: Vec<Value>) -> Result<Value, FlowError> {
+    check_spells(&spells, "pipe")?;
+    Ok(chain(spells))
+}
+
+fn check_spells(spells: &[Value], func: &str) -> Result<(), FlowError> {
+    if spells.is_empty() {
+        return Err(FlowError::runtime(&format!("{}() expects at least 1 Spell", func), 0, 0));
+    }
+    match spells.iter().position(|s| s.type_name() != "Spell") {
+        Some(i) => Err(FlowError::type_error(
+            &format!("{}() expects Spells, but argument {} is {}", func, i + 1, spells[i].type_name()),
+            0,
+            0,
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The first Spell gets every argument; each later one gets the result before it
+fn chain(spells: Vec<Value>) -> Value {
+    Value::InterpreterNativeFunction(InterpreterNativeFn::new(
+        move |args: Vec<Value>, interp: &mut Interpreter| -> InterpreterFuture<'_> {
+            let spells = spells.clone();
+            Box::pin(async move {
+                let mut args = args;
+                let mut result = Value::Null;
+                for spell in spells {
+                    result = call(interp, spell, args).await?;
+                    args = vec![result.clone()];
+                }
+                Ok(result)
+            })
+        },
+    ))
+}
+
+// `execute_function` binds whatever it is given, so check the count the way a direct call does
+async fn call(interp: &mut Interpreter, spell: Value, args: Vec<Value>) -> Result<Value, FlowError> {
+    if let Value::Function { params, .. } = &spell {
+        if params.len() != args.len() {
+            return Err(FlowError::runtime(
+                &format!("Spell expects {} essences, but {} were provided!", params.len(), args.len()),
+                0,
+                0,
+            ));
+        }
+    }
+    interp.execute_function(spell, args).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::output::Stream;
+    use crate::interpreter::Interpreter;
+
+    #[tokio::test]
+    async fn test_bind_and_pipe_user_spells() {
+        let source = "cast Spell add(a, b) {\n    return a + b\n}\nlet inc = add.bind(1)\nshout(pipe(inc, inc, add.bind(10))(0))\nshout(compose(inc, cast Spell x -> x * 3)(2))\n";
+        let program = crate::lexer::tokenize(source).and_then(crate::parser::parse).unwrap();
+        let mut interpreter = Interpreter::new(Default::default());
+        let (result, output) = interpreter.execute_captured(program).await;
+        result.unwrap();
+        assert_eq!(output, vec![(Stream::Stdout, "12".to_string()), (Stream::Stdout, "7".to_string())]);
+
+        let program = crate::lexer::tokenize("cast Spell add(a, b) {\n    return a + b\n}\nadd.bind(1)(2, 3)\n")
+            .and_then(crate::parser::parse)
+            .unwrap();
+        let (result, _) = interpreter.execute_captured(program).await;
+        assert!(result.unwrap_err().to_string().contains("expects 2 essences, but 3 were provided"));
+    }
+}
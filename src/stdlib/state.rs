@@ -0,0 +1,133 @@
+//! std:state - checkpoint variables to a file and restore them on the next run
+//!
+//! `state.save(path, ["cursor", "done"])` packs the named variables with std:serial
+//! and `state.load(path)` assigns them back, so a long-running script can pick up
+//! where it stopped. The file is replaced atomically, so a crash mid-save leaves the
+//! previous checkpoint intact.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::error::FlowError;
+use crate::interpreter::Interpreter;
+use crate::types::{InterpreterFuture, InterpreterNativeFn, RelicMap, Value};
+
+pub fn get_module() -> RelicMap {
+    let mut module = RelicMap::new();
+    module.insert("save".to_string(), Value::InterpreterNativeFunction(InterpreterNativeFn::new(state_save)));
+    module.insert("load".to_string(), Value::InterpreterNativeFunction(InterpreterNativeFn::new(state_load)));
+    module
+}
+
+/// state.save(path, names | values) -> Silk
+/// `names` lists variables to read; a Relic is saved as it is.
+fn state_save(args: Vec<Value>, interp: &mut Interpreter) -> InterpreterFuture<'_> {
+    Box::pin(async move {
+        let (path, selection) = match args.as_slice() {
+            [Value::String(path), selection] => (path.clone(), selection),
+            _ => return Err(FlowError::runtime(
+                "state.save expects (path: Silk, names: Constellation<Silk> or values: Relic)",
+                0,
+                0,
+            )),
+        };
+        let values = match selection {
+            Value::Array(names) => {
+                let mut values = RelicMap::new();
+                for name in names.iter() {
+                    let Value::String(name) = name else {
+                        return Err(FlowError::type_error("state.save expects variable names as Silk", 0, 0));
+                    };
+                    let value = interp.lookup(name).ok_or_else(|| {
+                        FlowError::undefined(&format!("state.save: there is no variable named '{}'", name), 0, 0)
+                    })?;
+                    values.insert(name.to_string(), value);
+                }
+                values
+            }
+            Value::Relic(values) => (**values).clone(),
+            other => return Err(FlowError::type_error(
+                &format!("state.save expects a Constellation of variable names or a Relic, got {}", other.type_name()),
+                0,
+                0,
+            )),
+        };
+        // Errors name the variable, e.g. "state.onDone is a Spell"
+        let bytes = super::serial::to_bytes(&Value::Relic(Arc::new(values)), "state")?;
+        write_atomically(Path::new(path.as_str()), &bytes)
+            .map_err(|e| FlowError::runtime(&format!("Failed to save state to '{}': {}", path, e), 0, 0))?;
+        Ok(Value::String(path))
+    })
+}
+
+/// state.load(path) -> Relic
+/// Assigns each saved value to the variable of the same name and returns them all;
+/// with no file yet, nothing is restored and the Relic is empty.
+fn state_load(args: Vec<Value>, interp: &mut Interpreter) -> InterpreterFuture<'_> {
+    Box::pin(async move {
+        let path = match args.as_slice() {
+            [Value::String(path)] => path.clone(),
+            _ => return Err(FlowError::runtime("state.load expects 1 argument (path: Silk)", 0, 0)),
+        };
+        let bytes = match std::fs::read(path.as_str()) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Value::Relic(Arc::new(RelicMap::new()))),
+            Err(e) => return Err(FlowError::runtime(&format!("Failed to load state from '{}': {}", path, e), 0, 0)),
+        };
+        let Value::Relic(values) = super::serial::from_bytes(&bytes)? else {
+            return Err(FlowError::glitch(&format!("'{}' does not hold saved state", path), 0, 0));
+        };
+        for (name, value) in values.iter() {
+            // Saved values the script no longer declares are still returned
+            if interp.lookup(name).is_some() {
+                interp.assign(name, value.clone())?;
+            }
+        }
+        Ok(Value::Relic(values))
+    })
+}
+
+/// Write to a temporary file beside `path`, then rename it over `path`
+fn write_atomically(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(format!(".{}.tmp", std::process::id()));
+    std::fs::write(&temp, bytes)?;
+    std::fs::rename(&temp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::output::Stream;
+    use crate::interpreter::Interpreter;
+
+    #[tokio::test]
+    async fn test_save_and_restore_variables() {
+        let path = std::env::temp_dir().join(format!("flowlang-state-{}.state", std::process::id()));
+        let path = path.display().to_string().replace('\\', "/");
+        let source = format!(
+            concat!(
+                "circle state from \"std:state\"\n",
+                "let cursor = 0\n",
+                "let done = []\n",
+                "state.load(\"{0}\")\n",
+                "shout(cursor)\n",
+                "cursor = cursor + 5\n",
+                "done = done.push(cursor)\n",
+                "state.save(\"{0}\", [\"cursor\", \"done\"])\n",
+            ),
+            path
+        );
+        let mut outputs = Vec::new();
+        for _ in 0..2 {
+            let program = crate::lexer::tokenize(&source).and_then(crate::parser::parse).unwrap();
+            let mut interpreter = Interpreter::new(Default::default());
+            let (result, output) = interpreter.execute_captured(program).await;
+            result.unwrap();
+            outputs.extend(output);
+        }
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(outputs, vec![(Stream::Stdout, "0".to_string()), (Stream::Stdout, "5".to_string())]);
+    }
+}
@@ -1,13 +1,23 @@
 //! std:stream - File Streaming Module
 //!
 //! Provides file streaming functionality for serving files and handling large data.
+//!
+//! Lazy pipelines (`stream.of`, `fromFile`, `fromProcess`, `fromUrl`) read one
+//! item at a time: `stream.fromFile(path).map(spell).filter(spell).batch(100).forEach(spell)`
+//! never holds more than a batch in memory. Async sources read a little ahead
+//! and then wait until the pipeline catches up.
 
 use crate::error::FlowError;
-use crate::types::{Value, NativeFn};
-use std::collections::HashMap;
-use std::sync::Arc;
+use crate::interpreter::Interpreter;
+use crate::types::{InterpreterFuture, InterpreterNativeFn, Value, NativeFn, RelicMap};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io::BufRead;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use std::fs;
 use std::path::Path;
+use tokio::sync::mpsc;
 
 /// Load the stream module
 pub fn load_stream_module() -> Vec<(&'static str, Value)> {
@@ -19,6 +29,10 @@ pub fn load_stream_module() -> Vec<(&'static str, Value)> {
         ("exists", Value::NativeFunction(NativeFn(Arc::new(stream_exists)))),
         ("stat", Value::NativeFunction(NativeFn(Arc::new(stream_stat)))),
         ("mimeType", Value::NativeFunction(NativeFn(Arc::new(stream_mime_type)))),
+        ("of", Value::NativeFunction(NativeFn(Arc::new(stream_of)))),
+        ("fromFile", Value::NativeFunction(NativeFn(Arc::new(stream_from_file)))),
+        ("fromProcess", Value::NativeFunction(NativeFn(Arc::new(stream_from_process)))),
+        ("fromUrl", Value::NativeFunction(NativeFn(Arc::new(stream_from_url)))),
     ]
 }
 
@@ -56,7 +70,7 @@ fn stream_read_file(args: Vec<Value>) -> Result<Value, FlowError> {
         Value::String(Arc::new(base64_encode(&content)))
     };
     
-    let mut result = HashMap::new();
+    let mut result = RelicMap::new();
     result.insert("content".to_string(), content_value);
     result.insert("size".to_string(), Value::Number(size));
     result.insert("mimeType".to_string(), Value::String(Arc::new(mime)));
@@ -143,7 +157,7 @@ fn stream_stat(args: Vec<Value>) -> Result<Value, FlowError> {
         FlowError::runtime(&format!("Failed to get file stats: {}", e), 0, 0)
     })?;
     
-    let mut result = HashMap::new();
+    let mut result = RelicMap::new();
     result.insert("size".to_string(), Value::Number(metadata.len() as f64));
     result.insert("isFile".to_string(), Value::Boolean(metadata.is_file()));
     result.insert("isDir".to_string(), Value::Boolean(metadata.is_dir()));
@@ -165,7 +179,7 @@ fn stream_mime_type(args: Vec<Value>) -> Result<Value, FlowError> {
 }
 
 /// Get MIME type from file extension
-fn get_mime_type(path: &str) -> String {
+pub fn get_mime_type(path: &str) -> String {
     let ext = Path::new(path)
         .extension()
         .and_then(|e| e.to_str())
@@ -248,3 +262,349 @@ fn base64_encode(data: &[u8]) -> String {
     
     result
 }
+
+// ===== Lazy pipelines =====
+
+/// How many lines an async source reads ahead before it waits for the pipeline
+const READ_AHEAD: usize = 64;
+
+type LineReader = std::io::Lines<std::io::BufReader<fs::File>>;
+type LineReceiver = mpsc::Receiver<Result<String, FlowError>>;
+type PullFuture<'a> = Pin<Box<dyn Future<Output = Result<Option<Value>, FlowError>> + Send + 'a>>;
+type CloseFuture<'a> = Pin<Box<dyn Future<Output = Result<(), FlowError>> + Send + 'a>>;
+
+/// One step of a pipeline. Sources produce items; the other stages pull from
+/// the stage before them, so nothing is read until the end of the pipeline asks.
+enum Stage {
+    Items(Mutex<VecDeque<Value>>),
+    Lines(Mutex<Option<LineReader>>),
+    /// Lines sent by a background task (process output, HTTP bodies)
+    Channel(tokio::sync::Mutex<Option<LineReceiver>>),
+    /// Any Relic with a `next` Spell, such as `file.lines`
+    Iterator { next: Value, close: Option<Value>, closed: Mutex<bool> },
+    Map(Arc<Stage>, Value),
+    Filter(Arc<Stage>, Value),
+    Batch(Arc<Stage>, usize),
+    Take(Arc<Stage>, Mutex<usize>),
+    Skip(Arc<Stage>, Mutex<usize>),
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The next item from `stage`, or None once it is exhausted
+fn pull<'a>(stage: &'a Stage, interp: &'a mut Interpreter) -> PullFuture<'a> {
+    Box::pin(async move {
+        match stage {
+            Stage::Items(items) => Ok(lock(items).pop_front()),
+            Stage::Lines(reader) => {
+                let mut reader = lock(reader);
+                match reader.as_mut().and_then(|lines| lines.next()) {
+                    Some(Ok(line)) => Ok(Some(Value::String(Arc::new(line)))),
+                    Some(Err(e)) => {
+                        *reader = None;
+                        Err(FlowError::runtime(&format!("Failed to read line: {}", e), 0, 0))
+                    }
+                    None => {
+                        *reader = None;
+                        Ok(None)
+                    }
+                }
+            }
+            Stage::Channel(receiver) => {
+                let mut receiver = receiver.lock().await;
+                let line = match receiver.as_mut() {
+                    Some(rx) => rx.recv().await,
+                    None => None,
+                };
+                match line {
+                    Some(Ok(line)) => Ok(Some(Value::String(Arc::new(line)))),
+                    Some(Err(e)) => {
+                        *receiver = None;
+                        Err(e)
+                    }
+                    None => {
+                        *receiver = None;
+                        Ok(None)
+                    }
+                }
+            }
+            Stage::Iterator { next, closed, .. } => {
+                if *lock(closed) {
+                    return Ok(None);
+                }
+                match interp.execute_function(next.clone(), vec![]).await? {
+                    Value::Null => Ok(None),
+                    item => Ok(Some(item)),
+                }
+            }
+            Stage::Map(upstream, spell) => match pull(upstream, interp).await? {
+                Some(item) => Ok(Some(interp.execute_function(spell.clone(), vec![item]).await?)),
+                None => Ok(None),
+            },
+            Stage::Filter(upstream, spell) => loop {
+                let Some(item) = pull(upstream, interp).await? else {
+                    return Ok(None);
+                };
+                if interp.execute_function(spell.clone(), vec![item.clone()]).await?.is_truthy() {
+                    return Ok(Some(item));
+                }
+            },
+            Stage::Batch(upstream, size) => {
+                let mut batch = Vec::with_capacity(*size);
+                while batch.len() < *size {
+                    match pull(upstream, interp).await? {
+                        Some(item) => batch.push(item),
+                        None => break,
+                    }
+                }
+                Ok((!batch.is_empty()).then(|| Value::Array(Arc::new(batch))))
+            }
+            Stage::Take(upstream, remaining) => {
+                if *lock(remaining) == 0 {
+                    return Ok(None);
+                }
+                let item = pull(upstream, interp).await?;
+                let done = {
+                    let mut remaining = lock(remaining);
+                    *remaining -= 1;
+                    *remaining == 0
+                };
+                // Stop the source as soon as the last item is taken, so a process or
+                // download isn't left running for items nobody will read
+                if done {
+                    close(upstream, interp).await?;
+                }
+                Ok(item)
+            }
+            Stage::Skip(upstream, remaining) => {
+                loop {
+                    if *lock(remaining) == 0 {
+                        break;
+                    }
+                    if pull(upstream, interp).await?.is_none() {
+                        return Ok(None);
+                    }
+                    *lock(remaining) -= 1;
+                }
+                pull(upstream, interp).await
+            }
+        }
+    })
+}
+
+/// Stop `stage`'s source; later pulls return None
+fn close<'a>(stage: &'a Stage, interp: &'a mut Interpreter) -> CloseFuture<'a> {
+    Box::pin(async move {
+        match stage {
+            Stage::Items(items) => lock(items).clear(),
+            Stage::Lines(reader) => *lock(reader) = None,
+            // Dropping the receiver stops the sending task (and kills a process source)
+            Stage::Channel(receiver) => *receiver.lock().await = None,
+            Stage::Iterator { close, closed, .. } => {
+                let already_closed = std::mem::replace(&mut *lock(closed), true);
+                if let (false, Some(close)) = (already_closed, close) {
+                    interp.execute_function(close.clone(), vec![]).await?;
+                }
+            }
+            Stage::Map(upstream, _)
+            | Stage::Filter(upstream, _)
+            | Stage::Batch(upstream, _)
+            | Stage::Take(upstream, _)
+            | Stage::Skip(upstream, _) => close(upstream, interp).await?,
+        }
+        Ok(())
+    })
+}
+
+fn spell_arg(args: &[Value], func: &str) -> Result<Value, FlowError> {
+    match args.first() {
+        Some(spell) if spell.type_name() == "Spell" => Ok(spell.clone()),
+        _ => Err(FlowError::type_error(&format!("stream.{} expects a Spell", func), 0, 0)),
+    }
+}
+
+fn count_arg(args: &[Value], func: &str, min: usize) -> Result<usize, FlowError> {
+    match args.first() {
+        Some(Value::Number(n)) if n.fract() == 0.0 && *n >= min as f64 => Ok(*n as usize),
+        _ => Err(FlowError::type_error(
+            &format!("stream.{} expects a whole Ember of at least {}", func, min),
+            0,
+            0,
+        )),
+    }
+}
+
+/// An InterpreterNativeFunction method that runs `f` with the pipeline's last stage
+fn interp_method<F>(stage: &Arc<Stage>, f: F) -> Value
+where
+    F: for<'a> Fn(Arc<Stage>, Vec<Value>, &'a mut Interpreter) -> InterpreterFuture<'a> + Send + Sync + 'static,
+{
+    let stage = stage.clone();
+    Value::InterpreterNativeFunction(InterpreterNativeFn::new(move |args, interp| f(stage.clone(), args, interp)))
+}
+
+/// A method that adds a stage and returns the longer pipeline
+fn stage_method<F>(stage: &Arc<Stage>, f: F) -> Value
+where
+    F: Fn(Arc<Stage>, Vec<Value>) -> Result<Stage, FlowError> + Send + Sync + 'static,
+{
+    let stage = stage.clone();
+    Value::NativeFunction(NativeFn::new(move |args| Ok(pipeline(Arc::new(f(stage.clone(), args)?)))))
+}
+
+/// Wrap a stage as a stream Relic. `next` makes it usable in `enter Phase ... in`.
+fn pipeline(stage: Arc<Stage>) -> Value {
+    let mut map = RelicMap::new();
+
+    map.insert("map".to_string(), stage_method(&stage, |up, args| Ok(Stage::Map(up, spell_arg(&args, "map")?))));
+    map.insert("filter".to_string(), stage_method(&stage, |up, args| Ok(Stage::Filter(up, spell_arg(&args, "filter")?))));
+    map.insert("batch".to_string(), stage_method(&stage, |up, args| Ok(Stage::Batch(up, count_arg(&args, "batch", 1)?))));
+    map.insert("take".to_string(), stage_method(&stage, |up, args| Ok(Stage::Take(up, Mutex::new(count_arg(&args, "take", 0)?)))));
+    map.insert("skip".to_string(), stage_method(&stage, |up, args| Ok(Stage::Skip(up, Mutex::new(count_arg(&args, "skip", 0)?)))));
+
+    map.insert("next".to_string(), interp_method(&stage, |stage, _args, interp| {
+        Box::pin(async move { Ok(pull(&stage, interp).await?.unwrap_or(Value::Null)) })
+    }));
+
+    // forEach(spell) -> Ember: items delivered; returning false from the Spell stops early
+    map.insert("forEach".to_string(), interp_method(&stage, |stage, args, interp| {
+        Box::pin(async move {
+            let spell = spell_arg(&args, "forEach")?;
+            let mut delivered = 0;
+            while let Some(item) = pull(&stage, interp).await? {
+                delivered += 1;
+                if matches!(interp.execute_function(spell.clone(), vec![item]).await?, Value::Boolean(false)) {
+                    close(&stage, interp).await?;
+                    break;
+                }
+            }
+            Ok(Value::Number(delivered as f64))
+        })
+    }));
+
+    map.insert("collect".to_string(), interp_method(&stage, |stage, _args, interp| {
+        Box::pin(async move {
+            let mut items = Vec::new();
+            while let Some(item) = pull(&stage, interp).await? {
+                items.push(item);
+            }
+            Ok(Value::Array(Arc::new(items)))
+        })
+    }));
+
+    map.insert("reduce".to_string(), interp_method(&stage, |stage, args, interp| {
+        Box::pin(async move {
+            let spell = spell_arg(&args, "reduce")?;
+            let mut acc = args.get(1).cloned().unwrap_or(Value::Null);
+            while let Some(item) = pull(&stage, interp).await? {
+                acc = interp.execute_function(spell.clone(), vec![acc, item]).await?;
+            }
+            Ok(acc)
+        })
+    }));
+
+    map.insert("close".to_string(), interp_method(&stage, |stage, _args, interp| {
+        Box::pin(async move {
+            close(&stage, interp).await?;
+            Ok(Value::Boolean(true))
+        })
+    }));
+
+    Value::Relic(Arc::new(map))
+}
+
+/// stream.of(items) -> Stream
+/// Accepts a Constellation, a Covenant, or any Relic with a `next` Spell
+fn stream_of(args: Vec<Value>) -> Result<Value, FlowError> {
+    let stage = match args.first() {
+        Some(Value::Array(items)) => Stage::Items(Mutex::new(items.iter().cloned().collect())),
+        Some(Value::Covenant(set)) => Stage::Items(Mutex::new(set.values().cloned().collect())),
+        Some(Value::Relic(iter)) if iter.get("next").is_some_and(|n| n.type_name() == "Spell") => Stage::Iterator {
+            next: iter["next"].clone(),
+            close: iter.get("close").filter(|c| c.type_name() == "Spell").cloned(),
+            closed: Mutex::new(false),
+        },
+        other => {
+            return Err(FlowError::type_error(
+                &format!(
+                    "stream.of expects a Constellation, Covenant or iterator, got {}",
+                    other.map(|v| v.type_name()).unwrap_or("nothing")
+                ),
+                0,
+                0,
+            ))
+        }
+    };
+    Ok(pipeline(Arc::new(stage)))
+}
+
+/// stream.fromFile(path) -> Stream of lines
+fn stream_from_file(args: Vec<Value>) -> Result<Value, FlowError> {
+    let path = match args.first() {
+        Some(Value::String(path)) => path.to_string(),
+        _ => return Err(FlowError::type_error("stream.fromFile expects a Silk path", 0, 0)),
+    };
+    let file = fs::File::open(&path).map_err(|e| {
+        FlowError::runtime(&format!("Failed to open file '{}': {}", path, e), 0, 0)
+    })?;
+    let lines = std::io::BufReader::new(file).lines();
+    Ok(pipeline(Arc::new(Stage::Lines(Mutex::new(Some(lines))))))
+}
+
+/// stream.fromProcess(program, args?, options?) -> Stream of stdout lines
+fn stream_from_process(args: Vec<Value>) -> Result<Value, FlowError> {
+    let rx = super::process::stdout_lines(&args, "stream.fromProcess", READ_AHEAD)?;
+    Ok(pipeline(Arc::new(Stage::Channel(tokio::sync::Mutex::new(Some(rx))))))
+}
+
+/// stream.fromUrl(url) -> Stream of response body lines
+fn stream_from_url(args: Vec<Value>) -> Result<Value, FlowError> {
+    let url = match args.first() {
+        Some(Value::String(url)) => url.to_string(),
+        _ => return Err(FlowError::type_error("stream.fromUrl expects a Silk URL", 0, 0)),
+    };
+    let (tx, rx) = mpsc::channel(READ_AHEAD);
+
+    tokio::spawn(async move {
+        let fail = |e: reqwest::Error| Err(FlowError::rift(&format!("stream.fromUrl failed: {}", e), 0, 0));
+        let mut response = match reqwest::get(&url).await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response,
+            Err(e) => {
+                let _ = tx.send(fail(e)).await;
+                return;
+            }
+        };
+
+        let mut pending = Vec::new();
+        loop {
+            let chunk = match response.chunk().await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(e) => {
+                    let _ = tx.send(fail(e)).await;
+                    return;
+                }
+            };
+            pending.extend_from_slice(&chunk);
+            while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                if tx.send(Ok(body_line(&line))).await.is_err() {
+                    return;
+                }
+            }
+        }
+        if !pending.is_empty() {
+            let _ = tx.send(Ok(body_line(&pending))).await;
+        }
+    });
+
+    Ok(pipeline(Arc::new(Stage::Channel(tokio::sync::Mutex::new(Some(rx))))))
+}
+
+/// A line of a response body without its `\n` or `\r\n`
+fn body_line(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    text.trim_end_matches('\n').trim_end_matches('\r').to_string()
+}
@@ -1,10 +1,9 @@
-use crate::types::{Value, NativeFn};
+use crate::types::{Value, NativeFn, RelicMap};
 use crate::error::FlowError;
-use std::collections::HashMap;
 use std::sync::Arc;
 
-pub fn get_module() -> HashMap<String, Value> {
-    let mut module = HashMap::new();
+pub fn get_module() -> RelicMap {
+    let mut module = RelicMap::new();
     
     module.insert("len".to_string(), Value::NativeFunction(NativeFn(Arc::new(|args| {
         if args.len() != 1 {
@@ -0,0 +1,253 @@
+//! std:sync - State shared between web requests
+//!
+//! - `sync.cell(initial?)` - One shared value
+//! - `sync.map(initial?)` - A shared Relic-like map
+//! - `sync.counter(start?)` - A shared number with atomic increments
+//!
+//! Each web request runs on a snapshot of the script's globals, so changing a
+//! global in a handler is an error. Values created here are shared by every
+//! request instead: all copies of a cell point at the same storage.
+//!
+//! Stored values outlive the request that stores them, so they are copied out of
+//! its arena onto the shared heap (see crate::arena).
+
+use crate::arena::on_heap;
+use crate::error::FlowError;
+use crate::interpreter::Interpreter;
+use crate::types::{InterpreterFuture, InterpreterNativeFn, NativeFn, Value, RelicMap};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+pub fn load_sync_module() -> Vec<(&'static str, Value)> {
+    vec![
+        ("cell", Value::NativeFunction(NativeFn::new(sync_cell))),
+        ("map", Value::NativeFunction(NativeFn::new(sync_map))),
+        ("counter", Value::NativeFunction(NativeFn::new(sync_counter))),
+    ]
+}
+
+/// How many times `update` re-runs its Spell when other requests keep changing the value
+const MAX_UPDATE_ATTEMPTS: usize = 100;
+
+/// A value plus a version that changes on every write, so `update` can tell
+/// whether someone else wrote while its Spell was running
+struct Versioned<T> {
+    value: T,
+    version: u64,
+}
+
+type Shared<T> = Arc<Mutex<Versioned<T>>>;
+
+fn shared<T>(value: T) -> Shared<T> {
+    on_heap(|| Arc::new(Mutex::new(Versioned { value, version: 0 })))
+}
+
+/// Lock shared storage, even if a panic elsewhere poisoned the lock; every write
+/// here is a single assignment, so the data is never left half-updated
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn method(f: impl Fn(Vec<Value>) -> Result<Value, FlowError> + Send + Sync + 'static) -> Value {
+    Value::NativeFunction(NativeFn::new(f))
+}
+
+/// Runs `spell` on a snapshot and commits the result only if nothing changed meanwhile.
+/// `read` takes (snapshot, version); `write` stores the result if the version still matches.
+async fn update_loop<R, W>(interp: &mut Interpreter, spell: Value, read: R, write: W) -> Result<Value, FlowError>
+where
+    R: Fn() -> Result<(Value, u64), FlowError>,
+    W: Fn(Value, u64) -> bool,
+{
+    for _ in 0..MAX_UPDATE_ATTEMPTS {
+        let (current, version) = read()?;
+        let next = interp.execute_function(spell.clone(), vec![current]).await?;
+        if write(next.clone(), version) {
+            return Ok(next);
+        }
+    }
+    Err(FlowError::runtime(
+        &format!("sync update gave up after {} attempts; the value kept changing underneath it", MAX_UPDATE_ATTEMPTS),
+        0,
+        0,
+    ))
+}
+
+fn spell_arg(args: &[Value], index: usize, func: &str) -> Result<Value, FlowError> {
+    match args.get(index) {
+        Some(spell) if spell.type_name() == "Spell" => Ok(spell.clone()),
+        _ => Err(FlowError::type_error(&format!("{} expects a Spell", func), 0, 0)),
+    }
+}
+
+fn key_arg(args: &[Value], func: &str) -> Result<String, FlowError> {
+    match args.first() {
+        Some(Value::String(key)) => Ok(key.to_string()),
+        Some(Value::Number(_)) => Ok(args[0].to_string()),
+        _ => Err(FlowError::type_error(&format!("{} expects a Silk key", func), 0, 0)),
+    }
+}
+
+/// sync.cell(initial?) -> Relic {get, set, update}
+fn sync_cell(args: Vec<Value>) -> Result<Value, FlowError> {
+    let cell = shared(on_heap(|| args.first().map_or(Value::Null, Value::deep_clone)));
+    let mut map = RelicMap::new();
+
+    let c = cell.clone();
+    map.insert("get".to_string(), method(move |_| Ok(lock(&c).value.clone())));
+
+    let c = cell.clone();
+    map.insert("set".to_string(), method(move |args| {
+        let value = args.into_iter().next().unwrap_or(Value::Null);
+        let mut slot = lock(&c);
+        slot.value = on_heap(|| value.deep_clone());
+        slot.version += 1;
+        Ok(value)
+    }));
+
+    map.insert("update".to_string(), Value::InterpreterNativeFunction(InterpreterNativeFn::new(
+        move |args: Vec<Value>, interp: &mut Interpreter| -> InterpreterFuture<'_> {
+            let cell = cell.clone();
+            Box::pin(async move {
+                let spell = spell_arg(&args, 0, "cell.update")?;
+                let read = || {
+                    let slot = lock(&cell);
+                    Ok((slot.value.clone(), slot.version))
+                };
+                let write = |next: Value, version: u64| {
+                    let mut slot = lock(&cell);
+                    if slot.version != version {
+                        return false;
+                    }
+                    slot.value = on_heap(|| next.deep_clone());
+                    slot.version += 1;
+                    true
+                };
+                update_loop(interp, spell, read, write).await
+            })
+        },
+    )));
+
+    Ok(Value::Relic(Arc::new(map)))
+}
+
+/// sync.map(initial?) -> Relic {get, set, delete, has, keys, size, update, snapshot}
+fn sync_map(args: Vec<Value>) -> Result<Value, FlowError> {
+    let initial = match args.first() {
+        Some(Value::Relic(initial)) => on_heap(|| initial.iter().map(|(k, v)| (k.clone(), v.deep_clone())).collect()),
+        None | Some(Value::Null) => RelicMap::new(),
+        Some(_) => return Err(FlowError::type_error("sync.map expects a Relic of initial entries", 0, 0)),
+    };
+    let store = shared(initial);
+    let mut map = RelicMap::new();
+
+    let s = store.clone();
+    map.insert("get".to_string(), method(move |args| {
+        let key = key_arg(&args, "map.get")?;
+        let default = args.get(1).cloned().unwrap_or(Value::Null);
+        Ok(lock(&s).value.get(&key).cloned().unwrap_or(default))
+    }));
+
+    let s = store.clone();
+    map.insert("set".to_string(), method(move |args| {
+        let key = key_arg(&args, "map.set")?;
+        let value = args.get(1).cloned().unwrap_or(Value::Null);
+        let mut slot = lock(&s);
+        on_heap(|| slot.value.insert(key.clone(), value.deep_clone()));
+        slot.version += 1;
+        Ok(value)
+    }));
+
+    let s = store.clone();
+    map.insert("delete".to_string(), method(move |args| {
+        let key = key_arg(&args, "map.delete")?;
+        let mut slot = lock(&s);
+        let removed = slot.value.shift_remove(&key).is_some();
+        if removed {
+            slot.version += 1;
+        }
+        Ok(Value::Boolean(removed))
+    }));
+
+    let s = store.clone();
+    map.insert("has".to_string(), method(move |args| {
+        let key = key_arg(&args, "map.has")?;
+        Ok(Value::Boolean(lock(&s).value.contains_key(&key)))
+    }));
+
+    let s = store.clone();
+    map.insert("keys".to_string(), method(move |_| {
+        let mut keys: Vec<String> = lock(&s).value.keys().cloned().collect();
+        keys.sort();
+        Ok(Value::Array(Arc::new(keys.into_iter().map(|k| Value::String(Arc::new(k))).collect())))
+    }));
+
+    let s = store.clone();
+    map.insert("size".to_string(), method(move |_| Ok(Value::Number(lock(&s).value.len() as f64))));
+
+    let s = store.clone();
+    map.insert("snapshot".to_string(), method(move |_| Ok(Value::Relic(Arc::new(lock(&s).value.clone())))));
+
+    map.insert("update".to_string(), Value::InterpreterNativeFunction(InterpreterNativeFn::new(
+        move |args: Vec<Value>, interp: &mut Interpreter| -> InterpreterFuture<'_> {
+            let store = store.clone();
+            Box::pin(async move {
+                let key = key_arg(&args, "map.update")?;
+                let spell = spell_arg(&args, 1, "map.update")?;
+                let default = args.get(2).cloned().unwrap_or(Value::Null);
+                let read = || {
+                    let slot = lock(&store);
+                    Ok((slot.value.get(&key).cloned().unwrap_or_else(|| default.clone()), slot.version))
+                };
+                let write = |next: Value, version: u64| {
+                    let mut slot = lock(&store);
+                    if slot.version != version {
+                        return false;
+                    }
+                    on_heap(|| slot.value.insert(key.clone(), next.deep_clone()));
+                    slot.version += 1;
+                    true
+                };
+                update_loop(interp, spell, read, write).await
+            })
+        },
+    )));
+
+    Ok(Value::Relic(Arc::new(map)))
+}
+
+/// sync.counter(start?) -> Relic {get, add, reset}
+fn sync_counter(args: Vec<Value>) -> Result<Value, FlowError> {
+    let start = match args.first() {
+        Some(Value::Number(n)) => *n,
+        None => 0.0,
+        Some(_) => return Err(FlowError::type_error("sync.counter expects an Ember to start from", 0, 0)),
+    };
+    let counter = Arc::new(Mutex::new(start));
+    let mut map = RelicMap::new();
+
+    let c = counter.clone();
+    map.insert("get".to_string(), method(move |_| Ok(Value::Number(*lock(&c)))));
+
+    let c = counter.clone();
+    map.insert("add".to_string(), method(move |args| {
+        let amount = match args.first() {
+            Some(Value::Number(n)) => *n,
+            None => 1.0,
+            Some(_) => return Err(FlowError::type_error("counter.add expects an Ember", 0, 0)),
+        };
+        let mut value = lock(&c);
+        *value += amount;
+        Ok(Value::Number(*value))
+    }));
+
+    map.insert("reset".to_string(), method(move |args| {
+        let to = match args.first() {
+            Some(Value::Number(n)) => *n,
+            _ => 0.0,
+        };
+        *lock(&counter) = to;
+        Ok(Value::Number(to))
+    }));
+
+    Ok(Value::Relic(Arc::new(map)))
+}
@@ -0,0 +1,405 @@
+//! std:template - Server-side HTML templates
+//!
+//! Handlebars-style tags:
+//! - `{{ user.name }}` interpolates with HTML escaping, `{{{ html }}}` without
+//! - `{{#if cond}} .. {{else}} .. {{/if}}` and `{{#unless cond}} .. {{/unless}}`
+//! - `{{#each items}} {{this}} {{@index}} {{/each}}`, with `{{else}}` for empty lists
+//! - `{{> header}}` includes a partial, `{{! note }}` is a comment
+//!
+//! Inside `each`, names are looked up on the current item first, then outwards.
+
+use crate::error::FlowError;
+use crate::types::{NativeFn, Value, RelicMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+// Partials including themselves would otherwise recurse forever
+const MAX_PARTIAL_DEPTH: usize = 32;
+
+pub fn load_template_module() -> Vec<(&'static str, Value)> {
+    vec![
+        ("render", Value::NativeFunction(NativeFn::new(template_render))),
+        ("renderString", Value::NativeFunction(NativeFn::new(template_render_string))),
+        ("escape", Value::NativeFunction(NativeFn::new(template_escape))),
+    ]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Block {
+    If,
+    Unless,
+    Each,
+}
+
+impl Block {
+    fn name(self) -> &'static str {
+        match self {
+            Block::If => "if",
+            Block::Unless => "unless",
+            Block::Each => "each",
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Var { path: String, escape: bool },
+    Section { block: Block, path: String, body: Vec<Node>, otherwise: Vec<Node> },
+    Partial(String),
+}
+
+/// Where partials are loaded from
+enum Partials<'a> {
+    /// Files next to the template being rendered
+    Dir(PathBuf),
+    /// Named sources passed to renderString
+    Map(&'a RelicMap),
+    None,
+}
+
+/// template.render(file, data?) -> Silk
+fn template_render(args: Vec<Value>) -> Result<Value, FlowError> {
+    let path = match args.first() {
+        Some(Value::String(s)) => PathBuf::from(s.as_str()),
+        _ => return Err(FlowError::type_error("template.render expects a file path", 0, 0)),
+    };
+    let data = args.get(1).cloned().unwrap_or(Value::Null);
+    let source = read_template(&path)?;
+    let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let extension = path.extension().map(|e| e.to_string_lossy().to_string());
+    let name = path.display().to_string();
+    let rendered = render(&source, &name, &data, &Partials::Dir(dir), extension.as_deref(), 0)?;
+    Ok(Value::String(Arc::new(rendered)))
+}
+
+/// template.renderString(source, data?, partials?) -> Silk
+fn template_render_string(args: Vec<Value>) -> Result<Value, FlowError> {
+    let source = match args.first() {
+        Some(Value::String(s)) => s.clone(),
+        _ => return Err(FlowError::type_error("template.renderString expects a Silk template", 0, 0)),
+    };
+    let data = args.get(1).cloned().unwrap_or(Value::Null);
+    let rendered = match args.get(2) {
+        Some(Value::Relic(partials)) => render(&source, "template", &data, &Partials::Map(partials), None, 0)?,
+        Some(_) => return Err(FlowError::type_error("template.renderString expects a Relic of partials", 0, 0)),
+        None => render(&source, "template", &data, &Partials::None, None, 0)?,
+    };
+    Ok(Value::String(Arc::new(rendered)))
+}
+
+/// template.escape(text) -> Silk
+fn template_escape(args: Vec<Value>) -> Result<Value, FlowError> {
+    match args.first() {
+        Some(value) => Ok(Value::String(Arc::new(escape_html(&display(value))))),
+        None => Err(FlowError::runtime("template.escape expects 1 argument", 0, 0)),
+    }
+}
+
+fn read_template(path: &Path) -> Result<String, FlowError> {
+    std::fs::read_to_string(path).map_err(|e| {
+        FlowError::runtime(&format!("Cannot read template '{}': {}", path.display(), e), 0, 0)
+    })
+}
+
+fn render(source: &str, name: &str, data: &Value, partials: &Partials, extension: Option<&str>, depth: usize) -> Result<String, FlowError> {
+    let nodes = parse(source).map_err(|(line, message)| {
+        FlowError::runtime(&format!("{}:{}: {}", name, line, message), 0, 0)
+    })?;
+    let mut out = String::new();
+    let mut renderer = Renderer { scopes: vec![Scope { value: data.clone(), index: None }], partials, extension, depth };
+    renderer.render_nodes(&nodes, &mut out)?;
+    Ok(out)
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+/// A block whose closing tag hasn't been seen yet
+struct OpenBlock {
+    block: Block,
+    path: String,
+    line: usize,
+    body: Vec<Node>,
+    /// Set once `{{else}}` is seen
+    otherwise: Option<Vec<Node>>,
+}
+
+/// Parse a template into nodes. Errors carry the 1-based line of the tag.
+fn parse(source: &str) -> Result<Vec<Node>, (usize, String)> {
+    let mut rest = source;
+    let mut stack: Vec<OpenBlock> = Vec::new();
+    let mut nodes: Vec<Node> = Vec::new();
+
+    // Where the next node goes: the innermost open branch, or the top level
+    fn current<'a>(nodes: &'a mut Vec<Node>, stack: &'a mut [OpenBlock]) -> &'a mut Vec<Node> {
+        match stack.last_mut() {
+            Some(OpenBlock { otherwise: Some(otherwise), .. }) => otherwise,
+            Some(open) => &mut open.body,
+            None => nodes,
+        }
+    }
+
+    while let Some(start) = rest.find("{{") {
+        let line = source[..source.len() - rest.len() + start].matches('\n').count() + 1;
+        if start > 0 {
+            current(&mut nodes, &mut stack).push(Node::Text(rest[..start].to_string()));
+        }
+
+        let triple = rest[start..].starts_with("{{{");
+        let (open, close) = if triple { (3, "}}}") } else { (2, "}}") };
+        let after = &rest[start + open..];
+        let end = after.find(close).ok_or((line, format!("Unclosed '{}'", &rest[start..start + open])))?;
+        let tag = after[..end].trim();
+        rest = &after[end + close.len()..];
+
+        if triple {
+            current(&mut nodes, &mut stack).push(Node::Var { path: tag.to_string(), escape: false });
+            continue;
+        }
+
+        match tag.chars().next() {
+            Some('!') => {}
+            Some('&') => current(&mut nodes, &mut stack).push(Node::Var { path: tag[1..].trim().to_string(), escape: false }),
+            Some('>') => current(&mut nodes, &mut stack).push(Node::Partial(tag[1..].trim().to_string())),
+            Some('#') => {
+                let mut parts = tag[1..].split_whitespace();
+                let block = match parts.next() {
+                    Some("if") => Block::If,
+                    Some("unless") => Block::Unless,
+                    Some("each") => Block::Each,
+                    other => return Err((line, format!("Unknown block '#{}' (expected #if, #unless or #each)", other.unwrap_or("")))),
+                };
+                let path = parts.next().ok_or((line, format!("'#{}' needs a value", block.name())))?;
+                stack.push(OpenBlock { block, path: path.to_string(), line, body: Vec::new(), otherwise: None });
+            }
+            Some('/') => {
+                let name = tag[1..].trim();
+                let open = stack.pop().ok_or((line, format!("'/{}' without an open block", name)))?;
+                if name != open.block.name() {
+                    return Err((line, format!("'/{}' closes '#{}'", name, open.block.name())));
+                }
+                let section = Node::Section {
+                    block: open.block,
+                    path: open.path,
+                    body: open.body,
+                    otherwise: open.otherwise.unwrap_or_default(),
+                };
+                current(&mut nodes, &mut stack).push(section);
+            }
+            _ if tag == "else" => match stack.last_mut() {
+                Some(OpenBlock { otherwise: otherwise @ None, .. }) => *otherwise = Some(Vec::new()),
+                Some(_) => return Err((line, "Second 'else' in one block".to_string())),
+                None => return Err((line, "'else' outside a block".to_string())),
+            },
+            None => return Err((line, "Empty tag".to_string())),
+            _ => current(&mut nodes, &mut stack).push(Node::Var { path: tag.to_string(), escape: true }),
+        }
+    }
+
+    if let Some(open) = stack.last() {
+        return Err((open.line, format!("'#{}' is never closed", open.block.name())));
+    }
+    if !rest.is_empty() {
+        nodes.push(Node::Text(rest.to_string()));
+    }
+    Ok(nodes)
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+struct Scope {
+    value: Value,
+    /// (index, length) inside #each
+    index: Option<(usize, usize)>,
+}
+
+struct Renderer<'a> {
+    scopes: Vec<Scope>,
+    partials: &'a Partials<'a>,
+    extension: Option<&'a str>,
+    depth: usize,
+}
+
+impl Renderer<'_> {
+    fn render_nodes(&mut self, nodes: &[Node], out: &mut String) -> Result<(), FlowError> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Var { path, escape } => {
+                    let text = display(&self.lookup(path));
+                    if *escape {
+                        out.push_str(&escape_html(&text));
+                    } else {
+                        out.push_str(&text);
+                    }
+                }
+                Node::Section { block, path, body, otherwise } => {
+                    let value = self.lookup(path);
+                    match block {
+                        Block::If | Block::Unless => {
+                            let branch = if value.is_truthy() == (*block == Block::If) { body } else { otherwise };
+                            self.render_nodes(branch, out)?;
+                        }
+                        Block::Each => {
+                            let items: Vec<Value> = match &value {
+                                Value::Array(items) => items.iter().cloned().collect(),
+                                // Relics iterate their values in insertion order
+                                Value::Relic(map) => map.values().cloned().collect(),
+                                _ => Vec::new(),
+                            };
+                            if items.is_empty() {
+                                self.render_nodes(otherwise, out)?;
+                            }
+                            let len = items.len();
+                            for (i, item) in items.into_iter().enumerate() {
+                                self.scopes.push(Scope { value: item, index: Some((i, len)) });
+                                let result = self.render_nodes(body, out);
+                                self.scopes.pop();
+                                result?;
+                            }
+                        }
+                    }
+                }
+                Node::Partial(name) => self.render_partial(name, out)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn render_partial(&mut self, name: &str, out: &mut String) -> Result<(), FlowError> {
+        if self.depth >= MAX_PARTIAL_DEPTH {
+            return Err(FlowError::runtime(&format!("Partial '{}' nests too deeply (recursive include?)", name), 0, 0));
+        }
+        let (source, partials, extension) = match self.partials {
+            Partials::Dir(dir) => {
+                let mut path = dir.join(name);
+                if path.extension().is_none() {
+                    if let Some(extension) = self.extension {
+                        path.set_extension(extension);
+                    }
+                }
+                let source = read_template(&path)?;
+                let child_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                (source, Partials::Dir(child_dir), self.extension)
+            }
+            Partials::Map(map) => match map.get(name) {
+                Some(Value::String(source)) => (source.to_string(), Partials::Map(map), None),
+                _ => return Err(FlowError::runtime(&format!("Unknown partial '{}'", name), 0, 0)),
+            },
+            Partials::None => return Err(FlowError::runtime(&format!("Unknown partial '{}' (pass partials to renderString)", name), 0, 0)),
+        };
+
+        let nodes = parse(&source).map_err(|(line, message)| {
+            FlowError::runtime(&format!("{}:{}: {}", name, line, message), 0, 0)
+        })?;
+        // Partials see the data of the place they are included from
+        let mut child = Renderer { scopes: std::mem::take(&mut self.scopes), partials: &partials, extension, depth: self.depth + 1 };
+        let result = child.render_nodes(&nodes, out);
+        self.scopes = child.scopes;
+        result
+    }
+
+    fn lookup(&self, path: &str) -> Value {
+        let scope = self.scopes.last().expect("template scope stack is never empty");
+        match path {
+            "this" | "." => return scope.value.clone(),
+            "@index" => return scope.index.map(|(i, _)| Value::Number(i as f64)).unwrap_or(Value::Null),
+            "@first" => return Value::Boolean(matches!(scope.index, Some((0, _)))),
+            "@last" => return Value::Boolean(matches!(scope.index, Some((i, len)) if i + 1 == len)),
+            _ => {}
+        }
+
+        let path = path.strip_prefix("this.").unwrap_or(path);
+        let mut segments = path.split('.');
+        let first = segments.next().unwrap_or("");
+        let found = self.scopes.iter().rev().find_map(|scope| match &scope.value {
+            Value::Relic(map) => map.get(first).cloned(),
+            _ => None,
+        });
+
+        let mut value = match found {
+            Some(value) => value,
+            None => return Value::Null,
+        };
+        for segment in segments {
+            value = match &value {
+                Value::Relic(map) => map.get(segment).cloned().unwrap_or(Value::Null),
+                Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i).cloned()).unwrap_or(Value::Null),
+                _ => Value::Null,
+            };
+        }
+        value
+    }
+}
+
+// Missing values render as nothing rather than "null"
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Value {
+        Value::String(Arc::new(s.to_string()))
+    }
+
+    fn relic(pairs: Vec<(&str, Value)>) -> Value {
+        Value::Relic(Arc::new(pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect()))
+    }
+
+    #[test]
+    fn test_render_blocks_and_escaping() {
+        let data = relic(vec![
+            ("title", text("<Guild>")),
+            ("raw", text("<b>hi</b>")),
+            ("admin", Value::Boolean(false)),
+            ("members", Value::Array(Arc::new(vec![
+                relic(vec![("name", text("Aria"))]),
+                relic(vec![("name", text("Kaito"))]),
+            ]))),
+            ("empty", Value::Array(Arc::new(vec![]))),
+        ]);
+        let source = "{{! header }}<h1>{{ title }}</h1>{{{raw}}}\
+            {{#each members}}{{@index}}:{{name}}/{{title}}{{#unless @last}}, {{/unless}}{{/each}}\
+            {{#if admin}}admin{{else}}guest{{/if}}\
+            {{#each empty}}x{{else}}none{{/each}}{{missing.deep}}";
+        let out = render(source, "test", &data, &Partials::None, None, 0).unwrap();
+        assert_eq!(out, "<h1>&lt;Guild&gt;</h1><b>hi</b>0:Aria/&lt;Guild&gt;, 1:Kaito/&lt;Guild&gt;guestnone");
+    }
+
+    #[test]
+    fn test_partials_and_errors() {
+        let partials: RelicMap = [("item".to_string(), text("[{{this}}]"))].into_iter().collect();
+        let data = relic(vec![("xs", Value::Array(Arc::new(vec![Value::Number(1.0), Value::Number(2.5)])))]);
+        let out = render("{{#each xs}}{{> item}}{{/each}}", "t", &data, &Partials::Map(&partials), None, 0).unwrap();
+        assert_eq!(out, "[1][2.5]");
+
+        let err = render("line\n{{#each xs}}\n{{/if}}", "page.html", &data, &Partials::None, None, 0).unwrap_err();
+        assert!(err.to_string().contains("page.html:3: '/if' closes '#each'"), "{}", err);
+        assert!(render("{{#if x}}", "t", &data, &Partials::None, None, 0).is_err());
+    }
+}
@@ -3,18 +3,20 @@
 //! Provides timer functions that keep the process alive:
 //! - `timer.interval(ms, callback)` - Repeating timer
 //! - `timer.timeout(ms, callback)` - One-shot timer
+//! - `timer.cron(expression, callback)` - Wall-clock schedule ("*/5 * * * *")
 //! - `timer.clear(handle)` - Cancel a timer
 
 use crate::error::FlowError;
 use crate::types::{AsyncNativeFn, Value, AsyncContext};
 use crate::runtime::handle::HandleType;
-use std::sync::Arc;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDateTime, TimeZone, Timelike};
 use tokio::sync::oneshot;
 
 pub fn load_timer_module() -> Vec<(&'static str, Value)> {
     vec![
         ("interval", Value::AsyncNativeFunction(AsyncNativeFn::new(timer_interval))),
         ("timeout", Value::AsyncNativeFunction(AsyncNativeFn::new(timer_timeout))),
+        ("cron", Value::AsyncNativeFunction(AsyncNativeFn::new(timer_cron))),
         ("clear", Value::AsyncNativeFunction(AsyncNativeFn::new(timer_clear))),
     ]
 }
@@ -39,7 +41,7 @@ async fn timer_interval(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, Fl
     };
 
     let callback = match &args[1] {
-        Value::Function { .. } | Value::NativeFunction(_) => args[1].clone(),
+        callback if callback.type_name() == "Spell" => callback.clone(),
         _ => return Err(FlowError::type_error(
             "timer.interval expects a Spell (function) as callback",
             0, 0,
@@ -109,7 +111,7 @@ async fn timer_timeout(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, Flo
     };
 
     let callback = match &args[1] {
-        Value::Function { .. } | Value::NativeFunction(_) => args[1].clone(),
+        callback if callback.type_name() == "Spell" => callback.clone(),
         _ => return Err(FlowError::type_error(
             "timer.timeout expects a Spell (function) as callback",
             0, 0,
@@ -156,8 +158,215 @@ async fn timer_timeout(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, Flo
     Ok(Value::Handle(handle_id))
 }
 
+/// timer.cron(expression, callback) -> Handle
+/// Calls the callback whenever the local time matches a 5-field cron expression
+/// (minute hour day-of-month month day-of-week). Fields accept `*`, numbers,
+/// ranges `a-b`, lists `a,b` and steps `*/n` or `a-b/n`; day-of-week 0 and 7
+/// are both Sunday. The callback receives the scheduled time as an RFC 3339 Silk.
+async fn timer_cron(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    if args.len() != 2 {
+        return Err(FlowError::runtime(
+            "timer.cron expects 2 arguments (expression, callback)",
+            0, 0,
+        ));
+    }
+
+    let expression = match &args[0] {
+        Value::String(s) => s.to_string(),
+        _ => return Err(FlowError::type_error(
+            "timer.cron expects a cron expression Silk",
+            0, 0,
+        )),
+    };
+
+    let callback = match &args[1] {
+        callback if callback.type_name() == "Spell" => callback.clone(),
+        _ => return Err(FlowError::type_error(
+            "timer.cron expects a Spell (function) as callback",
+            0, 0,
+        )),
+    };
+
+    let schedule = CronSchedule::parse(&expression)?;
+    if schedule.next_after(Local::now()).is_none() {
+        return Err(FlowError::runtime(
+            &format!("timer.cron expression '{}' never matches a real date", expression),
+            0, 0,
+        ));
+    }
+
+    // Create cancellation channel
+    let (cancel_tx, mut cancel_rx) = oneshot::channel::<()>();
+
+    let handle_id = ctx.runtime.register_handle(HandleType::Cron {
+        expression,
+        cancel_tx: Some(cancel_tx),
+    }).await;
+
+    let callback_tx = ctx.runtime.callback_sender();
+    let runtime = ctx.runtime.clone();
+
+    tokio::spawn(async move {
+        // Search from the last fire time, not "now": the sleep can wake a
+        // little early, which would otherwise fire the same minute twice
+        let mut after = Local::now();
+        while let Some(next) = schedule.next_after(after) {
+            after = next;
+            let delay = (next - Local::now()).to_std().unwrap_or_default();
+
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {
+                    let request = crate::runtime::CallbackRequest {
+                        callback: callback.clone(),
+                        args: vec![Value::String(std::sync::Arc::new(next.to_rfc3339()))],
+                    };
+                    let _ = callback_tx.send(request);
+                }
+                _ = &mut cancel_rx => {
+                    break;
+                }
+            }
+        }
+
+        runtime.unregister_handle(handle_id).await;
+    });
+
+    Ok(Value::Handle(handle_id))
+}
+
+/// Parsed cron expression: one bitmask of allowed values per field
+struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    // When both day fields are restricted, cron matches if either one does
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronSchedule {
+    fn parse(expression: &str) -> Result<Self, FlowError> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(FlowError::runtime(
+                &format!(
+                    "timer.cron expects 5 fields (minute hour day month weekday), got {} in '{}'",
+                    fields.len(), expression
+                ),
+                0, 0,
+            ));
+        }
+
+        let mut days_of_week = parse_cron_field(fields[4], 0, 7, "weekday")?;
+        // 7 is an alias for Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(CronSchedule {
+            minutes: parse_cron_field(fields[0], 0, 59, "minute")?,
+            hours: parse_cron_field(fields[1], 0, 23, "hour")?,
+            days_of_month: parse_cron_field(fields[2], 1, 31, "day")?,
+            months: parse_cron_field(fields[3], 1, 12, "month")?,
+            days_of_week,
+            dom_restricted: fields[2] != "*",
+            dow_restricted: fields[4] != "*",
+        })
+    }
+
+    fn day_matches(&self, time: &NaiveDateTime) -> bool {
+        let dom = self.days_of_month & (1 << time.day()) != 0;
+        let dow = self.days_of_week & (1 << time.weekday().num_days_from_sunday()) != 0;
+        let day = match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            _ => dom && dow,
+        };
+        day && self.months & (1 << time.month()) != 0
+    }
+
+    /// The first matching minute strictly after `after`, searching up to
+    /// five years ahead (enough for Feb 29 schedules). A minute that a DST change
+    /// skips is passed over, and one it repeats fires only the first time.
+    fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        self.next_local(after.naive_local(), |time| {
+            Local.from_local_datetime(&time).earliest().filter(|next| *next > after)
+        })
+    }
+
+    /// Searches wall-clock time from `after`; `resolve` turns a matching minute into
+    /// an instant, or None when that minute doesn't exist there
+    fn next_local<T>(&self, after: NaiveDateTime, resolve: impl Fn(NaiveDateTime) -> Option<T>) -> Option<T> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(366 * 5);
+
+        let mut time = start;
+        while time < limit {
+            if !self.day_matches(&time) {
+                // Skip to the start of the next day
+                time = time.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if self.hours & (1 << time.hour()) == 0 {
+                time = (time + Duration::hours(1)).with_minute(0)?;
+                continue;
+            }
+            if self.minutes & (1 << time.minute()) != 0 {
+                if let Some(next) = resolve(time) {
+                    return Some(next);
+                }
+            }
+            time += Duration::minutes(1);
+        }
+        None
+    }
+}
+
+fn parse_cron_field(field: &str, min: u32, max: u32, name: &str) -> Result<u64, FlowError> {
+    let invalid = || FlowError::runtime(
+        &format!("timer.cron: invalid {} field '{}' (allowed {}-{})", name, field, min, max),
+        0, 0,
+    );
+
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(invalid());
+        }
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (a.parse().map_err(|_| invalid())?, b.parse().map_err(|_| invalid())?)
+        } else {
+            let value: u32 = range.parse().map_err(|_| invalid())?;
+            // `5/15` means "from 5 to the end, every 15"
+            if part.contains('/') { (value, max) } else { (value, value) }
+        };
+
+        if start < min || end > max || start > end {
+            return Err(invalid());
+        }
+
+        let mut value = start;
+        while value <= end {
+            mask |= 1 << value;
+            value += step;
+        }
+    }
+
+    Ok(mask)
+}
+
 /// timer.clear(handle) -> Pulse
-/// Cancels a timer by its handle. Returns true if the timer was found and cancelled.
+/// Cancels a timer by its handle and removes it from the handle registry.
+/// Returns true if the timer was found and cancelled, false if it had already
+/// finished or been cleared.
 async fn timer_clear(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
     if args.len() != 1 {
         return Err(FlowError::runtime(
@@ -182,17 +391,20 @@ async fn timer_clear(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowE
     if let Some(handle) = registry.get_mut(handle_id) {
         // Send cancel signal based on handle type
         match &mut handle.handle_type {
-            HandleType::Interval { cancel_tx, .. } => {
+            HandleType::Interval { cancel_tx, .. }
+            | HandleType::Timeout { cancel_tx, .. }
+            | HandleType::Cron { cancel_tx, .. } => {
                 if let Some(tx) = cancel_tx.take() {
                     let _ = tx.send(());
                 }
             }
-            HandleType::Timeout { cancel_tx, .. } => {
-                if let Some(tx) = cancel_tx.take() {
-                    let _ = tx.send(());
-                }
+            // Removing a server or watcher here would leave it running untracked
+            other => {
+                return Err(FlowError::type_error(
+                    &format!("timer.clear expects a timer Handle, got a {} handle", other.type_name()),
+                    0, 0,
+                ))
             }
-            _ => {}
         }
         
         // Remove the handle from registry
@@ -203,3 +415,43 @@ async fn timer_clear(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowE
         Ok(Value::Boolean(false))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_cron_next_after() {
+        // Monday 2024-01-01 10:07:30
+        let start = Local.with_ymd_and_hms(2024, 1, 1, 10, 7, 30).unwrap();
+
+        let every_15 = CronSchedule::parse("*/15 9-17 * * 1-5").unwrap();
+        assert_eq!(every_15.next_after(start), Some(Local.with_ymd_and_hms(2024, 1, 1, 10, 15, 0).unwrap()));
+
+        // Friday evening rolls over to Monday morning
+        let friday = Local.with_ymd_and_hms(2024, 1, 5, 17, 50, 0).unwrap();
+        assert_eq!(every_15.next_after(friday), Some(Local.with_ymd_and_hms(2024, 1, 8, 9, 0, 0).unwrap()));
+
+        // Sundays only, written as 7
+        let sunday = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert_eq!(sunday.next_after(start), Some(Local.with_ymd_and_hms(2024, 1, 7, 0, 0, 0).unwrap()));
+
+        assert!(CronSchedule::parse("* * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("0 0 31 2 *").unwrap().next_after(start).is_none());
+    }
+
+    #[test]
+    fn test_cron_skips_missing_local_times() {
+        let at = |day, hour, minute| NaiveDate::from_ymd_opt(2024, 3, day).unwrap().and_hms_opt(hour, minute, 0).unwrap();
+        // Clocks jump from 02:00 to 03:00 on March 10th
+        let resolve = |time: NaiveDateTime| (time.date() != at(10, 0, 0).date() || time.hour() != 2).then_some(time);
+
+        let nightly = CronSchedule::parse("30 2 * * *").unwrap();
+        assert_eq!(nightly.next_local(at(9, 3, 0), resolve), Some(at(11, 2, 30)));
+        let hourly = CronSchedule::parse("0 * * * *").unwrap();
+        assert_eq!(hourly.next_local(at(10, 1, 15), resolve), Some(at(10, 3, 0)));
+    }
+}
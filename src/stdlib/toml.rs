@@ -0,0 +1,122 @@
+//! std:toml - TOML parsing and writing
+//!
+//! - `toml.parse(text)` - Parse a TOML document into a Relic
+//! - `toml.stringify(relic)` - Write a Relic as a TOML document
+//!
+//! Dates and times are read as Silks in their RFC 3339 form. TOML has no null,
+//! so Hollow entries are left out when writing.
+
+use crate::error::FlowError;
+use crate::types::{NativeFn, Value, RelicMap};
+use std::sync::Arc;
+
+pub fn load_toml_module() -> Vec<(&'static str, Value)> {
+    vec![
+        ("parse", Value::NativeFunction(NativeFn::new(toml_parse))),
+        ("stringify", Value::NativeFunction(NativeFn::new(toml_stringify))),
+    ]
+}
+
+/// toml.parse(text) -> Relic
+fn toml_parse(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime("toml.parse expects 1 argument (toml string)", 0, 0));
+    }
+
+    let text = match &args[0] {
+        Value::String(s) => s.clone(),
+        _ => return Err(FlowError::type_error("toml.parse expects a Silk (string)", 0, 0)),
+    };
+
+    let table: ::toml::Table = text
+        .parse()
+        .map_err(|e: ::toml::de::Error| FlowError::glitch(&format!("Invalid TOML: {}", e.to_string().trim()), 0, 0))?;
+    Ok(toml_to_value(::toml::Value::Table(table)))
+}
+
+/// toml.stringify(relic) -> Silk
+fn toml_stringify(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime("toml.stringify expects 1 argument (relic)", 0, 0));
+    }
+
+    let table = match value_to_toml(&args[0])? {
+        Some(::toml::Value::Table(table)) => table,
+        _ => {
+            return Err(FlowError::type_error(
+                &format!("toml.stringify expects a Relic at the top level, got {}", args[0].type_name()),
+                0, 0,
+            ))
+        }
+    };
+
+    let text = ::toml::to_string(&table)
+        .map_err(|e| FlowError::glitch(&format!("toml.stringify failed: {}", e), 0, 0))?;
+    Ok(Value::String(Arc::new(text)))
+}
+
+fn toml_to_value(v: ::toml::Value) -> Value {
+    match v {
+        ::toml::Value::String(s) => Value::String(Arc::new(s)),
+        ::toml::Value::Integer(i) => Value::Number(i as f64),
+        ::toml::Value::Float(f) => Value::Number(f),
+        ::toml::Value::Boolean(b) => Value::Boolean(b),
+        ::toml::Value::Datetime(dt) => Value::String(Arc::new(dt.to_string())),
+        ::toml::Value::Array(items) => {
+            Value::Array(Arc::new(items.into_iter().map(toml_to_value).collect()))
+        }
+        ::toml::Value::Table(table) => {
+            let map: RelicMap = table
+                .into_iter()
+                .map(|(k, v)| (k, toml_to_value(v)))
+                .collect();
+            Value::Relic(Arc::new(map))
+        }
+    }
+}
+
+// Returns None for values TOML cannot hold (Hollow and Spells) so callers can skip them
+fn value_to_toml(value: &Value) -> Result<Option<::toml::Value>, FlowError> {
+    Ok(Some(match value {
+        Value::Null
+        | Value::Function { .. }
+        | Value::NativeFunction(_)
+        | Value::AsyncNativeFunction(_)
+        | Value::InterpreterNativeFunction(_) => return Ok(None),
+        Value::Boolean(b) => ::toml::Value::Boolean(*b),
+        Value::Number(n) => {
+            if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+                ::toml::Value::Integer(*n as i64)
+            } else {
+                ::toml::Value::Float(*n)
+            }
+        }
+        Value::String(s) => ::toml::Value::String(s.to_string()),
+        Value::Handle(id) => ::toml::Value::Integer(*id as i64),
+        Value::Covenant(set) => return value_to_toml(&Value::Array(Arc::new(set.values().cloned().collect()))),
+        Value::Array(items) => {
+            let mut out = Vec::with_capacity(items.len());
+            for item in items.iter() {
+                match value_to_toml(item)? {
+                    Some(v) => out.push(v),
+                    None => {
+                        return Err(FlowError::type_error(
+                            &format!("toml.stringify cannot write {} inside a Constellation", item.type_name()),
+                            0, 0,
+                        ))
+                    }
+                }
+            }
+            ::toml::Value::Array(out)
+        }
+        Value::Relic(map) => {
+            let mut table = ::toml::Table::new();
+            for (key, v) in map.iter() {
+                if let Some(v) = value_to_toml(v)? {
+                    table.insert(key.clone(), v);
+                }
+            }
+            ::toml::Value::Table(table)
+        }
+    }))
+}
@@ -3,8 +3,7 @@
 //! Provides URL parsing functionality similar to Node.js URL module.
 
 use crate::error::FlowError;
-use crate::types::{Value, NativeFn};
-use std::collections::HashMap;
+use crate::types::{Value, NativeFn, RelicMap};
 use std::sync::Arc;
 
 /// Load the url module
@@ -28,7 +27,7 @@ fn url_parse(args: Vec<Value>) -> Result<Value, FlowError> {
     let url_str = args[0].to_string();
     
     // Parse the URL
-    let mut result = HashMap::new();
+    let mut result = RelicMap::new();
     
     // Check for protocol
     let (protocol, rest) = if let Some(idx) = url_str.find("://") {
@@ -79,7 +78,7 @@ fn url_parse(args: Vec<Value>) -> Result<Value, FlowError> {
         result.insert("search".to_string(), Value::String(Arc::new(format!("?{}", qs))));
         result.insert("query".to_string(), parse_query_to_relic(qs));
     } else {
-        result.insert("query".to_string(), Value::Relic(Arc::new(HashMap::new())));
+        result.insert("query".to_string(), Value::Relic(Arc::new(RelicMap::new())));
     }
     
     // Full href
@@ -103,8 +102,8 @@ fn url_parse_query(args: Vec<Value>) -> Result<Value, FlowError> {
 }
 
 /// Helper to parse query string into Value::Relic
-fn parse_query_to_relic(query: &str) -> Value {
-    let mut map = HashMap::new();
+pub fn parse_query_to_relic(query: &str) -> Value {
+    let mut map = RelicMap::new();
     
     for pair in query.split('&') {
         if pair.is_empty() {
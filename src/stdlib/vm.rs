@@ -0,0 +1,110 @@
+//! std:vm - run FlowLang source in a sandboxed child interpreter
+//!
+//! `vm.run(source, {timeout, allow})` parses and runs the source on a fresh
+//! Interpreter with empty globals and its own runtime, and returns the Relic of its
+//! `@export`-ed values. See `interpreter::sandbox` for the limits it runs under.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::FlowError;
+use crate::interpreter::sandbox::Sandbox;
+use crate::interpreter::Interpreter;
+use crate::types::{InterpreterFuture, InterpreterNativeFn, RelicMap, Value};
+
+/// Time a sandbox gets when no `timeout` is given
+const DEFAULT_TIMEOUT_MS: f64 = 1000.0;
+
+pub fn get_module() -> RelicMap {
+    let mut module = RelicMap::new();
+    module.insert("run".to_string(), Value::InterpreterNativeFunction(InterpreterNativeFn::new(vm_run)));
+    module
+}
+
+fn vm_run(args: Vec<Value>, interp: &mut Interpreter) -> InterpreterFuture<'_> {
+    Box::pin(async move {
+        let (source, options) = match args.as_slice() {
+            [Value::String(source)] => (source.clone(), None),
+            [Value::String(source), Value::Relic(options)] => (source.clone(), Some(options.clone())),
+            _ => return Err(FlowError::runtime("vm.run expects (source: Silk, options?: Relic)", 0, 0)),
+        };
+        let sandbox = sandbox_options(options.as_deref())?;
+
+        let program = crate::lexer::tokenize(&source).and_then(crate::parser::parse)?;
+        let mut child = interp.sandboxed(sandbox);
+        // Inside another sandbox these are the tighter of the two
+        let limits = child.sandbox().cloned().expect("sandboxed() sets the limits");
+
+        // Statements check the deadline themselves; the timer ends a sandbox that is
+        // waiting (e.g. on `wait`) when the deadline passes
+        let result = match tokio::time::timeout(limits.remaining(), child.execute(program)).await {
+            Ok(result) => result,
+            Err(_) => Err(limits.timed_out()),
+        };
+        // Servers and timers the code started end with it
+        child.runtime().close_all_handles().await;
+        result?;
+        Ok(Value::Relic(Arc::new(child.exports())))
+    })
+}
+
+/// `{timeout: ms, allow: ["math", ...]}`; only the listed std modules can be imported
+fn sandbox_options(options: Option<&RelicMap>) -> Result<Sandbox, FlowError> {
+    let timeout = match options.and_then(|o| o.get("timeout")) {
+        None => DEFAULT_TIMEOUT_MS,
+        Some(Value::Number(ms)) if *ms > 0.0 && ms.is_finite() => *ms,
+        Some(other) => return Err(FlowError::type_error(
+            &format!("vm.run timeout must be a positive Ember of milliseconds, got {}", other),
+            0,
+            0,
+        )),
+    };
+
+    let mut allow = HashSet::new();
+    match options.and_then(|o| o.get("allow")) {
+        None => {}
+        Some(Value::Array(names)) => {
+            for name in names.iter() {
+                let Value::String(name) = name else {
+                    return Err(FlowError::type_error("vm.run allow must list module names as Silk", 0, 0));
+                };
+                let name = name.strip_prefix("std:").unwrap_or(name);
+                if super::load_module(name).is_none() {
+                    return Err(FlowError::runtime(&format!("Unknown standard library module '{}'", name), 0, 0));
+                }
+                allow.insert(name.to_string());
+            }
+        }
+        Some(other) => return Err(FlowError::type_error(
+            &format!("vm.run allow must be a Constellation of module names, got {}", other.type_name()),
+            0,
+            0,
+        )),
+    }
+
+    Ok(Sandbox::new(allow, Duration::from_secs_f64(timeout / 1000.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::output::Stream;
+    use crate::interpreter::Interpreter;
+
+    #[tokio::test]
+    async fn test_run_returns_exports_and_stops_runaway_code() {
+        let source = concat!(
+            "circle vm from \"std:vm\"\n",
+            "let plugin = vm.run(\"circle math from \\\"std:math\\\"\\n@export let answer = math.sqrt(1764)\\nshout(\\\"loaded\\\")\", {\"allow\": [\"math\"]})\n",
+            "shout(plugin.answer)\n",
+            "attempt {\n    vm.run(\"enter Phase forever {\\n}\", {\"timeout\": 20})\n} rescue as e {\n    shout(e)\n}\n",
+        );
+        let program = crate::lexer::tokenize(source).and_then(crate::parser::parse).unwrap();
+        let mut interpreter = Interpreter::new(Default::default());
+        let (result, output) = interpreter.execute_captured(program).await;
+        result.unwrap();
+        assert_eq!(output[0], (Stream::Stdout, "loaded".to_string()));
+        assert_eq!(output[1], (Stream::Stdout, "42".to_string()));
+        assert!(output[2].1.contains("past its 20ms timeout"), "{:?}", output);
+    }
+}
@@ -3,12 +3,13 @@
 //! Provides HTTP server functionality using warp.
 
 use crate::error::FlowError;
-use crate::types::{Value, AsyncNativeFn, AsyncContext, NativeFn};
+use crate::types::{Value, AsyncNativeFn, AsyncContext, NativeFn, RelicMap};
 use crate::runtime::handle::HandleType;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, PoisonError};
 use tokio::sync::oneshot;
-use warp::Reply;
+use warp::{Filter, Reply};
 
 /// Load the web module
 pub fn load_web_module() -> Vec<(&'static str, Value)> {
@@ -16,6 +17,21 @@ pub fn load_web_module() -> Vec<(&'static str, Value)> {
         ("serve", Value::AsyncNativeFunction(AsyncNativeFn(Arc::new(|args, ctx| {
             Box::pin(web_serve(args, ctx))
         })))),
+        ("serveUnix", Value::AsyncNativeFunction(AsyncNativeFn(Arc::new(|args, ctx| {
+            Box::pin(web_serve_unix(args, ctx))
+        })))),
+        ("metrics", Value::AsyncNativeFunction(AsyncNativeFn(Arc::new(|args, ctx| {
+            Box::pin(web_metrics(args, ctx))
+        })))),
+        ("metricsText", Value::AsyncNativeFunction(AsyncNativeFn(Arc::new(|args, ctx| {
+            Box::pin(web_metrics_text(args, ctx))
+        })))),
+        ("queue", Value::AsyncNativeFunction(AsyncNativeFn(Arc::new(|args, ctx| {
+            Box::pin(web_queue(args, ctx))
+        })))),
+        ("graphql", Value::NativeFunction(NativeFn(Arc::new(web_graphql)))),
+        ("cors", Value::NativeFunction(NativeFn(Arc::new(web_cors)))),
+        ("securityHeaders", Value::NativeFunction(NativeFn(Arc::new(web_security_headers)))),
         // Response helpers
         ("json", Value::NativeFunction(NativeFn(Arc::new(res_json)))),
         ("html", Value::NativeFunction(NativeFn(Arc::new(res_html)))),
@@ -25,20 +41,420 @@ pub fn load_web_module() -> Vec<(&'static str, Value)> {
         ("notFound", Value::NativeFunction(NativeFn(Arc::new(res_not_found)))),
         ("badRequest", Value::NativeFunction(NativeFn(Arc::new(res_bad_request)))),
         ("serverError", Value::NativeFunction(NativeFn(Arc::new(res_server_error)))),
+        ("stream", Value::NativeFunction(NativeFn(Arc::new(res_stream)))),
+        ("sse", Value::NativeFunction(NativeFn(Arc::new(res_sse)))),
     ]
 }
 
-/// web.serve(port, handler) -> Handle
+/// web.metrics(port) -> Handle
+/// Serves Prometheus metrics for this process on GET /metrics.
+async fn web_metrics(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    let port = match args.first() {
+        Some(Value::Number(n)) if args.len() == 1 => *n as u16,
+        _ => return Err(FlowError::type_error(
+            "web.metrics expects a Ember for port",
+            0, 0,
+        )),
+    };
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let runtime = ctx.runtime.clone();
+
+    let scrape_runtime = runtime.clone();
+    let routes = warp::get()
+        .and(warp::path("metrics"))
+        .and(warp::path::end())
+        .then(move || {
+            let runtime = scrape_runtime.clone();
+            async move {
+                warp::reply::with_header(
+                    runtime.render_metrics().await,
+                    "Content-Type",
+                    "text/plain; version=0.0.4",
+                )
+            }
+        });
+
+    let (addr, server) = warp::serve(routes)
+        .try_bind_with_graceful_shutdown((ALL_INTERFACES, port), async {
+            let _ = shutdown_rx.await;
+        })
+        .map_err(|e| bind_error("web.metrics", (ALL_INTERFACES, port).into(), &e))?;
+    let handle_id = runtime.register_handle(HandleType::HttpServer {
+        port: addr.port(),
+        shutdown_tx: Some(shutdown_tx),
+    }).await;
+
+    tokio::spawn(async move {
+        server.await;
+        runtime.unregister_handle(handle_id).await;
+    });
+
+    Ok(Value::Handle(handle_id))
+}
+
+/// web.metricsText() -> Silk
+/// The same metrics as web.metrics, for serving /metrics from your own handler.
+async fn web_metrics_text(_args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    Ok(Value::String(Arc::new(ctx.runtime.render_metrics().await)))
+}
+
+/// web.queue() -> Relic {depth, capacity, policy, rejected, dropped, blocked}
+/// State of the queue of requests waiting for a handler.
+async fn web_queue(_args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    let queue = ctx.runtime.web_callback_sender();
+    let stats = queue.stats();
+    let mut map = RelicMap::new();
+    map.insert("depth".to_string(), Value::Number(stats.depth as f64));
+    map.insert("capacity".to_string(), Value::Number(stats.capacity as f64));
+    map.insert("policy".to_string(), Value::String(Arc::new(queue.policy().name().to_string())));
+    map.insert("rejected".to_string(), Value::Number(stats.rejected as f64));
+    map.insert("dropped".to_string(), Value::Number(stats.dropped as f64));
+    map.insert("blocked".to_string(), Value::Number(stats.blocked as f64));
+    Ok(Value::Relic(Arc::new(map)))
+}
+
+const ALL_INTERFACES: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+
+/// The `host` option of web.serve: the interface address to listen on, all of them by default
+fn host_option(value: Option<&Value>) -> Result<IpAddr, FlowError> {
+    match value {
+        None => Ok(ALL_INTERFACES),
+        Some(Value::String(host)) if host.as_str() == "localhost" => Ok(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        Some(Value::String(host)) => host.parse().map_err(|_| FlowError::runtime(
+            &format!("web.serve host must be an IP address such as \"127.0.0.1\" or \"::\", got \"{}\"", host),
+            0, 0,
+        )),
+        Some(other) => Err(FlowError::type_error(
+            &format!("web.serve host must be a Silk, got {}", other.type_name()),
+            0, 0,
+        )),
+    }
+}
+
+/// A catchable Rift for a server that could not listen, naming the usual causes
+fn bind_error(func: &str, addr: SocketAddr, error: &warp::Error) -> FlowError {
+    let mut source: Option<&dyn std::error::Error> = Some(error);
+    let mut io_kind = None;
+    while let Some(e) = source {
+        if let Some(io) = e.downcast_ref::<std::io::Error>() {
+            io_kind = Some(io.kind());
+        }
+        source = e.source();
+    }
+    let reason = match io_kind {
+        Some(std::io::ErrorKind::AddrInUse) => format!("port {} is already in use", addr.port()),
+        Some(std::io::ErrorKind::PermissionDenied) => "permission denied (ports below 1024 need privileges)".to_string(),
+        Some(std::io::ErrorKind::AddrNotAvailable) => format!("no interface has the address {}", addr.ip()),
+        _ => error.to_string(),
+    };
+    FlowError::rift(&format!("{} could not listen on {}: {}", func, addr, reason), 0, 0)
+}
+
+/// Access log line formats for the `accessLog` option of web.serve
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AccessLogFormat {
+    /// `GET /path 200 1.42ms 127.0.0.1`
+    Short,
+    /// NCSA Common Log Format
+    Common,
+    /// Common plus referer and user agent, as written by Apache and nginx
+    Combined,
+    /// One JSON object per line
+    Json,
+}
+
+impl AccessLogFormat {
+    fn from_option(value: &Value) -> Result<Option<Self>, FlowError> {
+        match value {
+            Value::Boolean(false) | Value::Null => Ok(None),
+            Value::Boolean(true) => Ok(Some(AccessLogFormat::Short)),
+            Value::String(s) => match s.as_str() {
+                "short" => Ok(Some(AccessLogFormat::Short)),
+                "common" => Ok(Some(AccessLogFormat::Common)),
+                "combined" => Ok(Some(AccessLogFormat::Combined)),
+                "json" => Ok(Some(AccessLogFormat::Json)),
+                other => Err(FlowError::runtime(
+                    &format!("Unknown accessLog format '{}' (expected short, common, combined or json)", other),
+                    0, 0,
+                )),
+            },
+            _ => Err(FlowError::type_error(
+                "web.serve accessLog expects a format name or a Pulse",
+                0, 0,
+            )),
+        }
+    }
+}
+
+/// Response rules from the `middleware` option of web.serve, built from the
+/// Relics returned by web.cors and web.securityHeaders
+#[derive(Default)]
+struct Middleware {
+    cors: Option<CorsPolicy>,
+    /// Added to every response unless the handler set the same header
+    headers: Vec<(String, String)>,
+}
+
+struct CorsPolicy {
+    /// None allows any origin
+    origins: Option<Vec<String>>,
+    methods: String,
+    headers: String,
+    credentials: bool,
+    max_age: u64,
+}
+
+impl Middleware {
+    fn from_option(value: &Value) -> Result<Self, FlowError> {
+        let items = match value {
+            Value::Array(items) => items.iter().collect::<Vec<_>>(),
+            single @ Value::Relic(_) => vec![single],
+            _ => return Err(FlowError::type_error(
+                "web.serve middleware expects a list from web.cors / web.securityHeaders",
+                0, 0,
+            )),
+        };
+
+        let mut middleware = Middleware::default();
+        for item in items {
+            let map = match item {
+                Value::Relic(map) => map,
+                _ => return Err(FlowError::type_error("web.serve middleware entries must be Relics", 0, 0)),
+            };
+            match map.get("middleware").map(|v| v.to_string()).as_deref() {
+                Some("cors") => {
+                    let origins = match map.get("origin") {
+                        Some(Value::Array(list)) => Some(list.iter().map(|v| v.to_string()).collect()),
+                        Some(Value::String(s)) if s.as_str() != "*" => Some(vec![s.to_string()]),
+                        _ => None,
+                    };
+                    middleware.cors = Some(CorsPolicy {
+                        origins,
+                        methods: string_option(map, "methods", CORS_METHODS),
+                        headers: string_option(map, "headers", CORS_HEADERS),
+                        credentials: matches!(map.get("credentials"), Some(Value::Boolean(true))),
+                        max_age: match map.get("maxAge") {
+                            Some(Value::Number(n)) => *n as u64,
+                            _ => 86400,
+                        },
+                    });
+                }
+                Some("securityHeaders") => {
+                    if let Some(Value::Relic(headers)) = map.get("headers") {
+                        let mut headers: Vec<(String, String)> = headers.iter().map(|(k, v)| (k.clone(), v.to_string())).collect();
+                        headers.sort();
+                        middleware.headers.extend(headers);
+                    }
+                }
+                _ => return Err(FlowError::runtime(
+                    "Unknown middleware; use web.cors(...) or web.securityHeaders(...)",
+                    0, 0,
+                )),
+            }
+        }
+        Ok(middleware)
+    }
+
+    /// Origin to echo back, if the request's origin is allowed
+    fn allowed_origin(cors: &CorsPolicy, origin: Option<&str>) -> Option<String> {
+        match (&cors.origins, origin) {
+            // Credentials can't be combined with a wildcard, so echo the caller instead
+            (None, Some(origin)) if cors.credentials => Some(origin.to_string()),
+            (None, _) => Some("*".to_string()),
+            (Some(allowed), Some(origin)) if allowed.iter().any(|o| o == origin) => Some(origin.to_string()),
+            _ => None,
+        }
+    }
+
+    fn preflight(&self, method: &warp::http::Method, headers: &warp::http::HeaderMap, origin: Option<&str>) -> Option<warp::reply::Response> {
+        let cors = self.cors.as_ref()?;
+        if method != warp::http::Method::OPTIONS || !headers.contains_key("access-control-request-method") {
+            return None;
+        }
+
+        let mut reply = warp::reply::with_status("", warp::http::StatusCode::NO_CONTENT).into_response();
+        if Self::allowed_origin(cors, origin).is_some() {
+            let out = reply.headers_mut();
+            insert_header(out, "Access-Control-Allow-Methods", &cors.methods);
+            insert_header(out, "Access-Control-Allow-Headers", &cors.headers);
+            insert_header(out, "Access-Control-Max-Age", &cors.max_age.to_string());
+        }
+        Some(reply)
+    }
+
+    fn apply(&self, origin: Option<&str>, reply: &mut warp::reply::Response) {
+        let out = reply.headers_mut();
+        if let Some(cors) = &self.cors {
+            if let Some(allowed) = Self::allowed_origin(cors, origin) {
+                if allowed != "*" {
+                    insert_header(out, "Vary", "Origin");
+                }
+                insert_header(out, "Access-Control-Allow-Origin", &allowed);
+                if cors.credentials {
+                    insert_header(out, "Access-Control-Allow-Credentials", "true");
+                }
+            }
+        }
+        for (name, value) in &self.headers {
+            if !out.contains_key(name.as_str()) {
+                insert_header(out, name, value);
+            }
+        }
+    }
+}
+
+const CORS_METHODS: &str = "GET, POST, PUT, PATCH, DELETE, OPTIONS";
+const CORS_HEADERS: &str = "Content-Type, Authorization";
+
+fn string_option(map: &RelicMap, key: &str, default: &str) -> String {
+    match map.get(key) {
+        Some(Value::Array(list)) => list.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "),
+        Some(Value::String(s)) => s.to_string(),
+        _ => default.to_string(),
+    }
+}
+
+fn insert_header(headers: &mut warp::http::HeaderMap, name: &str, value: &str) {
+    if let (Ok(n), Ok(v)) = (
+        warp::http::header::HeaderName::try_from(name),
+        warp::http::header::HeaderValue::try_from(value),
+    ) {
+        headers.insert(n, v);
+    }
+}
+
+/// web.graphql(schema, resolvers) -> Spell
+/// A `(req, res)` handler answering GraphQL requests; serve it directly or call
+/// it from your own handler for the /graphql path.
+fn web_graphql(args: Vec<Value>) -> Result<Value, FlowError> {
+    let schema = match args.first() {
+        Some(Value::String(s)) => s.clone(),
+        _ => return Err(FlowError::type_error("web.graphql expects a schema Silk", 0, 0)),
+    };
+    let resolvers = args.get(1).cloned().unwrap_or(Value::Null);
+    let endpoint = crate::stdlib::graphql::Endpoint::new(&schema, &resolvers)?;
+    Ok(crate::stdlib::graphql::handler(Arc::new(endpoint)))
+}
+
+/// web.cors(options?) -> middleware Relic for web.serve
+/// Options: origin ("*", a Silk or a list), methods, headers, credentials, maxAge.
+fn web_cors(args: Vec<Value>) -> Result<Value, FlowError> {
+    let mut map = match args.first() {
+        Some(Value::Relic(options)) => (**options).clone(),
+        Some(_) => return Err(FlowError::type_error("web.cors expects a Relic of options", 0, 0)),
+        None => RelicMap::new(),
+    };
+    map.insert("middleware".to_string(), Value::String(Arc::new("cors".to_string())));
+    Ok(Value::Relic(Arc::new(map)))
+}
+
+/// web.securityHeaders(options?) -> middleware Relic for web.serve
+/// Options override a preset: hsts, frameOptions, contentTypeOptions, referrerPolicy
+/// and csp take a Silk, or false to leave the header out.
+fn web_security_headers(args: Vec<Value>) -> Result<Value, FlowError> {
+    let options = match args.first() {
+        Some(Value::Relic(options)) => (**options).clone(),
+        Some(_) => return Err(FlowError::type_error("web.securityHeaders expects a Relic of options", 0, 0)),
+        None => RelicMap::new(),
+    };
+
+    let preset = [
+        ("hsts", "Strict-Transport-Security", "max-age=31536000; includeSubDomains"),
+        ("frameOptions", "X-Frame-Options", "DENY"),
+        ("contentTypeOptions", "X-Content-Type-Options", "nosniff"),
+        ("referrerPolicy", "Referrer-Policy", "strict-origin-when-cross-origin"),
+        ("csp", "Content-Security-Policy", "default-src 'self'"),
+    ];
+
+    let mut headers = RelicMap::new();
+    for (option, header, default) in preset {
+        let value = match options.get(option) {
+            Some(Value::Boolean(false)) => continue,
+            Some(Value::String(s)) => s.to_string(),
+            Some(Value::Boolean(true)) | None => default.to_string(),
+            Some(_) => return Err(FlowError::type_error(
+                &format!("web.securityHeaders option '{}' expects a Silk or false", option),
+                0, 0,
+            )),
+        };
+        headers.insert(header.to_string(), Value::String(Arc::new(value)));
+    }
+
+    let mut map = RelicMap::new();
+    map.insert("middleware".to_string(), Value::String(Arc::new("securityHeaders".to_string())));
+    map.insert("headers".to_string(), Value::Relic(Arc::new(headers)));
+    Ok(Value::Relic(Arc::new(map)))
+}
+
+/// What the access log records about one request
+struct AccessEntry {
+    time: chrono::DateTime<chrono::Local>,
+    ip: String,
+    method: String,
+    path: String,
+    status: u16,
+    bytes: Option<u64>,
+    duration: std::time::Duration,
+    referer: String,
+    user_agent: String,
+}
+
+impl AccessEntry {
+    fn format(&self, format: AccessLogFormat) -> String {
+        let bytes = self.bytes.map(|b| b.to_string()).unwrap_or_else(|| "-".to_string());
+        let common = || format!(
+            "{} - - [{}] \"{} {} HTTP/1.1\" {} {}",
+            self.ip, self.time.format("%d/%b/%Y:%H:%M:%S %z"), self.method, self.path, self.status, bytes
+        );
+        match format {
+            AccessLogFormat::Short => format!(
+                "{} {} {} {:.2}ms {}",
+                self.method, self.path, self.status, self.duration.as_secs_f64() * 1000.0, self.ip
+            ),
+            AccessLogFormat::Common => common(),
+            AccessLogFormat::Combined => format!(
+                "{} \"{}\" \"{}\"",
+                common(),
+                if self.referer.is_empty() { "-" } else { &self.referer },
+                if self.user_agent.is_empty() { "-" } else { &self.user_agent }
+            ),
+            AccessLogFormat::Json => serde_json::json!({
+                "time": self.time.to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+                "method": self.method,
+                "path": self.path,
+                "status": self.status,
+                "duration_ms": (self.duration.as_secs_f64() * 1000.0 * 1000.0).round() / 1000.0,
+                "ip": self.ip,
+                "bytes": self.bytes,
+                "referer": self.referer,
+                "user_agent": self.user_agent,
+            }).to_string(),
+        }
+    }
+}
+
+/// web.serve(port, handler, options?) -> Handle
 /// Creates an HTTP server on the specified port.
 /// The handler is called for each request and should return a response object.
+/// Options: `accessLog` ("short", "common", "combined", "json" or true) writes one
+/// line per request through std:log; `middleware` takes a list of web.cors and
+/// web.securityHeaders Relics; `host` is the interface address to listen on.
+/// A port that can't be bound raises a Rift instead of failing in the background.
 async fn web_serve(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
-    if args.len() != 2 {
+    if args.len() != 2 && args.len() != 3 {
         return Err(FlowError::runtime(
-            "web.serve expects 2 arguments (port, handler)",
+            "web.serve expects 2 or 3 arguments (port, handler, options?)",
             0, 0,
         ));
     }
 
+    let (access_log, middleware) = serve_options("web.serve", args.get(2))?;
+    let host = match args.get(2) {
+        Some(Value::Relic(options)) => host_option(options.get("host"))?,
+        _ => ALL_INTERFACES,
+    };
+
     let port = match &args[0] {
         Value::Number(n) => *n as u16,
         _ => return Err(FlowError::type_error(
@@ -48,216 +464,559 @@ async fn web_serve(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowErr
     };
 
     let handler = match &args[1] {
-        Value::Function { .. } => args[1].clone(),
+        handler if handler.type_name() == "Spell" => handler.clone(),
         _ => return Err(FlowError::type_error(
             "web.serve expects a Spell (function) as handler",
             0, 0,
         )),
     };
 
+    // A socket systemd opened for this port is used instead of binding a new one
+    if let Some(listener) = crate::stdlib::socket_activation::take_tcp(port) {
+        let routes = request_routes(port, handler, &ctx.runtime, middleware, access_log);
+        return serve_inherited_tcp(listener, routes, &ctx.runtime).await;
+    }
+
     // Create shutdown channel
     let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
 
-    // Register the handle
-    let handle_id = ctx.runtime.register_handle(HandleType::HttpServer {
-        port,
+    let runtime = ctx.runtime.clone();
+
+    // `flowlang replay` feeds recorded requests through the handler instead of binding the port
+    if let Some(requests) = crate::stdlib::flowrec::take_replay(port) {
+        let handle_id = ctx.runtime.register_handle(HandleType::HttpServer {
+            port,
+            shutdown_tx: Some(shutdown_tx),
+        }).await;
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = replay_requests(port, requests, handler, runtime.web_callback_sender(), response_prototype()) => {}
+                _ = shutdown_rx => {}
+            }
+            runtime.unregister_handle(handle_id).await;
+        });
+        return Ok(Value::Handle(handle_id));
+    }
+
+    let routes = request_routes(port, handler, &runtime, middleware, access_log);
+
+    // Bind before handing out the Handle, so a taken port fails the call itself
+    let (addr, server) = warp::serve(routes)
+        .try_bind_with_graceful_shutdown((host, port), async {
+            let _ = shutdown_rx.await;
+        })
+        .map_err(|e| bind_error("web.serve", (host, port).into(), &e))?;
+    let handle_id = runtime.register_handle(HandleType::HttpServer {
+        port: addr.port(),
         shutdown_tx: Some(shutdown_tx),
     }).await;
 
-    // Get web callback sender for request handling (with response support)
-    let callback_tx = ctx.runtime.web_callback_sender();
-    let runtime = ctx.runtime.clone();
+    tokio::spawn(async move {
+        server.await;
+        
+        // Unregister handle when server stops
+        runtime.unregister_handle(handle_id).await;
+    });
+
+    Ok(Value::Handle(handle_id))
+}
+
+/// The `accessLog` and `middleware` options of web.serve and web.serveUnix
+fn serve_options(func: &str, options: Option<&Value>) -> Result<(Option<AccessLogFormat>, Arc<Middleware>), FlowError> {
+    match options {
+        Some(Value::Relic(options)) => {
+            let access_log = match options.get("accessLog") {
+                Some(value) => AccessLogFormat::from_option(value)?,
+                None => None,
+            };
+            let middleware = match options.get("middleware") {
+                Some(value) => Middleware::from_option(value)?,
+                None => Middleware::default(),
+            };
+            Ok((access_log, Arc::new(middleware)))
+        }
+        Some(_) => Err(FlowError::type_error(
+            &format!("{} expects a Relic of options", func),
+            0, 0,
+        )),
+        None => Ok((None, Arc::new(Middleware::default()))),
+    }
+}
+
+/// web.serveUnix(path, handler, options?) -> Handle
+/// Serves HTTP on a Unix domain socket, e.g. behind nginx with
+/// `proxy_pass http://unix:/run/app.sock`. Takes the options of web.serve except
+/// `host`. A socket file left behind by a server that is no longer running is
+/// replaced, and the file is removed again when the server stops.
+#[cfg(unix)]
+async fn web_serve_unix(args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(FlowError::runtime(
+            "web.serveUnix expects 2 or 3 arguments (path, handler, options?)",
+            0, 0,
+        ));
+    }
+    let path = match &args[0] {
+        Value::String(path) => std::path::PathBuf::from(path.as_str()),
+        other => return Err(FlowError::type_error(
+            &format!("web.serveUnix expects a Silk for the socket path, got {}", other.type_name()),
+            0, 0,
+        )),
+    };
+    let handler = match &args[1] {
+        handler if handler.type_name() == "Spell" => handler.clone(),
+        _ => return Err(FlowError::type_error(
+            "web.serveUnix expects a Spell (function) as handler",
+            0, 0,
+        )),
+    };
+    let (access_log, middleware) = serve_options("web.serveUnix", args.get(2))?;
 
-    // Create Response Prototype (Singleton)
-    // Contains efficient static references to helper functions to avoid
-    // rebuilding this HashMap for every single request (allocating ~16 strings/Arcs per req).
-    let response_prototype = {
-        let mut map = HashMap::new();
-        map.insert("json".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_json))));
-        map.insert("html".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_html))));
-        map.insert("text".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_text))));
-        map.insert("status".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_status))));
-        map.insert("redirect".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_redirect))));
-        map.insert("notFound".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_not_found))));
-        map.insert("badRequest".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_bad_request))));
-        map.insert("serverError".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_server_error))));
-        map.insert("ok".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_ok))));
-        map.insert("created".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_created))));
-        map.insert("noContent".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_no_content))));
-        map.insert("unauthorized".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_unauthorized))));
-        map.insert("forbidden".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_forbidden))));
-        map.insert("send".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_send))));
-        map.insert("file".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_file))));
-        map.insert("header".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_header))));
-        Value::Relic(Arc::new(map))
+    // A socket systemd opened is its to clean up; one we bind is ours
+    let (listener, owned) = match crate::stdlib::socket_activation::take_unix(&path) {
+        Some(listener) => (listener, false),
+        None => (bind_unix("web.serveUnix", &path)?, true),
     };
+    let listener = listener.set_nonblocking(true)
+        .and_then(|_| tokio::net::UnixListener::from_std(listener))
+        .map_err(|e| FlowError::rift(&format!("web.serveUnix could not listen on {}: {}", path.display(), e), 0, 0))?;
+
+    let routes = request_routes(0, handler, &ctx.runtime, middleware, access_log);
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let handle_id = ctx.runtime.register_handle(HandleType::UnixServer {
+        path: path.display().to_string(),
+        shutdown_tx: Some(shutdown_tx),
+    }).await;
+    let runtime = ctx.runtime.clone();
 
-    // Spawn the server task
     tokio::spawn(async move {
-        use warp::Filter;
-        
-        let handler_clone = handler.clone();
-        let callback_tx_clone = callback_tx.clone();
-        let response_prototype = response_prototype.clone(); // Clone the prototype Value (cheap Arc clone)
-
-        // Warp route that handles all requests
-        // Note: Logic moved INSIDE the filter to run concurrently on Tokio thread pool
-        let routes = warp::any()
-            .and(warp::method())
-            .and(warp::path::full())
-            .and(warp::query::raw().or_else(|_| async { Ok::<_, warp::Rejection>((String::new(),)) }))
-            .and(warp::header::headers_cloned())
-            .and(warp::addr::remote())
-            .and(warp::body::bytes())
-            .and_then(move |method: warp::http::Method, 
-                           path: warp::path::FullPath, 
-                           query: String,
-                           headers: warp::http::HeaderMap,
-                           addr: Option<std::net::SocketAddr>,
-                           body: bytes::Bytes| {
+        let accept = hyper::server::accept::poll_fn(move |cx| {
+            listener.poll_accept(cx).map(|result| Some(result.map(|(stream, _)| stream)))
+        });
+        serve_accepted(accept, |_| None, routes.boxed(), shutdown_rx).await;
+        if owned {
+            let _ = std::fs::remove_file(&path);
+        }
+        runtime.unregister_handle(handle_id).await;
+    });
+
+    Ok(Value::Handle(handle_id))
+}
+
+#[cfg(not(unix))]
+async fn web_serve_unix(_args: Vec<Value>, _ctx: AsyncContext) -> Result<Value, FlowError> {
+    Err(FlowError::rift("web.serveUnix needs Unix domain sockets, which this platform doesn't have", 0, 0))
+}
+
+/// Bind a Unix socket, replacing a stale socket file but never a live server or another file
+#[cfg(unix)]
+pub(crate) fn bind_unix(func: &str, path: &std::path::Path) -> Result<std::os::unix::net::UnixListener, FlowError> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    let failed = |reason: String| FlowError::rift(&format!("{} could not listen on {}: {}", func, path.display(), reason), 0, 0);
+    match UnixListener::bind(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+            if UnixStream::connect(path).is_ok() {
+                return Err(failed("another server is listening on it".to_string()));
+            }
+            let is_socket = std::fs::symlink_metadata(path).map(|m| m.file_type().is_socket()).unwrap_or(false);
+            if !is_socket {
+                return Err(failed("a file that is not a socket is in the way".to_string()));
+            }
+            std::fs::remove_file(path).map_err(|e| failed(e.to_string()))?;
+            UnixListener::bind(path).map_err(|e| failed(e.to_string()))
+        }
+        result => result.map_err(|e| failed(e.to_string())),
+    }
+}
+
+/// Serve a socket systemd passed in for web.serve's port
+async fn serve_inherited_tcp<F>(listener: std::net::TcpListener, routes: F, runtime: &Arc<crate::runtime::Runtime>) -> Result<Value, FlowError>
+where
+    F: Filter<Extract = (warp::reply::Response,), Error = warp::Rejection> + Clone + Send + Sync + 'static,
+{
+    let port = listener.local_addr().map(|addr| addr.port()).unwrap_or(0);
+    let listener = listener.set_nonblocking(true)
+        .and_then(|_| tokio::net::TcpListener::from_std(listener))
+        .map_err(|e| FlowError::rift(&format!("web.serve could not use the socket for port {}: {}", port, e), 0, 0))?;
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let handle_id = runtime.register_handle(HandleType::HttpServer {
+        port,
+        shutdown_tx: Some(shutdown_tx),
+    }).await;
+    let runtime = runtime.clone();
+
+    tokio::spawn(async move {
+        let accept = hyper::server::accept::poll_fn(move |cx| {
+            listener.poll_accept(cx).map(|result| Some(result.map(|(stream, _)| stream)))
+        });
+        serve_accepted(accept, |stream: &tokio::net::TcpStream| stream.peer_addr().ok(), routes.boxed(), shutdown_rx).await;
+        runtime.unregister_handle(handle_id).await;
+    });
+
+    Ok(Value::Handle(handle_id))
+}
+
+/// The client address of a connection warp didn't accept itself
+#[derive(Clone, Copy)]
+struct PeerAddr(SocketAddr);
+
+/// Serve `routes` on connections from a listener warp can't bind (inherited and Unix
+/// sockets) until `shutdown_rx` fires; `peer` gives the client address of a connection
+async fn serve_accepted<A>(
+    accept: A,
+    peer: fn(&A::Conn) -> Option<SocketAddr>,
+    routes: warp::filters::BoxedFilter<(warp::reply::Response,)>,
+    shutdown_rx: oneshot::Receiver<()>,
+) where
+    A: hyper::server::accept::Accept<Error = std::io::Error>,
+    A::Conn: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
+    use hyper::service::{make_service_fn, service_fn, Service};
+
+    let make_service = make_service_fn(move |conn: &A::Conn| {
+        let peer = peer(conn);
+        let service = warp::service(routes.clone());
+        async move {
+            Ok::<_, std::convert::Infallible>(service_fn(move |mut request: hyper::Request<hyper::Body>| {
+                if let Some(addr) = peer {
+                    request.extensions_mut().insert(PeerAddr(addr));
+                }
+                service.clone().call(request)
+            }))
+        }
+    });
+    let server = hyper::Server::builder(accept)
+        .serve(make_service)
+        .with_graceful_shutdown(async {
+            let _ = shutdown_rx.await;
+        });
+    let _ = server.await;
+}
+
+/// `Some(client address)` for requests on any kind of server
+fn remote_addr() -> impl Filter<Extract = (Option<SocketAddr>,), Error = std::convert::Infallible> + Clone {
+    warp::addr::remote()
+        .and(warp::ext::optional::<PeerAddr>())
+        .map(|addr: Option<SocketAddr>, peer: Option<PeerAddr>| addr.or(peer.map(|p| p.0)))
+}
+
+/// The filter answering every request of a web.serve / web.serveUnix server
+fn request_routes(
+    port: u16,
+    handler: Value,
+    runtime: &Arc<crate::runtime::Runtime>,
+    middleware: Arc<Middleware>,
+    access_log: Option<AccessLogFormat>,
+) -> impl Filter<Extract = (warp::reply::Response,), Error = warp::Rejection> + Clone + Send + Sync + 'static {
+    // Get web callback sender for request handling (with response support)
+    let callback_tx = runtime.web_callback_sender();
+    let metrics = runtime.metrics();
+    let response_prototype = response_prototype();
+
+    // Warp route that handles all requests
+    // Note: Logic moved INSIDE the filter to run concurrently on Tokio thread pool
+    warp::any()
+        .and(warp::method())
+        .and(warp::path::full())
+        .and(warp::query::raw().or_else(|_| async { Ok::<_, warp::Rejection>((String::new(),)) }))
+        .and(warp::header::headers_cloned())
+        .and(remote_addr())
+        .and(warp::body::bytes())
+        .and_then(move |method: warp::http::Method, 
+                       path: warp::path::FullPath, 
+                       query: String,
+                       headers: warp::http::HeaderMap,
+                       addr: Option<std::net::SocketAddr>,
+                       body: bytes::Bytes| {
+            
+            // Clone shared resources for this specific request task
+            let handler = handler.clone();
+            let callback_tx = callback_tx.clone();
+            let response_proto = response_prototype.clone();
+            let metrics = metrics.clone();
+            let middleware = middleware.clone();
+            let origin = headers.get("origin").and_then(|v| v.to_str().ok()).map(str::to_string);
+            let preflight = middleware.preflight(&method, &headers, origin.as_deref());
+            let method_name = method.to_string();
+            metrics.request_started();
+            let started = std::time::Instant::now();
+            
+            // Captured before the request moves into the handler
+            let access_entry = access_log.map(|_| {
+                let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or("").to_string();
+                AccessEntry {
+                    time: chrono::Local::now(),
+                    ip: addr.map(|a| a.ip().to_string()).unwrap_or_else(|| "-".to_string()),
+                    method: method_name.clone(),
+                    path: if query.is_empty() { path.as_str().to_string() } else { format!("{}?{}", path.as_str(), query) },
+                    status: 0,
+                    bytes: None,
+                    duration: std::time::Duration::ZERO,
+                    referer: header("referer"),
+                    user_agent: header("user-agent"),
+                }
+            });
+            
+            let respond = async move {
+                // CORS preflights are answered without running the handler
+                if let Some(reply) = preflight {
+                    return Ok(reply);
+                }
+
+                // --- PRE-PROCESSING (Concurrent) ---
+                // This runs on a worker thread, unrelated to the interpreter lock
                 
-                // Clone shared resources for this specific request task
-                let handler = handler_clone.clone();
-                let callback_tx = callback_tx_clone.clone();
-                let response_proto = response_prototype.clone();
+                let path_str = path.as_str();
+                let ip = addr.map(|a| a.ip().to_string()).unwrap_or_else(|| "unknown".to_string());
                 
-                async move {
-                    // --- PRE-PROCESSING (Concurrent) ---
-                    // This runs on a worker thread, unrelated to the interpreter lock
-                    
-                    let path_str = path.as_str().to_string();
-                    let body_str = String::from_utf8_lossy(&body).to_string();
-                    
-                    // Combine path with query string for 'url' field
-                    let full_path = if query.is_empty() {
-                        path_str.clone()
-                    } else {
-                        format!("{}?{}", path_str, query)
-                    };
-                    
-                    // Single-Pass Header Processing
-                    // Extracts 'host' and builds the Relic map in one go
-                    let mut headers_relic = HashMap::new();
-                    let mut host = "localhost".to_string();
-                    
-                    for (k, v) in headers.iter() {
-                        let k_str = k.as_str();
-                        let v_str = v.to_str().unwrap_or("");
-                        
-                        if k_str == "host" {
-                            host = v_str.to_string();
-                        }
-                        
-                        headers_relic.insert(
-                            k_str.to_string(), 
-                            Value::String(Arc::new(v_str.to_string()))
-                        );
-                    }
+                if crate::stdlib::flowrec::is_recording() {
+                    let full_path = if query.is_empty() { path_str.to_string() } else { format!("{}?{}", path_str, query) };
+                    let recorded_headers = headers.iter()
+                        .map(|(k, v)| (k.as_str().to_string(), v.to_str().unwrap_or("").to_string()))
+                        .collect();
+                    crate::stdlib::flowrec::record(&crate::stdlib::flowrec::RecordedRequest::new(
+                        port, method.as_str(), &full_path, recorded_headers, &body, &ip,
+                    ));
+                }
+                
+                let request_value = request_value(
+                    method.as_str(),
+                    path_str,
+                    &query,
+                    headers.iter().map(|(k, v)| (k.as_str(), v.to_str().unwrap_or(""))),
+                    &body,
+                    ip,
+                );
+                
+                // Use cached Response Prototype (Ref count bump only, no allocation)
+                let response_value = response_proto;
 
-                    // Parse path and query (cheap string operations)
-                    let pathname = path_str.clone();
-                    
-                    // REMOVED: Eager Cookie Parsing (Expensive & often unused)
-                    // Users can parse req.headers["cookie"] if needed
-                    let cookies_map = Value::Relic(Arc::new(HashMap::new()));
-                    
-                    // REMOVED: Eager Query Parsing (Expensive & often unused)
-                    // Users can parse req.url or req.query_string if needed
-                    let query_map = Value::Relic(Arc::new(HashMap::new()));
-                    
-                    // Build URL
-                    let protocol = "http"; 
-                    let url = format!("{}://{}{}", protocol, host, full_path);
-                    let ip = addr.map(|a| a.ip().to_string()).unwrap_or_else(|| "unknown".to_string());
-                    
-                    // Create Request Object
-                    // Minimized allocations where possible
-                    let mut req_map = HashMap::new();
-                    req_map.insert("method".to_string(), Value::String(Arc::new(method.to_string())));
-                    req_map.insert("url".to_string(), Value::String(Arc::new(url)));
-                    req_map.insert("path".to_string(), Value::String(Arc::new(full_path))); // Full path with query
-                    req_map.insert("pathname".to_string(), Value::String(Arc::new(pathname))); // Just path
-                    req_map.insert("query".to_string(), query_map); // Empty (Lazy)
-                    req_map.insert("headers".to_string(), Value::Relic(Arc::new(headers_relic)));
-                    req_map.insert("cookies".to_string(), cookies_map); // Empty (Lazy)
-                    req_map.insert("body".to_string(), Value::String(Arc::new(body_str)));
-                    req_map.insert("ip".to_string(), Value::String(Arc::new(ip)));
-                    req_map.insert("host".to_string(), Value::String(Arc::new(host)));
-                    req_map.insert("protocol".to_string(), Value::String(Arc::new(protocol.to_string())));
-                    
-                    let request_value = Value::Relic(Arc::new(req_map));
-                    
-                    // Use cached Response Prototype (Ref count bump only, no allocation)
-                    let response_value = response_proto;
-
-                    // --- DISPATCH TO INTERPRETER ---
-                    let (response_tx, response_rx) = tokio::sync::oneshot::channel();
-                    
-                    let callback_request = crate::runtime::WebCallbackRequest {
-                        callback: handler,
-                        args: vec![request_value, response_value],
-                        response_tx,
-                    };
+                // --- DISPATCH TO INTERPRETER ---
+                let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+                
+                let callback_request = crate::runtime::WebCallbackRequest {
+                    callback: handler,
+                    args: vec![request_value, response_value],
+                    response_tx,
+                };
 
-                    if callback_tx.send(callback_request).is_err() {
-                        return Ok::<_, warp::Rejection>(
-                            warp::reply::with_status(
-                                "Server Busy",
-                                warp::http::StatusCode::SERVICE_UNAVAILABLE,
-                            ).into_response()
-                        );
-                    }
+                if callback_tx.send(callback_request).await.is_err() {
+                    return Ok::<_, warp::Rejection>(
+                        warp::reply::with_status(
+                            "Server Busy",
+                            warp::http::StatusCode::SERVICE_UNAVAILABLE,
+                        ).into_response()
+                    );
+                }
 
-                    // Wait for result from Interpreter
-                    match response_rx.await {
-                        Ok(result) => {
-                            let (status, body, content_type, custom_headers) = extract_response(result);
-                            
-                            let status_code = warp::http::StatusCode::from_u16(status)
-                                .unwrap_or(warp::http::StatusCode::OK);
-                                
-                            let mut reply = warp::reply::with_status(body, status_code).into_response();
-                            
-                            reply.headers_mut().insert(
-                                "Content-Type",
-                                content_type.parse().unwrap_or_else(|_| "text/plain".parse().unwrap())
-                            );
+                // Wait for result from Interpreter
+                match response_rx.await {
+                    Ok(result) => {
+                        let stream_body = open_stream(&result);
+                        let (status, body, content_type, custom_headers) = extract_response(result);
+                        
+                        let status_code = warp::http::StatusCode::from_u16(status)
+                            .unwrap_or(warp::http::StatusCode::OK);
                             
-                            for (name, value) in custom_headers {
-                                if let (Ok(n), Ok(v)) = (
-                                    warp::http::header::HeaderName::try_from(name.as_str()),
-                                    warp::http::header::HeaderValue::try_from(value.as_str())
-                                ) {
-                                    reply.headers_mut().insert(n, v);
-                                }
+                        let mut reply = match stream_body {
+                            Some(stream_body) => {
+                                let mut reply = warp::reply::Response::new(stream_body);
+                                *reply.status_mut() = status_code;
+                                reply
+                            }
+                            None => warp::reply::with_status(body, status_code).into_response(),
+                        };
+                        
+                        reply.headers_mut().insert(
+                            "Content-Type",
+                            content_type.parse().unwrap_or_else(|_| "text/plain".parse().unwrap())
+                        );
+                        
+                        for (name, value) in custom_headers {
+                            if let (Ok(n), Ok(v)) = (
+                                warp::http::header::HeaderName::try_from(name.as_str()),
+                                warp::http::header::HeaderValue::try_from(value.as_str())
+                            ) {
+                                reply.headers_mut().insert(n, v);
                             }
-                            
-                            Ok(reply)
                         }
-                        Err(_) => Ok(
-                            warp::reply::with_status(
-                                "Evaluation Error",
-                                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
-                            ).into_response()
-                        ),
+                        
+                        Ok(reply)
                     }
+                    // The request was dropped from a full queue (drop-oldest) or never ran
+                    Err(_) => Ok(
+                        warp::reply::with_status(
+                            "Server Busy",
+                            warp::http::StatusCode::SERVICE_UNAVAILABLE,
+                        ).into_response()
+                    ),
                 }
-            });
+            };
+            
+            async move {
+                let mut result = respond.await;
+                if let Ok(reply) = &mut result {
+                    middleware.apply(origin.as_deref(), reply);
+                }
+                let status = result.as_ref().map(|reply| reply.status().as_u16()).unwrap_or(500);
+                let elapsed = started.elapsed();
+                metrics.request_finished(&method_name, status, elapsed);
+                if let (Some(format), Some(mut entry)) = (access_log, access_entry) {
+                    use warp::hyper::body::HttpBody;
+                    entry.status = status;
+                    entry.duration = elapsed;
+                    entry.bytes = result.as_ref().ok().and_then(|reply| reply.body().size_hint().exact());
+                    let level = if status >= 500 { crate::stdlib::log::Level::Error } else { crate::stdlib::log::Level::Info };
+                    crate::stdlib::log::write_line(level, &entry.format(format));
+                }
+                result
+            }
+        })
+}
 
-        // Run server with graceful shutdown
-        let addr = ([0, 0, 0, 0], port);
-        let (_, server) = warp::serve(routes)
-            .bind_with_graceful_shutdown(addr, async {
-                let _ = shutdown_rx.await;
-            });
+/// The `res` argument of handlers, built once per server: every request shares it,
+/// so only a reference count changes per request
+pub fn response_prototype() -> Value {
+    let mut map = RelicMap::new();
+    map.insert("json".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_json))));
+    map.insert("html".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_html))));
+    map.insert("text".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_text))));
+    map.insert("status".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_status))));
+    map.insert("redirect".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_redirect))));
+    map.insert("notFound".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_not_found))));
+    map.insert("badRequest".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_bad_request))));
+    map.insert("serverError".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_server_error))));
+    map.insert("ok".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_ok))));
+    map.insert("created".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_created))));
+    map.insert("noContent".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_no_content))));
+    map.insert("unauthorized".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_unauthorized))));
+    map.insert("forbidden".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_forbidden))));
+    map.insert("send".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_send))));
+    map.insert("file".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_file))));
+    map.insert("header".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_header))));
+    map.insert("stream".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_stream))));
+    map.insert("sse".to_string(), Value::NativeFunction(NativeFn(Arc::new(res_sse))));
+    Value::Relic(Arc::new(map))
 
-        server.await;
-        
-        // Unregister handle when server stops
-        runtime.unregister_handle(handle_id).await;
-    });
+}
 
-    Ok(Value::Handle(handle_id))
+/// Build the request Relic passed to handlers, for live and replayed requests
+pub fn request_value<'a>(
+    method: &str,
+    path: &str,
+    query: &str,
+    headers: impl Iterator<Item = (&'a str, &'a str)>,
+    body: &[u8],
+    ip: String,
+) -> Value {
+    let body_str = String::from_utf8_lossy(body).to_string();
+
+    // Combine path with query string for 'url' field
+    let full_path = if query.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}?{}", path, query)
+    };
+
+    // Single-Pass Header Processing
+    // Extracts 'host' and builds the Relic map in one go
+    let mut headers_relic = RelicMap::new();
+    let mut host = "localhost".to_string();
+
+    for (k_str, v_str) in headers {
+        if k_str == "host" {
+            host = v_str.to_string();
+        }
+
+        headers_relic.insert(
+            k_str.to_string(),
+            Value::String(Arc::new(v_str.to_string()))
+        );
+    }
+
+    // REMOVED: Eager Cookie Parsing (Expensive & often unused)
+    // Users can parse req.headers["cookie"] if needed
+    let cookies_map = Value::Relic(Arc::new(RelicMap::new()));
+
+    // REMOVED: Eager Query Parsing (Expensive & often unused)
+    // Users can parse req.url or req.query_string if needed
+    let query_map = Value::Relic(Arc::new(RelicMap::new()));
+
+    // Build URL
+    let protocol = "http";
+    let url = format!("{}://{}{}", protocol, host, full_path);
+
+    // Create Request Object
+    // Minimized allocations where possible
+    let mut req_map = RelicMap::new();
+    req_map.insert("method".to_string(), Value::String(Arc::new(method.to_string())));
+    req_map.insert("url".to_string(), Value::String(Arc::new(url)));
+    req_map.insert("path".to_string(), Value::String(Arc::new(full_path))); // Full path with query
+    req_map.insert("pathname".to_string(), Value::String(Arc::new(path.to_string()))); // Just path
+    req_map.insert("query".to_string(), query_map); // Empty (Lazy)
+    req_map.insert("headers".to_string(), Value::Relic(Arc::new(headers_relic)));
+    req_map.insert("cookies".to_string(), cookies_map); // Empty (Lazy)
+    req_map.insert("body".to_string(), Value::String(Arc::new(body_str)));
+    req_map.insert("ip".to_string(), Value::String(Arc::new(ip)));
+    req_map.insert("host".to_string(), Value::String(Arc::new(host)));
+    req_map.insert("protocol".to_string(), Value::String(Arc::new(protocol.to_string())));
+
+    Value::Relic(Arc::new(req_map))
+}
+
+/// Feed recorded requests through the handler one at a time (`flowlang replay`)
+async fn replay_requests(
+    port: u16,
+    requests: Vec<crate::stdlib::flowrec::RecordedRequest>,
+    handler: Value,
+    callback_tx: Arc<crate::runtime::queue::WebQueue>,
+    response_prototype: Value,
+) {
+    use colored::Colorize;
+
+    println!("{} {} request(s) recorded for port {}", "⏪ Replaying".bright_cyan().bold(), requests.len(), port);
+    for recorded in requests {
+        let (path, query) = recorded.path.split_once('?').unwrap_or((recorded.path.as_str(), ""));
+        let request = request_value(
+            &recorded.method,
+            path,
+            query,
+            recorded.headers.iter().map(|(k, v)| (k.as_str(), v.as_str())),
+            &recorded.body_bytes(),
+            recorded.ip.clone(),
+        );
+
+        let started = std::time::Instant::now();
+        let (response_tx, response_rx) = oneshot::channel();
+        let callback_request = crate::runtime::WebCallbackRequest {
+            callback: handler.clone(),
+            args: vec![request, response_prototype.clone()],
+            response_tx,
+        };
+        let result = match callback_tx.send(callback_request).await {
+            Ok(()) => response_rx.await.ok(),
+            Err(_) => None,
+        };
+
+        let line = match result {
+            Some(result) => {
+                let streamed = open_stream(&result).is_some();
+                let (status, body, _, _) = extract_response(result);
+                let size = if streamed { "stream".to_string() } else { format!("{} bytes", body.len()) };
+                let status_text = match status {
+                    500.. => status.to_string().red().bold(),
+                    400..=499 => status.to_string().yellow(),
+                    _ => status.to_string().green(),
+                };
+                format!("{} ({}, {:.2}ms)", status_text, size, started.elapsed().as_secs_f64() * 1000.0)
+            }
+            None => "dropped".red().bold().to_string(),
+        };
+        println!("  {} {} {} {}", recorded.time.dimmed(), recorded.method, recorded.path, line);
+    }
 }
 
 /// Extract status code, body, content-type, and headers from a handler response value
@@ -326,7 +1085,7 @@ fn res_json(args: Vec<Value>) -> Result<Value, FlowError> {
         _ => args[0].to_string(),
     };
 
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(200.0));
     map.insert("body".to_string(), Value::String(Arc::new(body)));
     map.insert("contentType".to_string(), Value::String(Arc::new("application/json".to_string())));
@@ -343,7 +1102,7 @@ fn res_html(args: Vec<Value>) -> Result<Value, FlowError> {
 
     let body = args[0].to_string();
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(200.0));
     map.insert("body".to_string(), Value::String(Arc::new(body)));
     map.insert("contentType".to_string(), Value::String(Arc::new("text/html".to_string())));
@@ -360,7 +1119,7 @@ fn res_text(args: Vec<Value>) -> Result<Value, FlowError> {
 
     let body = args[0].to_string();
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(200.0));
     map.insert("body".to_string(), Value::String(Arc::new(body)));
     map.insert("contentType".to_string(), Value::String(Arc::new("text/plain".to_string())));
@@ -386,7 +1145,7 @@ fn res_status(args: Vec<Value>) -> Result<Value, FlowError> {
         String::new()
     };
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(status));
     map.insert("body".to_string(), Value::String(Arc::new(body)));
 
@@ -402,11 +1161,11 @@ fn res_redirect(args: Vec<Value>) -> Result<Value, FlowError> {
 
     let url = args[0].to_string();
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(302.0));
     map.insert("body".to_string(), Value::String(Arc::new(String::new())));
     map.insert("headers".to_string(), {
-        let mut headers = HashMap::new();
+        let mut headers = RelicMap::new();
         headers.insert("Location".to_string(), Value::String(Arc::new(url)));
         Value::Relic(Arc::new(headers))
     });
@@ -423,7 +1182,7 @@ fn res_not_found(args: Vec<Value>) -> Result<Value, FlowError> {
         args[0].to_string()
     };
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(404.0));
     map.insert("body".to_string(), Value::String(Arc::new(body)));
 
@@ -439,7 +1198,7 @@ fn res_bad_request(args: Vec<Value>) -> Result<Value, FlowError> {
         args[0].to_string()
     };
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(400.0));
     map.insert("body".to_string(), Value::String(Arc::new(body)));
 
@@ -455,7 +1214,7 @@ fn res_server_error(args: Vec<Value>) -> Result<Value, FlowError> {
         args[0].to_string()
     };
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(500.0));
     map.insert("body".to_string(), Value::String(Arc::new(body)));
 
@@ -471,7 +1230,7 @@ fn res_ok(args: Vec<Value>) -> Result<Value, FlowError> {
         args[0].to_string()
     };
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(200.0));
     map.insert("body".to_string(), Value::String(Arc::new(body)));
 
@@ -492,7 +1251,7 @@ fn res_created(args: Vec<Value>) -> Result<Value, FlowError> {
         }
     };
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(201.0));
     map.insert("body".to_string(), Value::String(Arc::new(body)));
 
@@ -502,7 +1261,7 @@ fn res_created(args: Vec<Value>) -> Result<Value, FlowError> {
 /// res.noContent() -> Relic
 /// Create a 204 No Content response
 fn res_no_content(_args: Vec<Value>) -> Result<Value, FlowError> {
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(204.0));
     map.insert("body".to_string(), Value::String(Arc::new(String::new())));
 
@@ -518,7 +1277,7 @@ fn res_unauthorized(args: Vec<Value>) -> Result<Value, FlowError> {
         args[0].to_string()
     };
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(401.0));
     map.insert("body".to_string(), Value::String(Arc::new(body)));
 
@@ -534,7 +1293,7 @@ fn res_forbidden(args: Vec<Value>) -> Result<Value, FlowError> {
         args[0].to_string()
     };
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(403.0));
     map.insert("body".to_string(), Value::String(Arc::new(body)));
 
@@ -564,7 +1323,7 @@ fn res_send(args: Vec<Value>) -> Result<Value, FlowError> {
         _ => (args[0].to_string(), "text/plain"),
     };
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(200.0));
     map.insert("body".to_string(), Value::String(Arc::new(body)));
     map.insert("contentType".to_string(), Value::String(Arc::new(content_type.to_string())));
@@ -586,7 +1345,7 @@ fn res_file(args: Vec<Value>) -> Result<Value, FlowError> {
         Ok(bytes) => bytes,
         Err(e) => {
             return Ok({
-                let mut map = HashMap::new();
+                let mut map = RelicMap::new();
                 map.insert("status".to_string(), Value::Number(404.0));
                 map.insert("body".to_string(), Value::String(Arc::new(format!("File not found: {}", e))));
                 Value::Relic(Arc::new(map))
@@ -630,11 +1389,11 @@ fn res_file(args: Vec<Value>) -> Result<Value, FlowError> {
         .unwrap_or("file");
     
     // Build headers with Content-Disposition
-    let mut headers = HashMap::new();
+    let mut headers = RelicMap::new();
     headers.insert("Content-Disposition".to_string(), 
         Value::String(Arc::new(format!("inline; filename=\"{}\"", filename))));
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(200.0));
     map.insert("body".to_string(), Value::String(Arc::new(body)));
     map.insert("contentType".to_string(), Value::String(Arc::new(content_type.to_string())));
@@ -654,13 +1413,326 @@ fn res_header(args: Vec<Value>) -> Result<Value, FlowError> {
     let value = args[1].to_string();
     
     // Return a Relic with headers field
-    let mut headers = HashMap::new();
+    let mut headers = RelicMap::new();
     headers.insert(name, Value::String(Arc::new(value)));
     
-    let mut map = HashMap::new();
+    let mut map = RelicMap::new();
     map.insert("status".to_string(), Value::Number(200.0));
     map.insert("body".to_string(), Value::String(Arc::new(String::new())));
     map.insert("headers".to_string(), Value::Relic(Arc::new(headers)));
 
     Ok(Value::Relic(Arc::new(map)))
 }
+
+// ============================================================================
+// Streaming responses
+// ============================================================================
+
+/// A streaming response body. Chunks written before the handler returns are
+/// buffered; once the server sends the headers they are flushed in order, and
+/// later writes wait until the client has taken the previous chunk.
+struct ResponseStream {
+    inner: tokio::sync::Mutex<StreamInner>,
+    /// Taken by the server when the handler returns the writer
+    body: std::sync::Mutex<Option<hyper::Body>>,
+}
+
+struct StreamInner {
+    started: bool,
+    closed: bool,
+    pending: Vec<bytes::Bytes>,
+    /// None once closed or the client went away
+    sender: Option<hyper::body::Sender>,
+}
+
+impl ResponseStream {
+    /// Queue or send one chunk; false once the stream is closed or the client disconnected
+    async fn write(&self, chunk: bytes::Bytes) -> bool {
+        let mut inner = self.inner.lock().await;
+        if inner.closed {
+            return false;
+        }
+        if !inner.started {
+            inner.pending.push(chunk);
+            return true;
+        }
+        match inner.sender.as_mut() {
+            Some(sender) => {
+                if sender.send_data(chunk).await.is_ok() {
+                    return true;
+                }
+                inner.sender = None;
+                inner.closed = true;
+                false
+            }
+            None => false,
+        }
+    }
+
+    async fn close(&self) {
+        let mut inner = self.inner.lock().await;
+        inner.closed = true;
+        // Before the response starts, the flush drops the sender after the pending chunks
+        if inner.started {
+            inner.sender = None;
+        }
+    }
+
+    /// Flush chunks written before the response started, then hand over to write()
+    async fn start(&self) {
+        let mut inner = self.inner.lock().await;
+        inner.started = true;
+        let pending = std::mem::take(&mut inner.pending);
+        for chunk in pending {
+            let Some(sender) = inner.sender.as_mut() else { break };
+            if sender.send_data(chunk).await.is_err() {
+                inner.sender = None;
+                inner.closed = true;
+            }
+        }
+        if inner.closed {
+            inner.sender = None;
+        }
+    }
+}
+
+static STREAMS: std::sync::Mutex<Option<HashMap<u64, Arc<ResponseStream>>>> = std::sync::Mutex::new(None);
+static NEXT_STREAM_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
+/// If a handler returned a stream writer, take its body and start flushing it
+fn open_stream(result: &Value) -> Option<hyper::Body> {
+    let id = match result {
+        Value::Relic(map) => match map.get("__stream") {
+            Some(Value::Number(id)) => *id as u64,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let stream = STREAMS.lock().unwrap_or_else(PoisonError::into_inner).as_mut()?.remove(&id)?;
+    let body = stream.body.lock().unwrap_or_else(PoisonError::into_inner).take()?;
+    tokio::spawn(async move { stream.start().await });
+    Some(body)
+}
+
+fn new_stream(content_type: &str, extra_headers: RelicMap, sse: bool) -> Value {
+    let (sender, body) = hyper::Body::channel();
+    let stream = Arc::new(ResponseStream {
+        inner: tokio::sync::Mutex::new(StreamInner { started: false, closed: false, pending: Vec::new(), sender: Some(sender) }),
+        body: std::sync::Mutex::new(Some(body)),
+    });
+    let id = NEXT_STREAM_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    STREAMS.lock().unwrap_or_else(PoisonError::into_inner).get_or_insert_with(HashMap::new).insert(id, stream.clone());
+
+    let mut map = RelicMap::new();
+    map.insert("__stream".to_string(), Value::Number(id as f64));
+    map.insert("status".to_string(), Value::Number(200.0));
+    map.insert("contentType".to_string(), Value::String(Arc::new(content_type.to_string())));
+    map.insert("headers".to_string(), Value::Relic(Arc::new(extra_headers)));
+
+    let writer = stream.clone();
+    map.insert("write".to_string(), Value::AsyncNativeFunction(AsyncNativeFn::new(move |args, _ctx| {
+        let writer = writer.clone();
+        async move {
+            let chunk = match args.first() {
+                Some(value) => value.to_string(),
+                None => return Err(FlowError::runtime("stream.write expects 1 argument (chunk)", 0, 0)),
+            };
+            Ok(Value::Boolean(writer.write(bytes::Bytes::from(chunk)).await))
+        }
+    })));
+
+    if sse {
+        let writer = stream.clone();
+        map.insert("send".to_string(), Value::AsyncNativeFunction(AsyncNativeFn::new(move |args, _ctx| {
+            let writer = writer.clone();
+            async move {
+                let data = match args.first() {
+                    Some(value @ (Value::Relic(_) | Value::Array(_))) => crate::stdlib::json::value_to_json_string(value),
+                    Some(value) => value.to_string(),
+                    None => return Err(FlowError::runtime("stream.send expects 1 argument (data)", 0, 0)),
+                };
+                let options = match args.get(1) {
+                    Some(Value::Relic(options)) => Some(options.clone()),
+                    Some(Value::String(event)) => {
+                        let mut options = RelicMap::new();
+                        options.insert("event".to_string(), Value::String(event.clone()));
+                        Some(Arc::new(options))
+                    }
+                    _ => None,
+                };
+                let event = sse_event(&data, options.as_deref());
+                Ok(Value::Boolean(writer.write(bytes::Bytes::from(event)).await))
+            }
+        })));
+    }
+
+    let closer = stream;
+    map.insert("close".to_string(), Value::AsyncNativeFunction(AsyncNativeFn::new(move |_args, _ctx| {
+        let closer = closer.clone();
+        async move {
+            closer.close().await;
+            Ok(Value::Null)
+        }
+    })));
+
+    Value::Relic(Arc::new(map))
+}
+
+/// One event in the text/event-stream format. Options: event, id, retry.
+fn sse_event(data: &str, options: Option<&RelicMap>) -> String {
+    let mut out = String::new();
+    if let Some(options) = options {
+        for field in ["event", "id", "retry"] {
+            if let Some(value) = options.get(field) {
+                out.push_str(&format!("{}: {}\n", field, value));
+            }
+        }
+    }
+    for line in data.split('\n') {
+        out.push_str("data: ");
+        out.push_str(line.trim_end_matches('\r'));
+        out.push('\n');
+    }
+    out.push('\n');
+    out
+}
+
+/// res.stream(contentType?) -> writer
+/// Returns a writer with write(chunk) and close(). Return it from the handler to
+/// send the headers; keep writing from callbacks until close().
+fn res_stream(args: Vec<Value>) -> Result<Value, FlowError> {
+    let content_type = match args.first() {
+        Some(Value::String(ct)) => ct.to_string(),
+        Some(_) => return Err(FlowError::type_error("res.stream expects a Silk content type", 0, 0)),
+        None => "text/plain; charset=utf-8".to_string(),
+    };
+    Ok(new_stream(&content_type, RelicMap::new(), false))
+}
+
+/// res.sse() -> writer
+/// A text/event-stream writer with send(data, event?) in addition to write and close
+fn res_sse(_args: Vec<Value>) -> Result<Value, FlowError> {
+    let mut headers = RelicMap::new();
+    headers.insert("Cache-Control".to_string(), Value::String(Arc::new("no-cache".to_string())));
+    headers.insert("X-Accel-Buffering".to_string(), Value::String(Arc::new("no".to_string())));
+    Ok(new_stream("text/event-stream", headers, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_sse_event_format() {
+        assert_eq!(sse_event("hello", None), "data: hello\n\n");
+
+        let mut options = RelicMap::new();
+        options.insert("event".to_string(), Value::String(Arc::new("tick".to_string())));
+        options.insert("id".to_string(), Value::Number(7.0));
+        assert_eq!(sse_event("a\nb", Some(&options)), "event: tick\nid: 7\ndata: a\ndata: b\n\n");
+    }
+
+    #[tokio::test]
+    async fn test_stream_flushes_pending_chunks_in_order() {
+        use hyper::body::HttpBody;
+
+        let writer = res_stream(vec![]).unwrap();
+        let Value::Relic(map) = &writer else { unreachable!() };
+        let Some(Value::AsyncNativeFunction(write)) = map.get("write") else { unreachable!() };
+        let Some(Value::AsyncNativeFunction(close)) = map.get("close") else { unreachable!() };
+        let ctx = || AsyncContext { runtime: Arc::new(crate::runtime::Runtime::new()) };
+
+        // Written before the response starts
+        (write.0)(vec![Value::String(Arc::new("one ".to_string()))], ctx()).await.unwrap();
+        let mut body = open_stream(&writer).unwrap();
+        (write.0)(vec![Value::String(Arc::new("two".to_string()))], ctx()).await.unwrap();
+        (close.0)(vec![], ctx()).await.unwrap();
+
+        let mut received = Vec::new();
+        while let Some(chunk) = body.data().await {
+            received.extend_from_slice(&chunk.unwrap());
+        }
+        assert_eq!(String::from_utf8(received).unwrap(), "one two");
+        assert!(open_stream(&writer).is_none());
+    }
+
+    #[test]
+    fn test_cors_and_security_headers() {
+        let relic = |pairs: Vec<(&str, Value)>| Value::Relic(Arc::new(pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect()));
+        let text = |s: &str| Value::String(Arc::new(s.to_string()));
+        let cors = web_cors(vec![relic(vec![("origin", Value::Array(Arc::new(vec![text("https://app.example")])))])]).unwrap();
+        let security = web_security_headers(vec![relic(vec![("csp", Value::Boolean(false))])]).unwrap();
+        let middleware = Middleware::from_option(&Value::Array(Arc::new(vec![cors, security]))).unwrap();
+
+        let mut request_headers = warp::http::HeaderMap::new();
+        request_headers.insert("access-control-request-method", "PUT".parse().unwrap());
+        let preflight = middleware.preflight(&warp::http::Method::OPTIONS, &request_headers, Some("https://app.example")).unwrap();
+        assert_eq!(preflight.status(), 204);
+        assert_eq!(preflight.headers()["access-control-allow-methods"], CORS_METHODS);
+        assert!(middleware.preflight(&warp::http::Method::GET, &request_headers, None).is_none());
+
+        let mut reply = warp::reply::with_header("ok", "X-Frame-Options", "SAMEORIGIN").into_response();
+        middleware.apply(Some("https://app.example"), &mut reply);
+        let headers = reply.headers();
+        assert_eq!(headers["access-control-allow-origin"], "https://app.example");
+        assert_eq!(headers["vary"], "Origin");
+        assert_eq!(headers["x-frame-options"], "SAMEORIGIN"); // the handler's value wins
+        assert_eq!(headers["x-content-type-options"], "nosniff");
+        assert!(!headers.contains_key("content-security-policy"));
+
+        let mut other = warp::reply::reply().into_response();
+        middleware.apply(Some("https://evil.example"), &mut other);
+        assert!(!other.headers().contains_key("access-control-allow-origin"));
+    }
+
+    #[test]
+    fn test_access_log_formats() {
+        let entry = AccessEntry {
+            time: chrono::Local.with_ymd_and_hms(2024, 3, 9, 14, 5, 7).unwrap(),
+            ip: "10.0.0.7".to_string(),
+            method: "GET".to_string(),
+            path: "/users?page=2".to_string(),
+            status: 200,
+            bytes: Some(512),
+            duration: std::time::Duration::from_micros(1500),
+            referer: String::new(),
+            user_agent: "curl/8.0".to_string(),
+        };
+
+        assert_eq!(entry.format(AccessLogFormat::Short), "GET /users?page=2 200 1.50ms 10.0.0.7");
+        let common = entry.format(AccessLogFormat::Common);
+        assert!(common.starts_with("10.0.0.7 - - [09/Mar/2024:14:05:07 "));
+        assert!(common.ends_with("] \"GET /users?page=2 HTTP/1.1\" 200 512"));
+        assert_eq!(entry.format(AccessLogFormat::Combined), format!("{} \"-\" \"curl/8.0\"", common));
+
+        let json: serde_json::Value = serde_json::from_str(&entry.format(AccessLogFormat::Json)).unwrap();
+        assert_eq!(json["status"], 200);
+        assert_eq!(json["duration_ms"], 1.5);
+        assert_eq!(json["ip"], "10.0.0.7");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_bind_unix_replaces_only_stale_sockets() {
+        let path = std::env::temp_dir().join(format!("flowlang-web-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let live = bind_unix("web.serveUnix", &path).unwrap();
+        assert!(bind_unix("web.serveUnix", &path).unwrap_err().to_string().contains("another server is listening"));
+        drop(live);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_host_option() {
+        let host = |h: &str| host_option(Some(&Value::String(Arc::new(h.to_string()))));
+        assert_eq!(host_option(None).unwrap(), ALL_INTERFACES);
+        assert_eq!(host("localhost").unwrap().to_string(), "127.0.0.1");
+        assert_eq!(host("::1").unwrap().to_string(), "::1");
+        assert!(host("example.com").is_err());
+        assert!(host_option(Some(&Value::Number(1.0))).is_err());
+    }
+}
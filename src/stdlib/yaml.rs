@@ -0,0 +1,119 @@
+//! std:yaml - YAML parsing and writing
+//!
+//! - `yaml.parse(text)` - Parse a single YAML document
+//! - `yaml.parseAll(text)` - Parse a `---` separated stream into a Constellation
+//! - `yaml.stringify(value)` - Write a value as YAML
+
+use crate::error::FlowError;
+use crate::types::{NativeFn, Value, RelicMap};
+use serde::Deserialize;
+use std::sync::Arc;
+
+pub fn load_yaml_module() -> Vec<(&'static str, Value)> {
+    vec![
+        ("parse", Value::NativeFunction(NativeFn::new(yaml_parse))),
+        ("parseAll", Value::NativeFunction(NativeFn::new(yaml_parse_all))),
+        ("stringify", Value::NativeFunction(NativeFn::new(yaml_stringify))),
+    ]
+}
+
+/// yaml.parse(text) -> Flux
+fn yaml_parse(args: Vec<Value>) -> Result<Value, FlowError> {
+    let text = expect_text(&args, "yaml.parse")?;
+
+    let doc: serde_yaml::Value = serde_yaml::from_str(&text)
+        .map_err(|e| FlowError::glitch(&format!("Invalid YAML: {}", e), 0, 0))?;
+    Ok(yaml_to_value(doc))
+}
+
+/// yaml.parseAll(text) -> Constellation
+fn yaml_parse_all(args: Vec<Value>) -> Result<Value, FlowError> {
+    let text = expect_text(&args, "yaml.parseAll")?;
+
+    let mut docs = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(&text) {
+        let doc = serde_yaml::Value::deserialize(document)
+            .map_err(|e| FlowError::glitch(&format!("Invalid YAML: {}", e), 0, 0))?;
+        docs.push(yaml_to_value(doc));
+    }
+    Ok(Value::Array(Arc::new(docs)))
+}
+
+/// yaml.stringify(value) -> Silk
+fn yaml_stringify(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime("yaml.stringify expects 1 argument", 0, 0));
+    }
+
+    let text = serde_yaml::to_string(&value_to_yaml(&args[0]))
+        .map_err(|e| FlowError::glitch(&format!("yaml.stringify failed: {}", e), 0, 0))?;
+    Ok(Value::String(Arc::new(text)))
+}
+
+fn expect_text(args: &[Value], func: &str) -> Result<Arc<String>, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime(&format!("{} expects 1 argument (yaml string)", func), 0, 0));
+    }
+    match &args[0] {
+        Value::String(s) => Ok(s.clone()),
+        _ => Err(FlowError::type_error(&format!("{} expects a Silk (string)", func), 0, 0)),
+    }
+}
+
+fn yaml_to_value(v: serde_yaml::Value) -> Value {
+    match v {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Boolean(b),
+        serde_yaml::Value::Number(n) => Value::Number(n.as_f64().unwrap_or(0.0)),
+        serde_yaml::Value::String(s) => Value::String(Arc::new(s)),
+        serde_yaml::Value::Sequence(items) => {
+            Value::Array(Arc::new(items.into_iter().map(yaml_to_value).collect()))
+        }
+        serde_yaml::Value::Mapping(mapping) => {
+            let map: RelicMap = mapping
+                .into_iter()
+                .map(|(k, v)| (yaml_key(k), yaml_to_value(v)))
+                .collect();
+            Value::Relic(Arc::new(map))
+        }
+        // Custom tags (e.g. !Ref) are dropped, keeping the tagged value
+        serde_yaml::Value::Tagged(tagged) => yaml_to_value(tagged.value),
+    }
+}
+
+fn yaml_key(key: serde_yaml::Value) -> String {
+    match key {
+        serde_yaml::Value::String(s) => s,
+        serde_yaml::Value::Null => "null".to_string(),
+        other => yaml_to_value(other).to_string(),
+    }
+}
+
+fn value_to_yaml(value: &Value) -> serde_yaml::Value {
+    match value {
+        Value::Null => serde_yaml::Value::Null,
+        Value::Boolean(b) => serde_yaml::Value::Bool(*b),
+        Value::Number(n) => {
+            if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+                serde_yaml::Value::Number((*n as i64).into())
+            } else {
+                serde_yaml::Value::Number((*n).into())
+            }
+        }
+        Value::String(s) => serde_yaml::Value::String(s.to_string()),
+        Value::Array(items) => serde_yaml::Value::Sequence(items.iter().map(value_to_yaml).collect()),
+        Value::Covenant(set) => serde_yaml::Value::Sequence(set.values().map(value_to_yaml).collect()),
+        Value::Relic(map) => {
+            let mut mapping = serde_yaml::Mapping::new();
+            for (key, item) in map.iter() {
+                mapping.insert(serde_yaml::Value::String(key.clone()), value_to_yaml(item));
+            }
+            serde_yaml::Value::Mapping(mapping)
+        }
+        Value::Handle(id) => serde_yaml::Value::Number((*id).into()),
+        Value::Function { .. }
+        | Value::NativeFunction(_)
+        | Value::AsyncNativeFunction(_)
+        | Value::InterpreterNativeFunction(_) => serde_yaml::Value::Null,
+    }
+}
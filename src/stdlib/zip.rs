@@ -0,0 +1,299 @@
+//! std:zip and std:gzip - Archives and compression
+//!
+//! - `zip.create(path, files)` - Build a .zip from files and directories
+//! - `zip.extract(path, dest)` - Unpack a .zip into a directory
+//! - `zip.list(path)` - Entry names inside a .zip
+//! - `gzip.compress(text)` / `gzip.decompress(data)` - In-memory gzip (base64 Silk)
+//! - `gzip.compressFile(src, dest?)` / `gzip.decompressFile(src, dest?)` - Gzip files on disk
+
+use crate::error::FlowError;
+use crate::types::{NativeFn, Value};
+use base64::{Engine as _, engine::general_purpose};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub fn load_zip_module() -> Vec<(&'static str, Value)> {
+    vec![
+        ("create", Value::NativeFunction(NativeFn::new(zip_create))),
+        ("extract", Value::NativeFunction(NativeFn::new(zip_extract))),
+        ("list", Value::NativeFunction(NativeFn::new(zip_list))),
+    ]
+}
+
+pub fn load_gzip_module() -> Vec<(&'static str, Value)> {
+    vec![
+        ("compress", Value::NativeFunction(NativeFn::new(gzip_compress))),
+        ("decompress", Value::NativeFunction(NativeFn::new(gzip_decompress))),
+        ("compressFile", Value::NativeFunction(NativeFn::new(gzip_compress_file))),
+        ("decompressFile", Value::NativeFunction(NativeFn::new(gzip_decompress_file))),
+    ]
+}
+
+/// zip.create(path, files) -> Ember
+/// `files` is a Constellation of paths (directories are added recursively,
+/// keeping their relative layout) or a Relic mapping archive names to paths.
+/// Returns the number of files written.
+fn zip_create(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 2 {
+        return Err(FlowError::runtime(
+            "zip.create expects 2 arguments (path, files)",
+            0, 0,
+        ));
+    }
+
+    let archive_path = expect_silk(&args[0], "zip.create", "path")?;
+
+    // (name inside archive, source on disk)
+    let mut entries: Vec<(String, PathBuf)> = Vec::new();
+    match &args[1] {
+        Value::Array(paths) => {
+            for item in paths.iter() {
+                let source = PathBuf::from(expect_silk(item, "zip.create", "file path")?);
+                collect_entries(&source, &archive_name(&source), &mut entries)?;
+            }
+        }
+        Value::Relic(map) => {
+            let mut names: Vec<&String> = map.keys().collect();
+            names.sort();
+            for name in names {
+                let source = PathBuf::from(expect_silk(&map[name], "zip.create", "file path")?);
+                collect_entries(&source, name, &mut entries)?;
+            }
+        }
+        _ => return Err(FlowError::type_error(
+            "zip.create expects a Constellation of paths or a Relic of name -> path",
+            0, 0,
+        )),
+    }
+
+    let file = File::create(&archive_path)
+        .map_err(|e| FlowError::rift(&format!("Failed to create archive '{}': {}", archive_path, e), 0, 0))?;
+    let mut writer = ::zip::ZipWriter::new(file);
+    let options = ::zip::write::FileOptions::default()
+        .compression_method(::zip::CompressionMethod::Deflated);
+
+    for (name, source) in &entries {
+        let mut input = File::open(source)
+            .map_err(|e| FlowError::rift(&format!("Failed to open '{}': {}", source.display(), e), 0, 0))?;
+        writer.start_file(name.as_str(), options)
+            .map_err(|e| FlowError::rift(&format!("Failed to add '{}' to archive: {}", name, e), 0, 0))?;
+        io::copy(&mut input, &mut writer)
+            .map_err(|e| FlowError::rift(&format!("Failed to compress '{}': {}", source.display(), e), 0, 0))?;
+    }
+
+    writer.finish()
+        .map_err(|e| FlowError::rift(&format!("Failed to finish archive '{}': {}", archive_path, e), 0, 0))?;
+
+    Ok(Value::Number(entries.len() as f64))
+}
+
+/// zip.extract(path, dest) -> Constellation
+/// Returns the extracted entry names. Entries that would escape `dest`
+/// (absolute paths, `..`) are rejected.
+fn zip_extract(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 2 {
+        return Err(FlowError::runtime(
+            "zip.extract expects 2 arguments (path, dest)",
+            0, 0,
+        ));
+    }
+
+    let archive_path = expect_silk(&args[0], "zip.extract", "path")?;
+    let dest = PathBuf::from(expect_silk(&args[1], "zip.extract", "dest")?);
+
+    let mut archive = open_archive(&archive_path)?;
+    let mut extracted = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)
+            .map_err(|e| FlowError::glitch(&format!("Corrupt archive '{}': {}", archive_path, e), 0, 0))?;
+
+        let relative = entry.enclosed_name().map(|p| p.to_path_buf()).ok_or_else(|| {
+            FlowError::glitch(&format!("Refusing to extract unsafe entry '{}'", entry.name()), 0, 0)
+        })?;
+        let target = dest.join(&relative);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&target)
+                .map_err(|e| FlowError::rift(&format!("Failed to create '{}': {}", target.display(), e), 0, 0))?;
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| FlowError::rift(&format!("Failed to create '{}': {}", parent.display(), e), 0, 0))?;
+        }
+        let mut output = File::create(&target)
+            .map_err(|e| FlowError::rift(&format!("Failed to create '{}': {}", target.display(), e), 0, 0))?;
+        io::copy(&mut entry, &mut output)
+            .map_err(|e| FlowError::rift(&format!("Failed to extract '{}': {}", entry.name(), e), 0, 0))?;
+
+        extracted.push(Value::String(Arc::new(entry.name().to_string())));
+    }
+
+    Ok(Value::Array(Arc::new(extracted)))
+}
+
+/// zip.list(path) -> Constellation
+fn zip_list(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime("zip.list expects 1 argument (path)", 0, 0));
+    }
+
+    let archive_path = expect_silk(&args[0], "zip.list", "path")?;
+    let archive = open_archive(&archive_path)?;
+    let names = archive.file_names()
+        .map(|name| Value::String(Arc::new(name.to_string())))
+        .collect();
+
+    Ok(Value::Array(Arc::new(names)))
+}
+
+/// gzip.compress(text) -> Silk
+/// Returns the gzip stream base64 encoded so it can travel as a Silk.
+fn gzip_compress(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime("gzip.compress expects 1 argument (text)", 0, 0));
+    }
+
+    let text = expect_silk(&args[0], "gzip.compress", "text")?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(text.as_bytes())
+        .and_then(|_| encoder.finish())
+        .map(|bytes| Value::String(Arc::new(general_purpose::STANDARD.encode(bytes))))
+        .map_err(|e| FlowError::runtime(&format!("gzip compression failed: {}", e), 0, 0))
+}
+
+/// gzip.decompress(data) -> Silk
+/// Accepts the base64 output of gzip.compress.
+fn gzip_decompress(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.len() != 1 {
+        return Err(FlowError::runtime("gzip.decompress expects 1 argument (data)", 0, 0));
+    }
+
+    let data = expect_silk(&args[0], "gzip.decompress", "data")?;
+    let bytes = general_purpose::STANDARD.decode(data.trim())
+        .map_err(|_| FlowError::glitch("gzip.decompress expects base64 data (from gzip.compress)", 0, 0))?;
+
+    let mut text = String::new();
+    GzDecoder::new(bytes.as_slice())
+        .read_to_string(&mut text)
+        .map_err(|e| FlowError::glitch(&format!("Invalid gzip data: {}", e), 0, 0))?;
+
+    Ok(Value::String(Arc::new(text)))
+}
+
+/// gzip.compressFile(src, dest?) -> Silk
+/// Writes `dest` (default `<src>.gz`) and returns its path.
+fn gzip_compress_file(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(FlowError::runtime("gzip.compressFile expects 1 or 2 arguments (src, dest?)", 0, 0));
+    }
+
+    let src = expect_silk(&args[0], "gzip.compressFile", "src")?;
+    let dest = match args.get(1) {
+        Some(value) => expect_silk(value, "gzip.compressFile", "dest")?,
+        None => format!("{}.gz", src),
+    };
+
+    let mut input = File::open(&src)
+        .map_err(|e| FlowError::rift(&format!("Failed to open '{}': {}", src, e), 0, 0))?;
+    let output = File::create(&dest)
+        .map_err(|e| FlowError::rift(&format!("Failed to create '{}': {}", dest, e), 0, 0))?;
+    let mut encoder = GzEncoder::new(output, Compression::default());
+    io::copy(&mut input, &mut encoder)
+        .and_then(|_| encoder.finish())
+        .map_err(|e| FlowError::rift(&format!("Failed to compress '{}': {}", src, e), 0, 0))?;
+
+    Ok(Value::String(Arc::new(dest)))
+}
+
+/// gzip.decompressFile(src, dest?) -> Silk
+/// Writes `dest` (default: `src` without `.gz`) and returns its path.
+fn gzip_decompress_file(args: Vec<Value>) -> Result<Value, FlowError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(FlowError::runtime("gzip.decompressFile expects 1 or 2 arguments (src, dest?)", 0, 0));
+    }
+
+    let src = expect_silk(&args[0], "gzip.decompressFile", "src")?;
+    let dest = match args.get(1) {
+        Some(value) => expect_silk(value, "gzip.decompressFile", "dest")?,
+        None => match src.strip_suffix(".gz") {
+            Some(stripped) => stripped.to_string(),
+            None => return Err(FlowError::runtime(
+                "gzip.decompressFile needs a dest when src doesn't end in .gz",
+                0, 0,
+            )),
+        },
+    };
+
+    let input = File::open(&src)
+        .map_err(|e| FlowError::rift(&format!("Failed to open '{}': {}", src, e), 0, 0))?;
+    let mut output = File::create(&dest)
+        .map_err(|e| FlowError::rift(&format!("Failed to create '{}': {}", dest, e), 0, 0))?;
+    io::copy(&mut GzDecoder::new(input), &mut output)
+        .map_err(|e| FlowError::glitch(&format!("Failed to decompress '{}': {}", src, e), 0, 0))?;
+
+    Ok(Value::String(Arc::new(dest)))
+}
+
+fn open_archive(path: &str) -> Result<::zip::ZipArchive<File>, FlowError> {
+    let file = File::open(path)
+        .map_err(|e| FlowError::rift(&format!("Failed to open archive '{}': {}", path, e), 0, 0))?;
+    ::zip::ZipArchive::new(file)
+        .map_err(|e| FlowError::glitch(&format!("Invalid zip archive '{}': {}", path, e), 0, 0))
+}
+
+/// Name a source path gets inside the archive: relative paths are kept,
+/// absolute ones are reduced to their final component.
+fn archive_name(source: &Path) -> String {
+    let name = if source.is_absolute() {
+        source.file_name().map(PathBuf::from).unwrap_or_default()
+    } else {
+        source.components()
+            .filter(|c| matches!(c, std::path::Component::Normal(_)))
+            .collect::<PathBuf>()
+    };
+    // Zip entries always use forward slashes
+    name.to_string_lossy().replace('\\', "/")
+}
+
+fn collect_entries(source: &Path, name: &str, entries: &mut Vec<(String, PathBuf)>) -> Result<(), FlowError> {
+    if source.is_file() {
+        entries.push((name.to_string(), source.to_path_buf()));
+        return Ok(());
+    }
+
+    if !source.is_dir() {
+        return Err(FlowError::rift(&format!("zip.create: '{}' does not exist", source.display()), 0, 0));
+    }
+
+    let mut children: Vec<PathBuf> = fs::read_dir(source)
+        .map_err(|e| FlowError::rift(&format!("Failed to read directory '{}': {}", source.display(), e), 0, 0))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    children.sort();
+
+    for child in children {
+        let child_name = child.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let nested = if name.is_empty() { child_name } else { format!("{}/{}", name.trim_end_matches('/'), child_name) };
+        collect_entries(&child, &nested, entries)?;
+    }
+
+    Ok(())
+}
+
+fn expect_silk(value: &Value, func: &str, what: &str) -> Result<String, FlowError> {
+    match value {
+        Value::String(s) => Ok((**s).clone()),
+        _ => Err(FlowError::type_error(
+            &format!("{} expects a Silk (string) {}", func, what),
+            0, 0,
+        )),
+    }
+}
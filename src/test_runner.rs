@@ -0,0 +1,196 @@
+// Test runner for `flowlang test`
+//
+// Discovers `*_test.flow` scripts, runs each one in a fresh interpreter and
+// reports which ones finished without an uncaught error. Assertions come from
+// std:assert; snapshots are stored next to each test in `__snapshots__/`. What a
+// test prints is captured and only shown when it fails (or with --verbose).
+
+use colored::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::config::ProjectConfig;
+use crate::error::FlowError;
+use crate::interpreter::output::Stream;
+use crate::stdlib::assert::{SnapshotMode, Snapshots};
+
+/// Run every test file found under `paths`. Returns true when all passed.
+pub async fn run(paths: Vec<PathBuf>, snapshots: SnapshotMode, config: ProjectConfig, verbose: bool) -> bool {
+    let workspace = crate::config::Workspace::discover(Path::new(".")).ok().flatten();
+    let roots = if paths.is_empty() { default_roots(workspace.as_ref()) } else { paths };
+
+    let mut files = Vec::new();
+    for root in &roots {
+        collect_test_files(root, &mut files);
+    }
+    files.sort();
+
+    if files.is_empty() {
+        eprintln!("{}", "❌ No test files found (looking for *_test.flow)".red().bold());
+        return false;
+    }
+
+    println!("{} {} test file(s)", "🧪 Running".bright_cyan().bold(), files.len());
+    match snapshots {
+        SnapshotMode::Update => println!("{}", "📸 Snapshot update mode: existing snapshots will be rewritten".yellow()),
+        SnapshotMode::Check => println!("{}", "📸 CI mode: missing snapshots fail instead of being written".yellow()),
+        SnapshotMode::Record => {}
+    }
+    println!();
+
+    let start = Instant::now();
+    let mut failures = Vec::new();
+
+    for file in &files {
+        let file_start = Instant::now();
+        // A member's tests run with that member's config
+        let file_config = workspace
+            .as_ref()
+            .and_then(|workspace| workspace.member_containing(file))
+            .and_then(|dir| ProjectConfig::for_dir(dir).ok())
+            .unwrap_or_else(|| config.clone());
+        let (result, output) = run_test_file(file, file_config, snapshots).await;
+        let elapsed = file_start.elapsed().as_secs_f64() * 1000.0;
+
+        match result {
+            Ok(()) => {
+                println!("{} {} {}", "✓".green().bold(), file.display(), format!("({:.1}ms)", elapsed).dimmed());
+                if verbose {
+                    print_output(&output);
+                }
+            }
+            Err(e) => {
+                println!("{} {} {}", "✗".red().bold(), file.display(), format!("({:.1}ms)", elapsed).dimmed());
+                if verbose {
+                    print_output(&output);
+                    println!("    {}", e.to_string().red());
+                }
+                failures.push((file.clone(), e, output));
+            }
+        }
+    }
+
+    println!();
+    if !failures.is_empty() {
+        println!("{}", "Failures:".red().bold());
+        for (file, error, output) in &failures {
+            println!("  {} {}", "●".red(), file.display());
+            print_output(output);
+            for line in error.to_string().lines() {
+                println!("    {}", line);
+            }
+        }
+        println!();
+    }
+
+    let passed = files.len() - failures.len();
+    let summary = format!(
+        "{} passed, {} failed, {} total ({:.2}s)",
+        passed,
+        failures.len(),
+        files.len(),
+        start.elapsed().as_secs_f64()
+    );
+    if failures.is_empty() {
+        println!("{} {}", "✅".green(), summary.bright_green().bold());
+    } else {
+        println!("{} {}", "❌".red(), summary.bright_red().bold());
+    }
+
+    failures.is_empty()
+}
+
+/// ./tests, plus every member's tests when run from a workspace root
+fn default_roots(workspace: Option<&crate::config::Workspace>) -> Vec<PathBuf> {
+    let mut roots = vec![PathBuf::from("tests")];
+    let cwd = crate::platform::canonicalize(Path::new(".")).ok();
+    if let Some(workspace) = workspace.filter(|workspace| cwd.as_ref() == Some(&workspace.root)) {
+        roots.extend(workspace.members.iter().map(|(_, dir)| dir.join("tests")));
+    }
+    roots
+}
+
+fn collect_test_files(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return;
+    }
+
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        if entry_path.is_dir() {
+            // Snapshot folders never contain runnable tests
+            if entry_path.file_name().map(|n| n == "__snapshots__").unwrap_or(false) {
+                continue;
+            }
+            collect_test_files(&entry_path, files);
+        } else if entry_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.ends_with("_test.flow"))
+            .unwrap_or(false)
+        {
+            files.push(entry_path);
+        }
+    }
+}
+
+/// Run one test file, returning its result and what it printed
+async fn run_test_file(
+    path: &Path,
+    config: ProjectConfig,
+    mode: SnapshotMode,
+) -> (Result<(), FlowError>, Vec<(Stream, String)>) {
+    let ast = match parse_test_file(path) {
+        Ok(ast) => ast,
+        Err(e) => return (Err(e), Vec::new()),
+    };
+
+    let script_dir = path.parent().unwrap_or_else(|| Path::new(".")).to_path_buf();
+    let snapshots = Snapshots { dir: script_dir.join("__snapshots__"), mode };
+
+    let mut interpreter = crate::interpreter::Interpreter::with_dir(script_dir, config);
+    interpreter.set_snapshots(snapshots);
+    interpreter.execute_captured(ast).await
+}
+
+/// `--update-snapshots` wins; otherwise `--ci`, or a CI environment, checks
+/// snapshots without writing missing ones
+pub fn snapshot_mode(update: bool, ci: bool) -> SnapshotMode {
+    let in_ci = std::env::var("CI").is_ok_and(|value| !value.is_empty() && value != "0" && value != "false");
+    if update {
+        SnapshotMode::Update
+    } else if ci || in_ci {
+        SnapshotMode::Check
+    } else {
+        SnapshotMode::Record
+    }
+}
+
+fn parse_test_file(path: &Path) -> Result<crate::parser::ast::Program, FlowError> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| FlowError::rift(&format!("Failed to read test file: {}", e), 0, 0))?
+        .replace("\u{feff}", "");
+
+    let tokens = crate::lexer::tokenize(&source)?;
+    let ast = crate::parser::parse(tokens)?;
+    Ok(crate::optimizer::Optimizer::new().optimize(ast))
+}
+
+/// Captured output, indented under the test's name; roar lines in red
+fn print_output(output: &[(Stream, String)]) {
+    for (stream, text) in output {
+        for line in text.split('\n') {
+            match stream {
+                Stream::Stdout => println!("    {} {}", "│".dimmed(), line),
+                Stream::Stderr => println!("    {} {}", "│".dimmed(), line.red()),
+            }
+        }
+    }
+}
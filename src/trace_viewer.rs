@@ -0,0 +1,320 @@
+// Viewer for `flowlang dev trace-view`
+//
+// Loads a trace written by `flowlang run --record-trace` and steps through it.
+// The state at an event (current line, call stack, variables) is rebuilt by
+// replaying the events before it, so stepping backwards is as cheap as forwards.
+
+use colored::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use crate::interpreter::time_travel::{Trace, TraceEvent};
+
+const HELP: &str = "\
+  n / Enter   next event          b          previous event
+  s           next statement      S          previous statement
+  o           step out of Spell   e          next error
+  w NAME      next write to NAME  W NAME     previous write to NAME
+  g N         go to event N       l          list events around here
+  h           this help           q          quit";
+
+/// Lines of source shown around the current one
+const CONTEXT_LINES: usize = 2;
+
+/// Where execution stood after an event
+struct State {
+    line: Option<usize>,
+    /// Spell names, outermost first
+    stack: Vec<String>,
+    /// Variables per frame; frame 0 holds the script's own
+    frames: Vec<BTreeMap<String, String>>,
+    /// Caller lines to return to
+    return_lines: Vec<Option<usize>>,
+}
+
+fn state_at(events: &[TraceEvent], position: usize) -> State {
+    let mut state = State { line: None, stack: Vec::new(), frames: vec![BTreeMap::new()], return_lines: Vec::new() };
+    for event in &events[..=position.min(events.len().saturating_sub(1))] {
+        match event {
+            TraceEvent::Step { line } => state.line = Some(*line),
+            TraceEvent::Write { name, value } => {
+                if let Some(frame) = state.frames.last_mut() {
+                    frame.insert(name.clone(), value.clone());
+                }
+            }
+            TraceEvent::Call { name, .. } => {
+                state.stack.push(name.clone());
+                state.frames.push(BTreeMap::new());
+                state.return_lines.push(state.line);
+            }
+            TraceEvent::Return { .. } => {
+                state.stack.pop();
+                if state.frames.len() > 1 {
+                    state.frames.pop();
+                }
+                if let Some(line) = state.return_lines.pop() {
+                    state.line = line;
+                }
+            }
+            TraceEvent::Error { .. } => {}
+        }
+    }
+    state
+}
+
+/// Call depth before each event, for indenting listings
+fn depths(events: &[TraceEvent]) -> Vec<usize> {
+    let mut depth = 0usize;
+    events
+        .iter()
+        .map(|event| match event {
+            TraceEvent::Call { .. } => {
+                depth += 1;
+                depth - 1
+            }
+            TraceEvent::Return { .. } => {
+                depth = depth.saturating_sub(1);
+                depth
+            }
+            _ => depth,
+        })
+        .collect()
+}
+
+fn describe(event: &TraceEvent) -> String {
+    match event {
+        TraceEvent::Step { line } => format!("line {}", line),
+        TraceEvent::Write { name, value } => format!("{} = {}", name.bright_white(), value),
+        TraceEvent::Call { name, args } => format!("{} {}({})", "call".bright_cyan(), name, args.join(", ")),
+        TraceEvent::Return { name, value: Some(value), .. } => format!("{} {} → {}", "return".bright_cyan(), name, value),
+        TraceEvent::Return { name, error, .. } => {
+            format!("{} {} ✗ {}", "return".bright_cyan(), name, error.as_deref().unwrap_or("error"))
+        }
+        TraceEvent::Error { message } => format!("{} {}", "error".red().bold(), message),
+    }
+}
+
+/// Open a trace. With `at`, print the state at that event and exit; with `list`,
+/// print every event; otherwise read stepping commands from stdin.
+pub fn run(path: PathBuf, at: Option<usize>, list: bool) -> bool {
+    let trace = match Trace::load(&path) {
+        Ok(trace) => trace,
+        Err(e) => {
+            crate::error::print_error(&e);
+            return false;
+        }
+    };
+    if trace.truncated {
+        eprintln!("{}", "⚠️  The trace ends early (the process exited before finishing it)".yellow());
+    }
+    if trace.events.is_empty() {
+        println!("{}", "The trace holds no events".dimmed());
+        return true;
+    }
+
+    let source: Vec<String> = fs::read_to_string(&trace.script)
+        .map(|text| text.lines().map(str::to_string).collect())
+        .unwrap_or_default();
+
+    if list {
+        print_events(&trace, 0, trace.events.len());
+        return true;
+    }
+    if let Some(at) = at {
+        if at >= trace.events.len() {
+            eprintln!("{} {} (the trace has {})", "❌ No event".red().bold(), at, trace.events.len());
+            return false;
+        }
+        show(&trace, &source, at);
+        return true;
+    }
+
+    println!(
+        "{} {} — {} events, h for help",
+        "⏪ Trace of".bright_cyan().bold(),
+        trace.script.display(),
+        trace.events.len()
+    );
+    let mut position = 0;
+    show(&trace, &source, position);
+
+    let stdin = io::stdin();
+    let mut input = String::new();
+    loop {
+        print!("{} ", "trace>".bright_magenta());
+        let _ = io::stdout().flush();
+        input.clear();
+        match stdin.lock().read_line(&mut input) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        let mut words = input.split_whitespace();
+        let command = words.next().unwrap_or("n");
+        let argument = words.next();
+
+        let target = match command {
+            "q" | "quit" => break,
+            "h" | "help" | "?" => {
+                println!("{}", HELP);
+                continue;
+            }
+            "l" | "list" => {
+                print_events(&trace, position.saturating_sub(5), position + 6);
+                continue;
+            }
+            "n" => Some(position + 1),
+            "b" => position.checked_sub(1),
+            "s" => find_forward(&trace.events, position, |e| matches!(e, TraceEvent::Step { .. })),
+            "S" => find_backward(&trace.events, position, |e| matches!(e, TraceEvent::Step { .. })),
+            "e" => find_forward(&trace.events, position, |e| matches!(e, TraceEvent::Error { .. })),
+            "o" => step_out(&trace.events, position),
+            "w" | "W" => match argument {
+                Some(name) => {
+                    let is_write = |e: &TraceEvent| matches!(e, TraceEvent::Write { name: n, .. } if n == name);
+                    if command == "w" {
+                        find_forward(&trace.events, position, is_write)
+                    } else {
+                        find_backward(&trace.events, position, is_write)
+                    }
+                }
+                None => {
+                    println!("{}", "Usage: w NAME".yellow());
+                    continue;
+                }
+            },
+            "g" => match argument.and_then(|n| n.parse().ok()) {
+                Some(n) => Some(n),
+                None => {
+                    println!("{}", "Usage: g N".yellow());
+                    continue;
+                }
+            },
+            other => {
+                println!("{} '{}', h for help", "Unknown command".yellow(), other);
+                continue;
+            }
+        };
+
+        match target.filter(|&t| t < trace.events.len()) {
+            Some(target) => {
+                position = target;
+                show(&trace, &source, position);
+            }
+            None => println!("{}", "No such event; staying put".dimmed()),
+        }
+    }
+    true
+}
+
+fn find_forward(events: &[TraceEvent], from: usize, matches: impl Fn(&TraceEvent) -> bool) -> Option<usize> {
+    (from + 1..events.len()).find(|&i| matches(&events[i]))
+}
+
+fn find_backward(events: &[TraceEvent], from: usize, matches: impl Fn(&TraceEvent) -> bool) -> Option<usize> {
+    (0..from).rev().find(|&i| matches(&events[i]))
+}
+
+/// The Return that ends the Spell running at `from`
+fn step_out(events: &[TraceEvent], from: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, event) in events.iter().enumerate().skip(from + 1) {
+        match event {
+            TraceEvent::Call { .. } => depth += 1,
+            TraceEvent::Return { .. } if depth == 0 => return Some(i),
+            TraceEvent::Return { .. } => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn print_events(trace: &Trace, from: usize, to: usize) {
+    let depths = depths(&trace.events);
+    let to = to.min(trace.events.len());
+    for (i, (event, depth)) in trace.events.iter().zip(depths).enumerate().take(to).skip(from) {
+        println!("{:>7}  {}{}", i.to_string().dimmed(), "  ".repeat(depth), describe(event));
+    }
+}
+
+fn show(trace: &Trace, source: &[String], position: usize) {
+    let state = state_at(&trace.events, position);
+    println!();
+
+    let stack = if state.stack.is_empty() {
+        "<main>".to_string()
+    } else {
+        state.stack.iter().rev().map(String::as_str).collect::<Vec<_>>().join(" ← ")
+    };
+    let line = state.line.map(|l| format!("line {}", l)).unwrap_or_else(|| "before the first statement".to_string());
+    println!(
+        "{} {}/{}  {}  in {}",
+        "⏱  event".bright_cyan().bold(),
+        position,
+        trace.events.len() - 1,
+        line,
+        stack.bright_white()
+    );
+
+    if let Some(current) = state.line.filter(|&l| l >= 1 && l <= source.len()) {
+        let first = current.saturating_sub(CONTEXT_LINES).max(1);
+        let last = (current + CONTEXT_LINES).min(source.len());
+        for number in first..=last {
+            let text = &source[number - 1];
+            if number == current {
+                println!(" {} {:>4} │ {}", "→".bright_green().bold(), number, text.bright_white());
+            } else {
+                println!("   {:>4} │ {}", number.to_string().dimmed(), text.dimmed());
+            }
+        }
+    }
+
+    println!("   {}", describe(&trace.events[position]));
+
+    let print_frame = |label: &str, frame: &BTreeMap<String, String>| {
+        if !frame.is_empty() {
+            let vars: Vec<String> = frame.iter().map(|(name, value)| format!("{} = {}", name, value)).collect();
+            println!("   {} {}", label.dimmed(), vars.join(", "));
+        }
+    };
+    if let Some(spell) = state.stack.last() {
+        print_frame(&format!("{}:", spell), &state.frames[state.frames.len() - 1]);
+    }
+    print_frame("<main>:", &state.frames[0]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_follows_calls_and_writes() {
+        let write = |name: &str, value: &str| TraceEvent::Write { name: name.to_string(), value: value.to_string() };
+        let events = vec![
+            TraceEvent::Step { line: 4 },
+            write("total", "0"),
+            TraceEvent::Step { line: 5 },
+            TraceEvent::Call { name: "add".to_string(), args: vec!["0".to_string(), "2".to_string()] },
+            TraceEvent::Step { line: 2 },
+            write("sum", "2"),
+            TraceEvent::Return { name: "add".to_string(), value: Some("2".to_string()), error: None },
+            write("total", "2"),
+        ];
+
+        let inside = state_at(&events, 5);
+        assert_eq!(inside.line, Some(2));
+        assert_eq!(inside.stack, vec!["add"]);
+        assert_eq!(inside.frames[1]["sum"], "2");
+        assert_eq!(inside.frames[0]["total"], "0");
+
+        let after = state_at(&events, 7);
+        assert_eq!(after.line, Some(5));
+        assert!(after.stack.is_empty());
+        assert_eq!(after.frames.len(), 1);
+        assert_eq!(after.frames[0]["total"], "2");
+
+        assert_eq!(step_out(&events, 4), Some(6));
+        assert_eq!(find_backward(&events, 4, |e| matches!(e, TraceEvent::Step { .. })), Some(2));
+    }
+}
@@ -1,7 +1,10 @@
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::pin::Pin;
+use std::task::Poll;
 use crate::error::FlowError;
 
 pub struct NativeFn(pub Arc<dyn Fn(Vec<Value>) -> Result<Value, FlowError> + Send + Sync>);
@@ -13,6 +16,11 @@ impl NativeFn {
     {
         NativeFn(Arc::new(f))
     }
+    
+    /// Call the function inside the panic boundary (see `catch_panic`)
+    pub fn call(&self, name: &str, args: Vec<Value>) -> Result<Value, FlowError> {
+        unwind_boundary(|| (self.0)(args)).unwrap_or_else(|payload| Err(panic_error(name, payload)))
+    }
 }
 
 impl std::fmt::Debug for NativeFn {
@@ -33,6 +41,60 @@ impl Clone for NativeFn {
     }
 }
 
+thread_local! {
+    /// How many panic boundaries this thread is inside of
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Drive `future`, turning a Rust panic while polling it into a FlowError::Panic.
+///
+/// This is the panic boundary: every native call (`NativeFn`, `AsyncNativeFn` and
+/// `InterpreterNativeFn`) and every web handler runs inside it, so one bad call
+/// fails with a catchable error naming `name` instead of killing the process and
+/// every server in it. The error already carries the panic message, so with
+/// `install_panic_hook` these panics aren't also printed to stderr.
+pub async fn catch_panic<F>(name: &str, future: F) -> Result<Value, FlowError>
+where
+    F: Future<Output = Result<Value, FlowError>>,
+{
+    let mut future = std::pin::pin!(future);
+    std::future::poll_fn(|cx| {
+        unwind_boundary(|| future.as_mut().poll(cx))
+            .unwrap_or_else(|payload| Poll::Ready(Err(panic_error(name, payload))))
+    })
+    .await
+}
+
+/// `catch_unwind`, marking the thread as inside the panic boundary while `f` runs
+fn unwind_boundary<T>(f: impl FnOnce() -> T) -> std::thread::Result<T> {
+    CATCHING.with(|depth| depth.set(depth.get() + 1));
+    let result = catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|depth| depth.set(depth.get() - 1));
+    result
+}
+
+/// Keep the default panic hook, except for panics the boundary catches (see
+/// `catch_panic`). The `flowlang` binary installs this; the library leaves the
+/// hook to whoever embeds it.
+pub fn install_panic_hook() {
+    let default = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if CATCHING.try_with(Cell::get).unwrap_or(0) == 0 {
+            default(info);
+        }
+    }));
+}
+
+/// A FlowError::Panic describing a caught panic from native `name`
+pub fn panic_error(name: &str, payload: Box<dyn std::any::Any + Send>) -> FlowError {
+    let reason = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown reason".to_string());
+    FlowError::panic(&format!("Spell '{}' panicked: {}", name, reason), 0, 0)
+}
+
 /// Async native function type for functions that need async runtime access
 /// (e.g., timers, servers, async I/O)
 pub struct AsyncNativeFn(
@@ -53,6 +115,14 @@ impl AsyncNativeFn {
     {
         AsyncNativeFn(Arc::new(move |args, ctx| Box::pin(f(args, ctx))))
     }
+    
+    /// Call the function with runtime access through `ctx`
+    pub async fn call(&self, name: &str, args: Vec<Value>, ctx: AsyncContext) -> Result<Value, FlowError> {
+        match unwind_boundary(|| (self.0)(args, ctx)) {
+            Ok(future) => catch_panic(name, future).await,
+            Err(payload) => Err(panic_error(name, payload)),
+        }
+    }
 }
 
 impl std::fmt::Debug for AsyncNativeFn {
@@ -73,6 +143,52 @@ impl Clone for AsyncNativeFn {
     }
 }
 
+/// Future returned by interpreter-aware native functions; borrows the interpreter
+pub type InterpreterFuture<'a> = Pin<Box<dyn Future<Output = Result<Value, FlowError>> + Send + 'a>>;
+
+/// The Rust side of an InterpreterNativeFn
+pub type InterpreterNativeCallback =
+    dyn for<'a> Fn(Vec<Value>, &'a mut crate::interpreter::Interpreter) -> InterpreterFuture<'a> + Send + Sync;
+
+/// Native function that runs with access to the calling interpreter, so it
+/// can invoke Spells passed in as arguments (property tests, directory walks, ...)
+pub struct InterpreterNativeFn(pub Arc<InterpreterNativeCallback>);
+
+impl InterpreterNativeFn {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: for<'a> Fn(Vec<Value>, &'a mut crate::interpreter::Interpreter) -> InterpreterFuture<'a> + Send + Sync + 'static,
+    {
+        InterpreterNativeFn(Arc::new(f))
+    }
+    
+    /// Call the function on behalf of `interpreter`
+    pub async fn call(&self, name: &str, args: Vec<Value>, interpreter: &mut crate::interpreter::Interpreter) -> Result<Value, FlowError> {
+        match unwind_boundary(|| (self.0)(args, interpreter)) {
+            Ok(future) => catch_panic(name, future).await,
+            Err(payload) => Err(panic_error(name, payload)),
+        }
+    }
+}
+
+impl std::fmt::Debug for InterpreterNativeFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<interpreter native fn>")
+    }
+}
+
+impl PartialEq for InterpreterNativeFn {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Clone for InterpreterNativeFn {
+    fn clone(&self) -> Self {
+        InterpreterNativeFn(self.0.clone())
+    }
+}
+
 use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -102,13 +218,22 @@ impl std::fmt::Display for EssenceType {
     }
 }
 
+/// Storage behind a Relic: keys stay in the order they were first inserted, so
+/// printing, iteration and JSON output are stable.
+pub type RelicMap = indexmap::IndexMap<String, Value>;
+
+/// Members of a Covenant (a set), each under a key that is equal for deeply equal
+/// values (see `stdlib::covenant::member_key`), in insertion order
+pub type CovenantSet = indexmap::IndexMap<String, Value>;
+
 #[derive(Debug, Clone)]
 pub enum Value {
     Number(f64),
     String(Arc<String>),
     Boolean(bool),
     Array(Arc<Vec<Value>>),
-    Relic(Arc<HashMap<String, Value>>),
+    Relic(Arc<RelicMap>),
+    Covenant(Arc<CovenantSet>),
     Null,
     Function {
         params: Vec<String>,
@@ -122,6 +247,8 @@ pub enum Value {
     NativeFunction(NativeFn),
     /// Async native function that has access to the runtime
     AsyncNativeFunction(AsyncNativeFn),
+    /// Native function that can call back into the interpreter
+    InterpreterNativeFunction(InterpreterNativeFn),
     /// Handle ID returned by timer.interval, server.http, etc.
     Handle(u64),
 }
@@ -134,8 +261,12 @@ impl Value {
             Value::Boolean(_) => "Pulse",
             Value::Array(_) => "Constellation",
             Value::Relic(_) => "Relic",
+            Value::Covenant(_) => "Covenant",
             Value::Null => "Hollow",
-            Value::Function { .. } | Value::NativeFunction(_) | Value::AsyncNativeFunction(_) => "Spell",
+            Value::Function { .. }
+            | Value::NativeFunction(_)
+            | Value::AsyncNativeFunction(_)
+            | Value::InterpreterNativeFunction(_) => "Spell",
             Value::Handle(_) => "Handle",
         }
     }
@@ -148,11 +279,68 @@ impl Value {
             Value::String(s) => !s.is_empty(),
             Value::Array(a) => !a.is_empty(),
             Value::Relic(m) => !m.is_empty(),
-            Value::Function { .. } | Value::NativeFunction(_) | Value::AsyncNativeFunction(_) => true,
+            Value::Covenant(s) => !s.is_empty(),
+            Value::Function { .. }
+            | Value::NativeFunction(_)
+            | Value::AsyncNativeFunction(_)
+            | Value::InterpreterNativeFunction(_) => true,
             Value::Handle(id) => *id > 0,
         }
     }
     
+    /// Structural equality: Constellations and Relics (and so Sigil instances) compare
+    /// element by element and Covenants by their members. A Spell only equals itself;
+    /// Handles are equal when they share an id.
+    pub fn deep_equals(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::Null, Value::Null) => true,
+            (Value::Handle(a), Value::Handle(b)) => a == b,
+            (Value::Array(a), Value::Array(b)) => {
+                Arc::ptr_eq(a, b) || (a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| x.deep_equals(y)))
+            }
+            (Value::Relic(a), Value::Relic(b)) => {
+                Arc::ptr_eq(a, b)
+                    || (a.len() == b.len() && a.iter().all(|(key, x)| b.get(key).is_some_and(|y| x.deep_equals(y))))
+            }
+            (Value::Covenant(a), Value::Covenant(b)) => a.len() == b.len() && a.keys().all(|key| b.contains_key(key)),
+            (Value::Function { body: a, .. }, Value::Function { body: b, .. }) => Arc::ptr_eq(a, b),
+            (Value::NativeFunction(a), Value::NativeFunction(b)) => a == b,
+            (Value::AsyncNativeFunction(a), Value::AsyncNativeFunction(b)) => a == b,
+            (Value::InterpreterNativeFunction(a), Value::InterpreterNativeFunction(b)) => a == b,
+            _ => false,
+        }
+    }
+    
+    /// A copy that shares no Constellation or Relic storage with `self`. Spells and
+    /// Handles are copied as references, since they are not plain data.
+    pub fn deep_clone(&self) -> Value {
+        match self {
+            Value::Array(arr) => Value::Array(Arc::new(arr.iter().map(Value::deep_clone).collect())),
+            Value::Relic(map) => Value::Relic(Arc::new(map.iter().map(|(k, v)| (k.clone(), v.deep_clone())).collect())),
+            Value::Covenant(set) => Value::Covenant(Arc::new(set.iter().map(|(k, v)| (k.clone(), v.deep_clone())).collect())),
+            Value::String(s) => Value::String(Arc::new(s.as_str().to_string())),
+            other => other.clone(),
+        }
+    }
+    
+    /// The path and type of the first Spell or Handle inside `self`, if any. Those carry
+    /// state that can change behind the value, so a value holding one cannot be frozen.
+    pub fn find_live_part(&self, path: &str) -> Option<(String, &'static str)> {
+        match self {
+            Value::Array(arr) => arr.iter().enumerate().find_map(|(i, v)| v.find_live_part(&format!("{}[{}]", path, i))),
+            Value::Relic(map) => map.iter().find_map(|(k, v)| v.find_live_part(&format!("{}.{}", path, k))),
+            Value::Function { .. }
+            | Value::NativeFunction(_)
+            | Value::AsyncNativeFunction(_)
+            | Value::InterpreterNativeFunction(_) => Some((path.to_string(), "Spell")),
+            Value::Handle(_) => Some((path.to_string(), "Handle")),
+            _ => None,
+        }
+    }
+    
     pub fn to_string(&self) -> String {
         match self {
             Value::Number(n) => {
@@ -169,17 +357,22 @@ impl Value {
                 format!("[{}]", elements.join(", "))
             }
             Value::Relic(map) => {
-                let mut entries: Vec<String> = map.iter()
+                let entries: Vec<String> = map.iter()
                     .map(|(k, v)| format!("{}: {}", k, v.to_string()))
                     .collect();
-                entries.sort(); // Sort for deterministic output
                 format!("{{ {} }}", entries.join(", "))
             }
+            Value::Covenant(set) => {
+                let members: Vec<String> = set.values().map(|v| v.to_string()).collect();
+                format!("Covenant({})", members.join(", "))
+            }
             Value::Null => "null".to_string(),
             Value::Function { params, .. } => {
                 format!("Spell({})", params.join(", "))
             }
-            Value::NativeFunction(_) | Value::AsyncNativeFunction(_) => "Spell(native)".to_string(),
+            Value::NativeFunction(_)
+            | Value::AsyncNativeFunction(_)
+            | Value::InterpreterNativeFunction(_) => "Spell(native)".to_string(),
             Value::Handle(id) => format!("Handle(#{})", id),
         }
     }
@@ -190,3 +383,69 @@ impl std::fmt::Display for Value {
         write!(f, "{}", self.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn panic_message(result: Result<Value, FlowError>) -> String {
+        match result {
+            Err(FlowError::Panic { message, .. }) => message,
+            other => panic!("expected a Panic error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_native_panics_become_errors() {
+        let native = NativeFn::new(|_| panic!("index out of bounds"));
+        let message = panic_message(native.call("array.boom", vec![]));
+        assert_eq!(message, "Spell 'array.boom' panicked: index out of bounds");
+
+        let future = async {
+            tokio::task::yield_now().await;
+            let id: u32 = 7;
+            panic!("handle {} vanished", id)
+        };
+        let message = panic_message(catch_panic("timer.boom", future).await);
+        assert_eq!(message, "Spell 'timer.boom' panicked: handle 7 vanished");
+
+        assert_eq!(catch_panic("ok", async { Ok(Value::Number(1.0)) }).await.unwrap().to_string(), "1");
+    }
+
+    #[test]
+    fn test_boundary_is_marked_while_a_native_runs() {
+        let depth = || CATCHING.with(Cell::get);
+        let native = NativeFn::new(move |_| Ok(Value::Number(depth() as f64)));
+        assert_eq!(native.call("probe", vec![]).unwrap().to_string(), "1");
+        let nested = NativeFn::new(move |_| native.call("probe", vec![]));
+        assert_eq!(nested.call("outer", vec![]).unwrap().to_string(), "2");
+
+        // Unwinding out of a native still leaves the boundary
+        let _ = NativeFn::new(|_| panic!("boom")).call("boom", vec![]);
+        assert_eq!(depth(), 0);
+    }
+
+    #[test]
+    fn test_deep_equals_and_clone() {
+        let relic = |pairs: &[(&str, Value)]| {
+            Value::Relic(Arc::new(pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()))
+        };
+        let list = Value::Array(Arc::new(vec![Value::Number(1.0), relic(&[("a", Value::Boolean(true))])]));
+        let same = Value::Array(Arc::new(vec![Value::Number(1.0), relic(&[("a", Value::Boolean(true))])]));
+        let different = Value::Array(Arc::new(vec![Value::Number(1.0), relic(&[("a", Value::Null)])]));
+        assert!(list.deep_equals(&same));
+        assert!(!list.deep_equals(&different));
+        assert!(!relic(&[("a", Value::Null)]).deep_equals(&relic(&[("b", Value::Null)])));
+
+        let copy = list.deep_clone();
+        assert!(copy.deep_equals(&list));
+        match (&copy, &list) {
+            (Value::Array(a), Value::Array(b)) => assert!(!Arc::ptr_eq(a, b)),
+            _ => unreachable!(),
+        }
+
+        assert_eq!(list.find_live_part("value"), None);
+        let live = relic(&[("inner", Value::Array(Arc::new(vec![Value::Null, Value::Handle(3)])))]);
+        assert_eq!(live.find_live_part("value"), Some(("value.inner[1]".to_string(), "Handle")));
+    }
+}